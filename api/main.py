@@ -65,6 +65,8 @@ synth_arb_state = {
 # Synth-arb Rust bot URL
 SYNTH_ARB_URL = os.getenv("SYNTH_ARB_URL", "http://localhost:8001")
 REDIS_URL = os.getenv("REDIS_URL", "redis://localhost:6379")
+# Must match the engine's REDIS_CHANNEL_PREFIX (poly:<INSTANCE_ID> when only INSTANCE_ID is set)
+REDIS_CHANNEL_PREFIX = os.getenv("REDIS_CHANNEL_PREFIX", "poly")
 
 
 async def broadcast_event(event_type: str, data: dict):
@@ -78,7 +80,7 @@ async def broadcast_event(event_type: str, data: dict):
 async def synth_arb_redis_subscriber():
     """Subscribe to Rust trading engine Redis channels for real-time updates.
 
-    Rust engine publishes to:
+    Rust engine publishes to (with the default "poly" prefix):
     - poly:state   - Engine state updates (every minute)
     - poly:signals - Trade signals
     - poly:trades  - Executed trades
@@ -95,7 +97,9 @@ async def synth_arb_redis_subscriber():
         pubsub = redis.pubsub()
 
         # Subscribe to all Rust engine channels
-        channels = ["poly:state", "poly:signals", "poly:trades", "poly:errors"]
+        channels = [
+            f"{REDIS_CHANNEL_PREFIX}:{name}" for name in ("state", "signals", "trades", "errors")
+        ]
         await pubsub.subscribe(*channels)
         print(f"[POLY-RUST] Subscribed to Redis channels: {channels}")
 
@@ -108,7 +112,7 @@ async def synth_arb_redis_subscriber():
 
                     data = json.loads(message["data"])
 
-                    if channel == "poly:state":
+                    if channel == f"{REDIS_CHANNEL_PREFIX}:state":
                         # Update local state from Rust engine state
                         synth_arb_state = {
                             "status": data.get("status", "unknown"),
@@ -116,6 +120,9 @@ async def synth_arb_redis_subscriber():
                             "opportunities_found": data.get("opportunities_found", 0),
                             "daily_pnl": str(data.get("daily_pnl", 0)),
                             "daily_trades": data.get("daily_trades", 0),
+                            "equity": str(data.get("equity", 0)),
+                            "drawdown": str(data.get("drawdown", 0)),
+                            "max_drawdown": str(data.get("max_drawdown", 0)),
                             "positions": data.get("positions", []),
                             "timestamp_ms": data.get("timestamp_ms", 0),
                         }
@@ -126,7 +133,7 @@ async def synth_arb_redis_subscriber():
                             "data": synth_arb_state
                         })
 
-                    elif channel == "poly:signals":
+                    elif channel == f"{REDIS_CHANNEL_PREFIX}:signals":
                         # Broadcast signal to WebSocket clients
                         await manager.broadcast({
                             "type": "signal",
@@ -134,7 +141,7 @@ async def synth_arb_redis_subscriber():
                             "data": data
                         })
 
-                    elif channel == "poly:trades":
+                    elif channel == f"{REDIS_CHANNEL_PREFIX}:trades":
                         # Broadcast trade to WebSocket clients
                         await manager.broadcast({
                             "type": "trade",
@@ -142,7 +149,7 @@ async def synth_arb_redis_subscriber():
                             "data": data
                         })
 
-                    elif channel == "poly:errors":
+                    elif channel == f"{REDIS_CHANNEL_PREFIX}:errors":
                         # Broadcast error to WebSocket clients
                         await manager.broadcast({
                             "type": "error",
@@ -9,8 +9,8 @@ fn bench_vwap_calculation(c: &mut Criterion) {
     c.bench_function("vwap_simple", |b| {
         b.iter(|| {
             // Simulate VWAP calculation
-            let prices = vec![0.45, 0.46, 0.47];
-            let sizes = vec![50.0, 30.0, 20.0];
+            let prices = [0.45, 0.46, 0.47];
+            let sizes = [50.0, 30.0, 20.0];
 
             let total_value: f64 = prices.iter().zip(sizes.iter()).map(|(p, s)| p * s).sum();
             let total_size: f64 = sizes.iter().sum();
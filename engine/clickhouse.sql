@@ -0,0 +1,79 @@
+-- =============================================================================
+-- Poly Trading System - ClickHouse Analytics Schema
+-- =============================================================================
+-- High-volume signal, near-miss and order book data (see src/db/clickhouse.rs).
+-- Trades stay in Postgres (init.sql). Rows carry epoch milliseconds; `ts` is
+-- derived for time functions. Tables are ordered by key then time so scans of
+-- one strategy, market or token over a window read contiguous data.
+-- =============================================================================
+
+CREATE DATABASE IF NOT EXISTS poly;
+
+-- ---------------------------------------------------------------------------
+-- Signals (every signal a strategy emits, executed or observed)
+-- ---------------------------------------------------------------------------
+CREATE TABLE IF NOT EXISTS poly.signals (
+    timestamp_ms UInt64,
+    ts DateTime64(3) MATERIALIZED fromUnixTimestamp64Milli(toInt64(timestamp_ms)),
+    strategy LowCardinality(String),
+    signal_type LowCardinality(String),
+    mode LowCardinality(String),
+    token_id Nullable(String),
+    yes_token_id Nullable(String),
+    no_token_id Nullable(String),
+    price Nullable(Float64),
+    yes_price Nullable(Float64),
+    no_price Nullable(Float64),
+    size Float64,
+    edge Nullable(Float64),
+    reason String,
+    context String,  -- JSON, observe mode only
+    metadata String  -- JSON, strategy-specific (see strategy::SignalMetadata)
+)
+ENGINE = MergeTree
+PARTITION BY toYYYYMM(ts)
+ORDER BY (strategy, ts)
+TTL toDateTime(ts) + INTERVAL 180 DAY;
+
+-- ---------------------------------------------------------------------------
+-- Near misses (opportunities just short of min_edge)
+-- ---------------------------------------------------------------------------
+CREATE TABLE IF NOT EXISTS poly.near_misses (
+    timestamp_ms UInt64,
+    ts DateTime64(3) MATERIALIZED fromUnixTimestamp64Milli(toInt64(timestamp_ms)),
+    market_id String,
+    yes_token_id String,
+    no_token_id String,
+    yes_price Float64,
+    no_price Float64,
+    sum Float64,
+    edge Float64,
+    min_edge Float64,
+    size Float64
+)
+ENGINE = MergeTree
+PARTITION BY toYYYYMM(ts)
+ORDER BY (market_id, ts)
+TTL toDateTime(ts) + INTERVAL 180 DAY;
+
+-- ---------------------------------------------------------------------------
+-- Book snapshots (top levels of every book, sampled on an interval)
+-- ---------------------------------------------------------------------------
+CREATE TABLE IF NOT EXISTS poly.book_snapshots (
+    timestamp_ms UInt64,
+    ts DateTime64(3) MATERIALIZED fromUnixTimestamp64Milli(toInt64(timestamp_ms)),
+    token_id String,
+    market_id Nullable(String),
+    best_bid Nullable(Float64),
+    best_ask Nullable(Float64),
+    bid_depth Float64,
+    ask_depth Float64,
+    bid_prices Array(Float64),
+    bid_sizes Array(Float64),
+    ask_prices Array(Float64),
+    ask_sizes Array(Float64)
+)
+ENGINE = MergeTree
+PARTITION BY toYYYYMMDD(ts)
+ORDER BY (token_id, ts)
+TTL toDateTime(ts) + INTERVAL 30 DAY;
@@ -14,6 +14,10 @@ CREATE TABLE IF NOT EXISTS trades (
     id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
     created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
 
+    -- Idempotency key, one per execution (NULL on rows from before it
+    -- existed; `poly-rust --dedupe-trades` adds it to older databases)
+    client_trade_id VARCHAR(64),
+
     -- Order details
     token_id VARCHAR(255) NOT NULL,
     side VARCHAR(10) NOT NULL,  -- 'BUY' or 'SELL'
@@ -31,6 +35,22 @@ CREATE TABLE IF NOT EXISTS trades (
     -- Paper trading flag
     is_paper BOOLEAN NOT NULL DEFAULT false,
 
+    -- Attribution
+    market_id VARCHAR(255),
+    category VARCHAR(100),
+    realized_pnl DECIMAL(20, 8),  -- set on sells; net of actual fees once reconciled
+
+    -- Fees (see db::fees)
+    estimated_fee DECIMAL(20, 8),     -- cost model's fee at execution
+    actual_fee DECIMAL(20, 8),        -- charged, from the exchange statement
+    fee_reconciled_at TIMESTAMPTZ,
+
+    -- Inserted from exchange trade history by `poly-rust --backfill-trades`
+    backfilled BOOLEAN NOT NULL DEFAULT false,
+
+    -- Strategy-specific context of the signal (Sniper game_id, ...)
+    metadata JSONB,
+
     -- Indexes for common queries
     CONSTRAINT valid_side CHECK (side IN ('BUY', 'SELL'))
 );
@@ -39,6 +59,8 @@ CREATE INDEX IF NOT EXISTS idx_trades_created_at ON trades(created_at DESC);
 CREATE INDEX IF NOT EXISTS idx_trades_token_id ON trades(token_id);
 CREATE INDEX IF NOT EXISTS idx_trades_strategy ON trades(strategy);
 CREATE INDEX IF NOT EXISTS idx_trades_is_paper ON trades(is_paper);
+CREATE INDEX IF NOT EXISTS idx_trades_order_id ON trades(order_id);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_trades_client_trade_id ON trades(client_trade_id);
 
 -- ---------------------------------------------------------------------------
 -- Arbitrage Trades Table (YES + NO pairs)
@@ -47,6 +69,9 @@ CREATE TABLE IF NOT EXISTS arb_trades (
     id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
     created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
 
+    -- Idempotency key, one per execution (see trades)
+    client_trade_id VARCHAR(64),
+
     -- Market info
     market_id VARCHAR(255) NOT NULL,
     yes_token_id VARCHAR(255) NOT NULL,
@@ -72,12 +97,26 @@ CREATE TABLE IF NOT EXISTS arb_trades (
     strategy VARCHAR(100) NOT NULL DEFAULT 'SumTo100',
 
     -- Paper trading flag
-    is_paper BOOLEAN NOT NULL DEFAULT false
+    is_paper BOOLEAN NOT NULL DEFAULT false,
+
+    category VARCHAR(100),
+
+    -- Fees (see db::fees); fees above also includes estimated slippage
+    estimated_fee DECIMAL(20, 8),     -- cost model's fee for both legs
+    yes_actual_fee DECIMAL(20, 8),    -- charged, from the exchange statement
+    no_actual_fee DECIMAL(20, 8),
+    fee_reconciled_at TIMESTAMPTZ,
+
+    -- Strategy-specific context of the signal (see trades)
+    metadata JSONB
 );
 
 CREATE INDEX IF NOT EXISTS idx_arb_trades_created_at ON arb_trades(created_at DESC);
 CREATE INDEX IF NOT EXISTS idx_arb_trades_market_id ON arb_trades(market_id);
 CREATE INDEX IF NOT EXISTS idx_arb_trades_is_paper ON arb_trades(is_paper);
+CREATE INDEX IF NOT EXISTS idx_arb_trades_yes_order_id ON arb_trades(yes_order_id);
+CREATE INDEX IF NOT EXISTS idx_arb_trades_no_order_id ON arb_trades(no_order_id);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_arb_trades_client_trade_id ON arb_trades(client_trade_id);
 
 -- ---------------------------------------------------------------------------
 -- Positions Table (current holdings)
@@ -116,6 +155,46 @@ CREATE TABLE IF NOT EXISTS daily_stats (
 
 CREATE INDEX IF NOT EXISTS idx_daily_stats_date ON daily_stats(date DESC);
 
+-- ---------------------------------------------------------------------------
+-- P&L Attribution (filled trades rolled up by day, hour, strategy, market)
+-- ---------------------------------------------------------------------------
+CREATE TABLE IF NOT EXISTS pnl_attribution (
+    day DATE NOT NULL,                -- in TRADING_TIMEZONE
+    hour SMALLINT NOT NULL,           -- hour of day in TRADING_TIMEZONE, 0-23
+    strategy VARCHAR(100) NOT NULL,
+    market_id VARCHAR(255) NOT NULL,  -- '' when unknown
+    category VARCHAR(100) NOT NULL,   -- '' when unknown
+    is_paper BOOLEAN NOT NULL,
+
+    trades INTEGER NOT NULL DEFAULT 0,
+    volume DECIMAL(20, 8) NOT NULL DEFAULT 0,
+    pnl DECIMAL(20, 8) NOT NULL DEFAULT 0,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (day, hour, strategy, market_id, category, is_paper)
+);
+
+CREATE INDEX IF NOT EXISTS idx_pnl_attribution_day ON pnl_attribution(day DESC);
+
+-- ---------------------------------------------------------------------------
+-- Equity Curve (cumulative P&L sampled every engine heartbeat)
+-- ---------------------------------------------------------------------------
+CREATE TABLE IF NOT EXISTS equity_curve (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    sampled_at TIMESTAMPTZ NOT NULL,
+
+    -- P&L since engine start
+    realized_pnl DECIMAL(20, 8) NOT NULL,
+    unrealized_pnl DECIMAL(20, 8) NOT NULL,  -- open positions marked to mid
+    equity DECIMAL(20, 8) NOT NULL,
+    drawdown DECIMAL(20, 8) NOT NULL,        -- below the running peak
+    max_drawdown DECIMAL(20, 8) NOT NULL,
+
+    is_paper BOOLEAN NOT NULL DEFAULT false
+);
+
+CREATE INDEX IF NOT EXISTS idx_equity_curve_sampled_at ON equity_curve(sampled_at DESC);
+
 -- ---------------------------------------------------------------------------
 -- Signals Table (strategy evaluations - for debugging)
 -- ---------------------------------------------------------------------------
@@ -125,6 +204,8 @@ CREATE TABLE IF NOT EXISTS signals (
 
     strategy VARCHAR(100) NOT NULL,
     signal_type VARCHAR(50) NOT NULL,  -- 'BUY', 'SELL', 'ARBITRAGE'
+    mode VARCHAR(20) NOT NULL DEFAULT 'live',  -- execution mode, or 'observe'
+    reason TEXT,
 
     -- Market data at signal time
     token_id VARCHAR(255),
@@ -142,13 +223,57 @@ CREATE TABLE IF NOT EXISTS signals (
     confidence DECIMAL(5, 4),
 
     -- Outcome
-    action_taken VARCHAR(50) NOT NULL,  -- 'EXECUTED', 'REJECTED_RISK', 'REJECTED_STALE', etc.
-    rejection_reason TEXT
+    action_taken VARCHAR(50) NOT NULL,  -- 'EXECUTED', 'OBSERVED', 'REJECTED_RISK', 'REJECTED_SIZE', etc.
+    rejection_reason TEXT,
+
+    -- Book and risk context (observe mode)
+    context JSONB,
+
+    -- Strategy-specific context (see trades)
+    metadata JSONB
 );
 
 CREATE INDEX IF NOT EXISTS idx_signals_created_at ON signals(created_at DESC);
 CREATE INDEX IF NOT EXISTS idx_signals_strategy ON signals(strategy);
 CREATE INDEX IF NOT EXISTS idx_signals_action ON signals(action_taken);
+CREATE INDEX IF NOT EXISTS idx_signals_mode ON signals(mode, created_at DESC);
+
+-- ---------------------------------------------------------------------------
+-- Order Tags (strategy and signal behind each order the engine placed; the
+-- CLOB has no client order ID, so exchange fills are attributed through here)
+-- ---------------------------------------------------------------------------
+CREATE TABLE IF NOT EXISTS order_tags (
+    order_id VARCHAR(255) PRIMARY KEY,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    strategy VARCHAR(100) NOT NULL,
+    signal_id VARCHAR(64),            -- signal_id in the trade/signal metadata
+    token_id VARCHAR(255) NOT NULL,
+    side VARCHAR(10) NOT NULL,        -- 'BUY' or 'SELL'
+    is_paper BOOLEAN NOT NULL DEFAULT false
+);
+
+CREATE INDEX IF NOT EXISTS idx_order_tags_signal ON order_tags(signal_id);
+
+-- ---------------------------------------------------------------------------
+-- Audit Log (append-only timeline of operator actions and interventions)
+-- ---------------------------------------------------------------------------
+CREATE TABLE IF NOT EXISTS audit_log (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    event_time TIMESTAMPTZ NOT NULL,  -- when the engine recorded the event
+
+    actor VARCHAR(100) NOT NULL,      -- 'operator:http', 'risk', 'engine', ...
+    action VARCHAR(50) NOT NULL,      -- 'emergency_stop', 'strategy_toggle', ...
+    target VARCHAR(255),              -- strategy / market / token, if any
+    details TEXT NOT NULL DEFAULT ''
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_event_time ON audit_log(event_time DESC);
+CREATE INDEX IF NOT EXISTS idx_audit_log_action ON audit_log(action);
+
+-- Reject edits so the trail stays trustworthy
+CREATE OR REPLACE RULE audit_log_no_update AS ON UPDATE TO audit_log DO INSTEAD NOTHING;
+CREATE OR REPLACE RULE audit_log_no_delete AS ON DELETE TO audit_log DO INSTEAD NOTHING;
 
 -- ---------------------------------------------------------------------------
 -- Grant permissions
@@ -2,7 +2,9 @@
 //!
 //! Contains analyzers that scan market data for profitable opportunities.
 
+mod near_miss;
 mod sum_deviation;
 
+pub use near_miss::NearMissReporter;
 #[allow(unused_imports)]
 pub use sum_deviation::{SumDeviationAnalyzer, SumDeviationOpportunity};
@@ -0,0 +1,144 @@
+//! Near-miss reporting for sum-to-100 arbitrage.
+//!
+//! A pair whose edge falls just short of `min_edge` is not traded, but how
+//! often that happens and by how much is what tells us where the threshold
+//! should sit. Near misses are counted, published to `poly:near_misses` and
+//! recorded in the analytics sink, at most once per market per cooldown so
+//! a persistent one does not flood the channel on every evaluation.
+
+use std::sync::Arc;
+
+use dashmap::DashMap;
+
+use crate::db::AnalyticsSink;
+use crate::metrics::NEAR_MISSES;
+use crate::redis::{now_ms, NearMissMessage, RedisPublisher};
+use crate::runtime::{self, TaskGroup};
+
+use super::SumDeviationOpportunity;
+
+/// Minimum time between reports for the same market
+const REPORT_COOLDOWN_NS: u64 = 60_000_000_000;
+
+/// Counts and publishes edges within a tolerance below the trade threshold.
+pub struct NearMissReporter {
+    min_edge: f64,
+    /// How far below `min_edge` still counts as a near miss (0 = off)
+    tolerance: f64,
+    /// Last report time per market (ns)
+    last_reported: DashMap<String, u64>,
+    publisher: Option<Arc<RedisPublisher>>,
+    analytics: Option<Arc<AnalyticsSink>>,
+}
+
+impl NearMissReporter {
+    pub fn new(min_edge: f64, tolerance: f64) -> Self {
+        Self {
+            min_edge,
+            tolerance,
+            last_reported: DashMap::new(),
+            publisher: None,
+            analytics: None,
+        }
+    }
+
+    /// Publish near misses to Redis as well as counting them.
+    pub fn set_redis_publisher(&mut self, publisher: Arc<RedisPublisher>) {
+        if publisher.is_enabled() {
+            self.publisher = Some(publisher);
+        }
+    }
+
+    /// Record near misses in the analytics sink as well.
+    pub fn set_analytics_sink(&mut self, sink: Arc<AnalyticsSink>) {
+        self.analytics = Some(sink);
+    }
+
+    /// Whether an edge is below `min_edge` but within the tolerance.
+    pub fn is_near_miss(&self, edge: f64) -> bool {
+        self.tolerance > 0.0 && edge < self.min_edge && edge >= self.min_edge - self.tolerance
+    }
+
+    /// Report an opportunity if it is a near miss and its market is not in
+    /// cooldown. Returns whether it was reported.
+    pub fn report(&self, opportunity: &SumDeviationOpportunity, now_ns: u64) -> bool {
+        if !self.is_near_miss(opportunity.edge) {
+            return false;
+        }
+
+        let mut last = self
+            .last_reported
+            .entry(opportunity.market_id.clone())
+            .or_insert(0);
+        if *last != 0 && now_ns.saturating_sub(*last) < REPORT_COOLDOWN_NS {
+            return false;
+        }
+        *last = now_ns;
+        drop(last);
+
+        NEAR_MISSES.inc();
+        if self.publisher.is_none() && self.analytics.is_none() {
+            return true;
+        }
+        let message = NearMissMessage {
+            timestamp_ms: now_ms(),
+            market_id: opportunity.market_id.clone(),
+            yes_token_id: opportunity.yes_token.clone(),
+            no_token_id: opportunity.no_token.clone(),
+            yes_price: opportunity.yes_vwap.vwap,
+            no_price: opportunity.no_vwap.vwap,
+            sum: opportunity.sum,
+            edge: opportunity.edge,
+            min_edge: self.min_edge,
+            size: opportunity.recommended_size,
+        };
+        if let Some(sink) = &self.analytics {
+            sink.record_near_miss(&message);
+        }
+        if let Some(publisher) = &self.publisher {
+            let publisher = Arc::clone(publisher);
+            runtime::spawn(TaskGroup::RedisPublish, async move {
+                publisher.publish_near_miss_logged(&message).await;
+            });
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::Side;
+    use crate::market::VwapResult;
+
+    fn opportunity(market_id: &str, edge: f64) -> SumDeviationOpportunity {
+        SumDeviationOpportunity {
+            market_id: market_id.into(),
+            yes_token: "yes".into(),
+            no_token: "no".into(),
+            side: Side::Buy,
+            yes_vwap: VwapResult::default(),
+            no_vwap: VwapResult::default(),
+            sum: 1.0 - edge,
+            edge,
+            recommended_size: 100.0,
+            confidence: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_near_misses_are_rate_limited_per_market() {
+        let reporter = NearMissReporter::new(0.003, 0.005);
+        assert!(reporter.is_near_miss(0.0));
+        assert!(!reporter.is_near_miss(0.003));
+        assert!(!reporter.is_near_miss(-0.0021));
+        assert!(!NearMissReporter::new(0.003, 0.0).is_near_miss(0.002));
+
+        let t0 = 1_000_000_000;
+        assert!(reporter.report(&opportunity("a", 0.002), t0));
+        assert!(!reporter.report(&opportunity("a", 0.002), t0 + 1));
+        assert!(reporter.report(&opportunity("b", 0.002), t0 + 1));
+        assert!(!reporter.report(&opportunity("c", 0.01), t0));
+        assert!(reporter.report(&opportunity("a", 0.002), t0 + REPORT_COOLDOWN_NS));
+    }
+}
@@ -1,10 +1,21 @@
 //! Sum-to-100 Deviation Analyzer
 //!
-//! Finds markets where YES_ask + NO_ask < 1.00 (exploitable mispricing).
+//! Finds markets where YES_ask + NO_ask < 1.00 (exploitable mispricing) and,
+//! with `sell_enabled`, where YES_bid + NO_bid > 1.00 (held pairs are worth
+//! more sold than held to resolution).
 //! Uses VWAP calculations to account for depth and liquidity.
 
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use crate::config::SumTo100Config;
-use crate::market::{MarketData, MarketPair, TokenId, VwapResult};
+use crate::db::AnalyticsSink;
+use crate::execution::Side;
+use crate::market::{ImpactModel, MarketPair, MarketSnapshot, OrderBook, TokenId, VwapResult};
+use crate::redis::RedisPublisher;
+use crate::strategy::CostModel;
+
+use super::NearMissReporter;
 
 /// A detected arbitrage opportunity
 #[derive(Debug, Clone)]
@@ -15,13 +26,15 @@ pub struct SumDeviationOpportunity {
     pub yes_token: TokenId,
     /// NO token ID
     pub no_token: TokenId,
-    /// VWAP result for buying YES
+    /// Buy both tokens below $1.00, or sell held ones above it
+    pub side: Side,
+    /// VWAP result for buying (or selling) YES
     pub yes_vwap: VwapResult,
-    /// VWAP result for buying NO
+    /// VWAP result for buying (or selling) NO
     pub no_vwap: VwapResult,
     /// Sum of VWAP prices (yes_vwap.vwap + no_vwap.vwap)
     pub sum: f64,
-    /// Net edge after fees (1.0 - sum - fees)
+    /// Net edge per share after fees and slippage (see `CostModel`)
     pub edge: f64,
     /// Recommended position size (min of available liquidity and config limits)
     pub recommended_size: f64,
@@ -32,20 +45,57 @@ pub struct SumDeviationOpportunity {
 /// Analyzer that scans markets for sum-to-100 arbitrage opportunities
 pub struct SumDeviationAnalyzer {
     config: SumTo100Config,
+    /// Participation cap and depth haircut applied to displayed depth
+    impact: ImpactModel,
+    /// Reports pairs just below `min_edge`
+    near_misses: NearMissReporter,
+    /// Fees and slippage netted out of the edge
+    cost_model: CostModel,
 }
 
 impl SumDeviationAnalyzer {
     /// Create a new analyzer with the given configuration
     pub fn new(config: SumTo100Config) -> Self {
-        Self { config }
+        let impact = config.impact_model();
+        let near_misses = NearMissReporter::new(config.min_edge, config.near_miss_tolerance);
+        Self {
+            config,
+            impact,
+            near_misses,
+            cost_model: CostModel::default(),
+        }
+    }
+
+    /// Use the shared cost model for edges
+    pub fn set_cost_model(&mut self, cost_model: CostModel) {
+        self.cost_model = cost_model;
+    }
+
+    /// Publish near misses to Redis
+    pub fn set_redis_publisher(&mut self, publisher: Arc<RedisPublisher>) {
+        self.near_misses.set_redis_publisher(publisher);
+    }
+
+    /// Record near misses in the analytics sink
+    pub fn set_analytics_sink(&mut self, sink: Arc<AnalyticsSink>) {
+        self.near_misses.set_analytics_sink(sink);
     }
 
     /// Analyze all markets and return opportunities sorted by edge (highest first)
-    pub fn analyze(&self, market_data: &MarketData) -> Vec<SumDeviationOpportunity> {
-        let mut opportunities: Vec<SumDeviationOpportunity> = market_data
-            .get_all_pairs()
+    pub fn analyze(&self, snapshot: &MarketSnapshot) -> Vec<SumDeviationOpportunity> {
+        let mut opportunities: Vec<SumDeviationOpportunity> = snapshot
+            .pairs()
             .iter()
-            .filter_map(|(market_id, pair)| self.analyze_pair(market_id, pair, market_data))
+            .filter(|pair| self.config.covers_category(pair.category.as_deref()))
+            .flat_map(|pair| {
+                let buy = self.analyze_pair(&pair.market_id, pair, snapshot);
+                let sell = self
+                    .config
+                    .sell_enabled
+                    .then(|| self.analyze_pair_sell(&pair.market_id, pair, snapshot))
+                    .flatten();
+                buy.into_iter().chain(sell)
+            })
             .collect();
 
         // Sort by edge descending (best opportunities first)
@@ -63,22 +113,15 @@ impl SumDeviationAnalyzer {
         &self,
         market_id: &str,
         pair: &MarketPair,
-        market_data: &MarketData,
+        snapshot: &MarketSnapshot,
     ) -> Option<SumDeviationOpportunity> {
-        // Get order books for both tokens
-        let yes_book = market_data.get_order_book(&pair.yes_token)?;
-        let no_book = market_data.get_order_book(&pair.no_token)?;
+        let (yes_book, no_book) = self.fresh_books(pair, snapshot)?;
 
-        // Check if data is stale
-        let max_age_ns = self.config.max_book_age_ms * 1_000_000;
-        if yes_book.is_stale(max_age_ns) || no_book.is_stale(max_age_ns) {
-            return None;
-        }
-
-        // Calculate VWAP for target position size
+        // Calculate VWAP for target position size against the depth we can
+        // realistically take (participation cap + fade haircut)
         let target_size = self.config.max_position;
-        let yes_vwap = yes_book.vwap_buy(target_size)?;
-        let no_vwap = no_book.vwap_buy(target_size)?;
+        let yes_vwap = yes_book.vwap_buy_with_impact(target_size, &self.impact)?;
+        let no_vwap = no_book.vwap_buy_with_impact(target_size, &self.impact)?;
 
         // Check minimum liquidity requirement
         if yes_vwap.total_size < self.config.min_liquidity
@@ -89,12 +132,9 @@ impl SumDeviationAnalyzer {
 
         // Calculate sum and edge
         let sum = yes_vwap.vwap + no_vwap.vwap;
-        let edge = 1.0 - sum - self.config.fee_rate;
-
-        // Only report if edge exceeds minimum threshold
-        if edge < self.config.min_edge {
-            return None;
-        }
+        let edge = self
+            .cost_model
+            .arbitrage_edge(yes_vwap.vwap, no_vwap.vwap);
 
         // Determine recommended size (limited by liquidity and config)
         let max_fillable = yes_vwap.total_size.min(no_vwap.total_size);
@@ -108,10 +148,67 @@ impl SumDeviationAnalyzer {
         let liquidity_ratio = max_fillable / target_size;
         let confidence = (liquidity_ratio.min(2.0) / 2.0).min(1.0);
 
+        let opportunity = SumDeviationOpportunity {
+            market_id: market_id.to_string(),
+            yes_token: pair.yes_token.clone(),
+            no_token: pair.no_token.clone(),
+            side: Side::Buy,
+            yes_vwap,
+            no_vwap,
+            sum,
+            edge,
+            recommended_size,
+            confidence,
+        };
+
+        // Only report if edge exceeds minimum threshold; just short is a near miss
+        if edge < self.cost_model.min_edge(market_id, self.config.min_edge) {
+            self.near_misses.report(&opportunity, now_ns());
+            return None;
+        }
+
+        Some(opportunity)
+    }
+
+    /// Analyze a single market pair for selling held YES and NO into bids
+    /// that sum above $1.00. Sized by depth only; the strategy caps it to
+    /// inventory.
+    fn analyze_pair_sell(
+        &self,
+        market_id: &str,
+        pair: &MarketPair,
+        snapshot: &MarketSnapshot,
+    ) -> Option<SumDeviationOpportunity> {
+        let (yes_book, no_book) = self.fresh_books(pair, snapshot)?;
+
+        let target_size = self.config.max_position;
+        let yes_vwap = yes_book.vwap_sell_with_impact(target_size, &self.impact)?;
+        let no_vwap = no_book.vwap_sell_with_impact(target_size, &self.impact)?;
+        if yes_vwap.total_size < self.config.min_liquidity
+            || no_vwap.total_size < self.config.min_liquidity
+        {
+            return None;
+        }
+
+        let sum = yes_vwap.vwap + no_vwap.vwap;
+        let edge = self
+            .cost_model
+            .arbitrage_sell_edge(yes_vwap.vwap, no_vwap.vwap);
+        if edge < self.cost_model.min_edge(market_id, self.config.min_edge) {
+            return None;
+        }
+
+        let max_fillable = yes_vwap.total_size.min(no_vwap.total_size);
+        let recommended_size = max_fillable
+            .min(self.config.max_position)
+            .min(self.config.max_notional / sum);
+        let confidence = ((max_fillable / target_size).min(2.0) / 2.0).min(1.0);
+
         Some(SumDeviationOpportunity {
             market_id: market_id.to_string(),
             yes_token: pair.yes_token.clone(),
             no_token: pair.no_token.clone(),
+            side: Side::Sell,
             yes_vwap,
             no_vwap,
             sum,
@@ -120,12 +217,37 @@ impl SumDeviationAnalyzer {
             confidence,
         })
     }
+
+    /// Order books for both tokens, unless either is missing or stale
+    fn fresh_books<'a>(
+        &self,
+        pair: &MarketPair,
+        snapshot: &'a MarketSnapshot,
+    ) -> Option<(&'a OrderBook, &'a OrderBook)> {
+        let yes_book = snapshot.get_order_book(&pair.yes_token)?;
+        let no_book = snapshot.get_order_book(&pair.no_token)?;
+
+        // Check if data is stale
+        let max_age_ns = self.config.max_book_age_ms * 1_000_000;
+        if yes_book.is_stale(max_age_ns) || no_book.is_stale(max_age_ns) {
+            return None;
+        }
+        Some((yes_book, no_book))
+    }
+}
+
+/// Current time in nanoseconds since UNIX epoch
+fn now_ns() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::market::{DepthLevel, MarketPair};
+    use crate::market::{DepthLevel, MarketData};
 
     fn create_test_config() -> SumTo100Config {
         SumTo100Config {
@@ -134,9 +256,13 @@ mod tests {
             max_position: 100.0,
             max_notional: 100.0,
             min_liquidity: 10.0,
-            fee_rate: 0.01,
             paper_trading: true,
             max_book_age_ms: 60000, // 60 seconds for tests
+            max_participation: 1.0,
+            depth_haircut: 0.0,
+            near_miss_tolerance: 0.0,
+            categories: Vec::new(),
+            sell_enabled: false,
         }
     }
 
@@ -152,12 +278,14 @@ mod tests {
             yes_token: "yes_token".into(),
             no_token: "no_token".into(),
             question: "Will it happen?".into(),
+            category: None,
+            end_date: None,
         };
         market_data.register_pair(pair);
 
         // Set up order books with profitable spread
         // YES ask: $0.45, NO ask: $0.50, sum = $0.95
-        // Edge = 1.0 - 0.95 - 0.01 (fees) = 0.04 = 4%
+        // Edge = 1.0 - 0.95 - 0.95 * 0.01 (fees) = 0.0405, about 4%
         market_data.update_order_book(
             &"yes_token".into(),
             vec![DepthLevel::new(0.44, 100.0)], // bids
@@ -169,7 +297,7 @@ mod tests {
             vec![DepthLevel::new(0.50, 100.0)], // asks
         );
 
-        let opportunities = analyzer.analyze(&market_data);
+        let opportunities = analyzer.analyze(&market_data.snapshot(0));
         assert_eq!(opportunities.len(), 1);
 
         let opp = &opportunities[0];
@@ -178,6 +306,74 @@ mod tests {
         assert!((opp.edge - 0.04).abs() < 0.001);
     }
 
+    #[test]
+    fn test_analyzer_skips_uncovered_categories() {
+        let market_data = MarketData::new();
+        market_data.register_pair(MarketPair {
+            market_id: "test_market".into(),
+            yes_token: "yes_token".into(),
+            no_token: "no_token".into(),
+            question: "Will it happen?".into(),
+            category: Some("Sports".into()),
+            end_date: None,
+        });
+        market_data.update_order_book(
+            &"yes_token".into(),
+            vec![DepthLevel::new(0.44, 100.0)],
+            vec![DepthLevel::new(0.45, 100.0)],
+        );
+        market_data.update_order_book(
+            &"no_token".into(),
+            vec![DepthLevel::new(0.49, 100.0)],
+            vec![DepthLevel::new(0.50, 100.0)],
+        );
+        let snapshot = market_data.snapshot(0);
+
+        let mut config = create_test_config();
+        config.categories = vec!["politics".into()];
+        assert!(SumDeviationAnalyzer::new(config.clone()).analyze(&snapshot).is_empty());
+
+        config.categories = vec!["sports".into()];
+        assert_eq!(SumDeviationAnalyzer::new(config).analyze(&snapshot).len(), 1);
+    }
+
+    #[test]
+    fn test_analyzer_finds_sell_side_opportunity() {
+        let market_data = MarketData::new();
+        market_data.register_pair(MarketPair {
+            market_id: "test_market".into(),
+            yes_token: "yes_token".into(),
+            no_token: "no_token".into(),
+            question: "Will it happen?".into(),
+            category: None,
+            end_date: None,
+        });
+        // YES bid $0.56 + NO bid $0.50 = $1.06; asks sum above $1.00
+        market_data.update_order_book(
+            &"yes_token".into(),
+            vec![DepthLevel::new(0.56, 100.0)],
+            vec![DepthLevel::new(0.57, 100.0)],
+        );
+        market_data.update_order_book(
+            &"no_token".into(),
+            vec![DepthLevel::new(0.50, 100.0)],
+            vec![DepthLevel::new(0.51, 100.0)],
+        );
+        let snapshot = market_data.snapshot(0);
+
+        let mut config = create_test_config();
+        assert!(SumDeviationAnalyzer::new(config.clone()).analyze(&snapshot).is_empty());
+
+        config.sell_enabled = true;
+        let opportunities = SumDeviationAnalyzer::new(config).analyze(&snapshot);
+        assert_eq!(opportunities.len(), 1);
+        let opp = &opportunities[0];
+        assert_eq!(opp.side, Side::Sell);
+        assert!((opp.sum - 1.06).abs() < 0.001);
+        // Edge = 1.06 - 1.0 - 1.06 * 0.01 (fees) = 0.0494
+        assert!((opp.edge - 0.0494).abs() < 0.001);
+    }
+
     #[test]
     fn test_analyzer_rejects_unprofitable() {
         let config = create_test_config();
@@ -190,6 +386,8 @@ mod tests {
             yes_token: "yes_token".into(),
             no_token: "no_token".into(),
             question: "Will it happen?".into(),
+            category: None,
+            end_date: None,
         };
         market_data.register_pair(pair);
 
@@ -206,7 +404,7 @@ mod tests {
             vec![DepthLevel::new(0.52, 100.0)],
         );
 
-        let opportunities = analyzer.analyze(&market_data);
+        let opportunities = analyzer.analyze(&market_data.snapshot(0));
         assert!(opportunities.is_empty());
     }
 
@@ -222,6 +420,8 @@ mod tests {
             yes_token: "yes_token".into(),
             no_token: "no_token".into(),
             question: "Will it happen?".into(),
+            category: None,
+            end_date: None,
         };
         market_data.register_pair(pair);
 
@@ -237,7 +437,7 @@ mod tests {
             vec![DepthLevel::new(0.50, 100.0)],
         );
 
-        let opportunities = analyzer.analyze(&market_data);
+        let opportunities = analyzer.analyze(&market_data.snapshot(0));
         assert!(opportunities.is_empty());
     }
 
@@ -253,6 +453,8 @@ mod tests {
             yes_token: "yes_token".into(),
             no_token: "no_token".into(),
             question: "Will it happen?".into(),
+            category: None,
+            end_date: None,
         };
         market_data.register_pair(pair);
 
@@ -270,7 +472,7 @@ mod tests {
             vec![DepthLevel::new(0.48, 100.0)],
         );
 
-        let opportunities = analyzer.analyze(&market_data);
+        let opportunities = analyzer.analyze(&market_data.snapshot(0));
         assert_eq!(opportunities.len(), 1);
 
         let opp = &opportunities[0];
@@ -279,4 +481,88 @@ mod tests {
         // NO VWAP for 100 shares: 0.48
         assert!((opp.no_vwap.vwap - 0.48).abs() < 0.001);
     }
+
+    #[test]
+    fn test_impact_model_limits_recommended_size() {
+        let mut config = create_test_config();
+        config.max_participation = 0.5;
+        config.depth_haircut = 0.2;
+        let analyzer = SumDeviationAnalyzer::new(config);
+        let market_data = MarketData::new();
+
+        let pair = MarketPair {
+            market_id: "test_market".into(),
+            yes_token: "yes_token".into(),
+            no_token: "no_token".into(),
+            question: "Will it happen?".into(),
+            category: None,
+            end_date: None,
+        };
+        market_data.register_pair(pair);
+
+        // 100 shares displayed on each side, only 40 treated as fillable
+        market_data.update_order_book(
+            &"yes_token".into(),
+            vec![DepthLevel::new(0.44, 100.0)],
+            vec![DepthLevel::new(0.45, 100.0)],
+        );
+        market_data.update_order_book(
+            &"no_token".into(),
+            vec![DepthLevel::new(0.49, 100.0)],
+            vec![DepthLevel::new(0.50, 100.0)],
+        );
+
+        let opportunities = analyzer.analyze(&market_data.snapshot(0));
+        assert_eq!(opportunities.len(), 1);
+        assert!((opportunities[0].recommended_size - 40.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_analyzer_reports_near_miss() {
+        let mut config = create_test_config();
+        config.near_miss_tolerance = 0.005;
+        let analyzer = SumDeviationAnalyzer::new(config);
+        let market_data = MarketData::new();
+
+        let pair = MarketPair {
+            market_id: "test_market".into(),
+            yes_token: "yes_token".into(),
+            no_token: "no_token".into(),
+            question: "Will it happen?".into(),
+            category: None,
+            end_date: None,
+        };
+        market_data.register_pair(pair);
+
+        // Sum = 0.988, edge = 1.0 - 0.988 - 0.00988 = 0.0021: 0.09% short of min_edge
+        market_data.update_order_book(
+            &"yes_token".into(),
+            vec![DepthLevel::new(0.48, 100.0)],
+            vec![DepthLevel::new(0.488, 100.0)],
+        );
+        market_data.update_order_book(
+            &"no_token".into(),
+            vec![DepthLevel::new(0.49, 100.0)],
+            vec![DepthLevel::new(0.50, 100.0)],
+        );
+
+        let snapshot = market_data.snapshot(0);
+        assert!(analyzer.analyze(&snapshot).is_empty());
+
+        // analyze() reported it, so the market is now in cooldown
+        let opp = SumDeviationOpportunity {
+            market_id: "test_market".into(),
+            yes_token: "yes_token".into(),
+            no_token: "no_token".into(),
+            side: Side::Buy,
+            yes_vwap: VwapResult::default(),
+            no_vwap: VwapResult::default(),
+            sum: 0.988,
+            edge: 0.002,
+            recommended_size: 100.0,
+            confidence: 1.0,
+        };
+        assert!(analyzer.near_misses.is_near_miss(opp.edge));
+        assert!(!analyzer.near_misses.report(&opp, now_ns()));
+    }
 }
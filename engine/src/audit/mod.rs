@@ -0,0 +1,10 @@
+//! Append-only audit trail of operator actions and automated interventions.
+//!
+//! Every event is written to the log stream (`[AUDIT]` lines), the
+//! `audit_log` table, and the `poly:audit` Redis channel, so post-incident
+//! reviews have one trustworthy timeline.
+
+mod trail;
+
+#[allow(unused_imports)]
+pub use trail::{AuditAction, AuditEvent, AuditLog};
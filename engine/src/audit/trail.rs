@@ -0,0 +1,235 @@
+//! Audit event recording.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::db::TradeStore;
+use crate::redis::{channels, now_ms, RedisPublisher};
+use crate::runtime::{self, TaskGroup};
+
+/// Number of recent events kept in memory for the control API
+const RECENT_EVENTS: usize = 200;
+
+/// Kinds of audited actions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    /// Engine started with a configuration
+    EngineStart,
+    /// Engine shutdown requested
+    Shutdown,
+    /// Emergency stop activated
+    EmergencyStop,
+    /// Emergency stop cleared
+    EmergencyStopCleared,
+    /// Runtime configuration changed
+    ConfigChange,
+    /// Strategy enabled at startup, or shadowed or disabled by the governor
+    StrategyToggle,
+    /// Position sold down ahead of market resolution
+    Flatten,
+    /// Component disabled automatically (risk limit, circuit breaker, ...)
+    AutoDisable,
+    /// This instance gained or lost the leader lease
+    LeaderChange,
+    /// Exchange fee statement imported (rewrites recorded net P&L)
+    FeeImport,
+    /// Signal parked for manual approval released for execution
+    ApprovalGranted,
+    /// Signal parked for manual approval dropped
+    ApprovalRejected,
+}
+
+impl AuditAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AuditAction::EngineStart => "engine_start",
+            AuditAction::Shutdown => "shutdown",
+            AuditAction::EmergencyStop => "emergency_stop",
+            AuditAction::EmergencyStopCleared => "emergency_stop_cleared",
+            AuditAction::ConfigChange => "config_change",
+            AuditAction::StrategyToggle => "strategy_toggle",
+            AuditAction::Flatten => "flatten",
+            AuditAction::AutoDisable => "auto_disable",
+            AuditAction::LeaderChange => "leader_change",
+            AuditAction::FeeImport => "fee_import",
+            AuditAction::ApprovalGranted => "approval_granted",
+            AuditAction::ApprovalRejected => "approval_rejected",
+        }
+    }
+}
+
+impl fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A single audit record (who / what / when)
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEvent {
+    pub timestamp_ms: u64,
+    /// Who: "operator:http", "risk", "engine", ...
+    pub actor: String,
+    /// What kind of action
+    pub action: AuditAction,
+    /// What it applied to (strategy, market, token), if anything specific
+    pub target: Option<String>,
+    /// Free-form details (reason, old/new values)
+    pub details: String,
+}
+
+/// Audit trail writer.
+///
+/// Recording never blocks: DB and Redis writes are fire-and-forget, and the
+/// log line is emitted synchronously so it survives even if both are down.
+pub struct AuditLog {
+    trade_repo: Option<Arc<dyn TradeStore>>,
+    redis_publisher: Option<Arc<RedisPublisher>>,
+    recent: Mutex<VecDeque<AuditEvent>>,
+}
+
+impl AuditLog {
+    pub fn new() -> Self {
+        Self {
+            trade_repo: None,
+            redis_publisher: None,
+            recent: Mutex::new(VecDeque::with_capacity(RECENT_EVENTS)),
+        }
+    }
+
+    /// Set the repository used to persist events to `audit_log`.
+    pub fn set_trade_repo(&mut self, repo: Arc<dyn TradeStore>) {
+        self.trade_repo = Some(repo);
+    }
+
+    /// Set the Redis publisher used to stream events on the `audit` channel.
+    pub fn set_redis_publisher(&mut self, publisher: Arc<RedisPublisher>) {
+        self.redis_publisher = Some(publisher);
+    }
+
+    /// Record an audit event.
+    pub fn record(
+        &self,
+        actor: &str,
+        action: AuditAction,
+        target: Option<&str>,
+        details: impl Into<String>,
+    ) {
+        let event = AuditEvent {
+            timestamp_ms: now_ms(),
+            actor: actor.to_string(),
+            action,
+            target: target.map(str::to_string),
+            details: details.into(),
+        };
+
+        info!(
+            "[AUDIT] actor={} action={} target={} details={}",
+            event.actor,
+            event.action,
+            event.target.as_deref().unwrap_or("-"),
+            event.details
+        );
+
+        if let Some(repo) = &self.trade_repo {
+            repo.insert_audit_event(event.clone());
+        }
+
+        if let Some(publisher) = &self.redis_publisher {
+            if publisher.is_enabled() {
+                match serde_json::to_string(&event) {
+                    Ok(json) => {
+                        let publisher = Arc::clone(publisher);
+                        runtime::spawn(TaskGroup::RedisPublish, async move {
+                            if let Err(e) = publisher.publish_raw(channels::AUDIT, &json).await {
+                                warn!("[AUDIT] Failed to publish audit event: {}", e);
+                            }
+                        });
+                    }
+                    Err(e) => warn!("[AUDIT] Failed to serialize audit event: {}", e),
+                }
+            }
+        }
+
+        let mut recent = self.recent.lock();
+        if recent.len() == RECENT_EVENTS {
+            recent.pop_front();
+        }
+        recent.push_back(event);
+    }
+
+    /// Most recent events, oldest first.
+    pub fn recent(&self) -> Vec<AuditEvent> {
+        self.recent.lock().iter().cloned().collect()
+    }
+}
+
+impl Default for AuditLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_keeps_recent_events_in_order() {
+        let audit = AuditLog::new();
+        audit.record(
+            "operator:http",
+            AuditAction::EmergencyStop,
+            None,
+            "manual halt",
+        );
+        audit.record(
+            "risk",
+            AuditAction::AutoDisable,
+            Some("SNIPER"),
+            "daily loss limit",
+        );
+
+        let events = audit.recent();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].action, AuditAction::EmergencyStop);
+        assert_eq!(events[1].actor, "risk");
+        assert_eq!(events[1].target.as_deref(), Some("SNIPER"));
+    }
+
+    #[test]
+    fn test_recent_is_bounded() {
+        let audit = AuditLog::new();
+        for i in 0..(RECENT_EVENTS + 5) {
+            audit.record(
+                "engine",
+                AuditAction::ConfigChange,
+                None,
+                format!("change {}", i),
+            );
+        }
+
+        let events = audit.recent();
+        assert_eq!(events.len(), RECENT_EVENTS);
+        assert_eq!(events[0].details, "change 5");
+    }
+
+    #[test]
+    fn test_event_serializes_action_as_snake_case() {
+        let event = AuditEvent {
+            timestamp_ms: 1,
+            actor: "operator:http".into(),
+            action: AuditAction::EmergencyStopCleared,
+            target: None,
+            details: String::new(),
+        };
+        let json = serde_json::to_string(&event).unwrap();
+        assert!(json.contains(r#""action":"emergency_stop_cleared""#));
+    }
+}
@@ -0,0 +1,281 @@
+//! Recorded order books to replay, and the markets they belong to.
+//!
+//! Records have the shape of the ClickHouse `book_snapshots` table, so a
+//! file is just an export of it:
+//! `SELECT * FROM poly.book_snapshots FORMAT JSONEachRow`.
+
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use tracing::{info, warn};
+
+use crate::db::ClickHouseConfig;
+use crate::market::{DepthLevel, MarketId, MarketPair, TokenId};
+
+/// Where recorded books come from
+#[derive(Debug, Clone, PartialEq)]
+pub enum BacktestSource {
+    /// JSON lines of book snapshots
+    File(PathBuf),
+    /// The `book_snapshots` table, between two times
+    ClickHouse {
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    },
+}
+
+/// A backtest run from the command line
+#[derive(Debug, Clone, PartialEq)]
+pub struct BacktestArgs {
+    pub source: BacktestSource,
+    /// JSON lines of `MarketPair`s; without it pairs are inferred
+    pub pairs: Option<PathBuf>,
+    /// Strategies to run instead of `STRATEGIES`
+    pub strategies: Option<Vec<String>>,
+    /// Where to write the JSON report
+    pub out: Option<PathBuf>,
+}
+
+impl BacktestArgs {
+    /// The backtest from `--backtest=<file>` or `--backtest=clickhouse`
+    /// (with `--from=` and `--to=`, default now), `--pairs=`,
+    /// `--strategies=` and `--out=`, if requested among `args`.
+    pub fn from_args(
+        args: impl IntoIterator<Item = String>,
+        now: DateTime<Utc>,
+    ) -> Result<Option<Self>> {
+        let mut source = None;
+        let (mut from, mut to) = (None, None);
+        let mut pairs = None;
+        let mut strategies = None;
+        let mut out = None;
+        for arg in args {
+            if let Some(value) = arg.strip_prefix("--backtest=") {
+                source = Some(value.to_string());
+            } else if let Some(value) = arg.strip_prefix("--from=") {
+                from = Some(parse_time(value)?);
+            } else if let Some(value) = arg.strip_prefix("--to=") {
+                to = Some(parse_time(value)?);
+            } else if let Some(value) = arg.strip_prefix("--pairs=") {
+                pairs = Some(PathBuf::from(value));
+            } else if let Some(value) = arg.strip_prefix("--strategies=") {
+                strategies = Some(
+                    value
+                        .split(',')
+                        .map(|s| s.trim().to_lowercase())
+                        .filter(|s| !s.is_empty())
+                        .collect(),
+                );
+            } else if let Some(value) = arg.strip_prefix("--out=") {
+                out = Some(PathBuf::from(value));
+            }
+        }
+        let Some(source) = source else {
+            return Ok(None);
+        };
+        let source = if source.eq_ignore_ascii_case("clickhouse") {
+            let Some(from) = from else {
+                bail!("--backtest=clickhouse needs --from=<RFC 3339 time>");
+            };
+            let to = to.unwrap_or(now);
+            if from >= to {
+                bail!("--from must be before --to");
+            }
+            BacktestSource::ClickHouse { from, to }
+        } else if source.is_empty() {
+            bail!("--backtest needs a file or 'clickhouse'");
+        } else {
+            BacktestSource::File(PathBuf::from(source))
+        };
+        Ok(Some(Self {
+            source,
+            pairs,
+            strategies,
+            out,
+        }))
+    }
+}
+
+fn parse_time(value: &str) -> Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|t| t.with_timezone(&Utc))
+        .with_context(|| format!("Invalid time '{}' (expected RFC 3339)", value))
+}
+
+/// One recorded order book
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct BookRecord {
+    pub timestamp_ms: u64,
+    pub token_id: TokenId,
+    #[serde(default)]
+    pub market_id: Option<MarketId>,
+    #[serde(default)]
+    pub bid_prices: Vec<f64>,
+    #[serde(default)]
+    pub bid_sizes: Vec<f64>,
+    #[serde(default)]
+    pub ask_prices: Vec<f64>,
+    #[serde(default)]
+    pub ask_sizes: Vec<f64>,
+}
+
+impl BookRecord {
+    pub fn bids(&self) -> Vec<DepthLevel> {
+        levels(&self.bid_prices, &self.bid_sizes)
+    }
+
+    pub fn asks(&self) -> Vec<DepthLevel> {
+        levels(&self.ask_prices, &self.ask_sizes)
+    }
+}
+
+fn levels(prices: &[f64], sizes: &[f64]) -> Vec<DepthLevel> {
+    prices
+        .iter()
+        .zip(sizes)
+        .map(|(&price, &size)| DepthLevel::new(price, size))
+        .collect()
+}
+
+/// Load the recorded books, oldest first.
+pub async fn load(source: &BacktestSource) -> Result<Vec<BookRecord>> {
+    let mut records = match source {
+        BacktestSource::File(path) => read_lines(path)?,
+        BacktestSource::ClickHouse { from, to } => {
+            query_clickhouse(&ClickHouseConfig::from_env(), *from, *to).await?
+        }
+    };
+    records.sort_by_key(|r| r.timestamp_ms);
+    info!("[BACKTEST] Loaded {} book snapshots", records.len());
+    Ok(records)
+}
+
+/// Parse JSON lines, skipping (and counting) lines that do not parse.
+fn read_lines<T: for<'de> Deserialize<'de>>(path: &Path) -> Result<Vec<T>> {
+    let file =
+        std::fs::File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut rows = Vec::new();
+    let mut invalid = 0;
+    for line in std::io::BufReader::new(file).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(&line) {
+            Ok(row) => rows.push(row),
+            Err(_) => invalid += 1,
+        }
+    }
+    if invalid > 0 {
+        warn!(
+            "[BACKTEST] Skipped {} unparseable lines in {}",
+            invalid,
+            path.display()
+        );
+    }
+    Ok(rows)
+}
+
+async fn query_clickhouse(
+    config: &ClickHouseConfig,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<BookRecord>> {
+    let url = config.url.as_deref().context("CLICKHOUSE_URL not set")?;
+    let query = format!(
+        "SELECT timestamp_ms, token_id, market_id, bid_prices, bid_sizes, ask_prices, ask_sizes \
+         FROM {}.book_snapshots WHERE timestamp_ms >= {} AND timestamp_ms < {} \
+         ORDER BY timestamp_ms FORMAT JSONEachRow",
+        config.database,
+        from.timestamp_millis(),
+        to.timestamp_millis()
+    );
+    let mut request = reqwest::Client::new()
+        .post(url)
+        // UInt64 as numbers, not quoted strings
+        .query(&[("output_format_json_quote_64bit_integers", "0")])
+        .body(query);
+    if let Some(user) = &config.user {
+        request = request.header("X-ClickHouse-User", user);
+    }
+    if let Some(password) = &config.password {
+        request = request.header("X-ClickHouse-Key", password);
+    }
+    let response = request.send().await.context("ClickHouse query failed")?;
+    let status = response.status();
+    let body = response.text().await?;
+    if !status.is_success() {
+        bail!("ClickHouse query failed ({}): {}", status, body.trim());
+    }
+    body.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(line).context("Invalid book snapshot row"))
+        .collect()
+}
+
+/// The markets to register: from the pairs file, or inferred from records
+/// whose market has exactly two tokens. Inferred pairs take the first token
+/// seen as YES, so strategies that care which outcome is which need the
+/// pairs file.
+pub fn pairs(path: Option<&Path>, records: &[BookRecord]) -> Result<Vec<MarketPair>> {
+    if let Some(path) = path {
+        return read_lines(path);
+    }
+    let mut tokens: HashMap<&MarketId, Vec<&TokenId>> = HashMap::new();
+    for record in records {
+        let Some(market_id) = &record.market_id else {
+            continue;
+        };
+        let seen = tokens.entry(market_id).or_default();
+        if !seen.contains(&&record.token_id) {
+            seen.push(&record.token_id);
+        }
+    }
+    let mut pairs: Vec<MarketPair> = tokens
+        .into_iter()
+        .filter_map(|(market_id, tokens)| match tokens[..] {
+            [yes, no] => Some(MarketPair {
+                market_id: market_id.clone(),
+                yes_token: yes.clone(),
+                no_token: no.clone(),
+                question: market_id.clone(),
+                category: None,
+                end_date: None,
+            }),
+            _ => None,
+        })
+        .collect();
+    pairs.sort_by(|a, b| a.market_id.cmp(&b.market_id));
+    Ok(pairs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_args_select_file_or_clickhouse() {
+        let now = Utc::now();
+        let args = |list: &[&str]| BacktestArgs::from_args(list.iter().map(|s| s.to_string()), now);
+
+        assert_eq!(args(&["--dry-run"]).unwrap(), None);
+        let file = args(&["--backtest=books.jsonl", "--strategies=SumTo100, clipper"])
+            .unwrap()
+            .unwrap();
+        assert_eq!(file.source, BacktestSource::File("books.jsonl".into()));
+        assert_eq!(
+            file.strategies,
+            Some(vec!["sumto100".to_string(), "clipper".to_string()])
+        );
+
+        assert!(args(&["--backtest=clickhouse"]).is_err());
+        let db = args(&["--backtest=clickhouse", "--from=2026-01-01T00:00:00Z"])
+            .unwrap()
+            .unwrap();
+        assert!(matches!(db.source, BacktestSource::ClickHouse { to, .. } if to == now));
+    }
+}
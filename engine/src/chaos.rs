@@ -0,0 +1,154 @@
+//! Fault injection for resilience testing (build with `--features chaos`).
+//!
+//! When the feature is enabled, env-controlled rates make order submissions
+//! slow or fail, WebSocket messages disappear, and Redis publishes error, so
+//! the retry, circuit-breaker and reconnect paths can be exercised against a
+//! real deployment. Without the feature every hook is a no-op that compiles
+//! away.
+//!
+//! Rates are probabilities in 0..=1:
+//! - `CHAOS_ORDER_FAIL_RATE`: order submission fails with a network error
+//! - `CHAOS_ORDER_DELAY_RATE` / `CHAOS_ORDER_DELAY_MS`: order submission is
+//!   delayed by up to `CHAOS_ORDER_DELAY_MS`
+//! - `CHAOS_WS_DROP_RATE`: an incoming WebSocket message is discarded
+//! - `CHAOS_REDIS_FAIL_RATE`: a Redis publish fails
+
+#[cfg(feature = "chaos")]
+mod imp {
+    use std::sync::OnceLock;
+    use std::time::Duration;
+
+    use tracing::warn;
+
+    use crate::execution::ExecutionError;
+    use crate::metrics::CHAOS_FAULTS;
+
+    /// Fault injection rates
+    #[derive(Debug, Clone, Default, PartialEq)]
+    pub(super) struct ChaosConfig {
+        pub order_fail_rate: f64,
+        pub order_delay_rate: f64,
+        pub order_delay: Duration,
+        pub ws_drop_rate: f64,
+        pub redis_fail_rate: f64,
+    }
+
+    impl ChaosConfig {
+        pub fn from_env() -> Self {
+            let rate = |name: &str| {
+                std::env::var(name)
+                    .ok()
+                    .and_then(|v| v.parse::<f64>().ok())
+                    .filter(|r| r.is_finite())
+                    .map(|r| r.clamp(0.0, 1.0))
+                    .unwrap_or(0.0)
+            };
+            Self {
+                order_fail_rate: rate("CHAOS_ORDER_FAIL_RATE"),
+                order_delay_rate: rate("CHAOS_ORDER_DELAY_RATE"),
+                order_delay: Duration::from_millis(
+                    std::env::var("CHAOS_ORDER_DELAY_MS")
+                        .ok()
+                        .and_then(|v| v.parse().ok())
+                        .unwrap_or(1000),
+                ),
+                ws_drop_rate: rate("CHAOS_WS_DROP_RATE"),
+                redis_fail_rate: rate("CHAOS_REDIS_FAIL_RATE"),
+            }
+        }
+
+        pub fn is_active(&self) -> bool {
+            self.order_fail_rate > 0.0
+                || self.order_delay_rate > 0.0
+                || self.ws_drop_rate > 0.0
+                || self.redis_fail_rate > 0.0
+        }
+    }
+
+    fn config() -> &'static ChaosConfig {
+        static CONFIG: OnceLock<ChaosConfig> = OnceLock::new();
+        CONFIG.get_or_init(ChaosConfig::from_env)
+    }
+
+    /// Roll a fault with probability `rate`, counting it by `kind`.
+    fn roll(rate: f64, kind: &str) -> bool {
+        let hit = rate > 0.0 && rand::random::<f64>() < rate;
+        if hit {
+            CHAOS_FAULTS.with_label_values(&[kind]).inc();
+        }
+        hit
+    }
+
+    pub fn log_active() {
+        let config = config();
+        if config.is_active() {
+            warn!("[CHAOS] Fault injection ACTIVE: {:?}", config);
+        }
+    }
+
+    pub async fn order_fault() -> Option<ExecutionError> {
+        let config = config();
+        if roll(config.order_delay_rate, "order_delay") {
+            let delay = config.order_delay.mul_f64(rand::random::<f64>());
+            tokio::time::sleep(delay).await;
+        }
+        roll(config.order_fail_rate, "order_fail")
+            .then(|| ExecutionError::Network("injected fault (chaos)".to_string()))
+    }
+
+    pub fn drop_ws_message() -> bool {
+        roll(config().ws_drop_rate, "ws_drop")
+    }
+
+    pub fn fail_redis_publish() -> bool {
+        roll(config().redis_fail_rate, "redis_fail")
+    }
+}
+
+/// Warn at startup if any fault injection is active.
+pub fn log_active() {
+    #[cfg(feature = "chaos")]
+    imp::log_active();
+}
+
+/// Injected delay and/or failure for an order submission.
+pub async fn order_fault() -> Option<crate::execution::ExecutionError> {
+    #[cfg(feature = "chaos")]
+    return imp::order_fault().await;
+    #[cfg(not(feature = "chaos"))]
+    None
+}
+
+/// Whether to discard the current WebSocket message.
+#[inline]
+pub fn drop_ws_message() -> bool {
+    #[cfg(feature = "chaos")]
+    return imp::drop_ws_message();
+    #[cfg(not(feature = "chaos"))]
+    false
+}
+
+/// Whether to fail the current Redis publish.
+#[inline]
+pub fn fail_redis_publish() -> bool {
+    #[cfg(feature = "chaos")]
+    return imp::fail_redis_publish();
+    #[cfg(not(feature = "chaos"))]
+    false
+}
+
+#[cfg(all(test, feature = "chaos"))]
+mod tests {
+    use super::imp::ChaosConfig;
+
+    #[test]
+    fn test_chaos_config_defaults_to_inactive() {
+        let config = ChaosConfig::default();
+        assert!(!config.is_active());
+        assert!(ChaosConfig {
+            ws_drop_rate: 0.1,
+            ..ChaosConfig::default()
+        }
+        .is_active());
+    }
+}
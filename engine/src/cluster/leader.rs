@@ -0,0 +1,401 @@
+//! Redis lease-based leader election.
+//!
+//! The leader holds a shared lease key (value = instance ID) with a TTL and
+//! renews it every third of the lease. A standby retries `SET NX` on the same
+//! schedule, so it takes over within one lease of the leader dying.
+//!
+//! Leadership is bounded by time as well as by Redis: if renewals fail, the
+//! leader stops trading once its last confirmed lease would have expired,
+//! which is exactly when a standby can acquire it.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use redis::aio::ConnectionManager;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::audit::{AuditAction, AuditLog};
+use crate::metrics::IS_LEADER;
+use crate::notifications::{ErrorAlert, SlackNotifier};
+use crate::redis::now_ms;
+
+/// Extend the lease only if we still own it
+const RENEW_SCRIPT: &str = r#"
+if redis.call('GET', KEYS[1]) == ARGV[1] then
+    return redis.call('PEXPIRE', KEYS[1], ARGV[2])
+end
+return 0
+"#;
+
+/// Delete the lease only if we still own it
+const RELEASE_SCRIPT: &str = r#"
+if redis.call('GET', KEYS[1]) == ARGV[1] then
+    return redis.call('DEL', KEYS[1])
+end
+return 0
+"#;
+
+/// Leader election settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeaderElectionConfig {
+    /// Whether to elect a leader (false = this instance always trades)
+    pub enabled: bool,
+
+    /// Redis key holding the lease; must be the same on every instance of
+    /// the group (instance channel prefixes differ, so it is not derived)
+    pub key: String,
+
+    /// Lease TTL; failover happens within one lease of the leader dying
+    pub lease: Duration,
+}
+
+impl Default for LeaderElectionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            key: "poly:leader".to_string(),
+            lease: Duration::from_secs(10),
+        }
+    }
+}
+
+impl LeaderElectionConfig {
+    /// Load from `LEADER_ELECTION_ENABLED`, `LEADER_LEASE_KEY`, and
+    /// `LEADER_LEASE_MS`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            enabled: std::env::var("LEADER_ELECTION_ENABLED")
+                .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+                .unwrap_or(defaults.enabled),
+            key: std::env::var("LEADER_LEASE_KEY").unwrap_or(defaults.key),
+            lease: std::env::var("LEADER_LEASE_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.lease),
+        }
+    }
+}
+
+/// Leader election state for this instance.
+pub struct LeaderElection {
+    instance_id: String,
+    key: String,
+    lease: Duration,
+    enabled: bool,
+    connection: Option<ConnectionManager>,
+    /// Epoch ms until which our last confirmed lease is valid (0 = none)
+    lease_until_ms: AtomicU64,
+    /// Role last reported (for transition alerts)
+    reported_leader: AtomicBool,
+    /// Set after the first acquisition attempt; later acquisitions are failovers
+    attempted: AtomicBool,
+    slack: Option<Arc<SlackNotifier>>,
+    audit: Option<Arc<AuditLog>>,
+}
+
+impl LeaderElection {
+    /// Connect to Redis and prepare to contend for the lease.
+    ///
+    /// Returns a disabled election (always leader) when `config.enabled` is
+    /// false.
+    pub async fn new(
+        config: LeaderElectionConfig,
+        redis_url: Option<&str>,
+        instance_id: &str,
+    ) -> Result<Self> {
+        if !config.enabled {
+            return Ok(Self::disabled(instance_id));
+        }
+        let Some(url) = redis_url else {
+            bail!("LEADER_ELECTION_ENABLED requires REDIS_URL");
+        };
+        if config.lease < Duration::from_millis(300) {
+            bail!(
+                "LEADER_LEASE_MS must be >= 300, got {}",
+                config.lease.as_millis()
+            );
+        }
+
+        let client = redis::Client::open(url).context("Failed to create Redis client")?;
+        let connection = ConnectionManager::new(client)
+            .await
+            .context("Failed to connect to Redis for leader election")?;
+
+        info!(
+            "[LEADER] Leader election enabled (instance={}, key={}, lease={}ms)",
+            instance_id,
+            config.key,
+            config.lease.as_millis()
+        );
+
+        Ok(Self {
+            connection: Some(connection),
+            enabled: true,
+            lease: config.lease,
+            ..Self::disabled_with_key(instance_id, config.key)
+        })
+    }
+
+    /// Election disabled: this instance is always the leader.
+    pub fn disabled(instance_id: &str) -> Self {
+        Self::disabled_with_key(instance_id, String::new())
+    }
+
+    fn disabled_with_key(instance_id: &str, key: String) -> Self {
+        Self {
+            instance_id: instance_id.to_string(),
+            key,
+            lease: LeaderElectionConfig::default().lease,
+            enabled: false,
+            connection: None,
+            lease_until_ms: AtomicU64::new(0),
+            reported_leader: AtomicBool::new(false),
+            attempted: AtomicBool::new(false),
+            slack: None,
+            audit: None,
+        }
+    }
+
+    /// Set the Slack notifier used for failover alerts.
+    pub fn set_slack_notifier(&mut self, notifier: Arc<SlackNotifier>) {
+        self.slack = Some(notifier);
+    }
+
+    /// Set the audit log used to record role changes.
+    pub fn set_audit_log(&mut self, audit: Arc<AuditLog>) {
+        self.audit = Some(audit);
+    }
+
+    /// Whether this instance may place orders right now.
+    pub fn is_leader(&self) -> bool {
+        !self.enabled || now_ms() < self.lease_until_ms.load(Ordering::Acquire)
+    }
+
+    /// Whether leader election is active
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Role name for health output and logs
+    pub fn role(&self) -> &'static str {
+        if self.is_leader() {
+            "leader"
+        } else {
+            "standby"
+        }
+    }
+
+    /// Contend for / renew the lease until cancelled, then release it.
+    pub async fn run(self: Arc<Self>, cancel: CancellationToken) {
+        if !self.enabled {
+            IS_LEADER.set(1.0);
+            return;
+        }
+
+        let mut ticker = tokio::time::interval(self.lease / 3);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => self.tick().await,
+                _ = cancel.cancelled() => break,
+            }
+        }
+
+        if self.reported_leader.load(Ordering::Acquire) {
+            match self.release().await {
+                Ok(()) => info!("[LEADER] Released leader lease for fast failover"),
+                Err(e) => warn!("[LEADER] Failed to release leader lease: {}", e),
+            }
+        }
+    }
+
+    /// One acquire-or-renew round.
+    async fn tick(&self) {
+        let started_ms = now_ms();
+        let outcome = if self.reported_leader.load(Ordering::Acquire) {
+            self.renew().await
+        } else {
+            self.try_acquire().await
+        };
+        self.apply(outcome, started_ms);
+    }
+
+    async fn try_acquire(&self) -> Result<bool> {
+        let mut conn = self.connection.clone().context("No Redis connection")?;
+        let acquired: Option<String> = redis::cmd("SET")
+            .arg(&self.key)
+            .arg(&self.instance_id)
+            .arg("NX")
+            .arg("PX")
+            .arg(self.lease.as_millis() as u64)
+            .query_async(&mut conn)
+            .await?;
+        Ok(acquired.is_some())
+    }
+
+    async fn renew(&self) -> Result<bool> {
+        let mut conn = self.connection.clone().context("No Redis connection")?;
+        let renewed: i64 = redis::cmd("EVAL")
+            .arg(RENEW_SCRIPT)
+            .arg(1)
+            .arg(&self.key)
+            .arg(&self.instance_id)
+            .arg(self.lease.as_millis() as u64)
+            .query_async(&mut conn)
+            .await?;
+        Ok(renewed == 1)
+    }
+
+    async fn release(&self) -> Result<()> {
+        self.lease_until_ms.store(0, Ordering::Release);
+        IS_LEADER.set(0.0);
+        let mut conn = self.connection.clone().context("No Redis connection")?;
+        let _: i64 = redis::cmd("EVAL")
+            .arg(RELEASE_SCRIPT)
+            .arg(1)
+            .arg(&self.key)
+            .arg(&self.instance_id)
+            .query_async(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Update the lease from an acquire/renew outcome and report transitions.
+    ///
+    /// `started_ms` is when the request was sent, so the lease we record is
+    /// never longer than the one Redis granted.
+    fn apply(&self, outcome: Result<bool>, started_ms: u64) {
+        match outcome {
+            Ok(true) => {
+                let lease_ms = self.lease.as_millis() as u64;
+                self.lease_until_ms
+                    .store(started_ms + lease_ms, Ordering::Release);
+            }
+            Ok(false) => self.lease_until_ms.store(0, Ordering::Release),
+            Err(e) => {
+                // Keep the last confirmed lease; it expires on its own
+                warn!("[LEADER] Lease request failed: {}", e);
+            }
+        }
+
+        let first_attempt = !self.attempted.swap(true, Ordering::AcqRel);
+        let leader = self.is_leader();
+        let was_leader = self.reported_leader.swap(leader, Ordering::AcqRel);
+        IS_LEADER.set(if leader { 1.0 } else { 0.0 });
+
+        match (was_leader, leader) {
+            (false, true) if first_attempt => {
+                info!("[LEADER] {} acquired leadership", self.instance_id);
+                self.record("acquired leader lease at startup");
+            }
+            (false, true) => {
+                warn!(
+                    "[LEADER] {} took over as leader (failover)",
+                    self.instance_id
+                );
+                self.record("took over leader lease (failover)");
+                self.alert("FAILOVER", "took over as leader - now placing orders");
+            }
+            (true, false) => {
+                warn!(
+                    "[LEADER] {} lost leadership - order placement stopped",
+                    self.instance_id
+                );
+                self.record("lost leader lease");
+                self.alert(
+                    "LEADERSHIP_LOST",
+                    "lost the leader lease - now standby, order placement stopped",
+                );
+            }
+            (false, false) if first_attempt => {
+                info!(
+                    "[LEADER] {} starting as standby (another instance holds the lease)",
+                    self.instance_id
+                );
+                self.record("starting as standby");
+            }
+            _ => {}
+        }
+    }
+
+    fn record(&self, details: &str) {
+        if let Some(audit) = &self.audit {
+            audit.record(
+                "leader-election",
+                AuditAction::LeaderChange,
+                Some(&self.instance_id),
+                details,
+            );
+        }
+    }
+
+    fn alert(&self, error_type: &str, message: &str) {
+        if let Some(slack) = &self.slack {
+            slack.notify_error(ErrorAlert {
+                source: "leader-election".to_string(),
+                error_type: error_type.to_string(),
+                message: format!("Instance {} {}", self.instance_id, message),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled_election(audit: Arc<AuditLog>) -> LeaderElection {
+        let mut election = LeaderElection {
+            enabled: true,
+            lease: Duration::from_millis(1_000),
+            ..LeaderElection::disabled_with_key("eu-1", "poly:leader".into())
+        };
+        election.set_audit_log(audit);
+        election
+    }
+
+    #[test]
+    fn test_disabled_election_is_always_leader() {
+        let election = LeaderElection::disabled("default");
+        assert!(election.is_leader());
+        assert_eq!(election.role(), "leader");
+    }
+
+    #[test]
+    fn test_lease_outcomes_drive_role() {
+        let audit = Arc::new(AuditLog::new());
+        let election = enabled_election(audit.clone());
+        assert!(!election.is_leader());
+
+        election.apply(Ok(true), now_ms());
+        assert!(election.is_leader());
+
+        // A failed renewal keeps the confirmed lease until it runs out
+        election.apply(Err(anyhow::anyhow!("redis down")), now_ms());
+        assert!(election.is_leader());
+
+        // Once the lease has lapsed the instance steps down
+        election.lease_until_ms.store(1, Ordering::Release);
+        election.apply(Err(anyhow::anyhow!("redis down")), now_ms());
+        assert!(!election.is_leader());
+
+        // Another instance holds the lease; acquiring later is a failover
+        election.apply(Ok(false), now_ms());
+        election.apply(Ok(true), now_ms());
+        assert!(election.is_leader());
+
+        let details: Vec<String> = audit.recent().into_iter().map(|e| e.details).collect();
+        assert_eq!(
+            details,
+            vec![
+                "acquired leader lease at startup",
+                "lost leader lease",
+                "took over leader lease (failover)",
+            ]
+        );
+    }
+}
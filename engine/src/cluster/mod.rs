@@ -0,0 +1,17 @@
+//! Multi-instance coordination.
+//!
+//! Active/standby deployments elect a single leader through a Redis lease;
+//! only the leader places orders while standbys keep ingesting market data.
+//! Sharded deployments split the market universe by hash across instances
+//! and share account-wide risk state through Redis.
+
+mod leader;
+mod shard;
+mod shared_risk;
+
+#[allow(unused_imports)]
+pub use leader::{LeaderElection, LeaderElectionConfig};
+#[allow(unused_imports)]
+pub use shard::ShardConfig;
+#[allow(unused_imports)]
+pub use shared_risk::{SharedRiskConfig, SharedRiskState};
@@ -0,0 +1,108 @@
+//! Deterministic market-to-shard assignment.
+//!
+//! Each instance is configured with a shard count and its own index; a market
+//! belongs to shard `fnv1a(market_id) % count`. FNV-1a is used instead of
+//! `DefaultHasher` because the assignment must agree across builds and
+//! processes.
+
+use anyhow::{bail, Result};
+
+/// This instance's slice of the market universe
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShardConfig {
+    /// Total number of shards (1 = unsharded)
+    pub count: u32,
+
+    /// This instance's shard, in `0..count`
+    pub index: u32,
+}
+
+impl Default for ShardConfig {
+    fn default() -> Self {
+        Self { count: 1, index: 0 }
+    }
+}
+
+impl ShardConfig {
+    /// Create a shard assignment, validating `index < count`.
+    pub fn new(count: u32, index: u32) -> Result<Self> {
+        if count == 0 {
+            bail!("SHARD_COUNT must be >= 1");
+        }
+        if index >= count {
+            bail!("SHARD_INDEX must be < SHARD_COUNT ({} >= {})", index, count);
+        }
+        Ok(Self { count, index })
+    }
+
+    /// Load from `SHARD_COUNT` and `SHARD_INDEX`.
+    pub fn from_env() -> Result<Self> {
+        let parse = |name: &str, default: u32| -> Result<u32> {
+            match std::env::var(name) {
+                Ok(v) => match v.parse() {
+                    Ok(n) => Ok(n),
+                    Err(_) => bail!("{} must be a non-negative integer, got '{}'", name, v),
+                },
+                Err(_) => Ok(default),
+            }
+        };
+        let defaults = Self::default();
+        Self::new(
+            parse("SHARD_COUNT", defaults.count)?,
+            parse("SHARD_INDEX", defaults.index)?,
+        )
+    }
+
+    /// Whether the market universe is split across instances
+    pub fn is_sharded(&self) -> bool {
+        self.count > 1
+    }
+
+    /// Shard a market belongs to
+    pub fn shard_of(&self, market_id: &str) -> u32 {
+        (fnv1a(market_id.as_bytes()) % u64::from(self.count)) as u32
+    }
+
+    /// Whether this instance trades the given market
+    pub fn owns(&self, market_id: &str) -> bool {
+        !self.is_sharded() || self.shard_of(market_id) == self.index
+    }
+}
+
+impl std::fmt::Display for ShardConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.index, self.count)
+    }
+}
+
+/// 64-bit FNV-1a
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_market_has_exactly_one_owner() {
+        let shards: Vec<ShardConfig> = (0..4).map(|i| ShardConfig::new(4, i).unwrap()).collect();
+        for n in 0..200 {
+            let market_id = format!("0x{:064x}", n);
+            let owners = shards.iter().filter(|s| s.owns(&market_id)).count();
+            assert_eq!(owners, 1, "{} owned by {} shards", market_id, owners);
+        }
+
+        // Stable across runs: pinned value for a known input
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+
+    #[test]
+    fn test_invalid_assignments_are_rejected() {
+        assert!(ShardConfig::new(0, 0).is_err());
+        assert!(ShardConfig::new(2, 2).is_err());
+        assert!(ShardConfig::default().owns("anything"));
+    }
+}
@@ -0,0 +1,238 @@
+//! Account-wide risk state shared between shards through Redis.
+//!
+//! Markets (and therefore positions and per-market budgets) never span
+//! shards, so only the daily P&L needs coordinating: the daily loss limit
+//! applies to the whole account. Each shard writes its P&L into a Redis hash
+//! field named after its index and feeds the sum of the other shards' values
+//! into its `RiskManager`. Entries are keyed by trading day, so peers' P&L
+//! rolls over at midnight in `TRADING_TIMEZONE` like the local daily reset.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use chrono::{NaiveDate, Utc};
+use redis::aio::ConnectionManager;
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::metrics::CLUSTER_DAILY_PNL;
+use crate::redis::now_ms;
+use crate::risk::RiskManager;
+use crate::timezone::TradingTimezone;
+
+use super::{LeaderElection, ShardConfig};
+
+/// Shared risk settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SharedRiskConfig {
+    /// Redis hash holding one entry per shard
+    pub key: String,
+
+    /// How often to publish our P&L and refresh peers'
+    pub interval: Duration,
+}
+
+impl Default for SharedRiskConfig {
+    fn default() -> Self {
+        Self {
+            key: "poly:risk".to_string(),
+            interval: Duration::from_secs(1),
+        }
+    }
+}
+
+impl SharedRiskConfig {
+    /// Load from `SHARED_RISK_KEY` and `SHARED_RISK_INTERVAL_MS`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            key: std::env::var("SHARED_RISK_KEY").unwrap_or(defaults.key),
+            interval: std::env::var("SHARED_RISK_INTERVAL_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|ms| *ms > 0)
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.interval),
+        }
+    }
+}
+
+/// One shard's entry in the shared hash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ShardRiskEntry {
+    instance_id: String,
+    /// Trading day the P&L belongs to
+    day: NaiveDate,
+    daily_pnl: f64,
+    updated_ms: u64,
+}
+
+/// Publishes this shard's risk usage and applies peers' to the risk manager.
+pub struct SharedRiskState {
+    shard: ShardConfig,
+    instance_id: String,
+    config: SharedRiskConfig,
+    risk_manager: Arc<RiskManager>,
+    leader: Option<Arc<LeaderElection>>,
+    /// Timezone whose midnight starts the trading day
+    timezone: TradingTimezone,
+    connection: ConnectionManager,
+}
+
+impl SharedRiskState {
+    /// Connect to Redis for sharing risk state.
+    pub async fn connect(
+        config: SharedRiskConfig,
+        redis_url: &str,
+        shard: ShardConfig,
+        instance_id: &str,
+        risk_manager: Arc<RiskManager>,
+    ) -> Result<Self> {
+        let client = redis::Client::open(redis_url).context("Failed to create Redis client")?;
+        let connection = ConnectionManager::new(client)
+            .await
+            .context("Failed to connect to Redis for shared risk state")?;
+
+        info!(
+            "[SHARD] Sharing risk state for shard {} via {} (every {}ms)",
+            shard,
+            config.key,
+            config.interval.as_millis()
+        );
+
+        Ok(Self {
+            shard,
+            instance_id: instance_id.to_string(),
+            config,
+            risk_manager,
+            leader: None,
+            timezone: TradingTimezone::default(),
+            connection,
+        })
+    }
+
+    /// Only publish while leader, so a standby never overwrites the active
+    /// instance's entry for the same shard.
+    pub fn set_leader_election(&mut self, leader: Arc<LeaderElection>) {
+        self.leader = Some(leader);
+    }
+
+    /// Set the timezone whose midnight starts the trading day (default UTC);
+    /// must match the risk manager's daily reset.
+    pub fn set_timezone(&mut self, timezone: TradingTimezone) {
+        self.timezone = timezone;
+    }
+
+    /// Sync until cancelled.
+    pub async fn run(self, cancel: CancellationToken) {
+        let mut ticker = tokio::time::interval(self.config.interval);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    if let Err(e) = self.sync().await {
+                        warn!("[SHARD] Shared risk sync failed: {}", e);
+                    }
+                }
+                _ = cancel.cancelled() => break,
+            }
+        }
+    }
+
+    /// Publish our entry and refresh the peers' combined P&L.
+    async fn sync(&self) -> Result<()> {
+        let mut conn = self.connection.clone();
+        let now = now_ms();
+        let today = self.timezone.date(Utc::now());
+
+        if self.leader.as_ref().is_none_or(|l| l.is_leader()) {
+            let entry = ShardRiskEntry {
+                instance_id: self.instance_id.clone(),
+                day: today,
+                daily_pnl: self.risk_manager.get_daily_pnl(),
+                updated_ms: now,
+            };
+            let _: () = redis::cmd("HSET")
+                .arg(&self.config.key)
+                .arg(self.shard.index.to_string())
+                .arg(serde_json::to_string(&entry)?)
+                .query_async(&mut conn)
+                .await?;
+        }
+
+        let entries: HashMap<String, String> = redis::cmd("HGETALL")
+            .arg(&self.config.key)
+            .query_async(&mut conn)
+            .await?;
+        let peer_pnl = peer_daily_pnl(&entries, self.shard, today);
+        self.risk_manager.set_peer_daily_pnl(peer_pnl);
+        CLUSTER_DAILY_PNL.set(self.risk_manager.get_daily_pnl() + peer_pnl);
+        Ok(())
+    }
+}
+
+/// Sum today's P&L over the other shards of this deployment.
+///
+/// Entries from earlier days, from shard indexes outside the current shard
+/// count, or that fail to parse are ignored.
+fn peer_daily_pnl(entries: &HashMap<String, String>, shard: ShardConfig, today: NaiveDate) -> f64 {
+    entries
+        .iter()
+        .filter_map(|(field, value)| {
+            let index: u32 = field.parse().ok()?;
+            if index == shard.index || index >= shard.count {
+                return None;
+            }
+            match serde_json::from_str::<ShardRiskEntry>(value) {
+                Ok(entry) if entry.day == today => Some(entry.daily_pnl),
+                Ok(_) => None,
+                Err(e) => {
+                    warn!(
+                        "[SHARD] Ignoring malformed entry for shard {}: {}",
+                        index, e
+                    );
+                    None
+                }
+            }
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, d).unwrap()
+    }
+
+    fn entry(day: NaiveDate, daily_pnl: f64) -> String {
+        serde_json::to_string(&ShardRiskEntry {
+            instance_id: "x".into(),
+            day,
+            daily_pnl,
+            updated_ms: 0,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_peer_pnl_sums_other_shards_for_today() {
+        let shard = ShardConfig::new(4, 0).unwrap();
+        let entries: HashMap<String, String> = [
+            ("0".to_string(), entry(day(10), -100.0)), // ourselves
+            ("1".to_string(), entry(day(10), -25.0)),
+            ("2".to_string(), entry(day(10), 5.0)),
+            ("3".to_string(), "garbage".to_string()),
+            ("4".to_string(), entry(day(10), -50.0)), // outside shard count
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(peer_daily_pnl(&entries, shard, day(10)), -20.0);
+
+        // Yesterday's losses do not count toward today's limit
+        assert_eq!(peer_daily_pnl(&entries, shard, day(11)), 0.0);
+    }
+}
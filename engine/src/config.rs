@@ -1,9 +1,65 @@
 //! Configuration management for the trading engine.
 
-use anyhow::{bail, Result};
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashMap;
 use std::env;
+use std::time::Duration;
 use tracing::warn;
 
+use crate::market::ImpactModel;
+use crate::timezone::TradingTimezone;
+
+/// Why a config value was rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueCode {
+    /// Outside the allowed range (or min above max)
+    OutOfRange,
+    /// Contains characters or a shape that isn't allowed
+    InvalidFormat,
+    /// Set but empty
+    Empty,
+    /// Must be set in this mode
+    Required,
+}
+
+/// One invalid config value, keyed by its env var
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConfigIssue {
+    pub field: String,
+    pub code: IssueCode,
+    pub message: String,
+}
+
+impl ConfigIssue {
+    fn new(field: impl Into<String>, code: IssueCode, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// Config validation failure, listing every invalid value
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConfigError {
+    pub issues: Vec<ConfigIssue>,
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Configuration validation failed:")?;
+        for issue in &self.issues {
+            write!(f, "\n  - {}", issue.message)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
 /// Main configuration struct
 #[derive(Clone, Debug)]
 pub struct Config {
@@ -23,6 +79,10 @@ pub struct Config {
     /// Dry run mode (no real orders)
     pub dry_run: bool,
 
+    /// Observe mode: strategies evaluate and signals are recorded, but
+    /// nothing is executed, not even paper fills
+    pub observe: bool,
+
     /// Risk configuration
     pub risk: RiskConfig,
 
@@ -34,6 +94,31 @@ pub struct Config {
 
     /// SumTo100 strategy config
     pub sum_to_100: SumTo100Config,
+
+    /// Named SumTo100 instances (`sumto100@<name>` in STRATEGIES), keyed by
+    /// lowercase name
+    pub sum_to_100_instances: HashMap<String, SumTo100Config>,
+
+    /// Sanity limits on outgoing order prices
+    pub order_guard: OrderGuardConfig,
+
+    /// Client-side rate limit and priority queue for CLOB requests
+    pub order_queue: OrderQueueConfig,
+
+    /// Fee and slippage assumptions shared by strategies, risk, and persistence
+    pub cost: CostConfig,
+
+    /// Strategies to construct from the registry (None = all registered)
+    pub strategies: Option<Vec<String>>,
+
+    /// Identifies this engine instance in published messages
+    pub instance_id: String,
+
+    /// Prefix applied to every Redis channel and key
+    pub redis_prefix: String,
+
+    /// IANA timezone whose midnight starts the trading day (see `timezone`)
+    pub trading_timezone: String,
 }
 
 #[derive(Clone, Debug)]
@@ -46,6 +131,32 @@ pub struct RiskConfig {
 
     /// Maximum daily loss before stopping (USD)
     pub max_daily_loss: f64,
+
+    /// Default per-market daily trading budget
+    pub market_budget: MarketBudget,
+
+    /// Per-category budget overrides, keyed by lowercase category name
+    pub category_budgets: HashMap<String, MarketBudget>,
+
+    /// Maximum notional across all legs of a basket (USD, 0 = only
+    /// `max_notional` applies)
+    pub max_basket_notional: f64,
+
+    /// Require every leg of a basket to trade one market or one correlation
+    /// group
+    pub basket_same_group: bool,
+}
+
+/// Per-market daily trade-count and turnover caps.
+///
+/// A value of zero disables the corresponding cap.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MarketBudget {
+    /// Maximum number of trades per market per day
+    pub max_daily_trades: u64,
+
+    /// Maximum traded notional per market per day (USD)
+    pub max_daily_turnover: f64,
 }
 
 #[allow(dead_code)]
@@ -71,6 +182,37 @@ pub struct SniperConfig {
 
     /// Enabled leagues
     pub leagues: Vec<String>,
+
+    /// Per-second rate at which competitors take stale quotes (paper fill model)
+    pub paper_competition_factor: f64,
+
+    /// Pre-sign orders for both outcomes of in-progress games and fire the
+    /// winner's the instant ESPN reports final
+    pub presign: bool,
+
+    /// Re-sign pre-signed orders older than this (exchanges reject stale timestamps)
+    pub presign_max_age_ms: u64,
+
+    /// Buy the winner at its stale ask the moment ESPN reports a game final,
+    /// through the engine's fast path instead of the next evaluation tick
+    pub fast_path: bool,
+
+    /// Buy in the closing minutes when the price lags a live win-probability model
+    pub preposition: bool,
+
+    /// Minimum model probability minus ask to pre-position
+    pub preposition_min_edge: f64,
+
+    /// Only pre-position with at most this many seconds of regulation left
+    pub preposition_window_secs: f64,
+
+    /// Pre-position size as a fraction of `order_size` (model trades carry
+    /// more risk than trades on final results)
+    pub preposition_size_fraction: f64,
+
+    /// Minimum team-name match confidence (0-1) for pairing a market with a
+    /// game; 0.5 also accepts one-letter typos
+    pub match_min_confidence: f64,
 }
 
 #[derive(Clone, Debug)]
@@ -86,6 +228,15 @@ pub struct ClipperConfig {
 
     /// Maximum notional per arb trade
     pub max_notional: f64,
+
+    /// Whether the spread-capture variant (resting bid, then cross) is enabled
+    pub spread_enabled: bool,
+
+    /// How far above the best bid the spread-capture bid rests
+    pub spread_improve: f64,
+
+    /// Cancel an unfilled spread-capture bid after this long (milliseconds)
+    pub spread_rest_timeout_ms: u64,
 }
 
 #[derive(Clone, Debug)]
@@ -105,14 +256,108 @@ pub struct SumTo100Config {
     /// Minimum liquidity required at VWAP
     pub min_liquidity: f64,
 
-    /// Total fee rate (both sides)
-    pub fee_rate: f64,
-
     /// Paper trading mode (simulate fills instead of real orders)
     pub paper_trading: bool,
 
     /// Maximum age of order book data in milliseconds before rejecting
     pub max_book_age_ms: u64,
+
+    /// Maximum fraction of each displayed depth level we expect to take
+    pub max_participation: f64,
+
+    /// Fraction of displayed depth assumed to fade before our order arrives
+    pub depth_haircut: f64,
+
+    /// Edges this far below `min_edge` are reported as near misses (0 = off)
+    pub near_miss_tolerance: f64,
+
+    /// Only trade markets in these categories, lowercase (empty = all)
+    pub categories: Vec<String>,
+
+    /// Also sell held YES + NO pairs when the bids sum above 1.00
+    pub sell_enabled: bool,
+}
+
+impl SumTo100Config {
+    /// Whether a market in `category` is traded by this config
+    pub fn covers_category(&self, category: Option<&str>) -> bool {
+        self.categories.is_empty()
+            || category.is_some_and(|c| self.categories.iter().any(|x| x.eq_ignore_ascii_case(c)))
+    }
+
+    /// Market impact model used for VWAP sizing and paper fills
+    pub fn impact_model(&self) -> ImpactModel {
+        ImpactModel {
+            max_participation: self.max_participation,
+            depth_haircut: self.depth_haircut,
+        }
+    }
+}
+
+/// Sanity limits checked on every outgoing order, to catch a bug upstream
+/// emitting a price far from the market.
+#[derive(Clone, Debug)]
+pub struct OrderGuardConfig {
+    /// Reject orders more than this fraction away from the current mid
+    /// (0 = off)
+    pub max_mid_deviation: f64,
+
+    /// Absolute price band for every token
+    pub price_band: PriceBand,
+
+    /// Per-token band overrides, keyed by token ID
+    pub token_bands: HashMap<String, PriceBand>,
+}
+
+impl OrderGuardConfig {
+    /// Band that applies to a token
+    pub fn band_for(&self, token_id: &str) -> PriceBand {
+        self.token_bands
+            .get(token_id)
+            .copied()
+            .unwrap_or(self.price_band)
+    }
+}
+
+/// Rate limit for live CLOB requests. When it is saturated (or the exchange
+/// answers 429), requests wait and are released by priority class.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OrderQueueConfig {
+    /// Sustained requests per second (0 = unlimited)
+    pub rate_per_sec: f64,
+
+    /// Requests that may be sent back to back before the rate applies
+    pub burst: u32,
+
+    /// A waiting request moves up one priority class per interval, so
+    /// passive quotes are delayed but never starved
+    pub aging: Duration,
+}
+
+/// Trading cost assumptions, as fractions of order notional.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CostConfig {
+    /// Fee on orders that take liquidity
+    pub taker_fee_rate: f64,
+
+    /// Fee on resting orders that get filled
+    pub maker_fee_rate: f64,
+
+    /// Expected slippage on orders that take liquidity
+    pub slippage_rate: f64,
+}
+
+/// Inclusive range of acceptable order prices.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PriceBand {
+    pub min: f64,
+    pub max: f64,
+}
+
+impl PriceBand {
+    pub fn contains(&self, price: f64) -> bool {
+        price >= self.min && price <= self.max
+    }
 }
 
 /// Helper to parse env var with warning on missing/invalid
@@ -137,6 +382,214 @@ fn parse_env_or_default<T: std::str::FromStr>(var_name: &str, default: T) -> T {
     }
 }
 
+/// Instance ID used when `INSTANCE_ID` is unset
+pub const DEFAULT_INSTANCE_ID: &str = "default";
+
+/// WebSocket URL used when `POLY_WS_URL` is unset
+pub const DEFAULT_WS_URL: &str = "wss://ws-subscriptions-clob.polymarket.com/ws/market";
+
+/// CLOB API URL used when `POLY_CLOB_URL` is unset
+pub const DEFAULT_CLOB_URL: &str = "https://clob.polymarket.com";
+
+/// Trading timezone used when `TRADING_TIMEZONE` is unset
+pub const DEFAULT_TRADING_TIMEZONE: &str = "UTC";
+
+/// Redis prefix when `REDIS_CHANNEL_PREFIX` is unset: plain `poly` for the
+/// default instance (what the dashboard subscribes to), `poly:<id>` otherwise
+/// so two instances never share channels.
+pub fn default_redis_prefix(instance_id: &str) -> String {
+    if instance_id == DEFAULT_INSTANCE_ID {
+        "poly".to_string()
+    } else {
+        format!("poly:{}", instance_id)
+    }
+}
+
+/// Whether `value` is a non-empty identifier of letters, digits, `-`, `_`, `.`
+fn is_valid_name(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// Parse a comma-separated strategy list (`sumto100,clipper`) into
+/// lowercase registry names.
+fn parse_strategy_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|s| s.trim().to_lowercase())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Instance names selected for a strategy type, e.g. `sports` from
+/// `sumto100@sports`.
+fn strategy_instances(strategies: Option<&[String]>, kind: &str) -> Vec<String> {
+    strategies
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|entry| entry.split_once('@'))
+        .filter(|(k, _)| *k == kind)
+        .map(|(_, name)| name.to_string())
+        .collect()
+}
+
+/// Env var for a SumTo100 setting: `SUMTO100_<KEY>`, or
+/// `SUMTO100_<INSTANCE>__<KEY>` for a named instance.
+fn sum_to_100_var(instance: Option<&str>, key: &str) -> String {
+    match instance {
+        Some(name) => format!("SUMTO100_{}__{}", name.to_uppercase(), key),
+        None => format!("SUMTO100_{}", key),
+    }
+}
+
+/// Load a SumTo100 config. A named instance reads its own
+/// `SUMTO100_<INSTANCE>__*` vars and falls back to the shared `SUMTO100_*`.
+fn sum_to_100_from_env(instance: Option<&str>) -> SumTo100Config {
+    let var = |key: &str| {
+        let own = sum_to_100_var(instance, key);
+        if env::var(&own).is_ok() {
+            own
+        } else {
+            sum_to_100_var(None, key)
+        }
+    };
+    SumTo100Config {
+        enabled: parse_bool_env_or_default(&var("ENABLED"), true),
+        min_edge: parse_env_or_default(&var("MIN_EDGE"), 0.003),
+        max_position: parse_env_or_default(&var("MAX_POSITION"), 100.0),
+        max_notional: parse_env_or_default(&var("MAX_NOTIONAL"), 100.0),
+        min_liquidity: parse_env_or_default(&var("MIN_LIQUIDITY"), 50.0),
+        paper_trading: parse_bool_env_or_default(&var("PAPER_TRADING"), true),
+        max_book_age_ms: parse_env_or_default(&var("MAX_BOOK_AGE_MS"), 500),
+        max_participation: parse_env_or_default(&var("MAX_PARTICIPATION"), 0.5),
+        depth_haircut: parse_env_or_default(&var("DEPTH_HAIRCUT"), 0.2),
+        near_miss_tolerance: parse_env_or_default(&var("NEAR_MISS_TOLERANCE"), 0.005),
+        categories: env::var(var("CATEGORIES"))
+            .map(|v| parse_strategy_list(&v))
+            .unwrap_or_default(),
+        sell_enabled: parse_bool_env_or_default(&var("SELL_ENABLED"), false),
+    }
+}
+
+/// Check a SumTo100 config; `prefix` names its env vars in the errors.
+fn validate_sum_to_100(prefix: &str, config: &SumTo100Config, errors: &mut Vec<ConfigIssue>) {
+    if config.min_edge < 0.0 {
+        errors.push(ConfigIssue::new(
+            format!("{}MIN_EDGE", prefix),
+            IssueCode::OutOfRange,
+            format!("{}MIN_EDGE must be >= 0, got {}", prefix, config.min_edge),
+        ));
+    }
+    if config.min_liquidity <= 0.0 {
+        errors.push(ConfigIssue::new(
+            format!("{}MIN_LIQUIDITY", prefix),
+            IssueCode::OutOfRange,
+            format!(
+                "{}MIN_LIQUIDITY must be > 0, got {}",
+                prefix, config.min_liquidity
+            ),
+        ));
+    }
+    if config.max_participation <= 0.0 || config.max_participation > 1.0 {
+        errors.push(ConfigIssue::new(
+            format!("{}MAX_PARTICIPATION", prefix),
+            IssueCode::OutOfRange,
+            format!(
+                "{}MAX_PARTICIPATION must be > 0.0 and <= 1.0, got {}",
+                prefix, config.max_participation
+            ),
+        ));
+    }
+    if config.depth_haircut < 0.0 || config.depth_haircut >= 1.0 {
+        errors.push(ConfigIssue::new(
+            format!("{}DEPTH_HAIRCUT", prefix),
+            IssueCode::OutOfRange,
+            format!(
+                "{}DEPTH_HAIRCUT must be >= 0.0 and < 1.0, got {}",
+                prefix, config.depth_haircut
+            ),
+        ));
+    }
+    if config.near_miss_tolerance < 0.0 {
+        errors.push(ConfigIssue::new(
+            format!("{}NEAR_MISS_TOLERANCE", prefix),
+            IssueCode::OutOfRange,
+            format!(
+                "{}NEAR_MISS_TOLERANCE must be >= 0, got {}",
+                prefix, config.near_miss_tolerance
+            ),
+        ));
+    }
+}
+
+/// Parse per-category budget overrides.
+///
+/// Format: `category:max_trades:max_turnover` entries separated by commas,
+/// e.g. `sports:20:1000,politics:5:250`. Invalid entries are skipped with a warning.
+fn parse_category_budgets(value: &str) -> HashMap<String, MarketBudget> {
+    let mut budgets = HashMap::new();
+
+    for entry in value.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let parts: Vec<&str> = entry.split(':').map(str::trim).collect();
+        let parsed = match parts.as_slice() {
+            [category, trades, turnover] if !category.is_empty() => {
+                match (trades.parse::<u64>(), turnover.parse::<f64>()) {
+                    (Ok(max_daily_trades), Ok(max_daily_turnover)) => Some((
+                        category.to_lowercase(),
+                        MarketBudget {
+                            max_daily_trades,
+                            max_daily_turnover,
+                        },
+                    )),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+
+        match parsed {
+            Some((category, budget)) => {
+                budgets.insert(category, budget);
+            }
+            None => warn!("RISK_CATEGORY_BUDGETS entry '{}' is invalid, skipping", entry),
+        }
+    }
+
+    budgets
+}
+
+/// Parse per-token price bands.
+///
+/// Format: `token_id:min:max` entries separated by commas. Invalid entries
+/// are skipped with a warning.
+fn parse_price_bands(value: &str) -> HashMap<String, PriceBand> {
+    let mut bands = HashMap::new();
+
+    for entry in value.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let parts: Vec<&str> = entry.split(':').map(str::trim).collect();
+        let parsed = match parts.as_slice() {
+            [token_id, min, max] if !token_id.is_empty() => {
+                match (min.parse::<f64>(), max.parse::<f64>()) {
+                    (Ok(min), Ok(max)) => Some((token_id.to_string(), PriceBand { min, max })),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+
+        match parsed {
+            Some((token_id, band)) => {
+                bands.insert(token_id, band);
+            }
+            None => warn!("ORDER_PRICE_BANDS entry '{}' is invalid, skipping", entry),
+        }
+    }
+
+    bands
+}
+
 /// Helper for boolean env vars with warning
 fn parse_bool_env_or_default(var_name: &str, default: bool) -> bool {
     match env::var(var_name) {
@@ -151,15 +604,20 @@ fn parse_bool_env_or_default(var_name: &str, default: bool) -> bool {
 impl Config {
     /// Load configuration from environment variables
     pub fn from_env() -> Result<Self> {
+        let instance_id =
+            env::var("INSTANCE_ID").unwrap_or_else(|_| DEFAULT_INSTANCE_ID.to_string());
+
+        let strategies = env::var("STRATEGIES").ok().map(|v| parse_strategy_list(&v));
+
         let config = Config {
             ws_url: env::var("POLY_WS_URL").unwrap_or_else(|_| {
                 warn!("POLY_WS_URL not set, using default WebSocket URL");
-                "wss://ws-subscriptions-clob.polymarket.com/ws/market".into()
+                DEFAULT_WS_URL.into()
             }),
 
             clob_url: env::var("POLY_CLOB_URL").unwrap_or_else(|_| {
                 warn!("POLY_CLOB_URL not set, using default CLOB URL");
-                "https://clob.polymarket.com".into()
+                DEFAULT_CLOB_URL.into()
             }),
 
             // In DRY_RUN mode, keys are optional (use placeholders)
@@ -180,11 +638,24 @@ impl Config {
             }),
 
             dry_run: parse_bool_env_or_default("DRY_RUN", true),
+            observe: parse_bool_env_or_default("OBSERVE", false),
 
             risk: RiskConfig {
                 max_position: parse_env_or_default("RISK_MAX_POSITION", 100.0),
                 max_notional: parse_env_or_default("RISK_MAX_NOTIONAL", 500.0),
                 max_daily_loss: parse_env_or_default("RISK_MAX_DAILY_LOSS", 200.0),
+                market_budget: MarketBudget {
+                    max_daily_trades: parse_env_or_default("RISK_MAX_DAILY_TRADES_PER_MARKET", 0),
+                    max_daily_turnover: parse_env_or_default(
+                        "RISK_MAX_DAILY_TURNOVER_PER_MARKET",
+                        0.0,
+                    ),
+                },
+                category_budgets: env::var("RISK_CATEGORY_BUDGETS")
+                    .map(|v| parse_category_budgets(&v))
+                    .unwrap_or_default(),
+                max_basket_notional: parse_env_or_default("RISK_MAX_BASKET_NOTIONAL", 0.0),
+                basket_same_group: parse_bool_env_or_default("RISK_BASKET_SAME_GROUP", false),
             },
 
             sniper: SniperConfig {
@@ -202,6 +673,21 @@ impl Config {
                     .split(',')
                     .map(|s| s.trim().to_uppercase())
                     .collect(),
+                paper_competition_factor: parse_env_or_default("SNIPER_PAPER_COMPETITION", 0.5),
+                presign: parse_bool_env_or_default("SNIPER_PRESIGN", false),
+                presign_max_age_ms: parse_env_or_default("SNIPER_PRESIGN_MAX_AGE_MS", 30_000),
+                fast_path: parse_bool_env_or_default("SNIPER_FAST_PATH", false),
+                preposition: parse_bool_env_or_default("SNIPER_PREPOSITION", false),
+                preposition_min_edge: parse_env_or_default("SNIPER_PREPOSITION_MIN_EDGE", 0.10),
+                preposition_window_secs: parse_env_or_default(
+                    "SNIPER_PREPOSITION_WINDOW_SECS",
+                    300.0,
+                ),
+                preposition_size_fraction: parse_env_or_default(
+                    "SNIPER_PREPOSITION_SIZE_FRACTION",
+                    0.25,
+                ),
+                match_min_confidence: parse_env_or_default("SNIPER_MATCH_MIN_CONFIDENCE", 0.7),
             },
 
             clipper: ClipperConfig {
@@ -209,18 +695,59 @@ impl Config {
                 min_profit: parse_env_or_default("CLIPPER_MIN_PROFIT", 0.01),
                 max_position: parse_env_or_default("CLIPPER_MAX_POSITION", 100.0),
                 max_notional: parse_env_or_default("CLIPPER_MAX_NOTIONAL", 100.0),
+                spread_enabled: parse_bool_env_or_default("CLIPPER_SPREAD_ENABLED", false),
+                spread_improve: parse_env_or_default("CLIPPER_SPREAD_IMPROVE", 0.01),
+                spread_rest_timeout_ms: parse_env_or_default(
+                    "CLIPPER_SPREAD_REST_TIMEOUT_MS",
+                    30_000,
+                ),
+            },
+
+            sum_to_100: sum_to_100_from_env(None),
+
+            sum_to_100_instances: strategy_instances(strategies.as_deref(), "sumto100")
+                .into_iter()
+                .map(|name| {
+                    let config = sum_to_100_from_env(Some(&name));
+                    (name, config)
+                })
+                .collect(),
+
+            order_guard: OrderGuardConfig {
+                max_mid_deviation: parse_env_or_default("ORDER_MAX_MID_DEVIATION", 0.5),
+                price_band: PriceBand {
+                    min: parse_env_or_default("ORDER_MIN_PRICE", 0.01),
+                    max: parse_env_or_default("ORDER_MAX_PRICE", 0.99),
+                },
+                token_bands: env::var("ORDER_PRICE_BANDS")
+                    .map(|v| parse_price_bands(&v))
+                    .unwrap_or_default(),
             },
 
-            sum_to_100: SumTo100Config {
-                enabled: parse_bool_env_or_default("SUMTO100_ENABLED", true),
-                min_edge: parse_env_or_default("SUMTO100_MIN_EDGE", 0.003),
-                max_position: parse_env_or_default("SUMTO100_MAX_POSITION", 100.0),
-                max_notional: parse_env_or_default("SUMTO100_MAX_NOTIONAL", 100.0),
-                min_liquidity: parse_env_or_default("SUMTO100_MIN_LIQUIDITY", 50.0),
-                fee_rate: parse_env_or_default("SUMTO100_FEE_RATE", 0.01),
-                paper_trading: parse_bool_env_or_default("SUMTO100_PAPER_TRADING", true),
-                max_book_age_ms: parse_env_or_default("SUMTO100_MAX_BOOK_AGE_MS", 500),
+            order_queue: OrderQueueConfig {
+                rate_per_sec: parse_env_or_default("ORDER_RATE_LIMIT", 0.0),
+                burst: parse_env_or_default("ORDER_RATE_BURST", 10),
+                aging: Duration::from_millis(parse_env_or_default("ORDER_QUEUE_AGING_MS", 250)),
             },
+
+            cost: CostConfig {
+                // SUMTO100_FEE_RATE predates the shared model; still honoured
+                taker_fee_rate: parse_env_or_default(
+                    "COST_TAKER_FEE_RATE",
+                    parse_env_or_default("SUMTO100_FEE_RATE", 0.01),
+                ),
+                maker_fee_rate: parse_env_or_default("COST_MAKER_FEE_RATE", 0.0),
+                slippage_rate: parse_env_or_default("COST_SLIPPAGE_RATE", 0.0),
+            },
+
+            strategies,
+
+            instance_id: instance_id.clone(),
+            redis_prefix: env::var("REDIS_CHANNEL_PREFIX")
+                .unwrap_or_else(|_| default_redis_prefix(&instance_id)),
+
+            trading_timezone: env::var("TRADING_TIMEZONE")
+                .unwrap_or_else(|_| DEFAULT_TRADING_TIMEZONE.into()),
         };
 
         // Validate configuration before returning
@@ -229,109 +756,355 @@ impl Config {
         Ok(config)
     }
 
+    /// Trading timezone (UTC if `trading_timezone` is invalid, which
+    /// `validate` rejects)
+    pub fn timezone(&self) -> TradingTimezone {
+        TradingTimezone::parse(&self.trading_timezone).unwrap_or_default()
+    }
+
     /// Validate configuration values
     ///
-    /// Returns an error if any configuration value is invalid.
+    /// Returns every invalid value if any configuration value is invalid.
     /// This prevents silent failures from misconfigured trading parameters.
-    pub fn validate(&self) -> Result<()> {
-        let mut errors: Vec<String> = Vec::new();
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let mut errors: Vec<ConfigIssue> = Vec::new();
 
         // Risk configuration validation
         if self.risk.max_position <= 0.0 {
-            errors.push(format!(
-                "RISK_MAX_POSITION must be > 0, got {}",
-                self.risk.max_position
+            errors.push(ConfigIssue::new(
+                "RISK_MAX_POSITION",
+                IssueCode::OutOfRange,
+                format!(
+                    "RISK_MAX_POSITION must be > 0, got {}",
+                    self.risk.max_position
+                ),
             ));
         }
         if self.risk.max_notional <= 0.0 {
-            errors.push(format!(
-                "RISK_MAX_NOTIONAL must be > 0, got {}",
-                self.risk.max_notional
+            errors.push(ConfigIssue::new(
+                "RISK_MAX_NOTIONAL",
+                IssueCode::OutOfRange,
+                format!(
+                    "RISK_MAX_NOTIONAL must be > 0, got {}",
+                    self.risk.max_notional
+                ),
             ));
         }
         if self.risk.max_daily_loss <= 0.0 {
-            errors.push(format!(
-                "RISK_MAX_DAILY_LOSS must be > 0, got {}",
-                self.risk.max_daily_loss
+            errors.push(ConfigIssue::new(
+                "RISK_MAX_DAILY_LOSS",
+                IssueCode::OutOfRange,
+                format!(
+                    "RISK_MAX_DAILY_LOSS must be > 0, got {}",
+                    self.risk.max_daily_loss
+                ),
+            ));
+        }
+        if self.risk.market_budget.max_daily_turnover < 0.0 {
+            errors.push(ConfigIssue::new(
+                "RISK_MAX_DAILY_TURNOVER_PER_MARKET",
+                IssueCode::OutOfRange,
+                format!(
+                    "RISK_MAX_DAILY_TURNOVER_PER_MARKET must be >= 0, got {}",
+                    self.risk.market_budget.max_daily_turnover
+                ),
             ));
         }
+        for (category, budget) in &self.risk.category_budgets {
+            if budget.max_daily_turnover < 0.0 {
+                errors.push(ConfigIssue::new(
+                    "RISK_CATEGORY_BUDGETS",
+                    IssueCode::OutOfRange,
+                    format!(
+                        "RISK_CATEGORY_BUDGETS turnover for '{}' must be >= 0, got {}",
+                        category, budget.max_daily_turnover
+                    ),
+                ));
+            }
+        }
 
         // Sniper configuration validation
         if self.sniper.min_price < 0.0 || self.sniper.min_price > 1.0 {
-            errors.push(format!(
-                "SNIPER_MIN_PRICE must be between 0.0 and 1.0, got {}",
-                self.sniper.min_price
+            errors.push(ConfigIssue::new(
+                "SNIPER_MIN_PRICE",
+                IssueCode::OutOfRange,
+                format!(
+                    "SNIPER_MIN_PRICE must be between 0.0 and 1.0, got {}",
+                    self.sniper.min_price
+                ),
             ));
         }
         if self.sniper.max_price < self.sniper.min_price || self.sniper.max_price > 1.0 {
-            errors.push(format!(
-                "SNIPER_MAX_PRICE must be between SNIPER_MIN_PRICE ({}) and 1.0, got {}",
-                self.sniper.min_price, self.sniper.max_price
+            errors.push(ConfigIssue::new(
+                "SNIPER_MAX_PRICE",
+                IssueCode::OutOfRange,
+                format!(
+                    "SNIPER_MAX_PRICE must be between SNIPER_MIN_PRICE ({}) and 1.0, got {}",
+                    self.sniper.min_price, self.sniper.max_price
+                ),
             ));
         }
         if self.sniper.min_profit < 0.0 {
-            errors.push(format!(
-                "SNIPER_MIN_PROFIT must be >= 0, got {}",
-                self.sniper.min_profit
+            errors.push(ConfigIssue::new(
+                "SNIPER_MIN_PROFIT",
+                IssueCode::OutOfRange,
+                format!(
+                    "SNIPER_MIN_PROFIT must be >= 0, got {}",
+                    self.sniper.min_profit
+                ),
+            ));
+        }
+        if self.sniper.paper_competition_factor < 0.0 {
+            errors.push(ConfigIssue::new(
+                "SNIPER_PAPER_COMPETITION",
+                IssueCode::OutOfRange,
+                format!(
+                    "SNIPER_PAPER_COMPETITION must be >= 0, got {}",
+                    self.sniper.paper_competition_factor
+                ),
+            ));
+        }
+        if self.sniper.presign && self.sniper.presign_max_age_ms == 0 {
+            errors.push(ConfigIssue::new(
+                "SNIPER_PRESIGN_MAX_AGE_MS",
+                IssueCode::OutOfRange,
+                "SNIPER_PRESIGN_MAX_AGE_MS must be > 0".to_string(),
+            ));
+        }
+        if self.sniper.preposition_min_edge <= 0.0 || self.sniper.preposition_min_edge >= 1.0 {
+            errors.push(ConfigIssue::new(
+                "SNIPER_PREPOSITION_MIN_EDGE",
+                IssueCode::OutOfRange,
+                format!(
+                    "SNIPER_PREPOSITION_MIN_EDGE must be between 0 and 1 (exclusive), got {}",
+                    self.sniper.preposition_min_edge
+                ),
+            ));
+        }
+        if self.sniper.preposition_window_secs <= 0.0 {
+            errors.push(ConfigIssue::new(
+                "SNIPER_PREPOSITION_WINDOW_SECS",
+                IssueCode::OutOfRange,
+                format!(
+                    "SNIPER_PREPOSITION_WINDOW_SECS must be > 0, got {}",
+                    self.sniper.preposition_window_secs
+                ),
+            ));
+        }
+        if self.sniper.preposition_size_fraction <= 0.0
+            || self.sniper.preposition_size_fraction > 1.0
+        {
+            errors.push(ConfigIssue::new(
+                "SNIPER_PREPOSITION_SIZE_FRACTION",
+                IssueCode::OutOfRange,
+                format!(
+                    "SNIPER_PREPOSITION_SIZE_FRACTION must be in (0, 1], got {}",
+                    self.sniper.preposition_size_fraction
+                ),
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.sniper.match_min_confidence) {
+            errors.push(ConfigIssue::new(
+                "SNIPER_MATCH_MIN_CONFIDENCE",
+                IssueCode::OutOfRange,
+                format!(
+                    "SNIPER_MATCH_MIN_CONFIDENCE must be between 0 and 1, got {}",
+                    self.sniper.match_min_confidence
+                ),
             ));
         }
 
         // Clipper configuration validation
         if self.clipper.min_profit < 0.0 {
-            errors.push(format!(
-                "CLIPPER_MIN_PROFIT must be >= 0, got {}",
-                self.clipper.min_profit
+            errors.push(ConfigIssue::new(
+                "CLIPPER_MIN_PROFIT",
+                IssueCode::OutOfRange,
+                format!(
+                    "CLIPPER_MIN_PROFIT must be >= 0, got {}",
+                    self.clipper.min_profit
+                ),
             ));
         }
+        if self.clipper.spread_improve <= 0.0 || self.clipper.spread_improve >= 1.0 {
+            errors.push(ConfigIssue::new(
+                "CLIPPER_SPREAD_IMPROVE",
+                IssueCode::OutOfRange,
+                format!(
+                    "CLIPPER_SPREAD_IMPROVE must be > 0.0 and < 1.0, got {}",
+                    self.clipper.spread_improve
+                ),
+            ));
+        }
+        if self.clipper.spread_rest_timeout_ms == 0 {
+            errors.push(ConfigIssue::new(
+                "CLIPPER_SPREAD_REST_TIMEOUT_MS",
+                IssueCode::OutOfRange,
+                "CLIPPER_SPREAD_REST_TIMEOUT_MS must be > 0".to_string(),
+            ));
+        }
+
+        // SumTo100 configuration validation (base and named instances)
+        validate_sum_to_100(&sum_to_100_var(None, ""), &self.sum_to_100, &mut errors);
+        for (name, instance) in &self.sum_to_100_instances {
+            validate_sum_to_100(&sum_to_100_var(Some(name), ""), instance, &mut errors);
+        }
+
+        // Instance names become env var prefixes and metric labels
+        for entry in self.strategies.iter().flatten() {
+            if let Some((_, name)) = entry.split_once('@') {
+                if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                    errors.push(ConfigIssue::new(
+                        "STRATEGIES",
+                        IssueCode::InvalidFormat,
+                        format!(
+                            "STRATEGIES instance '{}' must be letters, digits, or '_'",
+                            entry
+                        ),
+                    ));
+                }
+            }
+        }
+
+        // Order guard validation
+        if self.order_guard.max_mid_deviation < 0.0 {
+            errors.push(ConfigIssue::new(
+                "ORDER_MAX_MID_DEVIATION",
+                IssueCode::OutOfRange,
+                format!(
+                    "ORDER_MAX_MID_DEVIATION must be >= 0, got {}",
+                    self.order_guard.max_mid_deviation
+                ),
+            ));
+        }
+        let band = self.order_guard.price_band;
+        if band.min < 0.0 || band.max > 1.0 || band.min > band.max {
+            errors.push(ConfigIssue::new("ORDER_MIN_PRICE", IssueCode::OutOfRange, format!(
+                "ORDER_MIN_PRICE and ORDER_MAX_PRICE must satisfy 0 <= min <= max <= 1, got {} and {}",
+                band.min, band.max
+            )));
+        }
+        for (token_id, band) in &self.order_guard.token_bands {
+            if band.min < 0.0 || band.max > 1.0 || band.min > band.max {
+                errors.push(ConfigIssue::new(
+                    "ORDER_PRICE_BANDS",
+                    IssueCode::OutOfRange,
+                    format!(
+                    "ORDER_PRICE_BANDS band for '{}' must satisfy 0 <= min <= max <= 1, got {}:{}",
+                    token_id, band.min, band.max
+                ),
+                ));
+            }
+        }
+
+        // Order queue validation
+        let rate = self.order_queue.rate_per_sec;
+        if !rate.is_finite() || rate < 0.0 {
+            errors.push(ConfigIssue::new(
+                "ORDER_RATE_LIMIT",
+                IssueCode::OutOfRange,
+                format!("ORDER_RATE_LIMIT must be >= 0, got {}", rate),
+            ));
+        }
+        if self.order_queue.burst == 0 {
+            errors.push(ConfigIssue::new(
+                "ORDER_RATE_BURST",
+                IssueCode::OutOfRange,
+                "ORDER_RATE_BURST must be at least 1".to_string(),
+            ));
+        }
+        if self.order_queue.aging.is_zero() {
+            errors.push(ConfigIssue::new(
+                "ORDER_QUEUE_AGING_MS",
+                IssueCode::OutOfRange,
+                "ORDER_QUEUE_AGING_MS must be > 0".to_string(),
+            ));
+        }
+
+        // Cost model validation
+        for (name, rate) in [
+            ("COST_TAKER_FEE_RATE", self.cost.taker_fee_rate),
+            ("COST_MAKER_FEE_RATE", self.cost.maker_fee_rate),
+            ("COST_SLIPPAGE_RATE", self.cost.slippage_rate),
+        ] {
+            if !(0.0..=1.0).contains(&rate) {
+                errors.push(ConfigIssue::new(
+                    name,
+                    IssueCode::OutOfRange,
+                    format!("{} must be between 0.0 and 1.0, got {}", name, rate),
+                ));
+            }
+        }
 
-        // SumTo100 configuration validation
-        if self.sum_to_100.min_edge < 0.0 {
-            errors.push(format!(
-                "SUMTO100_MIN_EDGE must be >= 0, got {}",
-                self.sum_to_100.min_edge
+        if matches!(&self.strategies, Some(list) if list.is_empty()) {
+            errors.push(ConfigIssue::new(
+                "STRATEGIES",
+                IssueCode::Empty,
+                "STRATEGIES must list at least one strategy when set".to_string(),
             ));
         }
-        if self.sum_to_100.fee_rate < 0.0 || self.sum_to_100.fee_rate > 1.0 {
-            errors.push(format!(
-                "SUMTO100_FEE_RATE must be between 0.0 and 1.0, got {}",
-                self.sum_to_100.fee_rate
+
+        // Instance / Redis namespace validation
+        if !is_valid_name(&self.instance_id) {
+            errors.push(ConfigIssue::new("INSTANCE_ID", IssueCode::InvalidFormat, format!(
+                "INSTANCE_ID must be non-empty and contain only letters, digits, '-', '_' or '.', got '{}'",
+                self.instance_id
+            )));
+        }
+        if self.redis_prefix.is_empty() || self.redis_prefix.chars().any(char::is_whitespace) {
+            errors.push(ConfigIssue::new(
+                "REDIS_CHANNEL_PREFIX",
+                IssueCode::InvalidFormat,
+                format!(
+                    "REDIS_CHANNEL_PREFIX must be non-empty without whitespace, got '{}'",
+                    self.redis_prefix
+                ),
             ));
         }
-        if self.sum_to_100.min_liquidity <= 0.0 {
-            errors.push(format!(
-                "SUMTO100_MIN_LIQUIDITY must be > 0, got {}",
-                self.sum_to_100.min_liquidity
+
+        if TradingTimezone::parse(&self.trading_timezone).is_none() {
+            errors.push(ConfigIssue::new(
+                "TRADING_TIMEZONE",
+                IssueCode::InvalidFormat,
+                format!(
+                    "TRADING_TIMEZONE must be an IANA timezone such as America/New_York, got '{}'",
+                    self.trading_timezone
+                ),
             ));
         }
 
-        // Check for placeholder credentials when not in dry run mode
-        if !self.dry_run {
+        // Check for placeholder credentials when orders can go out
+        if !self.dry_run && !self.observe {
             if self.private_key
                 == "0x0000000000000000000000000000000000000000000000000000000000000000"
             {
-                errors.push(
+                errors.push(ConfigIssue::new(
+                    "POLY_PRIVATE_KEY",
+                    IssueCode::Required,
                     "POLY_PRIVATE_KEY is required when DRY_RUN=false (using placeholder key)"
                         .to_string(),
-                );
+                ));
             }
             if self.api_key == "mock-api-key" {
-                errors
-                    .push("POLY_API_KEY is required when DRY_RUN=false (using mock key)".to_string());
+                errors.push(ConfigIssue::new(
+                    "POLY_API_KEY",
+                    IssueCode::Required,
+                    "POLY_API_KEY is required when DRY_RUN=false (using mock key)",
+                ));
             }
             if self.api_secret == "mock-api-secret" {
-                errors.push(
-                    "POLY_API_SECRET is required when DRY_RUN=false (using mock secret)".to_string(),
-                );
+                errors.push(ConfigIssue::new(
+                    "POLY_API_SECRET",
+                    IssueCode::Required,
+                    "POLY_API_SECRET is required when DRY_RUN=false (using mock secret)"
+                        .to_string(),
+                ));
             }
         }
 
         if errors.is_empty() {
             Ok(())
         } else {
-            bail!(
-                "Configuration validation failed:\n  - {}",
-                errors.join("\n  - ")
-            )
+            Err(ConfigError { issues: errors })
         }
     }
 }
@@ -342,6 +1115,10 @@ impl Default for RiskConfig {
             max_position: 100.0,
             max_notional: 500.0,
             max_daily_loss: 200.0,
+            market_budget: MarketBudget::default(),
+            category_budgets: HashMap::new(),
+            max_basket_notional: 0.0,
+            basket_same_group: false,
         }
     }
 }
@@ -356,6 +1133,15 @@ impl Default for SniperConfig {
             min_profit: 0.05,
             poll_interval_ms: 1000,
             leagues: vec!["NBA".into(), "NFL".into(), "MLB".into(), "NHL".into()],
+            paper_competition_factor: 0.5,
+            presign: false,
+            presign_max_age_ms: 30_000,
+            fast_path: false,
+            preposition: false,
+            preposition_min_edge: 0.10,
+            preposition_window_secs: 300.0,
+            preposition_size_fraction: 0.25,
+            match_min_confidence: 0.7,
         }
     }
 }
@@ -367,6 +1153,9 @@ impl Default for ClipperConfig {
             min_profit: 0.01,
             max_position: 100.0,
             max_notional: 100.0,
+            spread_enabled: false, // Carries leg risk; opt in
+            spread_improve: 0.01,
+            spread_rest_timeout_ms: 30_000,
         }
     }
 }
@@ -379,9 +1168,46 @@ impl Default for SumTo100Config {
             max_position: 100.0,
             max_notional: 100.0,
             min_liquidity: 50.0,
-            fee_rate: 0.01,       // 1% total fees
             paper_trading: true,  // Safe default
             max_book_age_ms: 500, // 500ms max staleness
+            max_participation: 0.5, // Take at most half of displayed depth
+            depth_haircut: 0.2,     // Assume 20% of quoted depth fades
+            near_miss_tolerance: 0.005, // Report edges within 0.5% of min_edge
+            categories: Vec::new(),
+            sell_enabled: false, // Entries only
+        }
+    }
+}
+
+impl Default for OrderGuardConfig {
+    fn default() -> Self {
+        Self {
+            max_mid_deviation: 0.5,
+            price_band: PriceBand {
+                min: 0.01,
+                max: 0.99,
+            },
+            token_bands: HashMap::new(),
+        }
+    }
+}
+
+impl Default for OrderQueueConfig {
+    fn default() -> Self {
+        Self {
+            rate_per_sec: 0.0,
+            burst: 10,
+            aging: Duration::from_millis(250),
+        }
+    }
+}
+
+impl Default for CostConfig {
+    fn default() -> Self {
+        Self {
+            taker_fee_rate: 0.01, // 1% of notional, as the strategies assumed
+            maker_fee_rate: 0.0,
+            slippage_rate: 0.0,
         }
     }
 }
@@ -399,10 +1225,19 @@ mod tests {
             api_key: "test-key".into(),
             api_secret: "test-secret".into(),
             dry_run: true,
+            observe: false,
             risk: RiskConfig::default(),
             sniper: SniperConfig::default(),
             clipper: ClipperConfig::default(),
             sum_to_100: SumTo100Config::default(),
+            sum_to_100_instances: HashMap::new(),
+            order_guard: OrderGuardConfig::default(),
+            order_queue: OrderQueueConfig::default(),
+            cost: CostConfig::default(),
+            strategies: None,
+            instance_id: DEFAULT_INSTANCE_ID.into(),
+            redis_prefix: "poly".into(),
+            trading_timezone: DEFAULT_TRADING_TIMEZONE.into(),
         }
     }
 
@@ -507,6 +1342,17 @@ mod tests {
         assert!(err_msg.contains("SNIPER_MIN_PROFIT must be >= 0"));
     }
 
+    #[test]
+    fn test_config_validation_rejects_oversized_preposition_fraction() {
+        let mut config = valid_config();
+        config.sniper.preposition_size_fraction = 1.5;
+
+        let result = config.validate();
+        assert!(result.is_err());
+        let err_msg = result.unwrap_err().to_string();
+        assert!(err_msg.contains("SNIPER_PREPOSITION_SIZE_FRACTION must be in (0, 1]"));
+    }
+
     #[test]
     fn test_config_validation_rejects_negative_clipper_profit() {
         let mut config = valid_config();
@@ -529,25 +1375,64 @@ mod tests {
         assert!(err_msg.contains("SUMTO100_MIN_EDGE must be >= 0"));
     }
 
+    #[test]
+    fn test_config_validation_checks_named_instances() {
+        let mut config = valid_config();
+        config.strategies = Some(vec!["sumto100@sports".into(), "sumto100@bad-name".into()]);
+        config.sum_to_100_instances.insert(
+            "sports".into(),
+            SumTo100Config {
+                min_edge: -0.001,
+                ..SumTo100Config::default()
+            },
+        );
+
+        let err_msg = config.validate().unwrap_err().to_string();
+        assert!(err_msg.contains("SUMTO100_SPORTS__MIN_EDGE must be >= 0"));
+        assert!(err_msg.contains("'sumto100@bad-name'"));
+    }
+
+    #[test]
+    fn test_strategy_instances_and_categories() {
+        let strategies = parse_strategy_list("sniper,SumTo100@Sports,sumto100@all,clipper@x");
+        assert_eq!(
+            strategy_instances(Some(&strategies), "sumto100"),
+            vec!["sports", "all"]
+        );
+        assert!(strategy_instances(None, "sumto100").is_empty());
+        assert_eq!(sum_to_100_var(Some("sports"), "MIN_EDGE"), "SUMTO100_SPORTS__MIN_EDGE");
+
+        let config = SumTo100Config {
+            categories: vec!["sports".into()],
+            ..SumTo100Config::default()
+        };
+        assert!(config.covers_category(Some("Sports")));
+        assert!(!config.covers_category(Some("politics")));
+        assert!(!config.covers_category(None));
+        assert!(SumTo100Config::default().covers_category(None));
+    }
+
     #[test]
     fn test_config_validation_rejects_invalid_fee_rate() {
-        // Test fee_rate > 1.0
+        // Test taker fee > 1.0
         let mut config = valid_config();
-        config.sum_to_100.fee_rate = 1.5;
+        config.cost.taker_fee_rate = 1.5;
 
         let result = config.validate();
         assert!(result.is_err());
         let err_msg = result.unwrap_err().to_string();
-        assert!(err_msg.contains("SUMTO100_FEE_RATE must be between 0.0 and 1.0"));
+        assert!(err_msg.contains("COST_TAKER_FEE_RATE must be between 0.0 and 1.0"));
 
-        // Test fee_rate < 0.0
+        // Test maker fee and slippage < 0.0
         let mut config = valid_config();
-        config.sum_to_100.fee_rate = -0.1;
+        config.cost.maker_fee_rate = -0.1;
+        config.cost.slippage_rate = f64::NAN;
 
         let result = config.validate();
         assert!(result.is_err());
         let err_msg = result.unwrap_err().to_string();
-        assert!(err_msg.contains("SUMTO100_FEE_RATE must be between 0.0 and 1.0"));
+        assert!(err_msg.contains("COST_MAKER_FEE_RATE must be between 0.0 and 1.0"));
+        assert!(err_msg.contains("COST_SLIPPAGE_RATE must be between 0.0 and 1.0"));
     }
 
     #[test]
@@ -572,6 +1457,19 @@ mod tests {
         assert!(err_msg.contains("SUMTO100_MIN_LIQUIDITY must be > 0"));
     }
 
+    #[test]
+    fn test_config_validation_rejects_invalid_impact_model() {
+        let mut config = valid_config();
+        config.sum_to_100.max_participation = 0.0;
+        config.sum_to_100.depth_haircut = 1.0;
+
+        let result = config.validate();
+        assert!(result.is_err());
+        let err_msg = result.unwrap_err().to_string();
+        assert!(err_msg.contains("SUMTO100_MAX_PARTICIPATION must be > 0.0 and <= 1.0"));
+        assert!(err_msg.contains("SUMTO100_DEPTH_HAIRCUT must be >= 0.0 and < 1.0"));
+    }
+
     #[test]
     fn test_config_validation_requires_credentials_when_not_dry_run() {
         let mut config = valid_config();
@@ -602,6 +1500,114 @@ mod tests {
         assert!(config.validate().is_ok());
     }
 
+    #[test]
+    fn test_parse_strategy_list() {
+        assert_eq!(
+            parse_strategy_list(" SumTo100, clipper ,,"),
+            vec!["sumto100".to_string(), "clipper".to_string()]
+        );
+        assert!(parse_strategy_list("").is_empty());
+    }
+
+    #[test]
+    fn test_config_validation_rejects_empty_strategy_list() {
+        let mut config = valid_config();
+        config.strategies = Some(Vec::new());
+
+        let result = config.validate();
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("STRATEGIES must list at least one strategy"));
+    }
+
+    #[test]
+    fn test_redis_prefix_defaults_to_instance_namespace() {
+        assert_eq!(default_redis_prefix(DEFAULT_INSTANCE_ID), "poly");
+        assert_eq!(default_redis_prefix("eu-1"), "poly:eu-1");
+    }
+
+    #[test]
+    fn test_config_validation_rejects_bad_instance_id() {
+        let mut config = valid_config();
+        config.instance_id = "eu 1".into();
+        config.redis_prefix = String::new();
+
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("INSTANCE_ID"));
+        assert!(err.contains("REDIS_CHANNEL_PREFIX"));
+    }
+
+    #[test]
+    fn test_config_validation_rejects_unknown_timezone() {
+        let mut config = valid_config();
+        config.trading_timezone = "America/New_York".into();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.timezone().name(), "America/New_York");
+
+        config.trading_timezone = "EST5EDT-ish".into();
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("TRADING_TIMEZONE"));
+    }
+
+    #[test]
+    fn test_parse_category_budgets() {
+        let budgets = parse_category_budgets("Sports:20:1000, politics:5:250.5,bad,x:1");
+
+        assert_eq!(budgets.len(), 2);
+        assert_eq!(
+            budgets.get("sports"),
+            Some(&MarketBudget {
+                max_daily_trades: 20,
+                max_daily_turnover: 1000.0,
+            })
+        );
+        assert_eq!(budgets.get("politics").unwrap().max_daily_turnover, 250.5);
+    }
+
+    #[test]
+    fn test_parse_price_bands_and_validation() {
+        let bands = parse_price_bands("tok1:0.2:0.8, bad, tok2:x:1");
+        assert_eq!(bands.len(), 1);
+        assert_eq!(bands.get("tok1"), Some(&PriceBand { min: 0.2, max: 0.8 }));
+
+        let mut config = valid_config();
+        config.order_guard.token_bands = bands;
+        assert_eq!(config.order_guard.band_for("tok1").max, 0.8);
+        assert_eq!(config.order_guard.band_for("other").max, 0.99);
+        assert!(config.validate().is_ok());
+
+        config
+            .order_guard
+            .token_bands
+            .insert("tok3".into(), PriceBand { min: 0.9, max: 0.1 });
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("ORDER_PRICE_BANDS band for 'tok3'"));
+    }
+
+    #[test]
+    fn test_config_validation_rejects_bad_order_queue() {
+        let mut config = valid_config();
+        config.order_queue.rate_per_sec = f64::NAN;
+        config.order_queue.burst = 0;
+
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("ORDER_RATE_LIMIT must be >= 0"));
+        assert!(err.contains("ORDER_RATE_BURST must be at least 1"));
+    }
+
+    #[test]
+    fn test_config_validation_rejects_negative_market_turnover() {
+        let mut config = valid_config();
+        config.risk.market_budget.max_daily_turnover = -1.0;
+
+        let result = config.validate();
+        assert!(result.is_err());
+        let err_msg = result.unwrap_err().to_string();
+        assert!(err_msg.contains("RISK_MAX_DAILY_TURNOVER_PER_MARKET must be >= 0"));
+    }
+
     #[test]
     fn test_config_validation_collects_multiple_errors() {
         let mut config = valid_config();
@@ -618,4 +1624,28 @@ mod tests {
         assert!(err_msg.contains("RISK_MAX_NOTIONAL"));
         assert!(err_msg.contains("SNIPER_MIN_PRICE"));
     }
+
+    #[test]
+    fn test_config_validation_issues_are_structured() {
+        let mut config = valid_config();
+        config.risk.max_position = -10.0;
+        config.strategies = Some(Vec::new());
+        config.instance_id = "eu 1".into();
+
+        let issues = config.validate().unwrap_err().issues;
+        let fields: Vec<(&str, IssueCode)> =
+            issues.iter().map(|i| (i.field.as_str(), i.code)).collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("RISK_MAX_POSITION", IssueCode::OutOfRange),
+                ("STRATEGIES", IssueCode::Empty),
+                ("INSTANCE_ID", IssueCode::InvalidFormat),
+            ]
+        );
+        assert_eq!(
+            serde_json::to_value(&issues[0]).unwrap()["code"],
+            "out_of_range"
+        );
+    }
 }
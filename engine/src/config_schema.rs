@@ -0,0 +1,577 @@
+//! Machine-readable config docs (`poly-rust --config-schema`) and checks
+//! (`poly-rust --check-config`).
+//!
+//! The schema is a JSON Schema object keyed by env var: each property has
+//! its type, the default the engine falls back to (read from the config
+//! structs' `Default` impls), a description and the bounds
+//! `Config::validate` enforces. `--check-config` loads the environment the
+//! way startup does and prints `{"valid": .., "issues": [..]}`, each issue
+//! with its `field`, `code` and `message`; it exits non-zero when invalid.
+//!
+//! Both write JSON to stdout and nothing else, so they run before logging
+//! is set up.
+
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use crate::config::{
+    default_redis_prefix, ClipperConfig, Config, ConfigError, ConfigIssue, CostConfig,
+    OrderGuardConfig, OrderQueueConfig, RiskConfig, SniperConfig, SumTo100Config, DEFAULT_CLOB_URL,
+    DEFAULT_INSTANCE_ID, DEFAULT_TRADING_TIMEZONE, DEFAULT_WS_URL,
+};
+
+/// True if `--config-schema` was passed on the command line.
+pub fn schema_requested() -> bool {
+    std::env::args().skip(1).any(|arg| arg == "--config-schema")
+}
+
+/// True if `--check-config` was passed on the command line.
+pub fn check_requested() -> bool {
+    std::env::args().skip(1).any(|arg| arg == "--check-config")
+}
+
+/// One env var in the schema
+struct Field {
+    name: String,
+    schema: Map<String, Value>,
+}
+
+impl Field {
+    fn new(name: impl Into<String>, ty: &str, default: Value, description: &str) -> Self {
+        let mut schema = Map::new();
+        schema.insert("type".into(), ty.into());
+        if !default.is_null() {
+            schema.insert("default".into(), default);
+        }
+        schema.insert("description".into(), description.into());
+        Self {
+            name: name.into(),
+            schema,
+        }
+    }
+
+    fn number(name: impl Into<String>, default: f64, description: &str) -> Self {
+        Self::new(name, "number", json!(default), description)
+    }
+
+    fn integer(name: impl Into<String>, default: u64, description: &str) -> Self {
+        Self::new(name, "integer", json!(default), description).min(0.0)
+    }
+
+    fn boolean(name: impl Into<String>, default: bool, description: &str) -> Self {
+        Self::new(name, "boolean", json!(default), description)
+    }
+
+    fn string(name: impl Into<String>, default: Option<&str>, description: &str) -> Self {
+        Self::new(name, "string", json!(default), description)
+    }
+
+    fn constraint(mut self, key: &str, value: Value) -> Self {
+        self.schema.insert(key.into(), value);
+        self
+    }
+
+    /// Inclusive lower bound
+    fn min(self, value: f64) -> Self {
+        self.constraint("minimum", json!(value))
+    }
+
+    /// Exclusive lower bound
+    fn above(self, value: f64) -> Self {
+        self.constraint("exclusiveMinimum", json!(value))
+    }
+
+    /// Inclusive upper bound
+    fn max(self, value: f64) -> Self {
+        self.constraint("maximum", json!(value))
+    }
+
+    /// Exclusive upper bound
+    fn below(self, value: f64) -> Self {
+        self.constraint("exclusiveMaximum", json!(value))
+    }
+
+    fn pattern(self, pattern: &str) -> Self {
+        self.constraint("pattern", json!(pattern))
+    }
+}
+
+/// Settings shared by the base SumTo100 config and named instances
+fn sum_to_100_fields(prefix: &str) -> Vec<Field> {
+    let d = SumTo100Config::default();
+    let var = |key: &str| format!("{}{}", prefix, key);
+    vec![
+        Field::boolean(
+            var("ENABLED"),
+            d.enabled,
+            "Whether the SumTo100 strategy is enabled",
+        ),
+        Field::number(
+            var("MIN_EDGE"),
+            d.min_edge,
+            "Minimum edge per share after fees",
+        )
+        .min(0.0),
+        Field::number(
+            var("MAX_POSITION"),
+            d.max_position,
+            "Maximum shares per trade",
+        ),
+        Field::number(var("MAX_NOTIONAL"), d.max_notional, "Maximum USD per trade"),
+        Field::number(
+            var("MIN_LIQUIDITY"),
+            d.min_liquidity,
+            "Minimum liquidity required at VWAP",
+        )
+        .above(0.0),
+        Field::boolean(
+            var("PAPER_TRADING"),
+            d.paper_trading,
+            "Simulate fills instead of placing real orders",
+        ),
+        Field::integer(
+            var("MAX_BOOK_AGE_MS"),
+            d.max_book_age_ms,
+            "Maximum order book age in milliseconds before a market is skipped",
+        ),
+        Field::number(
+            var("MAX_PARTICIPATION"),
+            d.max_participation,
+            "Maximum fraction of each displayed depth level we expect to take",
+        )
+        .above(0.0)
+        .max(1.0),
+        Field::number(
+            var("DEPTH_HAIRCUT"),
+            d.depth_haircut,
+            "Fraction of displayed depth assumed to fade before our order arrives",
+        )
+        .min(0.0)
+        .below(1.0),
+        Field::number(
+            var("NEAR_MISS_TOLERANCE"),
+            d.near_miss_tolerance,
+            "Edges this far below MIN_EDGE are reported as near misses (0 = off)",
+        )
+        .min(0.0),
+        Field::string(
+            var("CATEGORIES"),
+            None,
+            "Comma-separated market categories to trade (unset = all)",
+        ),
+        Field::boolean(
+            var("SELL_ENABLED"),
+            d.sell_enabled,
+            "Also sell held YES + NO pairs when the bids sum above 1.00",
+        ),
+    ]
+}
+
+/// Every env var read into `Config`
+fn fields() -> Vec<Field> {
+    let risk = RiskConfig::default();
+    let sniper = SniperConfig::default();
+    let clipper = ClipperConfig::default();
+    let guard = OrderGuardConfig::default();
+    let queue = OrderQueueConfig::default();
+    let cost = CostConfig::default();
+
+    let mut fields = vec![
+        // Connection and credentials
+        Field::string(
+            "POLY_WS_URL",
+            Some(DEFAULT_WS_URL),
+            "Polymarket WebSocket URL",
+        ),
+        Field::string(
+            "POLY_CLOB_URL",
+            Some(DEFAULT_CLOB_URL),
+            "Polymarket CLOB API URL",
+        ),
+        Field::string(
+            "POLY_PRIVATE_KEY",
+            None,
+            "Private key for signing orders (required unless DRY_RUN or OBSERVE)",
+        ),
+        Field::string(
+            "POLY_API_KEY",
+            None,
+            "CLOB API key (required unless DRY_RUN or OBSERVE)",
+        ),
+        Field::string(
+            "POLY_API_SECRET",
+            None,
+            "CLOB API secret (required unless DRY_RUN or OBSERVE)",
+        ),
+        Field::boolean("DRY_RUN", true, "Simulate orders instead of sending them"),
+        Field::boolean(
+            "OBSERVE",
+            false,
+            "Evaluate and record signals without executing anything, not even paper fills",
+        ),
+        // Risk
+        Field::number(
+            "RISK_MAX_POSITION",
+            risk.max_position,
+            "Maximum position size per token",
+        )
+        .above(0.0),
+        Field::number(
+            "RISK_MAX_NOTIONAL",
+            risk.max_notional,
+            "Maximum notional per trade (USD)",
+        )
+        .above(0.0),
+        Field::number(
+            "RISK_MAX_DAILY_LOSS",
+            risk.max_daily_loss,
+            "Maximum daily loss before trading stops (USD)",
+        )
+        .above(0.0),
+        Field::integer(
+            "RISK_MAX_DAILY_TRADES_PER_MARKET",
+            risk.market_budget.max_daily_trades,
+            "Maximum trades per market per day (0 = no cap)",
+        ),
+        Field::number(
+            "RISK_MAX_DAILY_TURNOVER_PER_MARKET",
+            risk.market_budget.max_daily_turnover,
+            "Maximum traded notional per market per day in USD (0 = no cap)",
+        )
+        .min(0.0),
+        Field::string(
+            "RISK_CATEGORY_BUDGETS",
+            None,
+            "Per-category budget overrides: category:max_trades:max_turnover, comma separated",
+        ),
+        // Sniper
+        Field::boolean(
+            "SNIPER_ENABLED",
+            sniper.enabled,
+            "Whether Sniper is enabled",
+        ),
+        Field::number("SNIPER_MIN_PRICE", sniper.min_price, "Minimum price to buy")
+            .min(0.0)
+            .max(1.0),
+        Field::number(
+            "SNIPER_MAX_PRICE",
+            sniper.max_price,
+            "Maximum price to buy (at least SNIPER_MIN_PRICE)",
+        )
+        .max(1.0),
+        Field::number(
+            "SNIPER_ORDER_SIZE",
+            sniper.order_size,
+            "Order size in shares",
+        ),
+        Field::number(
+            "SNIPER_MIN_PROFIT",
+            sniper.min_profit,
+            "Minimum expected profit per share",
+        )
+        .min(0.0),
+        Field::integer(
+            "SNIPER_POLL_MS",
+            sniper.poll_interval_ms,
+            "ESPN poll interval in milliseconds",
+        ),
+        Field::string(
+            "SNIPER_LEAGUES",
+            Some(&sniper.leagues.join(",").to_lowercase()),
+            "Comma-separated leagues to follow",
+        ),
+        Field::number(
+            "SNIPER_PAPER_COMPETITION",
+            sniper.paper_competition_factor,
+            "Per-second rate at which competitors take stale quotes (paper fill model)",
+        )
+        .min(0.0),
+        Field::boolean(
+            "SNIPER_PRESIGN",
+            sniper.presign,
+            "Pre-sign orders for both outcomes of in-progress games",
+        ),
+        Field::integer(
+            "SNIPER_PRESIGN_MAX_AGE_MS",
+            sniper.presign_max_age_ms,
+            "Re-sign pre-signed orders older than this (must be > 0 with SNIPER_PRESIGN)",
+        ),
+        Field::boolean(
+            "SNIPER_FAST_PATH",
+            sniper.fast_path,
+            "Buy the winner as soon as a game goes final instead of on the next tick",
+        ),
+        Field::boolean(
+            "SNIPER_PREPOSITION",
+            sniper.preposition,
+            "Buy in the closing minutes when the price lags a live win-probability model",
+        ),
+        Field::number(
+            "SNIPER_PREPOSITION_MIN_EDGE",
+            sniper.preposition_min_edge,
+            "Minimum model probability minus ask to pre-position",
+        )
+        .above(0.0)
+        .below(1.0),
+        Field::number(
+            "SNIPER_PREPOSITION_WINDOW_SECS",
+            sniper.preposition_window_secs,
+            "Only pre-position with at most this many seconds of regulation left",
+        )
+        .above(0.0),
+        Field::number(
+            "SNIPER_PREPOSITION_SIZE_FRACTION",
+            sniper.preposition_size_fraction,
+            "Pre-position size as a fraction of SNIPER_ORDER_SIZE",
+        )
+        .above(0.0)
+        .max(1.0),
+        Field::number(
+            "SNIPER_MATCH_MIN_CONFIDENCE",
+            sniper.match_min_confidence,
+            "Minimum team-name match confidence for pairing a market with a game",
+        )
+        .min(0.0)
+        .max(1.0),
+        // Clipper
+        Field::boolean(
+            "CLIPPER_ENABLED",
+            clipper.enabled,
+            "Whether Clipper is enabled",
+        ),
+        Field::number(
+            "CLIPPER_MIN_PROFIT",
+            clipper.min_profit,
+            "Minimum profit per share for arbitrage",
+        )
+        .min(0.0),
+        Field::number(
+            "CLIPPER_MAX_POSITION",
+            clipper.max_position,
+            "Maximum position size for arbitrage trades",
+        ),
+        Field::number(
+            "CLIPPER_MAX_NOTIONAL",
+            clipper.max_notional,
+            "Maximum notional per arbitrage trade",
+        ),
+        Field::boolean(
+            "CLIPPER_SPREAD_ENABLED",
+            clipper.spread_enabled,
+            "Enable the spread-capture variant (resting bid, then cross)",
+        ),
+        Field::number(
+            "CLIPPER_SPREAD_IMPROVE",
+            clipper.spread_improve,
+            "How far above the best bid the spread-capture bid rests",
+        )
+        .above(0.0)
+        .below(1.0),
+        Field::integer(
+            "CLIPPER_SPREAD_REST_TIMEOUT_MS",
+            clipper.spread_rest_timeout_ms,
+            "Cancel an unfilled spread-capture bid after this many milliseconds",
+        )
+        .min(1.0),
+    ];
+
+    fields.extend(sum_to_100_fields("SUMTO100_"));
+
+    fields.extend([
+        // Order guard and queue
+        Field::number(
+            "ORDER_MAX_MID_DEVIATION",
+            guard.max_mid_deviation,
+            "Reject orders more than this fraction away from the mid (0 = off)",
+        )
+        .min(0.0),
+        Field::number(
+            "ORDER_MIN_PRICE",
+            guard.price_band.min,
+            "Lowest accepted order price",
+        )
+        .min(0.0)
+        .max(1.0),
+        Field::number(
+            "ORDER_MAX_PRICE",
+            guard.price_band.max,
+            "Highest accepted order price (at least ORDER_MIN_PRICE)",
+        )
+        .min(0.0)
+        .max(1.0),
+        Field::string(
+            "ORDER_PRICE_BANDS",
+            None,
+            "Per-token price bands: token_id:min:max, comma separated",
+        ),
+        Field::number(
+            "ORDER_RATE_LIMIT",
+            queue.rate_per_sec,
+            "Sustained CLOB requests per second (0 = unlimited)",
+        )
+        .min(0.0),
+        Field::integer(
+            "ORDER_RATE_BURST",
+            queue.burst.into(),
+            "Requests that may be sent back to back before the rate applies",
+        )
+        .min(1.0),
+        Field::integer(
+            "ORDER_QUEUE_AGING_MS",
+            queue.aging.as_millis() as u64,
+            "A waiting request moves up one priority class per this many milliseconds",
+        )
+        .min(1.0),
+        // Costs
+        Field::number(
+            "COST_TAKER_FEE_RATE",
+            cost.taker_fee_rate,
+            "Fee on orders that take liquidity, as a fraction of notional",
+        )
+        .min(0.0)
+        .max(1.0),
+        Field::number(
+            "COST_MAKER_FEE_RATE",
+            cost.maker_fee_rate,
+            "Fee on resting orders that get filled, as a fraction of notional",
+        )
+        .min(0.0)
+        .max(1.0),
+        Field::number(
+            "COST_SLIPPAGE_RATE",
+            cost.slippage_rate,
+            "Expected slippage on orders that take liquidity, as a fraction of notional",
+        )
+        .min(0.0)
+        .max(1.0),
+        Field::number(
+            "SUMTO100_FEE_RATE",
+            cost.taker_fee_rate,
+            "Legacy name for COST_TAKER_FEE_RATE, used when that is unset",
+        )
+        .min(0.0)
+        .max(1.0)
+        .constraint("deprecated", json!(true)),
+        // Strategies and namespace
+        Field::string(
+            "STRATEGIES",
+            None,
+            "Comma-separated strategies to run, e.g. sumto100,sumto100@sports (unset = all)",
+        ),
+        Field::string(
+            "INSTANCE_ID",
+            Some(DEFAULT_INSTANCE_ID),
+            "Identifies this engine instance in published messages",
+        )
+        .pattern("^[A-Za-z0-9._-]+$"),
+        Field::string(
+            "REDIS_CHANNEL_PREFIX",
+            Some(&default_redis_prefix(DEFAULT_INSTANCE_ID)),
+            "Prefix of every Redis channel and key (default poly, or poly:<INSTANCE_ID>)",
+        )
+        .pattern("^\\S+$"),
+        Field::string(
+            "TRADING_TIMEZONE",
+            Some(DEFAULT_TRADING_TIMEZONE),
+            "IANA timezone whose midnight starts the trading day (daily limits, reports, P&L days)",
+        ),
+    ]);
+
+    fields
+}
+
+/// JSON Schema of every config env var.
+pub fn schema() -> Value {
+    let properties: Map<String, Value> = fields()
+        .into_iter()
+        .map(|field| (field.name, Value::Object(field.schema)))
+        .collect();
+
+    // Named SumTo100 instances take the same settings under their own prefix
+    let instance_settings: Vec<String> = sum_to_100_fields("")
+        .into_iter()
+        .map(|field| field.name)
+        .collect();
+    let instance_pattern = format!("^SUMTO100_[A-Z0-9_]+__({})$", instance_settings.join("|"));
+
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "poly-rust engine configuration",
+        "description": "Environment variables read at startup. Named SumTo100 instances \
+            (sumto100@<name> in STRATEGIES) read SUMTO100_<NAME>__<SETTING> with the same \
+            constraints as SUMTO100_<SETTING>, falling back to it when unset.",
+        "type": "object",
+        "properties": properties,
+        "patternProperties": {
+            instance_pattern: { "description": "Per-instance SumTo100 setting" }
+        },
+    })
+}
+
+/// Result of `--check-config`
+#[derive(Debug, Serialize)]
+pub struct CheckReport {
+    pub valid: bool,
+    pub issues: Vec<ConfigIssue>,
+}
+
+/// Load the config from the environment and report every invalid value.
+pub fn check() -> Result<CheckReport> {
+    let issues = match Config::from_env() {
+        Ok(_) => Vec::new(),
+        Err(e) => e.downcast::<ConfigError>()?.issues,
+    };
+    Ok(CheckReport {
+        valid: issues.is_empty(),
+        issues,
+    })
+}
+
+/// Handle `--config-schema` or `--check-config` if one was passed. Returns
+/// false when neither was, so startup continues; exits 1 on an invalid
+/// config.
+pub fn run_if_requested() -> Result<bool> {
+    if schema_requested() {
+        println!("{}", serde_json::to_string_pretty(&schema())?);
+        return Ok(true);
+    }
+    if check_requested() {
+        dotenvy::dotenv().ok();
+        let report = check()?;
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        if !report.valid {
+            std::process::exit(1);
+        }
+        return Ok(true);
+    }
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_lists_fields_with_defaults_and_bounds() {
+        let schema = schema();
+        let properties = schema["properties"].as_object().unwrap();
+
+        let max_position = &properties["RISK_MAX_POSITION"];
+        assert_eq!(max_position["type"], "number");
+        assert_eq!(max_position["default"], 100.0);
+        assert_eq!(max_position["exclusiveMinimum"], 0.0);
+
+        let haircut = &properties["SUMTO100_DEPTH_HAIRCUT"];
+        assert_eq!(haircut["minimum"], 0.0);
+        assert_eq!(haircut["exclusiveMaximum"], 1.0);
+
+        assert_eq!(properties["ORDER_RATE_BURST"]["type"], "integer");
+        assert_eq!(properties["INSTANCE_ID"]["default"], DEFAULT_INSTANCE_ID);
+        assert!(properties["POLY_PRIVATE_KEY"].get("default").is_none());
+
+        let patterns = schema["patternProperties"].as_object().unwrap();
+        let pattern = patterns.keys().next().unwrap();
+        assert!(pattern.contains("MIN_EDGE|"));
+    }
+}
@@ -0,0 +1,387 @@
+//! Connectivity supervisor.
+//!
+//! The WebSocket feed, Redis and the database each reconnect on their own,
+//! so one network blip used to set off a burst of reconnects from every
+//! subsystem at once and a log or alert line from each. They now report to
+//! one supervisor instead: it hands out reconnect delays from a single
+//! backoff shared by all of them (doubling per attempt, with jitter, until
+//! everything is back up), and it decides when the network as a whole is
+//! degraded. That is when the market data feed is down, or
+//! `NETWORK_DEGRADED_SUBSYSTEMS` subsystems are down at once, for longer
+//! than `NETWORK_DEGRADED_GRACE_MS`. A degraded network opens an incident:
+//! one Slack alert when it starts, one when everything is back, and
+//! trading is paused in between (signals are observed, not executed).
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::metrics::{NETWORK_DEGRADED, RECONNECTS};
+use crate::notifications::{ErrorAlert, SlackNotifier};
+
+/// Time between incident checks
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Highest doubling of the base delay
+const MAX_DOUBLINGS: u32 = 16;
+
+/// A connection the engine keeps open
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Subsystem {
+    /// Market data WebSocket (trading needs it)
+    Ws,
+    Redis,
+    Db,
+}
+
+impl Subsystem {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Subsystem::Ws => "ws",
+            Subsystem::Redis => "redis",
+            Subsystem::Db => "db",
+        }
+    }
+
+    /// Whether losing it alone degrades the network
+    fn is_critical(self) -> bool {
+        self == Subsystem::Ws
+    }
+}
+
+/// Connectivity supervisor settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConnectivityConfig {
+    /// First reconnect delay, doubled per attempt across all subsystems
+    pub base_delay: Duration,
+    /// Longest reconnect delay
+    pub max_delay: Duration,
+    /// Subsystems down at once that degrade the network
+    pub degraded_subsystems: usize,
+    /// How long the network must look degraded before an incident opens
+    pub grace: Duration,
+    /// Observe instead of trading during an incident
+    pub pause_trading: bool,
+}
+
+impl Default for ConnectivityConfig {
+    fn default() -> Self {
+        Self {
+            base_delay: Duration::from_secs(5),
+            max_delay: Duration::from_secs(300),
+            degraded_subsystems: 2,
+            grace: Duration::from_secs(10),
+            pause_trading: true,
+        }
+    }
+}
+
+impl ConnectivityConfig {
+    /// Load from `RECONNECT_BASE_MS`, `RECONNECT_MAX_MS`,
+    /// `NETWORK_DEGRADED_SUBSYSTEMS`, `NETWORK_DEGRADED_GRACE_MS` and
+    /// `NETWORK_DEGRADED_PAUSE`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok();
+        let millis = |name: &str, default: Duration| {
+            var(name)
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_millis)
+                .unwrap_or(default)
+        };
+        Self {
+            base_delay: millis("RECONNECT_BASE_MS", defaults.base_delay),
+            max_delay: millis("RECONNECT_MAX_MS", defaults.max_delay),
+            degraded_subsystems: var("NETWORK_DEGRADED_SUBSYSTEMS")
+                .and_then(|v| v.parse().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.degraded_subsystems),
+            grace: millis("NETWORK_DEGRADED_GRACE_MS", defaults.grace),
+            pause_trading: var("NETWORK_DEGRADED_PAUSE")
+                .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+                .unwrap_or(defaults.pause_trading),
+        }
+    }
+}
+
+/// An open network incident
+#[derive(Debug)]
+struct Incident {
+    started: Instant,
+    /// Every subsystem that was down during the incident
+    subsystems: BTreeSet<Subsystem>,
+    reconnects: u64,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// Down subsystems and their last error
+    down: HashMap<Subsystem, String>,
+    /// Reconnect attempts since every subsystem was last up
+    failures: u32,
+    /// When the network started to look degraded
+    degraded_since: Option<Instant>,
+    incident: Option<Incident>,
+}
+
+impl State {
+    fn looks_degraded(&self, config: &ConnectivityConfig) -> bool {
+        self.down.keys().any(|s| s.is_critical()) || self.down.len() >= config.degraded_subsystems
+    }
+
+    fn update_degraded(&mut self, config: &ConnectivityConfig, now: Instant) {
+        if !self.looks_degraded(config) {
+            self.degraded_since = None;
+        } else if self.degraded_since.is_none() {
+            self.degraded_since = Some(now);
+        }
+        if let Some(incident) = &mut self.incident {
+            incident.subsystems.extend(self.down.keys().copied());
+        }
+    }
+
+    /// Down subsystems and their errors, for logs and alerts
+    fn describe_down(&self) -> String {
+        let mut down: Vec<_> = self.down.iter().collect();
+        down.sort_by_key(|(subsystem, _)| **subsystem);
+        down.iter()
+            .map(|(subsystem, error)| format!("{} ({})", subsystem.as_str(), error))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Shared reconnect backoff and network incident tracking
+pub struct ConnectivitySupervisor {
+    config: ConnectivityConfig,
+    state: Mutex<State>,
+    slack: Option<Arc<SlackNotifier>>,
+}
+
+impl Default for ConnectivitySupervisor {
+    fn default() -> Self {
+        Self::new(ConnectivityConfig::default())
+    }
+}
+
+impl ConnectivitySupervisor {
+    pub fn new(config: ConnectivityConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(State::default()),
+            slack: None,
+        }
+    }
+
+    /// Send the incident alerts to Slack
+    pub fn set_slack_notifier(&mut self, slack: Arc<SlackNotifier>) {
+        self.slack = Some(slack);
+    }
+
+    /// A subsystem lost its connection (repeat reports are ignored).
+    pub fn report_down(&self, subsystem: Subsystem, error: impl fmt::Display) {
+        let mut state = self.state.lock();
+        let error = error.to_string();
+        if state.down.insert(subsystem, error.clone()).is_none() {
+            warn!("[NET] {} down: {}", subsystem.as_str(), error);
+        }
+        state.update_degraded(&self.config, Instant::now());
+    }
+
+    /// A subsystem is connected (again).
+    pub fn report_up(&self, subsystem: Subsystem) {
+        let mut state = self.state.lock();
+        if state.down.remove(&subsystem).is_some() {
+            info!("[NET] {} back up", subsystem.as_str());
+        }
+        if state.down.is_empty() {
+            state.failures = 0;
+        }
+        state.update_degraded(&self.config, Instant::now());
+    }
+
+    /// Wait before a subsystem's next reconnect attempt. Attempts from all
+    /// subsystems share one backoff, so a blip that drops everything does
+    /// not turn into a reconnect storm.
+    pub fn reconnect_delay(&self, subsystem: Subsystem) -> Duration {
+        RECONNECTS.with_label_values(&[subsystem.as_str()]).inc();
+        let mut state = self.state.lock();
+        state.failures = state.failures.saturating_add(1);
+        if let Some(incident) = &mut state.incident {
+            incident.reconnects += 1;
+        }
+        let doublings = (state.failures - 1).min(MAX_DOUBLINGS);
+        let delay = self
+            .config
+            .base_delay
+            .saturating_mul(1 << doublings)
+            .min(self.config.max_delay);
+        // Full delay at most, half at least, so retries spread out
+        delay.mul_f64(0.5 + rand::random::<f64>() * 0.5)
+    }
+
+    /// Whether an incident is open and trading should pause
+    pub fn pauses_trading(&self) -> bool {
+        self.config.pause_trading && self.state.lock().incident.is_some()
+    }
+
+    /// Open an incident once the network has looked degraded for the grace
+    /// period, and close it when everything is back up.
+    pub fn check(&self, now: Instant) {
+        let mut state = self.state.lock();
+        match (state.incident.is_some(), state.degraded_since) {
+            (false, Some(since)) if now.saturating_duration_since(since) >= self.config.grace => {
+                let message = format!(
+                    "Network degraded: {}{}",
+                    state.describe_down(),
+                    if self.config.pause_trading {
+                        "; trading paused"
+                    } else {
+                        ""
+                    }
+                );
+                state.incident = Some(Incident {
+                    started: since,
+                    subsystems: state.down.keys().copied().collect(),
+                    reconnects: 0,
+                });
+                drop(state);
+                NETWORK_DEGRADED.set(1.0);
+                warn!("[NET] {}", message);
+                self.alert("NETWORK_DEGRADED", message);
+            }
+            (true, None) if state.down.is_empty() => {
+                let Some(incident) = state.incident.take() else {
+                    return;
+                };
+                drop(state);
+                let message = format!(
+                    "Network recovered after {:.0}s ({} affected, {} reconnect attempts)",
+                    now.saturating_duration_since(incident.started)
+                        .as_secs_f64(),
+                    incident
+                        .subsystems
+                        .iter()
+                        .map(|s| s.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    incident.reconnects
+                );
+                NETWORK_DEGRADED.set(0.0);
+                info!("[NET] {}", message);
+                self.alert("NETWORK_RECOVERED", message);
+            }
+            _ => {}
+        }
+    }
+
+    /// Check for incidents until cancelled.
+    pub async fn run(self: Arc<Self>, cancel: CancellationToken) {
+        let mut ticker = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => self.check(Instant::now()),
+                _ = cancel.cancelled() => break,
+            }
+        }
+    }
+
+    fn alert(&self, error_type: &str, message: String) {
+        if let Some(slack) = &self.slack {
+            slack.notify_error(ErrorAlert {
+                source: "connectivity".to_string(),
+                error_type: error_type.to_string(),
+                message,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn supervisor() -> ConnectivitySupervisor {
+        ConnectivitySupervisor::new(ConnectivityConfig {
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(10),
+            ..ConnectivityConfig::default()
+        })
+    }
+
+    #[test]
+    fn test_backoff_is_shared_and_resets_once_all_are_up() {
+        let net = supervisor();
+        net.report_down(Subsystem::Redis, "connection reset");
+        net.report_down(Subsystem::Db, "pool timed out");
+
+        let first = net.reconnect_delay(Subsystem::Redis);
+        let second = net.reconnect_delay(Subsystem::Db);
+        let third = net.reconnect_delay(Subsystem::Redis);
+        assert!(first >= Duration::from_millis(500) && first <= Duration::from_secs(1));
+        assert!(second >= Duration::from_secs(1) && second <= Duration::from_secs(2));
+        assert!(third >= Duration::from_secs(2) && third <= Duration::from_secs(4));
+        for _ in 0..10 {
+            assert!(net.reconnect_delay(Subsystem::Db) <= Duration::from_secs(10));
+        }
+
+        // Still one down: the backoff keeps growing
+        net.report_up(Subsystem::Redis);
+        assert!(net.reconnect_delay(Subsystem::Db) >= Duration::from_secs(5));
+        net.report_up(Subsystem::Db);
+        assert!(net.reconnect_delay(Subsystem::Ws) <= Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_one_incident_after_grace_pauses_trading() {
+        let net = supervisor();
+        let start = Instant::now();
+
+        // One non-critical subsystem down is not an incident
+        net.report_down(Subsystem::Db, "pool timed out");
+        net.check(start + Duration::from_secs(60));
+        assert!(!net.pauses_trading());
+
+        // Losing market data is, after the grace period
+        net.report_down(Subsystem::Ws, "connection reset");
+        net.check(start + Duration::from_secs(1));
+        assert!(!net.pauses_trading());
+        net.check(start + Duration::from_secs(11));
+        assert!(net.pauses_trading());
+        net.report_down(Subsystem::Redis, "broken pipe");
+        net.reconnect_delay(Subsystem::Ws);
+
+        // Closed only once everything is back
+        net.report_up(Subsystem::Ws);
+        net.report_up(Subsystem::Redis);
+        net.check(start + Duration::from_secs(20));
+        assert!(net.pauses_trading());
+        net.report_up(Subsystem::Db);
+        {
+            let state = net.state.lock();
+            let incident = state.incident.as_ref().unwrap();
+            assert_eq!(incident.reconnects, 1);
+            assert_eq!(incident.subsystems.len(), 3);
+        }
+        net.check(start + Duration::from_secs(21));
+        assert!(!net.pauses_trading());
+    }
+
+    #[test]
+    fn test_pause_can_be_disabled() {
+        let net = ConnectivitySupervisor::new(ConnectivityConfig {
+            grace: Duration::ZERO,
+            pause_trading: false,
+            ..ConnectivityConfig::default()
+        });
+        net.report_down(Subsystem::Ws, "connection reset");
+        net.check(Instant::now());
+        assert!(net.state.lock().incident.is_some());
+        assert!(!net.pauses_trading());
+    }
+}
@@ -0,0 +1,248 @@
+//! P&L attribution by market, category, strategy, and hour of day.
+//!
+//! Filled trades are periodically rolled up into the `pnl_attribution` table
+//! (one row per day, hour, strategy, market, category, and paper flag, with
+//! days and hours in the trading timezone), which the
+//! `/control/pnl-attribution` endpoint summarizes along any combination of
+//! dimensions. Single-leg trades contribute their realized
+//! P&L (set on sells); arbitrage pairs contribute their net profit.
+
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use super::TradeRepository;
+
+/// Rebuild the attribution rows for recent days from the trade tables.
+const REFRESH_SQL: &str = r#"
+    INSERT INTO pnl_attribution
+        (day, hour, strategy, market_id, category, is_paper, trades, volume, pnl, updated_at)
+    SELECT day, hour, strategy, market_id, category, is_paper,
+           COUNT(*), SUM(volume), SUM(pnl), NOW()
+    FROM (
+        SELECT DATE(created_at AT TIME ZONE $2) AS day,
+               EXTRACT(HOUR FROM created_at AT TIME ZONE $2)::SMALLINT AS hour,
+               strategy,
+               COALESCE(market_id, '') AS market_id,
+               COALESCE(category, '') AS category,
+               is_paper,
+               price * size AS volume,
+               COALESCE(realized_pnl, 0) AS pnl
+        FROM trades
+        WHERE status = 'FILLED'
+          AND DATE(created_at AT TIME ZONE $2) >= $1
+        UNION ALL
+        SELECT DATE(created_at AT TIME ZONE $2),
+               EXTRACT(HOUR FROM created_at AT TIME ZONE $2)::SMALLINT,
+               strategy,
+               market_id,
+               COALESCE(category, ''),
+               is_paper,
+               total_cost,
+               net_profit
+        FROM arb_trades
+        WHERE status = 'FILLED'
+          AND DATE(created_at AT TIME ZONE $2) >= $1
+    ) filled
+    GROUP BY day, hour, strategy, market_id, category, is_paper
+"#;
+
+/// A dimension the attribution summary can be grouped by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttributionDimension {
+    Market,
+    Category,
+    Strategy,
+    /// Hour of day in the trading timezone (0-23)
+    Hour,
+    /// Day of week in the trading timezone (Mon-Sun)
+    Weekday,
+    Day,
+}
+
+impl AttributionDimension {
+    /// SQL expression for this dimension (whitelisted, never user input)
+    fn column(self) -> &'static str {
+        match self {
+            AttributionDimension::Market => "market_id",
+            AttributionDimension::Category => "category",
+            AttributionDimension::Strategy => "strategy",
+            AttributionDimension::Hour => "hour::TEXT",
+            AttributionDimension::Weekday => "TO_CHAR(day, 'Dy')",
+            AttributionDimension::Day => "day::TEXT",
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AttributionDimension::Market => "market",
+            AttributionDimension::Category => "category",
+            AttributionDimension::Strategy => "strategy",
+            AttributionDimension::Hour => "hour",
+            AttributionDimension::Weekday => "weekday",
+            AttributionDimension::Day => "day",
+        }
+    }
+
+    /// Parse a comma-separated list such as `strategy,category`.
+    pub fn parse_list(s: &str) -> Result<Vec<Self>, String> {
+        let mut dims = Vec::new();
+        for part in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let dim: Self = part.parse()?;
+            if !dims.contains(&dim) {
+                dims.push(dim);
+            }
+        }
+        if dims.is_empty() {
+            return Err("no dimensions given".to_string());
+        }
+        Ok(dims)
+    }
+}
+
+impl FromStr for AttributionDimension {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "market" => Ok(AttributionDimension::Market),
+            "category" => Ok(AttributionDimension::Category),
+            "strategy" => Ok(AttributionDimension::Strategy),
+            "hour" => Ok(AttributionDimension::Hour),
+            "weekday" => Ok(AttributionDimension::Weekday),
+            "day" => Ok(AttributionDimension::Day),
+            other => Err(format!("unknown dimension '{}'", other)),
+        }
+    }
+}
+
+impl fmt::Display for AttributionDimension {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One group of the attribution summary
+#[derive(Debug, Clone, Serialize)]
+pub struct AttributionRow {
+    /// Values of the requested dimensions, in request order
+    pub key: Vec<String>,
+    pub trades: i64,
+    pub volume: f64,
+    pub pnl: f64,
+}
+
+/// Build the summary query for the given dimensions.
+fn summary_sql(dimensions: &[AttributionDimension]) -> String {
+    let columns: Vec<&str> = dimensions.iter().map(|d| d.column()).collect();
+    let columns = columns.join(", ");
+    format!(
+        r#"
+        SELECT ARRAY[{columns}] AS key,
+               SUM(trades)::BIGINT,
+               SUM(volume)::DOUBLE PRECISION,
+               SUM(pnl)::DOUBLE PRECISION
+        FROM pnl_attribution
+        WHERE day >= $1
+          AND ($2::BOOLEAN IS NULL OR is_paper = $2)
+        GROUP BY {columns}
+        ORDER BY 4 DESC
+        "#
+    )
+}
+
+/// First day included in a window of `days` days ending `today`
+pub(super) fn window_start(days: i32, today: chrono::NaiveDate) -> chrono::NaiveDate {
+    today - chrono::Duration::days(i64::from(days.max(1) - 1))
+}
+
+impl TradeRepository {
+    /// Rebuild attribution rows for the last `days` trading days (0 = today
+    /// only).
+    pub(super) async fn refresh_attribution_table(&self, days: i32) -> Result<u64> {
+        let Some(pool) = &self.pool else {
+            return Ok(0);
+        };
+        let since = window_start(days + 1, self.today());
+
+        let mut tx = pool.begin().await?;
+        sqlx::query("DELETE FROM pnl_attribution WHERE day >= $1")
+            .bind(since)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to clear attribution rows")?;
+        let inserted = sqlx::query(REFRESH_SQL)
+            .bind(since)
+            .bind(self.timezone.name())
+            .execute(&mut *tx)
+            .await
+            .context("Failed to aggregate attribution rows")?
+            .rows_affected();
+        tx.commit().await?;
+
+        Ok(inserted)
+    }
+
+    /// Summarize the last `days` days of attribution by `dimensions`,
+    /// optionally only paper or only live trades. Sorted by P&L, best first.
+    pub(super) async fn query_attribution(
+        &self,
+        days: i32,
+        dimensions: &[AttributionDimension],
+        is_paper: Option<bool>,
+    ) -> Result<Vec<AttributionRow>> {
+        let Some(pool) = &self.pool else {
+            return Ok(Vec::new());
+        };
+
+        let rows: Vec<(Vec<String>, i64, f64, f64)> = sqlx::query_as(&summary_sql(dimensions))
+            .bind(window_start(days, self.today()))
+            .bind(is_paper)
+            .fetch_all(pool)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(key, trades, volume, pnl)| AttributionRow {
+                key,
+                trades,
+                volume,
+                pnl,
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dimension_list() {
+        assert_eq!(
+            AttributionDimension::parse_list("strategy, Category,weekday,strategy"),
+            Ok(vec![
+                AttributionDimension::Strategy,
+                AttributionDimension::Category,
+                AttributionDimension::Weekday,
+            ])
+        );
+        assert!(AttributionDimension::parse_list("").is_err());
+        assert!(AttributionDimension::parse_list("market;drop table").is_err());
+    }
+
+    #[test]
+    fn test_summary_groups_by_requested_columns() {
+        let sql = summary_sql(&[AttributionDimension::Weekday, AttributionDimension::Hour]);
+        assert!(sql.contains("ARRAY[TO_CHAR(day, 'Dy'), hour::TEXT] AS key"));
+        assert!(sql.contains("GROUP BY TO_CHAR(day, 'Dy'), hour::TEXT"));
+        let today = chrono::NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+        assert_eq!(window_start(1, today), today);
+        assert_eq!(
+            window_start(7, today),
+            chrono::NaiveDate::from_ymd_opt(2026, 3, 4).unwrap()
+        );
+    }
+}
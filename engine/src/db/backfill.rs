@@ -0,0 +1,250 @@
+//! Backfill of trades missing after an outage
+//! (`poly-rust --backfill-trades --from=<RFC 3339> [--to=<RFC 3339>]`).
+//!
+//! Orders that fill while the engine is down, or whose trade row never made
+//! it to the database, leave P&L and attribution short. The backfill pulls
+//! the account's fills for the range from the CLOB trade history and
+//! inserts one row per fill for every order with no recorded trade (single
+//! or arbitrage leg). Rows are marked `backfilled` and keyed by the
+//! exchange fill ID, so re-running a range inserts nothing new. A fill whose
+//! order is in `order_tags` is attributed to the strategy that placed it,
+//! with the signal ID in its metadata; untagged fills get strategy
+//! `backfill`.
+
+use std::collections::HashMap;
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{json, Value};
+use tracing::info;
+
+use super::dedupe::MIGRATE_SQL;
+use super::{OrderTag, TradeRepository};
+use crate::config::Config;
+use crate::execution::{ExchangeFill, OrderManager, Side};
+
+/// Strategy recorded on backfilled rows of untagged orders
+const BACKFILL_STRATEGY: &str = "backfill";
+
+/// Adds the flag to databases created before it
+const BACKFILLED_COLUMN_SQL: &str =
+    "ALTER TABLE trades ADD COLUMN IF NOT EXISTS backfilled BOOLEAN NOT NULL DEFAULT false";
+
+/// Insert a fill unless its order already has a trade recorded by the engine.
+const INSERT_FILL_SQL: &str = r#"
+    INSERT INTO trades (
+        client_trade_id, created_at, token_id, side, price, size, order_id, status,
+        strategy, signal_reason, is_paper, market_id, backfilled, metadata
+    )
+    SELECT $1, TO_TIMESTAMP($2::DOUBLE PRECISION), $3, $4, $5, $6, $7, 'FILLED',
+           $8, 'Backfilled from exchange trade history', false, $9, true, $10::JSONB
+    WHERE NOT EXISTS (SELECT 1 FROM trades WHERE order_id = $7 AND NOT backfilled)
+      AND NOT EXISTS (
+          SELECT 1 FROM arb_trades WHERE yes_order_id = $7 OR no_order_id = $7
+      )
+    ON CONFLICT (client_trade_id) DO NOTHING
+"#;
+
+/// Time range to backfill
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackfillRange {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+}
+
+impl BackfillRange {
+    /// The range from `--from=` and `--to=` (default now) if
+    /// `--backfill-trades` is among `args`.
+    pub fn from_args(
+        args: impl IntoIterator<Item = String>,
+        now: DateTime<Utc>,
+    ) -> Result<Option<Self>> {
+        let mut requested = false;
+        let (mut from, mut to) = (None, None);
+        for arg in args {
+            if arg == "--backfill-trades" {
+                requested = true;
+            } else if let Some(value) = arg.strip_prefix("--from=") {
+                from = Some(parse_time(value)?);
+            } else if let Some(value) = arg.strip_prefix("--to=") {
+                to = Some(parse_time(value)?);
+            }
+        }
+        if !requested {
+            return Ok(None);
+        }
+        let Some(from) = from else {
+            bail!("--backfill-trades needs --from=<RFC 3339 time>");
+        };
+        let to = to.unwrap_or(now);
+        if from >= to {
+            bail!("--from must be before --to");
+        }
+        Ok(Some(Self { from, to }))
+    }
+}
+
+fn parse_time(value: &str) -> Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|t| t.with_timezone(&Utc))
+        .with_context(|| format!("Invalid time '{}' (expected RFC 3339)", value))
+}
+
+/// Outcome of a backfill
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct BackfillReport {
+    /// Our fills in the exchange history
+    pub fills: usize,
+    /// Rows inserted for fills with no recorded trade
+    pub inserted: u64,
+    /// Fills attributed to a strategy through their order's tag
+    pub attributed: usize,
+}
+
+/// Strategy and metadata recorded for a fill of an order with `tag`
+fn attribution(tag: Option<&OrderTag>) -> (&str, Option<Value>) {
+    match tag {
+        Some(tag) => (
+            tag.strategy.as_str(),
+            tag.signal_id.as_ref().map(|id| json!({ "signal_id": id })),
+        ),
+        None => (BACKFILL_STRATEGY, None),
+    }
+}
+
+impl TradeRepository {
+    /// Insert the fills whose orders have no trade recorded.
+    pub async fn backfill_fills(&self, fills: &[ExchangeFill]) -> Result<BackfillReport> {
+        let Some(pool) = &self.pool else {
+            bail!("database disabled (DATABASE_URL not set)");
+        };
+
+        let order_ids: Vec<String> = fills.iter().map(|f| f.order_id.clone()).collect();
+        let tags: HashMap<String, OrderTag> = self
+            .order_tags(&order_ids)
+            .await
+            .context("Failed to load order tags")?
+            .into_iter()
+            .map(|tag| (tag.order_id.clone(), tag))
+            .collect();
+
+        let mut tx = pool.begin().await?;
+        for sql in MIGRATE_SQL.iter().chain([&BACKFILLED_COLUMN_SQL]) {
+            sqlx::query(sql).execute(&mut *tx).await?;
+        }
+        let mut inserted = 0;
+        let mut attributed = 0;
+        for fill in fills {
+            let tag = tags.get(&fill.order_id);
+            attributed += usize::from(tag.is_some());
+            let (strategy, metadata) = attribution(tag);
+            let side = match fill.side {
+                Side::Buy => "BUY",
+                Side::Sell => "SELL",
+            };
+            inserted += sqlx::query(INSERT_FILL_SQL)
+                .bind(format!("exchange:{}", fill.fill_id))
+                .bind(fill.matched_at.timestamp() as f64)
+                .bind(&fill.token_id)
+                .bind(side)
+                .bind(fill.price)
+                .bind(fill.size)
+                .bind(&fill.order_id)
+                .bind(strategy)
+                .bind(&fill.market_id)
+                .bind(metadata.map(|m| m.to_string()))
+                .execute(&mut *tx)
+                .await
+                .with_context(|| format!("Failed to backfill fill {}", fill.fill_id))?
+                .rows_affected();
+        }
+        tx.commit().await?;
+
+        Ok(BackfillReport {
+            fills: fills.len(),
+            inserted,
+            attributed,
+        })
+    }
+}
+
+/// Pull the account's fills for `range` and insert the missing ones.
+pub async fn run(config: &Config, database_url: Option<&str>, range: BackfillRange) -> Result<()> {
+    if config.dry_run {
+        bail!("--backfill-trades needs live credentials (DRY_RUN=false)");
+    }
+    let repo = TradeRepository::new(database_url).await?;
+    let order_manager = OrderManager::new(config.clone(), None).await?;
+
+    info!(
+        "[DB] Backfilling trades from {} to {}",
+        range.from.to_rfc3339(),
+        range.to.to_rfc3339()
+    );
+    let fills = order_manager
+        .trade_history(range.from.timestamp(), range.to.timestamp())
+        .await
+        .context("Failed to fetch trade history")?;
+    let report = repo.backfill_fills(&fills).await?;
+    info!(
+        "[DB] Backfill done: {} fills in the exchange history ({} attributed to a strategy), {} missing rows inserted",
+        report.fills, report.attributed, report.inserted
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_range_from_args() {
+        let now = parse_time("2024-03-02T00:00:00Z").unwrap();
+        assert_eq!(
+            BackfillRange::from_args(args(&["--from=2024-03-01T00:00:00Z"]), now).unwrap(),
+            None
+        );
+
+        let range = BackfillRange::from_args(
+            args(&["--backfill-trades", "--from=2024-03-01T12:00:00+02:00"]),
+            now,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(range.from, parse_time("2024-03-01T10:00:00Z").unwrap());
+        assert_eq!(range.to, now);
+
+        assert!(BackfillRange::from_args(args(&["--backfill-trades"]), now).is_err());
+        assert!(BackfillRange::from_args(
+            args(&[
+                "--backfill-trades",
+                "--from=2024-03-01T00:00:00Z",
+                "--to=2024-02-01T00:00:00Z"
+            ]),
+            now
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_tagged_fills_are_attributed_to_their_strategy() {
+        let tag = OrderTag {
+            order_id: "0xabc".to_string(),
+            strategy: "SumTo100".to_string(),
+            signal_id: Some("sig-1".to_string()),
+            token_id: "token".to_string(),
+            side: "BUY".to_string(),
+            is_paper: false,
+        };
+        assert_eq!(
+            attribution(Some(&tag)),
+            ("SumTo100", Some(json!({ "signal_id": "sig-1" })))
+        );
+        assert_eq!(attribution(None), (BACKFILL_STRATEGY, None));
+    }
+}
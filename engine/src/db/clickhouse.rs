@@ -0,0 +1,477 @@
+//! ClickHouse sink for high-volume analytics.
+//!
+//! Signals, near misses and sampled order books arrive far faster than
+//! trades and do not belong in Postgres. The sink queues rows without
+//! blocking and writes them in batches through ClickHouse's HTTP interface
+//! (`INSERT ... FORMAT JSONEachRow`), every `CLICKHOUSE_FLUSH_MS` or once a
+//! table has `CLICKHOUSE_BATCH_SIZE` rows. When the queue is full, rows are
+//! dropped and counted rather than slowing the trading loop; a failed
+//! insert is logged and its rows are lost. The schema is in `clickhouse.sql`.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use parking_lot::Mutex;
+use reqwest::Client;
+use serde::Serialize;
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::market::{MarketData, OrderBook};
+use crate::metrics::ANALYTICS_ROWS;
+use crate::redis::{now_ms, NearMissMessage, SignalMessage};
+
+/// Analytics table a row goes to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AnalyticsTable {
+    Signals,
+    NearMisses,
+    BookSnapshots,
+}
+
+impl AnalyticsTable {
+    const ALL: [AnalyticsTable; 3] = [
+        AnalyticsTable::Signals,
+        AnalyticsTable::NearMisses,
+        AnalyticsTable::BookSnapshots,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AnalyticsTable::Signals => "signals",
+            AnalyticsTable::NearMisses => "near_misses",
+            AnalyticsTable::BookSnapshots => "book_snapshots",
+        }
+    }
+}
+
+/// ClickHouse sink settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClickHouseConfig {
+    /// HTTP endpoint, e.g. `http://clickhouse:8123` (None = disabled)
+    pub url: Option<String>,
+    pub database: String,
+    pub user: Option<String>,
+    pub password: Option<String>,
+    /// Rows per table that trigger a flush
+    pub batch_size: usize,
+    /// Time between flushes of partial batches
+    pub flush_interval: Duration,
+    /// Rows queued before new ones are dropped
+    pub queue_capacity: usize,
+    /// Time between order book samples (None = no book snapshots)
+    pub book_sample_interval: Option<Duration>,
+    /// Levels per side kept in each book snapshot
+    pub book_levels: usize,
+}
+
+impl Default for ClickHouseConfig {
+    fn default() -> Self {
+        Self {
+            url: None,
+            database: "poly".to_string(),
+            user: None,
+            password: None,
+            batch_size: 1000,
+            flush_interval: Duration::from_secs(1),
+            queue_capacity: 100_000,
+            book_sample_interval: Some(Duration::from_secs(5)),
+            book_levels: 5,
+        }
+    }
+}
+
+impl ClickHouseConfig {
+    /// Load from `CLICKHOUSE_URL`, `CLICKHOUSE_DATABASE`, `CLICKHOUSE_USER`,
+    /// `CLICKHOUSE_PASSWORD`, `CLICKHOUSE_BATCH_SIZE`, `CLICKHOUSE_FLUSH_MS`,
+    /// `CLICKHOUSE_QUEUE_CAPACITY`, `CLICKHOUSE_BOOK_SAMPLE_MS` (0 disables
+    /// book snapshots) and `CLICKHOUSE_BOOK_LEVELS`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        let positive = |name: &str| {
+            var(name)
+                .and_then(|v| v.parse::<u64>().ok())
+                .filter(|n| *n > 0)
+        };
+        Self {
+            url: var("CLICKHOUSE_URL").map(|url| url.trim_end_matches('/').to_string()),
+            database: var("CLICKHOUSE_DATABASE").unwrap_or(defaults.database),
+            user: var("CLICKHOUSE_USER"),
+            password: var("CLICKHOUSE_PASSWORD"),
+            batch_size: positive("CLICKHOUSE_BATCH_SIZE")
+                .map(|n| n as usize)
+                .unwrap_or(defaults.batch_size),
+            flush_interval: positive("CLICKHOUSE_FLUSH_MS")
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.flush_interval),
+            queue_capacity: positive("CLICKHOUSE_QUEUE_CAPACITY")
+                .map(|n| n as usize)
+                .unwrap_or(defaults.queue_capacity),
+            book_sample_interval: var("CLICKHOUSE_BOOK_SAMPLE_MS")
+                .and_then(|v| v.parse().ok())
+                .map(|ms: u64| (ms > 0).then(|| Duration::from_millis(ms)))
+                .unwrap_or(defaults.book_sample_interval),
+            book_levels: positive("CLICKHOUSE_BOOK_LEVELS")
+                .map(|n| n as usize)
+                .unwrap_or(defaults.book_levels),
+        }
+    }
+}
+
+/// A signal as stored (context flattened to a JSON string)
+#[derive(Serialize)]
+struct SignalRow<'a> {
+    timestamp_ms: u64,
+    strategy: &'a str,
+    signal_type: &'a str,
+    mode: &'a str,
+    token_id: Option<&'a str>,
+    yes_token_id: Option<&'a str>,
+    no_token_id: Option<&'a str>,
+    price: Option<f64>,
+    yes_price: Option<f64>,
+    no_price: Option<f64>,
+    size: f64,
+    edge: Option<f64>,
+    reason: &'a str,
+    context: String,
+    metadata: String,
+}
+
+/// Top of an order book at one instant
+#[derive(Debug, Serialize)]
+struct BookRow<'a> {
+    timestamp_ms: u64,
+    token_id: &'a str,
+    market_id: Option<String>,
+    best_bid: Option<f64>,
+    best_ask: Option<f64>,
+    bid_depth: f64,
+    ask_depth: f64,
+    bid_prices: Vec<f64>,
+    bid_sizes: Vec<f64>,
+    ask_prices: Vec<f64>,
+    ask_sizes: Vec<f64>,
+}
+
+impl<'a> BookRow<'a> {
+    fn new(
+        timestamp_ms: u64,
+        book: &'a OrderBook,
+        market_id: Option<String>,
+        levels: usize,
+    ) -> Self {
+        let bids = &book.bids[..levels.min(book.bids.len())];
+        let asks = &book.asks[..levels.min(book.asks.len())];
+        Self {
+            timestamp_ms,
+            token_id: &book.token_id,
+            market_id,
+            best_bid: book.best_bid(),
+            best_ask: book.best_ask(),
+            bid_depth: book.total_bid_size(),
+            ask_depth: book.total_ask_size(),
+            bid_prices: bids.iter().map(|l| l.price).collect(),
+            bid_sizes: bids.iter().map(|l| l.size).collect(),
+            ask_prices: asks.iter().map(|l| l.price).collect(),
+            ask_sizes: asks.iter().map(|l| l.size).collect(),
+        }
+    }
+}
+
+/// Batched, non-blocking writer of analytics rows to ClickHouse
+pub struct AnalyticsSink {
+    config: ClickHouseConfig,
+    client: Client,
+    tx: mpsc::Sender<(AnalyticsTable, String)>,
+    /// Taken by `run`
+    rx: Mutex<Option<mpsc::Receiver<(AnalyticsTable, String)>>>,
+}
+
+impl AnalyticsSink {
+    /// Create a sink, or None if `CLICKHOUSE_URL` is not configured.
+    pub fn new(config: ClickHouseConfig) -> Option<Self> {
+        config.url.as_ref()?;
+        let (tx, rx) = mpsc::channel(config.queue_capacity);
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .ok()?;
+        Some(Self {
+            config,
+            client,
+            tx,
+            rx: Mutex::new(Some(rx)),
+        })
+    }
+
+    /// Queue a strategy signal.
+    pub fn record_signal(&self, signal: &SignalMessage) {
+        self.enqueue(
+            AnalyticsTable::Signals,
+            &SignalRow {
+                timestamp_ms: signal.timestamp_ms,
+                strategy: &signal.strategy,
+                signal_type: &signal.signal_type,
+                mode: &signal.mode,
+                token_id: signal.token_id.as_deref(),
+                yes_token_id: signal.yes_token_id.as_deref(),
+                no_token_id: signal.no_token_id.as_deref(),
+                price: signal.price,
+                yes_price: signal.yes_price,
+                no_price: signal.no_price,
+                size: signal.size,
+                edge: signal.edge,
+                reason: &signal.reason,
+                context: signal
+                    .context
+                    .as_ref()
+                    .map(|c| c.to_string())
+                    .unwrap_or_default(),
+                metadata: signal
+                    .metadata
+                    .as_ref()
+                    .map(|m| m.to_string())
+                    .unwrap_or_default(),
+            },
+        );
+    }
+
+    /// Queue a near miss.
+    pub fn record_near_miss(&self, near_miss: &NearMissMessage) {
+        self.enqueue(AnalyticsTable::NearMisses, near_miss);
+    }
+
+    fn enqueue<T: Serialize>(&self, table: AnalyticsTable, row: &T) {
+        let json = match serde_json::to_string(row) {
+            Ok(json) => json,
+            Err(e) => {
+                warn!(
+                    "[ANALYTICS] Failed to serialize {} row: {}",
+                    table.as_str(),
+                    e
+                );
+                return;
+            }
+        };
+        match self.tx.try_send((table, json)) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) | Err(TrySendError::Closed(_)) => {
+                ANALYTICS_ROWS
+                    .with_label_values(&[table.as_str(), "dropped"])
+                    .inc();
+            }
+        }
+    }
+
+    /// Queue a snapshot of every order book.
+    fn sample_books(&self, market_data: &MarketData) {
+        let now = now_ms();
+        for (token_id, book) in market_data.iter_order_books() {
+            let market_id = market_data.get_market_id(&token_id);
+            self.enqueue(
+                AnalyticsTable::BookSnapshots,
+                &BookRow::new(now, &book, market_id, self.config.book_levels),
+            );
+        }
+    }
+
+    /// Write queued rows in batches (and sample books) until cancelled,
+    /// then flush what is left.
+    pub async fn run(self: Arc<Self>, market_data: Arc<MarketData>, cancel: CancellationToken) {
+        let Some(mut rx) = self.rx.lock().take() else {
+            warn!("[ANALYTICS] Sink is already running");
+            return;
+        };
+        info!(
+            "[ANALYTICS] Writing to ClickHouse database '{}' in batches of {} every {:?}",
+            self.config.database, self.config.batch_size, self.config.flush_interval
+        );
+
+        let mut batches: HashMap<AnalyticsTable, Vec<String>> = HashMap::new();
+        let mut flush_ticker = tokio::time::interval(self.config.flush_interval);
+        let mut sample_ticker = tokio::time::interval(
+            self.config
+                .book_sample_interval
+                .unwrap_or(Duration::from_secs(3600)),
+        );
+
+        loop {
+            tokio::select! {
+                row = rx.recv() => {
+                    let Some((table, json)) = row else { break };
+                    let batch = batches.entry(table).or_default();
+                    batch.push(json);
+                    if batch.len() >= self.config.batch_size {
+                        let rows = std::mem::take(batch);
+                        self.flush(table, rows).await;
+                    }
+                }
+                _ = flush_ticker.tick() => {
+                    for table in AnalyticsTable::ALL {
+                        if let Some(rows) = batches.get_mut(&table).filter(|b| !b.is_empty()) {
+                            let rows = std::mem::take(rows);
+                            self.flush(table, rows).await;
+                        }
+                    }
+                }
+                _ = sample_ticker.tick(), if self.config.book_sample_interval.is_some() => {
+                    self.sample_books(&market_data);
+                }
+                _ = cancel.cancelled() => {
+                    while let Ok((table, json)) = rx.try_recv() {
+                        batches.entry(table).or_default().push(json);
+                    }
+                    for (table, rows) in batches.drain() {
+                        if !rows.is_empty() {
+                            self.flush(table, rows).await;
+                        }
+                    }
+                    break;
+                }
+            }
+        }
+    }
+
+    async fn flush(&self, table: AnalyticsTable, rows: Vec<String>) {
+        let count = rows.len() as f64;
+        match self.insert(table, rows).await {
+            Ok(()) => ANALYTICS_ROWS
+                .with_label_values(&[table.as_str(), "written"])
+                .inc_by(count),
+            Err(e) => {
+                warn!(
+                    "[ANALYTICS] Failed to insert {} {} rows: {:#}",
+                    count,
+                    table.as_str(),
+                    e
+                );
+                ANALYTICS_ROWS
+                    .with_label_values(&[table.as_str(), "failed"])
+                    .inc_by(count);
+            }
+        }
+    }
+
+    async fn insert(&self, table: AnalyticsTable, rows: Vec<String>) -> Result<()> {
+        let url = self
+            .config
+            .url
+            .as_deref()
+            .context("ClickHouse URL not set")?;
+        let query = format!(
+            "INSERT INTO {}.{} FORMAT JSONEachRow",
+            self.config.database,
+            table.as_str()
+        );
+        let mut request = self
+            .client
+            .post(url)
+            .query(&[("query", query.as_str())])
+            .body(rows.join("\n"));
+        if let Some(user) = &self.config.user {
+            request = request.header("X-ClickHouse-User", user);
+        }
+        if let Some(password) = &self.config.password {
+            request = request.header("X-ClickHouse-Key", password);
+        }
+
+        let response = request.send().await.context("Request failed")?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            bail!("HTTP {}: {}", status, body.trim());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market::DepthLevel;
+    use hyper::service::{make_service_fn, service_fn};
+    use hyper::{Body, Request, Response, Server};
+
+    #[test]
+    fn test_book_row_keeps_top_levels() {
+        let mut book = OrderBook::new("token".to_string());
+        book.bids = vec![DepthLevel::new(0.48, 100.0), DepthLevel::new(0.47, 50.0)];
+        book.asks = vec![DepthLevel::new(0.52, 80.0)];
+
+        let row = BookRow::new(1_000, &book, Some("m1".to_string()), 1);
+        assert_eq!(row.best_bid, Some(0.48));
+        assert_eq!(row.bid_depth, 150.0);
+        assert_eq!((row.bid_prices, row.bid_sizes), (vec![0.48], vec![100.0]));
+        assert_eq!(row.ask_prices, vec![0.52]);
+    }
+
+    #[tokio::test]
+    async fn test_batches_rows_per_table() {
+        let received: Arc<Mutex<Vec<(String, String)>>> = Arc::default();
+        let captured = received.clone();
+        let make_svc = make_service_fn(move |_| {
+            let captured = captured.clone();
+            async move {
+                Ok::<_, hyper::Error>(service_fn(move |req: Request<Body>| {
+                    let captured = captured.clone();
+                    async move {
+                        let query = req.uri().query().unwrap_or_default().to_string();
+                        let body = hyper::body::to_bytes(req.into_body()).await?;
+                        captured
+                            .lock()
+                            .push((query, String::from_utf8_lossy(&body).into_owned()));
+                        Ok::<_, hyper::Error>(Response::new(Body::empty()))
+                    }
+                }))
+            }
+        });
+        let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_svc);
+        let addr = server.local_addr();
+        tokio::spawn(server);
+
+        let config = ClickHouseConfig {
+            url: Some(format!("http://{}", addr)),
+            batch_size: 2,
+            flush_interval: Duration::from_secs(3600),
+            book_sample_interval: None,
+            ..Default::default()
+        };
+        let sink = Arc::new(AnalyticsSink::new(config).unwrap());
+        let near_miss = |market_id: &str| NearMissMessage {
+            timestamp_ms: 1_000,
+            market_id: market_id.to_string(),
+            yes_token_id: "yes".to_string(),
+            no_token_id: "no".to_string(),
+            yes_price: 0.49,
+            no_price: 0.5,
+            sum: 0.99,
+            edge: 0.002,
+            min_edge: 0.003,
+            size: 100.0,
+        };
+        for market_id in ["m1", "m2", "m3"] {
+            sink.record_near_miss(&near_miss(market_id));
+        }
+
+        let cancel = CancellationToken::new();
+        let task = tokio::spawn(
+            sink.clone()
+                .run(Arc::new(MarketData::new()), cancel.clone()),
+        );
+        // Two rows make a full batch; the third is flushed on shutdown
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        cancel.cancel();
+        task.await.unwrap();
+
+        let received = received.lock();
+        assert_eq!(received.len(), 2);
+        assert!(received[0].0.contains("near_misses"));
+        assert_eq!(received[0].1.lines().count(), 2);
+        assert!(received[1].1.contains("\"market_id\":\"m3\""));
+    }
+}
@@ -0,0 +1,116 @@
+//! Duplicate trade rows (`poly-rust --dedupe-trades`).
+//!
+//! Every trade row carries a `client_trade_id` generated once per execution,
+//! and inserts are `ON CONFLICT (client_trade_id) DO NOTHING`, so a retried
+//! insert cannot record the same execution twice. The key and its unique
+//! index are added on connect. Rows written before the key existed have
+//! none; this command removes their duplicates (same trade fields, recorded
+//! within `DUPLICATE_WINDOW_SECS` of each other, keeping the earliest).
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use tracing::info;
+
+use super::TradeRepository;
+
+/// Rows this close together with identical fields are one trade
+const DUPLICATE_WINDOW_SECS: f64 = 60.0;
+
+/// Bring databases created before `client_trade_id` up to date (run by
+/// `TradeRepository::new`).
+pub(super) const MIGRATE_SQL: [&str; 4] = [
+    "ALTER TABLE trades ADD COLUMN IF NOT EXISTS client_trade_id VARCHAR(64)",
+    "ALTER TABLE arb_trades ADD COLUMN IF NOT EXISTS client_trade_id VARCHAR(64)",
+    "CREATE UNIQUE INDEX IF NOT EXISTS idx_trades_client_trade_id ON trades(client_trade_id)",
+    "CREATE UNIQUE INDEX IF NOT EXISTS idx_arb_trades_client_trade_id \
+     ON arb_trades(client_trade_id)",
+];
+
+/// Delete later copies of single-leg trades without a client trade ID.
+const DEDUPE_TRADES_SQL: &str = r#"
+    DELETE FROM trades a
+    USING trades b
+    WHERE a.client_trade_id IS NULL
+      AND b.client_trade_id IS NULL
+      AND a.token_id = b.token_id
+      AND a.side = b.side
+      AND a.price = b.price
+      AND a.size = b.size
+      AND a.status = b.status
+      AND a.strategy = b.strategy
+      AND a.is_paper = b.is_paper
+      AND a.order_id IS NOT DISTINCT FROM b.order_id
+      AND a.created_at <= b.created_at + make_interval(secs => $1)
+      AND (a.created_at, a.id) > (b.created_at, b.id)
+"#;
+
+/// Delete later copies of arbitrage pairs without a client trade ID.
+const DEDUPE_ARB_TRADES_SQL: &str = r#"
+    DELETE FROM arb_trades a
+    USING arb_trades b
+    WHERE a.client_trade_id IS NULL
+      AND b.client_trade_id IS NULL
+      AND a.market_id = b.market_id
+      AND a.yes_token_id = b.yes_token_id
+      AND a.no_token_id = b.no_token_id
+      AND a.yes_price = b.yes_price
+      AND a.no_price = b.no_price
+      AND a.size = b.size
+      AND a.status = b.status
+      AND a.strategy = b.strategy
+      AND a.is_paper = b.is_paper
+      AND a.yes_order_id IS NOT DISTINCT FROM b.yes_order_id
+      AND a.no_order_id IS NOT DISTINCT FROM b.no_order_id
+      AND a.created_at <= b.created_at + make_interval(secs => $1)
+      AND (a.created_at, a.id) > (b.created_at, b.id)
+"#;
+
+/// Rows removed by a cleanup
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct DedupeReport {
+    pub trades: u64,
+    pub arb_trades: u64,
+}
+
+/// True if `--dedupe-trades` was passed on the command line.
+pub fn requested() -> bool {
+    std::env::args().skip(1).any(|arg| arg == "--dedupe-trades")
+}
+
+impl TradeRepository {
+    /// Remove duplicate rows recorded before client trade IDs, in one
+    /// transaction.
+    pub async fn dedupe_trades(&self) -> Result<DedupeReport> {
+        let Some(pool) = &self.pool else {
+            anyhow::bail!("database disabled (DATABASE_URL not set)");
+        };
+
+        let mut tx = pool.begin().await?;
+        let trades = sqlx::query(DEDUPE_TRADES_SQL)
+            .bind(DUPLICATE_WINDOW_SECS)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to dedupe trades")?
+            .rows_affected();
+        let arb_trades = sqlx::query(DEDUPE_ARB_TRADES_SQL)
+            .bind(DUPLICATE_WINDOW_SECS)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to dedupe arb_trades")?
+            .rows_affected();
+        tx.commit().await?;
+
+        Ok(DedupeReport { trades, arb_trades })
+    }
+}
+
+/// Run the cleanup against `DATABASE_URL` and log what was removed.
+pub async fn run(database_url: Option<&str>) -> Result<()> {
+    let repo = TradeRepository::new(database_url).await?;
+    let report = repo.dedupe_trades().await?;
+    info!(
+        "[DB] Removed {} duplicate trades and {} duplicate arb trades",
+        report.trades, report.arb_trades
+    );
+    Ok(())
+}
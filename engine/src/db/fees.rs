@@ -0,0 +1,419 @@
+//! Fee reconciliation against exchange statements.
+//!
+//! Trades are stored with the fee the cost model expected. An exported
+//! Polymarket fee/activity statement (CSV, or the JSON of an API response)
+//! is imported through `POST /control/fees/import`: each order's actual fee
+//! is written next to the estimate and net P&L is corrected. Single-leg
+//! trades book the fee against `realized_pnl`; arbitrage pairs recompute
+//! `net_profit` once per leg. Re-importing a statement is idempotent.
+//!
+//! `GET /control/fee-errors` then reports estimated vs. actual fees per
+//! market, with the fee rate actually charged, to calibrate the `CostModel`.
+
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use super::attribution::window_start;
+use super::TradeRepository;
+use crate::metrics::FEE_STATEMENT_ORDERS;
+
+/// Column names accepted for the order ID, normalized (see `normalize`)
+const ORDER_COLUMNS: [&str; 4] = ["orderid", "order", "id", "orderhash"];
+
+/// Column names accepted for the fee charged, normalized
+const FEE_COLUMNS: [&str; 5] = ["fee", "fees", "feeamount", "feepaid", "feeusdc"];
+
+/// Spread an order's fee over its filled rows, by notional.
+const RECONCILE_TRADES_SQL: &str = r#"
+    UPDATE trades t
+    SET realized_pnl = COALESCE(t.realized_pnl, 0) + COALESCE(t.actual_fee, 0) - f.fee,
+        actual_fee = f.fee,
+        fee_reconciled_at = NOW()
+    FROM (
+        SELECT id,
+               COALESCE(
+                   $2 * (price * size) / NULLIF(SUM(price * size) OVER (), 0),
+                   $2 / COUNT(*) OVER ()
+               ) AS fee
+        FROM trades
+        WHERE order_id = $1 AND status = 'FILLED'
+    ) f
+    WHERE t.id = f.id
+"#;
+
+/// Book the fee of an arbitrage leg. Until the other leg is reconciled it
+/// keeps half the estimated fee; `fees - estimated_fee` is slippage.
+fn reconcile_arb_sql(leg: &str, other: &str) -> String {
+    format!(
+        r#"
+        UPDATE arb_trades
+        SET {leg}_actual_fee = $2,
+            net_profit = gross_profit
+                - (fees - COALESCE(estimated_fee, fees))
+                - $2
+                - COALESCE({other}_actual_fee, COALESCE(estimated_fee, fees) / 2),
+            fee_reconciled_at = NOW()
+        WHERE {leg}_order_id = $1 AND status = 'FILLED'
+        "#
+    )
+}
+
+/// Estimated vs. actual fees of reconciled trades, per market.
+const FEE_ERRORS_SQL: &str = r#"
+    SELECT market_id,
+           COUNT(*)::BIGINT,
+           SUM(notional)::DOUBLE PRECISION,
+           SUM(estimated)::DOUBLE PRECISION,
+           SUM(actual)::DOUBLE PRECISION
+    FROM (
+        SELECT COALESCE(market_id, '') AS market_id,
+               price * size AS notional,
+               COALESCE(estimated_fee, 0) AS estimated,
+               actual_fee AS actual
+        FROM trades
+        WHERE actual_fee IS NOT NULL
+          AND DATE(created_at AT TIME ZONE $2) >= $1
+        UNION ALL
+        SELECT market_id,
+               total_cost,
+               COALESCE(estimated_fee, fees),
+               yes_actual_fee + no_actual_fee
+        FROM arb_trades
+        WHERE yes_actual_fee IS NOT NULL AND no_actual_fee IS NOT NULL
+          AND DATE(created_at AT TIME ZONE $2) >= $1
+    ) reconciled
+    GROUP BY market_id
+    ORDER BY ABS(SUM(actual) - SUM(estimated)) DESC
+"#;
+
+/// Total fee charged on one order, from a statement
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatementFee {
+    pub order_id: String,
+    pub fee: f64,
+}
+
+/// Outcome of importing a statement
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FeeReconciliation {
+    /// Orders in the statement
+    pub orders: usize,
+    /// Orders matched to a recorded trade
+    pub matched: usize,
+    /// Orders with no recorded trade (other instances, manual trades, ...)
+    pub unmatched: Vec<String>,
+    /// Actual fees of the matched orders
+    pub fees: f64,
+}
+
+/// Estimated vs. actual fees for one market (or the total)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FeeErrorRow {
+    /// Empty for trades without a known market
+    pub market_id: String,
+    pub trades: i64,
+    pub notional: f64,
+    pub estimated: f64,
+    pub actual: f64,
+    /// `actual - estimated` (positive: we under-estimate fees)
+    pub error: f64,
+    /// `error` relative to the estimate
+    pub error_pct: Option<f64>,
+    /// Fee rate actually charged, comparable to `COST_TAKER_FEE_RATE`
+    pub actual_rate: Option<f64>,
+}
+
+impl FeeErrorRow {
+    fn new(market_id: String, trades: i64, notional: f64, estimated: f64, actual: f64) -> Self {
+        let error = actual - estimated;
+        Self {
+            market_id,
+            trades,
+            notional,
+            estimated,
+            actual,
+            error,
+            error_pct: (estimated > 0.0).then(|| error / estimated),
+            actual_rate: (notional > 0.0).then(|| actual / notional),
+        }
+    }
+
+    /// Sum of all rows, with `market_id` "total".
+    pub fn total(rows: &[FeeErrorRow]) -> Self {
+        Self::new(
+            "total".to_string(),
+            rows.iter().map(|r| r.trades).sum(),
+            rows.iter().map(|r| r.notional).sum(),
+            rows.iter().map(|r| r.estimated).sum(),
+            rows.iter().map(|r| r.actual).sum(),
+        )
+    }
+}
+
+/// Parse a fee statement: CSV with a header row, or JSON (an array of
+/// objects, or an object with the array under `data`). Column names are
+/// matched loosely (`Order ID`, `orderId`, `fee_amount`, ...). Rows for the
+/// same order (one per fill) are summed.
+pub fn parse_statement(body: &str) -> Result<Vec<StatementFee>, String> {
+    let body = body.trim_start_matches('\u{feff}').trim();
+    let rows = if body.starts_with('[') || body.starts_with('{') {
+        parse_json(body)?
+    } else {
+        parse_csv(body)?
+    };
+
+    let mut fees: HashMap<String, f64> = HashMap::new();
+    for (order_id, fee) in rows {
+        *fees.entry(order_id).or_default() += fee;
+    }
+    let mut fees: Vec<StatementFee> = fees
+        .into_iter()
+        .map(|(order_id, fee)| StatementFee { order_id, fee })
+        .collect();
+    fees.sort_by(|a, b| a.order_id.cmp(&b.order_id));
+    Ok(fees)
+}
+
+/// Lowercase and drop everything but letters and digits
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Parse a fee amount such as `0.12`, `$0.12`, or `-0.12` (charged)
+fn parse_fee(value: &str) -> Option<f64> {
+    let value = value.trim().replace(['$', ','], "");
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|v| v.is_finite())
+        .map(f64::abs)
+}
+
+fn parse_csv(body: &str) -> Result<Vec<(String, f64)>, String> {
+    let mut lines = body.lines().filter(|l| !l.trim().is_empty());
+    let header: Vec<String> = split_csv_line(lines.next().ok_or("empty statement")?)
+        .iter()
+        .map(|h| normalize(h))
+        .collect();
+    let column = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|name| header.iter().position(|h| h == name))
+    };
+    let order_col = column(&ORDER_COLUMNS).ok_or("no order id column")?;
+    let fee_col = column(&FEE_COLUMNS).ok_or("no fee column")?;
+
+    let mut rows = Vec::new();
+    for (n, line) in lines.enumerate() {
+        let fields = split_csv_line(line);
+        let order_id = fields.get(order_col).map(|s| s.trim()).unwrap_or_default();
+        if order_id.is_empty() {
+            continue;
+        }
+        let fee = fields
+            .get(fee_col)
+            .and_then(|f| parse_fee(f))
+            .ok_or_else(|| format!("row {}: invalid fee", n + 2))?;
+        rows.push((order_id.to_string(), fee));
+    }
+    Ok(rows)
+}
+
+/// Split one CSV line, honouring double-quoted fields with `""` escapes
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', _) => quoted = !quoted,
+            (',', false) => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+fn parse_json(body: &str) -> Result<Vec<(String, f64)>, String> {
+    let value: serde_json::Value =
+        serde_json::from_str(body).map_err(|e| format!("invalid JSON: {}", e))?;
+    let items = match &value {
+        serde_json::Value::Array(items) => items,
+        serde_json::Value::Object(map) => map
+            .get("data")
+            .and_then(|d| d.as_array())
+            .ok_or("expected an array of rows")?,
+        _ => return Err("expected an array of rows".to_string()),
+    };
+
+    let mut rows = Vec::new();
+    for (n, item) in items.iter().enumerate() {
+        let Some(object) = item.as_object() else {
+            return Err(format!("row {}: not an object", n + 1));
+        };
+        let field = |names: &[&str]| {
+            names.iter().find_map(|name| {
+                object
+                    .iter()
+                    .find(|(key, _)| normalize(key) == *name)
+                    .map(|(_, v)| v)
+            })
+        };
+        let Some(order_id) = field(&ORDER_COLUMNS).and_then(|v| match v {
+            serde_json::Value::String(s) => Some(s.clone()),
+            serde_json::Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        }) else {
+            continue;
+        };
+        let fee = field(&FEE_COLUMNS)
+            .and_then(|v| match v {
+                serde_json::Value::Number(n) => n.as_f64().map(f64::abs),
+                serde_json::Value::String(s) => parse_fee(s),
+                _ => None,
+            })
+            .ok_or_else(|| format!("row {}: invalid fee", n + 1))?;
+        rows.push((order_id, fee));
+    }
+    Ok(rows)
+}
+
+impl TradeRepository {
+    /// Write the actual fees from a statement onto the matching trades and
+    /// correct their net P&L, in one transaction.
+    pub(super) async fn apply_statement_fees(
+        &self,
+        fees: &[StatementFee],
+    ) -> Result<FeeReconciliation> {
+        let Some(pool) = &self.pool else {
+            return Ok(FeeReconciliation::default());
+        };
+
+        let yes_sql = reconcile_arb_sql("yes", "no");
+        let no_sql = reconcile_arb_sql("no", "yes");
+        let mut report = FeeReconciliation {
+            orders: fees.len(),
+            ..FeeReconciliation::default()
+        };
+
+        let mut tx = pool.begin().await?;
+        for statement in fees {
+            let mut updated = 0;
+            for sql in [RECONCILE_TRADES_SQL, yes_sql.as_str(), no_sql.as_str()] {
+                updated += sqlx::query(sql)
+                    .bind(&statement.order_id)
+                    .bind(statement.fee)
+                    .execute(&mut *tx)
+                    .await
+                    .with_context(|| format!("Failed to reconcile order {}", statement.order_id))?
+                    .rows_affected();
+            }
+            if updated > 0 {
+                report.matched += 1;
+                report.fees += statement.fee;
+            } else {
+                report.unmatched.push(statement.order_id.clone());
+            }
+        }
+        tx.commit().await?;
+
+        FEE_STATEMENT_ORDERS
+            .with_label_values(&["matched"])
+            .inc_by(report.matched as f64);
+        FEE_STATEMENT_ORDERS
+            .with_label_values(&["unmatched"])
+            .inc_by(report.unmatched.len() as f64);
+        Ok(report)
+    }
+
+    /// Estimated vs. actual fees of trades reconciled in the last `days`
+    /// days, per market, largest error first.
+    pub(super) async fn query_fee_errors(&self, days: i32) -> Result<Vec<FeeErrorRow>> {
+        let Some(pool) = &self.pool else {
+            return Ok(Vec::new());
+        };
+
+        let rows: Vec<(String, i64, f64, f64, f64)> = sqlx::query_as(FEE_ERRORS_SQL)
+            .bind(window_start(days, self.today()))
+            .bind(self.timezone.name())
+            .fetch_all(pool)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(market_id, trades, notional, estimated, actual)| {
+                FeeErrorRow::new(market_id, trades, notional, estimated, actual)
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_csv_statement() {
+        let csv = "\u{feff}Date,Order ID,Market,\"Fee Amount\"\n\
+                   2026-10-01,0xabc,\"Will it rain, today?\",$0.10\n\
+                   2026-10-01,0xabc,\"Will it rain, today?\",-0.05\n\
+                   \n\
+                   2026-10-02,0xdef,Other,0.2\n";
+        let fees = parse_statement(csv).unwrap();
+        assert_eq!(fees.len(), 2);
+        assert_eq!(fees[0].order_id, "0xabc");
+        assert!((fees[0].fee - 0.15).abs() < 1e-12);
+        assert_eq!(
+            fees[1],
+            StatementFee {
+                order_id: "0xdef".into(),
+                fee: 0.2,
+            }
+        );
+
+        assert!(parse_statement("Order ID,Price\n0xabc,0.5").is_err());
+        assert!(parse_statement("Order ID,Fee\n0xabc,n/a").is_err());
+    }
+
+    #[test]
+    fn test_parse_json_statement() {
+        let json = r#"{"data": [
+            {"orderId": "0xabc", "feeAmount": "0.10"},
+            {"orderId": "0xdef", "fee": 0.2},
+            {"type": "REDEEM"}
+        ]}"#;
+        let fees = parse_statement(json).unwrap();
+        assert_eq!(fees.len(), 2);
+        assert_eq!(fees[0].order_id, "0xabc");
+        assert_eq!(fees[1].fee, 0.2);
+
+        assert!(parse_statement(r#"{"rows": []}"#).is_err());
+    }
+
+    #[test]
+    fn test_fee_error_totals() {
+        let rows = vec![
+            FeeErrorRow::new("m1".into(), 2, 100.0, 1.0, 1.5),
+            FeeErrorRow::new("m2".into(), 1, 100.0, 1.0, 0.5),
+        ];
+        assert_eq!(rows[0].error_pct, Some(0.5));
+        assert_eq!(rows[1].actual_rate, Some(0.005));
+
+        let total = FeeErrorRow::total(&rows);
+        assert_eq!(total.trades, 3);
+        assert_eq!(total.error, 0.0);
+        assert_eq!(total.actual_rate, Some(0.01));
+        assert_eq!(FeeErrorRow::total(&[]).actual_rate, None);
+    }
+}
@@ -3,6 +3,26 @@
 //! All write operations are fire-and-forget (non-blocking) to ensure
 //! the trading loop is never delayed by database I/O.
 
+mod attribution;
+mod backfill;
+mod clickhouse;
+mod dedupe;
+mod fees;
+mod order_tags;
+mod pnl_history;
 mod repository;
+mod store;
 
-pub use repository::{ArbTrade, Trade, TradeRepository};
+#[allow(unused_imports)]
+pub use attribution::{AttributionDimension, AttributionRow};
+#[allow(unused_imports)]
+pub use backfill::{run as run_backfill, BackfillRange, BackfillReport};
+#[allow(unused_imports)]
+pub use clickhouse::{AnalyticsSink, ClickHouseConfig};
+#[allow(unused_imports)]
+pub use fees::{parse_statement, FeeErrorRow, FeeReconciliation, StatementFee};
+#[allow(unused_imports)]
+pub use dedupe::{requested as dedupe_requested, run as run_dedupe, DedupeReport};
+pub use order_tags::OrderTag;
+pub use repository::{new_client_trade_id, ArbTrade, Trade, TradeRepository};
+pub use store::{run_pnl_attribution, TradeStore};
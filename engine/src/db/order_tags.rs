@@ -0,0 +1,98 @@
+//! Order tags: which strategy and signal placed each order.
+//!
+//! The CLOB order API has no client order ID or free-form metadata field,
+//! so attribution is kept on our side. Every order the engine places is
+//! recorded in `order_tags` with the strategy and the ID the engine gave
+//! the signal (`SIGNAL_ID_KEY` in the signal metadata). Fills pulled from
+//! the exchange later, by the trade backfill, are attributed back to the
+//! strategy through their order ID.
+
+use anyhow::Result;
+use tracing::warn;
+
+use crate::runtime::{self, TaskGroup};
+
+use super::TradeRepository;
+
+/// Creates the table in databases set up before it
+pub(super) const CREATE_TABLE_SQL: &str = r#"
+    CREATE TABLE IF NOT EXISTS order_tags (
+        order_id VARCHAR(255) PRIMARY KEY,
+        created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        strategy VARCHAR(100) NOT NULL,
+        signal_id VARCHAR(64),
+        token_id VARCHAR(255) NOT NULL,
+        side VARCHAR(10) NOT NULL,
+        is_paper BOOLEAN NOT NULL DEFAULT false
+    )
+"#;
+
+/// First tag wins: an order ID is only ever placed once
+const INSERT_SQL: &str = r#"
+    INSERT INTO order_tags (order_id, strategy, signal_id, token_id, side, is_paper)
+    VALUES ($1, $2, $3, $4, $5, $6)
+    ON CONFLICT (order_id) DO NOTHING
+"#;
+
+/// Strategy and signal an order was placed for
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderTag {
+    pub order_id: String,
+    pub strategy: String,
+    /// ID of the signal behind the order (None for orders the engine
+    /// placed outside a signal)
+    pub signal_id: Option<String>,
+    pub token_id: String,
+    pub side: String, // "BUY" or "SELL"
+    pub is_paper: bool,
+}
+
+impl TradeRepository {
+    /// Record a tag (fire-and-forget, non-blocking)
+    pub(super) fn spawn_insert_order_tag(&self, tag: OrderTag) {
+        let Some(pool) = self.pool.clone() else {
+            return;
+        };
+        runtime::spawn(TaskGroup::DbWrite, async move {
+            let result = sqlx::query(INSERT_SQL)
+                .bind(&tag.order_id)
+                .bind(&tag.strategy)
+                .bind(&tag.signal_id)
+                .bind(&tag.token_id)
+                .bind(&tag.side)
+                .bind(tag.is_paper)
+                .execute(&pool)
+                .await;
+            if let Err(e) = result {
+                warn!("[DB] Failed to tag order {}: {}", tag.order_id, e);
+            }
+        });
+    }
+
+    /// Tags of `order_ids` (orders without a tag are left out)
+    pub async fn order_tags(&self, order_ids: &[String]) -> Result<Vec<OrderTag>> {
+        let Some(pool) = &self.pool else {
+            return Ok(Vec::new());
+        };
+        let rows: Vec<(String, String, Option<String>, String, String, bool)> = sqlx::query_as(
+            "SELECT order_id, strategy, signal_id, token_id, side, is_paper \
+             FROM order_tags WHERE order_id = ANY($1)",
+        )
+        .bind(order_ids)
+        .fetch_all(pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(
+                |(order_id, strategy, signal_id, token_id, side, is_paper)| OrderTag {
+                    order_id,
+                    strategy,
+                    signal_id,
+                    token_id,
+                    side,
+                    is_paper,
+                },
+            )
+            .collect())
+    }
+}
@@ -0,0 +1,74 @@
+//! Hourly P&L history for risk analytics (see `risk::var`).
+//!
+//! An hour's P&L is the P&L realized by trades filled in it (sells and
+//! arbitrage pairs) plus the change in the mark-to-market value of open
+//! positions, taken from the last equity curve sample of the hour against
+//! the last sample of the previous sampled hour.
+
+use anyhow::{Context, Result};
+
+use super::TradeRepository;
+use crate::risk::HourlyPnl;
+
+/// Realized and mark-to-market P&L per UTC hour over the last `$1` days.
+const PNL_HISTORY_SQL: &str = r#"
+    WITH realized AS (
+        SELECT DATE_TRUNC('hour', created_at) AS hour, COALESCE(realized_pnl, 0) AS pnl
+        FROM trades
+        WHERE status = 'FILLED'
+          AND is_paper = $2
+          AND created_at >= NOW() - make_interval(days => $1)
+        UNION ALL
+        SELECT DATE_TRUNC('hour', created_at), net_profit
+        FROM arb_trades
+        WHERE status = 'FILLED'
+          AND is_paper = $2
+          AND created_at >= NOW() - make_interval(days => $1)
+    ),
+    marks AS (
+        SELECT DISTINCT ON (DATE_TRUNC('hour', sampled_at))
+               DATE_TRUNC('hour', sampled_at) AS hour, unrealized_pnl
+        FROM equity_curve
+        WHERE is_paper = $2
+          AND sampled_at >= NOW() - make_interval(days => $1)
+        ORDER BY DATE_TRUNC('hour', sampled_at), sampled_at DESC
+    ),
+    unrealized AS (
+        SELECT hour, unrealized_pnl - LAG(unrealized_pnl) OVER (ORDER BY hour) AS pnl
+        FROM marks
+    )
+    SELECT EXTRACT(EPOCH FROM hour)::BIGINT, SUM(pnl)::DOUBLE PRECISION
+    FROM (
+        SELECT hour, pnl FROM realized
+        UNION ALL
+        SELECT hour, pnl FROM unrealized WHERE pnl IS NOT NULL
+    ) hourly
+    GROUP BY hour
+    ORDER BY hour
+"#;
+
+impl TradeRepository {
+    /// P&L per UTC hour over the last `days` days, oldest first. Hours with
+    /// no trades and no equity samples are left out.
+    pub(super) async fn query_pnl_history(
+        &self,
+        days: i32,
+        is_paper: bool,
+    ) -> Result<Vec<HourlyPnl>> {
+        let Some(pool) = &self.pool else {
+            return Ok(Vec::new());
+        };
+
+        let rows: Vec<(i64, f64)> = sqlx::query_as(PNL_HISTORY_SQL)
+            .bind(days)
+            .bind(is_paper)
+            .fetch_all(pool)
+            .await
+            .context("Failed to query P&L history")?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(hour_start, pnl)| HourlyPnl { hour_start, pnl })
+            .collect())
+    }
+}
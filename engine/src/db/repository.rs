@@ -5,14 +5,51 @@
 //! delayed by database I/O.
 
 use anyhow::Result;
-use sqlx::postgres::{PgPool, PgPoolOptions};
+use async_trait::async_trait;
+use serde_json::Value;
+use sqlx::postgres::{PgArguments, PgPool, PgPoolOptions};
+use sqlx::query::Query;
+use sqlx::Postgres;
 use std::sync::Arc;
 use std::time::Duration;
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
+
+use crate::audit::AuditEvent;
+use crate::connectivity::{ConnectivitySupervisor, Subsystem};
+use crate::redis::SignalMessage;
+use crate::risk::{EquitySample, HourlyPnl};
+use crate::runtime::{self, TaskGroup};
+use crate::timezone::TradingTimezone;
+
+use super::attribution::{AttributionDimension, AttributionRow};
+use super::dedupe::MIGRATE_SQL;
+use super::fees::{FeeErrorRow, FeeReconciliation, StatementFee};
+use super::order_tags::{self, OrderTag};
+use super::store::TradeStore;
+
+/// Attempts per trade insert; repeating one is safe (see `client_trade_id`)
+const INSERT_ATTEMPTS: u32 = 3;
+
+/// Signal metadata columns, added on connect to databases created before them
+const ADD_METADATA_COLUMNS: [&str; 3] = [
+    "ALTER TABLE trades ADD COLUMN IF NOT EXISTS metadata JSONB",
+    "ALTER TABLE arb_trades ADD COLUMN IF NOT EXISTS metadata JSONB",
+    "ALTER TABLE signals ADD COLUMN IF NOT EXISTS metadata JSONB",
+];
+
+/// Wait before the first retry, doubled for each further one
+const RETRY_DELAY: Duration = Duration::from_millis(250);
+
+/// A fresh idempotency key for the rows of one execution
+pub fn new_client_trade_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
 
 /// A trade record for the database
 #[derive(Debug, Clone)]
 pub struct Trade {
+    /// Unique per execution; a second insert with the same ID is dropped
+    pub client_trade_id: String,
     pub token_id: String,
     pub side: String, // "BUY" or "SELL"
     pub price: f64,
@@ -22,11 +59,21 @@ pub struct Trade {
     pub strategy: String,
     pub signal_reason: Option<String>,
     pub is_paper: bool,
+    pub market_id: Option<String>,
+    pub category: Option<String>,
+    /// P&L realized by this trade (sells)
+    pub realized_pnl: Option<f64>,
+    /// Fee the cost model expected (filled trades)
+    pub estimated_fee: Option<f64>,
+    /// Metadata of the signal behind the trade (JSON object)
+    pub metadata: Option<Value>,
 }
 
 /// An arbitrage trade record for the database
 #[derive(Debug, Clone)]
 pub struct ArbTrade {
+    /// Unique per execution; a second insert with the same ID is dropped
+    pub client_trade_id: String,
     pub market_id: String,
     pub yes_token_id: String,
     pub no_token_id: String,
@@ -42,13 +89,22 @@ pub struct ArbTrade {
     pub status: String,
     pub strategy: String,
     pub is_paper: bool,
+    pub category: Option<String>,
+    /// Fee part of `fees` (the rest is estimated slippage)
+    pub estimated_fee: f64,
+    /// Metadata of the signal behind the trade (JSON object)
+    pub metadata: Option<Value>,
 }
 
 /// Async PostgreSQL trade repository.
 /// All write operations are fire-and-forget to avoid blocking the trading loop.
 pub struct TradeRepository {
-    pool: Option<PgPool>,
+    pub(super) pool: Option<PgPool>,
     enabled: bool,
+    /// Days and hours are bucketed in this timezone
+    pub(super) timezone: TradingTimezone,
+    /// Told when trade writes stop and start reaching the database
+    connectivity: Option<Arc<ConnectivitySupervisor>>,
 }
 
 impl TradeRepository {
@@ -64,9 +120,29 @@ impl TradeRepository {
                     .await?;
 
                 info!("[DB] Connected to PostgreSQL");
+                for statement in ADD_METADATA_COLUMNS {
+                    if let Err(e) = sqlx::query(statement).execute(&pool).await {
+                        warn!("[DB] Failed to add signal metadata column: {}", e);
+                    }
+                }
+                // Rows from before the key are NULL, which the unique index
+                // allows any number of
+                for statement in MIGRATE_SQL {
+                    if let Err(e) = sqlx::query(statement).execute(&pool).await {
+                        warn!("[DB] Failed to add client trade ID column: {}", e);
+                    }
+                }
+                if let Err(e) = sqlx::query(order_tags::CREATE_TABLE_SQL)
+                    .execute(&pool)
+                    .await
+                {
+                    warn!("[DB] Failed to create order tags table: {}", e);
+                }
                 Ok(Self {
                     pool: Some(pool),
                     enabled: true,
+                    timezone: TradingTimezone::default(),
+                    connectivity: None,
                 })
             }
             None => {
@@ -74,6 +150,8 @@ impl TradeRepository {
                 Ok(Self {
                     pool: None,
                     enabled: false,
+                    timezone: TradingTimezone::default(),
+                    connectivity: None,
                 })
             }
         }
@@ -85,16 +163,89 @@ impl TradeRepository {
         Self {
             pool: None,
             enabled: false,
+            timezone: TradingTimezone::default(),
+            connectivity: None,
         }
     }
 
+    /// Set the timezone daily P&L, attribution days and fee error windows
+    /// are bucketed in (default UTC).
+    pub fn set_timezone(&mut self, timezone: TradingTimezone) {
+        self.timezone = timezone;
+    }
+
+    /// Report whether trade writes get through to the connectivity
+    /// supervisor. The pool reconnects on its own.
+    pub fn set_connectivity(&mut self, connectivity: Arc<ConnectivitySupervisor>) {
+        self.connectivity = Some(connectivity);
+    }
+
+    /// Current trading day
+    pub(super) fn today(&self) -> chrono::NaiveDate {
+        self.timezone.date(chrono::Utc::now())
+    }
+}
+
+/// Run an insert keyed by `client_trade_id`, retrying failures. A retry
+/// after an insert that did commit (e.g. the connection dropped before the
+/// reply) hits the key and writes nothing.
+async fn execute_idempotent<'q>(
+    pool: &PgPool,
+    query: impl Fn() -> Query<'q, Postgres, PgArguments>,
+    client_trade_id: &str,
+) -> Result<(), sqlx::Error> {
+    let mut delay = RETRY_DELAY;
+    let mut attempt = 1;
+    loop {
+        match query().execute(pool).await {
+            Ok(result) => {
+                if result.rows_affected() == 0 {
+                    debug!("[DB] Trade {} already recorded", client_trade_id);
+                }
+                return Ok(());
+            }
+            Err(e) if attempt < INSERT_ATTEMPTS => {
+                debug!("[DB] Insert attempt {} failed, retrying: {}", attempt, e);
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Tell the connectivity supervisor whether a write reached the database
+/// (a rejected query is not connection trouble and is not reported).
+fn report_connection(
+    connectivity: Option<&ConnectivitySupervisor>,
+    result: &Result<(), sqlx::Error>,
+) {
+    let Some(connectivity) = connectivity else {
+        return;
+    };
+    match result {
+        Ok(()) => connectivity.report_up(Subsystem::Db),
+        Err(
+            e @ (sqlx::Error::Io(_)
+            | sqlx::Error::Tls(_)
+            | sqlx::Error::PoolTimedOut
+            | sqlx::Error::PoolClosed),
+        ) => connectivity.report_down(Subsystem::Db, e),
+        Err(_) => {}
+    }
+}
+
+/// Postgres backend: writes spawn a query and return immediately.
+#[async_trait]
+impl TradeStore for TradeRepository {
     /// Check if database is enabled
-    pub fn is_enabled(&self) -> bool {
+    fn is_enabled(&self) -> bool {
         self.enabled
     }
 
     /// Insert a trade (fire-and-forget, non-blocking)
-    pub fn insert_trade(&self, trade: Trade) {
+    fn insert_trade(&self, trade: Trade) {
         if !self.enabled {
             return;
         }
@@ -104,34 +255,144 @@ impl TradeRepository {
             None => return,
         };
 
+        let connectivity = self.connectivity.clone();
+
         // Fire-and-forget: spawn task and return immediately
-        tokio::spawn(async move {
+        runtime::spawn(TaskGroup::DbWrite, async move {
+            let query = || {
+                sqlx::query(
+                    r#"
+                    INSERT INTO trades (
+                        client_trade_id, token_id, side, price, size, order_id, status,
+                        strategy, signal_reason, is_paper, market_id, category, realized_pnl,
+                        estimated_fee, metadata
+                    )
+                    VALUES (
+                        $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15::JSONB
+                    )
+                    ON CONFLICT (client_trade_id) DO NOTHING
+                    "#,
+                )
+                .bind(&trade.client_trade_id)
+                .bind(&trade.token_id)
+                .bind(&trade.side)
+                .bind(trade.price)
+                .bind(trade.size)
+                .bind(&trade.order_id)
+                .bind(&trade.status)
+                .bind(&trade.strategy)
+                .bind(&trade.signal_reason)
+                .bind(trade.is_paper)
+                .bind(&trade.market_id)
+                .bind(&trade.category)
+                .bind(trade.realized_pnl)
+                .bind(trade.estimated_fee)
+                .bind(trade.metadata.as_ref().map(|m| m.to_string()))
+            };
+
+            let result = execute_idempotent(&pool, query, &trade.client_trade_id).await;
+            report_connection(connectivity.as_deref(), &result);
+            if let Err(e) = result {
+                warn!("[DB] Failed to insert trade: {}", e);
+            }
+        });
+    }
+
+    /// Insert an arbitrage trade (fire-and-forget, non-blocking)
+    fn insert_arb_trade(&self, trade: ArbTrade) {
+        if !self.enabled {
+            return;
+        }
+
+        let pool = match &self.pool {
+            Some(p) => p.clone(),
+            None => return,
+        };
+
+        let connectivity = self.connectivity.clone();
+
+        // Fire-and-forget: spawn task and return immediately
+        runtime::spawn(TaskGroup::DbWrite, async move {
+            let query = || {
+                sqlx::query(
+                    r#"
+                    INSERT INTO arb_trades (
+                        client_trade_id, market_id, yes_token_id, no_token_id, yes_price,
+                        no_price, size, total_cost, fees, gross_profit, net_profit,
+                        yes_order_id, no_order_id, status, strategy, is_paper, category,
+                        estimated_fee, metadata
+                    )
+                    VALUES (
+                        $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16,
+                        $17, $18, $19::JSONB
+                    )
+                    ON CONFLICT (client_trade_id) DO NOTHING
+                    "#,
+                )
+                .bind(&trade.client_trade_id)
+                .bind(&trade.market_id)
+                .bind(&trade.yes_token_id)
+                .bind(&trade.no_token_id)
+                .bind(trade.yes_price)
+                .bind(trade.no_price)
+                .bind(trade.size)
+                .bind(trade.total_cost)
+                .bind(trade.fees)
+                .bind(trade.gross_profit)
+                .bind(trade.net_profit)
+                .bind(&trade.yes_order_id)
+                .bind(&trade.no_order_id)
+                .bind(&trade.status)
+                .bind(&trade.strategy)
+                .bind(trade.is_paper)
+                .bind(&trade.category)
+                .bind(trade.estimated_fee)
+                .bind(trade.metadata.as_ref().map(|m| m.to_string()))
+            };
+
+            let result = execute_idempotent(&pool, query, &trade.client_trade_id).await;
+            report_connection(connectivity.as_deref(), &result);
+            if let Err(e) = result {
+                warn!("[DB] Failed to insert arb trade: {}", e);
+            }
+        });
+    }
+
+    /// Append an audit event (fire-and-forget, non-blocking)
+    fn insert_audit_event(&self, event: AuditEvent) {
+        if !self.enabled {
+            return;
+        }
+
+        let pool = match &self.pool {
+            Some(p) => p.clone(),
+            None => return,
+        };
+
+        // Fire-and-forget: spawn task and return immediately
+        runtime::spawn(TaskGroup::DbWrite, async move {
             let result = sqlx::query(
                 r#"
-                INSERT INTO trades (token_id, side, price, size, order_id, status, strategy, signal_reason, is_paper)
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
-                "#
+                INSERT INTO audit_log (event_time, actor, action, target, details)
+                VALUES (TO_TIMESTAMP($1::DOUBLE PRECISION / 1000.0), $2, $3, $4, $5)
+                "#,
             )
-            .bind(&trade.token_id)
-            .bind(&trade.side)
-            .bind(trade.price)
-            .bind(trade.size)
-            .bind(&trade.order_id)
-            .bind(&trade.status)
-            .bind(&trade.strategy)
-            .bind(&trade.signal_reason)
-            .bind(trade.is_paper)
+            .bind(event.timestamp_ms as i64)
+            .bind(&event.actor)
+            .bind(event.action.as_str())
+            .bind(&event.target)
+            .bind(&event.details)
             .execute(&pool)
             .await;
 
             if let Err(e) = result {
-                warn!("[DB] Failed to insert trade: {}", e);
+                warn!("[DB] Failed to insert audit event: {}", e);
             }
         });
     }
 
-    /// Insert an arbitrage trade (fire-and-forget, non-blocking)
-    pub fn insert_arb_trade(&self, trade: ArbTrade) {
+    /// Append a point to the equity curve (fire-and-forget, non-blocking)
+    fn insert_equity_sample(&self, sample: EquitySample, is_paper: bool) {
         if !self.enabled {
             return;
         }
@@ -142,44 +403,97 @@ impl TradeRepository {
         };
 
         // Fire-and-forget: spawn task and return immediately
-        tokio::spawn(async move {
+        runtime::spawn(TaskGroup::DbWrite, async move {
             let result = sqlx::query(
                 r#"
-                INSERT INTO arb_trades (
-                    market_id, yes_token_id, no_token_id, yes_price, no_price, size,
-                    total_cost, fees, gross_profit, net_profit,
-                    yes_order_id, no_order_id, status, strategy, is_paper
+                INSERT INTO equity_curve (
+                    sampled_at, realized_pnl, unrealized_pnl, equity, drawdown, max_drawdown, is_paper
                 )
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+                VALUES (TO_TIMESTAMP($1::DOUBLE PRECISION / 1000.0), $2, $3, $4, $5, $6, $7)
                 "#,
             )
-            .bind(&trade.market_id)
-            .bind(&trade.yes_token_id)
-            .bind(&trade.no_token_id)
-            .bind(trade.yes_price)
-            .bind(trade.no_price)
-            .bind(trade.size)
-            .bind(trade.total_cost)
-            .bind(trade.fees)
-            .bind(trade.gross_profit)
-            .bind(trade.net_profit)
-            .bind(&trade.yes_order_id)
-            .bind(&trade.no_order_id)
-            .bind(&trade.status)
-            .bind(&trade.strategy)
-            .bind(trade.is_paper)
+            .bind(sample.timestamp_ms as i64)
+            .bind(sample.realized_pnl)
+            .bind(sample.unrealized_pnl)
+            .bind(sample.equity)
+            .bind(sample.drawdown)
+            .bind(sample.max_drawdown)
+            .bind(is_paper)
             .execute(&pool)
             .await;
 
             if let Err(e) = result {
-                warn!("[DB] Failed to insert arb trade: {}", e);
+                warn!("[DB] Failed to insert equity sample: {}", e);
+            }
+        });
+    }
+
+    /// Record a strategy signal and what became of it (fire-and-forget, non-blocking)
+    fn insert_signal(
+        &self,
+        signal: SignalMessage,
+        action_taken: &'static str,
+        rejection_reason: Option<String>,
+    ) {
+        if !self.enabled {
+            return;
+        }
+
+        let pool = match &self.pool {
+            Some(p) => p.clone(),
+            None => return,
+        };
+
+        // Fire-and-forget: spawn task and return immediately
+        runtime::spawn(TaskGroup::DbWrite, async move {
+            let result = sqlx::query(
+                r#"
+                INSERT INTO signals (
+                    created_at, strategy, signal_type, token_id, yes_token_id, no_token_id,
+                    price, yes_price, no_price, size, edge, action_taken, rejection_reason,
+                    mode, reason, context, metadata
+                )
+                VALUES (
+                    TO_TIMESTAMP($1::DOUBLE PRECISION / 1000.0), $2, $3, $4, $5, $6,
+                    $7, $8, $9, $10, $11, $12, $13, $14, $15, $16::JSONB, $17::JSONB
+                )
+                "#,
+            )
+            .bind(signal.timestamp_ms as i64)
+            .bind(&signal.strategy)
+            .bind(&signal.signal_type)
+            .bind(&signal.token_id)
+            .bind(&signal.yes_token_id)
+            .bind(&signal.no_token_id)
+            .bind(signal.price)
+            .bind(signal.yes_price)
+            .bind(signal.no_price)
+            .bind(signal.size)
+            .bind(signal.edge)
+            .bind(action_taken)
+            .bind(&rejection_reason)
+            .bind(&signal.mode)
+            .bind(&signal.reason)
+            .bind(signal.context.as_ref().map(|c| c.to_string()))
+            .bind(signal.metadata.as_ref().map(|m| m.to_string()))
+            .execute(&pool)
+            .await;
+
+            if let Err(e) = result {
+                warn!("[DB] Failed to insert signal: {}", e);
             }
         });
     }
 
+    /// Tag an order with its strategy and signal (fire-and-forget, non-blocking)
+    fn insert_order_tag(&self, tag: OrderTag) {
+        if self.enabled {
+            self.spawn_insert_order_tag(tag);
+        }
+    }
+
     /// Get recent trade count (for health checks)
-    #[allow(dead_code)]
-    pub async fn recent_trade_count(&self, minutes: i32) -> Result<i64> {
+    async fn recent_trade_count(&self, minutes: i32) -> Result<i64> {
         if !self.enabled {
             return Ok(0);
         }
@@ -200,8 +514,7 @@ impl TradeRepository {
     }
 
     /// Get today's P&L from arbitrage trades
-    #[allow(dead_code)]
-    pub async fn today_pnl(&self) -> Result<f64> {
+    async fn today_pnl(&self) -> Result<f64> {
         if !self.enabled {
             return Ok(0.0);
         }
@@ -215,16 +528,43 @@ impl TradeRepository {
             r#"
             SELECT COALESCE(SUM(net_profit), 0)
             FROM arb_trades
-            WHERE DATE(created_at) = CURRENT_DATE
+            WHERE DATE(created_at AT TIME ZONE $1) = $2
               AND status = 'FILLED'
               AND is_paper = false
             "#,
         )
+        .bind(self.timezone.name())
+        .bind(self.today())
         .fetch_one(pool)
         .await?;
 
         Ok(result.0.unwrap_or(0.0))
     }
+
+    async fn refresh_pnl_attribution(&self, days: i32) -> Result<u64> {
+        self.refresh_attribution_table(days).await
+    }
+
+    async fn pnl_attribution(
+        &self,
+        days: i32,
+        dimensions: &[AttributionDimension],
+        is_paper: Option<bool>,
+    ) -> Result<Vec<AttributionRow>> {
+        self.query_attribution(days, dimensions, is_paper).await
+    }
+
+    async fn reconcile_fees(&self, fees: &[StatementFee]) -> Result<FeeReconciliation> {
+        self.apply_statement_fees(fees).await
+    }
+
+    async fn fee_estimate_errors(&self, days: i32) -> Result<Vec<FeeErrorRow>> {
+        self.query_fee_errors(days).await
+    }
+
+    async fn pnl_history(&self, days: i32, is_paper: bool) -> Result<Vec<HourlyPnl>> {
+        self.query_pnl_history(days, is_paper).await
+    }
 }
 
 /// Helper to create a repository from Arc for sharing
@@ -248,6 +588,7 @@ mod tests {
     #[test]
     fn test_trade_struct() {
         let trade = Trade {
+            client_trade_id: new_client_trade_id(),
             token_id: "abc123".to_string(),
             side: "BUY".to_string(),
             price: 0.45,
@@ -257,7 +598,13 @@ mod tests {
             strategy: "SumTo100".to_string(),
             signal_reason: Some("Edge: 5%".to_string()),
             is_paper: false,
+            market_id: Some("market1".to_string()),
+            category: Some("sports".to_string()),
+            realized_pnl: None,
+            estimated_fee: Some(0.45),
+            metadata: None,
         };
         assert_eq!(trade.side, "BUY");
+        assert_ne!(trade.client_trade_id, new_client_trade_id());
     }
 }
@@ -0,0 +1,187 @@
+//! Storage backend interface.
+//!
+//! The engine, audit trail and control API talk to a `TradeStore` rather
+//! than to Postgres directly. `TradeRepository` is the Postgres backend;
+//! another store (ClickHouse, BigQuery, a file) implements this trait and
+//! is constructed in `main` in its place.
+//!
+//! Writes are called from the trading loop, so they must return at once
+//! and do their I/O in the background. Queries back the control API and
+//! may await. Only trade writes are required: the other writes default to
+//! no-ops and the queries to an "unsupported" error, so a minimal store
+//! can start with `insert_trade` and `insert_arb_trade`.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::audit::AuditEvent;
+use crate::redis::SignalMessage;
+use crate::risk::{EquitySample, HourlyPnl};
+
+use super::attribution::{AttributionDimension, AttributionRow};
+use super::fees::{FeeErrorRow, FeeReconciliation, StatementFee};
+use super::order_tags::OrderTag;
+use super::repository::{ArbTrade, Trade};
+
+/// Time between refreshes of derived tables (see `refresh_pnl_attribution`)
+const REFRESH_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Days before today rebuilt on each refresh, so trades recorded just
+/// after midnight still land in the right day
+const REFRESH_LOOKBACK_DAYS: i32 = 1;
+
+/// Persistence for trades, signals and the operator timeline.
+#[async_trait]
+pub trait TradeStore: Send + Sync {
+    /// Whether writes go anywhere (false makes every call a no-op)
+    fn is_enabled(&self) -> bool;
+
+    /// Record a single-leg trade (fire-and-forget, non-blocking)
+    fn insert_trade(&self, trade: Trade);
+
+    /// Record an arbitrage pair (fire-and-forget, non-blocking)
+    fn insert_arb_trade(&self, trade: ArbTrade);
+
+    /// Record an audit event (fire-and-forget, non-blocking)
+    fn insert_audit_event(&self, _event: AuditEvent) {}
+
+    /// Record a point of the equity curve (fire-and-forget, non-blocking)
+    fn insert_equity_sample(&self, _sample: EquitySample, _is_paper: bool) {}
+
+    /// Record a strategy signal and what became of it (fire-and-forget, non-blocking)
+    fn insert_signal(
+        &self,
+        _signal: SignalMessage,
+        _action_taken: &'static str,
+        _rejection_reason: Option<String>,
+    ) {
+    }
+
+    /// Record which strategy and signal placed an order (fire-and-forget,
+    /// non-blocking)
+    fn insert_order_tag(&self, _tag: OrderTag) {}
+
+    /// Trades recorded in the last `minutes` minutes
+    async fn recent_trade_count(&self, _minutes: i32) -> Result<i64> {
+        bail!("recent trade count is not supported by this store")
+    }
+
+    /// Today's net profit from live arbitrage trades
+    #[allow(dead_code)]
+    async fn today_pnl(&self) -> Result<f64> {
+        bail!("daily P&L is not supported by this store")
+    }
+
+    /// Rebuild derived attribution data for the last `days` UTC days
+    /// (0 = today only). Stores that aggregate at query time keep the
+    /// default, which has nothing to rebuild.
+    async fn refresh_pnl_attribution(&self, _days: i32) -> Result<u64> {
+        Ok(0)
+    }
+
+    /// Summarize the last `days` days of P&L by `dimensions`, optionally
+    /// only paper or only live trades. Sorted by P&L, best first.
+    async fn pnl_attribution(
+        &self,
+        _days: i32,
+        _dimensions: &[AttributionDimension],
+        _is_paper: Option<bool>,
+    ) -> Result<Vec<AttributionRow>> {
+        bail!("P&L attribution is not supported by this store")
+    }
+
+    /// Write the actual fees from an exchange statement onto the matching
+    /// trades and correct their net P&L.
+    async fn reconcile_fees(&self, _fees: &[StatementFee]) -> Result<FeeReconciliation> {
+        bail!("fee reconciliation is not supported by this store")
+    }
+
+    /// Estimated versus actual fees per market over the last `days` days
+    async fn fee_estimate_errors(&self, _days: i32) -> Result<Vec<FeeErrorRow>> {
+        bail!("fee estimate errors are not supported by this store")
+    }
+
+    /// Realized plus mark-to-market P&L per UTC hour over the last `days`
+    /// days, oldest first, for paper or live trading
+    async fn pnl_history(&self, _days: i32, _is_paper: bool) -> Result<Vec<HourlyPnl>> {
+        bail!("P&L history is not supported by this store")
+    }
+}
+
+/// Refresh the store's attribution data every interval until cancelled.
+pub async fn run_pnl_attribution(store: Arc<dyn TradeStore>, cancel: CancellationToken) {
+    if !store.is_enabled() {
+        return;
+    }
+
+    info!(
+        "[DB] P&L attribution refresh every {}s",
+        REFRESH_INTERVAL.as_secs()
+    );
+    let mut ticker = tokio::time::interval(REFRESH_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {
+                if let Err(e) = store.refresh_pnl_attribution(REFRESH_LOOKBACK_DAYS).await {
+                    warn!("[DB] P&L attribution refresh failed: {:#}", e);
+                }
+            }
+            _ = cancel.cancelled() => break,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+
+    /// A minimal store that only keeps trades in memory
+    #[derive(Default)]
+    struct MemoryStore {
+        trades: Mutex<Vec<Trade>>,
+    }
+
+    impl TradeStore for MemoryStore {
+        fn is_enabled(&self) -> bool {
+            true
+        }
+
+        fn insert_trade(&self, trade: Trade) {
+            self.trades.lock().push(trade);
+        }
+
+        fn insert_arb_trade(&self, _trade: ArbTrade) {}
+    }
+
+    #[tokio::test]
+    async fn test_minimal_store_uses_defaults() {
+        let store: Arc<dyn TradeStore> = Arc::new(MemoryStore::default());
+        store.insert_trade(Trade {
+            client_trade_id: "test-1".to_string(),
+            token_id: "token".to_string(),
+            side: "BUY".to_string(),
+            price: 0.5,
+            size: 10.0,
+            order_id: None,
+            status: "FILLED".to_string(),
+            strategy: "Test".to_string(),
+            signal_reason: None,
+            is_paper: true,
+            market_id: None,
+            category: None,
+            realized_pnl: None,
+            estimated_fee: None,
+            metadata: None,
+        });
+
+        assert_eq!(store.refresh_pnl_attribution(1).await.unwrap(), 0);
+        let err = store.fee_estimate_errors(30).await.unwrap_err();
+        assert!(err.to_string().contains("not supported"));
+    }
+}
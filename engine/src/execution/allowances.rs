@@ -0,0 +1,357 @@
+//! Exchange allowance approvals (`poly-rust approve`).
+//!
+//! Before a wallet can trade live, the exchange contracts need an ERC-20
+//! allowance on its USDC (to buy) and ERC-1155 operator approval on its
+//! conditional tokens (to sell), for each of the CTF exchange, the neg risk
+//! exchange and the neg risk adapter. Without them every order is rejected
+//! with "not enough balance / allowance".
+//!
+//! `poly-rust approve` reads the current allowances over `POLYGON_RPC_URL`,
+//! lists the approval transactions still needed, and submits them from the
+//! `POLY_PRIVATE_KEY` wallet once confirmed on the terminal (`--yes` skips
+//! the prompt). Live startup runs the same inspection and warns about
+//! anything missing; it never submits transactions itself.
+
+use std::fmt;
+use std::sync::Arc;
+
+use anyhow::{bail, Context, Result};
+use ethers::contract::abigen;
+use ethers::middleware::SignerMiddleware;
+use ethers::providers::{Http, Middleware, Provider};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Address, H256, U256};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tracing::{info, warn};
+
+use crate::config::Config;
+
+abigen!(
+    Erc20,
+    r#"[
+        function allowance(address owner, address spender) external view returns (uint256)
+        function approve(address spender, uint256 amount) external returns (bool)
+    ]"#
+);
+
+abigen!(
+    Erc1155,
+    r#"[
+        function isApprovedForAll(address account, address operator) external view returns (bool)
+        function setApprovalForAll(address operator, bool approved) external
+    ]"#
+);
+
+/// RPC endpoint used when `POLYGON_RPC_URL` is unset
+const DEFAULT_RPC_URL: &str = "https://polygon-rpc.com";
+
+/// USDC.e, the exchange's collateral token
+const COLLATERAL_TOKEN: &str = "0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174";
+
+/// Conditional Tokens Framework (ERC-1155 outcome tokens)
+const CONDITIONAL_TOKENS: &str = "0x4D97DCd97eC945f40cF65F87097ACe5EA0476045";
+
+/// Contracts that move the wallet's tokens when orders match
+const SPENDERS: [(&str, &str); 3] = [
+    ("CTF exchange", "0x4bFb41d5B3570DeFd03C39a9A4D8dE6bd8B8982E"),
+    (
+        "neg risk exchange",
+        "0xC5d563A36AE78145C45a50134d48A1215220f80a",
+    ),
+    (
+        "neg risk adapter",
+        "0xd91E80cF2E7be2e162c6513ceD06f1dD0dA35296",
+    ),
+];
+
+/// USDC allowance (in base units, $1M) below which a spender is re-approved.
+/// Approvals are for the maximum amount, so this only trips on allowances
+/// set by hand or worn down by years of volume.
+const MIN_COLLATERAL_ALLOWANCE: u64 = 1_000_000_000_000;
+
+/// Arguments of `poly-rust approve`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ApproveArgs {
+    /// Submit without asking (`--yes`)
+    pub assume_yes: bool,
+}
+
+impl ApproveArgs {
+    /// The approve command, if it was the first argument.
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Option<Self> {
+        let mut args = args.into_iter();
+        if args.next().as_deref() != Some("approve") {
+            return None;
+        }
+        Some(Self {
+            assume_yes: args.any(|arg| arg == "--yes" || arg == "-y"),
+        })
+    }
+}
+
+/// Current approvals of one spender
+#[derive(Debug, Clone, PartialEq)]
+struct SpenderAllowance {
+    name: &'static str,
+    spender: Address,
+    /// USDC allowance in base units
+    collateral: U256,
+    /// Operator approval on the conditional tokens
+    conditional_tokens: bool,
+}
+
+/// An approval transaction still needed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ApprovalTx {
+    /// `approve(spender, max)` on the USDC contract
+    Collateral {
+        name: &'static str,
+        spender: Address,
+    },
+    /// `setApprovalForAll(operator, true)` on the conditional tokens
+    ConditionalTokens {
+        name: &'static str,
+        operator: Address,
+    },
+}
+
+impl fmt::Display for ApprovalTx {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApprovalTx::Collateral { name, spender } => {
+                write!(f, "USDC allowance for the {} ({:?})", name, spender)
+            }
+            ApprovalTx::ConditionalTokens { name, operator } => {
+                write!(
+                    f,
+                    "conditional token approval for the {} ({:?})",
+                    name, operator
+                )
+            }
+        }
+    }
+}
+
+/// Approval transactions needed to bring every spender up to date.
+fn missing_approvals(allowances: &[SpenderAllowance]) -> Vec<ApprovalTx> {
+    let mut missing = Vec::new();
+    for allowance in allowances {
+        if allowance.collateral < U256::from(MIN_COLLATERAL_ALLOWANCE) {
+            missing.push(ApprovalTx::Collateral {
+                name: allowance.name,
+                spender: allowance.spender,
+            });
+        }
+        if !allowance.conditional_tokens {
+            missing.push(ApprovalTx::ConditionalTokens {
+                name: allowance.name,
+                operator: allowance.spender,
+            });
+        }
+    }
+    missing
+}
+
+fn rpc_url() -> String {
+    std::env::var("POLYGON_RPC_URL")
+        .ok()
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| DEFAULT_RPC_URL.to_string())
+}
+
+fn wallet(config: &Config) -> Result<LocalWallet> {
+    config
+        .private_key
+        .parse::<LocalWallet>()
+        .context("POLY_PRIVATE_KEY is not a valid private key")
+}
+
+fn address(hex: &str) -> Address {
+    hex.parse().expect("contract address constant")
+}
+
+/// Read the wallet's current approvals of every spender.
+async fn inspect(provider: Arc<Provider<Http>>, owner: Address) -> Result<Vec<SpenderAllowance>> {
+    let collateral = Erc20::new(address(COLLATERAL_TOKEN), provider.clone());
+    let conditional_tokens = Erc1155::new(address(CONDITIONAL_TOKENS), provider);
+
+    let mut allowances = Vec::with_capacity(SPENDERS.len());
+    for (name, spender) in SPENDERS {
+        let spender = address(spender);
+        allowances.push(SpenderAllowance {
+            name,
+            spender,
+            collateral: collateral
+                .allowance(owner, spender)
+                .call()
+                .await
+                .with_context(|| format!("Failed to read the USDC allowance of the {}", name))?,
+            conditional_tokens: conditional_tokens
+                .is_approved_for_all(owner, spender)
+                .call()
+                .await
+                .with_context(|| format!("Failed to read the token approval of the {}", name))?,
+        });
+    }
+    Ok(allowances)
+}
+
+/// Ask on the terminal whether to go ahead.
+async fn confirm(prompt: &str) -> Result<bool> {
+    println!("{} [y/N]", prompt);
+    let mut answer = String::new();
+    BufReader::new(tokio::io::stdin())
+        .read_line(&mut answer)
+        .await
+        .context("Failed to read confirmation")?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Inspect the wallet's allowances and submit the missing approvals.
+pub async fn run_approvals(config: &Config, args: &ApproveArgs) -> Result<()> {
+    let provider = Arc::new(
+        Provider::<Http>::try_from(rpc_url()).context("POLYGON_RPC_URL is not a valid URL")?,
+    );
+    let chain_id = provider
+        .get_chainid()
+        .await
+        .context("Failed to reach the Polygon RPC")?
+        .as_u64();
+    let wallet = wallet(config)?.with_chain_id(chain_id);
+    let owner = wallet.address();
+
+    let missing = missing_approvals(&inspect(provider.clone(), owner).await?);
+    if missing.is_empty() {
+        info!("[APPROVE] {:?} already has every exchange approval", owner);
+        return Ok(());
+    }
+
+    println!("Wallet {:?} needs {} approval(s):", owner, missing.len());
+    for tx in &missing {
+        println!("  - {}", tx);
+    }
+    let prompt = format!(
+        "Submit {} transaction(s) on chain {}?",
+        missing.len(),
+        chain_id
+    );
+    if !args.assume_yes && !confirm(&prompt).await? {
+        bail!("Approvals not confirmed, nothing submitted");
+    }
+
+    let client = Arc::new(SignerMiddleware::new(provider, wallet));
+    let collateral = Erc20::new(address(COLLATERAL_TOKEN), client.clone());
+    let conditional_tokens = Erc1155::new(address(CONDITIONAL_TOKENS), client.clone());
+    for tx in missing {
+        let call = match tx {
+            ApprovalTx::Collateral { spender, .. } => collateral.approve(spender, U256::MAX).tx,
+            ApprovalTx::ConditionalTokens { operator, .. } => {
+                conditional_tokens.set_approval_for_all(operator, true).tx
+            }
+        };
+        let hash = send(client.as_ref(), call)
+            .await
+            .with_context(|| format!("Failed to submit the {}", tx))?;
+        info!("[APPROVE] Approved the {} in {:?}", tx, hash);
+    }
+    info!("[APPROVE] {:?} is ready to trade", owner);
+    Ok(())
+}
+
+/// Send a transaction and wait for it to be mined. Returns its hash.
+async fn send<M: Middleware + 'static>(client: &M, tx: TypedTransaction) -> Result<H256> {
+    let pending = client
+        .send_transaction(tx, None)
+        .await
+        .context("Failed to send the transaction")?;
+    let hash = pending.tx_hash();
+    let receipt = pending
+        .await
+        .context("Failed to wait for the transaction")?
+        .context("Transaction was dropped")?;
+    if receipt.status.is_some_and(|status| status.is_zero()) {
+        bail!("Transaction {:?} reverted", hash);
+    }
+    Ok(hash)
+}
+
+/// Warn at live startup about approvals that are still missing.
+pub async fn check_allowances(config: &Config) {
+    let result = async {
+        let provider = Arc::new(Provider::<Http>::try_from(rpc_url())?);
+        let owner = wallet(config)?.address();
+        anyhow::Ok((owner, missing_approvals(&inspect(provider, owner).await?)))
+    }
+    .await;
+    match result {
+        Ok((owner, missing)) if missing.is_empty() => {
+            info!("[APPROVE] Exchange approvals in place for {:?}", owner);
+        }
+        Ok((owner, missing)) => {
+            for tx in &missing {
+                warn!("[APPROVE] {:?} is missing the {}", owner, tx);
+            }
+            warn!("[APPROVE] Orders will be rejected until approved: run `poly-rust approve`");
+        }
+        Err(e) => warn!("[APPROVE] Could not check exchange approvals: {:#}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Option<ApproveArgs> {
+        ApproveArgs::from_args(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn test_approve_command_parsing() {
+        assert_eq!(args(&[]), None);
+        assert_eq!(args(&["--self-test"]), None);
+        assert_eq!(args(&["approve"]), Some(ApproveArgs { assume_yes: false }));
+        assert_eq!(
+            args(&["approve", "--yes"]),
+            Some(ApproveArgs { assume_yes: true })
+        );
+    }
+
+    #[test]
+    fn test_missing_approvals_per_spender() {
+        let spender = address(SPENDERS[0].1);
+        let mut allowance = SpenderAllowance {
+            name: SPENDERS[0].0,
+            spender,
+            collateral: U256::MAX,
+            conditional_tokens: true,
+        };
+        assert!(missing_approvals(&[allowance.clone()]).is_empty());
+
+        allowance.collateral = U256::from(5_000_000u64);
+        allowance.conditional_tokens = false;
+        assert_eq!(
+            missing_approvals(&[allowance]),
+            vec![
+                ApprovalTx::Collateral {
+                    name: "CTF exchange",
+                    spender
+                },
+                ApprovalTx::ConditionalTokens {
+                    name: "CTF exchange",
+                    operator: spender
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_contract_addresses_parse() {
+        for hex in [COLLATERAL_TOKEN, CONDITIONAL_TOKENS] {
+            assert!(hex.parse::<Address>().is_ok());
+        }
+        for (_, hex) in SPENDERS {
+            assert!(hex.parse::<Address>().is_ok());
+        }
+    }
+}
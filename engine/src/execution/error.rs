@@ -0,0 +1,534 @@
+//! Typed execution errors with retryability classification.
+//!
+//! CLOB rejections arrive as an HTTP status and a free-text body. Known
+//! Polymarket error messages are matched against `KNOWN_REJECTIONS`, which
+//! gives each a stable code (metrics label), the typed variant it maps to,
+//! and a remediation hint for logs and Slack.
+
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use thiserror::Error;
+
+/// Result type for order execution
+pub type ExecutionResult<T> = Result<T, ExecutionError>;
+
+/// Variant a known rejection maps to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RejectionKind {
+    Balance,
+    InvalidOrder,
+    Duplicate,
+    NotFilled,
+    MarketUnavailable,
+    Signing,
+    Unauthorized,
+    Exchange,
+}
+
+/// A CLOB rejection message we recognize
+#[derive(Debug)]
+pub struct KnownRejection {
+    /// Stable code, used as a metrics label
+    pub code: &'static str,
+    /// Lowercase text the rejection body contains
+    pattern: &'static str,
+    kind: RejectionKind,
+    /// What to do about it
+    pub hint: &'static str,
+}
+
+/// Known Polymarket rejections, most specific first
+const KNOWN_REJECTIONS: &[KnownRejection] = &[
+    KnownRejection {
+        code: "not_enough_balance",
+        pattern: "not enough balance",
+        kind: RejectionKind::Balance,
+        hint: "top up USDC in the funder wallet, or run the allowance approval \
+               for the exchange contracts (USDC and conditional tokens)",
+    },
+    KnownRejection {
+        code: "allowance",
+        pattern: "allowance",
+        kind: RejectionKind::Balance,
+        hint: "run the allowance approval for the exchange contracts \
+               (USDC and conditional tokens)",
+    },
+    KnownRejection {
+        code: "min_tick_size",
+        pattern: "minimum tick size",
+        kind: RejectionKind::InvalidOrder,
+        hint: "price is off the market's tick; the tick size may have changed, \
+               check the market's order rules",
+    },
+    KnownRejection {
+        code: "min_size",
+        pattern: "lower than the minimum",
+        kind: RejectionKind::InvalidOrder,
+        hint: "size is below the market's minimum order size",
+    },
+    KnownRejection {
+        code: "duplicated",
+        pattern: "duplicated",
+        kind: RejectionKind::Duplicate,
+        hint: "the same signed order was already accepted; sign a fresh order \
+               instead of resending",
+    },
+    KnownRejection {
+        code: "invalid_expiration",
+        pattern: "invalid expiration",
+        kind: RejectionKind::InvalidOrder,
+        hint: "check the host clock (NTP) and the order expiration",
+    },
+    KnownRejection {
+        code: "invalid_nonce",
+        pattern: "invalid nonce",
+        kind: RejectionKind::InvalidOrder,
+        hint: "the order nonce is no longer valid (nonces are invalidated by a \
+               cancel-all); restart to pick up the current nonce",
+    },
+    KnownRejection {
+        code: "fok_not_filled",
+        pattern: "fully filled",
+        kind: RejectionKind::NotFilled,
+        hint: "not enough liquidity at the limit price; re-price from the \
+               current book",
+    },
+    KnownRejection {
+        code: "fak_not_filled",
+        pattern: "no orders found to match",
+        kind: RejectionKind::NotFilled,
+        hint: "the book moved before the order arrived; re-price from the \
+               current book",
+    },
+    KnownRejection {
+        code: "market_not_ready",
+        pattern: "not yet ready",
+        kind: RejectionKind::MarketUnavailable,
+        hint: "the market is not open for orders yet",
+    },
+    KnownRejection {
+        code: "closed_only",
+        pattern: "closed only",
+        kind: RejectionKind::MarketUnavailable,
+        hint: "closed-only mode: only orders that reduce a position are accepted",
+    },
+    KnownRejection {
+        code: "trading_disabled",
+        pattern: "trading is currently disabled",
+        kind: RejectionKind::MarketUnavailable,
+        hint: "the exchange has paused trading; wait for it to resume",
+    },
+    KnownRejection {
+        code: "orderbook_not_found",
+        pattern: "the orderbook",
+        kind: RejectionKind::MarketUnavailable,
+        hint: "the market has no order book (closed or resolved); it will drop \
+               out at the next metadata refresh",
+    },
+    KnownRejection {
+        code: "invalid_signature",
+        pattern: "invalid signature",
+        kind: RejectionKind::Signing,
+        hint: "check PRIVATE_KEY, the signature type and the funder address",
+    },
+    KnownRejection {
+        code: "invalid_api_key",
+        pattern: "api key",
+        kind: RejectionKind::Unauthorized,
+        hint: "re-derive the CLOB API credentials (POLY_API_KEY, POLY_API_SECRET, \
+               POLY_PASSPHRASE)",
+    },
+    KnownRejection {
+        code: "insert_failed",
+        pattern: "could not insert order",
+        kind: RejectionKind::Exchange,
+        hint: "exchange-side failure; not resent in case the order went \
+               through, check open orders",
+    },
+    KnownRejection {
+        code: "execution_failed",
+        pattern: "could not run the execution",
+        kind: RejectionKind::Exchange,
+        hint: "exchange-side failure; not resent in case the order went \
+               through, check open orders",
+    },
+];
+
+impl KnownRejection {
+    /// The known rejection a response body matches, if any
+    pub fn find(body: &str) -> Option<&'static KnownRejection> {
+        let lower = body.to_lowercase();
+        KNOWN_REJECTIONS.iter().find(|r| lower.contains(r.pattern))
+    }
+}
+
+/// Why an order could not be placed or cancelled
+#[derive(Debug, Clone, Error)]
+pub enum ExecutionError {
+    /// Wallet balance or allowance too low for the order
+    #[error("insufficient balance: {0}")]
+    InsufficientBalance(String),
+
+    /// Exchange throttled us (HTTP 429)
+    #[error("rate limited by exchange")]
+    RateLimited { retry_after: Option<Duration> },
+
+    /// Exchange rejected the order parameters (price, size, tick, token)
+    #[error("invalid order: {0}")]
+    InvalidOrder(String),
+
+    /// API credentials rejected (HTTP 401/403)
+    #[error("unauthorized: {0}")]
+    Unauthorized(String),
+
+    /// Request timed out; the order may or may not have reached the exchange
+    #[error("order request timed out")]
+    Timeout,
+
+    /// Connection failed before the request was delivered
+    #[error("network error: {0}")]
+    Network(String),
+
+    /// Connection failed after the request may have been sent; the order
+    /// may or may not have reached the exchange
+    #[error("connection lost: {0}")]
+    ConnectionLost(String),
+
+    /// Exchange-side failure (HTTP 5xx); the order may still have been
+    /// accepted behind a failing gateway
+    #[error("exchange error {status}: {body}")]
+    Exchange { status: u16, body: String },
+
+    /// Response could not be parsed
+    #[error("invalid exchange response: {0}")]
+    InvalidResponse(String),
+
+    /// Paper fill lost the race to competing traders
+    #[error("paper fill lost to competing traders")]
+    MissedFill,
+
+    /// No wallet configured for live trading
+    #[error("wallet not available - cannot place real orders")]
+    NoWallet,
+
+    /// Order signing failed
+    #[error("failed to sign order: {0}")]
+    Signing(String),
+
+    /// Size rounds below the market's minimum order size; never sent
+    #[error("size {size} below market minimum {min}")]
+    BelowMinimumSize { size: f64, min: f64 },
+
+    /// Price failed the pre-send sanity limits; never sent
+    #[error("order price rejected: {0}")]
+    PriceLimit(String),
+
+    /// Circuit breaker is open after repeated infrastructure failures
+    #[error("order circuit breaker open for {0:?}")]
+    CircuitOpen(Duration),
+
+    /// Exchange already has this exact order
+    #[error("duplicate order: {0}")]
+    Duplicate(String),
+
+    /// Fill-or-kill or fill-and-kill order found nothing to match
+    #[error("order not filled: {0}")]
+    NotFilled(String),
+
+    /// Market not accepting orders (not open yet, closed-only, no book)
+    #[error("market unavailable: {0}")]
+    MarketUnavailable(String),
+
+    /// Engine is shutting down and takes no new orders; never sent
+    #[error("shutting down - no new orders")]
+    ShuttingDown,
+}
+
+impl ExecutionError {
+    /// Classify a non-success HTTP response from the CLOB.
+    pub fn from_response(status: StatusCode, body: String) -> Self {
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            return ExecutionError::RateLimited { retry_after: None };
+        }
+        if let Some(known) = KnownRejection::find(&body) {
+            return match known.kind {
+                RejectionKind::Balance => ExecutionError::InsufficientBalance(body),
+                RejectionKind::InvalidOrder => ExecutionError::InvalidOrder(body),
+                RejectionKind::Duplicate => ExecutionError::Duplicate(body),
+                RejectionKind::NotFilled => ExecutionError::NotFilled(body),
+                RejectionKind::MarketUnavailable => ExecutionError::MarketUnavailable(body),
+                RejectionKind::Signing => ExecutionError::Signing(body),
+                RejectionKind::Unauthorized => ExecutionError::Unauthorized(body),
+                RejectionKind::Exchange => ExecutionError::Exchange {
+                    status: status.as_u16(),
+                    body,
+                },
+            };
+        }
+        let lower = body.to_lowercase();
+        match status {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => ExecutionError::Unauthorized(body),
+            s if s.is_client_error() => {
+                if lower.contains("balance") || lower.contains("allowance") {
+                    ExecutionError::InsufficientBalance(body)
+                } else {
+                    ExecutionError::InvalidOrder(body)
+                }
+            }
+            s => ExecutionError::Exchange {
+                status: s.as_u16(),
+                body,
+            },
+        }
+    }
+
+    /// Classify a transport error from reqwest.
+    ///
+    /// Only a failed connect proves the request never left; any other
+    /// transport failure may have happened after the exchange got it.
+    pub fn from_transport(err: reqwest::Error) -> Self {
+        Self::classify_transport(
+            err.is_timeout(),
+            err.is_connect(),
+            err.is_decode(),
+            err.to_string(),
+        )
+    }
+
+    fn classify_transport(timeout: bool, connect: bool, decode: bool, msg: String) -> Self {
+        if timeout {
+            ExecutionError::Timeout
+        } else if connect {
+            ExecutionError::Network(msg)
+        } else if decode {
+            ExecutionError::InvalidResponse(msg)
+        } else {
+            ExecutionError::ConnectionLost(msg)
+        }
+    }
+
+    /// Whether resubmitting the same order is safe and may succeed.
+    ///
+    /// Only failures where the order provably never reached the book are
+    /// retried. Timeouts, dropped connections and 5xx responses are not:
+    /// the first attempt may already be live, and its outcome is settled
+    /// from order status and trade history instead of by resending.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            ExecutionError::RateLimited { .. } | ExecutionError::Network(_)
+        )
+    }
+
+    /// Whether the failure points at exchange/network health rather than the
+    /// order itself (counts toward the circuit breaker).
+    pub fn is_infrastructure(&self) -> bool {
+        matches!(
+            self,
+            ExecutionError::RateLimited { .. }
+                | ExecutionError::Timeout
+                | ExecutionError::Network(_)
+                | ExecutionError::ConnectionLost(_)
+                | ExecutionError::Exchange { .. }
+        )
+    }
+
+    /// Whether the exchange or our own breaker refused the order for lack
+    /// of capacity; it never reached the book and may go through later.
+    pub fn is_capacity(&self) -> bool {
+        matches!(
+            self,
+            ExecutionError::RateLimited { .. } | ExecutionError::CircuitOpen(_)
+        )
+    }
+
+    /// Short, stable reason code for metrics labels and trade status.
+    pub fn reason(&self) -> &'static str {
+        match self {
+            ExecutionError::InsufficientBalance(_) => "insufficient_balance",
+            ExecutionError::RateLimited { .. } => "rate_limited",
+            ExecutionError::InvalidOrder(_) => "invalid_order",
+            ExecutionError::Unauthorized(_) => "unauthorized",
+            ExecutionError::Timeout => "timeout",
+            ExecutionError::Network(_) => "network",
+            ExecutionError::ConnectionLost(_) => "connection_lost",
+            ExecutionError::Exchange { .. } => "exchange_error",
+            ExecutionError::InvalidResponse(_) => "invalid_response",
+            ExecutionError::MissedFill => "missed",
+            ExecutionError::NoWallet => "no_wallet",
+            ExecutionError::Signing(_) => "signing",
+            ExecutionError::BelowMinimumSize { .. } => "below_min_size",
+            ExecutionError::PriceLimit(_) => "price_limit",
+            ExecutionError::CircuitOpen(_) => "circuit_open",
+            ExecutionError::Duplicate(_) => "duplicate",
+            ExecutionError::NotFilled(_) => "not_filled",
+            ExecutionError::MarketUnavailable(_) => "market_unavailable",
+            ExecutionError::ShuttingDown => "shutting_down",
+        }
+    }
+
+    /// Text the exchange sent with the rejection
+    fn exchange_message(&self) -> Option<&str> {
+        match self {
+            ExecutionError::InsufficientBalance(body)
+            | ExecutionError::InvalidOrder(body)
+            | ExecutionError::Unauthorized(body)
+            | ExecutionError::Signing(body)
+            | ExecutionError::Duplicate(body)
+            | ExecutionError::NotFilled(body)
+            | ExecutionError::MarketUnavailable(body)
+            | ExecutionError::Exchange { body, .. } => Some(body),
+            _ => None,
+        }
+    }
+
+    /// The known exchange rejection behind this error, if any
+    pub fn known_rejection(&self) -> Option<&'static KnownRejection> {
+        self.exchange_message().and_then(KnownRejection::find)
+    }
+
+    /// Specific code for metrics labels: the known rejection's code, else
+    /// the `reason`.
+    pub fn code(&self) -> &'static str {
+        self.known_rejection()
+            .map_or_else(|| self.reason(), |known| known.code)
+    }
+
+    /// What the operator can do about this error, if anything.
+    pub fn hint(&self) -> Option<&'static str> {
+        if let Some(known) = self.known_rejection() {
+            return Some(known.hint);
+        }
+        match self {
+            ExecutionError::InsufficientBalance(_) => {
+                Some("check the USDC balance and the exchange allowances")
+            }
+            ExecutionError::Unauthorized(_) => Some("check the CLOB API credentials"),
+            ExecutionError::RateLimited { .. } => {
+                Some("orders are arriving faster than the exchange allows; lower the order rate")
+            }
+            ExecutionError::NoWallet => Some("set PRIVATE_KEY to trade live"),
+            ExecutionError::CircuitOpen(_) => {
+                Some("repeated exchange or network failures; orders resume after the cooldown")
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_response_classification() {
+        let err = ExecutionError::from_response(StatusCode::TOO_MANY_REQUESTS, String::new());
+        assert_eq!(err.reason(), "rate_limited");
+        assert!(err.is_retryable());
+
+        let err = ExecutionError::from_response(
+            StatusCode::BAD_REQUEST,
+            "not enough balance / allowance".into(),
+        );
+        assert_eq!(err.reason(), "insufficient_balance");
+        assert!(!err.is_retryable());
+
+        let err =
+            ExecutionError::from_response(StatusCode::BAD_REQUEST, "invalid tick size".into());
+        assert_eq!(err.reason(), "invalid_order");
+        assert!(!err.is_infrastructure());
+
+        let err = ExecutionError::from_response(StatusCode::FORBIDDEN, String::new());
+        assert_eq!(err.reason(), "unauthorized");
+
+        let err = ExecutionError::from_response(StatusCode::BAD_GATEWAY, "upstream".into());
+        assert_eq!(err.reason(), "exchange_error");
+        assert!(!err.is_retryable());
+        assert!(err.is_infrastructure());
+    }
+
+    #[test]
+    fn test_known_rejections_are_typed_with_hints() {
+        let err = ExecutionError::from_response(
+            StatusCode::BAD_REQUEST,
+            r#"{"error":"not enough balance / allowance"}"#.into(),
+        );
+        assert_eq!(err.reason(), "insufficient_balance");
+        assert_eq!(err.code(), "not_enough_balance");
+        assert!(err.hint().unwrap().contains("allowance approval"));
+
+        let err = ExecutionError::from_response(
+            StatusCode::BAD_REQUEST,
+            "order is invalid. Duplicated. Same order has already been placed".into(),
+        );
+        assert!(matches!(err, ExecutionError::Duplicate(_)));
+        assert!(!err.is_retryable());
+
+        let err = ExecutionError::from_response(
+            StatusCode::BAD_REQUEST,
+            "order couldn't be fully filled, FOK orders are fully filled/killed".into(),
+        );
+        assert_eq!(err.code(), "fok_not_filled");
+
+        let err = ExecutionError::from_response(
+            StatusCode::BAD_REQUEST,
+            "the market is not yet ready to process new orders".into(),
+        );
+        assert_eq!(err.reason(), "market_unavailable");
+
+        // Exchange-side failures are not resent
+        let err = ExecutionError::from_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "could not insert order".into(),
+        );
+        assert_eq!(err.code(), "insert_failed");
+        assert!(!err.is_retryable());
+
+        // Unknown text keeps the status-based classification
+        let err = ExecutionError::from_response(StatusCode::BAD_REQUEST, "mystery".into());
+        assert_eq!(err.code(), "invalid_order");
+        assert_eq!(err.hint(), None);
+    }
+
+    #[test]
+    fn test_timeout_is_not_retryable() {
+        assert!(!ExecutionError::Timeout.is_retryable());
+        assert!(ExecutionError::Timeout.is_infrastructure());
+        assert!(!ExecutionError::MissedFill.is_infrastructure());
+    }
+
+    #[test]
+    fn test_only_undelivered_requests_are_retryable() {
+        let classify = |timeout, connect, decode| {
+            ExecutionError::classify_transport(timeout, connect, decode, "io".into())
+        };
+
+        // Connect failed: the request never left
+        let err = classify(false, true, false);
+        assert_eq!(err.reason(), "network");
+        assert!(err.is_retryable());
+
+        // Timed out while connecting: still a timeout, never resent
+        assert_eq!(classify(true, true, false).reason(), "timeout");
+
+        // Dropped after sending (e.g. connection reset): may be live
+        let err = classify(false, false, false);
+        assert_eq!(err.reason(), "connection_lost");
+        assert!(!err.is_retryable());
+        assert!(err.is_infrastructure());
+
+        assert_eq!(classify(false, false, true).reason(), "invalid_response");
+
+        for status in [
+            StatusCode::INTERNAL_SERVER_ERROR,
+            StatusCode::BAD_GATEWAY,
+            StatusCode::SERVICE_UNAVAILABLE,
+            StatusCode::GATEWAY_TIMEOUT,
+        ] {
+            assert!(!ExecutionError::from_response(status, String::new()).is_retryable());
+        }
+        assert!(ExecutionError::from_response(StatusCode::TOO_MANY_REQUESTS, String::new())
+            .is_retryable());
+    }
+}
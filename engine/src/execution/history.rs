@@ -0,0 +1,183 @@
+//! The account's trade history from the CLOB (`/data/trades`).
+//!
+//! The exchange reports one trade per match, with the taker order and the
+//! maker orders it matched. For each trade the account took part in, the
+//! fill is reported from our side: the taker order if we were the taker,
+//! otherwise each of our maker orders (identified by the API key as
+//! `owner`).
+
+use chrono::{DateTime, TimeZone, Utc};
+use serde::Deserialize;
+
+use super::order_manager::Side;
+
+/// Cursor the CLOB returns after the last page
+pub(super) const END_CURSOR: &str = "LTE=";
+
+/// One page of `/data/trades`
+#[derive(Debug, Deserialize)]
+pub(super) struct TradesPage {
+    #[serde(default)]
+    pub data: Vec<ClobTrade>,
+    #[serde(default)]
+    pub next_cursor: String,
+}
+
+/// A maker order matched by a trade
+#[derive(Debug, Deserialize)]
+pub(super) struct MakerOrder {
+    order_id: String,
+    #[serde(default)]
+    owner: String,
+    asset_id: String,
+    matched_amount: String,
+    price: String,
+    #[serde(default)]
+    side: Option<String>,
+}
+
+/// A trade in `/data/trades` (fields we use)
+#[derive(Debug, Deserialize)]
+pub(super) struct ClobTrade {
+    id: String,
+    taker_order_id: String,
+    #[serde(default)]
+    market: Option<String>,
+    asset_id: String,
+    side: String,
+    size: String,
+    price: String,
+    match_time: String,
+    /// TAKER or MAKER: the account's role
+    #[serde(default)]
+    trader_side: String,
+    #[serde(default)]
+    maker_orders: Vec<MakerOrder>,
+}
+
+/// A fill of one of our orders, as the exchange recorded it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExchangeFill {
+    /// Exchange trade ID (with the order ID for maker fills, which can share
+    /// a trade)
+    pub fill_id: String,
+    pub order_id: String,
+    pub market_id: Option<String>,
+    pub token_id: String,
+    pub side: Side,
+    pub price: f64,
+    pub size: f64,
+    pub matched_at: DateTime<Utc>,
+}
+
+fn parse_side(side: &str) -> Option<Side> {
+    match side.to_uppercase().as_str() {
+        "BUY" => Some(Side::Buy),
+        "SELL" => Some(Side::Sell),
+        _ => None,
+    }
+}
+
+fn opposite(side: Side) -> Side {
+    match side {
+        Side::Buy => Side::Sell,
+        Side::Sell => Side::Buy,
+    }
+}
+
+impl ClobTrade {
+    /// Our fills in this trade. `api_key` identifies our maker orders.
+    pub(super) fn fills(&self, api_key: &str) -> Vec<ExchangeFill> {
+        let Some(matched_at) = self
+            .match_time
+            .parse::<i64>()
+            .ok()
+            .and_then(|secs| Utc.timestamp_opt(secs, 0).single())
+        else {
+            return Vec::new();
+        };
+        let Some(taker_side) = parse_side(&self.side) else {
+            return Vec::new();
+        };
+
+        if !self.trader_side.eq_ignore_ascii_case("MAKER") {
+            let (Ok(price), Ok(size)) = (self.price.parse(), self.size.parse()) else {
+                return Vec::new();
+            };
+            return vec![ExchangeFill {
+                fill_id: self.id.clone(),
+                order_id: self.taker_order_id.clone(),
+                market_id: self.market.clone(),
+                token_id: self.asset_id.clone(),
+                side: taker_side,
+                price,
+                size,
+                matched_at,
+            }];
+        }
+
+        self.maker_orders
+            .iter()
+            .filter(|maker| maker.owner == api_key)
+            .filter_map(|maker| {
+                Some(ExchangeFill {
+                    fill_id: format!("{}:{}", self.id, maker.order_id),
+                    order_id: maker.order_id.clone(),
+                    market_id: self.market.clone(),
+                    token_id: maker.asset_id.clone(),
+                    // Makers on the same token take the other side
+                    side: maker
+                        .side
+                        .as_deref()
+                        .and_then(parse_side)
+                        .unwrap_or_else(|| opposite(taker_side)),
+                    price: maker.price.parse().ok()?,
+                    size: maker.matched_amount.parse().ok()?,
+                    matched_at,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fills_from_our_side() {
+        let page: TradesPage = serde_json::from_str(
+            r#"{
+                "data": [
+                    {"id": "t1", "taker_order_id": "0xtake", "market": "0xm", "asset_id": "yes",
+                     "side": "BUY", "size": "10", "price": "0.42", "match_time": "1700000000",
+                     "trader_side": "TAKER", "maker_orders": []},
+                    {"id": "t2", "taker_order_id": "0xother", "market": "0xm", "asset_id": "yes",
+                     "side": "BUY", "size": "8", "price": "0.45", "match_time": "1700000060",
+                     "trader_side": "MAKER", "maker_orders": [
+                        {"order_id": "0xmine", "owner": "key", "asset_id": "yes",
+                         "matched_amount": "5", "price": "0.45"},
+                        {"order_id": "0xtheirs", "owner": "someone", "asset_id": "yes",
+                         "matched_amount": "3", "price": "0.45"}
+                     ]}
+                ],
+                "next_cursor": "LTE="
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(page.next_cursor, END_CURSOR);
+
+        let taker = page.data[0].fills("key");
+        assert_eq!(taker.len(), 1);
+        assert_eq!(
+            (taker[0].order_id.as_str(), taker[0].side),
+            ("0xtake", Side::Buy)
+        );
+        assert_eq!(taker[0].matched_at.timestamp(), 1_700_000_000);
+
+        let maker = page.data[1].fills("key");
+        assert_eq!(maker.len(), 1);
+        assert_eq!(maker[0].fill_id, "t2:0xmine");
+        assert_eq!((maker[0].side, maker[0].size), (Side::Sell, 5.0));
+    }
+}
@@ -0,0 +1,620 @@
+//! In-process mock exchange for dry runs.
+//!
+//! Plain dry runs hand back an order ID and forget the order, so fill
+//! polling and cancels are never exercised before real money is at stake.
+//! With `MOCK_EXCHANGE=true` the order manager sends dry-run orders here
+//! instead. Orders are accepted, the marketable part fills at once against
+//! the live book, and the rest rests like a GTC order on the CLOB. Every
+//! tick, resting orders whose price the market has reached fill against a
+//! share of the crossing depth, until they are filled or cancelled.
+//!
+//! Matching follows the CLOB's rules:
+//! - An order only fills at its limit price or better. The marketable part
+//!   is matched against the book as it stands once `MOCK_EXCHANGE_LATENCY_MS`
+//!   has passed, so a book that moved past the limit in the meantime leaves
+//!   the order resting unfilled.
+//! - Fills walk the book level by level, so a large order partially fills
+//!   down to its limit and rests the remainder.
+//! - Price-time priority: a resting order queues behind the size displayed
+//!   at its price when it arrived, which must trade before it fills (unless
+//!   the market trades through the price). Our own resting orders are
+//!   matched best price first, then oldest first, against one shared view
+//!   of the depth each tick.
+//! - Self-trade prevention: an order that would cross one of our own
+//!   resting orders on the other side is rejected.
+//! - Fill-and-kill orders (market orders) cancel whatever does not fill on
+//!   arrival; fill-or-kill orders are rejected unless they fill in full.
+//!
+//! The live book is not depleted by our fills between ticks;
+//! `MOCK_EXCHANGE_PARTICIPATION` stands in for the share of it we would
+//! really get.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info};
+
+use crate::market::{DepthLevel, MarketData, TokenId};
+use crate::metrics::MOCK_OPEN_ORDERS;
+
+use super::error::{ExecutionError, ExecutionResult};
+use super::order_manager::{OrderFill, OrderType, Side};
+
+/// Closed orders are kept this long so late fill checks still find them
+const CLOSED_RETENTION: Duration = Duration::from_secs(600);
+
+/// Mock exchange settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockExchangeConfig {
+    pub enabled: bool,
+    /// Time between fill checks on resting orders
+    pub tick: Duration,
+    /// Share of the crossing depth a resting order takes per tick
+    pub participation: f64,
+    /// Delay before each order or cancel is acknowledged
+    pub latency: Duration,
+}
+
+impl Default for MockExchangeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            tick: Duration::from_millis(250),
+            participation: 0.25,
+            latency: Duration::ZERO,
+        }
+    }
+}
+
+impl MockExchangeConfig {
+    /// Load from `MOCK_EXCHANGE`, `MOCK_EXCHANGE_TICK_MS`,
+    /// `MOCK_EXCHANGE_PARTICIPATION` and `MOCK_EXCHANGE_LATENCY_MS`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok();
+        Self {
+            enabled: var("MOCK_EXCHANGE")
+                .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+                .unwrap_or(defaults.enabled),
+            tick: var("MOCK_EXCHANGE_TICK_MS")
+                .and_then(|v| v.parse().ok())
+                .filter(|ms| *ms > 0)
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.tick),
+            participation: var("MOCK_EXCHANGE_PARTICIPATION")
+                .and_then(|v| v.parse::<f64>().ok())
+                .filter(|p| *p > 0.0 && *p <= 1.0)
+                .unwrap_or(defaults.participation),
+            latency: var("MOCK_EXCHANGE_LATENCY_MS")
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.latency),
+        }
+    }
+}
+
+/// An order held by the mock exchange
+#[derive(Debug, Clone)]
+struct MockOrder {
+    token_id: TokenId,
+    side: Side,
+    price: f64,
+    size: f64,
+    filled: f64,
+    /// Size-weighted fill price (0 until filled)
+    avg_price: f64,
+    /// None while resting
+    closed_at: Option<Instant>,
+    /// Arrival order, for time priority
+    seq: u64,
+    /// Displayed size at our price that queued before us and has not traded
+    queue_ahead: f64,
+}
+
+impl MockOrder {
+    fn remaining(&self) -> f64 {
+        (self.size - self.filled).max(0.0)
+    }
+
+    /// Matching priority among orders on the same token and side: best
+    /// price first, then oldest first.
+    fn priority(&self) -> (f64, u64) {
+        let price = match self.side {
+            Side::Buy => -self.price,
+            Side::Sell => self.price,
+        };
+        (price, self.seq)
+    }
+
+    /// Whether an incoming order would trade against this resting one.
+    fn crossed_by(&self, token_id: &TokenId, side: Side, price: f64) -> bool {
+        self.closed_at.is_none()
+            && self.token_id == *token_id
+            && match (self.side, side) {
+                (Side::Sell, Side::Buy) => price >= self.price - 1e-9,
+                (Side::Buy, Side::Sell) => price <= self.price + 1e-9,
+                _ => false,
+            }
+    }
+
+    /// Add a fill, closing the order once nothing remains.
+    fn fill(&mut self, size: f64, value: f64) {
+        if size <= 0.0 {
+            return;
+        }
+        self.avg_price = (self.avg_price * self.filled + value) / (self.filled + size);
+        self.filled += size;
+        if self.remaining() < 1e-9 {
+            self.closed_at = Some(Instant::now());
+        }
+    }
+}
+
+/// Accepts, rests, fills, and cancels dry-run orders against the live book.
+pub struct MockExchange {
+    config: MockExchangeConfig,
+    market_data: Arc<MarketData>,
+    orders: Mutex<HashMap<String, MockOrder>>,
+    next_id: AtomicU64,
+}
+
+impl MockExchange {
+    pub fn new(config: MockExchangeConfig, market_data: Arc<MarketData>) -> Self {
+        Self {
+            config,
+            market_data,
+            orders: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Accept an order; its marketable part fills immediately and the rest
+    /// rests (GTC) or is cancelled (FAK).
+    pub async fn submit(
+        &self,
+        token_id: &TokenId,
+        side: Side,
+        price: f64,
+        size: f64,
+        order_type: OrderType,
+    ) -> ExecutionResult<String> {
+        if !(price > 0.0 && price < 1.0 && size > 0.0) {
+            return Err(ExecutionError::InvalidOrder(format!(
+                "mock exchange rejects {} @ {}",
+                size, price
+            )));
+        }
+        self.delay().await;
+
+        let mut orders = self.orders.lock();
+        if let Some((resting_id, _)) = orders
+            .iter()
+            .find(|(_, o)| o.crossed_by(token_id, side, price))
+        {
+            return Err(ExecutionError::InvalidOrder(format!(
+                "mock exchange rejects self-trade against {}",
+                resting_id
+            )));
+        }
+
+        let seq = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let mut order = MockOrder {
+            token_id: token_id.clone(),
+            side,
+            price,
+            size,
+            filled: 0.0,
+            avg_price: 0.0,
+            closed_at: None,
+            seq,
+            queue_ahead: 0.0,
+        };
+        let mut levels = self.opposite_levels(token_id, side);
+        let (filled, value) = match_levels(
+            side,
+            price,
+            order.remaining(),
+            &mut levels,
+            1.0,
+            &mut order.queue_ahead,
+        );
+        if order_type == OrderType::Fok && filled < size - 1e-9 {
+            return Err(ExecutionError::InvalidOrder(format!(
+                "mock exchange kills FOK {} @ {}: {:.2} fillable",
+                size, price, filled
+            )));
+        }
+        order.fill(filled, value);
+        if order.closed_at.is_none() {
+            if order_type == OrderType::Gtc {
+                order.queue_ahead = self.displayed_at(token_id, side, price);
+            } else {
+                order.closed_at = Some(Instant::now());
+            }
+        }
+
+        let order_id = format!("mock-{}", seq);
+        info!(
+            "[MOCK] Accepted {} {:?} {} @ ${:.4} x {:.2} (filled {:.2})",
+            order_id, side, token_id, price, size, order.filled
+        );
+        orders.insert(order_id.clone(), order);
+        MOCK_OPEN_ORDERS.set(open_count(&orders) as f64);
+        Ok(order_id)
+    }
+
+    /// Fill state of an order.
+    pub fn order_fill(&self, order_id: &str) -> ExecutionResult<OrderFill> {
+        self.orders
+            .lock()
+            .get(order_id)
+            .map(|order| OrderFill {
+                filled: order.filled,
+                open: order.closed_at.is_none(),
+            })
+            .ok_or_else(|| unknown(order_id))
+    }
+
+    /// Cancel a resting order. Cancelling a closed order is a no-op, as it
+    /// is on the CLOB.
+    pub async fn cancel(&self, order_id: &str) -> ExecutionResult<()> {
+        self.delay().await;
+        let mut orders = self.orders.lock();
+        let order = orders.get_mut(order_id).ok_or_else(|| unknown(order_id))?;
+        if order.closed_at.is_none() {
+            order.closed_at = Some(Instant::now());
+            info!(
+                "[MOCK] Cancelled {} with {:.2}/{:.2} filled",
+                order_id, order.filled, order.size
+            );
+        }
+        MOCK_OPEN_ORDERS.set(open_count(&orders) as f64);
+        Ok(())
+    }
+
+    /// Match resting orders every tick until cancelled.
+    pub async fn run(self: Arc<Self>, cancel: CancellationToken) {
+        info!(
+            "[MOCK] Mock exchange matching every {:?} at {:.0}% participation",
+            self.config.tick,
+            self.config.participation * 100.0
+        );
+        let mut ticker = tokio::time::interval(self.config.tick);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => self.match_resting(),
+                _ = cancel.cancelled() => break,
+            }
+        }
+    }
+
+    /// Fill resting orders the market has reached, in price-time priority,
+    /// and drop closed orders past retention.
+    pub(super) fn match_resting(&self) {
+        let mut orders = self.orders.lock();
+        orders.retain(|_, order| {
+            order
+                .closed_at
+                .is_none_or(|at| at.elapsed() < CLOSED_RETENTION)
+        });
+
+        let mut open: Vec<(&String, &mut MockOrder)> = orders
+            .iter_mut()
+            .filter(|(_, order)| order.closed_at.is_none())
+            .collect();
+        open.sort_by(|(_, a), (_, b)| {
+            let (a_price, a_seq) = a.priority();
+            let (b_price, b_seq) = b.priority();
+            a_price.total_cmp(&b_price).then(a_seq.cmp(&b_seq))
+        });
+
+        // Depth left this tick per (token, buy side), shared by our orders
+        let mut books: HashMap<(TokenId, bool), Vec<DepthLevel>> = HashMap::new();
+        for (order_id, order) in open {
+            let key = (order.token_id.clone(), order.side == Side::Buy);
+            let levels = books
+                .entry(key)
+                .or_insert_with(|| self.opposite_levels(&order.token_id, order.side));
+            let (filled, value) = match_levels(
+                order.side,
+                order.price,
+                order.remaining(),
+                levels,
+                self.config.participation,
+                &mut order.queue_ahead,
+            );
+            if filled > 0.0 {
+                order.fill(filled, value);
+                debug!(
+                    "[MOCK] {} filled {:.2}/{:.2} @ ${:.4}",
+                    order_id, order.filled, order.size, order.avg_price
+                );
+            }
+        }
+        MOCK_OPEN_ORDERS.set(open_count(&orders) as f64);
+    }
+
+    /// Levels an order on `side` matches against (asks for a buy), best
+    /// first.
+    fn opposite_levels(&self, token_id: &TokenId, side: Side) -> Vec<DepthLevel> {
+        match self.market_data.get_order_book(token_id) {
+            Some(book) => match side {
+                Side::Buy => book.asks,
+                Side::Sell => book.bids,
+            },
+            // Without a book the top of book is treated as deep enough
+            None => {
+                let Some(quote) = self.market_data.get_price(token_id) else {
+                    return Vec::new();
+                };
+                let top = match side {
+                    Side::Buy => quote.ask,
+                    Side::Sell => quote.bid,
+                };
+                vec![DepthLevel::new(top, f64::INFINITY)]
+            }
+        }
+    }
+
+    /// Size displayed on our own side at exactly `price`, which queued there
+    /// before a new resting order.
+    fn displayed_at(&self, token_id: &TokenId, side: Side, price: f64) -> f64 {
+        let Some(book) = self.market_data.get_order_book(token_id) else {
+            return 0.0;
+        };
+        let levels = match side {
+            Side::Buy => book.bids,
+            Side::Sell => book.asks,
+        };
+        levels
+            .iter()
+            .find(|level| (level.price - price).abs() < 1e-9)
+            .map_or(0.0, |level| level.size)
+    }
+
+    async fn delay(&self) {
+        if !self.config.latency.is_zero() {
+            tokio::time::sleep(self.config.latency).await;
+        }
+    }
+}
+
+/// Walk `levels` (best first) while they cross `price`, taking up to
+/// `participation` of each and depleting what is taken. Volume at exactly
+/// `price` goes to `queue_ahead` first; a level through the price clears
+/// the queue. Returns the size and value filled.
+fn match_levels(
+    side: Side,
+    price: f64,
+    remaining: f64,
+    levels: &mut [DepthLevel],
+    participation: f64,
+    queue_ahead: &mut f64,
+) -> (f64, f64) {
+    let mut filled = 0.0;
+    let mut value = 0.0;
+    for level in levels.iter_mut() {
+        let crosses = level.price > 0.0
+            && match side {
+                Side::Buy => level.price <= price + 1e-9,
+                Side::Sell => level.price >= price - 1e-9,
+            };
+        if !crosses || filled >= remaining {
+            break;
+        }
+        let mut available = level.size * participation;
+        if (level.price - price).abs() < 1e-9 {
+            let ahead = queue_ahead.min(available);
+            *queue_ahead -= ahead;
+            level.size -= ahead;
+            available -= ahead;
+        } else {
+            *queue_ahead = 0.0;
+        }
+        let take = available.min(remaining - filled);
+        level.size -= take;
+        filled += take;
+        value += take * level.price;
+    }
+    (filled, value)
+}
+
+fn open_count(orders: &HashMap<String, MockOrder>) -> usize {
+    orders.values().filter(|o| o.closed_at.is_none()).count()
+}
+
+fn unknown(order_id: &str) -> ExecutionError {
+    ExecutionError::InvalidOrder(format!("unknown order {}", order_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exchange() -> (MockExchange, Arc<MarketData>) {
+        let data = Arc::new(MarketData::new());
+        let config = MockExchangeConfig {
+            enabled: true,
+            participation: 0.5,
+            ..MockExchangeConfig::default()
+        };
+        (MockExchange::new(config, data.clone()), data)
+    }
+
+    #[test]
+    fn test_match_levels_stops_at_limit_price() {
+        let asks = || {
+            [
+                DepthLevel::new(0.40, 10.0),
+                DepthLevel::new(0.41, 10.0),
+                DepthLevel::new(0.45, 10.0),
+            ]
+        };
+        let (filled, value) = match_levels(Side::Buy, 0.41, 50.0, &mut asks(), 1.0, &mut 0.0);
+        assert!((filled - 20.0).abs() < 1e-9);
+        assert!((value - 8.1).abs() < 1e-9);
+
+        let (filled, _) = match_levels(Side::Buy, 0.41, 50.0, &mut asks(), 0.5, &mut 0.0);
+        assert!((filled - 10.0).abs() < 1e-9);
+
+        let mut bids = [DepthLevel::new(0.39, 10.0)];
+        assert_eq!(
+            match_levels(Side::Sell, 0.40, 5.0, &mut bids, 1.0, &mut 0.0).0,
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_match_levels_queue_ahead_trades_first() {
+        // 6 queued ahead at our price take the first 6 of the 10 offered
+        let mut asks = [DepthLevel::new(0.40, 10.0)];
+        let mut ahead = 6.0;
+        let (filled, _) = match_levels(Side::Buy, 0.40, 50.0, &mut asks, 1.0, &mut ahead);
+        assert!((filled - 4.0).abs() < 1e-9);
+        assert_eq!(ahead, 0.0);
+        assert!(asks[0].size.abs() < 1e-9);
+
+        // Trading through the price fills the whole queue at it
+        let mut asks = [DepthLevel::new(0.39, 5.0), DepthLevel::new(0.40, 10.0)];
+        let mut ahead = 100.0;
+        let (filled, _) = match_levels(Side::Buy, 0.40, 50.0, &mut asks, 1.0, &mut ahead);
+        assert!((filled - 15.0).abs() < 1e-9);
+        assert_eq!(ahead, 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_orders_rest_fill_over_time_and_cancel() {
+        let (exchange, data) = exchange();
+        let token = "t1".to_string();
+        data.update_order_book(
+            &token,
+            vec![DepthLevel::new(0.38, 100.0)],
+            vec![DepthLevel::new(0.40, 8.0), DepthLevel::new(0.45, 100.0)],
+        );
+
+        // Marketable part fills on acceptance, the rest rests
+        let id = exchange
+            .submit(&token, Side::Buy, 0.40, 20.0, OrderType::Gtc)
+            .await
+            .unwrap();
+        let fill = exchange.order_fill(&id).unwrap();
+        assert_eq!(
+            fill,
+            OrderFill {
+                filled: 8.0,
+                open: true
+            }
+        );
+
+        // Half of the crossing depth per tick once the ask comes down
+        exchange.match_resting();
+        assert_eq!(exchange.order_fill(&id).unwrap().filled, 12.0);
+        exchange.match_resting();
+        assert_eq!(exchange.order_fill(&id).unwrap().filled, 16.0);
+
+        exchange.cancel(&id).await.unwrap();
+        exchange.match_resting();
+        assert_eq!(
+            exchange.order_fill(&id).unwrap(),
+            OrderFill {
+                filled: 16.0,
+                open: false
+            }
+        );
+
+        // Full fill closes the order
+        let id = exchange
+            .submit(&token, Side::Sell, 0.38, 30.0, OrderType::Gtc)
+            .await
+            .unwrap();
+        assert_eq!(
+            exchange.order_fill(&id).unwrap(),
+            OrderFill {
+                filled: 30.0,
+                open: false
+            }
+        );
+
+        assert!(exchange.order_fill("mock-999").is_err());
+        assert!(exchange.submit(&token, Side::Buy, 1.5, 1.0, OrderType::Gtc).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_price_time_priority_and_self_trade() {
+        let (exchange, data) = exchange();
+        let token = "t1".to_string();
+        data.update_order_book(
+            &token,
+            vec![DepthLevel::new(0.38, 10.0)],
+            vec![DepthLevel::new(0.45, 100.0)],
+        );
+
+        // Joins 10 displayed at 0.38; a later, better bid goes first
+        let joined = exchange
+            .submit(&token, Side::Buy, 0.38, 20.0, OrderType::Gtc)
+            .await
+            .unwrap();
+        let better = exchange
+            .submit(&token, Side::Buy, 0.39, 20.0, OrderType::Gtc)
+            .await
+            .unwrap();
+
+        // Would buy from our own 0.38/0.39 bids
+        assert!(exchange
+            .submit(&token, Side::Sell, 0.38, 5.0, OrderType::Gtc)
+            .await
+            .is_err());
+
+        // 40 offered at 0.38, half of it ours: the better bid takes the
+        // first 20, and what is left goes to the 10 queued ahead of us
+        data.update_order_book(
+            &token,
+            vec![DepthLevel::new(0.37, 10.0)],
+            vec![DepthLevel::new(0.38, 40.0)],
+        );
+        exchange.match_resting();
+        assert_eq!(exchange.order_fill(&better).unwrap().filled, 20.0);
+        assert_eq!(exchange.order_fill(&joined).unwrap().filled, 0.0);
+
+        // Next tick the queue ahead has traded and the offer reaches us
+        exchange.match_resting();
+        assert_eq!(exchange.order_fill(&joined).unwrap().filled, 20.0);
+    }
+
+    #[tokio::test]
+    async fn test_fill_and_kill_and_fill_or_kill() {
+        let (exchange, data) = exchange();
+        let token = "t1".to_string();
+        data.update_order_book(
+            &token,
+            vec![DepthLevel::new(0.38, 100.0)],
+            vec![DepthLevel::new(0.40, 8.0), DepthLevel::new(0.45, 100.0)],
+        );
+
+        // The unfilled part is cancelled instead of resting
+        let id = exchange
+            .submit(&token, Side::Buy, 0.40, 20.0, OrderType::Ioc)
+            .await
+            .unwrap();
+        let fill = OrderFill {
+            filled: 8.0,
+            open: false,
+        };
+        assert_eq!(exchange.order_fill(&id).unwrap(), fill);
+
+        // All or nothing
+        assert!(exchange
+            .submit(&token, Side::Buy, 0.40, 20.0, OrderType::Fok)
+            .await
+            .is_err());
+        let id = exchange
+            .submit(&token, Side::Buy, 0.45, 20.0, OrderType::Fok)
+            .await
+            .unwrap();
+        assert_eq!(exchange.order_fill(&id).unwrap().filled, 20.0);
+    }
+}
@@ -1,8 +1,28 @@
 //! Order execution module.
 
+mod allowances;
+mod error;
+mod history;
+mod mock_exchange;
 mod order_manager;
+mod order_queue;
+mod order_tracker;
 mod paper;
+mod price_guard;
+mod twap;
+mod volume;
 
-pub use order_manager::{OrderManager, Side};
+pub use allowances::{check_allowances, run_approvals, ApproveArgs};
 #[allow(unused_imports)]
-pub use paper::{PaperArbTrade, PaperFill, PaperTrader, PaperTraderStats};
+pub use error::{ExecutionError, ExecutionResult};
+pub use history::ExchangeFill;
+pub use mock_exchange::{MockExchange, MockExchangeConfig};
+pub use order_manager::{ExecutionStyle, OrderFill, OrderManager, Side, SignedOrder};
+pub use order_queue::OrderPriority;
+#[allow(unused_imports)]
+pub use order_tracker::{OrderState, OrderTracker, OrderTrackingConfig, OrderUpdate, TrackedOrder};
+#[allow(unused_imports)]
+pub use paper::{ContestedFillModel, PaperArbTrade, PaperFill, PaperTrader, PaperTraderStats};
+#[allow(unused_imports)]
+pub use twap::{TwapConfig, TwapEvent, TwapExecutor, TwapOutcome, TwapReport};
+pub use volume::VolumeTracker;
@@ -2,19 +2,27 @@
 
 use anyhow::{Context, Result};
 use ethers::signers::{LocalWallet, Signer};
-use reqwest::Client;
+use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
-use tracing::{debug, info};
+use tokio::sync::Notify;
+use tracing::{debug, info, warn};
 
 use crate::config::Config;
-use crate::execution::paper::{PaperTrader, PaperTraderStats};
-use crate::market::{MarketData, TokenId};
-use crate::metrics::{ORDERS_TOTAL, ORDER_LATENCY};
+use crate::execution::error::{ExecutionError, ExecutionResult};
+use crate::execution::history::{ExchangeFill, TradesPage, END_CURSOR};
+use crate::execution::mock_exchange::MockExchange;
+use crate::execution::order_queue::{OrderPriority, OrderQueue};
+use crate::execution::paper::{ContestedFillModel, PaperTrader, PaperTraderStats};
+use crate::execution::price_guard::PriceGuard;
+use crate::market::{MarketData, TokenId, SIZE_INCREMENT};
+use crate::metrics::{EXCHANGE_REJECTIONS, ORDERS_TOTAL, ORDER_LATENCY};
+use crate::strategy::{CostModel, Leg};
 
 /// Order side
-#[derive(Debug, Clone, Copy, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum Side {
     Buy,
@@ -23,12 +31,47 @@ pub enum Side {
 
 /// Order type
 #[allow(dead_code)]
-#[derive(Debug, Clone, Copy, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum OrderType {
     Gtc, // Good til cancelled
     Fok, // Fill or kill
-    Ioc, // Immediate or cancel
+    #[serde(rename = "FAK")]
+    Ioc, // Immediate or cancel (the CLOB calls it fill and kill)
+}
+
+/// How an order meets the book
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExecutionStyle {
+    /// Limit order at a chosen price, resting if not marketable
+    #[default]
+    Limit,
+    /// Take whatever liquidity exists now (see `OrderManager::place_market`)
+    Market,
+}
+
+impl ExecutionStyle {
+    /// Parse `limit` or `market`.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "limit" => Some(ExecutionStyle::Limit),
+            "market" => Some(ExecutionStyle::Market),
+            _ => None,
+        }
+    }
+}
+
+/// A market order as placed: the size the book could fill within the
+/// limit, and its expected average price
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarketOrder {
+    pub order_id: String,
+    /// Size sent (what the book showed within the limit)
+    pub size: f64,
+    /// VWAP of the book levels the order sweeps
+    pub avg_price: f64,
+    /// Limit price sent: the deepest level swept
+    pub worst_price: f64,
 }
 
 /// Order request to Polymarket CLOB
@@ -47,16 +90,141 @@ struct OrderRequest {
 
 /// Order response from Polymarket
 #[allow(dead_code)]
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OrderResponse {
+    #[serde(rename = "orderID", alias = "orderId", default)]
     pub order_id: String,
+    #[serde(default)]
     pub status: String,
+    /// False when the order was refused despite a success status
+    #[serde(default = "default_success")]
+    pub success: bool,
+    /// Why the order was refused
+    #[serde(default)]
+    pub error_msg: String,
+}
+
+fn default_success() -> bool {
+    true
+}
+
+/// CLOB `/data/order/{id}` response (fields we use)
+/// Balance and allowance of one asset
+#[derive(Debug, Deserialize)]
+struct BalanceAllowanceResponse {
+    /// In base units
+    balance: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OrderStatusResponse {
+    status: String,
+    #[serde(default)]
+    size_matched: String,
+}
+
+/// Fill state of a resting order
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrderFill {
+    /// Size executed so far
+    pub filled: f64,
+    /// Whether the order is still resting on the book
+    pub open: bool,
+}
+
+/// An order signed and serialized, ready to submit.
+///
+/// `OrderManager::presign_buy` returns these so signing and serialization
+/// happen ahead of time, off the critical path.
+#[derive(Debug, Clone)]
+pub struct SignedOrder {
+    pub token_id: TokenId,
+    pub price: f64,
+    pub size: f64,
+    pub side: Side,
+    /// Serialized request body (empty for dry-run templates)
+    body: Vec<u8>,
+    timestamp: u64,
+    signed_at: Instant,
+}
+
+impl SignedOrder {
+    /// Time since the order was signed
+    pub fn age(&self) -> Duration {
+        self.signed_at.elapsed()
+    }
 }
 
 /// HTTP timeout for order requests (500ms for latency-sensitive trading)
 const ORDER_TIMEOUT: Duration = Duration::from_millis(500);
 
+/// HTTP timeout for trade history pages (not latency sensitive)
+const HISTORY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Extra attempts for retryable order failures (rate limit, failed connect)
+const ORDER_MAX_RETRIES: u32 = 1;
+
+/// Backoff before retrying when the exchange gives no Retry-After
+const ORDER_RETRY_BACKOFF: Duration = Duration::from_millis(50);
+
+/// Most orders the CLOB batch endpoint takes in one request
+const BATCH_LIMIT: usize = 15;
+
+/// Consecutive infrastructure failures before the circuit breaker opens
+const CIRCUIT_BREAKER_THRESHOLD: u32 = 5;
+
+/// Collateral balances are reported in USDC base units (6 decimals)
+const USDC_UNIT: f64 = 1_000_000.0;
+
+/// How long the circuit breaker stays open
+const CIRCUIT_BREAKER_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// Count and log an order the exchange refused, with what to do about it.
+fn exchange_rejected(err: ExecutionError) -> ExecutionError {
+    EXCHANGE_REJECTIONS.with_label_values(&[err.code()]).inc();
+    match err.hint() {
+        Some(hint) => warn!("Order rejected ({}): {} - {}", err.code(), err, hint),
+        None => warn!("Order rejected ({}): {}", err.code(), err),
+    }
+    err
+}
+
+/// An order response as a result: refused despite a success status, or
+/// accepted without an order ID, is an error.
+fn accepted(order: OrderResponse) -> ExecutionResult<OrderResponse> {
+    if !order.success {
+        return Err(exchange_rejected(ExecutionError::from_response(
+            StatusCode::BAD_REQUEST,
+            order.error_msg,
+        )));
+    }
+    if order.order_id.is_empty() {
+        return Err(ExecutionError::InvalidResponse(
+            "order accepted without an order ID".to_string(),
+        ));
+    }
+    Ok(order)
+}
+
+fn epoch_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Holds one order in `OrderManager::in_flight` until dropped
+struct InFlight<'a>(&'a OrderManager);
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        if self.0.in_flight.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.0.idle.notify_waiters();
+        }
+    }
+}
+
 /// Order manager for placing and tracking orders.
 pub struct OrderManager {
     client: Client,
@@ -68,8 +236,24 @@ pub struct OrderManager {
     dry_run: bool,
     /// Paper trader for simulating fills with VWAP calculations in dry-run mode
     paper_trader: Option<PaperTrader>,
-    /// Market data for paper trading simulations
+    /// In-process exchange that takes over dry-run orders when set
+    mock_exchange: Option<Arc<MockExchange>>,
+    /// Market data for paper trading simulations and the mid-price check
     market_data: Option<Arc<MarketData>>,
+    /// Pre-send sanity limits on order prices
+    price_guard: PriceGuard,
+    /// Rate limit and priority ordering for live requests
+    queue: OrderQueue,
+    /// Consecutive infrastructure failures (reset on success)
+    consecutive_failures: AtomicU32,
+    /// Circuit breaker open until this time (ms since epoch, 0 = closed)
+    circuit_open_until_ms: AtomicU64,
+    /// Orders between entry and result
+    in_flight: AtomicUsize,
+    /// Set by `drain`; new orders fail with `ShuttingDown`
+    closed: AtomicBool,
+    /// Woken when the last in-flight order finishes
+    idle: Notify,
 }
 
 impl OrderManager {
@@ -107,12 +291,19 @@ impl OrderManager {
 
         // Create paper trader in dry-run mode for realistic fill simulation
         let paper_trader = if config.dry_run {
-            let fee_rate = config.sum_to_100.fee_rate;
             info!(
-                "Paper trader enabled with fee_rate={:.2}%",
-                fee_rate * 100.0
+                "Paper trader enabled with taker fee {:.2}%/side, slippage {:.2}%",
+                config.cost.taker_fee_rate * 100.0,
+                config.cost.slippage_rate * 100.0
             );
-            Some(PaperTrader::new(fee_rate))
+            let mut paper_trader = PaperTrader::new(CostModel::new(config.cost.clone()));
+            // Sniper fills race other traders; we learn of events within one ESPN poll
+            paper_trader.set_contested_fill_model(ContestedFillModel {
+                competition_factor: config.sniper.paper_competition_factor,
+                default_event_age_ms: config.sniper.poll_interval_ms,
+            });
+            paper_trader.set_impact_model(config.sum_to_100.impact_model());
+            Some(paper_trader)
         } else {
             None
         };
@@ -131,42 +322,541 @@ impl OrderManager {
             base_url: config.clob_url,
             dry_run: config.dry_run,
             paper_trader,
+            mock_exchange: None,
             market_data,
+            price_guard: PriceGuard::new(config.order_guard),
+            queue: OrderQueue::new(config.order_queue),
+            consecutive_failures: AtomicU32::new(0),
+            circuit_open_until_ms: AtomicU64::new(0),
+            in_flight: AtomicUsize::new(0),
+            closed: AtomicBool::new(false),
+            idle: Notify::new(),
         })
     }
 
+    /// Send dry-run orders to a mock exchange instead of simulating them
+    /// (ignored in live mode).
+    pub fn set_mock_exchange(&mut self, mock_exchange: Arc<MockExchange>) {
+        if self.dry_run {
+            info!("Mock exchange enabled - dry-run orders rest, fill and cancel in process");
+            self.mock_exchange = Some(mock_exchange);
+        }
+    }
+
     /// Check if running in dry-run mode (no real orders).
     pub fn is_dry_run(&self) -> bool {
         self.dry_run
     }
 
+    /// Whether placed orders count as filled right away (dry run without a
+    /// mock exchange, where the paper trader simulates the fill)
+    pub fn fills_on_placement(&self) -> bool {
+        self.dry_run && self.mock_exchange.is_none()
+    }
+
+    /// Orders placed but not yet answered
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// Refuse new orders, then wait for those in flight to finish.
+    pub async fn drain(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+        loop {
+            // Registered before the check so a finish in between still wakes us
+            let idle = self.idle.notified();
+            if self.in_flight() == 0 {
+                return;
+            }
+            idle.await;
+        }
+    }
+
+    /// Count an order as in flight until the guard drops, or refuse it
+    /// once draining has started.
+    fn begin_order(&self) -> ExecutionResult<InFlight<'_>> {
+        // Counted before the check so `drain` never misses an order that got past it
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        let guard = InFlight(self);
+        if self.closed.load(Ordering::SeqCst) {
+            return Err(ExecutionError::ShuttingDown);
+        }
+        Ok(guard)
+    }
+
     /// Place a buy order.
-    pub async fn place_buy(&self, token_id: &TokenId, price: f64, size: f64) -> Result<String> {
-        self.place_order(token_id, price, size, Side::Buy).await
+    pub async fn place_buy(
+        &self,
+        token_id: &TokenId,
+        price: f64,
+        size: f64,
+    ) -> ExecutionResult<String> {
+        self.place_buy_with_priority(token_id, price, size, OrderPriority::Taker)
+            .await
+    }
+
+    /// Place a buy order in the given queue class (hedges use `Urgent`).
+    pub async fn place_buy_with_priority(
+        &self,
+        token_id: &TokenId,
+        price: f64,
+        size: f64,
+        priority: OrderPriority,
+    ) -> ExecutionResult<String> {
+        let (price, size) = self.check_order(token_id, price, size, Side::Buy, true)?;
+        self.place_order(
+            token_id,
+            price,
+            size,
+            Side::Buy,
+            false,
+            None,
+            priority,
+            OrderType::Gtc,
+        )
+        .await
+    }
+
+    /// Place a buy order that races other traders for a stale quote.
+    ///
+    /// Identical to `place_buy` in live mode. In paper mode the fill is drawn
+    /// from the contested fill model, so orders can lose the race and fail.
+    /// `event_age_ms` is the time since the triggering event, if known.
+    pub async fn place_contested_buy(
+        &self,
+        token_id: &TokenId,
+        price: f64,
+        size: f64,
+        event_age_ms: Option<u64>,
+    ) -> ExecutionResult<String> {
+        let (price, size) = self.check_order(token_id, price, size, Side::Buy, true)?;
+        self.place_order(
+            token_id,
+            price,
+            size,
+            Side::Buy,
+            true,
+            event_age_ms,
+            OrderPriority::Taker,
+            OrderType::Gtc,
+        )
+        .await
+    }
+
+    /// Place a resting (GTC) limit buy that is not expected to fill
+    /// immediately. Poll it with `order_fill`.
+    ///
+    /// In dry-run mode nothing is simulated at placement; `order_fill`
+    /// simulates the fill once the market trades through the price.
+    pub async fn place_bid(
+        &self,
+        token_id: &TokenId,
+        price: f64,
+        size: f64,
+    ) -> ExecutionResult<String> {
+        let (price, size) = self.check_order(token_id, price, size, Side::Buy, true)?;
+        if self.dry_run && self.mock_exchange.is_none() {
+            let mode = self.mode_label();
+            info!(
+                "[{}] Resting bid: {} @ ${:.4} x {:.2}",
+                mode.to_uppercase(),
+                token_id,
+                price,
+                size
+            );
+            ORDERS_TOTAL
+                .with_label_values(&["buy", "success", mode])
+                .inc();
+            return Ok(format!("{}-bid-{}", mode, rand::random::<u32>()));
+        }
+        self.place_order(
+            token_id,
+            price,
+            size,
+            Side::Buy,
+            false,
+            None,
+            OrderPriority::Passive,
+            OrderType::Gtc,
+        )
+        .await
+    }
+
+    /// Fill state of a resting bid placed with `place_bid`.
+    ///
+    /// Paper bids fill in full once the best ask reaches the bid price (a
+    /// seller had to trade through our level); without market data they
+    /// never fill. Mock exchange orders report their simulated fills.
+    pub async fn order_fill(
+        &self,
+        order_id: &str,
+        token_id: &TokenId,
+        price: f64,
+        size: f64,
+    ) -> ExecutionResult<OrderFill> {
+        if let Some(mock) = &self.mock_exchange {
+            return mock.order_fill(order_id);
+        }
+        if self.dry_run {
+            let crossed = self
+                .market_data
+                .as_ref()
+                .and_then(|md| md.get_price(token_id))
+                .is_some_and(|level| level.ask > 0.0 && level.ask <= price);
+            return Ok(if crossed {
+                OrderFill {
+                    filled: size,
+                    open: false,
+                }
+            } else {
+                OrderFill {
+                    filled: 0.0,
+                    open: true,
+                }
+            });
+        }
+
+        let timestamp = epoch_ms() / 1000;
+        let response = self
+            .client
+            .get(format!("{}/data/order/{}", self.base_url, order_id))
+            .header("POLY-API-KEY", &self.api_key)
+            .header("POLY-SIGNATURE", &self.api_secret)
+            .header("POLY-TIMESTAMP", timestamp.to_string())
+            .send()
+            .await
+            .map_err(ExecutionError::from_transport)?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(ExecutionError::from_response(status, body));
+        }
+
+        let order: OrderStatusResponse = response
+            .json()
+            .await
+            .map_err(|e| ExecutionError::InvalidResponse(e.to_string()))?;
+        Ok(OrderFill {
+            filled: order.size_matched.parse().unwrap_or(0.0),
+            open: order.status.eq_ignore_ascii_case("LIVE"),
+        })
     }
 
     /// Place a sell order.
-    pub async fn place_sell(&self, token_id: &TokenId, price: f64, size: f64) -> Result<String> {
-        self.place_order(token_id, price, size, Side::Sell).await
+    pub async fn place_sell(
+        &self,
+        token_id: &TokenId,
+        price: f64,
+        size: f64,
+    ) -> ExecutionResult<String> {
+        let (price, size) = self.check_order(token_id, price, size, Side::Sell, true)?;
+        self.place_order(
+            token_id,
+            price,
+            size,
+            Side::Sell,
+            false,
+            None,
+            OrderPriority::Taker,
+            OrderType::Gtc,
+        )
+        .await
+    }
+
+    /// Most legs that go out in one batch request, or None when batches are
+    /// simulated leg by leg (dry runs).
+    pub fn batch_limit(&self) -> Option<usize> {
+        (!self.dry_run).then_some(BATCH_LIMIT)
+    }
+
+    /// Place several orders together, returning each leg's outcome in leg
+    /// order.
+    ///
+    /// Live orders are checked and signed up front, then sent to the batch
+    /// endpoint `BATCH_LIMIT` at a time; a request that fails fails every
+    /// leg in it. An `atomic` batch is sent as one request or not at all:
+    /// if any leg fails its checks, none is sent. Simulated modes place the
+    /// legs one after another.
+    pub async fn place_batch(&self, legs: &[Leg], atomic: bool) -> Vec<ExecutionResult<String>> {
+        if self.dry_run {
+            let mut results = Vec::with_capacity(legs.len());
+            for leg in legs {
+                results.push(match leg.side {
+                    Side::Buy => self.place_buy(&leg.token_id, leg.price, leg.size).await,
+                    Side::Sell => self.place_sell(&leg.token_id, leg.price, leg.size).await,
+                });
+            }
+            return results;
+        }
+
+        let fail_all = |err: ExecutionError| -> Vec<ExecutionResult<String>> {
+            legs.iter()
+                .map(|leg| Err(self.rejected(leg.side, err.clone())))
+                .collect()
+        };
+        let _in_flight = match self.begin_order() {
+            Ok(guard) => guard,
+            Err(e) => return fail_all(e),
+        };
+        if let Err(e) = self.check_circuit() {
+            return fail_all(e);
+        }
+        if atomic && legs.len() > BATCH_LIMIT {
+            return fail_all(ExecutionError::InvalidOrder(format!(
+                "{} legs do not fit one batch of {}",
+                legs.len(),
+                BATCH_LIMIT
+            )));
+        }
+        let start = Instant::now();
+        let timestamp = epoch_ms() / 1000;
+
+        // Sign every leg that passes the pre-send checks
+        let signed = futures::future::join_all(legs.iter().map(|leg| async move {
+            let (price, size) =
+                self.check_order(&leg.token_id, leg.price, leg.size, leg.side, true)?;
+            let nonce = timestamp * 1000 + rand::random::<u64>() % 1000;
+            self.sign_order(
+                &leg.token_id,
+                price,
+                size,
+                leg.side,
+                OrderType::Gtc,
+                timestamp,
+                nonce,
+            )
+            .await
+        }))
+        .await;
+
+        let mut results: Vec<Option<ExecutionResult<String>>> = Vec::with_capacity(legs.len());
+        let mut pending = Vec::new();
+        for (i, order) in signed.into_iter().enumerate() {
+            match order {
+                Ok(order) => {
+                    results.push(None);
+                    pending.push((i, order));
+                }
+                Err(e) => results.push(Some(Err(e))),
+            }
+        }
+        if atomic && !pending.is_empty() && pending.len() < legs.len() {
+            let refused = ExecutionError::InvalidOrder("another leg of the batch failed".into());
+            for (i, order) in pending.drain(..) {
+                results[i] = Some(Err(self.rejected(order.side, refused.clone())));
+            }
+        }
+
+        for chunk in pending.chunks(BATCH_LIMIT) {
+            let orders: Vec<SignedOrder> = chunk.iter().map(|(_, o)| o.clone()).collect();
+            let sent = self
+                .with_retries(OrderPriority::Taker, || self.submit_batch(&orders))
+                .await;
+            match &sent {
+                Ok(_) => self.consecutive_failures.store(0, Ordering::Relaxed),
+                Err(e) => self.record_failure(e),
+            }
+
+            for (n, (i, order)) in chunk.iter().enumerate() {
+                let side_label = if matches!(order.side, Side::Buy) { "buy" } else { "sell" };
+                ORDER_LATENCY
+                    .with_label_values(&[side_label])
+                    .observe(start.elapsed().as_secs_f64());
+                let result = match &sent {
+                    Ok(responses) => accepted(responses[n].clone()).map(|r| r.order_id),
+                    Err(e) => Err(e.clone()),
+                };
+                let status = result.as_ref().map_or_else(|e| e.reason(), |_| "success");
+                ORDERS_TOTAL
+                    .with_label_values(&[side_label, status, "live"])
+                    .inc();
+                if let Ok(order_id) = &result {
+                    info!(
+                        "Batch order placed: {} - {:?} {} @ ${} x {}",
+                        order_id, order.side, order.token_id, order.price, order.size
+                    );
+                }
+                results[*i] = Some(result);
+            }
+        }
+
+        results.into_iter().flatten().collect()
+    }
+
+    /// Take whatever liquidity exists for up to `size` shares, never paying
+    /// more (buy) or receiving less (sell) than `limit`.
+    ///
+    /// The CLOB has no separate market order endpoint; a market order is a
+    /// marketable limit. The book is swept within `limit` and the order is
+    /// priced at the deepest level needed, so it crosses every level the
+    /// VWAP counted, then sent fill-and-kill: whatever has gone by the time
+    /// it arrives is cancelled instead of left resting.
+    pub async fn place_market(
+        &self,
+        token_id: &TokenId,
+        side: Side,
+        size: f64,
+        limit: f64,
+        priority: OrderPriority,
+    ) -> ExecutionResult<MarketOrder> {
+        let book = self
+            .market_data
+            .as_ref()
+            .and_then(|md| md.get_order_book(token_id));
+        let sweep = book.and_then(|book| match side {
+            Side::Buy => book.vwap_buy_within(size, limit),
+            Side::Sell => book.vwap_sell_within(size, limit),
+        });
+        let Some(sweep) = sweep else {
+            return Err(self.rejected(
+                side,
+                ExecutionError::InvalidOrder(format!(
+                    "no liquidity for {} within ${:.4}",
+                    token_id, limit
+                )),
+            ));
+        };
+
+        let (price, size) =
+            self.check_order(token_id, sweep.worst_price, sweep.total_size, side, true)?;
+        let order_id = self
+            .place_order(
+                token_id,
+                price,
+                size,
+                side,
+                false,
+                None,
+                priority,
+                OrderType::Ioc,
+            )
+            .await?;
+        Ok(MarketOrder {
+            order_id,
+            size,
+            avg_price: sweep.vwap,
+            worst_price: price,
+        })
+    }
+
+    /// Apply the market's order rules and price limits before anything is
+    /// signed or sent. Returns the price snapped to the tick and the size
+    /// rounded down to the share increment.
+    ///
+    /// `check_mid` is false for pre-signed race orders, which are priced at
+    /// the limit on purpose while the book still reflects the game in play.
+    fn check_order(
+        &self,
+        token_id: &TokenId,
+        price: f64,
+        size: f64,
+        side: Side,
+        check_mid: bool,
+    ) -> ExecutionResult<(f64, f64)> {
+        let rules = self
+            .market_data
+            .as_ref()
+            .map(|md| md.order_rules(token_id))
+            .unwrap_or_default();
+        let Some(rounded_size) = rules.round_size(size) else {
+            warn!(
+                "[ORDER] Rejected {:?} {} @ ${:.4} x {}: below minimum size {:?}",
+                side, token_id, price, size, rules.min_order_size
+            );
+            return Err(self.rejected(
+                side,
+                ExecutionError::BelowMinimumSize {
+                    size,
+                    min: rules.min_order_size.unwrap_or(SIZE_INCREMENT),
+                },
+            ));
+        };
+        let price = rules.round_price(price);
+
+        let mid = self
+            .market_data
+            .as_ref()
+            .filter(|_| check_mid)
+            .and_then(|md| md.get_price(token_id))
+            .filter(|level| level.bid > 0.0 && level.ask > 0.0)
+            .map(|level| (level.bid + level.ask) / 2.0);
+
+        let Err(violation) = self.price_guard.check(token_id, price, mid) else {
+            return Ok((price, rounded_size));
+        };
+        warn!(
+            "[ORDER] Rejected {:?} {} @ ${:.4} x {:.2} (mid {}): {}",
+            side,
+            token_id,
+            price,
+            rounded_size,
+            mid.map_or("unknown".to_string(), |m| format!("${:.4}", m)),
+            violation
+        );
+        Err(self.rejected(side, ExecutionError::PriceLimit(violation.to_string())))
+    }
+
+    /// Count an order rejected before sending.
+    fn rejected(&self, side: Side, err: ExecutionError) -> ExecutionError {
+        let side_label = if matches!(side, Side::Buy) { "buy" } else { "sell" };
+        ORDERS_TOTAL
+            .with_label_values(&[side_label, err.reason(), self.mode_label()])
+            .inc();
+        err
+    }
+
+    /// Execution mode label for metrics
+    pub fn mode_label(&self) -> &'static str {
+        if !self.dry_run {
+            "live"
+        } else if self.mock_exchange.is_some() {
+            "mock"
+        } else if self.paper_trader.is_some() {
+            "paper"
+        } else {
+            "dry_run"
+        }
     }
 
     /// Place an order.
+    #[allow(clippy::too_many_arguments)]
     async fn place_order(
         &self,
         token_id: &TokenId,
         price: f64,
         size: f64,
         side: Side,
-    ) -> Result<String> {
+        contested: bool,
+        event_age_ms: Option<u64>,
+        priority: OrderPriority,
+        order_type: OrderType,
+    ) -> ExecutionResult<String> {
+        let _in_flight = self
+            .begin_order()
+            .map_err(|e| self.rejected(side, e))?;
         let start = Instant::now();
         let side_label = if matches!(side, Side::Buy) { "buy" } else { "sell" };
-        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let timestamp = epoch_ms() / 1000;
         let nonce = timestamp * 1000 + rand::random::<u64>() % 1000;
 
         // Format price and size for API
         let price_str = format!("{:.4}", price);
         let size_str = format!("{:.2}", size);
 
+        // The mock exchange takes every dry-run order through its lifecycle
+        if let Some(mock) = &self.mock_exchange {
+            let result = mock.submit(token_id, side, price, size, order_type).await;
+            ORDER_LATENCY
+                .with_label_values(&[side_label])
+                .observe(start.elapsed().as_secs_f64());
+            let status = result.as_ref().map_or_else(|e| e.reason(), |_| "success");
+            ORDERS_TOTAL
+                .with_label_values(&[side_label, status, "mock"])
+                .inc();
+            return result;
+        }
+
         // In dry-run mode, use paper trader for realistic simulation if available
         if self.dry_run {
             // Try to simulate with paper trader for realistic VWAP-based fills
@@ -174,7 +864,31 @@ impl OrderManager {
                 (&self.paper_trader, &self.market_data)
             {
                 if matches!(side, Side::Buy) {
-                    if let Some(fill) = paper_trader.simulate_buy(market_data, token_id, size) {
+                    let simulated = if contested {
+                        match paper_trader.simulate_contested_buy(
+                            market_data,
+                            token_id,
+                            size,
+                            event_age_ms,
+                        ) {
+                            Some(None) => {
+                                ORDER_LATENCY
+                                    .with_label_values(&[side_label])
+                                    .observe(start.elapsed().as_secs_f64());
+                                let err = ExecutionError::MissedFill;
+                                ORDERS_TOTAL
+                                    .with_label_values(&[side_label, err.reason(), "paper"])
+                                    .inc();
+                                return Err(err);
+                            }
+                            Some(fill) => fill,
+                            None => None,
+                        }
+                    } else {
+                        paper_trader.simulate_buy(market_data, token_id, size)
+                    };
+
+                    if let Some(fill) = simulated {
                         info!(
                             "[PAPER] Simulated {:?} fill: {} @ ${:.4} (requested ${}) x {:.2}",
                             side, token_id, fill.price, price, fill.size
@@ -211,11 +925,31 @@ impl OrderManager {
             return Ok(format!("dry-run-{}", nonce));
         }
 
+        self.check_circuit()?;
+
+        let order = self
+            .sign_order(token_id, price, size, side, order_type, timestamp, nonce)
+            .await?;
+        self.submit_live(&order, start, priority).await
+    }
+
+    /// Sign an order and serialize the request body.
+    #[allow(clippy::too_many_arguments)]
+    async fn sign_order(
+        &self,
+        token_id: &TokenId,
+        price: f64,
+        size: f64,
+        side: Side,
+        order_type: OrderType,
+        timestamp: u64,
+        nonce: u64,
+    ) -> ExecutionResult<SignedOrder> {
+        let price_str = format!("{:.4}", price);
+        let size_str = format!("{:.2}", size);
+
         // Wallet is required for real orders
-        let wallet = self
-            .wallet
-            .as_ref()
-            .context("Wallet not available - cannot place real orders")?;
+        let wallet = self.wallet.as_ref().ok_or(ExecutionError::NoWallet)?;
 
         // Create message to sign
         let message = format!(
@@ -233,15 +967,14 @@ impl OrderManager {
 
         // Sign the message off the async runtime (ECDSA is CPU-bound)
         let wallet = Arc::clone(wallet);
-        let msg = message.clone();
         let signature = tokio::task::spawn_blocking(move || {
             // Use futures::executor::block_on since we're outside the tokio runtime
             // in spawn_blocking. This avoids nesting tokio runtimes.
-            futures::executor::block_on(wallet.sign_message(&msg))
+            futures::executor::block_on(wallet.sign_message(&message))
         })
         .await
-        .context("Signing task panicked")?
-        .context("Failed to sign order")?
+        .map_err(|e| ExecutionError::Signing(format!("signing task panicked: {}", e)))?
+        .map_err(|e| ExecutionError::Signing(e.to_string()))?
         .to_string();
 
         let request = OrderRequest {
@@ -249,22 +982,39 @@ impl OrderManager {
             price: price_str,
             size: size_str,
             side,
-            order_type: OrderType::Gtc,
+            order_type,
             signature,
             timestamp,
             nonce,
         };
+        debug!("Signed order: {:?}", request);
+        let body = serde_json::to_vec(&request).map_err(|e| {
+            ExecutionError::InvalidOrder(format!("failed to serialize order: {}", e))
+        })?;
 
-        debug!("Placing order: {:?}", request);
+        Ok(SignedOrder {
+            token_id: token_id.clone(),
+            price,
+            size,
+            side,
+            body,
+            timestamp,
+            signed_at: Instant::now(),
+        })
+    }
 
-        let response = self
-            .client
-            .post(format!("{}/order", self.base_url))
-            .header("POLY-API-KEY", &self.api_key)
-            .header("POLY-SIGNATURE", &self.api_secret)
-            .header("POLY-TIMESTAMP", timestamp.to_string())
-            .json(&request)
-            .send()
+    /// Send a signed order with retries, recording metrics. Each attempt
+    /// waits its turn in the order queue.
+    async fn submit_live(
+        &self,
+        order: &SignedOrder,
+        start: Instant,
+        priority: OrderPriority,
+    ) -> ExecutionResult<String> {
+        let side_label = if matches!(order.side, Side::Buy) { "buy" } else { "sell" };
+
+        let result = self
+            .with_retries(priority, || self.submit_order(&order.body, order.timestamp))
             .await;
 
         // Record latency regardless of success/failure
@@ -272,21 +1022,17 @@ impl OrderManager {
             .with_label_values(&[side_label])
             .observe(start.elapsed().as_secs_f64());
 
-        let response = response.context("Failed to send order request")?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            ORDERS_TOTAL
-                .with_label_values(&[side_label, "failed", "live"])
-                .inc();
-            anyhow::bail!("Order failed with status {}: {}", status, body);
-        }
-
-        let order_response: OrderResponse = response
-            .json()
-            .await
-            .context("Failed to parse order response")?;
+        let order_response = match result {
+            Ok(r) => r,
+            Err(e) => {
+                self.record_failure(&e);
+                ORDERS_TOTAL
+                    .with_label_values(&[side_label, e.reason(), "live"])
+                    .inc();
+                return Err(e);
+            }
+        };
+        self.consecutive_failures.store(0, Ordering::Relaxed);
 
         ORDERS_TOTAL
             .with_label_values(&[side_label, "success", "live"])
@@ -294,21 +1040,254 @@ impl OrderManager {
 
         info!(
             "Order placed: {} - {:?} {} @ ${} x {}",
-            order_response.order_id, side, token_id, price, size
+            order_response.order_id, order.side, order.token_id, order.price, order.size
         );
 
         Ok(order_response.order_id)
     }
 
-    /// Cancel an order.
+    /// Run a request until it succeeds or fails for good. Each attempt
+    /// waits its turn in the order queue.
+    async fn with_retries<T, F, Fut>(
+        &self,
+        priority: OrderPriority,
+        mut send: F,
+    ) -> ExecutionResult<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = ExecutionResult<T>>,
+    {
+        let mut attempt = 0;
+        loop {
+            self.queue.acquire(priority).await;
+            let result = send().await;
+            match &result {
+                Err(e) if e.is_retryable() && attempt < ORDER_MAX_RETRIES => {
+                    self.record_failure(e);
+                    attempt += 1;
+                    let backoff = match e {
+                        ExecutionError::RateLimited {
+                            retry_after: Some(d),
+                        } => (*d).min(ORDER_TIMEOUT),
+                        _ => ORDER_RETRY_BACKOFF,
+                    };
+                    warn!(
+                        "Order attempt {} failed ({}), retrying in {:?}",
+                        attempt, e, backoff
+                    );
+                    if matches!(e, ExecutionError::RateLimited { .. }) {
+                        // Hold every queued request, then let priority decide who retries first
+                        self.queue.pause(backoff);
+                    } else {
+                        tokio::time::sleep(backoff).await;
+                    }
+                }
+                _ => return result,
+            }
+        }
+    }
+
+    /// Sign a buy order now so it can be submitted later without signing or
+    /// serialization on the critical path.
+    ///
+    /// In dry-run mode nothing is signed; submitting the template goes
+    /// through the paper trader as a contested buy.
+    pub async fn presign_buy(
+        &self,
+        token_id: &TokenId,
+        price: f64,
+        size: f64,
+    ) -> ExecutionResult<SignedOrder> {
+        let (price, size) = self.check_order(token_id, price, size, Side::Buy, false)?;
+        let timestamp = epoch_ms() / 1000;
+        let nonce = timestamp * 1000 + rand::random::<u64>() % 1000;
+
+        if self.dry_run {
+            return Ok(SignedOrder {
+                token_id: token_id.clone(),
+                price,
+                size,
+                side: Side::Buy,
+                body: Vec::new(),
+                timestamp,
+                signed_at: Instant::now(),
+            });
+        }
+
+        self.sign_order(
+            token_id,
+            price,
+            size,
+            Side::Buy,
+            OrderType::Gtc,
+            timestamp,
+            nonce,
+        )
+        .await
+    }
+
+    /// Submit a template from `presign_buy` as a contested buy.
+    ///
+    /// `event_age_ms` is the time since the triggering event, if known.
+    pub async fn submit_presigned(
+        &self,
+        order: &SignedOrder,
+        event_age_ms: Option<u64>,
+    ) -> ExecutionResult<String> {
+        if self.dry_run {
+            // Already checked at signing
+            return self
+                .place_order(
+                    &order.token_id,
+                    order.price,
+                    order.size,
+                    Side::Buy,
+                    true,
+                    event_age_ms,
+                    OrderPriority::Taker,
+                    OrderType::Gtc,
+                )
+                .await;
+        }
+
+        let _in_flight = self
+            .begin_order()
+            .map_err(|e| self.rejected(Side::Buy, e))?;
+        self.check_circuit()?;
+        self.submit_live(order, Instant::now(), OrderPriority::Taker)
+            .await
+    }
+
+    /// Send one order request and classify the outcome.
+    async fn submit_order(&self, body: &[u8], timestamp: u64) -> ExecutionResult<OrderResponse> {
+        let response = self.post_orders("order", body.to_vec(), timestamp).await?;
+        let order: OrderResponse = response
+            .json()
+            .await
+            .map_err(|e| ExecutionError::InvalidResponse(e.to_string()))?;
+        accepted(order)
+    }
+
+    /// Send signed orders to the batch endpoint as one request. Responses
+    /// come back in request order.
+    async fn submit_batch(&self, orders: &[SignedOrder]) -> ExecutionResult<Vec<OrderResponse>> {
+        let mut body = b"[".to_vec();
+        for (i, order) in orders.iter().enumerate() {
+            if i > 0 {
+                body.push(b',');
+            }
+            body.extend_from_slice(&order.body);
+        }
+        body.push(b']');
+
+        let response = self
+            .post_orders("orders", body, orders[0].timestamp)
+            .await?;
+        let responses: Vec<OrderResponse> = response
+            .json()
+            .await
+            .map_err(|e| ExecutionError::InvalidResponse(e.to_string()))?;
+        if responses.len() != orders.len() {
+            return Err(ExecutionError::InvalidResponse(format!(
+                "{} responses for a batch of {} orders",
+                responses.len(),
+                orders.len()
+            )));
+        }
+        Ok(responses)
+    }
+
+    /// POST a request body to an order endpoint, classifying transport and
+    /// HTTP failures.
+    async fn post_orders(
+        &self,
+        path: &str,
+        body: Vec<u8>,
+        timestamp: u64,
+    ) -> ExecutionResult<reqwest::Response> {
+        if let Some(err) = crate::chaos::order_fault().await {
+            return Err(err);
+        }
+
+        let response = self
+            .client
+            .post(format!("{}/{}", self.base_url, path))
+            .header("POLY-API-KEY", &self.api_key)
+            .header("POLY-SIGNATURE", &self.api_secret)
+            .header("POLY-TIMESTAMP", timestamp.to_string())
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body)
+            .send()
+            .await
+            .map_err(ExecutionError::from_transport)?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let retry_after = response
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<u64>().ok())
+                .map(Duration::from_secs);
+            let body = response.text().await.unwrap_or_default();
+            let err = match ExecutionError::from_response(status, body) {
+                ExecutionError::RateLimited { .. } => ExecutionError::RateLimited { retry_after },
+                other => other,
+            };
+            return Err(exchange_rejected(err));
+        }
+        Ok(response)
+    }
+
+    /// Fail fast while the circuit breaker is open.
+    fn check_circuit(&self) -> ExecutionResult<()> {
+        let open_until = self.circuit_open_until_ms.load(Ordering::Relaxed);
+        let now = epoch_ms();
+        if open_until > now {
+            return Err(ExecutionError::CircuitOpen(Duration::from_millis(
+                open_until - now,
+            )));
+        }
+        Ok(())
+    }
+
+    /// Count an infrastructure failure, opening the breaker at the threshold.
+    fn record_failure(&self, err: &ExecutionError) {
+        if !err.is_infrastructure() {
+            return;
+        }
+        let failures = self.consecutive_failures.fetch_add(1, Ordering::Relaxed) + 1;
+        if failures >= CIRCUIT_BREAKER_THRESHOLD {
+            self.consecutive_failures.store(0, Ordering::Relaxed);
+            self.circuit_open_until_ms.store(
+                epoch_ms() + CIRCUIT_BREAKER_COOLDOWN.as_millis() as u64,
+                Ordering::Relaxed,
+            );
+            warn!(
+                "Order circuit breaker OPEN for {:?} after {} consecutive failures (last: {})",
+                CIRCUIT_BREAKER_COOLDOWN, failures, err
+            );
+        }
+    }
+
+    /// Whether the order circuit breaker is currently open.
+    pub fn is_circuit_open(&self) -> bool {
+        self.check_circuit().is_err()
+    }
+
+    /// Cancel an order. Cancels go ahead of every order in the queue.
     #[allow(dead_code)]
-    pub async fn cancel_order(&self, order_id: &str) -> Result<()> {
+    pub async fn cancel_order(&self, order_id: &str) -> ExecutionResult<()> {
+        if let Some(mock) = &self.mock_exchange {
+            return mock.cancel(order_id).await;
+        }
         if self.dry_run {
             info!("[DRY RUN] Would cancel order: {}", order_id);
             return Ok(());
         }
 
-        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        self.queue.acquire(OrderPriority::Urgent).await;
+        let timestamp = epoch_ms() / 1000;
 
         let response = self
             .client
@@ -318,18 +1297,132 @@ impl OrderManager {
             .header("POLY-TIMESTAMP", timestamp.to_string())
             .send()
             .await
-            .context("Failed to send cancel request")?;
+            .map_err(ExecutionError::from_transport)?;
 
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("Cancel failed with status {}: {}", status, body);
+            return Err(ExecutionError::from_response(status, body));
         }
 
         info!("Order cancelled: {}", order_id);
         Ok(())
     }
 
+    /// Verify the API credentials with an authenticated read (used by the
+    /// startup self-test; sends a request even in dry-run mode).
+    pub async fn check_auth(&self) -> ExecutionResult<()> {
+        let timestamp = epoch_ms() / 1000;
+
+        let response = self
+            .client
+            .get(format!("{}/auth/api-keys", self.base_url))
+            .header("POLY-API-KEY", &self.api_key)
+            .header("POLY-SIGNATURE", &self.api_secret)
+            .header("POLY-TIMESTAMP", timestamp.to_string())
+            .send()
+            .await
+            .map_err(ExecutionError::from_transport)?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(ExecutionError::from_response(status, body));
+        }
+        Ok(())
+    }
+
+    /// Our fills matched between `after` and `before` (unix seconds), from
+    /// the account's trade history. Dry-run mode has none.
+    pub async fn trade_history(
+        &self,
+        after: i64,
+        before: i64,
+    ) -> ExecutionResult<Vec<ExchangeFill>> {
+        if self.dry_run {
+            return Ok(Vec::new());
+        }
+
+        let mut fills = Vec::new();
+        let mut cursor = String::new();
+        loop {
+            let timestamp = epoch_ms() / 1000;
+            let mut query = vec![
+                ("after", after.to_string()),
+                ("before", before.to_string()),
+            ];
+            if !cursor.is_empty() {
+                query.push(("next_cursor", cursor.clone()));
+            }
+            let response = self
+                .client
+                .get(format!("{}/data/trades", self.base_url))
+                .timeout(HISTORY_TIMEOUT)
+                .query(&query)
+                .header("POLY-API-KEY", &self.api_key)
+                .header("POLY-SIGNATURE", &self.api_secret)
+                .header("POLY-TIMESTAMP", timestamp.to_string())
+                .send()
+                .await
+                .map_err(ExecutionError::from_transport)?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let body = response.text().await.unwrap_or_default();
+                return Err(ExecutionError::from_response(status, body));
+            }
+
+            let page: TradesPage = response
+                .json()
+                .await
+                .map_err(|e| ExecutionError::InvalidResponse(e.to_string()))?;
+            fills.extend(page.data.iter().flat_map(|trade| trade.fills(&self.api_key)));
+            if page.next_cursor.is_empty()
+                || page.next_cursor == END_CURSOR
+                || page.next_cursor == cursor
+            {
+                break;
+            }
+            cursor = page.next_cursor;
+        }
+        Ok(fills)
+    }
+
+    /// Collateral (USDC) in the wallet. Dry-run mode has none to report.
+    pub async fn collateral_balance(&self) -> ExecutionResult<Option<f64>> {
+        if self.dry_run {
+            return Ok(None);
+        }
+
+        let timestamp = epoch_ms() / 1000;
+        let response = self
+            .client
+            .get(format!("{}/balance-allowance", self.base_url))
+            .timeout(HISTORY_TIMEOUT)
+            .query(&[("asset_type", "COLLATERAL")])
+            .header("POLY-API-KEY", &self.api_key)
+            .header("POLY-SIGNATURE", &self.api_secret)
+            .header("POLY-TIMESTAMP", timestamp.to_string())
+            .send()
+            .await
+            .map_err(ExecutionError::from_transport)?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(ExecutionError::from_response(status, body));
+        }
+
+        let body: BalanceAllowanceResponse = response
+            .json()
+            .await
+            .map_err(|e| ExecutionError::InvalidResponse(e.to_string()))?;
+        body.balance
+            .parse::<f64>()
+            .map(|units| Some(units / USDC_UNIT))
+            .map_err(|e| ExecutionError::InvalidResponse(format!("balance: {}", e)))
+    }
+
     /// Get paper trading statistics if paper trader is enabled.
     ///
     /// Returns None if not in dry-run mode or paper trader is not available.
@@ -338,3 +1431,264 @@ impl OrderManager {
         self.paper_trader.as_ref().map(|pt| pt.get_stats())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{
+        ClipperConfig, CostConfig, OrderGuardConfig, OrderQueueConfig, RiskConfig,
+        SniperConfig, SumTo100Config,
+    };
+    use crate::execution::mock_exchange::MockExchangeConfig;
+    use crate::market::DepthLevel;
+
+    async fn dry_run_manager() -> OrderManager {
+        let config = Config {
+            ws_url: "wss://test.com".into(),
+            clob_url: "https://test.com".into(),
+            private_key: "0x1234".into(),
+            api_key: "test-key".into(),
+            api_secret: "test-secret".into(),
+            dry_run: true,
+            observe: false,
+            risk: RiskConfig::default(),
+            sniper: SniperConfig::default(),
+            clipper: ClipperConfig::default(),
+            sum_to_100: SumTo100Config::default(),
+            sum_to_100_instances: Default::default(),
+            order_guard: OrderGuardConfig::default(),
+            order_queue: OrderQueueConfig::default(),
+            cost: CostConfig::default(),
+            strategies: None,
+            instance_id: "default".into(),
+            redis_prefix: "poly".into(),
+            trading_timezone: "UTC".into(),
+        };
+        OrderManager::new(config, None).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_presigned_dry_run_order_submits_as_contested_buy() {
+        let manager = dry_run_manager().await;
+        let order = manager
+            .presign_buy(&"token1".to_string(), 0.95, 10.0)
+            .await
+            .unwrap();
+        assert!(order.body.is_empty());
+        assert!(matches!(order.side, Side::Buy));
+
+        let order_id = manager.submit_presigned(&order, None).await.unwrap();
+        assert!(order_id.starts_with("dry-run-"));
+    }
+
+    #[tokio::test]
+    async fn test_orders_failing_market_rules_are_rejected_before_sending() {
+        let mut manager = dry_run_manager().await;
+        let market_data = Arc::new(MarketData::new());
+        manager.market_data = Some(market_data.clone());
+        let token = "token1".to_string();
+        market_data.update_price(&token, 0.29, 0.31);
+
+        let err = manager.place_buy(&token, 0.99, 10.0).await.unwrap_err();
+        assert_eq!(err.reason(), "price_limit");
+        let err = manager.place_sell(&token, 0.0, 10.0).await.unwrap_err();
+        assert_eq!(err.reason(), "price_limit");
+        assert!(manager.place_buy(&token, 0.31, 10.0).await.is_ok());
+
+        market_data.set_min_order_size(&token, 5.0);
+        let err = manager.place_buy(&token, 0.31, 4.999).await.unwrap_err();
+        assert_eq!(err.reason(), "below_min_size");
+
+        // Race orders are priced at the limit on purpose
+        let order = manager.presign_buy(&token, 0.95, 10.0).await.unwrap();
+        assert!(manager.submit_presigned(&order, None).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_paper_bid_fills_when_ask_reaches_price() {
+        let mut manager = dry_run_manager().await;
+        let market_data = Arc::new(MarketData::new());
+        manager.market_data = Some(market_data.clone());
+        let token = "token1".to_string();
+
+        market_data.update_price(&token, 0.40, 0.45);
+        let order_id = manager.place_bid(&token, 0.41, 10.0).await.unwrap();
+        let fill = manager.order_fill(&order_id, &token, 0.41, 10.0).await.unwrap();
+        assert_eq!(
+            fill,
+            OrderFill {
+                filled: 0.0,
+                open: true
+            }
+        );
+
+        market_data.update_price(&token, 0.39, 0.41);
+        let fill = manager.order_fill(&order_id, &token, 0.41, 10.0).await.unwrap();
+        assert_eq!(
+            fill,
+            OrderFill {
+                filled: 10.0,
+                open: false
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mock_exchange_runs_bid_lifecycle() {
+        let mut manager = dry_run_manager().await;
+        let market_data = Arc::new(MarketData::new());
+        manager.market_data = Some(market_data.clone());
+        let mock = Arc::new(MockExchange::new(
+            MockExchangeConfig {
+                participation: 1.0,
+                ..MockExchangeConfig::default()
+            },
+            market_data.clone(),
+        ));
+        manager.set_mock_exchange(mock.clone());
+        let token = "token1".to_string();
+        market_data.update_order_book(
+            &token,
+            vec![DepthLevel::new(0.40, 100.0)],
+            vec![DepthLevel::new(0.45, 100.0)],
+        );
+
+        let order_id = manager.place_bid(&token, 0.41, 10.0).await.unwrap();
+        assert!(order_id.starts_with("mock-"));
+        let fill = manager
+            .order_fill(&order_id, &token, 0.41, 10.0)
+            .await
+            .unwrap();
+        assert_eq!(
+            fill,
+            OrderFill {
+                filled: 0.0,
+                open: true
+            }
+        );
+
+        market_data.update_order_book(
+            &token,
+            vec![DepthLevel::new(0.39, 100.0)],
+            vec![DepthLevel::new(0.41, 4.0), DepthLevel::new(0.43, 100.0)],
+        );
+        mock.match_resting();
+        manager.cancel_order(&order_id).await.unwrap();
+        let fill = manager
+            .order_fill(&order_id, &token, 0.41, 10.0)
+            .await
+            .unwrap();
+        assert_eq!(
+            fill,
+            OrderFill {
+                filled: 4.0,
+                open: false
+            }
+        );
+
+        // Taker orders cross the book on acceptance
+        let order_id = manager.place_sell(&token, 0.39, 10.0).await.unwrap();
+        let fill = manager
+            .order_fill(&order_id, &token, 0.39, 10.0)
+            .await
+            .unwrap();
+        assert_eq!(
+            fill,
+            OrderFill {
+                filled: 10.0,
+                open: false
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_market_order_sweeps_book_within_limit() {
+        let mut manager = dry_run_manager().await;
+        let market_data = Arc::new(MarketData::new());
+        manager.market_data = Some(market_data.clone());
+        let mock = Arc::new(MockExchange::new(
+            MockExchangeConfig::default(),
+            market_data.clone(),
+        ));
+        manager.set_mock_exchange(mock);
+        let token = "token1".to_string();
+        market_data.update_order_book(
+            &token,
+            vec![DepthLevel::new(0.40, 100.0)],
+            vec![
+                DepthLevel::new(0.41, 5.0),
+                DepthLevel::new(0.42, 5.0),
+                DepthLevel::new(0.50, 100.0),
+            ],
+        );
+
+        // Takes the 10 shares offered within the limit and rests nothing
+        let order = manager
+            .place_market(&token, Side::Buy, 20.0, 0.45, OrderPriority::Urgent)
+            .await
+            .unwrap();
+        assert_eq!(order.size, 10.0);
+        assert_eq!(order.worst_price, 0.42);
+        assert!((order.avg_price - 0.415).abs() < 1e-9);
+        let fill = manager
+            .order_fill(&order.order_id, &token, 0.42, 10.0)
+            .await
+            .unwrap();
+        assert_eq!(
+            fill,
+            OrderFill {
+                filled: 10.0,
+                open: false
+            }
+        );
+
+        // Nothing within the limit
+        assert!(manager
+            .place_market(&token, Side::Sell, 10.0, 0.45, OrderPriority::Taker)
+            .await
+            .is_err());
+        assert_eq!(ExecutionStyle::parse(" Market "), Some(ExecutionStyle::Market));
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_opens_on_infrastructure_failures() {
+        let manager = dry_run_manager().await;
+
+        // Order-specific rejections never trip the breaker
+        for _ in 0..CIRCUIT_BREAKER_THRESHOLD {
+            manager.record_failure(&ExecutionError::InvalidOrder("bad tick".into()));
+        }
+        assert!(!manager.is_circuit_open());
+
+        for _ in 0..CIRCUIT_BREAKER_THRESHOLD {
+            manager.record_failure(&ExecutionError::Timeout);
+        }
+        assert!(manager.is_circuit_open());
+        assert!(matches!(
+            manager.check_circuit(),
+            Err(ExecutionError::CircuitOpen(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_drain_waits_for_in_flight_orders_then_refuses_new_ones() {
+        let manager = Arc::new(dry_run_manager().await);
+        let guard = manager.begin_order().unwrap();
+        assert_eq!(manager.in_flight(), 1);
+        let draining = tokio::spawn({
+            let manager = manager.clone();
+            async move { manager.drain().await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!draining.is_finished());
+        drop(guard);
+        tokio::time::timeout(Duration::from_secs(5), draining)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let result = manager.place_buy(&"token1".to_string(), 0.5, 10.0).await;
+        assert!(matches!(result, Err(ExecutionError::ShuttingDown)));
+        assert_eq!(manager.in_flight(), 0);
+    }
+}
@@ -0,0 +1,328 @@
+//! Priority admission for live CLOB requests.
+//!
+//! Every live order submission and cancel passes through an `OrderQueue`
+//! before it is sent. While the client-side rate limit has room (and the
+//! exchange has not answered 429), requests go straight through. Otherwise
+//! they wait and are released one token at a time by priority class, so a
+//! cancel or hedge is not stuck behind a backlog of routine entries. A
+//! waiting request moves up one class per `ORDER_QUEUE_AGING_MS`; within a
+//! class requests are first come, first served.
+
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use tokio::sync::Notify;
+use tokio::time::Instant;
+
+use crate::config::OrderQueueConfig;
+use crate::metrics::{ORDER_QUEUE_DEPTH, ORDER_QUEUE_WAIT};
+
+/// How long a waiter that is not next in line sleeps between checks when
+/// tokens are available (the waiter that is next wakes the rest)
+const RECHECK_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Priority class of a CLOB request, highest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum OrderPriority {
+    /// Cancels and hedges: reduce risk, must not wait behind entries
+    Urgent,
+    /// Orders that take liquidity (arbitrage legs, Sniper, exits)
+    Taker,
+    /// Resting quotes
+    Passive,
+}
+
+impl OrderPriority {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OrderPriority::Urgent => "urgent",
+            OrderPriority::Taker => "taker",
+            OrderPriority::Passive => "passive",
+        }
+    }
+
+    fn rank(self) -> u32 {
+        self as u32
+    }
+}
+
+#[derive(Debug)]
+struct Waiter {
+    id: u64,
+    priority: OrderPriority,
+    enqueued: Instant,
+}
+
+#[derive(Debug)]
+struct State {
+    /// Available send tokens (unused when the rate is unlimited)
+    tokens: f64,
+    refilled: Instant,
+    /// Sends are held until this time after a 429
+    paused_until: Option<Instant>,
+    waiters: Vec<Waiter>,
+    next_id: u64,
+}
+
+/// Rate limiter that releases waiting requests by priority.
+pub struct OrderQueue {
+    config: OrderQueueConfig,
+    state: Mutex<State>,
+    notify: Notify,
+}
+
+/// Outcome of one check by a waiting request
+enum Turn {
+    Go,
+    Wait(Duration),
+}
+
+impl OrderQueue {
+    pub fn new(config: OrderQueueConfig) -> Self {
+        let state = State {
+            tokens: f64::from(config.burst),
+            refilled: Instant::now(),
+            paused_until: None,
+            waiters: Vec::new(),
+            next_id: 0,
+        };
+        Self {
+            config,
+            state: Mutex::new(state),
+            notify: Notify::new(),
+        }
+    }
+
+    fn is_limited(&self) -> bool {
+        self.config.rate_per_sec > 0.0
+    }
+
+    /// Wait until a request of `priority` may be sent.
+    pub async fn acquire(&self, priority: OrderPriority) {
+        let started = Instant::now();
+        let id = {
+            let mut state = self.state.lock();
+            if state.waiters.is_empty() && self.try_take(&mut state, started) {
+                ORDER_QUEUE_WAIT
+                    .with_label_values(&[priority.as_str()])
+                    .observe(0.0);
+                return;
+            }
+            let id = state.next_id;
+            state.next_id += 1;
+            state.waiters.push(Waiter {
+                id,
+                priority,
+                enqueued: started,
+            });
+            ORDER_QUEUE_DEPTH.set(state.waiters.len() as f64);
+            id
+        };
+        // Leaves the queue (and wakes the rest) however this future ends
+        let _waiter = WaiterGuard { queue: self, id };
+
+        loop {
+            let notified = self.notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            match self.turn(id) {
+                Turn::Go => break,
+                Turn::Wait(wait) => {
+                    tokio::select! {
+                        _ = tokio::time::sleep(wait) => {}
+                        _ = &mut notified => {}
+                    }
+                }
+            }
+        }
+
+        ORDER_QUEUE_WAIT
+            .with_label_values(&[priority.as_str()])
+            .observe(started.elapsed().as_secs_f64());
+    }
+
+    /// Hold all sends for `duration` (the exchange rate-limited us).
+    pub fn pause(&self, duration: Duration) {
+        let until = Instant::now() + duration;
+        let mut state = self.state.lock();
+        state.paused_until = Some(state.paused_until.map_or(until, |u| u.max(until)));
+    }
+
+    /// Check whether waiter `id` may go now, taking a token if so.
+    fn turn(&self, id: u64) -> Turn {
+        let now = Instant::now();
+        let mut state = self.state.lock();
+
+        if let Some(until) = state.paused_until.filter(|u| *u > now) {
+            return Turn::Wait(until - now);
+        }
+        let next = state
+            .waiters
+            .iter()
+            .min_by_key(|w| (self.effective_rank(w, now), w.id))
+            .map(|w| w.id);
+        if next != Some(id) {
+            return Turn::Wait(self.time_to_token(&mut state, now).max(RECHECK_INTERVAL));
+        }
+        if self.try_take(&mut state, now) {
+            Turn::Go
+        } else {
+            Turn::Wait(self.time_to_token(&mut state, now))
+        }
+    }
+
+    /// Priority class after aging: one class up per aging interval waited.
+    fn effective_rank(&self, waiter: &Waiter, now: Instant) -> u32 {
+        let waited = now.saturating_duration_since(waiter.enqueued);
+        let promotions = (waited.as_nanos() / self.config.aging.as_nanos().max(1)) as u32;
+        waiter.priority.rank().saturating_sub(promotions)
+    }
+
+    /// Take a send token if one is available.
+    fn try_take(&self, state: &mut State, now: Instant) -> bool {
+        if state.paused_until.is_some_and(|u| u > now) {
+            return false;
+        }
+        if !self.is_limited() {
+            return true;
+        }
+        self.refill(state, now);
+        if state.tokens >= 1.0 {
+            state.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn refill(&self, state: &mut State, now: Instant) {
+        let elapsed = now.saturating_duration_since(state.refilled).as_secs_f64();
+        state.tokens =
+            (state.tokens + elapsed * self.config.rate_per_sec).min(f64::from(self.config.burst));
+        state.refilled = now;
+    }
+
+    /// Time until the next token is available.
+    fn time_to_token(&self, state: &mut State, now: Instant) -> Duration {
+        if !self.is_limited() {
+            return Duration::ZERO;
+        }
+        self.refill(state, now);
+        let missing = (1.0 - state.tokens).max(0.0);
+        Duration::from_secs_f64(missing / self.config.rate_per_sec)
+    }
+}
+
+/// Removes a waiter from the queue when its request proceeds or is dropped.
+struct WaiterGuard<'a> {
+    queue: &'a OrderQueue,
+    id: u64,
+}
+
+impl Drop for WaiterGuard<'_> {
+    fn drop(&mut self) {
+        let mut state = self.queue.state.lock();
+        state.waiters.retain(|w| w.id != self.id);
+        ORDER_QUEUE_DEPTH.set(state.waiters.len() as f64);
+        drop(state);
+        self.queue.notify.notify_waiters();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn queue(rate_per_sec: f64, aging_ms: u64) -> Arc<OrderQueue> {
+        Arc::new(OrderQueue::new(OrderQueueConfig {
+            rate_per_sec,
+            burst: 1,
+            aging: Duration::from_millis(aging_ms),
+        }))
+    }
+
+    /// Queue one request per priority (in the given order) behind an empty
+    /// bucket and return the order they were released in.
+    async fn release_order(
+        queue: Arc<OrderQueue>,
+        priorities: &[OrderPriority],
+    ) -> Vec<OrderPriority> {
+        queue.acquire(OrderPriority::Taker).await; // drain the burst
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        for &priority in priorities {
+            let queue = queue.clone();
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                queue.acquire(priority).await;
+                let _ = tx.send(priority);
+            });
+            tokio::time::sleep(Duration::from_millis(2)).await;
+        }
+        drop(tx);
+
+        let mut released = Vec::new();
+        while let Some(priority) = rx.recv().await {
+            released.push(priority);
+        }
+        released
+    }
+
+    #[tokio::test]
+    async fn test_releases_by_priority_when_saturated() {
+        let released = release_order(
+            queue(40.0, 10_000),
+            &[
+                OrderPriority::Passive,
+                OrderPriority::Taker,
+                OrderPriority::Urgent,
+            ],
+        )
+        .await;
+        assert_eq!(
+            released,
+            vec![
+                OrderPriority::Urgent,
+                OrderPriority::Taker,
+                OrderPriority::Passive
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_aging_prevents_starvation() {
+        // Aged past every class, waiters are served first come, first served
+        let released = release_order(
+            queue(40.0, 1),
+            &[
+                OrderPriority::Passive,
+                OrderPriority::Taker,
+                OrderPriority::Urgent,
+            ],
+        )
+        .await;
+        assert_eq!(
+            released,
+            vec![
+                OrderPriority::Passive,
+                OrderPriority::Taker,
+                OrderPriority::Urgent
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unlimited_queue_only_waits_for_pause() {
+        let queue = queue(0.0, 250);
+        for _ in 0..100 {
+            queue.acquire(OrderPriority::Passive).await;
+        }
+
+        queue.pause(Duration::from_millis(30));
+        let started = std::time::Instant::now();
+        queue.acquire(OrderPriority::Urgent).await;
+        assert!(started.elapsed() >= Duration::from_millis(25));
+        assert!(queue.state.lock().waiters.is_empty());
+    }
+}
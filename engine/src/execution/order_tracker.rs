@@ -0,0 +1,300 @@
+//! Order lifecycle tracking.
+//!
+//! An order accepted by the CLOB has not necessarily traded: it can rest
+//! unfilled, fill in part, or be cancelled or expire. With
+//! `ORDER_TRACKING_ENABLED` every taker order the engine places, including
+//! the hedger's and the exit scheduler's, is tracked here and polled
+//! (`/data/order/{id}`) every `ORDER_TRACKING_POLL_MS` until it is filled or
+//! closed, moving through `OrderState`s. The engine reserves a tracked
+//! order's size with the risk manager, records the trade as PLACED and books
+//! P&L, and a FILLED trade, only for confirmed fills. An order still open
+//! after `ORDER_TRACKING_MAX_AGE_MS` is cancelled and dropped once the
+//! cancel succeeds. Dry-run orders simulated by the paper trader fill on
+//! placement.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use tracing::{debug, info};
+
+use crate::market::TokenId;
+use crate::metrics::ORDER_STATES;
+
+use super::order_manager::{OrderFill, Side};
+
+/// Fill size below which an order counts as fully filled
+const FILL_EPSILON: f64 = 1e-9;
+
+/// Order tracking settings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrderTrackingConfig {
+    pub enabled: bool,
+    /// Time between status polls of open orders
+    pub poll_interval: Duration,
+    /// Age at which an order still open is cancelled and no longer tracked
+    pub max_age: Duration,
+}
+
+impl Default for OrderTrackingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            poll_interval: Duration::from_secs(1),
+            max_age: Duration::from_secs(60),
+        }
+    }
+}
+
+impl OrderTrackingConfig {
+    /// Load from `ORDER_TRACKING_ENABLED`, `ORDER_TRACKING_POLL_MS` and
+    /// `ORDER_TRACKING_MAX_AGE_MS`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        Self {
+            enabled: var("ORDER_TRACKING_ENABLED")
+                .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+                .unwrap_or(defaults.enabled),
+            poll_interval: var("ORDER_TRACKING_POLL_MS")
+                .and_then(|v| v.parse().ok())
+                .filter(|ms| *ms > 0)
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.poll_interval),
+            max_age: var("ORDER_TRACKING_MAX_AGE_MS")
+                .and_then(|v| v.parse().ok())
+                .filter(|ms| *ms > 0)
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.max_age),
+        }
+    }
+}
+
+/// Where an order is in its life
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderState {
+    /// Resting, nothing filled yet
+    Open,
+    /// Resting with part of its size filled
+    PartiallyFilled,
+    /// Fully filled
+    Filled,
+    /// Closed (cancelled or expired) before filling in full
+    Cancelled,
+}
+
+impl OrderState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OrderState::Open => "open",
+            OrderState::PartiallyFilled => "partially_filled",
+            OrderState::Filled => "filled",
+            OrderState::Cancelled => "cancelled",
+        }
+    }
+
+    /// Whether the order can still fill
+    pub fn is_open(self) -> bool {
+        matches!(self, OrderState::Open | OrderState::PartiallyFilled)
+    }
+
+    fn of(fill: OrderFill, size: f64) -> Self {
+        match (fill.open, fill.filled > 0.0) {
+            (true, false) => OrderState::Open,
+            (true, true) => OrderState::PartiallyFilled,
+            (false, _) if fill.filled >= size - FILL_EPSILON => OrderState::Filled,
+            (false, _) => OrderState::Cancelled,
+        }
+    }
+}
+
+/// An order being tracked
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackedOrder {
+    pub order_id: String,
+    pub token_id: TokenId,
+    pub side: Side,
+    pub price: f64,
+    pub size: f64,
+    /// Size confirmed filled so far
+    pub filled: f64,
+    pub state: OrderState,
+    pub placed_at: Instant,
+}
+
+/// A status change of a tracked order
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderUpdate {
+    /// The order after the change
+    pub order: TrackedOrder,
+    /// Size filled since the last update
+    pub new_fill: f64,
+}
+
+/// Per-order state of placed orders until they are filled or closed
+#[derive(Debug)]
+pub struct OrderTracker {
+    config: OrderTrackingConfig,
+    orders: Mutex<BTreeMap<String, TrackedOrder>>,
+}
+
+impl OrderTracker {
+    pub fn new(config: OrderTrackingConfig) -> Self {
+        Self {
+            config,
+            orders: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn poll_interval(&self) -> Duration {
+        self.config.poll_interval
+    }
+
+    pub fn max_age(&self) -> Duration {
+        self.config.max_age
+    }
+
+    /// Start tracking a placed order.
+    pub fn track(&self, order_id: &str, token_id: &TokenId, side: Side, price: f64, size: f64) {
+        ORDER_STATES
+            .with_label_values(&[OrderState::Open.as_str()])
+            .inc();
+        self.orders.lock().insert(
+            order_id.to_string(),
+            TrackedOrder {
+                order_id: order_id.to_string(),
+                token_id: token_id.clone(),
+                side,
+                price,
+                size,
+                filled: 0.0,
+                state: OrderState::Open,
+                placed_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Orders that can still fill, oldest first.
+    pub fn open_orders(&self) -> Vec<TrackedOrder> {
+        let mut orders: Vec<TrackedOrder> = self.orders.lock().values().cloned().collect();
+        orders.sort_by_key(|order| order.placed_at);
+        orders
+    }
+
+    pub fn len(&self) -> usize {
+        self.orders.lock().len()
+    }
+
+    /// Apply a polled fill state. Returns the update if the fill or state
+    /// changed; orders that are done are no longer tracked. Fills never go
+    /// backwards or past the order's size.
+    pub fn apply(&self, order_id: &str, fill: OrderFill) -> Option<OrderUpdate> {
+        let mut orders = self.orders.lock();
+        let order = orders.get_mut(order_id)?;
+        let filled = fill.filled.clamp(order.filled, order.size);
+        let new_fill = filled - order.filled;
+        let state = OrderState::of(
+            OrderFill {
+                filled,
+                open: fill.open,
+            },
+            order.size,
+        );
+        if new_fill <= 0.0 && state == order.state {
+            return None;
+        }
+        if state != order.state {
+            ORDER_STATES.with_label_values(&[state.as_str()]).inc();
+            debug!(
+                "[ORDERS] {} {} -> {} ({:.2}/{:.2} filled)",
+                order_id,
+                order.state.as_str(),
+                state.as_str(),
+                filled,
+                order.size
+            );
+        }
+        order.filled = filled;
+        order.state = state;
+        let order = if state.is_open() {
+            order.clone()
+        } else {
+            let order = orders.remove(order_id)?;
+            if state == OrderState::Cancelled {
+                info!(
+                    "[ORDERS] {} closed with {:.2}/{:.2} filled",
+                    order_id, order.filled, order.size
+                );
+            }
+            order
+        };
+        Some(OrderUpdate { order, new_fill })
+    }
+
+    /// Stop tracking an order at its last confirmed fill, e.g. once it is
+    /// cancelled for age and its status cannot be read.
+    pub fn close(&self, order_id: &str) -> Option<OrderUpdate> {
+        let filled = self.orders.lock().get(order_id)?.filled;
+        self.apply(order_id, OrderFill { filled, open: false })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker() -> OrderTracker {
+        let tracker = OrderTracker::new(OrderTrackingConfig {
+            enabled: true,
+            ..OrderTrackingConfig::default()
+        });
+        tracker.track("o1", &"tok".to_string(), Side::Buy, 0.5, 10.0);
+        tracker
+    }
+
+    fn fill(filled: f64, open: bool) -> OrderFill {
+        OrderFill { filled, open }
+    }
+
+    #[test]
+    fn test_order_moves_through_partial_fill_to_filled() {
+        let tracker = tracker();
+        assert_eq!(tracker.apply("o1", fill(0.0, true)), None);
+
+        let update = tracker.apply("o1", fill(4.0, true)).unwrap();
+        assert_eq!(update.order.state, OrderState::PartiallyFilled);
+        assert_eq!(update.new_fill, 4.0);
+        // A stale poll reporting less changes nothing
+        assert_eq!(tracker.apply("o1", fill(3.0, true)), None);
+
+        let update = tracker.apply("o1", fill(10.0, false)).unwrap();
+        assert_eq!(update.order.state, OrderState::Filled);
+        assert_eq!(update.new_fill, 6.0);
+        assert_eq!(tracker.len(), 0);
+        assert_eq!(tracker.apply("o1", fill(10.0, false)), None);
+    }
+
+    #[test]
+    fn test_closed_before_full_fill_is_cancelled() {
+        let tracker = tracker();
+        let update = tracker.apply("o1", fill(2.5, false)).unwrap();
+        assert_eq!(update.order.state, OrderState::Cancelled);
+        assert_eq!(update.order.filled, 2.5);
+        assert!(tracker.open_orders().is_empty());
+    }
+
+    #[test]
+    fn test_close_drops_order_at_last_fill() {
+        let tracker = tracker();
+        tracker.apply("o1", fill(3.0, true)).unwrap();
+
+        let update = tracker.close("o1").unwrap();
+        assert_eq!(update.order.state, OrderState::Cancelled);
+        assert_eq!(update.order.filled, 3.0);
+        assert_eq!(update.new_fill, 0.0);
+        assert_eq!(tracker.len(), 0);
+        assert_eq!(tracker.close("o1"), None);
+    }
+}
@@ -10,7 +10,8 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use tracing::info;
 
 use crate::execution::Side;
-use crate::market::{MarketData, TokenId};
+use crate::market::{ImpactModel, MarketData, TokenId};
+use crate::strategy::CostModel;
 
 /// A simulated fill
 #[allow(dead_code)]
@@ -34,6 +35,44 @@ pub struct PaperArbTrade {
     pub timestamp_ns: u64,
 }
 
+/// Fill-probability model for orders that race other traders for a stale quote.
+///
+/// Sniping a stale ask only works if we reach the book before everyone else
+/// who saw the same event. The chance of filling decays with the time since
+/// the event and shrinks when our order is larger than the displayed size.
+#[derive(Debug, Clone, Copy)]
+pub struct ContestedFillModel {
+    /// Per-second hazard rate of the stale quote being taken by competitors
+    pub competition_factor: f64,
+    /// Event age assumed when the caller doesn't know when the event happened (ms)
+    pub default_event_age_ms: u64,
+}
+
+impl ContestedFillModel {
+    /// Probability (0.0 - 1.0) that an order of `order_size` fills against
+    /// `displayed_size` at the best ask, `event_age_ms` after the triggering event.
+    pub fn fill_probability(&self, displayed_size: f64, order_size: f64, event_age_ms: u64) -> f64 {
+        if displayed_size <= 0.0 || order_size <= 0.0 {
+            return 0.0;
+        }
+
+        let size_factor = (displayed_size / order_size).min(1.0);
+        let age_secs = event_age_ms as f64 / 1000.0;
+        let survival = (-self.competition_factor.max(0.0) * age_secs).exp();
+
+        (size_factor * survival).clamp(0.0, 1.0)
+    }
+}
+
+impl Default for ContestedFillModel {
+    fn default() -> Self {
+        Self {
+            competition_factor: 0.5,
+            default_event_age_ms: 1000,
+        }
+    }
+}
+
 /// Paper trading simulator for validating strategies
 #[allow(dead_code)]
 pub struct PaperTrader {
@@ -41,22 +80,42 @@ pub struct PaperTrader {
     arb_trades: RwLock<Vec<PaperArbTrade>>,
     total_pnl_cents: AtomicU64, // Store as cents to use atomic
     trade_count: AtomicU64,
-    fee_rate: f64,
+    /// Fees and slippage charged on simulated arbitrage
+    cost_model: CostModel,
+    /// Fill model for contested (time-sensitive) buys
+    contested_fill_model: ContestedFillModel,
+    /// Contested buys that lost the race to competitors
+    missed_fills: AtomicU64,
+    /// Participation cap and depth haircut applied to simulated fills
+    impact_model: ImpactModel,
 }
 
 #[allow(dead_code)]
 impl PaperTrader {
     /// Create a new paper trader
-    pub fn new(fee_rate: f64) -> Self {
+    pub fn new(cost_model: CostModel) -> Self {
         Self {
             fills: RwLock::new(Vec::new()),
             arb_trades: RwLock::new(Vec::new()),
             total_pnl_cents: AtomicU64::new(0),
             trade_count: AtomicU64::new(0),
-            fee_rate,
+            cost_model,
+            contested_fill_model: ContestedFillModel::default(),
+            missed_fills: AtomicU64::new(0),
+            impact_model: ImpactModel::none(),
         }
     }
 
+    /// Set the market impact model applied to simulated fills.
+    pub fn set_impact_model(&mut self, model: ImpactModel) {
+        self.impact_model = model;
+    }
+
+    /// Set the fill model used for contested buys.
+    pub fn set_contested_fill_model(&mut self, model: ContestedFillModel) {
+        self.contested_fill_model = model;
+    }
+
     /// Get current timestamp in nanoseconds
     fn now_ns() -> u64 {
         SystemTime::now()
@@ -73,7 +132,7 @@ impl PaperTrader {
         target_size: f64,
     ) -> Option<PaperFill> {
         let book = market_data.get_order_book(token_id)?;
-        let vwap = book.vwap_buy(target_size)?;
+        let vwap = book.vwap_buy_with_impact(target_size, &self.impact_model)?;
 
         let fill = PaperFill {
             token_id: token_id.clone(),
@@ -87,6 +146,88 @@ impl PaperTrader {
         Some(fill)
     }
 
+    /// Simulate a limit order that takes liquidity: it fills against the
+    /// levels at or better than `limit` only (for backtests).
+    pub fn simulate_limit(
+        &self,
+        market_data: &MarketData,
+        token_id: &TokenId,
+        side: Side,
+        target_size: f64,
+        limit: f64,
+    ) -> Option<PaperFill> {
+        let book = market_data.get_order_book(token_id)?;
+        let vwap = match side {
+            Side::Buy => book.vwap_buy_within(target_size, limit),
+            Side::Sell => book.vwap_sell_within(target_size, limit),
+        }?;
+
+        let fill = PaperFill {
+            token_id: token_id.clone(),
+            side,
+            price: vwap.vwap,
+            size: vwap.total_size,
+            timestamp_ns: Self::now_ns(),
+        };
+
+        self.fills.write().push(fill.clone());
+        Some(fill)
+    }
+
+    /// Simulate a buy that races competitors for a stale quote (e.g. Sniper).
+    ///
+    /// Returns `None` when there is no order book data, `Some(None)` when the
+    /// simulated order lost the race, and `Some(Some(fill))` when it filled.
+    pub fn simulate_contested_buy(
+        &self,
+        market_data: &MarketData,
+        token_id: &TokenId,
+        target_size: f64,
+        event_age_ms: Option<u64>,
+    ) -> Option<Option<PaperFill>> {
+        self.simulate_contested_buy_with_roll(
+            market_data,
+            token_id,
+            target_size,
+            event_age_ms,
+            rand::random::<f64>(),
+        )
+    }
+
+    /// Contested buy with an explicit random roll in [0, 1) (for deterministic tests).
+    fn simulate_contested_buy_with_roll(
+        &self,
+        market_data: &MarketData,
+        token_id: &TokenId,
+        target_size: f64,
+        event_age_ms: Option<u64>,
+        roll: f64,
+    ) -> Option<Option<PaperFill>> {
+        let book = market_data.get_order_book(token_id)?;
+        let displayed_size = book.asks.first()?.size;
+        let event_age_ms = event_age_ms.unwrap_or(self.contested_fill_model.default_event_age_ms);
+
+        let probability =
+            self.contested_fill_model
+                .fill_probability(displayed_size, target_size, event_age_ms);
+
+        if roll >= probability {
+            self.missed_fills.fetch_add(1, Ordering::Relaxed);
+            info!(
+                "[PAPER] Contested buy missed: {} | p_fill={:.2} | displayed={:.0} | event_age={}ms",
+                token_id, probability, displayed_size, event_age_ms
+            );
+            return Some(None);
+        }
+
+        Some(self.simulate_buy(market_data, token_id, target_size))
+    }
+
+    /// Get number of contested buys that lost the race
+    pub fn get_missed_fills(&self) -> u64 {
+        self.missed_fills.load(Ordering::Relaxed)
+    }
+
     /// Simulate an arbitrage trade (buy YES + buy NO)
     pub fn simulate_arb_trade(
         &self,
@@ -101,11 +242,12 @@ impl PaperTrader {
         // Calculate actual fillable size (min of both)
         let actual_size = yes_fill.size.min(no_fill.size);
 
-        // Calculate profits
-        let total_cost = yes_fill.price * actual_size + no_fill.price * actual_size;
-        let total_fees = total_cost * self.fee_rate;
-        let gross_profit = actual_size - total_cost; // 1 share YES + 1 share NO = $1
-        let net_profit = gross_profit - total_fees;
+        // Calculate profits (1 share YES + 1 share NO = $1)
+        let costs = self
+            .cost_model
+            .arbitrage(yes_fill.price, no_fill.price, actual_size);
+        let gross_profit = costs.gross_edge.unwrap_or_default();
+        let net_profit = costs.net_edge.unwrap_or_default();
 
         let trade = PaperArbTrade {
             yes_fill,
@@ -184,6 +326,7 @@ impl PaperTrader {
         self.arb_trades.write().clear();
         self.total_pnl_cents.store(0, Ordering::Relaxed);
         self.trade_count.store(0, Ordering::Relaxed);
+        self.missed_fills.store(0, Ordering::Relaxed);
     }
 }
 
@@ -206,7 +349,7 @@ mod tests {
 
     #[test]
     fn test_paper_buy() {
-        let trader = PaperTrader::new(0.01);
+        let trader = PaperTrader::new(CostModel::default());
         let market_data = MarketData::new();
 
         let pair = MarketPair {
@@ -214,6 +357,8 @@ mod tests {
             yes_token: "yes".into(),
             no_token: "no".into(),
             question: "Test?".into(),
+            category: None,
+            end_date: None,
         };
         market_data.register_pair(pair);
 
@@ -233,7 +378,7 @@ mod tests {
 
     #[test]
     fn test_paper_arb_trade() {
-        let trader = PaperTrader::new(0.01);
+        let trader = PaperTrader::new(CostModel::default());
         let market_data = MarketData::new();
 
         let pair = MarketPair {
@@ -241,6 +386,8 @@ mod tests {
             yes_token: "yes".into(),
             no_token: "no".into(),
             question: "Test?".into(),
+            category: None,
+            end_date: None,
         };
         market_data.register_pair(pair);
 
@@ -265,4 +412,89 @@ mod tests {
         assert!(trade.net_profit > 0.0);
         assert!(trader.get_pnl() > 0.0);
     }
+
+    #[test]
+    fn test_contested_fill_probability() {
+        let model = ContestedFillModel {
+            competition_factor: 0.5,
+            default_event_age_ms: 1000,
+        };
+
+        // Instant reaction with ample displayed size always fills
+        assert!((model.fill_probability(100.0, 10.0, 0) - 1.0).abs() < 1e-9);
+
+        // Probability decays with event age
+        let p_1s = model.fill_probability(100.0, 10.0, 1000);
+        let p_5s = model.fill_probability(100.0, 10.0, 5000);
+        assert!((p_1s - (-0.5f64).exp()).abs() < 1e-9);
+        assert!(p_5s < p_1s);
+
+        // Displayed size smaller than our order scales the probability down
+        let p_thin = model.fill_probability(5.0, 10.0, 0);
+        assert!((p_thin - 0.5).abs() < 1e-9);
+
+        // No displayed liquidity never fills
+        assert_eq!(model.fill_probability(0.0, 10.0, 0), 0.0);
+    }
+
+    #[test]
+    fn test_contested_buy_miss_and_fill() {
+        let trader = PaperTrader::new(CostModel::default());
+        let market_data = MarketData::new();
+        market_data.update_order_book(
+            &"yes".into(),
+            vec![DepthLevel::new(0.60, 100.0)],
+            vec![DepthLevel::new(0.62, 100.0)],
+        );
+
+        // p_fill = exp(-0.5 * 2) ~= 0.37
+        let missed = trader.simulate_contested_buy_with_roll(
+            &market_data,
+            &"yes".into(),
+            10.0,
+            Some(2000),
+            0.9,
+        );
+        assert!(matches!(missed, Some(None)));
+        assert_eq!(trader.get_missed_fills(), 1);
+
+        let filled = trader.simulate_contested_buy_with_roll(
+            &market_data,
+            &"yes".into(),
+            10.0,
+            Some(2000),
+            0.1,
+        );
+        let fill = filled.flatten().expect("expected a fill");
+        assert!((fill.price - 0.62).abs() < 0.001);
+
+        // Unknown tokens have no book to simulate against
+        assert!(trader
+            .simulate_contested_buy(&market_data, &"unknown".into(), 10.0, None)
+            .is_none());
+    }
+
+    #[test]
+    fn test_paper_buy_with_impact_model() {
+        let mut trader = PaperTrader::new(CostModel::default());
+        trader.set_impact_model(ImpactModel {
+            max_participation: 0.5,
+            depth_haircut: 0.0,
+        });
+        let market_data = MarketData::new();
+
+        market_data.update_order_book(
+            &"yes".into(),
+            vec![DepthLevel::new(0.44, 100.0)],
+            vec![DepthLevel::new(0.45, 60.0), DepthLevel::new(0.47, 100.0)],
+        );
+
+        // Only 30 shares at 0.45 are takeable, the rest walks to 0.47
+        let fill = trader
+            .simulate_buy(&market_data, &"yes".into(), 50.0)
+            .unwrap();
+        let expected = (30.0 * 0.45 + 20.0 * 0.47) / 50.0;
+        assert!((fill.price - expected).abs() < 0.0001);
+        assert!((fill.size - 50.0).abs() < 0.001);
+    }
 }
@@ -0,0 +1,103 @@
+//! Pre-send sanity limits on order prices.
+//!
+//! The last line of defence against a bug upstream emitting, say, a buy at
+//! 0.99 on a token trading at 0.30: every order must fall inside its token's
+//! absolute price band and, when the book has both sides, within
+//! `ORDER_MAX_MID_DEVIATION` of the current mid.
+
+use crate::config::{OrderGuardConfig, PriceBand};
+
+/// Why an order price was rejected
+#[derive(Debug, Clone, PartialEq)]
+pub enum PriceViolation {
+    /// Outside the token's absolute band
+    OutsideBand { band: PriceBand },
+    /// Too far from the current mid
+    FarFromMid { mid: f64, deviation: f64 },
+}
+
+impl std::fmt::Display for PriceViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PriceViolation::OutsideBand { band } => {
+                write!(f, "outside band ${:.4}-${:.4}", band.min, band.max)
+            }
+            PriceViolation::FarFromMid { mid, deviation } => {
+                write!(f, "{:.1}% from mid ${:.4}", deviation * 100.0, mid)
+            }
+        }
+    }
+}
+
+/// Checks order prices against the configured bands and the mid.
+pub struct PriceGuard {
+    config: OrderGuardConfig,
+}
+
+impl PriceGuard {
+    pub fn new(config: OrderGuardConfig) -> Self {
+        Self { config }
+    }
+
+    /// Check a price for `token_id`; `mid` is skipped when unknown.
+    pub fn check(
+        &self,
+        token_id: &str,
+        price: f64,
+        mid: Option<f64>,
+    ) -> Result<(), PriceViolation> {
+        let band = self.config.band_for(token_id);
+        if !band.contains(price) {
+            return Err(PriceViolation::OutsideBand { band });
+        }
+
+        if let Some(mid) = mid.filter(|m| *m > 0.0) {
+            let deviation = (price - mid).abs() / mid;
+            let max = self.config.max_mid_deviation;
+            if max > 0.0 && deviation > max {
+                return Err(PriceViolation::FarFromMid { mid, deviation });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rejects_prices_outside_band_or_far_from_mid() {
+        let mut config = OrderGuardConfig::default();
+        config
+            .token_bands
+            .insert("capped".into(), PriceBand { min: 0.1, max: 0.6 });
+        let guard = PriceGuard::new(config);
+
+        assert!(guard.check("tok", 0.31, Some(0.30)).is_ok());
+        assert!(guard.check("tok", 0.99, None).is_ok());
+        assert_eq!(
+            guard.check("tok", 0.995, None),
+            Err(PriceViolation::OutsideBand {
+                band: PriceBand {
+                    min: 0.01,
+                    max: 0.99
+                }
+            })
+        );
+        assert!(matches!(
+            guard.check("tok", 0.99, Some(0.30)),
+            Err(PriceViolation::FarFromMid { .. })
+        ));
+        assert!(matches!(
+            guard.check("capped", 0.65, Some(0.64)),
+            Err(PriceViolation::OutsideBand { .. })
+        ));
+
+        let lenient = PriceGuard::new(OrderGuardConfig {
+            max_mid_deviation: 0.0,
+            ..OrderGuardConfig::default()
+        });
+        assert!(lenient.check("tok", 0.99, Some(0.30)).is_ok());
+    }
+}
@@ -0,0 +1,427 @@
+//! Time-sliced execution of large signals.
+//!
+//! Sweeping a large order at once walks the book and signals our intent to
+//! everyone watching it. Buy and sell signals at or above `TWAP_MIN_NOTIONAL`
+//! are instead split into equal child orders sent over `TWAP_DURATION_SECS`.
+//! Before each child the book is checked again: once the best price is
+//! through the parent's limit (the edge is gone), or trading is stopped, the
+//! remaining children are abandoned, as they are once a child would break a
+//! risk limit. Child fills are reported to the strategy engine as they happen
+//! so positions and risk build up with them; a final report carries the
+//! aggregate fill price.
+//!
+//! Arbitrage signals are never sliced: their edge exists only while both
+//! legs are priced together.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::market::{MarketData, OrderRules, TokenId};
+use crate::metrics::TWAP_ORDERS;
+use crate::risk::RiskManager;
+use crate::runtime::{self, TaskGroup};
+use crate::strategy::{SignalMetadata, TradeSignal};
+
+use super::order_manager::{OrderManager, Side};
+
+/// Prices closer than this are the same
+const PRICE_EPSILON: f64 = 1e-9;
+
+/// TWAP execution settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct TwapConfig {
+    /// Buy and sell signals at or above this notional are sliced (0 = never)
+    pub min_notional: f64,
+    /// Child orders per parent
+    pub slices: u32,
+    /// Time over which the children are sent
+    pub duration: Duration,
+}
+
+impl Default for TwapConfig {
+    fn default() -> Self {
+        Self {
+            min_notional: 0.0,
+            slices: 5,
+            duration: Duration::from_secs(60),
+        }
+    }
+}
+
+impl TwapConfig {
+    /// Load from `TWAP_MIN_NOTIONAL`, `TWAP_SLICES` and `TWAP_DURATION_SECS`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok();
+        Self {
+            min_notional: var("TWAP_MIN_NOTIONAL")
+                .and_then(|v| v.parse::<f64>().ok())
+                .filter(|n| n.is_finite() && *n >= 0.0)
+                .unwrap_or(defaults.min_notional),
+            slices: var("TWAP_SLICES")
+                .and_then(|v| v.parse().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.slices),
+            duration: var("TWAP_DURATION_SECS")
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.duration),
+        }
+    }
+}
+
+/// How a sliced order ended
+#[derive(Debug, Clone, PartialEq)]
+pub enum TwapOutcome {
+    /// Every child was filled
+    Completed,
+    /// The best price moved through the limit
+    EdgeGone,
+    /// Emergency stop or shutdown
+    Stopped,
+    /// A child no longer passed the risk checks
+    RiskLimit,
+    /// A child order failed (with its reason)
+    OrderFailed(&'static str),
+}
+
+impl TwapOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TwapOutcome::Completed => "completed",
+            TwapOutcome::EdgeGone => "edge_gone",
+            TwapOutcome::Stopped => "stopped",
+            TwapOutcome::RiskLimit => "risk_limit",
+            TwapOutcome::OrderFailed(_) => "order_failed",
+        }
+    }
+}
+
+/// Summary of a sliced order
+#[derive(Debug, Clone)]
+pub struct TwapReport {
+    pub strategy: &'static str,
+    pub token_id: TokenId,
+    pub side: Side,
+    pub limit_price: f64,
+    pub requested: f64,
+    pub filled: f64,
+    /// Size-weighted price of the filled children (0 if none)
+    pub avg_price: f64,
+    pub slices_sent: usize,
+    pub slices: usize,
+    pub outcome: TwapOutcome,
+    /// The parent signal's metadata
+    pub metadata: SignalMetadata,
+}
+
+/// Progress of a sliced order, sent to the strategy engine
+#[derive(Debug, Clone)]
+pub enum TwapEvent {
+    /// A child order was placed; `signal` carries its size and the price
+    /// it was expected to fill at
+    Fill {
+        strategy: &'static str,
+        signal: TradeSignal,
+        order_id: String,
+        started: Instant,
+    },
+    /// The parent finished or was abandoned
+    Done(TwapReport),
+}
+
+/// Splits large signals into child orders sent over time.
+pub struct TwapExecutor {
+    config: TwapConfig,
+    order_manager: Arc<OrderManager>,
+    market_data: Arc<MarketData>,
+    risk_manager: Arc<RiskManager>,
+    cancel: CancellationToken,
+    /// Tokens with a parent in progress
+    active: Mutex<HashSet<TokenId>>,
+}
+
+impl TwapExecutor {
+    pub fn new(
+        config: TwapConfig,
+        order_manager: Arc<OrderManager>,
+        market_data: Arc<MarketData>,
+        risk_manager: Arc<RiskManager>,
+        cancel: CancellationToken,
+    ) -> Self {
+        Self {
+            config,
+            order_manager,
+            market_data,
+            risk_manager,
+            cancel,
+            active: Mutex::new(HashSet::new()),
+        }
+    }
+
+    pub fn config(&self) -> &TwapConfig {
+        &self.config
+    }
+
+    /// Whether `signal` is large enough to be sliced.
+    pub fn should_slice(&self, signal: &TradeSignal) -> bool {
+        self.config.min_notional > 0.0
+            && self.config.slices > 1
+            && matches!(signal, TradeSignal::Buy { .. } | TradeSignal::Sell { .. })
+            && signal.notional() >= self.config.min_notional
+    }
+
+    /// Start slicing `signal` in the background, reporting to `events`.
+    /// Returns false if a sliced order for the same token is in progress.
+    pub fn start(
+        self: &Arc<Self>,
+        strategy: &'static str,
+        signal: TradeSignal,
+        events: UnboundedSender<TwapEvent>,
+    ) -> bool {
+        let (token_id, side, limit_price, size, reason, metadata) = match signal {
+            TradeSignal::Buy {
+                token_id,
+                price,
+                size,
+                reason,
+                metadata,
+            } => (token_id, Side::Buy, price, size, reason, metadata),
+            TradeSignal::Sell {
+                token_id,
+                price,
+                size,
+                reason,
+                metadata,
+            } => (token_id, Side::Sell, price, size, reason, metadata),
+            _ => return false,
+        };
+        if !self.active.lock().insert(token_id.clone()) {
+            return false;
+        }
+
+        let parent = Parent {
+            strategy,
+            token_id,
+            side,
+            limit_price,
+            size,
+            reason,
+            metadata,
+        };
+        let executor = Arc::clone(self);
+        runtime::spawn(TaskGroup::Engine, async move {
+            let report = executor.execute(&parent, &events).await;
+            executor.active.lock().remove(&parent.token_id);
+            TWAP_ORDERS
+                .with_label_values(&[report.outcome.as_str()])
+                .inc();
+            let _ = events.send(TwapEvent::Done(report));
+        });
+        true
+    }
+
+    /// Send the children of `parent`, stopping early when the edge is gone.
+    async fn execute(&self, parent: &Parent, events: &UnboundedSender<TwapEvent>) -> TwapReport {
+        let rules = self.market_data.order_rules(&parent.token_id);
+        let children = plan_slices(parent.size, self.config.slices, &rules);
+        let gap = self.config.duration / children.len().max(1) as u32;
+        info!(
+            "[TWAP] {} {} {:.2} @ {:.4} in {} slices over {:?} ({})",
+            parent.strategy,
+            match parent.side {
+                Side::Buy => "BUY",
+                Side::Sell => "SELL",
+            },
+            parent.size,
+            parent.limit_price,
+            children.len(),
+            self.config.duration,
+            parent.token_id
+        );
+
+        let mut report = TwapReport {
+            strategy: parent.strategy,
+            token_id: parent.token_id.clone(),
+            side: parent.side,
+            limit_price: parent.limit_price,
+            requested: parent.size,
+            filled: 0.0,
+            avg_price: 0.0,
+            slices_sent: 0,
+            slices: children.len(),
+            outcome: TwapOutcome::Completed,
+            metadata: parent.metadata.clone(),
+        };
+        let mut value = 0.0;
+
+        for (i, &child_size) in children.iter().enumerate() {
+            if i > 0 {
+                tokio::select! {
+                    _ = tokio::time::sleep(gap) => {}
+                    _ = self.cancel.cancelled() => {}
+                }
+            }
+            if self.cancel.is_cancelled() || self.risk_manager.is_emergency_stopped() {
+                report.outcome = TwapOutcome::Stopped;
+                break;
+            }
+            let Some(price) = self.child_price(parent) else {
+                report.outcome = TwapOutcome::EdgeGone;
+                break;
+            };
+
+            // Positions may have moved since the parent passed its checks
+            let reason = format!("{} [TWAP {}/{}]", parent.reason, i + 1, children.len());
+            let signal = parent.child(price, child_size, reason);
+            if !self.risk_manager.check_signal(&signal) {
+                report.outcome = TwapOutcome::RiskLimit;
+                break;
+            }
+
+            let started = Instant::now();
+            report.slices_sent += 1;
+            let placed = match parent.side {
+                Side::Buy => {
+                    self.order_manager
+                        .place_buy(&parent.token_id, parent.limit_price, child_size)
+                        .await
+                }
+                Side::Sell => {
+                    self.order_manager
+                        .place_sell(&parent.token_id, parent.limit_price, child_size)
+                        .await
+                }
+            };
+            match placed {
+                Ok(order_id) => {
+                    report.filled += child_size;
+                    value += price * child_size;
+                    let _ = events.send(TwapEvent::Fill {
+                        strategy: parent.strategy,
+                        signal,
+                        order_id,
+                        started,
+                    });
+                }
+                Err(e) => {
+                    warn!("[TWAP] Child order {} failed: {}", i + 1, e);
+                    report.outcome = TwapOutcome::OrderFailed(e.reason());
+                    break;
+                }
+            }
+        }
+
+        if report.filled > 0.0 {
+            report.avg_price = value / report.filled;
+        }
+        report
+    }
+
+    /// Expected fill price of the next child, or None once the best price
+    /// is through the parent's limit.
+    fn child_price(&self, parent: &Parent) -> Option<f64> {
+        let quote = self.market_data.get_price(&parent.token_id)?;
+        child_price(parent.side, parent.limit_price, quote.bid, quote.ask)
+    }
+}
+
+/// A signal being sliced
+#[derive(Debug)]
+struct Parent {
+    strategy: &'static str,
+    token_id: TokenId,
+    side: Side,
+    limit_price: f64,
+    size: f64,
+    reason: String,
+    /// Copied to every child
+    metadata: SignalMetadata,
+}
+
+impl Parent {
+    /// A child order of `size` expected to fill at `price`.
+    fn child(&self, price: f64, size: f64, reason: String) -> TradeSignal {
+        let token_id = self.token_id.clone();
+        match self.side {
+            Side::Buy => TradeSignal::Buy {
+                token_id,
+                price,
+                size,
+                reason,
+                metadata: self.metadata.clone(),
+            },
+            Side::Sell => TradeSignal::Sell {
+                token_id,
+                price,
+                size,
+                reason,
+                metadata: self.metadata.clone(),
+            },
+        }
+    }
+}
+
+/// Price a child is expected to fill at: the best opposite price, if it is
+/// still within the limit.
+fn child_price(side: Side, limit: f64, bid: f64, ask: f64) -> Option<f64> {
+    match side {
+        Side::Buy => (ask > 0.0 && ask <= limit + PRICE_EPSILON).then_some(ask),
+        Side::Sell => (bid > 0.0 && bid >= limit - PRICE_EPSILON).then_some(bid),
+    }
+}
+
+/// Child sizes for `total` split into up to `slices` orders. Fewer slices
+/// are used when equal children would fall below the minimum order size;
+/// the last child takes the rounding remainder.
+fn plan_slices(total: f64, slices: u32, rules: &OrderRules) -> Vec<f64> {
+    let mut n = slices.max(1);
+    while n > 1 && rules.round_size(total / f64::from(n)).is_none() {
+        n -= 1;
+    }
+    let Some(child) = rules.round_size(total / f64::from(n)).filter(|_| n > 1) else {
+        return vec![total];
+    };
+    let mut sizes = vec![child; n as usize - 1];
+    let rest = total - child * f64::from(n - 1);
+    sizes.push(rules.round_size(rest).unwrap_or(rest));
+    sizes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_slices_respects_minimum_and_keeps_total() {
+        let rules = OrderRules::default();
+        let sizes = plan_slices(100.0, 3, &rules);
+        assert_eq!(sizes, vec![33.33, 33.33, 33.34]);
+
+        let rules = OrderRules {
+            min_order_size: Some(15.0),
+            ..OrderRules::default()
+        };
+        // 5 slices of 10 would be under the minimum: 3 slices of 16.66+
+        let sizes = plan_slices(50.0, 5, &rules);
+        assert_eq!(sizes.len(), 3);
+        assert!((sizes.iter().sum::<f64>() - 50.0).abs() < 1e-9);
+        assert_eq!(plan_slices(20.0, 5, &rules), vec![20.0]);
+    }
+
+    #[test]
+    fn test_child_price_stops_when_edge_is_gone() {
+        assert_eq!(child_price(Side::Buy, 0.50, 0.47, 0.48), Some(0.48));
+        assert_eq!(child_price(Side::Buy, 0.50, 0.49, 0.50), Some(0.50));
+        assert_eq!(child_price(Side::Buy, 0.50, 0.50, 0.51), None);
+
+        assert_eq!(child_price(Side::Sell, 0.60, 0.62, 0.64), Some(0.62));
+        assert_eq!(child_price(Side::Sell, 0.60, 0.59, 0.61), None);
+    }
+}
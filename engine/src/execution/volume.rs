@@ -0,0 +1,273 @@
+//! Monthly maker/taker volume per market.
+//!
+//! If Polymarket introduces volume-based fee tiers, the tier depends on the
+//! account's own monthly volume. Every fill the engine books is counted here
+//! by market and liquidity role: resting bid fills as maker, everything else
+//! as taker. Counts roll over at the start of each month in the trading
+//! timezone, keeping the previous month's totals. Exposed on `GET /stats` and in the daily Slack
+//! volume report.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use parking_lot::Mutex;
+use serde::Serialize;
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+
+use crate::notifications::{MarketVolumeLine, SlackNotifier, VolumeReport};
+use crate::timezone::TradingTimezone;
+
+/// Markets listed in the daily report, by volume
+const REPORT_TOP_MARKETS: usize = 5;
+
+/// Volume by liquidity role
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct RoleVolume {
+    pub maker_volume: f64,
+    pub taker_volume: f64,
+    pub maker_fills: u64,
+    pub taker_fills: u64,
+}
+
+impl RoleVolume {
+    fn add(&mut self, notional: f64, maker: bool) {
+        if maker {
+            self.maker_volume += notional;
+            self.maker_fills += 1;
+        } else {
+            self.taker_volume += notional;
+            self.taker_fills += 1;
+        }
+    }
+
+    fn merge(&mut self, other: &RoleVolume) {
+        self.maker_volume += other.maker_volume;
+        self.taker_volume += other.taker_volume;
+        self.maker_fills += other.maker_fills;
+        self.taker_fills += other.taker_fills;
+    }
+
+    /// Maker plus taker volume
+    pub fn total(&self) -> f64 {
+        self.maker_volume + self.taker_volume
+    }
+}
+
+/// Totals for a closed month
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MonthVolume {
+    /// `YYYY-MM` (trading timezone)
+    pub month: String,
+    #[serde(flatten)]
+    pub total: RoleVolume,
+}
+
+/// Month-to-date volume, as served on `/stats`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VolumeSnapshot {
+    /// `YYYY-MM` (trading timezone)
+    pub month: String,
+    pub total: RoleVolume,
+    pub markets: BTreeMap<String, RoleVolume>,
+    /// The month before, once a rollover has happened since startup
+    pub previous: Option<MonthVolume>,
+}
+
+struct VolumeState {
+    month: String,
+    markets: BTreeMap<String, RoleVolume>,
+    previous: Option<MonthVolume>,
+}
+
+impl VolumeState {
+    /// Close the month if `now` is in a later one.
+    fn roll(&mut self, month: String) {
+        if month == self.month {
+            return;
+        }
+        let mut total = RoleVolume::default();
+        for volume in self.markets.values() {
+            total.merge(volume);
+        }
+        info!(
+            "[VOLUME] {} closed: maker ${:.2}, taker ${:.2}",
+            self.month, total.maker_volume, total.taker_volume
+        );
+        self.previous = Some(MonthVolume {
+            month: std::mem::replace(&mut self.month, month),
+            total,
+        });
+        self.markets.clear();
+    }
+}
+
+/// Monthly maker/taker volume per market
+pub struct VolumeTracker {
+    timezone: TradingTimezone,
+    state: Mutex<VolumeState>,
+}
+
+impl VolumeTracker {
+    pub fn new(timezone: TradingTimezone) -> Self {
+        Self::starting(timezone, Utc::now())
+    }
+
+    fn starting(timezone: TradingTimezone, now: DateTime<Utc>) -> Self {
+        Self {
+            timezone,
+            state: Mutex::new(VolumeState {
+                month: timezone.month_key(now),
+                markets: BTreeMap::new(),
+                previous: None,
+            }),
+        }
+    }
+
+    /// Count a fill of `notional` dollars in `market_id`.
+    pub fn record(&self, market_id: &str, notional: f64, maker: bool) {
+        self.record_at(market_id, notional, maker, Utc::now());
+    }
+
+    fn record_at(&self, market_id: &str, notional: f64, maker: bool, now: DateTime<Utc>) {
+        if notional <= 0.0 {
+            return;
+        }
+        let mut state = self.state.lock();
+        state.roll(self.timezone.month_key(now));
+        state
+            .markets
+            .entry(market_id.to_string())
+            .or_default()
+            .add(notional, maker);
+    }
+
+    /// Month-to-date volume.
+    pub fn snapshot(&self) -> VolumeSnapshot {
+        self.snapshot_at(Utc::now())
+    }
+
+    fn snapshot_at(&self, now: DateTime<Utc>) -> VolumeSnapshot {
+        let mut state = self.state.lock();
+        state.roll(self.timezone.month_key(now));
+        let mut total = RoleVolume::default();
+        for volume in state.markets.values() {
+            total.merge(volume);
+        }
+        VolumeSnapshot {
+            month: state.month.clone(),
+            total,
+            markets: state.markets.clone(),
+            previous: state.previous.clone(),
+        }
+    }
+
+    /// Daily report for `date`, sent at `now`.
+    fn report_at(&self, date: NaiveDate, now: DateTime<Utc>) -> VolumeReport {
+        let snapshot = self.snapshot_at(now);
+        let mut markets: Vec<_> = snapshot.markets.into_iter().collect();
+        markets.sort_by(|(_, a), (_, b)| b.total().total_cmp(&a.total()));
+        VolumeReport {
+            date: date.to_string(),
+            month: snapshot.month,
+            maker_volume: snapshot.total.maker_volume,
+            taker_volume: snapshot.total.taker_volume,
+            maker_fills: snapshot.total.maker_fills,
+            taker_fills: snapshot.total.taker_fills,
+            markets: markets
+                .into_iter()
+                .take(REPORT_TOP_MARKETS)
+                .map(|(market_id, volume)| MarketVolumeLine {
+                    market_id,
+                    maker_volume: volume.maker_volume,
+                    taker_volume: volume.taker_volume,
+                })
+                .collect(),
+            previous_month: snapshot.previous.as_ref().map(|p| p.month.clone()),
+            previous_volume: snapshot.previous.as_ref().map(|p| p.total.total()),
+        }
+    }
+
+    /// Send the volume report to Slack as each trading day ends until
+    /// cancelled.
+    pub async fn run_daily_report(
+        self: Arc<Self>,
+        slack: Arc<SlackNotifier>,
+        cancel: CancellationToken,
+    ) {
+        loop {
+            let now = Utc::now();
+            let today = self.timezone.date(now);
+            tokio::select! {
+                _ = tokio::time::sleep(self.timezone.until_next_day(now)) => {
+                    slack.notify_volume_report(self.report_at(today, Utc::now()));
+                }
+                _ = cancel.cancelled() => break,
+            }
+        }
+    }
+}
+
+impl Default for VolumeTracker {
+    fn default() -> Self {
+        Self::new(TradingTimezone::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_volume_by_role_and_monthly_rollover() {
+        let march = Utc.with_ymd_and_hms(2026, 3, 31, 23, 0, 0).unwrap();
+        let tracker = VolumeTracker::starting(TradingTimezone::default(), march);
+        tracker.record_at("m1", 40.0, true, march);
+        tracker.record_at("m1", 10.0, false, march);
+        tracker.record_at("m2", 25.0, false, march);
+        tracker.record_at("m2", 0.0, true, march);
+
+        let snapshot = tracker.snapshot_at(march);
+        assert_eq!(snapshot.month, "2026-03");
+        assert_eq!(snapshot.total.maker_volume, 40.0);
+        assert_eq!(snapshot.total.taker_volume, 35.0);
+        assert_eq!(snapshot.total.taker_fills, 2);
+        assert_eq!(snapshot.markets["m1"].total(), 50.0);
+
+        let report = tracker.report_at(march.date_naive(), march);
+        assert_eq!(report.markets[0].market_id, "m1");
+
+        // First fill in April closes March
+        let april = Utc.with_ymd_and_hms(2026, 4, 1, 0, 0, 5).unwrap();
+        tracker.record_at("m2", 5.0, true, april);
+        let snapshot = tracker.snapshot_at(april);
+        assert_eq!(snapshot.month, "2026-04");
+        assert_eq!(snapshot.total.maker_volume, 5.0);
+        assert_eq!(snapshot.markets.len(), 1);
+        let previous = snapshot.previous.unwrap();
+        assert_eq!(previous.month, "2026-03");
+        assert_eq!(previous.total.total(), 75.0);
+
+        let report = tracker.report_at(march.date_naive(), april);
+        assert_eq!(report.date, "2026-03-31");
+        assert_eq!(report.previous_month.as_deref(), Some("2026-03"));
+    }
+
+    #[test]
+    fn test_month_rolls_over_in_trading_timezone() {
+        let tokyo = TradingTimezone::parse("Asia/Tokyo").unwrap();
+        // 2026-03-31 16:00 UTC is already April 1st in Tokyo
+        let march = Utc.with_ymd_and_hms(2026, 3, 31, 14, 0, 0).unwrap();
+        let april = Utc.with_ymd_and_hms(2026, 3, 31, 16, 0, 0).unwrap();
+        let tracker = VolumeTracker::starting(tokyo, march);
+        tracker.record_at("m1", 10.0, true, march);
+        tracker.record_at("m1", 4.0, false, april);
+
+        let snapshot = tracker.snapshot_at(april);
+        assert_eq!(snapshot.month, "2026-04");
+        assert_eq!(snapshot.total.total(), 4.0);
+        assert_eq!(snapshot.previous.unwrap().month, "2026-03");
+    }
+}
@@ -1,17 +1,29 @@
 //! ESPN API client for sports data.
 //!
 //! Polls ESPN for game results to enable time arbitrage on sports markets.
+//!
+//! Each league is polled on its own schedule: at the configured interval
+//! while a game is live, idle until shortly before the next scheduled start,
+//! and with exponential backoff after errors. Requests are conditional
+//! (`If-None-Match`) so an unchanged scoreboard costs a 304.
 
 use anyhow::{Context, Result};
-use reqwest::Client;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use parking_lot::Mutex;
+use rand::Rng;
+use reqwest::header::{ETAG, IF_NONE_MATCH};
+use reqwest::{Client, StatusCode};
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::RwLock;
-use tokio::time::interval;
+use tokio::sync::{broadcast, RwLock};
+use tokio::time::Instant;
 use tracing::{debug, info, warn};
 
+use crate::metrics::{ESPN_FETCHES, ESPN_FETCH_LATENCY};
+use crate::redis::now_ms;
+
 /// Supported sports leagues.
 #[allow(dead_code)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -23,6 +35,27 @@ pub enum League {
 }
 
 impl League {
+    /// Parse a league name as used in `SNIPER_LEAGUES` (case-insensitive).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "nfl" => Some(League::Nfl),
+            "nba" => Some(League::Nba),
+            "mlb" => Some(League::Mlb),
+            "nhl" => Some(League::Nhl),
+            _ => None,
+        }
+    }
+
+    /// Lowercase name, as used in `SNIPER_LEAGUES` and metric labels.
+    pub fn name(&self) -> &'static str {
+        match self {
+            League::Nfl => "nfl",
+            League::Nba => "nba",
+            League::Mlb => "mlb",
+            League::Nhl => "nhl",
+        }
+    }
+
     /// Get ESPN API path for this league.
     fn api_path(&self) -> &'static str {
         match self {
@@ -32,6 +65,159 @@ impl League {
             League::Nhl => "hockey/nhl",
         }
     }
+
+    /// Regulation periods and period length in seconds (None for MLB,
+    /// which has no game clock).
+    pub fn regulation(&self) -> Option<(u32, f64)> {
+        match self {
+            League::Nfl => Some((4, 900.0)),
+            League::Nba => Some((4, 720.0)),
+            League::Nhl => Some((3, 1200.0)),
+            League::Mlb => None,
+        }
+    }
+}
+
+/// Poll pacing settings for `EspnClient`
+#[derive(Debug, Clone, PartialEq)]
+pub struct EspnPollConfig {
+    /// Interval for leagues with no game live or about to start
+    pub idle_interval: Duration,
+
+    /// Upper bound for the backoff after failed fetches
+    pub max_backoff: Duration,
+
+    /// Random spread applied to each poll delay (0.1 = +/-10%)
+    pub jitter: f64,
+}
+
+impl Default for EspnPollConfig {
+    fn default() -> Self {
+        Self {
+            idle_interval: Duration::from_secs(300),
+            max_backoff: Duration::from_secs(60),
+            jitter: 0.1,
+        }
+    }
+}
+
+impl EspnPollConfig {
+    /// Load from `ESPN_IDLE_POLL_MS`, `ESPN_MAX_BACKOFF_MS` and
+    /// `ESPN_POLL_JITTER` (clamped to 0..=0.5).
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let duration = |name: &str, default: Duration| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|ms| *ms > 0)
+                .map(Duration::from_millis)
+                .unwrap_or(default)
+        };
+        Self {
+            idle_interval: duration("ESPN_IDLE_POLL_MS", defaults.idle_interval),
+            max_backoff: duration("ESPN_MAX_BACKOFF_MS", defaults.max_backoff),
+            jitter: std::env::var("ESPN_POLL_JITTER")
+                .ok()
+                .and_then(|v| v.parse::<f64>().ok())
+                .filter(|j| j.is_finite())
+                .map(|j| j.clamp(0.0, 0.5))
+                .unwrap_or(defaults.jitter),
+        }
+    }
+
+    /// Delay before the next poll of a league, before jitter.
+    fn next_delay(
+        &self,
+        interval: Duration,
+        activity: Activity,
+        errors: u32,
+        now: DateTime<Utc>,
+    ) -> Duration {
+        let idle = self.idle_interval.max(interval);
+        if errors > 0 {
+            let backoff = interval.saturating_mul(1 << errors.min(16));
+            return backoff.min(self.max_backoff).max(interval);
+        }
+        match activity {
+            Activity::Live => interval,
+            Activity::StartsAt(start) => (start - now)
+                .to_std()
+                .unwrap_or(Duration::ZERO)
+                .clamp(interval, idle),
+            Activity::Idle => idle,
+        }
+    }
+
+    /// Spread `delay` by up to `jitter` in either direction (`unit` in
+    /// -1..=1), so leagues do not poll in lockstep.
+    fn jittered(&self, delay: Duration, unit: f64) -> Duration {
+        delay.mul_f64((1.0 + self.jitter * unit).max(0.0))
+    }
+}
+
+/// How soon a league's scoreboard needs polling again
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Activity {
+    /// A game is in progress (or scheduled without a known start)
+    #[default]
+    Live,
+    /// No game in progress; the next one starts at this time
+    StartsAt(DateTime<Utc>),
+    /// No games left on today's scoreboard
+    Idle,
+}
+
+impl Activity {
+    /// Classify a scoreboard from its games' status and start time.
+    fn of(games: &[(GameStatus, Option<DateTime<Utc>>)]) -> Self {
+        if games
+            .iter()
+            .any(|(status, _)| *status == GameStatus::InProgress)
+        {
+            return Activity::Live;
+        }
+        let mut next_start: Option<DateTime<Utc>> = None;
+        for (status, start) in games {
+            if *status != GameStatus::Scheduled {
+                continue;
+            }
+            match start {
+                Some(start) => next_start = Some(next_start.map_or(*start, |n| n.min(*start))),
+                None => return Activity::Live,
+            }
+        }
+        next_start.map_or(Activity::Idle, Activity::StartsAt)
+    }
+}
+
+/// Poll state for one league
+#[derive(Debug, Default)]
+struct LeaguePoll {
+    /// ETag of the last scoreboard, sent back as `If-None-Match`
+    etag: Option<String>,
+    activity: Activity,
+    /// Consecutive failed fetches
+    errors: u32,
+}
+
+/// Result of a successful fetch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FetchOutcome {
+    Updated,
+    NotModified,
+}
+
+/// Parse an ESPN event date ("2024-01-15T00:30Z", seconds optional).
+fn parse_start(date: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(date)
+        .map(|d| d.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NaiveDateTime::parse_from_str(date, "%Y-%m-%dT%H:%MZ")
+                .ok()
+                .map(|d| d.and_utc())
+        })
 }
 
 /// Game status.
@@ -56,6 +242,12 @@ pub struct Game {
     pub home_score: u32,
     pub away_score: u32,
     pub status: GameStatus,
+    /// Current period (quarter, period; 0 before the start)
+    pub period: u32,
+    /// Seconds left in the current period
+    pub clock_secs: f64,
+    /// When the poll that reported this state returned (ms since epoch)
+    pub observed_at_ms: u64,
 }
 
 #[allow(dead_code)]
@@ -70,6 +262,15 @@ impl Game {
         self.status == GameStatus::Final && self.away_score > self.home_score
     }
 
+    /// Seconds left in regulation (overtime: in the current period).
+    ///
+    /// None for leagues without a game clock.
+    pub fn seconds_remaining(&self) -> Option<f64> {
+        let (periods, period_secs) = self.league.regulation()?;
+        let remaining_periods = periods.saturating_sub(self.period.max(1));
+        Some(f64::from(remaining_periods) * period_secs + self.clock_secs.max(0.0))
+    }
+
     /// Get the winner team name.
     pub fn winner(&self) -> Option<&str> {
         if self.status != GameStatus::Final {
@@ -94,6 +295,9 @@ struct EspnResponse {
 #[derive(Debug, Deserialize)]
 struct EspnEvent {
     id: String,
+    /// Scheduled start
+    #[serde(default)]
+    date: Option<String>,
     competitions: Vec<EspnCompetition>,
     status: EspnStatus,
 }
@@ -121,6 +325,11 @@ struct EspnTeam {
 struct EspnStatus {
     #[serde(rename = "type")]
     status_type: EspnStatusType,
+    /// Seconds left in the current period
+    #[serde(default)]
+    clock: f64,
+    #[serde(default)]
+    period: u32,
 }
 
 #[derive(Debug, Deserialize)]
@@ -136,10 +345,15 @@ pub struct EspnClient {
     base_url: String,
     leagues: Vec<League>,
     poll_interval_ms: u64,
+    poll_config: EspnPollConfig,
+    /// Conditional-request and scheduling state by league
+    polls: Mutex<HashMap<League, LeaguePoll>>,
     /// Cache of games by league
     games: Arc<RwLock<HashMap<League, Vec<Game>>>>,
     /// Games that have finished (for sniper strategy)
     finished_games: Arc<RwLock<Vec<Game>>>,
+    /// Pushes each game the moment it is first seen final
+    finished_tx: broadcast::Sender<Game>,
 }
 
 #[allow(dead_code)]
@@ -156,49 +370,121 @@ impl EspnClient {
             base_url: "https://site.api.espn.com/apis/site/v2/sports".to_string(),
             leagues,
             poll_interval_ms,
+            poll_config: EspnPollConfig::default(),
+            polls: Mutex::new(HashMap::new()),
             games: Arc::new(RwLock::new(HashMap::new())),
             finished_games: Arc::new(RwLock::new(Vec::new())),
+            finished_tx: broadcast::channel(64).0,
         })
     }
 
+    /// Override the poll pacing settings.
+    pub fn set_poll_config(&mut self, config: EspnPollConfig) {
+        self.poll_config = config;
+    }
+
     /// Start the polling loop.
     pub async fn run(&self) {
-        info!("ESPN client starting for leagues: {:?}", self.leagues);
+        info!(
+            "ESPN client starting for leagues: {:?} ({:?})",
+            self.leagues, self.poll_config
+        );
 
-        let mut ticker = interval(Duration::from_millis(self.poll_interval_ms));
+        let mut next_poll: HashMap<League, Instant> = self
+            .leagues
+            .iter()
+            .map(|league| (*league, Instant::now()))
+            .collect();
 
-        loop {
-            ticker.tick().await;
+        while let Some(due) = next_poll.values().min().copied() {
+            tokio::time::sleep_until(due).await;
 
+            let now = Instant::now();
             for league in &self.leagues {
-                if let Err(e) = self.fetch_league(*league).await {
-                    warn!("Failed to fetch {:?}: {}", league, e);
+                if next_poll.get(league).is_some_and(|at| *at <= now) {
+                    let delay = self.poll_league(*league).await;
+                    next_poll.insert(*league, Instant::now() + delay);
                 }
             }
         }
     }
 
+    /// Fetch one league, record metrics, and return the delay until its next
+    /// poll.
+    async fn poll_league(&self, league: League) -> Duration {
+        let timer = ESPN_FETCH_LATENCY
+            .with_label_values(&[league.name()])
+            .start_timer();
+        let result = self.fetch_league(league).await;
+        timer.observe_duration();
+
+        let mut polls = self.polls.lock();
+        let poll = polls.entry(league).or_default();
+        let outcome = match result {
+            Ok(FetchOutcome::Updated) => "ok",
+            Ok(FetchOutcome::NotModified) => "not_modified",
+            Err(e) => {
+                poll.errors = poll.errors.saturating_add(1);
+                warn!(
+                    "Failed to fetch {:?} ({} in a row): {}",
+                    league, poll.errors, e
+                );
+                "error"
+            }
+        };
+        ESPN_FETCHES
+            .with_label_values(&[league.name(), outcome])
+            .inc();
+        if outcome != "error" {
+            poll.errors = 0;
+        }
+
+        let delay = self.poll_config.next_delay(
+            Duration::from_millis(self.poll_interval_ms),
+            poll.activity,
+            poll.errors,
+            Utc::now(),
+        );
+        self.poll_config
+            .jittered(delay, rand::thread_rng().gen_range(-1.0..=1.0))
+    }
+
     /// Fetch games for a specific league.
-    async fn fetch_league(&self, league: League) -> Result<()> {
+    async fn fetch_league(&self, league: League) -> Result<FetchOutcome> {
         let url = format!("{}/{}/scoreboard", self.base_url, league.api_path());
 
         debug!("Fetching {:?} from {}", league, url);
 
-        let response: EspnResponse = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .context("Failed to fetch ESPN data")?
+        let mut request = self.client.get(&url);
+        if let Some(etag) = self.polls.lock().get(&league).and_then(|p| p.etag.clone()) {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        let response = request.send().await.context("Failed to fetch ESPN data")?;
+        if response.status() == StatusCode::NOT_MODIFIED {
+            return Ok(FetchOutcome::NotModified);
+        }
+        let response = response
+            .error_for_status()
+            .context("ESPN returned an error status")?;
+        let etag = response
+            .headers()
+            .get(ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let response: EspnResponse = response
             .json()
             .await
             .context("Failed to parse ESPN response")?;
+        let observed_at_ms = now_ms();
 
         let mut new_finished = Vec::new();
         let mut games = Vec::new();
+        let mut schedule = Vec::new();
 
         for event in response.events {
-            let game = self.parse_event(league, event)?;
+            let start = event.date.as_deref().and_then(parse_start);
+            let game = self.parse_event(league, event, observed_at_ms)?;
+            schedule.push((game.status.clone(), start));
 
             // Check if this is a newly finished game
             if game.status == GameStatus::Final {
@@ -216,6 +502,8 @@ impl EspnClient {
                         game.away_team,
                         game.winner()
                     );
+                    // Notify subscribers before any bookkeeping (latency-critical)
+                    let _ = self.finished_tx.send(game.clone());
                     new_finished.push(game.clone());
                 }
             }
@@ -241,11 +529,16 @@ impl EspnClient {
             }
         }
 
-        Ok(())
+        let mut polls = self.polls.lock();
+        let poll = polls.entry(league).or_default();
+        poll.etag = etag;
+        poll.activity = Activity::of(&schedule);
+
+        Ok(FetchOutcome::Updated)
     }
 
     /// Parse an ESPN event into a Game.
-    fn parse_event(&self, league: League, event: EspnEvent) -> Result<Game> {
+    fn parse_event(&self, league: League, event: EspnEvent, observed_at_ms: u64) -> Result<Game> {
         let competition = event
             .competitions
             .first()
@@ -289,9 +582,17 @@ impl EspnClient {
             home_score,
             away_score,
             status,
+            period: event.status.period,
+            clock_secs: event.status.clock,
+            observed_at_ms,
         })
     }
 
+    /// Receive each game as soon as a poll first sees it final.
+    pub fn subscribe_finished(&self) -> broadcast::Receiver<Game> {
+        self.finished_tx.subscribe()
+    }
+
     /// Get recently finished games (for sniper strategy).
     pub async fn get_finished_games(&self) -> Vec<Game> {
         self.finished_games.read().await.clone()
@@ -303,6 +604,20 @@ impl EspnClient {
         finished.retain(|g| g.id != game_id);
     }
 
+    /// All cached games across leagues without waiting (empty while a poll
+    /// is updating the cache); for synchronous callers such as strategies.
+    pub fn try_all_games(&self) -> Vec<Game> {
+        self.games
+            .try_read()
+            .map(|games| games.values().flatten().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Replace the cached games for a league.
+    pub async fn set_games(&self, league: League, games: Vec<Game>) {
+        self.games.write().await.insert(league, games);
+    }
+
     /// Get all games for a league.
     pub async fn get_games(&self, league: League) -> Vec<Game> {
         self.games
@@ -317,6 +632,7 @@ impl EspnClient {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::TimeZone;
 
     #[test]
     fn test_game_winner() {
@@ -328,6 +644,9 @@ mod tests {
             home_score: 110,
             away_score: 105,
             status: GameStatus::Final,
+            period: 4,
+            clock_secs: 0.0,
+            observed_at_ms: 0,
         };
 
         assert!(game.home_won());
@@ -342,4 +661,114 @@ mod tests {
         assert_eq!(League::Mlb.api_path(), "baseball/mlb");
         assert_eq!(League::Nhl.api_path(), "hockey/nhl");
     }
+
+    #[test]
+    fn test_seconds_remaining() {
+        let mut game = Game {
+            id: "1".to_string(),
+            league: League::Nba,
+            home_team: "Lakers".to_string(),
+            away_team: "Celtics".to_string(),
+            home_score: 0,
+            away_score: 0,
+            status: GameStatus::InProgress,
+            period: 3,
+            clock_secs: 30.0,
+            observed_at_ms: 0,
+        };
+        assert_eq!(game.seconds_remaining(), Some(750.0));
+
+        // Overtime counts only the current period
+        game.period = 5;
+        assert_eq!(game.seconds_remaining(), Some(30.0));
+
+        game.league = League::Mlb;
+        assert_eq!(game.seconds_remaining(), None);
+    }
+
+    #[test]
+    fn test_scoreboard_activity() {
+        let at = |h: u32| Utc.with_ymd_and_hms(2024, 1, 15, h, 0, 0).single();
+
+        assert_eq!(Activity::of(&[]), Activity::Idle);
+        assert_eq!(Activity::of(&[(GameStatus::Final, at(1))]), Activity::Idle);
+        assert_eq!(
+            Activity::of(&[
+                (GameStatus::Scheduled, at(23)),
+                (GameStatus::Scheduled, at(20)),
+                (GameStatus::Final, at(1)),
+            ]),
+            Activity::StartsAt(at(20).unwrap())
+        );
+        assert_eq!(
+            Activity::of(&[
+                (GameStatus::Scheduled, at(23)),
+                (GameStatus::InProgress, at(19)),
+            ]),
+            Activity::Live
+        );
+        assert_eq!(
+            Activity::of(&[(GameStatus::Scheduled, None)]),
+            Activity::Live
+        );
+
+        assert_eq!(parse_start("2024-01-15T20:00Z"), at(20));
+        assert_eq!(parse_start("2024-01-15T20:00:00Z"), at(20));
+        assert_eq!(parse_start("tonight"), None);
+    }
+
+    #[test]
+    fn test_poll_delay() {
+        let config = EspnPollConfig::default();
+        let interval = Duration::from_secs(1);
+        let now = Utc.with_ymd_and_hms(2024, 1, 15, 19, 0, 0).unwrap();
+
+        assert_eq!(
+            config.next_delay(interval, Activity::Live, 0, now),
+            interval
+        );
+        assert_eq!(
+            config.next_delay(interval, Activity::Idle, 0, now),
+            config.idle_interval
+        );
+        // Wake for the next start, but never later than the idle interval
+        let soon = Activity::StartsAt(now + chrono::Duration::seconds(90));
+        assert_eq!(
+            config.next_delay(interval, soon, 0, now),
+            Duration::from_secs(90)
+        );
+        let tonight = Activity::StartsAt(now + chrono::Duration::hours(3));
+        assert_eq!(
+            config.next_delay(interval, tonight, 0, now),
+            config.idle_interval
+        );
+        let late = Activity::StartsAt(now - chrono::Duration::minutes(5));
+        assert_eq!(config.next_delay(interval, late, 0, now), interval);
+
+        // Errors back off exponentially up to the cap
+        assert_eq!(
+            config.next_delay(interval, Activity::Live, 3, now),
+            Duration::from_secs(8)
+        );
+        assert_eq!(
+            config.next_delay(interval, Activity::Live, 40, now),
+            config.max_backoff
+        );
+
+        assert_eq!(
+            config.jittered(Duration::from_secs(10), 1.0),
+            Duration::from_secs(11)
+        );
+        assert_eq!(
+            config.jittered(Duration::from_secs(10), -1.0),
+            Duration::from_secs(9)
+        );
+    }
+
+    #[test]
+    fn test_league_from_name() {
+        assert_eq!(League::from_name("NBA"), Some(League::Nba));
+        assert_eq!(League::from_name(" nfl "), Some(League::Nfl));
+        assert_eq!(League::from_name("epl"), None);
+    }
 }
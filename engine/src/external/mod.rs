@@ -1,6 +1,13 @@
 //! External data sources (ESPN, etc).
 
 mod espn;
+mod polymarket_data;
+pub mod teams;
 
 #[allow(unused_imports)]
-pub use espn::{EspnClient, Game, League};
+pub use espn::{EspnClient, EspnPollConfig, Game, GameStatus, League};
+#[allow(unused_imports)]
+pub use polymarket_data::{
+    run_price_import, Holder, MarketVolume, PolymarketDataClient, PolymarketDataConfig,
+    PriceImport, PricePoint, TokenHolders,
+};
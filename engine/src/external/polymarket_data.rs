@@ -0,0 +1,570 @@
+//! Client for Polymarket's public market data.
+//!
+//! Covers what the order book does not show: price history (CLOB
+//! `/prices-history`), traded volume (Gamma `/markets`) and the largest
+//! holders of each outcome and open interest (data API `/holders` and
+//! `/oi`). None of it needs
+//! credentials. Responses are cached for `POLYMARKET_DATA_CACHE_TTL_MS`
+//! and requests are spaced to `POLYMARKET_DATA_RATE_PER_SEC`, so callers
+//! in loops (discovery filters, liquidity scoring) cannot flood the APIs.
+//!
+//! `--import-prices=<token_id>[,<token_id>...] --from=<time> [--to=<time>]
+//! [--out=<file>]` writes price history as JSON lines for backtests.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::metrics::POLYMARKET_DATA_REQUESTS;
+
+/// Longest range fetched per price history request (the API caps long ranges)
+const HISTORY_WINDOW: Duration = Duration::from_secs(14 * 24 * 3600);
+
+/// Data API settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolymarketDataConfig {
+    pub data_url: String,
+    pub gamma_url: String,
+    /// How long responses are reused
+    pub cache_ttl: Duration,
+    /// Most requests per second across all endpoints (0 = unlimited)
+    pub rate_per_sec: f64,
+}
+
+impl Default for PolymarketDataConfig {
+    fn default() -> Self {
+        Self {
+            data_url: "https://data-api.polymarket.com".to_string(),
+            gamma_url: "https://gamma-api.polymarket.com".to_string(),
+            cache_ttl: Duration::from_secs(300),
+            rate_per_sec: 5.0,
+        }
+    }
+}
+
+impl PolymarketDataConfig {
+    /// Load from `POLYMARKET_DATA_API_URL`, `GAMMA_API_URL`,
+    /// `POLYMARKET_DATA_CACHE_TTL_MS` and `POLYMARKET_DATA_RATE_PER_SEC`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok();
+        Self {
+            data_url: var("POLYMARKET_DATA_API_URL").unwrap_or(defaults.data_url),
+            gamma_url: var("GAMMA_API_URL").unwrap_or(defaults.gamma_url),
+            cache_ttl: var("POLYMARKET_DATA_CACHE_TTL_MS")
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.cache_ttl),
+            rate_per_sec: var("POLYMARKET_DATA_RATE_PER_SEC")
+                .and_then(|v| v.parse::<f64>().ok())
+                .filter(|r| r.is_finite() && *r >= 0.0)
+                .unwrap_or(defaults.rate_per_sec),
+        }
+    }
+}
+
+/// One point of a token's price history
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PricePoint {
+    /// Unix seconds
+    pub t: i64,
+    pub p: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct PriceHistoryResponse {
+    #[serde(default)]
+    history: Vec<PricePoint>,
+}
+
+/// Traded volume of a market, in USD
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct MarketVolume {
+    pub volume_24h: f64,
+    pub volume_total: f64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GammaVolume {
+    #[serde(default)]
+    volume_24hr: Option<f64>,
+    #[serde(default)]
+    volume_num: Option<f64>,
+}
+
+/// A wallet holding one outcome of a market
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Holder {
+    pub proxy_wallet: String,
+    /// Shares held
+    pub amount: f64,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub outcome_index: u32,
+}
+
+/// The largest holders of one outcome token
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenHolders {
+    pub token: String,
+    #[serde(default)]
+    pub holders: Vec<Holder>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenInterest {
+    market: String,
+    value: f64,
+}
+
+/// Responses kept until they are `ttl` old
+struct Cache<K, V> {
+    ttl: Duration,
+    entries: Mutex<HashMap<K, (Instant, V)>>,
+}
+
+impl<K: Eq + Hash, V: Clone> Cache<K, V> {
+    fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// A fresh entry
+    fn get(&self, key: &K, now: Instant) -> Option<V> {
+        let entries = self.entries.lock();
+        let (at, value) = entries.get(key)?;
+        (now.duration_since(*at) < self.ttl).then(|| value.clone())
+    }
+
+    /// An entry of any age
+    fn get_stale(&self, key: &K) -> Option<V> {
+        self.entries.lock().get(key).map(|(_, value)| value.clone())
+    }
+
+    fn insert(&self, key: K, value: V, now: Instant) {
+        let mut entries = self.entries.lock();
+        entries.retain(|_, (at, _)| now.duration_since(*at) < self.ttl);
+        entries.insert(key, (now, value));
+    }
+}
+
+/// Spaces requests to a steady rate
+struct Pacer {
+    interval: Duration,
+    next: tokio::sync::Mutex<Option<tokio::time::Instant>>,
+}
+
+impl Pacer {
+    fn new(rate_per_sec: f64) -> Self {
+        Self {
+            interval: if rate_per_sec > 0.0 {
+                Duration::from_secs_f64(1.0 / rate_per_sec)
+            } else {
+                Duration::ZERO
+            },
+            next: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    /// Wait for the next request slot.
+    async fn wait(&self) {
+        if self.interval.is_zero() {
+            return;
+        }
+        let mut next = self.next.lock().await;
+        let now = tokio::time::Instant::now();
+        let slot = next.map_or(now, |n| n.max(now));
+        *next = Some(slot + self.interval);
+        // Held while sleeping so callers go out in order
+        tokio::time::sleep_until(slot).await;
+    }
+}
+
+/// Cached, rate-limited client for Polymarket's public market data
+pub struct PolymarketDataClient {
+    config: PolymarketDataConfig,
+    clob_url: String,
+    client: Client,
+    pacer: Pacer,
+    history: Cache<(String, i64, i64, u32), Vec<PricePoint>>,
+    volumes: Cache<String, MarketVolume>,
+    holders: Cache<(String, usize), Vec<TokenHolders>>,
+    open_interest: Cache<String, f64>,
+}
+
+impl PolymarketDataClient {
+    /// Create a client; price history comes from `clob_url`.
+    pub fn new(config: PolymarketDataConfig, clob_url: &str) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(15))
+            .build()
+            .context("Failed to create Polymarket data HTTP client")?;
+        Ok(Self {
+            pacer: Pacer::new(config.rate_per_sec),
+            history: Cache::new(config.cache_ttl),
+            volumes: Cache::new(config.cache_ttl),
+            holders: Cache::new(config.cache_ttl),
+            open_interest: Cache::new(config.cache_ttl),
+            clob_url: clob_url.trim_end_matches('/').to_string(),
+            config,
+            client,
+        })
+    }
+
+    /// Prices of a token between `start` and `end` (unix seconds), one
+    /// point per `fidelity_min` minutes.
+    pub async fn prices_history(
+        &self,
+        token_id: &str,
+        start: i64,
+        end: i64,
+        fidelity_min: u32,
+    ) -> Result<Vec<PricePoint>> {
+        let key = (token_id.to_string(), start, end, fidelity_min);
+        if let Some(points) = self.history.get(&key, Instant::now()) {
+            return Ok(points);
+        }
+        let url = format!("{}/prices-history", self.clob_url);
+        let query = [
+            ("market", token_id.to_string()),
+            ("startTs", start.to_string()),
+            ("endTs", end.to_string()),
+            ("fidelity", fidelity_min.to_string()),
+        ];
+        let response: PriceHistoryResponse = self.get("prices_history", &url, &query).await?;
+        self.history
+            .insert(key, response.history.clone(), Instant::now());
+        Ok(response.history)
+    }
+
+    /// Traded volume of a market (condition ID).
+    pub async fn market_volume(&self, market_id: &str) -> Result<MarketVolume> {
+        let key = market_id.to_string();
+        if let Some(volume) = self.volumes.get(&key, Instant::now()) {
+            return Ok(volume);
+        }
+        let url = format!("{}/markets", self.config.gamma_url.trim_end_matches('/'));
+        let markets: Vec<GammaVolume> = self
+            .get("volume", &url, &[("condition_ids", key.clone())])
+            .await?;
+        let Some(market) = markets.into_iter().next() else {
+            bail!("Market {} not found", market_id);
+        };
+        let volume = MarketVolume {
+            volume_24h: market.volume_24hr.unwrap_or(0.0),
+            volume_total: market.volume_num.unwrap_or(0.0),
+        };
+        self.volumes.insert(key, volume, Instant::now());
+        Ok(volume)
+    }
+
+    /// Last volume fetched for a market, however old, without a request.
+    pub fn cached_volume(&self, market_id: &str) -> Option<MarketVolume> {
+        self.volumes.get_stale(&market_id.to_string())
+    }
+
+    /// Whether a market's volume is missing from the cache or expired
+    pub fn volume_stale(&self, market_id: &str) -> bool {
+        self.volumes
+            .get(&market_id.to_string(), Instant::now())
+            .is_none()
+    }
+
+    /// The `limit` largest holders of each outcome of a market.
+    pub async fn holders(&self, market_id: &str, limit: usize) -> Result<Vec<TokenHolders>> {
+        let key = (market_id.to_string(), limit);
+        if let Some(holders) = self.holders.get(&key, Instant::now()) {
+            return Ok(holders);
+        }
+        let url = format!("{}/holders", self.config.data_url.trim_end_matches('/'));
+        let query = [
+            ("market", market_id.to_string()),
+            ("limit", limit.to_string()),
+        ];
+        let holders: Vec<TokenHolders> = self.get("holders", &url, &query).await?;
+        self.holders.insert(key, holders.clone(), Instant::now());
+        Ok(holders)
+    }
+
+    /// Open interest of a market, in USD.
+    pub async fn open_interest(&self, market_id: &str) -> Result<f64> {
+        let key = market_id.to_string();
+        if let Some(value) = self.open_interest.get(&key, Instant::now()) {
+            return Ok(value);
+        }
+        let url = format!("{}/oi", self.config.data_url.trim_end_matches('/'));
+        let entries: Vec<OpenInterest> = self
+            .get("open_interest", &url, &[("market", key.clone())])
+            .await?;
+        let Some(entry) = entries.into_iter().find(|e| e.market == key) else {
+            bail!("No open interest for market {}", market_id);
+        };
+        self.open_interest.insert(key, entry.value, Instant::now());
+        Ok(entry.value)
+    }
+
+    async fn get<T: serde::de::DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        url: &str,
+        query: &[(&str, String)],
+    ) -> Result<T> {
+        self.pacer.wait().await;
+        let result = async {
+            self.client
+                .get(url)
+                .query(query)
+                .send()
+                .await
+                .with_context(|| format!("Failed to fetch {}", url))?
+                .error_for_status()
+                .with_context(|| format!("{} returned an error status", url))?
+                .json()
+                .await
+                .with_context(|| format!("Failed to parse {}", url))
+        }
+        .await;
+        let status = if result.is_ok() { "ok" } else { "error" };
+        POLYMARKET_DATA_REQUESTS
+            .with_label_values(&[endpoint, status])
+            .inc();
+        result
+    }
+}
+
+/// A price history import for backtests
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceImport {
+    pub tokens: Vec<String>,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    /// Minutes between points
+    pub fidelity_min: u32,
+    pub out: PathBuf,
+}
+
+impl PriceImport {
+    /// The import from `--import-prices=`, `--from=`, `--to=` (default
+    /// now), `--fidelity=` (minutes, default 1) and `--out=` (default
+    /// `prices.jsonl`), if requested among `args`.
+    pub fn from_args(
+        args: impl IntoIterator<Item = String>,
+        now: DateTime<Utc>,
+    ) -> Result<Option<Self>> {
+        let mut tokens = None;
+        let (mut from, mut to) = (None, None);
+        let mut fidelity_min = 1;
+        let mut out = PathBuf::from("prices.jsonl");
+        for arg in args {
+            if let Some(value) = arg.strip_prefix("--import-prices=") {
+                tokens = Some(
+                    value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|t| !t.is_empty())
+                        .map(str::to_string)
+                        .collect::<Vec<_>>(),
+                );
+            } else if let Some(value) = arg.strip_prefix("--from=") {
+                from = Some(parse_time(value)?);
+            } else if let Some(value) = arg.strip_prefix("--to=") {
+                to = Some(parse_time(value)?);
+            } else if let Some(value) = arg.strip_prefix("--fidelity=") {
+                fidelity_min = value
+                    .parse()
+                    .ok()
+                    .filter(|m| *m > 0)
+                    .with_context(|| format!("Invalid --fidelity '{}'", value))?;
+            } else if let Some(value) = arg.strip_prefix("--out=") {
+                out = PathBuf::from(value);
+            }
+        }
+        let Some(tokens) = tokens else {
+            return Ok(None);
+        };
+        if tokens.is_empty() {
+            bail!("--import-prices needs at least one token ID");
+        }
+        let Some(from) = from else {
+            bail!("--import-prices needs --from=<RFC 3339 time>");
+        };
+        let to = to.unwrap_or(now);
+        if from >= to {
+            bail!("--from must be before --to");
+        }
+        Ok(Some(Self {
+            tokens,
+            from,
+            to,
+            fidelity_min,
+            out,
+        }))
+    }
+
+    /// Request windows covering the range, oldest first
+    fn windows(&self) -> Vec<(i64, i64)> {
+        let step = HISTORY_WINDOW.as_secs() as i64;
+        let (from, to) = (self.from.timestamp(), self.to.timestamp());
+        (from..to)
+            .step_by(step as usize)
+            .map(|start| (start, (start + step).min(to)))
+            .collect()
+    }
+}
+
+/// One imported point, as written
+#[derive(Debug, Serialize)]
+struct ImportedPoint<'a> {
+    token_id: &'a str,
+    t: i64,
+    p: f64,
+}
+
+/// Fetch the requested price history and write it as JSON lines.
+pub async fn run_price_import(client: &PolymarketDataClient, import: &PriceImport) -> Result<()> {
+    let file = std::fs::File::create(&import.out)
+        .with_context(|| format!("Failed to create {}", import.out.display()))?;
+    let mut out = std::io::BufWriter::new(file);
+    for token_id in &import.tokens {
+        let mut written = 0;
+        let mut last_t = i64::MIN;
+        for (start, end) in import.windows() {
+            let points = client
+                .prices_history(token_id, start, end, import.fidelity_min)
+                .await?;
+            // Windows share their edges
+            for point in points {
+                if point.t <= last_t {
+                    continue;
+                }
+                last_t = point.t;
+                serde_json::to_writer(
+                    &mut out,
+                    &ImportedPoint {
+                        token_id,
+                        t: point.t,
+                        p: point.p,
+                    },
+                )?;
+                out.write_all(b"\n")?;
+                written += 1;
+            }
+        }
+        info!("[IMPORT] {} price points for {}", written, token_id);
+    }
+    out.flush()?;
+    info!("[IMPORT] Price history written to {}", import.out.display());
+    Ok(())
+}
+
+fn parse_time(value: &str) -> Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|t| t.with_timezone(&Utc))
+        .with_context(|| format!("Invalid time '{}' (expected RFC 3339)", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_api_responses() {
+        let history: PriceHistoryResponse = serde_json::from_str(
+            r#"{"history":[{"t":1700000000,"p":0.51},{"t":1700000060,"p":0.52}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            history.history[1],
+            PricePoint {
+                t: 1700000060,
+                p: 0.52
+            }
+        );
+
+        let volumes: Vec<GammaVolume> =
+            serde_json::from_str(r#"[{"volume24hr":1234.5,"volumeNum":99000.0,"question":"q"}]"#)
+                .unwrap();
+        assert_eq!(volumes[0].volume_24hr, Some(1234.5));
+
+        let holders: Vec<TokenHolders> = serde_json::from_str(
+            r#"[{"token":"123","holders":[{"proxyWallet":"0xabc","amount":500.0,"name":"whale","outcomeIndex":0,"bio":""}]}]"#,
+        )
+        .unwrap();
+        assert_eq!(holders[0].holders[0].proxy_wallet, "0xabc");
+        assert_eq!(holders[0].holders[0].amount, 500.0);
+
+        let oi: Vec<OpenInterest> =
+            serde_json::from_str(r#"[{"market":"0xabc","value":25000.5}]"#).unwrap();
+        assert_eq!((oi[0].market.as_str(), oi[0].value), ("0xabc", 25000.5));
+    }
+
+    #[test]
+    fn test_cache_expires_entries() {
+        let cache = Cache::new(Duration::from_secs(10));
+        let start = Instant::now();
+        cache.insert("m1".to_string(), 1.0, start);
+        assert_eq!(
+            cache.get(&"m1".to_string(), start + Duration::from_secs(5)),
+            Some(1.0)
+        );
+        assert_eq!(
+            cache.get(&"m1".to_string(), start + Duration::from_secs(10)),
+            None
+        );
+        assert_eq!(cache.get_stale(&"m1".to_string()), Some(1.0));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_pacer_spaces_requests() {
+        let pacer = Pacer::new(10.0);
+        let start = tokio::time::Instant::now();
+        for _ in 0..3 {
+            pacer.wait().await;
+        }
+        assert_eq!(start.elapsed(), Duration::from_millis(200));
+    }
+
+    #[test]
+    fn test_price_import_args() {
+        let now = Utc::now();
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            PriceImport::from_args(args(&["--self-test"]), now).unwrap(),
+            None
+        );
+
+        let import = PriceImport::from_args(
+            args(&[
+                "--import-prices=111, 222",
+                "--from=2024-01-01T00:00:00Z",
+                "--to=2024-01-31T00:00:00Z",
+                "--fidelity=60",
+            ]),
+            now,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(import.tokens, vec!["111", "222"]);
+        assert_eq!(import.fidelity_min, 60);
+        let windows = import.windows();
+        assert_eq!(windows.len(), 3);
+        assert_eq!(windows[0].0, import.from.timestamp());
+        assert_eq!(windows[2].1, import.to.timestamp());
+
+        assert!(PriceImport::from_args(args(&["--import-prices=111"]), now).is_err());
+    }
+}
@@ -0,0 +1,409 @@
+//! Team-name normalization for matching ESPN games to market questions.
+//!
+//! ESPN reports full display names ("Los Angeles Lakers") while market
+//! questions use whatever the author wrote ("LA Lakers", "the Lakers",
+//! "Sixers"). Names are compared as normalized word sequences against a
+//! per-league alias dictionary, with a typo-tolerant fallback at lower
+//! confidence.
+
+use super::espn::League;
+
+/// Confidence of a full-name match
+const FULL_NAME: f64 = 1.0;
+/// Confidence of a dictionary alias (nickname, abbreviation) match
+const ALIAS: f64 = 0.9;
+/// Confidence of a derived nickname (last word) for a team missing from
+/// the dictionary
+const DERIVED_NICKNAME: f64 = 0.7;
+/// Confidence of a single-word match one edit away (typos, plurals)
+const FUZZY: f64 = 0.5;
+
+/// A team found in a piece of text
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TeamMatch {
+    /// Index of the first matched word in the normalized text
+    pub position: usize,
+    /// 0-1, higher is more certain
+    pub confidence: f64,
+}
+
+/// Lowercase, strip punctuation and collapse whitespace. Apostrophes are
+/// dropped ("A's" -> "as") and other punctuation splits words
+/// ("D-backs" -> "d backs").
+pub fn normalize(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            out.extend(c.to_lowercase());
+        } else if c == '\'' || c == '\u{2019}' || c == '.' {
+            continue;
+        } else if !out.ends_with(' ') && !out.is_empty() {
+            out.push(' ');
+        }
+    }
+    out.truncate(out.trim_end().len());
+    out
+}
+
+/// Dictionary name for a team (`name` may be the full name or any alias).
+pub fn canonical_team(league: League, name: &str) -> Option<&'static str> {
+    let name = normalize(name);
+    teams(league)
+        .iter()
+        .find(|(full, aliases)| {
+            normalize(full) == name || aliases.iter().any(|a| normalize(a) == name)
+        })
+        .map(|(full, _)| *full)
+}
+
+/// Find `team` (as named by ESPN) in `text`.
+///
+/// Returns the most confident match, earliest first among equals.
+pub fn find_team(league: League, text: &str, team: &str) -> Option<TeamMatch> {
+    let words: Vec<String> = normalize(text).split(' ').map(str::to_string).collect();
+
+    let candidates: Vec<(String, f64)> = match canonical_team(league, team) {
+        Some(full) => {
+            let aliases = teams(league)
+                .iter()
+                .find(|(f, _)| *f == full)
+                .map(|(_, aliases)| *aliases)
+                .unwrap_or_default();
+            std::iter::once((normalize(full), FULL_NAME))
+                .chain(std::iter::once((normalize(team), FULL_NAME)))
+                .chain(aliases.iter().map(|a| (normalize(a), ALIAS)))
+                .collect()
+        }
+        None => {
+            let full = normalize(team);
+            let nickname = full.rsplit(' ').next().unwrap_or_default().to_string();
+            vec![(full, FULL_NAME), (nickname, DERIVED_NICKNAME)]
+        }
+    };
+
+    let mut best: Option<TeamMatch> = None;
+
+    for (candidate, confidence) in &candidates {
+        let needle: Vec<&str> = candidate.split(' ').filter(|w| !w.is_empty()).collect();
+        if needle.is_empty() {
+            continue;
+        }
+        if let Some(position) = words
+            .windows(needle.len())
+            .position(|window| window.iter().zip(&needle).all(|(a, b)| a == b))
+        {
+            prefer(
+                &mut best,
+                TeamMatch {
+                    position,
+                    confidence: *confidence,
+                },
+            );
+        }
+    }
+    if best.is_some() {
+        return best;
+    }
+
+    // Typo-tolerant pass over single words long enough to be distinctive
+    for (candidate, _) in &candidates {
+        if candidate.contains(' ') || candidate.len() < 5 {
+            continue;
+        }
+        if let Some(position) = words.iter().position(|w| within_one_edit(w, candidate)) {
+            prefer(
+                &mut best,
+                TeamMatch {
+                    position,
+                    confidence: FUZZY,
+                },
+            );
+        }
+    }
+    best
+}
+
+/// Keep the more confident match (the earlier one among equals).
+fn prefer(best: &mut Option<TeamMatch>, found: TeamMatch) {
+    let better = best.is_none_or(|b| {
+        found.confidence > b.confidence
+            || (found.confidence == b.confidence && found.position < b.position)
+    });
+    if better {
+        *best = Some(found);
+    }
+}
+
+/// True if `a` and `b` differ by at most one insertion, deletion or
+/// substitution.
+fn within_one_edit(a: &str, b: &str) -> bool {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    if long.len() - short.len() > 1 {
+        return false;
+    }
+    let prefix = short.iter().zip(&long).take_while(|(x, y)| x == y).count();
+    if short.len() == long.len() {
+        short[prefix..]
+            .iter()
+            .skip(1)
+            .eq(long[prefix..].iter().skip(1))
+    } else {
+        short[prefix..].iter().eq(long[prefix + 1..].iter())
+    }
+}
+
+/// Full team names (as ESPN displays them) and their common aliases
+fn teams(league: League) -> &'static [(&'static str, &'static [&'static str])] {
+    match league {
+        League::Nba => NBA,
+        League::Nfl => NFL,
+        League::Mlb => MLB,
+        League::Nhl => NHL,
+    }
+}
+
+const NBA: &[(&str, &[&str])] = &[
+    ("Atlanta Hawks", &["Hawks"]),
+    ("Boston Celtics", &["Celtics", "Celts"]),
+    ("Brooklyn Nets", &["Nets"]),
+    ("Charlotte Hornets", &["Hornets"]),
+    ("Chicago Bulls", &["Bulls"]),
+    ("Cleveland Cavaliers", &["Cavaliers", "Cavs"]),
+    ("Dallas Mavericks", &["Mavericks", "Mavs"]),
+    ("Denver Nuggets", &["Nuggets"]),
+    ("Detroit Pistons", &["Pistons"]),
+    ("Golden State Warriors", &["Warriors", "Dubs", "GSW"]),
+    ("Houston Rockets", &["Rockets"]),
+    ("Indiana Pacers", &["Pacers"]),
+    (
+        "LA Clippers",
+        &["Los Angeles Clippers", "Clippers", "Clips"],
+    ),
+    ("Los Angeles Lakers", &["LA Lakers", "Lakers"]),
+    ("Memphis Grizzlies", &["Grizzlies", "Grizz"]),
+    ("Miami Heat", &["Heat"]),
+    ("Milwaukee Bucks", &["Bucks"]),
+    (
+        "Minnesota Timberwolves",
+        &["Timberwolves", "Wolves", "T-Wolves"],
+    ),
+    ("New Orleans Pelicans", &["Pelicans", "Pels"]),
+    ("New York Knicks", &["NY Knicks", "Knicks"]),
+    ("Oklahoma City Thunder", &["OKC Thunder", "Thunder", "OKC"]),
+    ("Orlando Magic", &["Magic"]),
+    ("Philadelphia 76ers", &["76ers", "Sixers"]),
+    ("Phoenix Suns", &["Suns"]),
+    ("Portland Trail Blazers", &["Trail Blazers", "Blazers"]),
+    ("Sacramento Kings", &["Kings"]),
+    ("San Antonio Spurs", &["Spurs"]),
+    ("Toronto Raptors", &["Raptors"]),
+    ("Utah Jazz", &["Jazz"]),
+    ("Washington Wizards", &["Wizards"]),
+];
+
+const NFL: &[(&str, &[&str])] = &[
+    ("Arizona Cardinals", &["Cardinals"]),
+    ("Atlanta Falcons", &["Falcons"]),
+    ("Baltimore Ravens", &["Ravens"]),
+    ("Buffalo Bills", &["Bills"]),
+    ("Carolina Panthers", &["Panthers"]),
+    ("Chicago Bears", &["Bears"]),
+    ("Cincinnati Bengals", &["Bengals"]),
+    ("Cleveland Browns", &["Browns"]),
+    ("Dallas Cowboys", &["Cowboys"]),
+    ("Denver Broncos", &["Broncos"]),
+    ("Detroit Lions", &["Lions"]),
+    ("Green Bay Packers", &["Packers"]),
+    ("Houston Texans", &["Texans"]),
+    ("Indianapolis Colts", &["Colts"]),
+    ("Jacksonville Jaguars", &["Jaguars", "Jags"]),
+    ("Kansas City Chiefs", &["KC Chiefs", "Chiefs"]),
+    ("Las Vegas Raiders", &["Raiders"]),
+    (
+        "Los Angeles Chargers",
+        &["LA Chargers", "Chargers", "Bolts"],
+    ),
+    ("Los Angeles Rams", &["LA Rams", "Rams"]),
+    ("Miami Dolphins", &["Dolphins", "Fins"]),
+    ("Minnesota Vikings", &["Vikings", "Vikes"]),
+    ("New England Patriots", &["Patriots", "Pats"]),
+    ("New Orleans Saints", &["Saints"]),
+    ("New York Giants", &["NY Giants", "Giants"]),
+    ("New York Jets", &["NY Jets", "Jets"]),
+    ("Philadelphia Eagles", &["Eagles"]),
+    ("Pittsburgh Steelers", &["Steelers"]),
+    ("San Francisco 49ers", &["SF 49ers", "49ers", "Niners"]),
+    ("Seattle Seahawks", &["Seahawks"]),
+    ("Tampa Bay Buccaneers", &["Buccaneers", "Bucs"]),
+    ("Tennessee Titans", &["Titans"]),
+    ("Washington Commanders", &["Commanders"]),
+];
+
+const MLB: &[(&str, &[&str])] = &[
+    (
+        "Arizona Diamondbacks",
+        &["Diamondbacks", "D-backs", "Dbacks"],
+    ),
+    ("Athletics", &["Oakland Athletics", "A's"]),
+    ("Atlanta Braves", &["Braves"]),
+    ("Baltimore Orioles", &["Orioles", "O's"]),
+    ("Boston Red Sox", &["Red Sox"]),
+    ("Chicago Cubs", &["Cubs"]),
+    ("Chicago White Sox", &["White Sox"]),
+    ("Cincinnati Reds", &["Reds"]),
+    ("Cleveland Guardians", &["Guardians"]),
+    ("Colorado Rockies", &["Rockies"]),
+    ("Detroit Tigers", &["Tigers"]),
+    ("Houston Astros", &["Astros"]),
+    ("Kansas City Royals", &["KC Royals", "Royals"]),
+    ("Los Angeles Angels", &["LA Angels", "Angels"]),
+    ("Los Angeles Dodgers", &["LA Dodgers", "Dodgers"]),
+    ("Miami Marlins", &["Marlins"]),
+    ("Milwaukee Brewers", &["Brewers"]),
+    ("Minnesota Twins", &["Twins"]),
+    ("New York Mets", &["NY Mets", "Mets"]),
+    ("New York Yankees", &["NY Yankees", "Yankees", "Yanks"]),
+    ("Philadelphia Phillies", &["Phillies"]),
+    ("Pittsburgh Pirates", &["Pirates", "Bucs"]),
+    ("San Diego Padres", &["Padres"]),
+    ("San Francisco Giants", &["SF Giants", "Giants"]),
+    ("Seattle Mariners", &["Mariners"]),
+    ("St. Louis Cardinals", &["Cardinals", "Cards"]),
+    ("Tampa Bay Rays", &["Rays"]),
+    ("Texas Rangers", &["Rangers"]),
+    ("Toronto Blue Jays", &["Blue Jays", "Jays"]),
+    ("Washington Nationals", &["Nationals", "Nats"]),
+];
+
+const NHL: &[(&str, &[&str])] = &[
+    ("Anaheim Ducks", &["Ducks"]),
+    ("Boston Bruins", &["Bruins"]),
+    ("Buffalo Sabres", &["Sabres"]),
+    ("Calgary Flames", &["Flames"]),
+    ("Carolina Hurricanes", &["Hurricanes", "Canes"]),
+    ("Chicago Blackhawks", &["Blackhawks", "Hawks"]),
+    ("Colorado Avalanche", &["Avalanche", "Avs"]),
+    ("Columbus Blue Jackets", &["Blue Jackets", "Jackets"]),
+    ("Dallas Stars", &["Stars"]),
+    ("Detroit Red Wings", &["Red Wings", "Wings"]),
+    ("Edmonton Oilers", &["Oilers"]),
+    ("Florida Panthers", &["Panthers"]),
+    ("Los Angeles Kings", &["LA Kings", "Kings"]),
+    ("Minnesota Wild", &["Wild"]),
+    (
+        "Montreal Canadiens",
+        &["Montréal Canadiens", "Canadiens", "Habs"],
+    ),
+    ("Nashville Predators", &["Predators", "Preds"]),
+    ("New Jersey Devils", &["NJ Devils", "Devils"]),
+    (
+        "New York Islanders",
+        &["NY Islanders", "Islanders", "Isles"],
+    ),
+    ("New York Rangers", &["NY Rangers", "Rangers"]),
+    ("Ottawa Senators", &["Senators", "Sens"]),
+    ("Philadelphia Flyers", &["Flyers"]),
+    ("Pittsburgh Penguins", &["Penguins", "Pens"]),
+    ("San Jose Sharks", &["Sharks"]),
+    ("Seattle Kraken", &["Kraken"]),
+    ("St. Louis Blues", &["Blues"]),
+    ("Tampa Bay Lightning", &["Lightning", "Bolts"]),
+    ("Toronto Maple Leafs", &["Maple Leafs", "Leafs"]),
+    ("Utah Hockey Club", &["Utah HC", "Utah Mammoth", "Mammoth"]),
+    ("Vancouver Canucks", &["Canucks", "Nucks"]),
+    ("Vegas Golden Knights", &["Golden Knights", "Knights"]),
+    ("Washington Capitals", &["Capitals", "Caps"]),
+    ("Winnipeg Jets", &["Jets"]),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("  LA Lakers "), "la lakers");
+        assert_eq!(normalize("St. Louis Blues"), "st louis blues");
+        assert_eq!(normalize("Will the A's win?"), "will the as win");
+        assert_eq!(normalize("D-backs vs. Giants"), "d backs vs giants");
+    }
+
+    #[test]
+    fn test_canonical_team() {
+        assert_eq!(
+            canonical_team(League::Nba, "LA Lakers"),
+            Some("Los Angeles Lakers")
+        );
+        assert_eq!(
+            canonical_team(League::Nba, "sixers"),
+            Some("Philadelphia 76ers")
+        );
+        assert_eq!(
+            canonical_team(League::Nhl, "Montréal Canadiens"),
+            Some("Montreal Canadiens")
+        );
+        assert_eq!(canonical_team(League::Nba, "Dodgers"), None);
+    }
+
+    #[test]
+    fn test_find_team_in_questions() {
+        let nba = |q: &str, team: &str| find_team(League::Nba, q, team);
+
+        let lakers = nba("Will the Lakers beat the Celtics?", "Los Angeles Lakers").unwrap();
+        let celtics = nba("Will the Lakers beat the Celtics?", "Boston Celtics").unwrap();
+        assert_eq!(lakers.confidence, ALIAS);
+        assert!(lakers.position < celtics.position);
+
+        let full = nba(
+            "Los Angeles Lakers vs. Boston Celtics",
+            "Los Angeles Lakers",
+        )
+        .unwrap();
+        assert_eq!(
+            full,
+            TeamMatch {
+                position: 0,
+                confidence: FULL_NAME
+            }
+        );
+        assert_eq!(
+            nba("LA Lakers vs Celtics", "Los Angeles Lakers")
+                .unwrap()
+                .confidence,
+            ALIAS
+        );
+        assert!(nba("Sixers vs. Knicks: who will win?", "Philadelphia 76ers").is_some());
+        assert!(nba("Portland Trail Blazers vs. Jazz", "Utah Jazz").is_some());
+
+        // Word boundaries: "Nets" is not in "Hornets"
+        assert!(nba("Will the Hornets win?", "Brooklyn Nets").is_none());
+
+        // Typos fall back to a low-confidence match
+        assert_eq!(
+            nba("Will the Celtic beat the Heat?", "Boston Celtics")
+                .unwrap()
+                .confidence,
+            FUZZY
+        );
+
+        let mlb = |q: &str, team: &str| find_team(League::Mlb, q, team);
+        assert!(mlb("Will the D-backs beat the Giants?", "Arizona Diamondbacks").is_some());
+        assert!(mlb("Red Sox vs. Yankees", "Boston Red Sox").is_some());
+        assert!(mlb("Red Sox vs. Yankees", "Chicago White Sox").is_none());
+        assert!(mlb("Will the A's win?", "Athletics").is_some());
+
+        // Teams missing from the dictionary fall back to their last word
+        let unknown = find_team(League::Nhl, "Will the Mammoths win?", "Utah Mammoths").unwrap();
+        assert_eq!(unknown.confidence, DERIVED_NICKNAME);
+    }
+
+    #[test]
+    fn test_within_one_edit() {
+        assert!(within_one_edit("celtic", "celtics"));
+        assert!(within_one_edit("lakers", "lakors"));
+        assert!(within_one_edit("lakers", "lakers"));
+        assert!(!within_one_edit("lakers", "lkrs"));
+        assert!(!within_one_edit("nets", "jets1"));
+    }
+}
@@ -0,0 +1,332 @@
+//! Round-trip latency to the exchange.
+//!
+//! Edge thresholds only hold if orders reach the book quickly, and a VPS
+//! that starts routing badly gives no other sign. Every interval the probe
+//! times a request to the CLOB REST API, and the WebSocket handler reports
+//! the round trip of each ping. Percentiles over a rolling window are
+//! exported per path, and a Slack alert fires when the p95 of either path
+//! rises above the threshold (and once more when it recovers).
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use parking_lot::Mutex;
+use reqwest::Client;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::metrics::{EXCHANGE_RTT, LATENCY_PROBE_ERRORS};
+use crate::notifications::{ErrorAlert, SlackNotifier};
+
+/// Samples needed before a path can be flagged as degraded
+const MIN_SAMPLES: usize = 5;
+
+/// A degraded path recovers once its p95 is below this share of the threshold
+const RECOVERY_RATIO: f64 = 0.8;
+
+/// Network path to the exchange
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LatencyPath {
+    /// CLOB REST request
+    Rest,
+    /// WebSocket ping/pong
+    Ws,
+}
+
+impl LatencyPath {
+    const ALL: [LatencyPath; 2] = [LatencyPath::Rest, LatencyPath::Ws];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LatencyPath::Rest => "rest",
+            LatencyPath::Ws => "ws",
+        }
+    }
+}
+
+/// Latency probe settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct LatencyProbeConfig {
+    pub enabled: bool,
+    /// Time between REST probes (and percentile updates)
+    pub interval: Duration,
+    /// Samples kept per path
+    pub window: usize,
+    /// Alert when a path's p95 exceeds this (zero = no alerts)
+    pub alert_p95: Duration,
+}
+
+impl Default for LatencyProbeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval: Duration::from_secs(10),
+            window: 60,
+            alert_p95: Duration::from_millis(750),
+        }
+    }
+}
+
+impl LatencyProbeConfig {
+    /// Load from `LATENCY_PROBE_ENABLED`, `LATENCY_PROBE_INTERVAL_MS`,
+    /// `LATENCY_PROBE_WINDOW` and `LATENCY_ALERT_P95_MS`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok();
+        Self {
+            enabled: var("LATENCY_PROBE_ENABLED")
+                .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+                .unwrap_or(defaults.enabled),
+            interval: var("LATENCY_PROBE_INTERVAL_MS")
+                .and_then(|v| v.parse().ok())
+                .filter(|ms| *ms > 0)
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.interval),
+            window: var("LATENCY_PROBE_WINDOW")
+                .and_then(|v| v.parse().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.window),
+            alert_p95: var("LATENCY_ALERT_P95_MS")
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.alert_p95),
+        }
+    }
+}
+
+/// Percentiles of one path's window, in seconds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LatencySummary {
+    pub p50: f64,
+    pub p95: f64,
+    pub p99: f64,
+    pub samples: usize,
+}
+
+/// Rolling round-trip samples for one path
+#[derive(Debug, Default)]
+struct PathState {
+    samples: Mutex<VecDeque<f64>>,
+    degraded: AtomicBool,
+}
+
+/// Measures round trips to the exchange and alerts on degradation.
+pub struct LatencyProbe {
+    config: LatencyProbeConfig,
+    client: Client,
+    probe_url: String,
+    paths: [PathState; 2],
+    slack: Option<Arc<SlackNotifier>>,
+}
+
+impl LatencyProbe {
+    /// Create a probe that times requests to `clob_url`.
+    pub fn new(config: LatencyProbeConfig, clob_url: &str) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .context("Failed to create latency probe HTTP client")?;
+
+        Ok(Self {
+            config,
+            client,
+            probe_url: format!("{}/time", clob_url.trim_end_matches('/')),
+            paths: Default::default(),
+            slack: None,
+        })
+    }
+
+    /// Set the Slack notifier used for degradation alerts.
+    pub fn set_slack_notifier(&mut self, notifier: Arc<SlackNotifier>) {
+        self.slack = Some(notifier);
+    }
+
+    fn path(&self, path: LatencyPath) -> &PathState {
+        &self.paths[path as usize]
+    }
+
+    /// Add one round-trip sample.
+    pub fn record(&self, path: LatencyPath, rtt: Duration) {
+        let mut samples = self.path(path).samples.lock();
+        if samples.len() >= self.config.window {
+            samples.pop_front();
+        }
+        samples.push_back(rtt.as_secs_f64());
+    }
+
+    /// Percentiles of the current window, if any samples were taken.
+    pub fn summary(&self, path: LatencyPath) -> Option<LatencySummary> {
+        let mut sorted: Vec<f64> = self.path(path).samples.lock().iter().copied().collect();
+        if sorted.is_empty() {
+            return None;
+        }
+        sorted.sort_by(f64::total_cmp);
+        // Nearest rank
+        let rank =
+            |q: f64| sorted[((q * sorted.len() as f64).ceil() as usize).clamp(1, sorted.len()) - 1];
+        Some(LatencySummary {
+            p50: rank(0.50),
+            p95: rank(0.95),
+            p99: rank(0.99),
+            samples: sorted.len(),
+        })
+    }
+
+    /// Whether either path is currently over the alert threshold.
+    #[allow(dead_code)]
+    pub fn is_degraded(&self) -> bool {
+        self.paths
+            .iter()
+            .any(|p| p.degraded.load(Ordering::Relaxed))
+    }
+
+    /// Probe every interval until cancelled.
+    pub async fn run(self: Arc<Self>, cancel: CancellationToken) {
+        info!(
+            "[LATENCY] Probing {} every {:?} (alert above p95 {:?})",
+            self.probe_url, self.config.interval, self.config.alert_p95
+        );
+        let mut ticker = tokio::time::interval(self.config.interval);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    self.probe_rest().await;
+                    self.evaluate();
+                }
+                _ = cancel.cancelled() => break,
+            }
+        }
+    }
+
+    /// Time one REST round trip. Any HTTP response counts as a sample.
+    async fn probe_rest(&self) {
+        let started = Instant::now();
+        match self.client.get(&self.probe_url).send().await {
+            Ok(_) => self.record(LatencyPath::Rest, started.elapsed()),
+            Err(e) => {
+                LATENCY_PROBE_ERRORS.inc();
+                debug!("[LATENCY] REST probe failed: {}", e);
+            }
+        }
+    }
+
+    /// Export percentiles and alert on threshold crossings.
+    fn evaluate(&self) {
+        for path in LatencyPath::ALL {
+            let Some(summary) = self.summary(path) else {
+                continue;
+            };
+            for (quantile, value) in [
+                ("p50", summary.p50),
+                ("p95", summary.p95),
+                ("p99", summary.p99),
+            ] {
+                EXCHANGE_RTT
+                    .with_label_values(&[path.as_str(), quantile])
+                    .set(value);
+            }
+
+            let threshold = self.config.alert_p95.as_secs_f64();
+            if threshold <= 0.0 || summary.samples < MIN_SAMPLES {
+                continue;
+            }
+            let state = self.path(path);
+            let degraded = state.degraded.load(Ordering::Relaxed);
+            if !degraded && summary.p95 > threshold {
+                state.degraded.store(true, Ordering::Relaxed);
+                let message = format!(
+                    "{} p95 round trip {:.0}ms over {:.0}ms (p50 {:.0}ms)",
+                    path.as_str(),
+                    summary.p95 * 1000.0,
+                    threshold * 1000.0,
+                    summary.p50 * 1000.0
+                );
+                warn!("[LATENCY] Degraded: {}", message);
+                self.alert("LATENCY_DEGRADED", message);
+            } else if degraded && summary.p95 < threshold * RECOVERY_RATIO {
+                state.degraded.store(false, Ordering::Relaxed);
+                let message = format!(
+                    "{} p95 round trip back to {:.0}ms",
+                    path.as_str(),
+                    summary.p95 * 1000.0
+                );
+                info!("[LATENCY] Recovered: {}", message);
+                self.alert("LATENCY_RECOVERED", message);
+            }
+        }
+    }
+
+    fn alert(&self, error_type: &str, message: String) {
+        if let Some(slack) = &self.slack {
+            slack.notify_error(ErrorAlert {
+                source: "latency-probe".to_string(),
+                error_type: error_type.to_string(),
+                message,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn probe(window: usize) -> LatencyProbe {
+        let config = LatencyProbeConfig {
+            window,
+            alert_p95: Duration::from_millis(100),
+            ..LatencyProbeConfig::default()
+        };
+        LatencyProbe::new(config, "http://localhost:1/").unwrap()
+    }
+
+    #[test]
+    fn test_percentiles_over_rolling_window() {
+        let probe = probe(100);
+        assert_eq!(probe.summary(LatencyPath::Ws), None);
+        for ms in 1..=100 {
+            probe.record(LatencyPath::Ws, Duration::from_millis(ms));
+        }
+        let summary = probe.summary(LatencyPath::Ws).unwrap();
+        assert!((summary.p50 - 0.050).abs() < 1e-9);
+        assert!((summary.p95 - 0.095).abs() < 1e-9);
+        assert!((summary.p99 - 0.099).abs() < 1e-9);
+
+        // Old samples roll out of the window
+        for _ in 0..100 {
+            probe.record(LatencyPath::Ws, Duration::from_millis(7));
+        }
+        assert!((probe.summary(LatencyPath::Ws).unwrap().p99 - 0.007).abs() < 1e-9);
+        assert_eq!(probe.probe_url, "http://localhost:1/time");
+    }
+
+    #[test]
+    fn test_degrades_and_recovers_with_hysteresis() {
+        let probe = probe(5);
+        for _ in 0..4 {
+            probe.record(LatencyPath::Rest, Duration::from_millis(500));
+        }
+        probe.evaluate();
+        assert!(!probe.is_degraded(), "too few samples");
+
+        probe.record(LatencyPath::Rest, Duration::from_millis(500));
+        probe.evaluate();
+        assert!(probe.is_degraded());
+
+        // Just under the threshold is not yet a recovery
+        for _ in 0..5 {
+            probe.record(LatencyPath::Rest, Duration::from_millis(90));
+        }
+        probe.evaluate();
+        assert!(probe.is_degraded());
+
+        for _ in 0..5 {
+            probe.record(LatencyPath::Rest, Duration::from_millis(20));
+        }
+        probe.evaluate();
+        assert!(!probe.is_degraded());
+    }
+}
@@ -0,0 +1,253 @@
+//! Rate-limited logging for hot paths.
+//!
+//! At debug level the WebSocket handler alone can log a line per book
+//! update, tens of thousands per minute. Hot-path logs go through
+//! [`debug_limited!`] instead, which gives each category a line budget per
+//! window, and optionally each key (usually a token) within it. Lines over
+//! budget are dropped and counted in `poly_log_suppressed_total`; the next
+//! line that gets through carries a `suppressed` field with the count.
+//!
+//! - `LOG_BUDGET_WINDOW_MS`: budget window (default 1000)
+//! - `LOG_BUDGET_PER_CATEGORY`: lines per category per window (default 50)
+//! - `LOG_BUDGET_PER_KEY`: lines per key per window (default 2)
+//! - `LOG_BUDGETS`: per-category overrides, e.g. `ws_book=200,ws_price=0`
+//!
+//! A budget of 0 means unlimited.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+
+use crate::metrics::LOG_SUPPRESSED;
+
+/// Keyed buckets kept before stale ones are pruned
+const MAX_BUCKETS: usize = 10_000;
+
+/// Log budget settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogBudgetConfig {
+    pub window: Duration,
+    pub per_category: u32,
+    pub per_key: u32,
+    /// Category-specific overrides of `per_category`
+    pub categories: HashMap<String, u32>,
+}
+
+impl Default for LogBudgetConfig {
+    fn default() -> Self {
+        Self {
+            window: Duration::from_secs(1),
+            per_category: 50,
+            per_key: 2,
+            categories: HashMap::new(),
+        }
+    }
+}
+
+impl LogBudgetConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let budget = |name: &str, default: u32| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(default)
+        };
+
+        Self {
+            window: std::env::var("LOG_BUDGET_WINDOW_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|ms| *ms > 0)
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.window),
+            per_category: budget("LOG_BUDGET_PER_CATEGORY", defaults.per_category),
+            per_key: budget("LOG_BUDGET_PER_KEY", defaults.per_key),
+            categories: std::env::var("LOG_BUDGETS")
+                .map(|v| parse_budgets(&v))
+                .unwrap_or_default(),
+        }
+    }
+
+    fn category_budget(&self, category: &str) -> u32 {
+        self.categories
+            .get(category)
+            .copied()
+            .unwrap_or(self.per_category)
+    }
+}
+
+/// Parse `category=lines` pairs, skipping malformed entries.
+fn parse_budgets(s: &str) -> HashMap<String, u32> {
+    s.split(',')
+        .filter_map(|entry| {
+            let (category, lines) = entry.split_once('=')?;
+            Some((category.trim().to_string(), lines.trim().parse().ok()?))
+        })
+        .collect()
+}
+
+/// Lines logged and dropped in the current window
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    window: u64,
+    logged: u32,
+    suppressed: u64,
+}
+
+impl Bucket {
+    fn roll(&mut self, window: u64) {
+        if self.window != window {
+            self.window = window;
+            self.logged = 0;
+        }
+    }
+
+    fn has_room(&self, budget: u32) -> bool {
+        budget == 0 || self.logged < budget
+    }
+}
+
+/// Per-category and per-key line budgets.
+pub struct LogLimiter {
+    config: LogBudgetConfig,
+    start: Instant,
+    categories: DashMap<&'static str, Bucket>,
+    keys: DashMap<(&'static str, String), Bucket>,
+}
+
+impl LogLimiter {
+    pub fn new(config: LogBudgetConfig) -> Self {
+        Self {
+            config,
+            start: Instant::now(),
+            categories: DashMap::new(),
+            keys: DashMap::new(),
+        }
+    }
+
+    /// Whether a line may be logged now. `Some(n)` means yes, with `n`
+    /// lines of this category suppressed since the last one got through.
+    pub fn allow(&self, category: &'static str, key: Option<&str>) -> Option<u64> {
+        let window = (self.start.elapsed().as_nanos() / self.config.window.as_nanos()) as u64;
+        self.allow_in(window, category, key)
+    }
+
+    fn allow_in(&self, window: u64, category: &'static str, key: Option<&str>) -> Option<u64> {
+        let mut bucket = self.categories.entry(category).or_insert(Bucket {
+            window,
+            logged: 0,
+            suppressed: 0,
+        });
+        bucket.roll(window);
+
+        let mut allowed = bucket.has_room(self.config.category_budget(category));
+        if let (true, Some(key), per_key) = (allowed, key, self.config.per_key) {
+            if per_key > 0 {
+                allowed = self.take_key(window, category, key, per_key);
+            }
+        }
+
+        if !allowed {
+            bucket.suppressed += 1;
+            LOG_SUPPRESSED.with_label_values(&[category]).inc();
+            return None;
+        }
+        bucket.logged += 1;
+        Some(std::mem::take(&mut bucket.suppressed))
+    }
+
+    /// Count a line against a key's budget, returning whether it fits.
+    fn take_key(&self, window: u64, category: &'static str, key: &str, budget: u32) -> bool {
+        if self.keys.len() >= MAX_BUCKETS {
+            self.keys.retain(|_, b| b.window == window);
+        }
+
+        let mut bucket = self
+            .keys
+            .entry((category, key.to_string()))
+            .or_insert(Bucket {
+                window,
+                logged: 0,
+                suppressed: 0,
+            });
+        bucket.roll(window);
+        if !bucket.has_room(budget) {
+            return false;
+        }
+        bucket.logged += 1;
+        true
+    }
+}
+
+/// Process-wide limiter, configured from the environment on first use
+pub fn limiter() -> &'static LogLimiter {
+    static LIMITER: OnceLock<LogLimiter> = OnceLock::new();
+    LIMITER.get_or_init(|| LogLimiter::new(LogBudgetConfig::from_env()))
+}
+
+/// `debug!` with a line budget: `debug_limited!(category, key, fmt, args..)`
+/// where `key` is an `Option<&str>` (usually the token).
+///
+/// The budget is only consulted when debug logging is enabled, so this
+/// costs one level check otherwise.
+macro_rules! debug_limited {
+    ($category:expr, $key:expr, $($arg:tt)+) => {
+        if tracing::enabled!(tracing::Level::DEBUG) {
+            if let Some(suppressed) = $crate::log_budget::limiter().allow($category, $key) {
+                if suppressed > 0 {
+                    tracing::debug!(suppressed, $($arg)+);
+                } else {
+                    tracing::debug!($($arg)+);
+                }
+            }
+        }
+    };
+}
+
+pub(crate) use debug_limited;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(per_category: u32, per_key: u32) -> LogLimiter {
+        LogLimiter::new(LogBudgetConfig {
+            per_category,
+            per_key,
+            categories: parse_budgets("quiet=1, loud=0, bad=x"),
+            ..LogBudgetConfig::default()
+        })
+    }
+
+    #[test]
+    fn test_category_budget_resets_each_window_with_suppressed_count() {
+        let limiter = limiter(2, 0);
+        assert_eq!(limiter.allow_in(0, "ws_book", None), Some(0));
+        assert_eq!(limiter.allow_in(0, "ws_book", None), Some(0));
+        assert_eq!(limiter.allow_in(0, "ws_book", None), None);
+        assert_eq!(limiter.allow_in(0, "ws_book", None), None);
+        // Other categories have their own budget
+        assert_eq!(limiter.allow_in(0, "ws_price", None), Some(0));
+
+        assert_eq!(limiter.allow_in(1, "ws_book", None), Some(2));
+        assert_eq!(limiter.allow_in(1, "ws_book", None), Some(0));
+    }
+
+    #[test]
+    fn test_per_key_budget_and_overrides() {
+        let limiter = limiter(10, 1);
+        assert_eq!(limiter.allow_in(0, "ws_book", Some("a")), Some(0));
+        assert_eq!(limiter.allow_in(0, "ws_book", Some("a")), None);
+        assert_eq!(limiter.allow_in(0, "ws_book", Some("b")), Some(1));
+
+        assert_eq!(limiter.allow_in(0, "quiet", None), Some(0));
+        assert_eq!(limiter.allow_in(0, "quiet", None), None);
+        for _ in 0..100 {
+            assert!(limiter.allow_in(0, "loud", None).is_some());
+        }
+        assert!(!limiter.config.categories.contains_key("bad"));
+    }
+}
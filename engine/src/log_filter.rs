@@ -0,0 +1,106 @@
+//! Log filter that can be changed while the engine runs.
+//!
+//! Debug logging is needed exactly when the engine cannot be restarted, so
+//! the tracing filter sits behind a reload handle. Operators replace it
+//! with `PUT /loglevel` or a `loglevel` command on the Redis commands
+//! channel, using `RUST_LOG` syntax: `poly_rust=info,poly_rust::ws=debug`.
+//! `reset` restores the filter the engine started with.
+
+use anyhow::{Context, Result};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, EnvFilter, Registry};
+
+/// Directive added to `RUST_LOG` at startup
+pub const DEFAULT_DIRECTIVE: &str = "poly_rust=info";
+
+/// Keyword that restores the startup filter
+pub const RESET: &str = "reset";
+
+/// Handle to the global log filter
+pub struct LogFilter {
+    handle: reload::Handle<EnvFilter, Registry>,
+    /// Filter at startup, restored by `reset`
+    initial: String,
+}
+
+impl LogFilter {
+    /// Install the global subscriber, filtered by `RUST_LOG` plus
+    /// `poly_rust=info`.
+    pub fn init() -> Result<Self> {
+        let filter = EnvFilter::from_default_env().add_directive(DEFAULT_DIRECTIVE.parse()?);
+        let (layer, log_filter) = Self::new(filter);
+        tracing_subscriber::registry()
+            .with(layer)
+            .with(fmt::layer())
+            .try_init()
+            .context("Failed to install tracing subscriber")?;
+        Ok(log_filter)
+    }
+
+    fn new(filter: EnvFilter) -> (reload::Layer<EnvFilter, Registry>, Self) {
+        let initial = filter.to_string();
+        let (layer, handle) = reload::Layer::new(filter);
+        (layer, Self { handle, initial })
+    }
+
+    /// The directives in effect
+    pub fn current(&self) -> String {
+        self.handle
+            .with_current(|filter| filter.to_string())
+            .unwrap_or_default()
+    }
+
+    /// Replace the filter with `directives` (or restore the startup filter
+    /// for `reset`), returning the filter now in effect. Invalid directives
+    /// leave the filter unchanged.
+    pub fn set(&self, directives: &str) -> Result<String> {
+        let directives = directives.trim();
+        let directives = if directives.eq_ignore_ascii_case(RESET) {
+            self.initial.as_str()
+        } else {
+            directives
+        };
+        if directives.is_empty() {
+            anyhow::bail!("empty log filter");
+        }
+        let filter = EnvFilter::try_new(directives)
+            .with_context(|| format!("Invalid log filter '{}'", directives))?;
+        self.handle
+            .reload(filter)
+            .context("Failed to reload log filter")?;
+        Ok(self.current())
+    }
+
+    /// A filter attached to a subscriber that lives for the whole test run
+    /// but is never installed globally.
+    #[cfg(test)]
+    pub fn for_test(directives: &str) -> Self {
+        let (layer, log_filter) = Self::new(EnvFilter::new(directives));
+        Box::leak(Box::new(tracing_subscriber::registry().with(layer)));
+        log_filter
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_and_reset_filter() {
+        let log_filter = LogFilter::for_test("poly_rust=info");
+        assert_eq!(log_filter.current(), "poly_rust=info");
+
+        let current = log_filter
+            .set(" poly_rust=info,poly_rust::ws=debug ")
+            .unwrap();
+        assert!(current.contains("poly_rust::ws=debug"));
+
+        // Invalid directives keep the current filter
+        assert!(log_filter.set("poly_rust=loud").is_err());
+        assert!(log_filter.set("").is_err());
+        assert!(log_filter.current().contains("poly_rust::ws=debug"));
+
+        assert_eq!(log_filter.set("RESET").unwrap(), "poly_rust=info");
+    }
+}
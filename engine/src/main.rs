@@ -3,184 +3,360 @@
 //! This is the main entry point for the trading engine.
 
 mod analysis;
+mod audit;
+mod backtest;
+mod chaos;
+mod cluster;
 mod config;
+mod config_schema;
+mod connectivity;
 mod db;
 mod execution;
 mod external;
+mod latency;
+mod log_budget;
+mod log_filter;
 mod market;
 mod metrics;
 mod notifications;
+mod profile;
 mod redis;
+mod restarts;
 mod risk;
+mod runtime;
+mod selftest;
+mod server;
+mod shutdown;
+mod status;
 mod strategy;
+mod timezone;
+mod watchdog;
 mod ws;
 
 use anyhow::Result;
-use std::convert::Infallible;
-use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::signal;
+use tokio::signal::unix::SignalKind;
 use tokio_util::sync::CancellationToken;
 use tracing::{info, warn};
 
+use crate::audit::{AuditAction, AuditLog};
+use crate::cluster::{
+    LeaderElection, LeaderElectionConfig, ShardConfig, SharedRiskConfig, SharedRiskState,
+};
 use crate::config::Config;
-use crate::db::TradeRepository;
-use crate::execution::OrderManager;
-use crate::market::MarketData;
+use crate::connectivity::{ConnectivityConfig, ConnectivitySupervisor};
+use crate::db::{
+    run_pnl_attribution, AnalyticsSink, ClickHouseConfig, TradeRepository, TradeStore,
+};
+use crate::execution::{
+    check_allowances, run_approvals, ApproveArgs, MockExchange, MockExchangeConfig,
+    OrderManager, OrderTracker, OrderTrackingConfig, TwapConfig, TwapExecutor, VolumeTracker,
+};
+use crate::market::{
+    BookValidator, BookValidatorConfig, CorrelationConfig, HousekeepingConfig, LiquidityConfig,
+    MarketCorrelations, MarketData, MarketLiquidity, MetadataConfig, MetadataRefresher,
+    OrderRulesLoader, PairResolver, PriceAlertConfig, PriceAlerts, ResyncRequests,
+    SubscriptionConfig, SubscriptionPrioritizer, WhaleConfig, WhaleMonitor,
+};
 use crate::notifications::SlackNotifier;
-use crate::redis::RedisPublisher;
-use crate::risk::RiskManager;
-use crate::strategy::{ClipperStrategy, SniperStrategy, StrategyEngine, SumTo100Strategy};
-use crate::ws::WebSocketHandler;
-
-/// Global health check state
-struct HealthState {
-    start_time: Instant,
-    market_data: Arc<MarketData>,
-}
-
-/// Simple health check HTTP handler (no framework dependencies)
-async fn health_check_handler(state: Arc<HealthState>) -> Result<String, Infallible> {
-    let uptime = state.start_time.elapsed().as_secs();
-    let tokens = state.market_data.token_count();
-    let order_books = state.market_data.order_book_count();
-    let markets = state.market_data.market_count();
-    let has_data = state.market_data.has_data();
-
-    let status = if has_data {
-        "healthy"
-    } else {
-        "waiting_for_data"
-    };
-
-    // JSON response
-    let json = format!(
-        r#"{{"status":"{}","uptime_secs":{},"tokens":{},"order_books":{},"markets":{},"has_data":{}}}"#,
-        status, uptime, tokens, order_books, markets, has_data
-    );
-
-    Ok(json)
-}
-
-/// Generate Prometheus metrics output
-fn metrics_handler() -> String {
-    use prometheus::Encoder;
-    let encoder = prometheus::TextEncoder::new();
-    let metric_families = prometheus::gather();
-    let mut buffer = Vec::new();
-    encoder.encode(&metric_families, &mut buffer).unwrap_or_default();
-    String::from_utf8(buffer).unwrap_or_default()
+use crate::redis::{channels, CommandListener, RedisPublisher};
+use crate::restarts::{RestartConfig, TaskSupervisor};
+use crate::risk::{
+    ExitConfig, ExitScheduler, FundsConfig, FundsMonitor, HedgeConfig, Hedger, Portfolio,
+    RiskAnalytics, RiskManager, VarConfig,
+};
+use crate::runtime::{RuntimeConfig, TaskGroup};
+use crate::server::{HttpServer, HttpServerConfig, HttpState};
+use crate::shutdown::{Shutdown, ShutdownConfig, FLUSHED_GROUPS};
+use crate::external::{
+    EspnClient, EspnPollConfig, PolymarketDataClient, PolymarketDataConfig, PriceImport,
+};
+use crate::latency::{LatencyProbe, LatencyProbeConfig};
+use crate::log_filter::LogFilter;
+use crate::strategy::{
+    ApprovalConfig, CostModel, DecisionLog, EdgeTuner, EdgeTuningConfig, GovernorConfig,
+    RecentTrades, RetryConfig, SniperRacer, StrategyEngine, StrategyGovernor, StrategyRegistry,
+    VolatilityGateConfig, WarmupConfig,
+};
+use crate::watchdog::{Watchdog, WatchdogConfig};
+use crate::ws::{
+    MarketRelay, RelayConfig, WebSocketHandler, WsCapture, WsFailoverConfig, WsTransportConfig,
+};
+
+fn main() -> Result<()> {
+    // Runtime settings are read before the runtime exists
+    dotenvy::dotenv().ok();
+    let runtime_config = RuntimeConfig::from_env();
+    runtime_config.build()?.block_on(run(runtime_config))
 }
 
-/// Start a minimal health check HTTP server on port 8080
-async fn start_health_server(state: Arc<HealthState>) {
-    use tokio::io::{AsyncReadExt, AsyncWriteExt};
-    use tokio::net::TcpListener;
-
-    let port = std::env::var("HEALTH_PORT")
-        .ok()
-        .and_then(|p| p.parse().ok())
-        .unwrap_or(8080u16);
-
-    let addr = SocketAddr::from(([0, 0, 0, 0], port));
+async fn run(runtime_config: RuntimeConfig) -> Result<()> {
+    // Config schema / check for ops tooling: JSON on stdout, then exit
+    if config_schema::run_if_requested()? {
+        return Ok(());
+    }
 
-    let listener = match TcpListener::bind(addr).await {
-        Ok(l) => {
-            info!("[HEALTH] Health check server listening on http://{}", addr);
-            info!("[HEALTH] Metrics available at http://{}/metrics", addr);
-            l
-        }
-        Err(e) => {
-            warn!("[HEALTH] Failed to bind health server on {}: {}", addr, e);
-            return;
-        }
-    };
+    // Replay a market data capture for profiling, then exit
+    if let Some(capture) = profile::from_args(std::env::args().skip(1))? {
+        return profile::run(&capture).await;
+    }
 
-    loop {
-        match listener.accept().await {
-            Ok((mut socket, _)) => {
-                let state = Arc::clone(&state);
-                tokio::spawn(async move {
-                    let mut buf = [0u8; 1024];
-                    // Read the request to check the path
-                    let n = socket.read(&mut buf).await.unwrap_or(0);
-                    let request = String::from_utf8_lossy(&buf[..n]);
-
-                    // Check if this is a metrics request
-                    let is_metrics = request.contains("/metrics");
-
-                    let (body, content_type) = if is_metrics {
-                        (metrics_handler(), "text/plain; version=0.0.4; charset=utf-8")
-                    } else {
-                        (health_check_handler(state).await.unwrap_or_default(), "application/json")
-                    };
-
-                    // Write HTTP response
-                    let response = format!(
-                        "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
-                        content_type,
-                        body.len(),
-                        body
-                    );
-
-                    let _ = socket.write_all(response.as_bytes()).await;
-                });
-            }
-            Err(e) => {
-                warn!("[HEALTH] Accept error: {}", e);
-            }
-        }
+    // Compare the decision logs of two runs, then exit
+    if let Some((old, new)) = strategy::decisions::diff_from_args(std::env::args().skip(1))? {
+        return strategy::decisions::run_diff(&old, &new);
     }
-}
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    // Initialize logging
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::from_default_env()
-                .add_directive("poly_rust=info".parse()?),
-        )
-        .init();
+    // Initialize logging (filter adjustable at runtime, see log_filter)
+    let log_filter = Arc::new(LogFilter::init()?);
 
     info!("===========================================");
     info!("  POLY-RUST TRADING ENGINE");
     info!("===========================================");
 
     // Load configuration
-    dotenvy::dotenv().ok();
     let config = Config::from_env()?;
     info!("Configuration loaded");
+    info!(
+        "Runtime: {} worker threads",
+        tokio::runtime::Handle::current().metrics().num_workers()
+    );
+    let timezone = config.timezone();
+    info!("Trading day starts at midnight {}", timezone);
+
+    // Deploy gate: verify external dependencies and exit
+    if selftest::requested() {
+        return selftest::run(&config).await;
+    }
+
+    // Approve the exchange contracts to move the wallet's tokens, then exit
+    if let Some(args) = ApproveArgs::from_args(std::env::args().skip(1)) {
+        return run_approvals(&config, &args).await;
+    }
+
+    // One-off cleanup of duplicate trade rows, then exit
+    if db::dedupe_requested() {
+        return db::run_dedupe(std::env::var("DATABASE_URL").ok().as_deref()).await;
+    }
+
+    // Insert trades the exchange executed but the database missed, then exit
+    let backfill = db::BackfillRange::from_args(std::env::args().skip(1), chrono::Utc::now())?;
+    if let Some(range) = backfill {
+        let database_url = std::env::var("DATABASE_URL").ok();
+        return db::run_backfill(&config, database_url.as_deref(), range).await;
+    }
+
+    // Export price history for backtests, then exit
+    let import = PriceImport::from_args(std::env::args().skip(1), chrono::Utc::now())?;
+    if let Some(import) = import {
+        let client = PolymarketDataClient::new(PolymarketDataConfig::from_env(), &config.clob_url)?;
+        return external::run_price_import(&client, &import).await;
+    }
+
+    // Replay recorded order books through the strategies, then exit
+    let backtest = backtest::BacktestArgs::from_args(std::env::args().skip(1), chrono::Utc::now())?;
+    if let Some(args) = backtest {
+        return backtest::run(&config, &args).await;
+    }
 
     // Initialize Prometheus metrics
     metrics::init();
     info!("Prometheus metrics initialized");
+    chaos::log_active();
+
+    // Restore counters saved by the previous run (optional - keeps Grafana
+    // totals monotonic across deploys)
+    let metrics_state_path = std::env::var("METRICS_STATE_PATH").ok().map(PathBuf::from);
+    if let Some(path) = &metrics_state_path {
+        if let Err(e) = metrics::restore_from_file(path) {
+            warn!("[METRICS] Failed to restore counters: {}", e);
+        }
+    }
+
+    // Initialize Slack notifier (optional - for trade notifications)
+    let slack_notifier = Arc::new(SlackNotifier::from_env());
+
+    // One reconnect backoff for WS, Redis and DB, and one alert per
+    // network incident (trading pauses while it lasts)
+    let mut connectivity = ConnectivitySupervisor::new(ConnectivityConfig::from_env());
+    connectivity.set_slack_notifier(slack_notifier.clone());
+    let connectivity = Arc::new(connectivity);
+
+    // Restart crashed tasks, and stop (and alert) when one crash loops
+    let mut restarts = TaskSupervisor::new(RestartConfig::from_env());
+    restarts.set_slack_notifier(slack_notifier.clone());
+    let restarts = Arc::new(restarts);
 
     // Initialize Redis publisher (optional - for Python dashboard integration)
     let redis_url = std::env::var("REDIS_URL").ok();
-    let redis_publisher = Arc::new(RedisPublisher::new(redis_url.as_deref()).await?);
+    let mut redis_publisher = RedisPublisher::new(redis_url.as_deref()).await?;
+    redis_publisher.set_namespace(&config.redis_prefix, &config.instance_id);
+    redis_publisher.set_connectivity(connectivity.clone());
+    let redis_publisher = Arc::new(redis_publisher);
     if redis_publisher.is_enabled() {
         info!("Redis publisher enabled - streaming to Python dashboard");
     }
 
-    // Initialize Slack notifier (optional - for trade notifications)
-    let slack_notifier = Arc::new(SlackNotifier::from_env());
-
-    // Initialize database repository (optional - for trade persistence)
+    // Initialize database repository (optional - for trade persistence).
+    // Any `TradeStore` backend can be swapped in here.
     let database_url = std::env::var("DATABASE_URL").ok();
-    let trade_repo = Arc::new(TradeRepository::new(database_url.as_deref()).await?);
+    let mut trade_repo = TradeRepository::new(database_url.as_deref()).await?;
+    trade_repo.set_timezone(timezone);
+    trade_repo.set_connectivity(connectivity.clone());
+    let trade_repo: Arc<dyn TradeStore> = Arc::new(trade_repo);
+
+    // Initialize ClickHouse sink (optional - for signal and book analytics)
+    let analytics = AnalyticsSink::new(ClickHouseConfig::from_env()).map(Arc::new);
+
+    // Initialize audit trail (log stream + DB + Redis)
+    let mut audit_log = AuditLog::new();
+    audit_log.set_trade_repo(trade_repo.clone());
+    audit_log.set_redis_publisher(redis_publisher.clone());
+    let audit_log = Arc::new(audit_log);
+    audit_log.record(
+        "engine",
+        AuditAction::EngineStart,
+        None,
+        format!(
+            "dry_run={} observe={} sniper={} clipper={} sum_to_100={}",
+            config.dry_run,
+            config.observe,
+            config.sniper.enabled,
+            config.clipper.enabled,
+            config.sum_to_100.enabled
+        ),
+    );
 
-    // Initialize shared state
-    let market_data = Arc::new(MarketData::new());
-    let risk_manager = Arc::new(RiskManager::new(config.risk.clone()));
-    // Pass market_data to OrderManager for paper trading simulations
-    let order_manager = Arc::new(OrderManager::new(config.clone(), Some(market_data.clone())).await?);
+    // Active/standby: only the instance holding the Redis lease places orders
+    let mut leader_election = LeaderElection::new(
+        LeaderElectionConfig::from_env(),
+        redis_url.as_deref(),
+        &config.instance_id,
+    )
+    .await?;
+    leader_election.set_slack_notifier(slack_notifier.clone());
+    leader_election.set_audit_log(audit_log.clone());
+    let leader_election = Arc::new(leader_election);
+
+    // Horizontal sharding: this instance only trades its slice of the markets
+    let shard = ShardConfig::from_env()?;
+    if shard.is_sharded() {
+        if redis_url.is_none() {
+            anyhow::bail!("SHARD_COUNT > 1 requires REDIS_URL for shared risk state");
+        }
+        info!("[SHARD] Running as shard {}", shard);
+    }
 
-    // Initialize strategies
-    let sniper = SniperStrategy::new(config.sniper.clone());
-    let clipper = ClipperStrategy::new(config.clipper.clone());
-    let sum_to_100 = SumTo100Strategy::new(config.sum_to_100.clone());
+    // Initialize shared state
+    let mut market_data = MarketData::new();
+    market_data.set_shard(shard);
+    // Strategies only use the top of the book; the tail is kept as one level
+    market_data.set_max_book_levels(
+        std::env::var("BOOK_MAX_LEVELS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(20),
+    );
+    let market_data = Arc::new(market_data);
+
+    // Resubscribe to the previous run's markets as soon as the WebSocket
+    // connects, instead of waiting for discovery to repopulate them
+    let market_state_path = std::env::var("MARKET_STATE_PATH").ok().map(PathBuf::from);
+    if let Some(path) = &market_state_path {
+        if let Err(e) = market_data.restore_subscriptions(path) {
+            warn!("[MARKET] Failed to restore subscriptions: {}", e);
+        }
+    }
+    let mut risk_manager = RiskManager::new(config.risk.clone());
+    risk_manager.set_market_data(market_data.clone());
+    risk_manager.set_audit_log(audit_log.clone());
+    // One fee/slippage model for strategy edges, booked P&L, and stored trades
+    let mut cost_model = CostModel::new(config.cost.clone());
+    // Minimum edges raised per market by realized execution shortfall
+    let edge_tuning = EdgeTuningConfig::from_env();
+    let edge_tuner = Arc::new(EdgeTuner::new(edge_tuning));
+    if edge_tuning.enabled() {
+        cost_model.set_edge_tuner(edge_tuner.clone());
+    }
+    risk_manager.set_cost_model(cost_model.clone());
+    // Cached, rate-limited market volumes for liquidity scoring and discovery
+    let polymarket_data = Arc::new(PolymarketDataClient::new(
+        PolymarketDataConfig::from_env(),
+        &config.clob_url,
+    )?);
+    // Tier markets by depth, spread, and activity; entries are capped at a
+    // fraction of the depth near the mid
+    let liquidity_config = LiquidityConfig::from_env();
+    let liquidity = liquidity_config.enabled.then(|| {
+        let mut liquidity = MarketLiquidity::new(liquidity_config);
+        liquidity.set_data_client(polymarket_data.clone());
+        Arc::new(liquidity)
+    });
+    if let Some(liquidity) = &liquidity {
+        risk_manager.set_liquidity(liquidity.clone());
+    }
+    // Group markets whose returns move together; groups share an exposure cap
+    let correlation_config = CorrelationConfig::from_env();
+    let correlations = correlation_config
+        .enabled
+        .then(|| Arc::new(MarketCorrelations::new(correlation_config)));
+    if let Some(correlations) = &correlations {
+        risk_manager.set_correlations(correlations.clone());
+    }
+    // Under a WebSocket asset limit, subscribe to held and liquid markets
+    // first and rotate through the rest
+    let mut subscriptions =
+        SubscriptionPrioritizer::new(SubscriptionConfig::from_env(), market_data.clone());
+    if let Some(liquidity) = &liquidity {
+        subscriptions.set_liquidity(liquidity.clone());
+    }
+    let subscriptions = Arc::new(subscriptions);
+    // Pass market_data to OrderManager for paper trading simulations.
+    // Observe mode never touches the exchange, so it never needs a live one.
+    let mut order_config = config.clone();
+    order_config.dry_run |= config.observe;
+    let mut order_manager = OrderManager::new(order_config, Some(market_data.clone())).await?;
+    // Dry runs can go through an in-process exchange with resting orders,
+    // fills over time, and cancels
+    let mock_config = MockExchangeConfig::from_env();
+    let mock_exchange = (config.dry_run && mock_config.enabled)
+        .then(|| Arc::new(MockExchange::new(mock_config, market_data.clone())));
+    if let Some(mock_exchange) = &mock_exchange {
+        order_manager.set_mock_exchange(mock_exchange.clone());
+    }
+    let order_manager = Arc::new(order_manager);
+    // Orders fail without exchange approvals; warn early (see `approve`)
+    if !order_manager.is_dry_run() {
+        let config = config.clone();
+        tokio::spawn(async move { check_allowances(&config).await });
+    }
+    // Atomic baskets must fit one batch request
+    risk_manager.set_batch_limit(order_manager.batch_limit());
+    let risk_manager = Arc::new(risk_manager);
+
+    // Build the strategies selected by STRATEGIES (all registered by default)
+    #[allow(unused_mut)]
+    let mut registry = StrategyRegistry::with_builtins();
+    #[cfg(feature = "wasm-plugins")]
+    strategy::wasm::register_plugins_from_env(&mut registry);
+    #[cfg(feature = "python")]
+    strategy::python::register_strategies_from_env(&mut registry);
+    let strategies = registry.build_enabled(&config)?;
+    let strategy_count = strategies.len();
+    for strategy in &strategies {
+        audit_log.record(
+            "engine",
+            AuditAction::StrategyToggle,
+            Some(strategy.name()),
+            if strategy.is_active() {
+                "enabled at startup"
+            } else {
+                "built but inactive (disabled in config)"
+            },
+        );
+    }
 
     // Create strategy engine
     let mut strategy_engine = StrategyEngine::new(
@@ -189,18 +365,110 @@ async fn main() -> Result<()> {
         order_manager.clone(),
     );
 
+    strategy_engine.set_cost_model(cost_model);
+
     // Wire Redis publisher to strategy engine for real-time dashboard updates
     strategy_engine.set_redis_publisher(redis_publisher.clone());
 
+    // Heartbeat snapshots, published whether or not Redis is configured
+    let status_board = strategy_engine.status_board();
+
     // Wire Slack notifier to strategy engine for trade alerts
     strategy_engine.set_slack_notifier(slack_notifier.clone());
 
     // Wire database repository to strategy engine for trade persistence
     strategy_engine.set_trade_repo(trade_repo.clone());
 
-    strategy_engine.add_strategy(Box::new(sniper));
-    strategy_engine.add_strategy(Box::new(clipper));
-    strategy_engine.add_strategy(Box::new(sum_to_100));
+    // Wire analytics sink so signals and near misses land in ClickHouse
+    if let Some(sink) = &analytics {
+        strategy_engine.set_analytics_sink(sink.clone());
+    }
+
+    // Record executed trades for drill-down from metric spikes (/debug/trades)
+    let recent_trades = Arc::new(RecentTrades::new());
+    strategy_engine.set_recent_trades(recent_trades.clone());
+    let volume = Arc::new(VolumeTracker::new(timezone));
+    strategy_engine.set_volume_tracker(volume.clone());
+
+    // One JSON line per decision, to diff against another version's run
+    if let Some(log) = DecisionLog::from_env()? {
+        strategy_engine.set_decision_log(Arc::new(log));
+    }
+
+    // Park large signals and new strategies for operator approval (/control/approvals)
+    let approvals = strategy_engine.set_approvals(ApprovalConfig::from_env());
+
+    // Wire leader election so standbys evaluate without executing
+    strategy_engine.set_leader_election(leader_election.clone());
+
+    // Observe mode records would-be signals instead of executing them
+    strategy_engine.set_observe(config.observe);
+
+    // Cold start: observe until the books have filled in
+    strategy_engine.set_warmup(WarmupConfig::from_env());
+    strategy_engine.set_volatility_gate(VolatilityGateConfig::from_env());
+    // Book P&L only for fills confirmed by polling order status
+    let order_tracking = OrderTrackingConfig::from_env();
+    let mut portfolio = Portfolio::new(market_data.clone(), risk_manager.clone());
+    if order_tracking.enabled {
+        let tracker = Arc::new(OrderTracker::new(order_tracking));
+        strategy_engine.set_order_tracker(tracker.clone());
+        portfolio.set_order_tracker(tracker);
+    }
+
+    // Retry strong opportunities blocked by risk limits or rate limits
+    strategy_engine.set_retries(RetryConfig::from_env());
+    strategy_engine.set_connectivity(connectivity.clone());
+
+    // Shadow, disable or flag strategies that break their kill rules
+    let governor_config = GovernorConfig::from_env();
+    if !governor_config.rules.is_empty() {
+        let mut governor = StrategyGovernor::new(governor_config);
+        governor.set_slack_notifier(slack_notifier.clone());
+        governor.set_audit_log(audit_log.clone());
+        strategy_engine.set_governor(governor);
+    }
+
+    // systemd watchdog and heartbeat file, beaten by the engine loop
+    let watchdog = Arc::new(Watchdog::new(WatchdogConfig::from_env()));
+    strategy_engine.set_watchdog(watchdog.clone());
+
+    // Live ESPN scores for Sniper race mode, pre-positioning and the fast path
+    let game_feed = if config.sniper.enabled
+        && (config.sniper.presign || config.sniper.preposition || config.sniper.fast_path)
+    {
+        let leagues = config
+            .sniper
+            .leagues
+            .iter()
+            .filter_map(|name| external::League::from_name(name))
+            .collect();
+        let mut espn = EspnClient::new(leagues, config.sniper.poll_interval_ms)?;
+        espn.set_poll_config(EspnPollConfig::from_env());
+        let espn = Arc::new(espn);
+        strategy_engine.set_game_feed(espn.clone());
+        Some(espn)
+    } else {
+        None
+    };
+
+    // Report large holder and open interest moves in held and watched markets
+    let whale_config = WhaleConfig::from_env();
+    let whales = whale_config.enabled.then(|| {
+        Arc::new(WhaleMonitor::new(
+            whale_config,
+            polymarket_data.clone(),
+            market_data.clone(),
+            risk_manager.clone(),
+        ))
+    });
+    if let Some(whales) = &whales {
+        strategy_engine.set_whale_monitor(whales.clone());
+    }
+
+    for strategy in strategies {
+        strategy_engine.add_strategy(strategy);
+    }
 
     info!(
         "SumTo100 strategy: {} | min_edge={:.1}% | paper_trading={}",
@@ -212,46 +480,435 @@ async fn main() -> Result<()> {
         config.sum_to_100.min_edge * 100.0,
         config.sum_to_100.paper_trading
     );
+    for (name, instance) in &config.sum_to_100_instances {
+        info!(
+            "SumTo100@{}: min_edge={:.1}% | categories={}",
+            name,
+            instance.min_edge * 100.0,
+            if instance.categories.is_empty() {
+                "all".to_string()
+            } else {
+                instance.categories.join(",")
+            }
+        );
+    }
 
-    // Create cancellation token for graceful shutdown
+    // Create cancellation token for graceful shutdown. Signal generation and
+    // the WebSocket get child tokens so shutdown can stop them in order.
     let cancellation_token = CancellationToken::new();
+    let signals_token = cancellation_token.child_token();
+    let ws_token = cancellation_token.child_token();
+    let shutdown_requested = CancellationToken::new();
 
-    // Start health check server (no cancellation needed - can be aborted immediately)
-    let health_state = Arc::new(HealthState {
-        start_time: Instant::now(),
-        market_data: market_data.clone(),
-    });
-    let health_task = tokio::spawn(start_health_server(health_state));
+    // Task poll times and scheduling delays, and runtime load
+    tokio::spawn(runtime::run_metrics(
+        runtime_config.metrics_interval,
+        cancellation_token.clone(),
+    ));
+
+    // Poll live game state for the Sniper
+    if let Some(espn) = &game_feed {
+        let espn = espn.clone();
+        tokio::spawn(
+            restarts
+                .clone()
+                .supervise("espn", cancellation_token.clone(), move || {
+                    let espn = espn.clone();
+                    async move { espn.run().await }
+                }),
+        );
+    }
+
+    // Open and close network incidents
+    tokio::spawn(connectivity.clone().run(cancellation_token.clone()));
+
+    // Contend for leadership (releases the lease on shutdown)
+    let leader_task = tokio::spawn(leader_election.clone().run(cancellation_token.clone()));
+
+    // Export market data sizes and evict tokens that went quiet
+    tokio::spawn(
+        market_data
+            .clone()
+            .run_housekeeping(HousekeepingConfig::from_env(), cancellation_token.clone()),
+    );
+
+    // Operator price alerts, for trading by hand around the bot
+    let price_alerts = PriceAlertConfig::from_env();
+    if !price_alerts.rules.is_empty() {
+        let alerts = Arc::new(PriceAlerts::new(
+            price_alerts,
+            market_data.clone(),
+            slack_notifier.clone(),
+        ));
+        tokio::spawn(alerts.run(cancellation_token.clone()));
+    }
+
+    // Dashboard state on Redis renders from the engine's status snapshots
+    if redis_publisher.is_enabled() {
+        let publisher = redis_publisher.clone();
+        let status = status_board.clone();
+        let cancel = cancellation_token.clone();
+        tokio::spawn(restarts.clone().supervise(
+            "redis_state",
+            cancellation_token.clone(),
+            move || {
+                publisher
+                    .clone()
+                    .run_state_forwarder(status.clone(), cancel.clone())
+            },
+        ));
+    }
+
+    // Daily loss and per-market budgets start over with each trading day
+    tokio::spawn(
+        risk_manager
+            .clone()
+            .run_daily_reset(timezone, cancellation_token.clone()),
+    );
+
+    // Month-to-date maker/taker volume, reported as each trading day ends
+    if slack_notifier.is_enabled() {
+        tokio::spawn(
+            volume
+                .clone()
+                .run_daily_report(slack_notifier.clone(), cancellation_token.clone()),
+        );
+    }
+
+    // Save subscriptions periodically so a crash loses at most one interval
+    if let Some(path) = market_state_path.clone() {
+        let market_data = market_data.clone();
+        let cancel = cancellation_token.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(Duration::from_secs(60));
+            ticker.tick().await;
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {
+                        if let Err(e) = market_data.persist_subscriptions(&path) {
+                            warn!("[MARKET] Failed to save subscriptions: {}", e);
+                        }
+                    }
+                    _ = cancel.cancelled() => break,
+                }
+            }
+        });
+    }
+
+    // Save counters periodically too: a crash or a kill without a graceful
+    // shutdown then restores from the last interval instead of from zero
+    if let Some(path) = metrics_state_path.clone() {
+        let cancel = cancellation_token.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(Duration::from_secs(60));
+            ticker.tick().await;
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {
+                        if let Err(e) = metrics::persist_to_file(&path) {
+                            warn!("[METRICS] Failed to save counters: {}", e);
+                        }
+                    }
+                    _ = cancel.cancelled() => break,
+                }
+            }
+        });
+    }
+
+    // Fetch minimum order sizes and tick sizes for subscribed markets
+    let rules_loader = OrderRulesLoader::new(&config.clob_url, market_data.clone())?;
+    tokio::spawn(rules_loader.run(cancellation_token.clone()));
+
+    if let Some(mock_exchange) = mock_exchange {
+        tokio::spawn(mock_exchange.run(cancellation_token.clone()));
+    }
+
+    // Sniper race mode: pre-signed orders fired on ESPN game completion
+    if let (true, Some(espn)) = (config.sniper.presign, game_feed.clone()) {
+        let mut racer = SniperRacer::new(
+            config.sniper.clone(),
+            market_data.clone(),
+            risk_manager.clone(),
+            order_manager.clone(),
+        );
+        racer.set_leader_election(leader_election.clone());
+        tokio::spawn(Arc::new(racer).run(espn, signals_token.clone()));
+    }
+
+    // Hedge one-sided positions (failed legs, Sniper holdings)
+    let hedge_config = HedgeConfig::from_env();
+    if hedge_config.enabled {
+        let mut hedger = Hedger::new(
+            hedge_config,
+            market_data.clone(),
+            risk_manager.clone(),
+            order_manager.clone(),
+        );
+        hedger.set_leader_election(leader_election.clone());
+        hedger.set_placed_orders(strategy_engine.placed_orders());
+        tokio::spawn(hedger.run(signals_token.clone()));
+    }
+
+    // Scale out of positions as their markets approach resolution
+    let exit_config = ExitConfig::from_env();
+    if exit_config.enabled {
+        let mut exits = ExitScheduler::new(
+            exit_config,
+            market_data.clone(),
+            risk_manager.clone(),
+            order_manager.clone(),
+        );
+        exits.set_leader_election(leader_election.clone());
+        exits.set_audit_log(audit_log.clone());
+        exits.set_placed_orders(strategy_engine.placed_orders());
+        tokio::spawn(exits.run(signals_token.clone()));
+    }
+
+    // Compare committed notional with the wallet balance, alerting before
+    // orders start failing for lack of funds
+    let funds_config = FundsConfig::from_env();
+    if funds_config.enabled {
+        let mut funds =
+            FundsMonitor::new(funds_config, order_manager.clone(), risk_manager.clone());
+        funds.set_slack_notifier(slack_notifier.clone());
+        let funds = Arc::new(funds);
+        strategy_engine.set_funds_monitor(funds.clone());
+        portfolio.set_funds_monitor(funds.clone());
+        tokio::spawn(funds.run(cancellation_token.clone()));
+    }
+
+    // Positions, exposure and NAV for the dashboard, every heartbeat
+    let portfolio = Arc::new(portfolio);
+    if redis_publisher.is_enabled() {
+        let publisher = redis_publisher.clone();
+        let status = status_board.clone();
+        let portfolio = portfolio.clone();
+        let cancel = cancellation_token.clone();
+        tokio::spawn(restarts.clone().supervise(
+            "redis_portfolio",
+            cancellation_token.clone(),
+            move || {
+                publisher.clone().run_portfolio_forwarder(
+                    status.clone(),
+                    portfolio.clone(),
+                    cancel.clone(),
+                )
+            },
+        ));
+    }
+
+    // Roll filled trades up into the P&L attribution table
+    tokio::spawn(run_pnl_attribution(trade_repo.clone(), cancellation_token.clone()));
+
+    // Rolling VaR / expected shortfall from the persisted P&L history
+    let var_config = VarConfig::from_env();
+    if var_config.enabled && trade_repo.is_enabled() {
+        let analytics = RiskAnalytics::new(
+            var_config,
+            trade_repo.clone(),
+            risk_manager.clone(),
+            order_manager.is_dry_run(),
+        );
+        tokio::spawn(analytics.run(cancellation_token.clone()));
+    }
+
+    // Batch analytics rows into ClickHouse and sample order books; stopped
+    // when shutdown flushes writes, so its last batch is flushed with them
+    let analytics_token = cancellation_token.child_token();
+    if let Some(sink) = analytics {
+        runtime::spawn(
+            TaskGroup::DbWrite,
+            sink.run(market_data.clone(), analytics_token.clone()),
+        );
+    }
+
+    // Share account-wide risk state with the other shards
+    if let (true, Some(url)) = (shard.is_sharded(), redis_url.as_deref()) {
+        let mut shared_risk = SharedRiskState::connect(
+            SharedRiskConfig::from_env(),
+            url,
+            shard,
+            &config.instance_id,
+            risk_manager.clone(),
+        )
+        .await?;
+        shared_risk.set_leader_election(leader_election.clone());
+        shared_risk.set_timezone(timezone);
+        tokio::spawn(shared_risk.run(cancellation_token.clone()));
+    }
+
+    // Accept operator commands (log level) on the Redis commands channel
+    if let Some(url) = redis_url.as_deref() {
+        let mut commands = CommandListener::new(
+            url,
+            redis_publisher.channel(channels::COMMANDS),
+            &config.instance_id,
+            log_filter.clone(),
+            audit_log.clone(),
+        );
+        commands.set_connectivity(connectivity.clone());
+        tokio::spawn(commands.run(cancellation_token.clone()));
+    }
+
+    // Start health/metrics/control HTTP server (stops with the cancellation token)
+    let http_server = HttpServer::bind(
+        HttpServerConfig::from_env()?,
+        HttpState {
+            start_time: Instant::now(),
+            market_data: market_data.clone(),
+            risk_manager: risk_manager.clone(),
+            audit: audit_log.clone(),
+            leader: leader_election.clone(),
+            trade_repo: trade_repo.clone(),
+            recent_trades: recent_trades.clone(),
+            subscriptions: subscriptions.clone(),
+            shutdown: shutdown_requested.clone(),
+            log_filter: log_filter.clone(),
+            volume: volume.clone(),
+            edge_tuner,
+            portfolio,
+            approvals: approvals.clone(),
+            status: status_board.clone(),
+            restarts: restarts.clone(),
+        },
+    )
+    .await?;
+    let http_task = tokio::spawn(http_server.run(cancellation_token.clone()));
 
     // Start WebSocket handler with cancellation support
-    let ws_handler = WebSocketHandler::new(
+    let mut ws_handler = WebSocketHandler::new(
         config.ws_url.clone(),
         market_data.clone(),
-        cancellation_token.clone(),
+        ws_token.clone(),
     );
-    let ws_task = tokio::spawn(async move {
-        if let Err(e) = ws_handler.run().await {
-            warn!("WebSocket error: {}", e);
+    ws_handler.set_transport(WsTransportConfig::from_env());
+    ws_handler.set_failover(WsFailoverConfig::from_env());
+    ws_handler.set_connectivity(connectivity.clone());
+    // Record the raw stream for replay with --profile
+    if let Some(capture) = WsCapture::from_env()? {
+        ws_handler.set_capture(Arc::new(capture));
+    }
+    // Register the market pair of tokens subscribed without one
+    if std::env::var("PAIR_RESOLVER_ENABLED").map_or(true, |v| v != "false" && v != "0") {
+        let resolver = Arc::new(PairResolver::new(&config.clob_url, market_data.clone())?);
+        ws_handler.set_pair_resolver(resolver.clone());
+        tokio::spawn(resolver.run(cancellation_token.clone()));
+    }
+
+    // Compare sampled books with exchange snapshots; diverged books are
+    // replaced and resubscribed through the WebSocket handler
+    let book_check = BookValidatorConfig::from_env();
+    if book_check.enabled {
+        let resync = Arc::new(ResyncRequests::default());
+        ws_handler.set_resync_requests(resync.clone());
+        let validator =
+            BookValidator::new(book_check, &config.clob_url, market_data.clone(), resync)?;
+        tokio::spawn(validator.run(cancellation_token.clone()));
+    }
+    if let Some(liquidity) = liquidity {
+        tokio::spawn(liquidity.run(market_data.clone(), cancellation_token.clone()));
+    }
+    if let Some(correlations) = correlations {
+        tokio::spawn(correlations.run(market_data.clone(), cancellation_token.clone()));
+    }
+    if let Some(whales) = whales {
+        tokio::spawn(whales.run(cancellation_token.clone()));
+    }
+    ws_handler.set_subscriptions(subscriptions.clone());
+
+    // Time round trips to the CLOB (REST requests and WebSocket pings)
+    let latency_config = LatencyProbeConfig::from_env();
+    if latency_config.enabled {
+        let mut probe = LatencyProbe::new(latency_config, &config.clob_url)?;
+        probe.set_slack_notifier(slack_notifier.clone());
+        let probe = Arc::new(probe);
+        ws_handler.set_latency_probe(probe.clone());
+        tokio::spawn(probe.run(cancellation_token.clone()));
+    }
+    {
+        let risk_manager = risk_manager.clone();
+        let held = move || {
+            risk_manager
+                .get_all_positions()
+                .into_iter()
+                .filter(|(_, p)| p.size > 0.0)
+                .map(|(token, _)| token)
+                .collect()
+        };
+        tokio::spawn(subscriptions.run(held.clone(), cancellation_token.clone()));
+
+        // Keep market metadata current and drop closed or delisted markets
+        let metadata_config = MetadataConfig::from_env();
+        if metadata_config.enabled {
+            let mut refresher = MetadataRefresher::new(metadata_config, market_data.clone())?;
+            refresher.set_data_client(polymarket_data.clone());
+            tokio::spawn(Arc::new(refresher).run(held, cancellation_token.clone()));
         }
-    });
+    }
+    // Rebroadcast the normalized book stream to local tools
+    let relay_config = RelayConfig::from_env();
+    if relay_config.enabled {
+        let relay = MarketRelay::new(relay_config, market_data.clone());
+        let cancel = cancellation_token.clone();
+        tokio::spawn(async move {
+            if let Err(e) = relay.run(cancel).await {
+                warn!("[RELAY] Relay stopped: {:#}", e);
+            }
+        });
+    }
 
-    // Wire cancellation token to strategy engine for graceful shutdown
-    strategy_engine.set_cancellation_token(cancellation_token.clone());
+    let ws_handler = Arc::new(ws_handler);
+    let ws_task = tokio::spawn(restarts.clone().supervise("ws", ws_token.clone(), move || {
+        let ws_handler = ws_handler.clone();
+        async move {
+            if let Err(e) = ws_handler.run().await {
+                warn!("WebSocket error: {}", e);
+            }
+        }
+    }));
+
+    // Wire the signal token to strategy engine for graceful shutdown
+    strategy_engine.set_cancellation_token(signals_token.clone());
+
+    // Slice large buys and sells into child orders over time
+    let twap_config = TwapConfig::from_env();
+    if twap_config.min_notional > 0.0 {
+        strategy_engine.set_twap_executor(Arc::new(TwapExecutor::new(
+            twap_config,
+            order_manager.clone(),
+            market_data.clone(),
+            risk_manager.clone(),
+            signals_token.clone(),
+        )));
+    }
 
     // Start strategy engine
-    let engine_task = tokio::spawn(async move {
-        strategy_engine.run().await;
-    });
+    let strategy_engine = Arc::new(tokio::sync::Mutex::new(strategy_engine));
+    let engine_task = runtime::spawn(
+        TaskGroup::Engine,
+        restarts
+            .clone()
+            .supervise("engine", signals_token.clone(), move || {
+                let strategy_engine = strategy_engine.clone();
+                async move { strategy_engine.lock().await.run().await }
+            }),
+    );
 
     info!("==========================================");
     info!("  TRADING ENGINE STARTED");
     info!("==========================================");
     info!("  - Health check: http://0.0.0.0:8080/health");
     info!("  - Metrics: http://0.0.0.0:8080/metrics");
-    info!("  - Strategies: {} active", 3);
+    info!("  - Strategies: {} active", strategy_count);
     info!(
         "  - Mode: {}",
-        if config.dry_run { "DRY RUN" } else { "LIVE" }
+        if config.observe {
+            "OBSERVE"
+        } else if config.dry_run {
+            "DRY RUN"
+        } else {
+            "LIVE"
+        }
     );
     info!(
         "  - Redis: {}",
@@ -279,40 +936,88 @@ async fn main() -> Result<()> {
     );
     info!("==========================================");
     info!("Press Ctrl+C to shutdown");
+    watchdog.notify_ready();
 
-    // Wait for shutdown signal
-    signal::ctrl_c().await?;
+    // Wait for shutdown signal (Ctrl+C, SIGTERM from Docker, Railway or
+    // systemd, or POST /control/shutdown)
+    let mut sigterm = signal::unix::signal(SignalKind::terminate())?;
+    tokio::select! {
+        result = signal::ctrl_c() => {
+            result?;
+            audit_log.record("signal", AuditAction::Shutdown, None, "SIGINT received");
+        }
+        _ = sigterm.recv() => {
+            audit_log.record("signal", AuditAction::Shutdown, None, "SIGTERM received");
+        }
+        _ = shutdown_requested.cancelled() => {}
+        _ = cancellation_token.cancelled() => {}
+    }
     info!("[SHUTDOWN] Signal received - initiating graceful shutdown...");
+    watchdog.notify_stopping();
+    let shutdown = Shutdown::new(ShutdownConfig::from_env());
+
+    // 1. No new signals: strategies, Sniper, hedger, exits and TWAP stop
+    signals_token.cancel();
+    shutdown
+        .stage("Stopping signal generation", engine_task)
+        .await;
+
+    // 2. Write barrier: refuse new orders, let those already sent finish
+    let in_flight = order_manager.in_flight();
+    if !shutdown
+        .stage(
+            &format!("Waiting for {} in-flight orders", in_flight),
+            order_manager.drain(),
+        )
+        .await
+    {
+        warn!(
+            "[SHUTDOWN] {} orders still in flight - their results may not be recorded",
+            order_manager.in_flight()
+        );
+    }
 
-    // Cancel all tasks that support graceful shutdown
-    cancellation_token.cancel();
-
-    // Wait for tasks with timeout (30 seconds max)
-    let shutdown_timeout = Duration::from_secs(30);
-
-    // Create a future that waits for both tasks to complete
-    let shutdown_future = async {
-        // Wait for WebSocket and engine tasks to complete gracefully
-        let ws_result = ws_task.await;
-        info!("[SHUTDOWN] WebSocket handler stopped (result: {:?})", ws_result.is_ok());
+    // 3. Trade inserts, Redis publishes and Slack posts spawned by those
+    // orders, and the last ClickHouse batch
+    analytics_token.cancel();
+    shutdown
+        .stage(
+            "Flushing DB, Redis and Slack writes",
+            shutdown::flush(&FLUSHED_GROUPS),
+        )
+        .await;
 
-        let engine_result = engine_task.await;
-        info!("[SHUTDOWN] Strategy engine stopped (result: {:?})", engine_result.is_ok());
-    };
+    // 4. Market data is no longer needed
+    ws_token.cancel();
+    shutdown.stage("Closing WebSocket", ws_task).await;
 
-    tokio::select! {
-        _ = tokio::time::sleep(shutdown_timeout) => {
-            warn!("[SHUTDOWN] Timeout waiting for tasks (30s) - forcing abort");
-            // Note: Tasks may already be dropped if the future was partially completed
-        }
-        _ = shutdown_future => {
-            info!("[SHUTDOWN] All tasks completed gracefully");
+    // 5. Everything else; the HTTP server drains in-flight requests and the
+    // leader lease is released so a standby can take over immediately
+    cancellation_token.cancel();
+    shutdown.stage("Stopping HTTP server", http_task).await;
+    shutdown.stage("Releasing leader lease", leader_task).await;
+
+    // Save counters so the next run continues from these totals
+    if let Some(path) = &metrics_state_path {
+        match metrics::persist_to_file(path) {
+            Ok(()) => info!("[SHUTDOWN] Metrics saved to {}", path.display()),
+            Err(e) => warn!("[SHUTDOWN] Failed to save metrics: {}", e),
         }
     }
 
-    // Health server can always be aborted immediately (no cleanup needed)
-    health_task.abort();
+    // Save subscriptions so the next run resubscribes immediately
+    if let Some(path) = &market_state_path {
+        match market_data.persist_subscriptions(path) {
+            Ok(tokens) => {
+                info!("[SHUTDOWN] {} subscribed tokens saved to {}", tokens, path.display())
+            }
+            Err(e) => warn!("[SHUTDOWN] Failed to save subscriptions: {}", e),
+        }
+    }
 
-    info!("[SHUTDOWN] Complete");
+    info!(
+        "[SHUTDOWN] Complete in {}ms",
+        shutdown.elapsed().as_millis()
+    );
     Ok(())
 }
@@ -0,0 +1,385 @@
+//! Operator price alerts.
+//!
+//! Rules from `PRICE_ALERTS` are checked against live prices every
+//! `PRICE_ALERT_INTERVAL_MS` and routed to the notifier, for trading by
+//! hand around the bot. A rule watches one token or both outcomes of a
+//! market, and fires at most once per cooldown for each token it watches.
+//!
+//! Format: `target:condition[:cooldown_secs]` entries separated by commas,
+//! where the target is `token=<token_id>` or `market=<market_id>` and the
+//! condition compares `bid`, `ask`, `mid` or `spread` with `<`, `<=`, `>`
+//! or `>=`, e.g. `token=123:ask<0.10,market=0xabc:spread>0.05:600`.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::metrics::PRICE_ALERTS_FIRED;
+use crate::notifications::{PriceAlert, SlackNotifier};
+
+use super::data::{MarketData, MarketId, PriceLevel, TokenId};
+
+/// What a rule watches
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlertTarget {
+    Token(TokenId),
+    /// Both outcome tokens of a market
+    Market(MarketId),
+}
+
+/// Price field a rule compares
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertField {
+    Bid,
+    Ask,
+    Mid,
+    Spread,
+}
+
+impl AlertField {
+    fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "bid" => Some(Self::Bid),
+            "ask" => Some(Self::Ask),
+            "mid" => Some(Self::Mid),
+            "spread" => Some(Self::Spread),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Bid => "bid",
+            Self::Ask => "ask",
+            Self::Mid => "mid",
+            Self::Spread => "spread",
+        }
+    }
+
+    fn value(self, price: &PriceLevel) -> f64 {
+        match self {
+            Self::Bid => price.bid,
+            Self::Ask => price.ask,
+            Self::Mid => price.mid,
+            Self::Spread => price.spread,
+        }
+    }
+}
+
+/// Comparison of a rule's condition
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertOp {
+    Below,
+    AtOrBelow,
+    Above,
+    AtOrAbove,
+}
+
+impl AlertOp {
+    /// Longest first, so `<=` is not read as `<`
+    const ALL: [(&'static str, AlertOp); 4] = [
+        ("<=", AlertOp::AtOrBelow),
+        (">=", AlertOp::AtOrAbove),
+        ("<", AlertOp::Below),
+        (">", AlertOp::Above),
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Below => "<",
+            Self::AtOrBelow => "<=",
+            Self::Above => ">",
+            Self::AtOrAbove => ">=",
+        }
+    }
+
+    fn holds(self, value: f64, threshold: f64) -> bool {
+        match self {
+            Self::Below => value < threshold,
+            Self::AtOrBelow => value <= threshold,
+            Self::Above => value > threshold,
+            Self::AtOrAbove => value >= threshold,
+        }
+    }
+}
+
+/// One alert rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertRule {
+    /// The entry as configured, for messages and the metric label
+    pub text: String,
+    pub target: AlertTarget,
+    pub field: AlertField,
+    pub op: AlertOp,
+    pub threshold: f64,
+    pub cooldown: Duration,
+}
+
+impl AlertRule {
+    /// Parse one `target:condition[:cooldown_secs]` entry.
+    pub fn parse(entry: &str, default_cooldown: Duration) -> Option<Self> {
+        let parts: Vec<&str> = entry.split(':').map(str::trim).collect();
+        let (target, condition, cooldown) = match parts.as_slice() {
+            [target, condition] => (*target, *condition, default_cooldown),
+            [target, condition, secs] => {
+                (*target, *condition, Duration::from_secs(secs.parse().ok()?))
+            }
+            _ => return None,
+        };
+
+        let target = match target.split_once('=')? {
+            ("token", id) if !id.trim().is_empty() => AlertTarget::Token(id.trim().to_string()),
+            ("market", id) if !id.trim().is_empty() => AlertTarget::Market(id.trim().to_string()),
+            _ => return None,
+        };
+        let (field, op, threshold) = AlertOp::ALL.iter().find_map(|(symbol, op)| {
+            let (field, threshold) = condition.split_once(symbol)?;
+            Some((
+                AlertField::parse(field.trim())?,
+                *op,
+                threshold.trim().parse::<f64>().ok()?,
+            ))
+        })?;
+
+        Some(Self {
+            text: entry.to_string(),
+            target,
+            field,
+            op,
+            threshold,
+            cooldown,
+        })
+    }
+}
+
+/// Price alert settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceAlertConfig {
+    pub rules: Vec<AlertRule>,
+    /// Time between checks
+    pub interval: Duration,
+}
+
+impl Default for PriceAlertConfig {
+    fn default() -> Self {
+        Self {
+            rules: Vec::new(),
+            interval: Duration::from_secs(1),
+        }
+    }
+}
+
+impl PriceAlertConfig {
+    /// Load from `PRICE_ALERTS`, `PRICE_ALERT_INTERVAL_MS` and
+    /// `PRICE_ALERT_COOLDOWN_SECS` (for rules without their own, default
+    /// 300). Invalid rules are skipped with a warning.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok();
+        let cooldown = var("PRICE_ALERT_COOLDOWN_SECS")
+            .and_then(|v| v.parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(300));
+        Self {
+            rules: var("PRICE_ALERTS")
+                .map(|v| parse_rules(&v, cooldown))
+                .unwrap_or_default(),
+            interval: var("PRICE_ALERT_INTERVAL_MS")
+                .and_then(|v| v.parse().ok())
+                .filter(|ms| *ms > 0)
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.interval),
+        }
+    }
+}
+
+fn parse_rules(value: &str, default_cooldown: Duration) -> Vec<AlertRule> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|e| !e.is_empty())
+        .filter_map(|entry| {
+            let rule = AlertRule::parse(entry, default_cooldown);
+            if rule.is_none() {
+                warn!("PRICE_ALERTS entry '{}' is invalid, skipping", entry);
+            }
+            rule
+        })
+        .collect()
+}
+
+/// Checks alert rules against market data and notifies when they fire
+pub struct PriceAlerts {
+    config: PriceAlertConfig,
+    market_data: Arc<MarketData>,
+    notifier: Arc<SlackNotifier>,
+    /// Last time each rule fired for each token
+    last_fired: Mutex<HashMap<(usize, TokenId), Instant>>,
+}
+
+impl PriceAlerts {
+    pub fn new(
+        config: PriceAlertConfig,
+        market_data: Arc<MarketData>,
+        notifier: Arc<SlackNotifier>,
+    ) -> Self {
+        Self {
+            config,
+            market_data,
+            notifier,
+            last_fired: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Check the rules every interval until cancelled.
+    pub async fn run(self: Arc<Self>, cancel: CancellationToken) {
+        info!(
+            "[ALERT] Watching {} price alert rule(s) every {:?}",
+            self.config.rules.len(),
+            self.config.interval
+        );
+        let mut ticker = tokio::time::interval(self.config.interval);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    for alert in self.check(Instant::now()) {
+                        info!(
+                            "[ALERT] {} fired: {} {} = {:.4}",
+                            alert.rule, alert.token_id, alert.field, alert.value
+                        );
+                        self.notifier.notify_price_alert(alert);
+                    }
+                }
+                _ = cancel.cancelled() => break,
+            }
+        }
+    }
+
+    /// Alerts that fire at `now`, starting their cooldowns.
+    fn check(&self, now: Instant) -> Vec<PriceAlert> {
+        let mut last_fired = self.last_fired.lock();
+        let mut alerts = Vec::new();
+        for (index, rule) in self.config.rules.iter().enumerate() {
+            let (tokens, market) = match &rule.target {
+                AlertTarget::Token(token_id) => (
+                    vec![token_id.clone()],
+                    self.market_data
+                        .get_market_id(token_id)
+                        .and_then(|id| self.market_data.get_pair(&id)),
+                ),
+                AlertTarget::Market(market_id) => match self.market_data.get_pair(market_id) {
+                    Some(pair) => (
+                        vec![pair.yes_token.clone(), pair.no_token.clone()],
+                        Some(pair),
+                    ),
+                    None => continue,
+                },
+            };
+
+            for token_id in tokens {
+                let Some(price) = self.market_data.get_price(&token_id) else {
+                    continue;
+                };
+                let value = rule.field.value(&price);
+                if !rule.op.holds(value, rule.threshold) {
+                    continue;
+                }
+                let key = (index, token_id.clone());
+                if last_fired
+                    .get(&key)
+                    .is_some_and(|fired| now.duration_since(*fired) < rule.cooldown)
+                {
+                    continue;
+                }
+                last_fired.insert(key, now);
+                PRICE_ALERTS_FIRED.with_label_values(&[&rule.text]).inc();
+
+                alerts.push(PriceAlert {
+                    rule: rule.text.clone(),
+                    token_id,
+                    market_id: market.as_ref().map(|p| p.market_id.clone()),
+                    question: market.as_ref().map(|p| p.question.clone()),
+                    field: rule.field.as_str().to_string(),
+                    op: rule.op.as_str().to_string(),
+                    threshold: rule.threshold,
+                    value,
+                    bid: price.bid,
+                    ask: price.ask,
+                });
+            }
+        }
+        alerts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market::MarketPair;
+
+    const COOLDOWN: Duration = Duration::from_secs(300);
+
+    #[test]
+    fn test_parse_rules() {
+        let rules = parse_rules(
+            "token=123:ask <= 0.10, market=0xabc:spread>0.05:60, token=1:last<0.5, bogus",
+            COOLDOWN,
+        );
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].target, AlertTarget::Token("123".into()));
+        assert_eq!(
+            (
+                rules[0].field,
+                rules[0].op,
+                rules[0].threshold,
+                rules[0].cooldown
+            ),
+            (AlertField::Ask, AlertOp::AtOrBelow, 0.10, COOLDOWN)
+        );
+        assert_eq!(rules[1].target, AlertTarget::Market("0xabc".into()));
+        assert_eq!(
+            (rules[1].field, rules[1].op, rules[1].cooldown),
+            (AlertField::Spread, AlertOp::Above, Duration::from_secs(60))
+        );
+    }
+
+    #[test]
+    fn test_rules_fire_once_per_cooldown() {
+        let market_data = Arc::new(MarketData::new());
+        market_data.register_pair(MarketPair {
+            market_id: "m1".into(),
+            yes_token: "yes".into(),
+            no_token: "no".into(),
+            question: "Will it?".into(),
+            category: None,
+            end_date: None,
+        });
+        market_data.update_price(&"yes".to_string(), 0.04, 0.06);
+        market_data.update_price(&"no".to_string(), 0.90, 0.96);
+
+        let config = PriceAlertConfig {
+            rules: parse_rules("token=yes:ask<0.10,market=m1:spread>0.05", COOLDOWN),
+            ..Default::default()
+        };
+        let alerts = PriceAlerts::new(
+            config,
+            market_data.clone(),
+            Arc::new(SlackNotifier::disabled()),
+        );
+
+        let start = Instant::now();
+        let fired = alerts.check(start);
+        assert_eq!(fired.len(), 2);
+        assert_eq!((fired[0].token_id.as_str(), fired[0].value), ("yes", 0.06));
+        assert_eq!(fired[0].question.as_deref(), Some("Will it?"));
+        assert_eq!(fired[1].token_id, "no");
+
+        // Still true, but cooling down
+        assert!(alerts.check(start + Duration::from_secs(10)).is_empty());
+        assert_eq!(alerts.check(start + COOLDOWN).len(), 2);
+    }
+}
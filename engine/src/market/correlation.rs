@@ -0,0 +1,340 @@
+//! Rolling correlations between markets and correlated exposure groups.
+//!
+//! Category tags are set by hand and miss markets that move together across
+//! categories (a candidate's primary and general election odds, a team's
+//! game and season markets). Every interval the YES mid of each market is
+//! sampled; the correlation of every pair's mid changes over the last
+//! `window` samples is computed, and markets whose returns correlate at
+//! least `threshold` in absolute value are linked. Linked markets (directly
+//! or through others) form an exposure group, named after its
+//! lexicographically first market. Negative correlation counts as well: YES
+//! on one market and NO on the other is the same bet twice.
+//!
+//! The risk manager caps the open cost basis of each group at
+//! `max_group_exposure`, so correlated positions share one budget.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+
+use dashmap::DashMap;
+use parking_lot::Mutex;
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+
+use crate::metrics::{CORRELATED_MARKETS, CORRELATION_GROUPS};
+
+use super::data::{MarketData, MarketId};
+
+/// Correlation grouping settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorrelationConfig {
+    pub enabled: bool,
+
+    /// Time between mid samples
+    pub interval: Duration,
+
+    /// Samples the correlations are computed over
+    pub window: usize,
+
+    /// Returns two markets need in common before they are compared
+    pub min_samples: usize,
+
+    /// Absolute correlation at which two markets are grouped
+    pub threshold: f64,
+
+    /// Largest open cost basis (USD) per group (0 = groups are only reported)
+    pub max_group_exposure: f64,
+}
+
+impl Default for CorrelationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval: Duration::from_secs(60),
+            window: 240,
+            min_samples: 30,
+            threshold: 0.7,
+            max_group_exposure: 0.0,
+        }
+    }
+}
+
+impl CorrelationConfig {
+    /// Load from `CORRELATION_ENABLED`, `CORRELATION_INTERVAL_MS`,
+    /// `CORRELATION_WINDOW`, `CORRELATION_MIN_SAMPLES`,
+    /// `CORRELATION_THRESHOLD` and `CORRELATION_MAX_GROUP_EXPOSURE`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok();
+        let count = |name: &str, default: usize| {
+            var(name)
+                .and_then(|v| v.parse().ok())
+                .filter(|n: &usize| *n >= 2)
+                .unwrap_or(default)
+        };
+        Self {
+            enabled: var("CORRELATION_ENABLED")
+                .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+                .unwrap_or(defaults.enabled),
+            interval: var("CORRELATION_INTERVAL_MS")
+                .and_then(|v| v.parse().ok())
+                .filter(|ms| *ms > 0)
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.interval),
+            window: count("CORRELATION_WINDOW", defaults.window),
+            min_samples: count("CORRELATION_MIN_SAMPLES", defaults.min_samples),
+            threshold: var("CORRELATION_THRESHOLD")
+                .and_then(|v| v.parse().ok())
+                .filter(|t: &f64| *t > 0.0 && *t <= 1.0)
+                .unwrap_or(defaults.threshold),
+            max_group_exposure: var("CORRELATION_MAX_GROUP_EXPOSURE")
+                .and_then(|v| v.parse().ok())
+                .filter(|x: &f64| x.is_finite() && *x >= 0.0)
+                .unwrap_or(defaults.max_group_exposure),
+        }
+    }
+}
+
+/// Correlated exposure groups, refreshed periodically.
+pub struct MarketCorrelations {
+    config: CorrelationConfig,
+    /// YES mid per sample, newest last (None when the market had no price)
+    history: Mutex<HashMap<MarketId, VecDeque<Option<f64>>>>,
+    /// Group of every market correlated with at least one other
+    groups: DashMap<MarketId, MarketId>,
+}
+
+impl MarketCorrelations {
+    pub fn new(config: CorrelationConfig) -> Self {
+        Self {
+            config,
+            history: Mutex::new(HashMap::new()),
+            groups: DashMap::new(),
+        }
+    }
+
+    pub fn config(&self) -> &CorrelationConfig {
+        &self.config
+    }
+
+    /// Exposure group of a market, if it correlates with any other.
+    pub fn group_of(&self, market_id: &MarketId) -> Option<MarketId> {
+        self.groups.get(market_id).map(|g| g.clone())
+    }
+
+    #[cfg(test)]
+    pub(crate) fn set_group(&self, market_id: &str, group: &str) {
+        self.groups.insert(market_id.to_string(), group.to_string());
+    }
+
+    /// Sample mids and regroup markets until cancelled.
+    pub async fn run(self: Arc<Self>, market_data: Arc<MarketData>, cancel: CancellationToken) {
+        info!(
+            "[MARKET] Correlation grouping every {:?} over {} samples (threshold {:.2})",
+            self.config.interval, self.config.window, self.config.threshold
+        );
+        let mut ticker = tokio::time::interval(self.config.interval);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => self.refresh(&market_data),
+                _ = cancel.cancelled() => break,
+            }
+        }
+    }
+
+    fn refresh(&self, market_data: &MarketData) {
+        let returns = self.sample(market_data);
+        let groups = cluster(&returns, self.config.min_samples, self.config.threshold);
+
+        let mut sizes: HashMap<&MarketId, usize> = HashMap::new();
+        for group in groups.values() {
+            *sizes.entry(group).or_default() += 1;
+        }
+        CORRELATION_GROUPS.set(sizes.len() as f64);
+        CORRELATED_MARKETS.set(groups.len() as f64);
+
+        self.groups.retain(|market, _| groups.contains_key(market));
+        for (market, group) in groups {
+            self.groups.insert(market, group);
+        }
+    }
+
+    /// Record each market's mid and return the mid changes of the window,
+    /// aligned so the last entries of every series are the same samples.
+    fn sample(&self, market_data: &MarketData) -> Vec<(MarketId, Vec<Option<f64>>)> {
+        let mut history = self.history.lock();
+        let mut live = HashMap::new();
+        for pair in market_data.iter_pairs() {
+            let mid = market_data.get_price(&pair.yes_token).map(|p| p.mid);
+            let mut mids = history.remove(&pair.market_id).unwrap_or_default();
+            mids.push_back(mid);
+            while mids.len() > self.config.window + 1 {
+                mids.pop_front();
+            }
+            live.insert(pair.market_id, mids);
+        }
+        // Markets that are gone (evicted or unregistered) are dropped
+        *history = live;
+
+        history
+            .iter()
+            .map(|(market, mids)| {
+                let returns = mids
+                    .iter()
+                    .zip(mids.iter().skip(1))
+                    .map(|(prev, next)| Some(next.as_ref()? - prev.as_ref()?))
+                    .collect();
+                (market.clone(), returns)
+            })
+            .collect()
+    }
+}
+
+/// Pearson correlation of two return series aligned at their ends, over the
+/// samples both have. None with too few samples or a flat series.
+fn correlation(a: &[Option<f64>], b: &[Option<f64>], min_samples: usize) -> Option<f64> {
+    let len = a.len().min(b.len());
+    let pairs: Vec<(f64, f64)> = a[a.len() - len..]
+        .iter()
+        .zip(&b[b.len() - len..])
+        .filter_map(|(x, y)| Some(((*x)?, (*y)?)))
+        .collect();
+    if pairs.len() < min_samples.max(2) {
+        return None;
+    }
+
+    let n = pairs.len() as f64;
+    let mean_x = pairs.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = pairs.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let (mut cov, mut var_x, mut var_y) = (0.0, 0.0, 0.0);
+    for (x, y) in &pairs {
+        let (dx, dy) = (x - mean_x, y - mean_y);
+        cov += dx * dy;
+        var_x += dx * dx;
+        var_y += dy * dy;
+    }
+    if var_x <= f64::EPSILON || var_y <= f64::EPSILON {
+        return None;
+    }
+    Some(cov / (var_x * var_y).sqrt())
+}
+
+/// Link markets whose returns correlate at least `threshold` in absolute
+/// value and return the group of every market in a group of two or more.
+fn cluster(
+    returns: &[(MarketId, Vec<Option<f64>>)],
+    min_samples: usize,
+    threshold: f64,
+) -> HashMap<MarketId, MarketId> {
+    // Markets that never moved cannot correlate with anything
+    let moving: Vec<&(MarketId, Vec<Option<f64>>)> = returns
+        .iter()
+        .filter(|(_, r)| r.iter().flatten().any(|x| x.abs() > f64::EPSILON))
+        .collect();
+
+    let mut parent: Vec<usize> = (0..moving.len()).collect();
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+
+    for i in 0..moving.len() {
+        for j in i + 1..moving.len() {
+            let linked = correlation(&moving[i].1, &moving[j].1, min_samples)
+                .is_some_and(|c| c.abs() >= threshold);
+            if linked {
+                let (ri, rj) = (root(&mut parent, i), root(&mut parent, j));
+                parent[ri] = rj;
+            }
+        }
+    }
+
+    let mut members: HashMap<usize, Vec<&MarketId>> = HashMap::new();
+    for (i, (market, _)) in moving.iter().enumerate() {
+        let r = root(&mut parent, i);
+        members.entry(r).or_default().push(market);
+    }
+
+    let mut groups = HashMap::new();
+    for markets in members.into_values().filter(|m| m.len() > 1) {
+        let name = markets.iter().min().copied().cloned().unwrap_or_default();
+        for market in markets {
+            groups.insert(market.clone(), name.clone());
+        }
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market::MarketPair;
+
+    fn series(values: &[f64]) -> Vec<Option<f64>> {
+        values.iter().copied().map(Some).collect()
+    }
+
+    #[test]
+    fn test_correlation() {
+        let a = series(&[0.01, -0.02, 0.03, 0.0, -0.01]);
+        let b = series(&[0.02, -0.04, 0.06, 0.0, -0.02]);
+        let c = series(&[-0.01, 0.02, -0.03, 0.0, 0.01]);
+        assert!((correlation(&a, &b, 3).unwrap() - 1.0).abs() < 1e-9);
+        assert!((correlation(&a, &c, 3).unwrap() + 1.0).abs() < 1e-9);
+        assert!(correlation(&a, &b, 10).is_none());
+        assert!(correlation(&a, &series(&[0.01; 5]), 3).is_none());
+
+        // Series are aligned at their ends; gaps are skipped
+        let mut short = b[2..].to_vec();
+        short[1] = None;
+        assert!((correlation(&a, &short, 2).unwrap() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_clusters_link_transitively() {
+        let up = [0.01, -0.02, 0.03, -0.01, 0.02, -0.03];
+        let noise = [0.02, 0.01, -0.01, -0.02, 0.03, 0.0];
+        let returns = vec![
+            ("b".to_string(), series(&up)),
+            ("a".to_string(), series(&up.map(|x| -x))),
+            ("c".to_string(), series(&up.map(|x| 0.9 * x))),
+            ("d".to_string(), series(&noise)),
+            ("flat".to_string(), series(&[0.0; 6])),
+        ];
+        let groups = cluster(&returns, 5, 0.8);
+        assert_eq!(groups.len(), 3);
+        assert!(["a", "b", "c"].iter().all(|m| groups[*m] == "a"));
+        assert!(!groups.contains_key("d"));
+    }
+
+    #[test]
+    fn test_refresh_groups_markets_that_move_together() {
+        let data = MarketData::new();
+        for id in ["x", "y"] {
+            data.register_pair(MarketPair {
+                market_id: id.into(),
+                yes_token: format!("{}-yes", id),
+                no_token: format!("{}-no", id),
+                question: "Q?".into(),
+                category: None,
+                end_date: None,
+            });
+        }
+        let correlations = MarketCorrelations::new(CorrelationConfig {
+            min_samples: 4,
+            ..CorrelationConfig::default()
+        });
+
+        for mid in [0.50, 0.52, 0.49, 0.55, 0.53, 0.58] {
+            data.update_price(&"x-yes".to_string(), mid - 0.01, mid + 0.01);
+            data.update_price(&"y-yes".to_string(), mid / 2.0 - 0.01, mid / 2.0 + 0.01);
+            correlations.refresh(&data);
+        }
+        assert_eq!(correlations.group_of(&"y".to_string()).as_deref(), Some("x"));
+        assert_eq!(correlations.group_of(&"unknown".to_string()), None);
+    }
+}
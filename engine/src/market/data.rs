@@ -1,10 +1,27 @@
 //! Lock-free market data storage.
 
+use chrono::{DateTime, Utc};
+use dashmap::mapref::entry::Entry;
 use dashmap::DashMap;
-use parking_lot::RwLock;
-use std::collections::VecDeque;
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, VecDeque};
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::{broadcast, watch};
+use tracing::debug;
+
+use crate::cluster::ShardConfig;
+use crate::metrics::PRICE_BOOK_DIVERGENCE;
+
+use super::order_rules::OrderRules;
+
+/// Prices closer than this are the same level
+const PRICE_EPSILON: f64 = 1e-9;
+
+/// Quote updates buffered per listener before a slow one starts skipping
+const UPDATE_CAPACITY: usize = 4096;
 
 /// Token ID type (Polymarket uses hex strings)
 pub type TokenId = String;
@@ -35,6 +52,100 @@ pub struct VwapResult {
     pub total_size: f64,
     /// Number of depth levels consumed
     pub levels_used: usize,
+    /// Price of the deepest level consumed
+    pub worst_price: f64,
+}
+
+/// Market impact assumptions applied to displayed depth before sizing.
+///
+/// Our own order consumes the book and quoted depth often fades before we
+/// arrive, so only a fraction of each displayed level is treated as fillable.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ImpactModel {
+    /// Maximum fraction of each displayed level we expect to take (0.0 - 1.0]
+    pub max_participation: f64,
+    /// Fraction of displayed depth assumed to fade before our order arrives [0.0 - 1.0)
+    pub depth_haircut: f64,
+}
+
+impl ImpactModel {
+    /// No impact: the full displayed depth is fillable
+    pub fn none() -> Self {
+        Self {
+            max_participation: 1.0,
+            depth_haircut: 0.0,
+        }
+    }
+
+    /// Fraction of displayed size at each level that is treated as fillable
+    #[inline]
+    pub fn depth_factor(&self) -> f64 {
+        (self.max_participation * (1.0 - self.depth_haircut)).clamp(0.0, 1.0)
+    }
+}
+
+impl Default for ImpactModel {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+/// Walk depth levels to fill `target_size`, treating only `depth_factor` of
+/// each level's size as available.
+fn walk_levels(levels: &[DepthLevel], target_size: f64, depth_factor: f64) -> Option<VwapResult> {
+    if levels.is_empty() || target_size <= 0.0 || depth_factor <= 0.0 {
+        return None;
+    }
+
+    let mut remaining = target_size;
+    let mut total_value = 0.0;
+    let mut total_filled = 0.0;
+    let mut levels_used = 0;
+    let mut worst_price = 0.0;
+
+    for level in levels {
+        if remaining <= 0.0 {
+            break;
+        }
+
+        let fill_size = remaining.min(level.size * depth_factor);
+        total_value += fill_size * level.price;
+        total_filled += fill_size;
+        remaining -= fill_size;
+        levels_used += 1;
+        worst_price = level.price;
+    }
+
+    if total_filled > 0.0 {
+        Some(VwapResult {
+            vwap: total_value / total_filled,
+            total_size: total_filled,
+            levels_used,
+            worst_price,
+        })
+    } else {
+        None
+    }
+}
+
+/// Keep the first `max_levels` levels (best first) and fold the rest into a
+/// single level at their volume-weighted price, so total size and
+/// full-depth VWAP are preserved. `max_levels == 0` keeps everything.
+fn truncate_levels(mut levels: Vec<DepthLevel>, max_levels: usize) -> Vec<DepthLevel> {
+    if max_levels == 0 || levels.len() <= max_levels + 1 {
+        return levels;
+    }
+
+    let (size, value) = levels[max_levels..]
+        .iter()
+        .fold((0.0, 0.0), |(size, value), l| {
+            (size + l.size, value + l.price * l.size)
+        });
+    levels.truncate(max_levels);
+    if size > 0.0 {
+        levels.push(DepthLevel::new(value / size, size));
+    }
+    levels
 }
 
 /// Full order book for a token
@@ -80,71 +191,47 @@ impl OrderBook {
     /// Calculate VWAP for buying (lifting asks)
     /// Returns the volume-weighted average price to fill `target_size` shares
     pub fn vwap_buy(&self, target_size: f64) -> Option<VwapResult> {
-        if self.asks.is_empty() || target_size <= 0.0 {
-            return None;
-        }
-
-        let mut remaining = target_size;
-        let mut total_cost = 0.0;
-        let mut total_filled = 0.0;
-        let mut levels_used = 0;
-
-        for level in &self.asks {
-            if remaining <= 0.0 {
-                break;
-            }
+        walk_levels(&self.asks, target_size, 1.0)
+    }
 
-            let fill_size = remaining.min(level.size);
-            total_cost += fill_size * level.price;
-            total_filled += fill_size;
-            remaining -= fill_size;
-            levels_used += 1;
-        }
+    /// Calculate VWAP for buying after applying a market impact model
+    /// (participation cap and depth haircut) to the displayed asks
+    pub fn vwap_buy_with_impact(
+        &self,
+        target_size: f64,
+        impact: &ImpactModel,
+    ) -> Option<VwapResult> {
+        walk_levels(&self.asks, target_size, impact.depth_factor())
+    }
 
-        if total_filled > 0.0 {
-            Some(VwapResult {
-                vwap: total_cost / total_filled,
-                total_size: total_filled,
-                levels_used,
-            })
-        } else {
-            None
-        }
+    /// VWAP for buying up to `target_size` from asks priced at or below
+    /// `max_price` (a marketable limit order's sweep)
+    pub fn vwap_buy_within(&self, target_size: f64, max_price: f64) -> Option<VwapResult> {
+        let end = self.asks.partition_point(|l| l.price <= max_price + 1e-9);
+        walk_levels(&self.asks[..end], target_size, 1.0)
     }
 
     /// Calculate VWAP for selling (hitting bids)
     /// Returns the volume-weighted average price to fill `target_size` shares
     pub fn vwap_sell(&self, target_size: f64) -> Option<VwapResult> {
-        if self.bids.is_empty() || target_size <= 0.0 {
-            return None;
-        }
-
-        let mut remaining = target_size;
-        let mut total_proceeds = 0.0;
-        let mut total_filled = 0.0;
-        let mut levels_used = 0;
-
-        for level in &self.bids {
-            if remaining <= 0.0 {
-                break;
-            }
+        walk_levels(&self.bids, target_size, 1.0)
+    }
 
-            let fill_size = remaining.min(level.size);
-            total_proceeds += fill_size * level.price;
-            total_filled += fill_size;
-            remaining -= fill_size;
-            levels_used += 1;
-        }
+    /// Calculate VWAP for selling after applying a market impact model
+    /// (participation cap and depth haircut) to the displayed bids
+    pub fn vwap_sell_with_impact(
+        &self,
+        target_size: f64,
+        impact: &ImpactModel,
+    ) -> Option<VwapResult> {
+        walk_levels(&self.bids, target_size, impact.depth_factor())
+    }
 
-        if total_filled > 0.0 {
-            Some(VwapResult {
-                vwap: total_proceeds / total_filled,
-                total_size: total_filled,
-                levels_used,
-            })
-        } else {
-            None
-        }
+    /// VWAP for selling up to `target_size` into bids priced at or above
+    /// `min_price` (a marketable limit order's sweep)
+    pub fn vwap_sell_within(&self, target_size: f64, min_price: f64) -> Option<VwapResult> {
+        let end = self.bids.partition_point(|l| l.price >= min_price - 1e-9);
+        walk_levels(&self.bids[..end], target_size, 1.0)
     }
 
     /// Get total bid liquidity
@@ -170,12 +257,17 @@ impl OrderBook {
 
 /// A YES/NO token pair for a market
 #[allow(dead_code)]
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct MarketPair {
     pub market_id: MarketId,
     pub yes_token: TokenId,
     pub no_token: TokenId,
     pub question: String,
+    /// Market category (e.g. "sports", "politics") when known from metadata
+    pub category: Option<String>,
+    /// Scheduled end of trading when known from metadata
+    #[serde(default)]
+    pub end_date: Option<DateTime<Utc>>,
 }
 
 /// Price level for a token
@@ -206,6 +298,62 @@ impl PriceLevel {
     }
 }
 
+/// Top of book and depth of one token, written together so readers never
+/// see a price and a book that disagree
+#[derive(Clone, Debug)]
+struct TokenQuote {
+    price: PriceLevel,
+    /// Shared with snapshots; written copy-on-write
+    book: Option<Arc<OrderBook>>,
+    /// Full book snapshots received since the token was first (or last
+    /// evicted and) quoted
+    book_updates: u64,
+}
+
+impl TokenQuote {
+    /// Bring the book in line with a new top of book. Levels the new price
+    /// has crossed out are removed; if the top still differs (a level we
+    /// have no size for), the book is dropped. Returns false if it was.
+    fn reconcile_book(&mut self) -> bool {
+        let Some(book) = &mut self.book else {
+            return true;
+        };
+        let book = Arc::make_mut(book);
+        let (bid, ask) = (self.price.bid, self.price.ask);
+        book.bids.retain(|l| l.price <= bid + PRICE_EPSILON);
+        book.asks.retain(|l| l.price >= ask - PRICE_EPSILON);
+
+        let top_matches = |top: Option<f64>, price: f64, empty: f64| {
+            (top.unwrap_or(empty) - price).abs() <= PRICE_EPSILON
+        };
+        if top_matches(book.best_bid(), bid, 0.0) && top_matches(book.best_ask(), ask, 1.0) {
+            book.timestamp_ns = self.price.timestamp_ns;
+            true
+        } else {
+            self.book = None;
+            false
+        }
+    }
+}
+
+/// A change to a token's quote, for listeners outside the engine
+#[derive(Clone, Debug)]
+pub enum QuoteUpdate {
+    /// New full book (the top of book follows from it)
+    Book(Arc<OrderBook>),
+    /// New top of book
+    Price { token_id: TokenId, price: PriceLevel },
+}
+
+impl QuoteUpdate {
+    pub fn token_id(&self) -> &TokenId {
+        match self {
+            QuoteUpdate::Book(book) => &book.token_id,
+            QuoteUpdate::Price { token_id, .. } => token_id,
+        }
+    }
+}
+
 /// Price history entry
 #[allow(dead_code)]
 #[derive(Clone, Copy, Debug)]
@@ -214,14 +362,71 @@ pub struct PriceTick {
     pub timestamp_ns: u64,
 }
 
+/// The last ticks of a token's history handed to a snapshot
+#[derive(Debug)]
+struct HistoryWindow {
+    len: usize,
+    /// `TokenHistory::pushes` when the window was cut
+    pushes: u64,
+    ticks: Arc<[PriceTick]>,
+}
+
+/// Price ticks of one token
+#[derive(Debug, Default)]
+struct TokenHistory {
+    ticks: VecDeque<PriceTick>,
+    /// Ticks pushed so far; the cached window is current while it matches
+    pushes: u64,
+    window: Mutex<Option<HistoryWindow>>,
+}
+
+impl TokenHistory {
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            ticks: VecDeque::with_capacity(capacity),
+            ..Self::default()
+        }
+    }
+
+    /// The last `len` ticks (oldest first), rebuilt only after new ticks.
+    fn recent(&self, len: usize) -> Arc<[PriceTick]> {
+        let mut window = self.window.lock();
+        match &*window {
+            Some(cached) if cached.len == len && cached.pushes == self.pushes => {
+                cached.ticks.clone()
+            }
+            _ => {
+                let skip = self.ticks.len().saturating_sub(len);
+                let ticks: Arc<[PriceTick]> = self.ticks.iter().skip(skip).copied().collect();
+                *window = Some(HistoryWindow {
+                    len,
+                    pushes: self.pushes,
+                    ticks: ticks.clone(),
+                });
+                ticks
+            }
+        }
+    }
+}
+
+/// Entry counts for memory observability
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MarketDataStats {
+    /// Tokens with a price
+    pub tokens: usize,
+    pub books: usize,
+    /// Bid and ask levels across all books
+    pub depth_levels: usize,
+    /// Price ticks across all histories
+    pub history_entries: usize,
+    pub pairs: usize,
+}
+
 /// Lock-free market data store
 #[allow(dead_code)]
 pub struct MarketData {
-    /// Token prices (lock-free reads via DashMap)
-    prices: DashMap<TokenId, PriceLevel>,
-
-    /// Full order books per token (lock-free reads via DashMap)
-    order_books: DashMap<TokenId, OrderBook>,
+    /// Top of book and full depth per token (lock-free reads via DashMap)
+    quotes: DashMap<TokenId, TokenQuote>,
 
     /// Market pairs (YES/NO mapping)
     pairs: DashMap<MarketId, MarketPair>,
@@ -230,13 +435,31 @@ pub struct MarketData {
     token_to_market: DashMap<TokenId, MarketId>,
 
     /// Price history per token (for crash detection, etc.)
-    history: DashMap<TokenId, RwLock<VecDeque<PriceTick>>>,
+    history: DashMap<TokenId, RwLock<TokenHistory>>,
 
     /// Last update timestamp (atomic for lock-free access)
     last_update_ns: AtomicU64,
 
+    /// Tokens to subscribe to that have no price or market pair yet
+    tracked_tokens: DashMap<TokenId, ()>,
+
     /// History size limit
     max_history_size: usize,
+
+    /// Markets outside this shard are not registered
+    shard: ShardConfig,
+
+    /// Levels kept per book side (0 = unlimited)
+    max_book_levels: usize,
+
+    /// Minimum order size and tick size per token
+    pub(super) order_rules: DashMap<TokenId, OrderRules>,
+
+    /// Every price and book update, for listeners outside the engine
+    updates: broadcast::Sender<QuoteUpdate>,
+
+    /// Bumped whenever the set of subscription tokens changes
+    token_changes: watch::Sender<u64>,
 }
 
 #[allow(dead_code)]
@@ -247,23 +470,76 @@ impl MarketData {
 
     pub fn with_history_size(max_history_size: usize) -> Self {
         Self {
-            prices: DashMap::new(),
-            order_books: DashMap::new(),
+            quotes: DashMap::new(),
             pairs: DashMap::new(),
             token_to_market: DashMap::new(),
             history: DashMap::new(),
             last_update_ns: AtomicU64::new(0),
+            tracked_tokens: DashMap::new(),
             max_history_size,
+            shard: ShardConfig::default(),
+            max_book_levels: 0,
+            order_rules: DashMap::new(),
+            updates: broadcast::channel(UPDATE_CAPACITY).0,
+            token_changes: watch::channel(0).0,
         }
     }
 
-    /// Update price for a token (lock-free for readers)
+    /// Receive every price and book update from now on. Updates are only
+    /// built while someone is listening.
+    pub fn subscribe_updates(&self) -> broadcast::Receiver<QuoteUpdate> {
+        self.updates.subscribe()
+    }
+
+    /// Notified whenever tokens are registered, tracked or removed, so a
+    /// live WebSocket can update its subscription without reconnecting.
+    pub fn watch_tokens(&self) -> watch::Receiver<u64> {
+        self.token_changes.subscribe()
+    }
+
+    fn tokens_changed(&self) {
+        self.token_changes
+            .send_modify(|generation| *generation += 1);
+    }
+
+    /// Restrict registration to markets owned by this shard.
+    pub fn set_shard(&mut self, shard: ShardConfig) {
+        self.shard = shard;
+    }
+
+    /// Keep at most `levels` levels per book side (0 = unlimited); depth
+    /// beyond the cut is folded into one aggregate level.
+    pub fn set_max_book_levels(&mut self, levels: usize) {
+        self.max_book_levels = levels;
+    }
+
+    /// Update the top of book for a token (lock-free for readers).
+    ///
+    /// The stored book is trimmed to agree with the new prices. Returns
+    /// false if it could not be and was dropped; the caller should fetch a
+    /// fresh book.
     #[inline]
-    pub fn update_price(&self, token_id: &TokenId, bid: f64, ask: f64) {
+    pub fn update_price(&self, token_id: &TokenId, bid: f64, ask: f64) -> bool {
         let level = PriceLevel::new(bid, ask);
 
-        // Update price
-        self.prices.insert(token_id.clone(), level);
+        let consistent = match self.quotes.entry(token_id.clone()) {
+            Entry::Occupied(mut entry) => {
+                let quote = entry.get_mut();
+                quote.price = level;
+                quote.reconcile_book()
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(TokenQuote {
+                    price: level,
+                    book: None,
+                    book_updates: 0,
+                });
+                true
+            }
+        };
+        if !consistent {
+            PRICE_BOOK_DIVERGENCE.inc();
+        }
 
         // Update last update timestamp
         self.last_update_ns
@@ -271,27 +547,43 @@ impl MarketData {
 
         // Add to history
         self.add_to_history(token_id, level.mid, level.timestamp_ns);
+
+        if self.updates.receiver_count() > 0 {
+            let _ = self.updates.send(QuoteUpdate::Price {
+                token_id: token_id.clone(),
+                price: level,
+            });
+        }
+        consistent
     }
 
     /// Get current price for a token (lock-free)
     #[inline]
     pub fn get_price(&self, token_id: &TokenId) -> Option<PriceLevel> {
-        self.prices.get(token_id).map(|p| *p)
+        self.quotes.get(token_id).map(|q| q.price)
     }
 
     /// Get best ask price for a token
     #[inline]
     pub fn get_ask(&self, token_id: &TokenId) -> Option<f64> {
-        self.prices.get(token_id).map(|p| p.ask)
+        self.quotes.get(token_id).map(|q| q.price.ask)
     }
 
     /// Get best bid price for a token
     #[inline]
     pub fn get_bid(&self, token_id: &TokenId) -> Option<f64> {
-        self.prices.get(token_id).map(|p| p.bid)
+        self.quotes.get(token_id).map(|q| q.price.bid)
+    }
+
+    /// Get the price and book of a token as of the same update.
+    pub fn get_quote(&self, token_id: &TokenId) -> Option<(PriceLevel, Option<OrderBook>)> {
+        self.quotes
+            .get(token_id)
+            .map(|q| (q.price, q.book.as_deref().cloned()))
     }
 
-    /// Update full order book for a token (preserves depth)
+    /// Update full order book for a token (preserves depth). The top of
+    /// book price is set from the same levels in the same write.
     #[inline]
     pub fn update_order_book(
         &self,
@@ -306,19 +598,48 @@ impl MarketData {
 
         let order_book = OrderBook {
             token_id: token_id.clone(),
-            bids,
-            asks,
+            bids: truncate_levels(bids, self.max_book_levels),
+            asks: truncate_levels(asks, self.max_book_levels),
             timestamp_ns: now,
         };
+        let mut price = PriceLevel::new(
+            order_book.best_bid().unwrap_or(0.0),
+            order_book.best_ask().unwrap_or(1.0),
+        );
+        price.timestamp_ns = now;
 
-        self.order_books.insert(token_id.clone(), order_book);
+        let order_book = Arc::new(order_book);
+        if self.updates.receiver_count() > 0 {
+            let _ = self.updates.send(QuoteUpdate::Book(order_book.clone()));
+        }
+        match self.quotes.entry(token_id.clone()) {
+            Entry::Occupied(mut entry) => {
+                let quote = entry.get_mut();
+                quote.price = price;
+                quote.book = Some(order_book);
+                quote.book_updates += 1;
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(TokenQuote {
+                    price,
+                    book: Some(order_book),
+                    book_updates: 1,
+                });
+            }
+        }
         self.last_update_ns.store(now, Ordering::Release);
+        self.add_to_history(token_id, price.mid, now);
     }
 
     /// Get full order book for a token (lock-free)
     #[inline]
     pub fn get_order_book(&self, token_id: &TokenId) -> Option<OrderBook> {
-        self.order_books.get(token_id).map(|ob| ob.clone())
+        self.quotes.get(token_id).and_then(|q| q.book.as_deref().cloned())
+    }
+
+    /// Number of full book snapshots received for a token (0 if unquoted)
+    pub fn book_updates(&self, token_id: &TokenId) -> u64 {
+        self.quotes.get(token_id).map_or(0, |q| q.book_updates)
     }
 
     /// Get order books for both YES and NO tokens in a pair
@@ -328,13 +649,49 @@ impl MarketData {
         Some((yes_book, no_book))
     }
 
-    /// Register a market pair
-    pub fn register_pair(&self, pair: MarketPair) {
+    /// Whether this instance's shard trades `market_id`.
+    pub fn owns_market(&self, market_id: &MarketId) -> bool {
+        self.shard.owns(market_id)
+    }
+
+    /// Register a market pair.
+    ///
+    /// Returns false (and registers nothing) if the market belongs to
+    /// another shard.
+    pub fn register_pair(&self, pair: MarketPair) -> bool {
+        if !self.owns_market(&pair.market_id) {
+            debug!(
+                "[SHARD] Skipping market {} (shard {} of {}, we are {})",
+                pair.market_id,
+                self.shard.shard_of(&pair.market_id),
+                self.shard.count,
+                self.shard.index
+            );
+            return false;
+        }
         self.token_to_market
             .insert(pair.yes_token.clone(), pair.market_id.clone());
         self.token_to_market
             .insert(pair.no_token.clone(), pair.market_id.clone());
-        self.pairs.insert(pair.market_id.clone(), pair);
+        if self.pairs.insert(pair.market_id.clone(), pair).is_none() {
+            self.tokens_changed();
+        }
+        true
+    }
+
+    /// Remove a market pair and everything stored for its tokens, so they
+    /// are no longer subscribed or traded. Returns the removed pair.
+    pub fn remove_pair(&self, market_id: &MarketId) -> Option<MarketPair> {
+        let (_, pair) = self.pairs.remove(market_id)?;
+        for token in [&pair.yes_token, &pair.no_token] {
+            self.token_to_market.remove(token);
+            self.tracked_tokens.remove(token);
+            self.quotes.remove(token);
+            self.history.remove(token);
+            self.order_rules.remove(token);
+        }
+        self.tokens_changed();
+        Some(pair)
     }
 
     /// Get market pair by market ID
@@ -342,6 +699,11 @@ impl MarketData {
         self.pairs.get(market_id).map(|p| p.clone())
     }
 
+    /// Get the market ID a token belongs to
+    pub fn get_market_id(&self, token_id: &TokenId) -> Option<MarketId> {
+        self.token_to_market.get(token_id).map(|m| m.clone())
+    }
+
     /// Get complement token (YES -> NO, NO -> YES)
     pub fn get_complement(&self, token_id: &TokenId) -> Option<TokenId> {
         let market_id = self.token_to_market.get(token_id)?;
@@ -376,19 +738,82 @@ impl MarketData {
 
     /// Check if we have any market data yet
     pub fn has_data(&self) -> bool {
-        !self.prices.is_empty()
+        !self.quotes.is_empty()
     }
 
     /// Iterate over all prices
     pub fn iter_prices(&self) -> impl Iterator<Item = (TokenId, PriceLevel)> + '_ {
-        self.prices.iter().map(|r| (r.key().clone(), *r.value()))
+        self.quotes.iter().map(|r| (r.key().clone(), r.price))
+    }
+
+    /// Iterate over all prices with the book stored alongside each (shared,
+    /// not copied)
+    pub fn iter_quotes(
+        &self,
+    ) -> impl Iterator<Item = (TokenId, PriceLevel, Option<Arc<OrderBook>>)> + '_ {
+        self.quotes
+            .iter()
+            .map(|r| (r.key().clone(), r.price, r.book.clone()))
+    }
+
+    /// Track a token for subscription before any price arrives for it.
+    pub fn track_token(&self, token_id: TokenId) {
+        if self.tracked_tokens.insert(token_id, ()).is_none() {
+            self.tokens_changed();
+        }
+    }
+
+    /// Every token the WebSocket should subscribe to: tokens with prices,
+    /// both sides of each registered pair, and tracked tokens (sorted).
+    pub fn subscription_tokens(&self) -> Vec<TokenId> {
+        let mut tokens: BTreeSet<TokenId> = self.quotes.iter().map(|r| r.key().clone()).collect();
+        for pair in self.pairs.iter() {
+            tokens.insert(pair.yes_token.clone());
+            tokens.insert(pair.no_token.clone());
+        }
+        tokens.extend(self.tracked_tokens.iter().map(|r| r.key().clone()));
+        tokens.into_iter().collect()
+    }
+
+    /// Tokens that have an order book
+    pub fn order_book_tokens(&self) -> Vec<TokenId> {
+        self.quotes
+            .iter()
+            .filter(|r| r.book.is_some())
+            .map(|r| r.key().clone())
+            .collect()
+    }
+
+    /// Iterate over all order books
+    pub fn iter_order_books(&self) -> impl Iterator<Item = (TokenId, OrderBook)> + '_ {
+        self.quotes
+            .iter()
+            .filter_map(|r| Some((r.key().clone(), r.book.as_deref()?.clone())))
     }
 
     /// Get price history for a token
     pub fn get_history(&self, token_id: &TokenId) -> Option<Vec<PriceTick>> {
         self.history
             .get(token_id)
-            .map(|h| h.read().iter().copied().collect())
+            .map(|h| h.read().ticks.iter().copied().collect())
+    }
+
+    /// Get the last `ticks` history entries for a token (oldest first)
+    pub fn get_recent_history(&self, token_id: &TokenId, ticks: usize) -> Option<Vec<PriceTick>> {
+        self.history.get(token_id).map(|h| {
+            let history = h.read();
+            let skip = history.ticks.len().saturating_sub(ticks);
+            history.ticks.iter().skip(skip).copied().collect()
+        })
+    }
+
+    /// The last `ticks` history entries of every token with history,
+    /// shared with earlier calls while a token has no new ticks
+    pub(super) fn recent_histories(&self, ticks: usize) -> Vec<(TokenId, Arc<[PriceTick]>)> {
+        self.history
+            .iter()
+            .map(|h| (h.key().clone(), h.read().recent(ticks)))
+            .collect()
     }
 
     /// Get recent high price for a token
@@ -396,6 +821,7 @@ impl MarketData {
         self.history.get(token_id).and_then(|h| {
             let history = h.read();
             history
+                .ticks
                 .iter()
                 .rev()
                 .take(ticks)
@@ -410,11 +836,11 @@ impl MarketData {
             .get(token_id)
             .map(|h| {
                 let history = h.read();
-                if history.len() < ticks {
+                if history.ticks.len() < ticks {
                     return false;
                 }
 
-                let recent: Vec<f64> = history.iter().rev().take(ticks).map(|t| t.price).collect();
+                let recent: Vec<f64> = history.ticks.iter().rev().take(ticks).map(|t| t.price).collect();
 
                 if recent.is_empty() {
                     return false;
@@ -433,18 +859,17 @@ impl MarketData {
             timestamp_ns,
         };
 
-        self.history
+        let entry = self
+            .history
             .entry(token_id.clone())
-            .or_insert_with(|| RwLock::new(VecDeque::with_capacity(self.max_history_size)))
-            .write()
-            .push_back(tick);
+            .or_insert_with(|| RwLock::new(TokenHistory::with_capacity(self.max_history_size)));
+        let mut history = entry.write();
+        history.ticks.push_back(tick);
+        history.pushes += 1;
 
         // Trim history if needed
-        if let Some(h) = self.history.get(token_id) {
-            let mut history = h.write();
-            while history.len() > self.max_history_size {
-                history.pop_front();
-            }
+        while history.ticks.len() > self.max_history_size {
+            history.ticks.pop_front();
         }
     }
 
@@ -455,7 +880,7 @@ impl MarketData {
 
     /// Get number of tracked tokens
     pub fn token_count(&self) -> usize {
-        self.prices.len()
+        self.quotes.len()
     }
 
     /// Get number of tracked markets
@@ -465,7 +890,51 @@ impl MarketData {
 
     /// Get number of order books
     pub fn order_book_count(&self) -> usize {
-        self.order_books.len()
+        self.quotes.iter().filter(|q| q.book.is_some()).count()
+    }
+
+    /// Count stored entries (walks every book and history).
+    pub fn stats(&self) -> MarketDataStats {
+        MarketDataStats {
+            tokens: self.quotes.len(),
+            books: self.order_book_count(),
+            depth_levels: self
+                .quotes
+                .iter()
+                .filter_map(|q| q.book.as_ref().map(|b| b.bids.len() + b.asks.len()))
+                .sum(),
+            history_entries: self.history.iter().map(|h| h.read().ticks.len()).sum(),
+            pairs: self.pairs.len(),
+        }
+    }
+
+    /// Drop the price, book and history of tokens we stopped subscribing
+    /// to. They stay subscription candidates.
+    pub fn forget_quotes(&self, tokens: &[TokenId]) {
+        for token in tokens {
+            if !self.token_to_market.contains_key(token) {
+                self.tracked_tokens.insert(token.clone(), ());
+            }
+            self.quotes.remove(token);
+            self.history.remove(token);
+        }
+    }
+
+    /// Drop the price, book and history of every token not updated since
+    /// `cutoff_ns`. Pairs and tracked tokens are kept, so the token is
+    /// still subscribed. Returns the number of tokens evicted.
+    pub fn evict_idle(&self, cutoff_ns: u64) -> usize {
+        // Every book write also sets the price, so the price is the newest
+        let mut evicted = 0;
+        self.quotes.retain(|token, quote| {
+            if quote.price.timestamp_ns >= cutoff_ns {
+                return true;
+            }
+            self.history.remove(token);
+            evicted += 1;
+            false
+        });
+        evicted
     }
 }
 
@@ -501,6 +970,8 @@ mod tests {
             yes_token: "yes_token".into(),
             no_token: "no_token".into(),
             question: "Test?".into(),
+            category: None,
+            end_date: None,
         };
 
         data.register_pair(pair);
@@ -515,6 +986,61 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_token_changes_notify_watchers() {
+        let data = MarketData::new();
+        let mut changes = data.watch_tokens();
+        assert!(!changes.has_changed().unwrap());
+
+        data.register_pair(MarketPair {
+            market_id: "market1".into(),
+            yes_token: "yes_token".into(),
+            no_token: "no_token".into(),
+            question: "Test?".into(),
+            category: None,
+            end_date: None,
+        });
+        assert!(changes.has_changed().unwrap());
+        changes.mark_unchanged();
+
+        data.track_token("extra".into());
+        assert!(changes.has_changed().unwrap());
+        changes.mark_unchanged();
+
+        // Tracking a token twice changes nothing
+        data.track_token("extra".into());
+        assert!(!changes.has_changed().unwrap());
+
+        data.remove_pair(&"market1".into());
+        assert!(changes.has_changed().unwrap());
+    }
+
+    #[test]
+    fn test_sharded_registration_skips_foreign_markets() {
+        let mut data = MarketData::new();
+        let shard = ShardConfig::new(2, 0).unwrap();
+        data.set_shard(shard);
+
+        let registered = (0..20)
+            .filter(|n| {
+                let market_id = format!("market{}", n);
+                let owned = shard.owns(&market_id);
+                let ok = data.register_pair(MarketPair {
+                    market_id,
+                    yes_token: format!("yes{}", n),
+                    no_token: format!("no{}", n),
+                    question: "Test?".into(),
+                    category: None,
+                    end_date: None,
+                });
+                assert_eq!(ok, owned);
+                ok
+            })
+            .count();
+        assert!(registered > 0 && registered < 20);
+        assert_eq!(data.market_count(), registered);
+    }
+
     #[test]
     fn test_price_history() {
         let data = MarketData::new();
@@ -582,6 +1108,27 @@ mod tests {
         assert_eq!(result.levels_used, 2);
     }
 
+    #[test]
+    fn test_vwap_within_limit_price() {
+        let mut book = OrderBook::new("token1".into());
+        book.asks = vec![
+            DepthLevel::new(0.50, 100.0),
+            DepthLevel::new(0.52, 100.0),
+            DepthLevel::new(0.60, 100.0),
+        ];
+        book.bids = vec![DepthLevel::new(0.48, 100.0), DepthLevel::new(0.40, 100.0)];
+
+        // Stops at the limit even though deeper levels could fill the rest
+        let result = book.vwap_buy_within(250.0, 0.55).unwrap();
+        assert!((result.total_size - 200.0).abs() < 0.0001);
+        assert_eq!(result.worst_price, 0.52);
+
+        let result = book.vwap_sell_within(150.0, 0.45).unwrap();
+        assert!((result.total_size - 100.0).abs() < 0.0001);
+        assert_eq!(result.worst_price, 0.48);
+        assert!(book.vwap_sell_within(10.0, 0.49).is_none());
+    }
+
     #[test]
     fn test_order_book_update() {
         let data = MarketData::new();
@@ -597,5 +1144,125 @@ mod tests {
         assert_eq!(book.asks.len(), 2);
         assert!((book.best_bid().unwrap() - 0.48).abs() < 0.0001);
         assert!((book.best_ask().unwrap() - 0.50).abs() < 0.0001);
+        assert_eq!(data.book_updates(&token), 1);
+
+        // Price changes keep the count; another snapshot adds to it
+        data.update_price(&token, 0.48, 0.50);
+        data.update_order_book(&token, vec![DepthLevel::new(0.47, 10.0)], vec![]);
+        assert_eq!(data.book_updates(&token), 2);
+        assert_eq!(data.book_updates(&"0x789".to_string()), 0);
+    }
+
+    #[test]
+    fn test_order_book_depth_truncation() {
+        let mut data = MarketData::new();
+        data.set_max_book_levels(2);
+        let token = "0x789".to_string();
+
+        let asks: Vec<DepthLevel> = (0..5)
+            .map(|i| DepthLevel::new(0.50 + 0.01 * i as f64, 100.0))
+            .collect();
+        data.update_order_book(&token, vec![DepthLevel::new(0.48, 10.0)], asks.clone());
+
+        let book = data.get_order_book(&token).unwrap();
+        assert_eq!(book.bids.len(), 1);
+        // Two levels kept plus one aggregate of the remaining three
+        assert_eq!(book.asks.len(), 3);
+        assert!((book.asks[1].price - 0.51).abs() < 1e-9);
+        assert!((book.asks[2].price - 0.53).abs() < 1e-9);
+        assert!((book.asks[2].size - 300.0).abs() < 1e-9);
+        assert!((book.total_ask_size() - 500.0).abs() < 1e-9);
+
+        let mut full = OrderBook::new(token.clone());
+        full.asks = asks;
+        let expected = full.vwap_buy(500.0).unwrap().vwap;
+        assert!((book.vwap_buy(500.0).unwrap().vwap - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_stats_and_idle_eviction() {
+        let data = MarketData::new();
+        data.update_price(&"old".into(), 0.4, 0.5);
+        data.update_order_book(
+            &"old".into(),
+            vec![DepthLevel::new(0.4, 10.0)],
+            vec![DepthLevel::new(0.5, 10.0)],
+        );
+        data.update_price(&"old".into(), 0.4, 0.5);
+
+        let stats = data.stats();
+        assert_eq!(stats.tokens, 1);
+        assert_eq!(stats.books, 1);
+        assert_eq!(stats.depth_levels, 2);
+        assert_eq!(stats.history_entries, 3);
+
+        let cutoff = data.last_update_ns() + 1;
+        std::thread::sleep(std::time::Duration::from_millis(1));
+        data.update_price(&"fresh".into(), 0.4, 0.5);
+        assert_eq!(data.evict_idle(cutoff), 1);
+
+        let stats = data.stats();
+        assert_eq!(stats.tokens, 1);
+        assert_eq!(stats.books, 0);
+        assert_eq!(stats.history_entries, 1);
+        assert!(data.get_price(&"fresh".into()).is_some());
+        assert!(data.get_price(&"old".into()).is_none());
+    }
+
+    #[test]
+    fn test_price_changes_keep_book_consistent() {
+        let data = MarketData::new();
+        let token: TokenId = "t".into();
+        data.update_order_book(
+            &token,
+            vec![DepthLevel::new(0.45, 10.0), DepthLevel::new(0.44, 20.0)],
+            vec![DepthLevel::new(0.47, 10.0), DepthLevel::new(0.49, 30.0)],
+        );
+        // The book write sets the price
+        assert_eq!(data.get_ask(&token), Some(0.47));
+
+        // Best ask taken out: the book drops the level with it
+        assert!(data.update_price(&token, 0.45, 0.49));
+        let (price, book) = data.get_quote(&token).unwrap();
+        let book = book.unwrap();
+        assert_eq!(book.best_ask(), Some(price.ask));
+        assert_eq!(book.asks.len(), 1);
+
+        // A new best bid we have no size for: the book is dropped, not left
+        // contradicting the price
+        assert!(!data.update_price(&token, 0.46, 0.49));
+        assert_eq!(data.get_bid(&token), Some(0.46));
+        assert!(data.get_order_book(&token).is_none());
+        assert!(data.snapshot(0).get_order_book(&token).is_none());
+    }
+
+    #[test]
+    fn test_vwap_buy_with_impact() {
+        let mut book = OrderBook::new("token1".into());
+        book.asks = vec![DepthLevel::new(0.50, 100.0), DepthLevel::new(0.52, 100.0)];
+
+        // 50% participation with 20% fade leaves 40 shares per level
+        let impact = ImpactModel {
+            max_participation: 0.5,
+            depth_haircut: 0.2,
+        };
+        assert!((impact.depth_factor() - 0.4).abs() < 1e-9);
+
+        let result = book.vwap_buy_with_impact(60.0, &impact).unwrap();
+        let expected_vwap = (40.0 * 0.50 + 20.0 * 0.52) / 60.0;
+        assert!((result.vwap - expected_vwap).abs() < 0.0001);
+        assert!((result.total_size - 60.0).abs() < 0.0001);
+        assert_eq!(result.levels_used, 2);
+
+        // Fillable size is capped at 40% of total displayed depth
+        let result = book.vwap_buy_with_impact(500.0, &impact).unwrap();
+        assert!((result.total_size - 80.0).abs() < 0.0001);
+
+        // No impact matches the plain VWAP
+        let plain = book.vwap_buy(150.0).unwrap();
+        let none = book
+            .vwap_buy_with_impact(150.0, &ImpactModel::none())
+            .unwrap();
+        assert!((plain.vwap - none.vwap).abs() < 1e-12);
     }
 }
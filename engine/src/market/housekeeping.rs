@@ -0,0 +1,107 @@
+//! Periodic market data housekeeping.
+//!
+//! Exports entry counts as gauges and evicts the data of tokens that have
+//! not been updated for a while, so a long-running instance does not keep
+//! every market it has ever seen in memory.
+
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+
+use crate::metrics::{MARKET_DATA_ENTRIES, MARKET_DATA_EVICTIONS};
+
+use super::data::MarketData;
+
+/// Housekeeping settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HousekeepingConfig {
+    /// Time between runs
+    pub interval: Duration,
+
+    /// Evict tokens untouched for this long (None = never)
+    pub evict_after: Option<Duration>,
+}
+
+impl Default for HousekeepingConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(60),
+            evict_after: Some(Duration::from_secs(60 * 60)),
+        }
+    }
+}
+
+impl HousekeepingConfig {
+    /// Load from `MARKET_HOUSEKEEPING_INTERVAL_MS` and
+    /// `MARKET_EVICT_IDLE_MINS` (0 disables eviction).
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            interval: std::env::var("MARKET_HOUSEKEEPING_INTERVAL_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|ms| *ms > 0)
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.interval),
+            evict_after: match std::env::var("MARKET_EVICT_IDLE_MINS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+            {
+                Some(0) => None,
+                Some(mins) => Some(Duration::from_secs(mins * 60)),
+                None => defaults.evict_after,
+            },
+        }
+    }
+}
+
+impl MarketData {
+    /// Update memory gauges and evict idle tokens until cancelled.
+    pub async fn run_housekeeping(
+        self: Arc<Self>,
+        config: HousekeepingConfig,
+        cancel: CancellationToken,
+    ) {
+        let mut ticker = tokio::time::interval(config.interval);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => self.housekeep(&config),
+                _ = cancel.cancelled() => break,
+            }
+        }
+    }
+
+    fn housekeep(&self, config: &HousekeepingConfig) {
+        if let Some(max_idle) = config.evict_after {
+            let now_ns = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos() as u64;
+            let cutoff = now_ns.saturating_sub(max_idle.as_nanos() as u64);
+            let evicted = self.evict_idle(cutoff);
+            if evicted > 0 {
+                MARKET_DATA_EVICTIONS.inc_by(evicted as f64);
+                info!(
+                    "[MARKET] Evicted {} tokens idle for over {}m",
+                    evicted,
+                    max_idle.as_secs() / 60
+                );
+            }
+        }
+
+        let stats = self.stats();
+        for (kind, count) in [
+            ("tokens", stats.tokens),
+            ("books", stats.books),
+            ("depth_levels", stats.depth_levels),
+            ("history_entries", stats.history_entries),
+            ("pairs", stats.pairs),
+        ] {
+            MARKET_DATA_ENTRIES
+                .with_label_values(&[kind])
+                .set(count as f64);
+        }
+    }
+}
@@ -0,0 +1,498 @@
+//! Rolling liquidity scores and tiers per market.
+//!
+//! Displayed depth far from the mid is not depth we can trade, so every
+//! interval each market is sampled for the dollar depth within a band of the
+//! mid, its spread, and whether its books changed since the last sample.
+//! These are smoothed into a 0-1 score and a tier (A/B/C). The risk manager
+//! caps order notional at a fraction of the smoothed near-mid depth and can
+//! refuse markets below a minimum tier, which sizes every strategy through
+//! `RiskManager::max_allowed`.
+//!
+//! With `LIQUIDITY_TARGET_VOLUME` set, traded 24h volume from the data API
+//! is blended into the activity term: a quiet book in a market that trades
+//! heavily elsewhere is not penalized as much.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use dashmap::DashMap;
+use serde::Serialize;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info};
+
+use crate::external::PolymarketDataClient;
+use crate::metrics::MARKET_LIQUIDITY_TIERS;
+
+use super::data::{DepthLevel, MarketData, MarketId, MarketPair, OrderBook, TokenId};
+
+/// Weight of the newest sample in the smoothed values
+const SMOOTHING: f64 = 0.2;
+
+/// Spread at or above which a market scores zero for spread
+const MAX_SPREAD: f64 = 0.10;
+
+/// Minimum score for tiers A and B
+const TIER_A_SCORE: f64 = 0.7;
+const TIER_B_SCORE: f64 = 0.4;
+
+/// Market volumes fetched per sample, so the data API is not flooded
+const VOLUME_LOOKUPS: usize = 20;
+
+/// Liquidity tier, best first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum LiquidityTier {
+    A,
+    B,
+    C,
+}
+
+impl LiquidityTier {
+    fn from_score(score: f64) -> Self {
+        if score >= TIER_A_SCORE {
+            LiquidityTier::A
+        } else if score >= TIER_B_SCORE {
+            LiquidityTier::B
+        } else {
+            LiquidityTier::C
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LiquidityTier::A => "A",
+            LiquidityTier::B => "B",
+            LiquidityTier::C => "C",
+        }
+    }
+}
+
+impl FromStr for LiquidityTier {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_uppercase().as_str() {
+            "A" => Ok(LiquidityTier::A),
+            "B" => Ok(LiquidityTier::B),
+            "C" => Ok(LiquidityTier::C),
+            other => Err(format!("unknown liquidity tier '{}'", other)),
+        }
+    }
+}
+
+impl fmt::Display for LiquidityTier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Liquidity scoring settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiquidityConfig {
+    pub enabled: bool,
+
+    /// Time between samples
+    pub interval: Duration,
+
+    /// Depth counts only within this distance of the mid (dollars)
+    pub band: f64,
+
+    /// Near-mid depth (USD) that earns a full depth score
+    pub target_depth: f64,
+
+    /// Largest order notional as a fraction of smoothed near-mid depth
+    pub max_depth_fraction: f64,
+
+    /// Markets below this tier are not traded
+    pub min_tier: LiquidityTier,
+
+    /// 24h volume (USD) that earns a full volume score (zero = ignore volume)
+    pub target_volume: f64,
+}
+
+impl Default for LiquidityConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval: Duration::from_secs(10),
+            band: 0.02,
+            target_depth: 500.0,
+            max_depth_fraction: 0.5,
+            min_tier: LiquidityTier::C,
+            target_volume: 0.0,
+        }
+    }
+}
+
+impl LiquidityConfig {
+    /// Load from `LIQUIDITY_ENABLED`, `LIQUIDITY_INTERVAL_MS`,
+    /// `LIQUIDITY_BAND`, `LIQUIDITY_TARGET_DEPTH`,
+    /// `LIQUIDITY_MAX_DEPTH_FRACTION`, `LIQUIDITY_MIN_TIER` and
+    /// `LIQUIDITY_TARGET_VOLUME`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok();
+        let positive = |name: &str, default: f64| {
+            var(name)
+                .and_then(|v| v.parse::<f64>().ok())
+                .filter(|x| x.is_finite() && *x > 0.0)
+                .unwrap_or(default)
+        };
+        Self {
+            enabled: var("LIQUIDITY_ENABLED")
+                .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+                .unwrap_or(defaults.enabled),
+            interval: var("LIQUIDITY_INTERVAL_MS")
+                .and_then(|v| v.parse().ok())
+                .filter(|ms| *ms > 0)
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.interval),
+            band: positive("LIQUIDITY_BAND", defaults.band),
+            target_depth: positive("LIQUIDITY_TARGET_DEPTH", defaults.target_depth),
+            max_depth_fraction: positive(
+                "LIQUIDITY_MAX_DEPTH_FRACTION",
+                defaults.max_depth_fraction,
+            ),
+            min_tier: var("LIQUIDITY_MIN_TIER")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.min_tier),
+            target_volume: var("LIQUIDITY_TARGET_VOLUME")
+                .and_then(|v| v.parse::<f64>().ok())
+                .filter(|x| x.is_finite() && *x >= 0.0)
+                .unwrap_or(defaults.target_volume),
+        }
+    }
+}
+
+/// Smoothed liquidity of one market
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct LiquidityScore {
+    /// Dollar depth within the band of the mid, on the thinner token
+    pub depth_usd: f64,
+    /// Spread of the wider token
+    pub spread: f64,
+    /// Fraction of recent samples in which the books changed
+    pub activity: f64,
+    /// Traded volume over the last 24h (USD), if known
+    pub volume_24h: Option<f64>,
+    /// Weighted 0-1 score of depth, spread and activity
+    pub score: f64,
+    pub tier: LiquidityTier,
+}
+
+/// One sample of a market, before smoothing
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    depth_usd: f64,
+    spread: f64,
+    active: bool,
+    volume_24h: Option<f64>,
+}
+
+/// Liquidity scores for all markets, refreshed periodically.
+pub struct MarketLiquidity {
+    config: LiquidityConfig,
+    scores: DashMap<MarketId, LiquidityScore>,
+    /// Book timestamp per token at the last sample
+    last_seen_ns: DashMap<TokenId, u64>,
+    data: Option<Arc<PolymarketDataClient>>,
+}
+
+impl MarketLiquidity {
+    pub fn new(config: LiquidityConfig) -> Self {
+        Self {
+            config,
+            scores: DashMap::new(),
+            last_seen_ns: DashMap::new(),
+            data: None,
+        }
+    }
+
+    /// Set the client that supplies traded volume.
+    pub fn set_data_client(&mut self, data: Arc<PolymarketDataClient>) {
+        self.data = Some(data);
+    }
+
+    pub fn config(&self) -> &LiquidityConfig {
+        &self.config
+    }
+
+    /// Current score of a market, if it has been sampled.
+    pub fn score(&self, market_id: &MarketId) -> Option<LiquidityScore> {
+        self.scores.get(market_id).map(|s| *s)
+    }
+
+    /// Largest order notional the market's depth supports, if scored.
+    pub fn max_notional(&self, market_id: &MarketId) -> Option<f64> {
+        self.score(market_id)
+            .map(|s| s.depth_usd * self.config.max_depth_fraction)
+    }
+
+    /// Whether the market's tier is too low to trade. Unscored markets pass.
+    pub fn below_min_tier(&self, market_id: &MarketId) -> bool {
+        self.score(market_id)
+            .is_some_and(|s| s.tier > self.config.min_tier)
+    }
+
+    #[cfg(test)]
+    pub(crate) fn set_score(&self, market_id: &str, depth_usd: f64, tier: LiquidityTier) {
+        self.scores.insert(
+            market_id.to_string(),
+            LiquidityScore {
+                depth_usd,
+                spread: 0.0,
+                activity: 0.0,
+                volume_24h: None,
+                score: match tier {
+                    LiquidityTier::A => 0.9,
+                    LiquidityTier::B => 0.5,
+                    LiquidityTier::C => 0.1,
+                },
+                tier,
+            },
+        );
+    }
+
+    /// Sample every market and update the scores until cancelled.
+    pub async fn run(self: Arc<Self>, market_data: Arc<MarketData>, cancel: CancellationToken) {
+        info!(
+            "[MARKET] Liquidity scoring every {:?} (band ${:.2}, min tier {})",
+            self.config.interval, self.config.band, self.config.min_tier
+        );
+        let mut ticker = tokio::time::interval(self.config.interval);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    self.refresh_volumes(&market_data).await;
+                    self.refresh(&market_data);
+                }
+                _ = cancel.cancelled() => break,
+            }
+        }
+    }
+
+    /// Fetch volume for a bounded number of markets whose volume is stale.
+    async fn refresh_volumes(&self, market_data: &MarketData) {
+        let Some(data) = &self.data else {
+            return;
+        };
+        if self.config.target_volume <= 0.0 {
+            return;
+        }
+        let stale: Vec<MarketId> = market_data
+            .iter_pairs()
+            .map(|p| p.market_id)
+            .filter(|id| data.volume_stale(id))
+            .take(VOLUME_LOOKUPS)
+            .collect();
+        for market_id in stale {
+            if let Err(e) = data.market_volume(&market_id).await {
+                debug!("[MARKET] Volume lookup for {} failed: {:#}", market_id, e);
+            }
+        }
+    }
+
+    fn refresh(&self, market_data: &MarketData) {
+        let mut tiers = [0u32; 3];
+        let mut live = HashSet::new();
+        for pair in market_data.iter_pairs() {
+            let Some(sample) = self.sample(market_data, &pair) else {
+                continue;
+            };
+            let score = self.update(&pair.market_id, sample);
+            tiers[score.tier as usize] += 1;
+            live.insert(pair.market_id);
+        }
+        // Forget markets that are gone (evicted or unregistered)
+        self.scores.retain(|id, _| live.contains(id));
+        self.last_seen_ns
+            .retain(|token, _| market_data.get_order_book(token).is_some());
+
+        for tier in [LiquidityTier::A, LiquidityTier::B, LiquidityTier::C] {
+            MARKET_LIQUIDITY_TIERS
+                .with_label_values(&[tier.as_str()])
+                .set(f64::from(tiers[tier as usize]));
+        }
+    }
+
+    /// Measure a market's books now. None until both books have a mid.
+    fn sample(&self, market_data: &MarketData, pair: &MarketPair) -> Option<Sample> {
+        let (yes, no) = market_data.get_pair_order_books(pair)?;
+        let (yes_depth, yes_spread) = book_liquidity(&yes, self.config.band)?;
+        let (no_depth, no_spread) = book_liquidity(&no, self.config.band)?;
+
+        let mut active = false;
+        for book in [&yes, &no] {
+            let previous = self
+                .last_seen_ns
+                .insert(book.token_id.clone(), book.timestamp_ns);
+            active |= previous != Some(book.timestamp_ns);
+        }
+
+        Some(Sample {
+            depth_usd: yes_depth.min(no_depth),
+            spread: yes_spread.max(no_spread),
+            active,
+            volume_24h: self
+                .data
+                .as_ref()
+                .and_then(|data| data.cached_volume(&pair.market_id))
+                .map(|v| v.volume_24h),
+        })
+    }
+
+    /// Fold a sample into a market's smoothed score.
+    fn update(&self, market_id: &MarketId, sample: Sample) -> LiquidityScore {
+        let activity = if sample.active { 1.0 } else { 0.0 };
+        let (depth_usd, spread, activity) = match self.score(market_id) {
+            Some(prev) => (
+                smooth(prev.depth_usd, sample.depth_usd),
+                smooth(prev.spread, sample.spread),
+                smooth(prev.activity, activity),
+            ),
+            None => (sample.depth_usd, sample.spread, activity),
+        };
+
+        let depth_score = (depth_usd / self.config.target_depth).min(1.0);
+        let spread_score = (1.0 - spread / MAX_SPREAD).clamp(0.0, 1.0);
+        let activity_score = match sample.volume_24h {
+            Some(volume) if self.config.target_volume > 0.0 => {
+                0.5 * activity + 0.5 * (volume / self.config.target_volume).min(1.0)
+            }
+            _ => activity,
+        };
+        let score = 0.6 * depth_score + 0.25 * spread_score + 0.15 * activity_score;
+        let scored = LiquidityScore {
+            depth_usd,
+            spread,
+            activity,
+            volume_24h: sample.volume_24h,
+            score,
+            tier: LiquidityTier::from_score(score),
+        };
+        self.scores.insert(market_id.clone(), scored);
+        scored
+    }
+}
+
+fn smooth(previous: f64, sample: f64) -> f64 {
+    previous + SMOOTHING * (sample - previous)
+}
+
+/// Dollar depth within `band` of the mid on both sides, and the spread.
+fn book_liquidity(book: &OrderBook, band: f64) -> Option<(f64, f64)> {
+    let (bid, ask) = (book.best_bid()?, book.best_ask()?);
+    let mid = (bid + ask) / 2.0;
+    let notional = |levels: &[DepthLevel], near: &dyn Fn(f64) -> bool| -> f64 {
+        levels
+            .iter()
+            .take_while(|l| near(l.price))
+            .map(|l| l.price * l.size)
+            .sum()
+    };
+    let depth =
+        notional(&book.bids, &|p| p >= mid - band) + notional(&book.asks, &|p| p <= mid + band);
+    Some((depth, (ask - bid).max(0.0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn register(data: &MarketData, id: &str) -> MarketPair {
+        let pair = MarketPair {
+            market_id: id.into(),
+            yes_token: format!("{}-yes", id),
+            no_token: format!("{}-no", id),
+            question: "Q?".into(),
+            category: None,
+            end_date: None,
+        };
+        data.register_pair(pair.clone());
+        pair
+    }
+
+    /// Book with `size` shares on each side at `mid` +/- `half_spread`, and
+    /// a large level far from the mid that must not count
+    fn book(data: &MarketData, token: &str, mid: f64, half_spread: f64, size: f64) {
+        data.update_order_book(
+            &token.to_string(),
+            vec![
+                DepthLevel::new(mid - half_spread, size),
+                DepthLevel::new(mid - 0.2, 10_000.0),
+            ],
+            vec![DepthLevel::new(mid + half_spread, size)],
+        );
+    }
+
+    #[test]
+    fn test_depth_counts_only_near_mid() {
+        let data = MarketData::new();
+        register(&data, "m");
+        book(&data, "m-yes", 0.5, 0.01, 100.0);
+        let yes = data.get_order_book(&"m-yes".to_string()).unwrap();
+
+        let (depth, spread) = book_liquidity(&yes, 0.02).unwrap();
+        assert!((depth - (0.49 * 100.0 + 0.51 * 100.0)).abs() < 1e-9);
+        assert!((spread - 0.02).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_tiers_and_depth_cap() {
+        let data = MarketData::new();
+        let deep = register(&data, "deep");
+        let thin = register(&data, "thin");
+        book(&data, "deep-yes", 0.5, 0.005, 1_000.0);
+        book(&data, "deep-no", 0.5, 0.005, 1_000.0);
+        // $30 of real depth on the thinner token, wide spread
+        book(&data, "thin-yes", 0.5, 0.019, 30.0);
+        book(&data, "thin-no", 0.5, 0.019, 500.0);
+
+        let liquidity = MarketLiquidity::new(LiquidityConfig {
+            min_tier: LiquidityTier::B,
+            ..LiquidityConfig::default()
+        });
+        liquidity.refresh(&data);
+
+        let deep_score = liquidity.score(&deep.market_id).unwrap();
+        assert_eq!(deep_score.tier, LiquidityTier::A);
+        assert!(!liquidity.below_min_tier(&deep.market_id));
+
+        let thin_score = liquidity.score(&thin.market_id).unwrap();
+        assert_eq!(thin_score.tier, LiquidityTier::C);
+        assert!(liquidity.below_min_tier(&thin.market_id));
+        assert!((liquidity.max_notional(&thin.market_id).unwrap() - 15.0).abs() < 1e-9);
+
+        // Unchanged books lose their activity over time
+        liquidity.refresh(&data);
+        let later = liquidity.score(&deep.market_id).unwrap();
+        assert!(later.activity < deep_score.activity);
+        assert!(!liquidity.below_min_tier(&"unknown".to_string()));
+    }
+
+    #[test]
+    fn test_volume_offsets_quiet_books() {
+        let liquidity = MarketLiquidity::new(LiquidityConfig {
+            target_volume: 10_000.0,
+            ..LiquidityConfig::default()
+        });
+        let quiet = |volume_24h| Sample {
+            depth_usd: 250.0,
+            spread: 0.05,
+            active: false,
+            volume_24h,
+        };
+        let unknown = liquidity.update(&"a".to_string(), quiet(None));
+        let traded = liquidity.update(&"b".to_string(), quiet(Some(20_000.0)));
+        assert!((traded.score - unknown.score - 0.075).abs() < 1e-9);
+        assert_eq!(traded.volume_24h, Some(20_000.0));
+    }
+
+    #[test]
+    fn test_parse_tier() {
+        assert_eq!("b".parse(), Ok(LiquidityTier::B));
+        assert!("D".parse::<LiquidityTier>().is_err());
+    }
+}
@@ -0,0 +1,578 @@
+//! Periodic refresh of market metadata from the Gamma API.
+//!
+//! Questions, categories, and end dates change now and then, markets close,
+//! and new ones are listed. Every interval the active listing is paged
+//! through: registered pairs whose metadata changed are updated in place,
+//! unknown markets are reported (and registered when `GAMMA_REGISTER_NEW` is
+//! set), and registered markets missing from the listing are looked up to
+//! tell a close from a delisting. Both are removed from the tradable
+//! universe once we hold nothing in them.
+//!
+//! With `GAMMA_MIN_VOLUME_24H` set, new markets are only reported or
+//! registered once their 24h volume (from the data API) reaches it; a
+//! market whose volume could not be read waits for the next refresh.
+//!
+//! Failures never clear what we have: a listing that could not be read in
+//! full still applies the pages it got, but nothing is removed until a
+//! complete listing confirms it. `poly_market_metadata_age_seconds` shows
+//! how stale the served metadata is.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use reqwest::Client;
+use serde::Deserialize;
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::external::PolymarketDataClient;
+use crate::metrics::{MARKET_EVENTS, MARKET_METADATA_AGE, MARKET_METADATA_ERRORS};
+
+use super::data::{MarketData, MarketId, MarketPair, TokenId};
+
+/// Markets per listing page
+const PAGE_SIZE: usize = 500;
+
+/// Listing pages read per refresh, so a runaway listing cannot loop forever
+const MAX_PAGES: usize = 40;
+
+/// Markets looked up per request when telling closes from delistings
+const LOOKUP_BATCH: usize = 20;
+
+/// New market volumes fetched per refresh; the rest wait for the next one
+const VOLUME_LOOKUPS: usize = 50;
+
+/// Gamma metadata refresh settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetadataConfig {
+    pub enabled: bool,
+    pub gamma_url: String,
+    /// Time between refreshes
+    pub interval: Duration,
+    /// Register newly listed markets (otherwise they are only reported)
+    pub register_new: bool,
+    /// New markets trading less than this over 24h (USD) are ignored
+    pub min_volume_24h: f64,
+}
+
+impl Default for MetadataConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            gamma_url: "https://gamma-api.polymarket.com".to_string(),
+            interval: Duration::from_secs(300),
+            register_new: false,
+            min_volume_24h: 0.0,
+        }
+    }
+}
+
+impl MetadataConfig {
+    /// Load from `GAMMA_REFRESH_ENABLED`, `GAMMA_API_URL`,
+    /// `GAMMA_REFRESH_INTERVAL_MS`, `GAMMA_REGISTER_NEW` and
+    /// `GAMMA_MIN_VOLUME_24H`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok();
+        let flag = |v: String| v.eq_ignore_ascii_case("true") || v == "1";
+        Self {
+            enabled: var("GAMMA_REFRESH_ENABLED")
+                .map(flag)
+                .unwrap_or(defaults.enabled),
+            gamma_url: var("GAMMA_API_URL").unwrap_or(defaults.gamma_url),
+            interval: var("GAMMA_REFRESH_INTERVAL_MS")
+                .and_then(|v| v.parse().ok())
+                .filter(|ms| *ms > 0)
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.interval),
+            register_new: var("GAMMA_REGISTER_NEW")
+                .map(flag)
+                .unwrap_or(defaults.register_new),
+            min_volume_24h: var("GAMMA_MIN_VOLUME_24H")
+                .and_then(|v| v.parse::<f64>().ok())
+                .filter(|usd| usd.is_finite() && *usd >= 0.0)
+                .unwrap_or(defaults.min_volume_24h),
+        }
+    }
+}
+
+/// A change to the market universe
+#[derive(Debug, Clone)]
+pub enum MarketEvent {
+    /// A market we did not know was listed
+    Listed(MarketPair),
+    /// A registered market's question, category, or end date changed
+    Updated(MarketPair),
+    /// A registered market closed and was removed
+    Closed(MarketPair),
+    /// A registered market disappeared from Gamma and was removed
+    Delisted(MarketPair),
+}
+
+impl MarketEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MarketEvent::Listed(_) => "listed",
+            MarketEvent::Updated(_) => "updated",
+            MarketEvent::Closed(_) => "closed",
+            MarketEvent::Delisted(_) => "delisted",
+        }
+    }
+
+    pub fn pair(&self) -> &MarketPair {
+        match self {
+            MarketEvent::Listed(pair)
+            | MarketEvent::Updated(pair)
+            | MarketEvent::Closed(pair)
+            | MarketEvent::Delisted(pair) => pair,
+        }
+    }
+}
+
+/// Fields of a Gamma `/markets` entry
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GammaMarket {
+    #[serde(default)]
+    condition_id: String,
+    #[serde(default)]
+    question: String,
+    #[serde(default)]
+    category: Option<String>,
+    #[serde(default)]
+    end_date: Option<DateTime<Utc>>,
+    /// JSON-encoded array of token IDs, YES first
+    #[serde(default)]
+    clob_token_ids: Option<String>,
+    #[serde(default)]
+    closed: bool,
+}
+
+impl GammaMarket {
+    /// The market as a pair, if it has exactly two outcome tokens.
+    fn into_pair(self) -> Option<MarketPair> {
+        let tokens: Vec<TokenId> = serde_json::from_str(self.clob_token_ids.as_deref()?).ok()?;
+        let [yes_token, no_token] = <[TokenId; 2]>::try_from(tokens).ok()?;
+        (!self.condition_id.is_empty()).then(|| MarketPair {
+            market_id: self.condition_id,
+            yes_token,
+            no_token,
+            question: self.question,
+            category: self.category.filter(|c| !c.is_empty()),
+            end_date: self.end_date,
+        })
+    }
+}
+
+/// How a registered market missing from the listing is resolved
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Missing {
+    Closed,
+    Delisted,
+    /// Still open (it moved between pages while we read them)
+    Open,
+}
+
+/// Keeps registered market metadata in step with Gamma.
+pub struct MetadataRefresher {
+    config: MetadataConfig,
+    client: Client,
+    market_data: Arc<MarketData>,
+    events: broadcast::Sender<MarketEvent>,
+    /// Listed markets already reported but not registered
+    reported: Mutex<HashSet<MarketId>>,
+    /// Last complete refresh
+    refreshed_at: Mutex<Option<Instant>>,
+    data: Option<Arc<PolymarketDataClient>>,
+}
+
+impl MetadataRefresher {
+    pub fn new(config: MetadataConfig, market_data: Arc<MarketData>) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(15))
+            .build()
+            .context("Failed to create Gamma HTTP client")?;
+
+        Ok(Self {
+            config,
+            client,
+            market_data,
+            events: broadcast::channel(256).0,
+            reported: Mutex::new(HashSet::new()),
+            refreshed_at: Mutex::new(None),
+            data: None,
+        })
+    }
+
+    /// Set the client used to check new markets' volume.
+    pub fn set_data_client(&mut self, data: Arc<PolymarketDataClient>) {
+        self.data = Some(data);
+    }
+
+    /// Receive market events from now on.
+    #[allow(dead_code)]
+    pub fn subscribe(&self) -> broadcast::Receiver<MarketEvent> {
+        self.events.subscribe()
+    }
+
+    /// Refresh every interval until cancelled. Markets with a token in
+    /// `held` are kept registered until the position is gone.
+    pub async fn run<F>(self: Arc<Self>, held: F, cancel: CancellationToken)
+    where
+        F: Fn() -> HashSet<TokenId>,
+    {
+        info!(
+            "[MARKET] Refreshing Gamma metadata every {:?} (register new markets: {})",
+            self.config.interval, self.config.register_new
+        );
+        let mut ticker = tokio::time::interval(self.config.interval);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => self.refresh(&held()).await,
+                _ = cancel.cancelled() => break,
+            }
+        }
+    }
+
+    async fn refresh(&self, held: &HashSet<TokenId>) {
+        let (listed, complete) = self.fetch_listing().await;
+        let listed_ids: HashSet<MarketId> = listed.iter().map(|p| p.market_id.clone()).collect();
+        let listed = self.filter_by_volume(listed).await;
+        self.apply_listing(listed);
+
+        if complete {
+            let missing: Vec<MarketId> = self
+                .market_data
+                .iter_pairs()
+                .map(|p| p.market_id)
+                .filter(|id| !listed_ids.contains(id))
+                .collect();
+            match self.resolve_missing(&missing).await {
+                Ok(resolved) => {
+                    for (market_id, status) in resolved {
+                        self.retire(&market_id, status, held);
+                    }
+                    *self.refreshed_at.lock() = Some(Instant::now());
+                }
+                Err(e) => {
+                    MARKET_METADATA_ERRORS.inc();
+                    warn!("[MARKET] Gamma lookup of closed markets failed: {:#}", e);
+                }
+            }
+        }
+
+        let age = self
+            .refreshed_at
+            .lock()
+            .map_or(f64::INFINITY, |at| at.elapsed().as_secs_f64());
+        MARKET_METADATA_AGE.set(age);
+    }
+
+    /// Every active market, and whether the whole listing was read.
+    async fn fetch_listing(&self) -> (Vec<MarketPair>, bool) {
+        let mut pairs = Vec::new();
+        for page in 0..MAX_PAGES {
+            let offset = (page * PAGE_SIZE).to_string();
+            let limit = PAGE_SIZE.to_string();
+            let query = [
+                ("active", "true"),
+                ("closed", "false"),
+                ("limit", limit.as_str()),
+                ("offset", offset.as_str()),
+            ];
+            let markets = match self.fetch_markets(&query).await {
+                Ok(markets) => markets,
+                Err(e) => {
+                    MARKET_METADATA_ERRORS.inc();
+                    warn!(
+                        "[MARKET] Gamma listing failed at offset {}, keeping current metadata: {:#}",
+                        offset, e
+                    );
+                    return (pairs, false);
+                }
+            };
+            let last = markets.len() < PAGE_SIZE;
+            pairs.extend(markets.into_iter().filter_map(GammaMarket::into_pair));
+            if last {
+                return (pairs, true);
+            }
+        }
+        warn!(
+            "[MARKET] Gamma listing longer than {} markets, not removing any",
+            MAX_PAGES * PAGE_SIZE
+        );
+        (pairs, false)
+    }
+
+    async fn fetch_markets(&self, query: &[(&str, &str)]) -> Result<Vec<GammaMarket>> {
+        self.client
+            .get(format!(
+                "{}/markets",
+                self.config.gamma_url.trim_end_matches('/')
+            ))
+            .query(query)
+            .send()
+            .await
+            .context("Failed to fetch markets")?
+            .error_for_status()
+            .context("Gamma returned an error status")?
+            .json()
+            .await
+            .context("Failed to parse markets")
+    }
+
+    /// Drop new markets below the volume floor. Registered markets, and
+    /// those `apply_listing` would ignore anyway, pass unchecked.
+    async fn filter_by_volume(&self, listed: Vec<MarketPair>) -> Vec<MarketPair> {
+        let min_volume = self.config.min_volume_24h;
+        let Some(data) = self.data.as_ref().filter(|_| min_volume > 0.0) else {
+            return listed;
+        };
+        let mut lookups = 0;
+        let mut kept = Vec::with_capacity(listed.len());
+        for pair in listed {
+            let new = self.market_data.get_pair(&pair.market_id).is_none()
+                && (self.config.register_new
+                    || (self.market_data.owns_market(&pair.market_id)
+                        && !self.reported.lock().contains(&pair.market_id)));
+            if !new {
+                kept.push(pair);
+                continue;
+            }
+            if data.volume_stale(&pair.market_id) {
+                if lookups == VOLUME_LOOKUPS {
+                    continue;
+                }
+                lookups += 1;
+            }
+            match data.market_volume(&pair.market_id).await {
+                Ok(volume) if volume.volume_24h >= min_volume => kept.push(pair),
+                Ok(volume) => debug!(
+                    "[MARKET] Ignoring {} (24h volume ${:.0} below ${:.0})",
+                    pair.market_id, volume.volume_24h, min_volume
+                ),
+                Err(e) => debug!(
+                    "[MARKET] Volume lookup for {} failed, retrying next refresh: {:#}",
+                    pair.market_id, e
+                ),
+            }
+        }
+        kept
+    }
+
+    /// Update changed metadata of registered pairs and report new markets.
+    fn apply_listing(&self, listed: Vec<MarketPair>) {
+        for pair in listed {
+            match self.market_data.get_pair(&pair.market_id) {
+                Some(current) => {
+                    // Token IDs never change; only metadata is taken over
+                    let updated = MarketPair {
+                        question: pair.question,
+                        category: pair.category.or(current.category.clone()),
+                        end_date: pair.end_date.or(current.end_date),
+                        ..current.clone()
+                    };
+                    if updated.question != current.question
+                        || updated.category != current.category
+                        || updated.end_date != current.end_date
+                    {
+                        self.market_data.register_pair(updated.clone());
+                        self.emit(MarketEvent::Updated(updated));
+                    }
+                }
+                None if self.config.register_new => {
+                    if self.market_data.register_pair(pair.clone()) {
+                        self.emit(MarketEvent::Listed(pair));
+                    }
+                }
+                None => {
+                    if self.market_data.owns_market(&pair.market_id)
+                        && self.reported.lock().insert(pair.market_id.clone())
+                    {
+                        self.emit(MarketEvent::Listed(pair));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Whether each registered market missing from the listing closed or
+    /// was delisted.
+    async fn resolve_missing(&self, missing: &[MarketId]) -> Result<Vec<(MarketId, Missing)>> {
+        let mut resolved = Vec::with_capacity(missing.len());
+        for batch in missing.chunks(LOOKUP_BATCH) {
+            let query: Vec<(&str, &str)> = batch
+                .iter()
+                .map(|id| ("condition_ids", id.as_str()))
+                .collect();
+            let found: HashMap<String, bool> = self
+                .fetch_markets(&query)
+                .await?
+                .into_iter()
+                .map(|m| (m.condition_id, m.closed))
+                .collect();
+            resolved.extend(batch.iter().map(|id| {
+                let status = match found.get(id) {
+                    Some(true) => Missing::Closed,
+                    Some(false) => Missing::Open,
+                    None => Missing::Delisted,
+                };
+                (id.clone(), status)
+            }));
+        }
+        Ok(resolved)
+    }
+
+    /// Remove a closed or delisted market unless we hold one of its tokens.
+    fn retire(&self, market_id: &MarketId, status: Missing, held: &HashSet<TokenId>) {
+        let Some(pair) = self.market_data.get_pair(market_id) else {
+            return;
+        };
+        if status == Missing::Open {
+            return;
+        }
+        if held.contains(&pair.yes_token) || held.contains(&pair.no_token) {
+            debug!(
+                "[MARKET] {} is no longer listed but still held, keeping it",
+                market_id
+            );
+            return;
+        }
+        let Some(pair) = self.market_data.remove_pair(market_id) else {
+            return;
+        };
+        self.emit(match status {
+            Missing::Closed => MarketEvent::Closed(pair),
+            _ => MarketEvent::Delisted(pair),
+        });
+    }
+
+    fn emit(&self, event: MarketEvent) {
+        MARKET_EVENTS.with_label_values(&[event.as_str()]).inc();
+        info!(
+            "[MARKET] Market {}: {} ({})",
+            event.as_str(),
+            event.pair().question,
+            event.pair().market_id
+        );
+        // No receivers is fine
+        let _ = self.events.send(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair(id: &str, question: &str) -> MarketPair {
+        MarketPair {
+            market_id: id.into(),
+            yes_token: format!("{}-yes", id),
+            no_token: format!("{}-no", id),
+            question: question.into(),
+            category: None,
+            end_date: None,
+        }
+    }
+
+    fn refresher(register_new: bool) -> (MetadataRefresher, Arc<MarketData>) {
+        let data = Arc::new(MarketData::new());
+        let config = MetadataConfig {
+            register_new,
+            ..MetadataConfig::default()
+        };
+        (MetadataRefresher::new(config, data.clone()).unwrap(), data)
+    }
+
+    #[test]
+    fn test_parses_binary_gamma_markets() {
+        let json = r#"[
+            {"conditionId":"0xabc","question":"Will it rain?","category":"Weather",
+             "endDate":"2026-11-05T12:00:00Z","clobTokenIds":"[\"1\",\"2\"]","closed":false},
+            {"conditionId":"0xdef","question":"Who wins?","clobTokenIds":"[\"1\",\"2\",\"3\"]"},
+            {"conditionId":"0x123","question":"No tokens yet"}
+        ]"#;
+        let markets: Vec<GammaMarket> = serde_json::from_str(json).unwrap();
+        let pairs: Vec<MarketPair> = markets
+            .into_iter()
+            .filter_map(GammaMarket::into_pair)
+            .collect();
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].market_id, "0xabc");
+        assert_eq!(
+            (pairs[0].yes_token.as_str(), pairs[0].no_token.as_str()),
+            ("1", "2")
+        );
+        assert_eq!(pairs[0].category.as_deref(), Some("Weather"));
+        assert_eq!(
+            pairs[0].end_date.unwrap().to_rfc3339(),
+            "2026-11-05T12:00:00+00:00"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_listing_updates_in_place_and_reports_new_markets() {
+        let (refresher, data) = refresher(false);
+        let mut events = refresher.subscribe();
+        data.register_pair(pair("m1", "Old question?"));
+        data.update_price(&"m1-yes".to_string(), 0.40, 0.42);
+
+        refresher.apply_listing(vec![pair("m1", "New question?"), pair("m2", "Fresh?")]);
+        assert_eq!(
+            data.get_pair(&"m1".into()).unwrap().question,
+            "New question?"
+        );
+        assert!(
+            data.get_price(&"m1-yes".to_string()).is_some(),
+            "quotes kept"
+        );
+        assert!(data.get_pair(&"m2".into()).is_none(), "only reported");
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            MarketEvent::Updated(_)
+        ));
+        assert!(matches!(events.try_recv().unwrap(), MarketEvent::Listed(_)));
+
+        // Unchanged metadata and already reported markets are quiet
+        refresher.apply_listing(vec![pair("m1", "New question?"), pair("m2", "Fresh?")]);
+        assert!(events.try_recv().is_err());
+
+        let (refresher, data) = self::refresher(true);
+        refresher.apply_listing(vec![pair("m2", "Fresh?")]);
+        assert!(data.get_pair(&"m2".into()).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_closed_markets_leave_the_universe_once_flat() {
+        let (refresher, data) = refresher(false);
+        let mut events = refresher.subscribe();
+        for id in ["m1", "m2", "m3"] {
+            data.register_pair(pair(id, "?"));
+            data.update_price(&format!("{}-yes", id), 0.40, 0.42);
+        }
+
+        let held: HashSet<TokenId> = ["m2-no".to_string()].into();
+        refresher.retire(&"m1".into(), Missing::Closed, &held);
+        refresher.retire(&"m2".into(), Missing::Delisted, &held);
+        refresher.retire(&"m3".into(), Missing::Open, &held);
+
+        assert!(data.get_pair(&"m1".into()).is_none());
+        assert!(data.get_price(&"m1-yes".to_string()).is_none());
+        assert!(!data.subscription_tokens().contains(&"m1-no".to_string()));
+        assert!(data.get_pair(&"m2".into()).is_some(), "held");
+        assert!(data.get_pair(&"m3".into()).is_some());
+        assert!(matches!(events.try_recv().unwrap(), MarketEvent::Closed(_)));
+        assert!(events.try_recv().is_err());
+
+        refresher.retire(&"m2".into(), Missing::Delisted, &HashSet::new());
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            MarketEvent::Delisted(_)
+        ));
+    }
+}
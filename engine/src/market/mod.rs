@@ -2,7 +2,39 @@
 //!
 //! Uses lock-free data structures for minimal latency.
 
+mod alerts;
+mod correlation;
 mod data;
+mod housekeeping;
+mod liquidity;
+mod metadata;
+mod order_rules;
+mod prioritizer;
+mod resolver;
+mod snapshot;
+mod subscriptions;
+mod validator;
+mod whales;
 
 #[allow(unused_imports)]
-pub use data::{DepthLevel, MarketData, MarketPair, OrderBook, PriceLevel, TokenId, VwapResult};
+pub use alerts::{AlertRule, PriceAlertConfig, PriceAlerts};
+pub use correlation::{CorrelationConfig, MarketCorrelations};
+#[allow(unused_imports)]
+pub use data::{
+    DepthLevel, ImpactModel, MarketData, MarketDataStats, MarketId, MarketPair, OrderBook,
+    PriceLevel, PriceTick, QuoteUpdate, TokenId, VwapResult,
+};
+pub use housekeeping::HousekeepingConfig;
+#[allow(unused_imports)]
+pub use liquidity::{LiquidityConfig, LiquidityScore, LiquidityTier, MarketLiquidity};
+#[allow(unused_imports)]
+pub use metadata::{MarketEvent, MetadataConfig, MetadataRefresher};
+#[allow(unused_imports)]
+pub use order_rules::{OrderRules, OrderRulesLoader, SIZE_INCREMENT};
+#[allow(unused_imports)]
+pub use prioritizer::{SubscriptionConfig, SubscriptionPlan, SubscriptionPrioritizer};
+pub use resolver::PairResolver;
+pub use snapshot::MarketSnapshot;
+pub use validator::{BookValidator, BookValidatorConfig, ResyncRequests};
+#[allow(unused_imports)]
+pub use whales::{WhaleConfig, WhaleEvent, WhaleMonitor, WhaleMove, WhaleMoveKind};
@@ -0,0 +1,245 @@
+//! Per-market order size and price rules.
+//!
+//! The CLOB rejects orders below a market's minimum size or off its tick,
+//! and accepts sizes in 0.01-share increments. Rules for every subscribed
+//! token are fetched from the REST `/book` endpoint (which reports
+//! `min_order_size` and `tick_size`); tick size changes also arrive over the
+//! WebSocket. Until a token's rules are known only the increment applies.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::Value;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info};
+
+use super::data::{MarketData, TokenId};
+
+/// Share increment accepted by the exchange
+pub const SIZE_INCREMENT: f64 = 0.01;
+
+/// Tick size assumed until the market reports one
+const DEFAULT_TICK_SIZE: f64 = 0.01;
+
+/// Time between fetches of missing rules
+const LOAD_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Tokens fetched per round, so a large subscription does not burst requests
+const MAX_TOKENS_PER_ROUND: usize = 100;
+
+/// Order rules for one token.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrderRules {
+    pub tick_size: f64,
+    /// Minimum order size in shares (None until fetched)
+    pub min_order_size: Option<f64>,
+}
+
+impl Default for OrderRules {
+    fn default() -> Self {
+        Self {
+            tick_size: DEFAULT_TICK_SIZE,
+            min_order_size: None,
+        }
+    }
+}
+
+impl OrderRules {
+    /// Round a size down to the share increment. None if it rounds to zero
+    /// or below the minimum order size.
+    pub fn round_size(&self, size: f64) -> Option<f64> {
+        // Dividing by the scale keeps e.g. 10.45 exact; the epsilon keeps
+        // 0.29 (28.999.. increments) from losing an increment
+        let scale = 1.0 / SIZE_INCREMENT;
+        let increments = (size * scale + 1e-6).floor();
+        let rounded = increments / scale;
+        let valid = increments >= 1.0 && self.min_order_size.is_none_or(|min| rounded >= min);
+        valid.then_some(rounded)
+    }
+
+    /// Snap a price to the nearest tick.
+    pub fn round_price(&self, price: f64) -> f64 {
+        (price / self.tick_size).round() * self.tick_size
+    }
+}
+
+impl MarketData {
+    /// Order rules for a token (defaults if none are known).
+    pub fn order_rules(&self, token_id: &TokenId) -> OrderRules {
+        self.order_rules
+            .get(token_id)
+            .map(|r| *r)
+            .unwrap_or_default()
+    }
+
+    /// Record a token's tick size.
+    pub fn set_tick_size(&self, token_id: &TokenId, tick_size: f64) {
+        self.order_rules
+            .entry(token_id.clone())
+            .or_default()
+            .tick_size = tick_size;
+    }
+
+    /// Record a token's minimum order size.
+    pub fn set_min_order_size(&self, token_id: &TokenId, min_order_size: f64) {
+        self.order_rules
+            .entry(token_id.clone())
+            .or_default()
+            .min_order_size = Some(min_order_size);
+    }
+
+    /// Subscribed tokens whose minimum order size is not known yet.
+    pub fn tokens_missing_order_rules(&self) -> Vec<TokenId> {
+        self.subscription_tokens()
+            .into_iter()
+            .filter(|token| self.order_rules(token).min_order_size.is_none())
+            .collect()
+    }
+}
+
+/// Rule fields of a CLOB REST `/book` response
+#[derive(Debug, Deserialize)]
+struct BookRules {
+    #[serde(default)]
+    tick_size: Value,
+    #[serde(default)]
+    min_order_size: Value,
+}
+
+/// Parse a positive number sent as either a JSON number or a string.
+fn positive_number(value: &Value) -> Option<f64> {
+    let number = match value {
+        Value::Number(n) => n.as_f64()?,
+        Value::String(s) => s.parse().ok()?,
+        _ => return None,
+    };
+    (number.is_finite() && number > 0.0).then_some(number)
+}
+
+/// Fetches order rules for subscribed tokens that have none yet.
+pub struct OrderRulesLoader {
+    client: Client,
+    base_url: String,
+    market_data: Arc<MarketData>,
+}
+
+impl OrderRulesLoader {
+    /// Create a loader that fetches from `clob_url`.
+    pub fn new(clob_url: &str, market_data: Arc<MarketData>) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .context("Failed to create order rules HTTP client")?;
+
+        Ok(Self {
+            client,
+            base_url: clob_url.trim_end_matches('/').to_string(),
+            market_data,
+        })
+    }
+
+    /// Load missing rules every interval until cancelled.
+    pub async fn run(self, cancel: CancellationToken) {
+        let mut ticker = tokio::time::interval(LOAD_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => self.load_missing().await,
+                _ = cancel.cancelled() => break,
+            }
+        }
+    }
+
+    async fn load_missing(&self) {
+        let missing = self.market_data.tokens_missing_order_rules();
+        if missing.is_empty() {
+            return;
+        }
+
+        let mut loaded = 0;
+        for token_id in missing.iter().take(MAX_TOKENS_PER_ROUND) {
+            match self.fetch(token_id).await {
+                Ok(()) => loaded += 1,
+                Err(e) => debug!("[MARKET] Order rules for {} failed: {:#}", token_id, e),
+            }
+        }
+        info!(
+            "[MARKET] Loaded order rules for {}/{} tokens",
+            loaded,
+            missing.len()
+        );
+    }
+
+    /// Fetch and store one token's rules.
+    async fn fetch(&self, token_id: &TokenId) -> Result<()> {
+        let rules: BookRules = self
+            .client
+            .get(format!("{}/book", self.base_url))
+            .query(&[("token_id", token_id)])
+            .send()
+            .await
+            .context("Failed to fetch book")?
+            .error_for_status()
+            .context("CLOB returned an error status")?
+            .json()
+            .await
+            .context("Failed to parse book")?;
+
+        if let Some(tick_size) = positive_number(&rules.tick_size).filter(|t| *t < 1.0) {
+            self.market_data.set_tick_size(token_id, tick_size);
+        }
+        let min_order_size =
+            positive_number(&rules.min_order_size).context("Book has no min_order_size")?;
+        self.market_data
+            .set_min_order_size(token_id, min_order_size);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sizes_round_down_to_increment_and_minimum() {
+        let rules = OrderRules::default();
+        assert_eq!(rules.round_size(10.456), Some(10.45));
+        assert_eq!(rules.round_size(0.29), Some(0.29));
+        assert_eq!(rules.round_size(0.004), None);
+
+        let rules = OrderRules {
+            tick_size: 0.001,
+            min_order_size: Some(5.0),
+        };
+        assert_eq!(rules.round_size(5.0), Some(5.0));
+        assert_eq!(rules.round_size(4.999), None);
+        assert!((rules.round_price(0.4126) - 0.413).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_rules_are_tracked_per_token() {
+        let data = MarketData::new();
+        let token: TokenId = "t1".into();
+        data.track_token(token.clone());
+        assert_eq!(data.tokens_missing_order_rules(), vec![token.clone()]);
+
+        data.set_tick_size(&token, 0.001);
+        assert_eq!(data.tokens_missing_order_rules().len(), 1);
+        data.set_min_order_size(&token, 5.0);
+        assert!(data.tokens_missing_order_rules().is_empty());
+        assert_eq!(
+            data.order_rules(&token),
+            OrderRules {
+                tick_size: 0.001,
+                min_order_size: Some(5.0),
+            }
+        );
+
+        let book: BookRules =
+            serde_json::from_str(r#"{"tick_size":"0.01","min_order_size":5}"#).unwrap();
+        assert_eq!(positive_number(&book.tick_size), Some(0.01));
+        assert_eq!(positive_number(&book.min_order_size), Some(5.0));
+    }
+}
@@ -0,0 +1,386 @@
+//! Choosing which tokens to subscribe to under a WebSocket asset limit.
+//!
+//! When the feed accepts fewer assets than we know about, markets we hold
+//! positions in are kept first, then the most liquid markets fill the core
+//! of the budget. A fraction of the budget cycles through the remaining
+//! long tail every interval, so thin and newly discovered markets still get
+//! books (and a liquidity score that can promote them into the core).
+//! Quotes of tokens rotated out are dropped so strategies never act on a
+//! book that is no longer updated.
+
+use std::collections::{BTreeSet, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::{Mutex, RwLock};
+use serde::Serialize;
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+
+use crate::metrics::WS_SUBSCRIPTIONS;
+
+use super::data::{MarketData, MarketId, TokenId};
+use super::liquidity::MarketLiquidity;
+
+/// Subscription limit settings
+#[derive(Debug, Clone)]
+pub struct SubscriptionConfig {
+    /// Most tokens subscribed at once (0 = unlimited)
+    pub max_assets: usize,
+    /// Share of `max_assets` that rotates through the long tail
+    pub rotation_fraction: f64,
+    /// How often the selection is recomputed and the tail rotated
+    pub rotation_interval: Duration,
+}
+
+impl Default for SubscriptionConfig {
+    fn default() -> Self {
+        Self {
+            max_assets: 0,
+            rotation_fraction: 0.2,
+            rotation_interval: Duration::from_secs(60),
+        }
+    }
+}
+
+impl SubscriptionConfig {
+    /// Load from `WS_MAX_ASSETS`, `WS_ROTATION_FRACTION` and
+    /// `WS_ROTATION_INTERVAL_MS`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok();
+        Self {
+            max_assets: var("WS_MAX_ASSETS")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.max_assets),
+            rotation_fraction: var("WS_ROTATION_FRACTION")
+                .and_then(|v| v.parse::<f64>().ok())
+                .filter(|f| (0.0..=1.0).contains(f))
+                .unwrap_or(defaults.rotation_fraction),
+            rotation_interval: var("WS_ROTATION_INTERVAL_MS")
+                .and_then(|v| v.parse().ok())
+                .filter(|ms| *ms > 0)
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.rotation_interval),
+        }
+    }
+}
+
+/// Current subscription selection (served by `/control/subscriptions`)
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SubscriptionPlan {
+    /// 0 = unlimited
+    pub max_assets: usize,
+    /// Tokens we could subscribe to
+    pub candidates: usize,
+    /// Held and most liquid markets, always subscribed
+    pub core: Vec<TokenId>,
+    /// Long-tail tokens in the current rotation window
+    pub rotating: Vec<TokenId>,
+    /// Number of rotations so far
+    pub rotations: u64,
+}
+
+/// A market pair, or a token without one, subscribed as a whole
+#[derive(Debug)]
+struct Unit {
+    key: String,
+    tokens: Vec<TokenId>,
+    held: bool,
+    score: f64,
+}
+
+#[derive(Debug, Default)]
+struct RotationState {
+    /// Index into the long tail where the next window starts
+    offset: usize,
+    rotations: u64,
+}
+
+/// Chooses the subscribed tokens and rotates the long tail.
+pub struct SubscriptionPrioritizer {
+    config: SubscriptionConfig,
+    market_data: Arc<MarketData>,
+    liquidity: Option<Arc<MarketLiquidity>>,
+    plan: RwLock<SubscriptionPlan>,
+    rotation: Mutex<RotationState>,
+    /// Signalled when the subscribed set changes (the permit is kept if
+    /// the WebSocket handler is not waiting)
+    changed: Notify,
+}
+
+impl SubscriptionPrioritizer {
+    pub fn new(config: SubscriptionConfig, market_data: Arc<MarketData>) -> Self {
+        let plan = SubscriptionPlan {
+            max_assets: config.max_assets,
+            ..SubscriptionPlan::default()
+        };
+        Self {
+            config,
+            market_data,
+            liquidity: None,
+            plan: RwLock::new(plan),
+            rotation: Mutex::new(RotationState::default()),
+            changed: Notify::new(),
+        }
+    }
+
+    /// Rank markets by liquidity score
+    pub fn set_liquidity(&mut self, liquidity: Arc<MarketLiquidity>) {
+        self.liquidity = Some(liquidity);
+    }
+
+    pub fn plan(&self) -> SubscriptionPlan {
+        self.plan.read().clone()
+    }
+
+    /// Tokens the WebSocket should be subscribed to now (sorted).
+    pub fn active_tokens(&self) -> Vec<TokenId> {
+        if self.config.max_assets == 0 {
+            return self.market_data.subscription_tokens();
+        }
+        let plan = self.plan.read();
+        let tokens: BTreeSet<&TokenId> = plan.core.iter().chain(&plan.rotating).collect();
+        tokens.into_iter().cloned().collect()
+    }
+
+    /// Wait until the subscribed set changes.
+    pub async fn changed(&self) {
+        self.changed.notified().await;
+    }
+
+    /// Recompute the selection every interval, rotating the long tail,
+    /// until cancelled. `held` returns the tokens we hold positions in.
+    pub async fn run<F>(self: Arc<Self>, held: F, cancel: CancellationToken)
+    where
+        F: Fn() -> HashSet<TokenId>,
+    {
+        if self.config.max_assets > 0 {
+            info!(
+                "[MARKET] Subscribing to at most {} assets, rotating {:.0}% every {:?}",
+                self.config.max_assets,
+                self.config.rotation_fraction * 100.0,
+                self.config.rotation_interval
+            );
+        }
+        let mut ticker = tokio::time::interval(self.config.rotation_interval);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => self.refresh(&held()),
+                _ = cancel.cancelled() => break,
+            }
+        }
+    }
+
+    /// Select the core and the next rotation window.
+    fn refresh(&self, held: &HashSet<TokenId>) {
+        let before = self.active_tokens();
+        let units = self.units(held);
+        let candidates: usize = units.iter().map(|u| u.tokens.len()).sum();
+        let max = self.config.max_assets;
+
+        let mut rotation = self.rotation.lock();
+        let plan = if max == 0 || candidates <= max {
+            SubscriptionPlan {
+                max_assets: max,
+                candidates,
+                core: units.into_iter().flat_map(|u| u.tokens).collect(),
+                rotating: Vec::new(),
+                rotations: rotation.rotations,
+            }
+        } else {
+            let rotating_slots = (max as f64 * self.config.rotation_fraction).floor() as usize;
+            let core_budget = max - rotating_slots;
+            let mut core = Vec::new();
+            let mut tail = Vec::new();
+            for unit in units {
+                if core.len() + unit.tokens.len() <= core_budget {
+                    core.extend(unit.tokens);
+                } else {
+                    tail.push(unit);
+                }
+            }
+            // Cycle through the tail in a stable order
+            tail.sort_by(|a, b| a.key.cmp(&b.key));
+
+            let mut rotating = Vec::new();
+            let start = rotation.offset % tail.len().max(1);
+            let mut taken = 0;
+            for unit in tail.iter().cycle().skip(start).take(tail.len()) {
+                if core.len() + rotating.len() + unit.tokens.len() > max {
+                    break;
+                }
+                rotating.extend(unit.tokens.iter().cloned());
+                taken += 1;
+            }
+            rotation.offset = start + taken;
+            rotation.rotations += 1;
+
+            SubscriptionPlan {
+                max_assets: max,
+                candidates,
+                core,
+                rotating,
+                rotations: rotation.rotations,
+            }
+        };
+        drop(rotation);
+
+        WS_SUBSCRIPTIONS
+            .with_label_values(&["core"])
+            .set(plan.core.len() as f64);
+        WS_SUBSCRIPTIONS
+            .with_label_values(&["rotating"])
+            .set(plan.rotating.len() as f64);
+        WS_SUBSCRIPTIONS
+            .with_label_values(&["idle"])
+            .set((candidates - plan.core.len() - plan.rotating.len()) as f64);
+        *self.plan.write() = plan;
+
+        let after = self.active_tokens();
+        if after != before {
+            if max > 0 {
+                let active: HashSet<&TokenId> = after.iter().collect();
+                let dropped: Vec<TokenId> =
+                    before.into_iter().filter(|t| !active.contains(t)).collect();
+                self.market_data.forget_quotes(&dropped);
+            }
+            self.changed.notify_one();
+        }
+    }
+
+    /// Subscribable units, best first: held markets, then by liquidity score.
+    fn units(&self, held: &HashSet<TokenId>) -> Vec<Unit> {
+        let score = |market_id: &MarketId| {
+            self.liquidity
+                .as_ref()
+                .and_then(|l| l.score(market_id))
+                .map_or(0.0, |s| s.score)
+        };
+
+        let mut paired = HashSet::new();
+        let mut units: Vec<Unit> = self
+            .market_data
+            .iter_pairs()
+            .map(|pair| {
+                paired.insert(pair.yes_token.clone());
+                paired.insert(pair.no_token.clone());
+                Unit {
+                    held: held.contains(&pair.yes_token) || held.contains(&pair.no_token),
+                    score: score(&pair.market_id),
+                    tokens: vec![pair.yes_token, pair.no_token],
+                    key: pair.market_id,
+                }
+            })
+            .collect();
+        units.extend(
+            self.market_data
+                .subscription_tokens()
+                .into_iter()
+                .filter(|t| !paired.contains(t))
+                .map(|token| Unit {
+                    held: held.contains(&token),
+                    score: 0.0,
+                    tokens: vec![token.clone()],
+                    key: token,
+                }),
+        );
+
+        units.sort_by(|a, b| {
+            b.held
+                .cmp(&a.held)
+                .then(b.score.total_cmp(&a.score))
+                .then_with(|| a.key.cmp(&b.key))
+        });
+        units
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market::{LiquidityConfig, LiquidityTier, MarketPair};
+
+    fn register(data: &MarketData, id: &str) {
+        data.register_pair(MarketPair {
+            market_id: id.into(),
+            yes_token: format!("{}-yes", id),
+            no_token: format!("{}-no", id),
+            question: "Q?".into(),
+            category: None,
+            end_date: None,
+        });
+    }
+
+    fn prioritizer(max_assets: usize, markets: &[&str]) -> SubscriptionPrioritizer {
+        let data = Arc::new(MarketData::new());
+        for id in markets {
+            register(&data, id);
+        }
+        let liquidity = Arc::new(MarketLiquidity::new(LiquidityConfig::default()));
+        liquidity.set_score("deep", 1_000.0, LiquidityTier::A);
+        let mut prioritizer = SubscriptionPrioritizer::new(
+            SubscriptionConfig {
+                max_assets,
+                rotation_fraction: 0.5,
+                ..SubscriptionConfig::default()
+            },
+            data,
+        );
+        prioritizer.set_liquidity(liquidity);
+        prioritizer
+    }
+
+    #[test]
+    fn test_unlimited_subscribes_everything() {
+        let prioritizer = prioritizer(0, &["a", "b"]);
+        prioritizer.refresh(&HashSet::new());
+        assert_eq!(prioritizer.active_tokens().len(), 4);
+        assert!(prioritizer.plan().rotating.is_empty());
+    }
+
+    #[test]
+    fn test_core_keeps_held_and_liquid_markets_and_tail_rotates() {
+        let prioritizer = prioritizer(8, &["a", "b", "c", "d", "deep", "held"]);
+        let held = HashSet::from(["held-no".to_string()]);
+
+        let mut windows = Vec::new();
+        for _ in 0..3 {
+            prioritizer.refresh(&held);
+            let plan = prioritizer.plan();
+            assert_eq!(plan.candidates, 12);
+            assert_eq!(
+                plan.core,
+                vec!["held-yes", "held-no", "deep-yes", "deep-no"]
+            );
+            assert_eq!(prioritizer.active_tokens().len(), 8);
+            windows.push(plan.rotating);
+        }
+        assert_eq!(windows[0], vec!["a-yes", "a-no", "b-yes", "b-no"]);
+        assert_eq!(windows[1], vec!["c-yes", "c-no", "d-yes", "d-no"]);
+        assert_eq!(windows[2], windows[0]);
+    }
+
+    #[test]
+    fn test_rotated_out_tokens_lose_their_quotes() {
+        let prioritizer = prioritizer(4, &["a", "b", "c"]);
+        prioritizer.refresh(&HashSet::new());
+        prioritizer
+            .market_data
+            .update_price(&"b-yes".into(), 0.4, 0.5);
+        assert_eq!(
+            prioritizer.active_tokens(),
+            vec!["a-no", "a-yes", "b-no", "b-yes"]
+        );
+
+        prioritizer.refresh(&HashSet::new());
+        assert_eq!(
+            prioritizer.active_tokens(),
+            vec!["a-no", "a-yes", "c-no", "c-yes"]
+        );
+        assert!(prioritizer.market_data.get_price(&"b-yes".into()).is_none());
+        // Still a candidate for later windows
+        assert_eq!(prioritizer.plan().candidates, 6);
+    }
+}
@@ -0,0 +1,254 @@
+//! Market pair discovery for tokens seen without one.
+//!
+//! The WebSocket feed can deliver books for token IDs that were subscribed
+//! without a pair mapping (tracked tokens, relays). On first sight of such
+//! an asset the resolver asks the CLOB for the token's market (the REST
+//! `/book` response names it), looks up the market's outcome tokens with
+//! `/markets/{condition_id}` and registers the pair. Lookups run one at a
+//! time off the feed's path; a token whose lookup failed is retried at most
+//! once per `RETRY_AFTER`.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use reqwest::Client;
+use serde::Deserialize;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info};
+
+use super::data::{MarketData, MarketPair, TokenId};
+
+/// Time before an unresolved token is looked up again
+const RETRY_AFTER: Duration = Duration::from_secs(300);
+
+/// Unknown tokens queued for lookup; the rest wait to be seen again
+const QUEUE_CAPACITY: usize = 1024;
+
+/// Market field of a CLOB REST `/book` response
+#[derive(Debug, Deserialize)]
+struct BookMarket {
+    #[serde(default)]
+    market: String,
+}
+
+/// CLOB REST `/markets/{condition_id}` response
+#[derive(Debug, Deserialize)]
+struct ClobMarket {
+    #[serde(default)]
+    condition_id: String,
+    #[serde(default)]
+    question: String,
+    #[serde(default)]
+    end_date_iso: Option<DateTime<Utc>>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    tokens: Vec<ClobToken>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClobToken {
+    token_id: TokenId,
+    #[serde(default)]
+    outcome: String,
+}
+
+impl ClobMarket {
+    /// The market as a pair, if it has exactly two outcome tokens. The
+    /// "Yes" outcome is the YES token; otherwise the listed order is kept.
+    fn into_pair(self) -> Option<MarketPair> {
+        let [first, second] = <[ClobToken; 2]>::try_from(self.tokens).ok()?;
+        let (yes, no) = if second.outcome.eq_ignore_ascii_case("yes") {
+            (second, first)
+        } else {
+            (first, second)
+        };
+        (!self.condition_id.is_empty()).then(|| MarketPair {
+            market_id: self.condition_id,
+            yes_token: yes.token_id,
+            no_token: no.token_id,
+            question: self.question,
+            category: self.tags.into_iter().find(|t| !t.is_empty()),
+            end_date: self.end_date_iso,
+        })
+    }
+}
+
+/// Registers the market pair of tokens that arrive without one.
+pub struct PairResolver {
+    client: Client,
+    base_url: String,
+    market_data: Arc<MarketData>,
+    queue: (flume::Sender<TokenId>, flume::Receiver<TokenId>),
+    /// Last lookup queued per unknown token
+    attempts: DashMap<TokenId, Instant>,
+}
+
+impl PairResolver {
+    /// Create a resolver that looks tokens up at `clob_url`.
+    pub fn new(clob_url: &str, market_data: Arc<MarketData>) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .context("Failed to create pair resolver HTTP client")?;
+
+        Ok(Self {
+            client,
+            base_url: clob_url.trim_end_matches('/').to_string(),
+            market_data,
+            queue: flume::bounded(QUEUE_CAPACITY),
+            attempts: DashMap::new(),
+        })
+    }
+
+    /// Note a token seen on the feed, queueing a lookup if it has no pair.
+    /// Cheap for known tokens, so it can be called on every update.
+    pub fn observe(&self, token_id: &str) {
+        if self
+            .market_data
+            .get_market_id(&token_id.to_string())
+            .is_some()
+        {
+            return;
+        }
+        let now = Instant::now();
+        let due = self
+            .attempts
+            .get(token_id)
+            .is_none_or(|last| now.duration_since(*last) >= RETRY_AFTER);
+        if due && self.queue.0.try_send(token_id.to_string()).is_ok() {
+            self.attempts.insert(token_id.to_string(), now);
+        }
+    }
+
+    /// Resolve queued tokens until cancelled.
+    pub async fn run(self: Arc<Self>, cancel: CancellationToken) {
+        loop {
+            tokio::select! {
+                token = self.queue.1.recv_async() => {
+                    let Ok(token_id) = token else { break };
+                    if self.market_data.get_market_id(&token_id).is_some() {
+                        continue;
+                    }
+                    match self.resolve(&token_id).await {
+                        Ok(pair) => {
+                            let market_id = pair.market_id.clone();
+                            if self.market_data.register_pair(pair) {
+                                self.attempts.remove(&token_id);
+                                info!(
+                                    "[MARKET] Registered market {} for unknown token {}",
+                                    market_id, token_id
+                                );
+                            }
+                        }
+                        Err(e) => debug!(
+                            "[MARKET] Pair lookup for token {} failed: {:#}",
+                            token_id, e
+                        ),
+                    }
+                }
+                _ = cancel.cancelled() => break,
+            }
+        }
+    }
+
+    /// Look up the market pair a token belongs to.
+    async fn resolve(&self, token_id: &TokenId) -> Result<MarketPair> {
+        let book: BookMarket = self
+            .get(
+                &format!("{}/book", self.base_url),
+                &[("token_id", token_id)],
+            )
+            .await
+            .context("Failed to fetch book")?;
+        if book.market.is_empty() {
+            anyhow::bail!("Book names no market");
+        }
+
+        let market: ClobMarket = self
+            .get(&format!("{}/markets/{}", self.base_url, book.market), &[])
+            .await
+            .context("Failed to fetch market")?;
+        let pair = market
+            .into_pair()
+            .context("Market does not have exactly two outcome tokens")?;
+        if pair.yes_token != *token_id && pair.no_token != *token_id {
+            anyhow::bail!("Market {} does not list the token", pair.market_id);
+        }
+        Ok(pair)
+    }
+
+    async fn get<T: serde::de::DeserializeOwned>(
+        &self,
+        url: &str,
+        query: &[(&str, &str)],
+    ) -> Result<T> {
+        self.client
+            .get(url)
+            .query(query)
+            .send()
+            .await
+            .context("Request failed")?
+            .error_for_status()
+            .context("CLOB returned an error status")?
+            .json()
+            .await
+            .context("Failed to parse response")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_market_response_becomes_pair() {
+        let market: ClobMarket = serde_json::from_str(
+            r#"{
+                "condition_id": "0xabc",
+                "question": "Will it rain?",
+                "end_date_iso": "2026-01-01T00:00:00Z",
+                "tags": ["Weather"],
+                "tokens": [
+                    {"token_id": "no_tok", "outcome": "No", "price": 0.4},
+                    {"token_id": "yes_tok", "outcome": "Yes", "price": 0.6}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let pair = market.into_pair().unwrap();
+        assert_eq!(pair.market_id, "0xabc");
+        assert_eq!(pair.yes_token, "yes_tok");
+        assert_eq!(pair.no_token, "no_tok");
+        assert_eq!(pair.category.as_deref(), Some("Weather"));
+        assert!(pair.end_date.is_some());
+
+        let multi: ClobMarket =
+            serde_json::from_str(r#"{"condition_id": "0xdef", "tokens": [{"token_id": "a"}]}"#)
+                .unwrap();
+        assert!(multi.into_pair().is_none());
+    }
+
+    #[test]
+    fn test_only_unknown_tokens_are_queued_once() {
+        let data = Arc::new(MarketData::new());
+        data.register_pair(MarketPair {
+            market_id: "m1".into(),
+            yes_token: "yes".into(),
+            no_token: "no".into(),
+            question: "Test?".into(),
+            category: None,
+            end_date: None,
+        });
+        let resolver = PairResolver::new("http://localhost", data).unwrap();
+
+        resolver.observe("yes");
+        resolver.observe("unknown");
+        resolver.observe("unknown");
+        assert_eq!(resolver.queue.1.len(), 1);
+        assert_eq!(resolver.queue.1.try_recv().unwrap(), "unknown");
+    }
+}
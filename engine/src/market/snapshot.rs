@@ -0,0 +1,247 @@
+//! Immutable per-cycle market view handed to strategies.
+//!
+//! The strategy engine captures one snapshot per tick so every strategy sees
+//! the same consistent state, and a backtest can replay strategies by building
+//! snapshots directly. Books and history windows are shared with the store
+//! (`Arc`, copy-on-write on the store side), so a snapshot copies pointers
+//! rather than depth levels and ticks.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::data::{MarketData, PriceTick};
+use super::{MarketId, MarketPair, OrderBook, PriceLevel, TokenId};
+
+/// Point-in-time copy of pairs, prices, order books, and recent history
+#[derive(Clone, Debug, Default)]
+pub struct MarketSnapshot {
+    timestamp_ns: u64,
+    /// Sorted by market ID so iteration order is deterministic
+    pairs: Vec<MarketPair>,
+    prices: HashMap<TokenId, PriceLevel>,
+    books: HashMap<TokenId, Arc<OrderBook>>,
+    history: HashMap<TokenId, Arc<[PriceTick]>>,
+}
+
+#[allow(dead_code)]
+impl MarketSnapshot {
+    /// Create an empty snapshot at the given time (for backtests and tests).
+    pub fn new(timestamp_ns: u64) -> Self {
+        Self {
+            timestamp_ns,
+            ..Self::default()
+        }
+    }
+
+    /// Add a market pair.
+    pub fn with_pair(mut self, pair: MarketPair) -> Self {
+        let idx = self.pairs.partition_point(|p| p.market_id < pair.market_id);
+        self.pairs.insert(idx, pair);
+        self
+    }
+
+    /// Set the top-of-book price for a token.
+    pub fn with_price(mut self, token_id: TokenId, price: PriceLevel) -> Self {
+        self.prices.insert(token_id, price);
+        self
+    }
+
+    /// Set the order book for a token.
+    pub fn with_order_book(mut self, book: OrderBook) -> Self {
+        self.books.insert(book.token_id.clone(), Arc::new(book));
+        self
+    }
+
+    /// Set the recent price history for a token (oldest first).
+    pub fn with_history(mut self, token_id: TokenId, ticks: Vec<PriceTick>) -> Self {
+        self.history.insert(token_id, ticks.into());
+        self
+    }
+
+    /// Time of the last market data update captured
+    pub fn timestamp_ns(&self) -> u64 {
+        self.timestamp_ns
+    }
+
+    /// All market pairs, ordered by market ID
+    pub fn pairs(&self) -> &[MarketPair] {
+        &self.pairs
+    }
+
+    /// Get market pair by market ID
+    pub fn get_pair(&self, market_id: &MarketId) -> Option<&MarketPair> {
+        self.pairs
+            .binary_search_by(|p| p.market_id.cmp(market_id))
+            .ok()
+            .map(|idx| &self.pairs[idx])
+    }
+
+    /// Sports markets (all markets until category filtering lands)
+    pub fn sports_markets(&self) -> &[MarketPair] {
+        &self.pairs
+    }
+
+    /// Get current price for a token
+    pub fn get_price(&self, token_id: &TokenId) -> Option<PriceLevel> {
+        self.prices.get(token_id).copied()
+    }
+
+    /// Get best ask price for a token
+    pub fn get_ask(&self, token_id: &TokenId) -> Option<f64> {
+        self.prices.get(token_id).map(|p| p.ask)
+    }
+
+    /// Get best bid price for a token
+    pub fn get_bid(&self, token_id: &TokenId) -> Option<f64> {
+        self.prices.get(token_id).map(|p| p.bid)
+    }
+
+    /// Get full order book for a token
+    pub fn get_order_book(&self, token_id: &TokenId) -> Option<&OrderBook> {
+        self.books.get(token_id).map(Arc::as_ref)
+    }
+
+    /// Get recent price history for a token (oldest first)
+    pub fn get_history(&self, token_id: &TokenId) -> Option<&[PriceTick]> {
+        self.history.get(token_id).map(Arc::as_ref)
+    }
+
+    /// Whether any prices were captured
+    pub fn has_data(&self) -> bool {
+        !self.prices.is_empty()
+    }
+
+    /// Number of order books captured
+    pub fn order_book_count(&self) -> usize {
+        self.books.len()
+    }
+}
+
+impl MarketData {
+    /// Capture an immutable snapshot, keeping the last `history_ticks`
+    /// price ticks per token.
+    pub fn snapshot(&self, history_ticks: usize) -> MarketSnapshot {
+        let mut pairs: Vec<MarketPair> = self.iter_pairs().collect();
+        pairs.sort_by(|a, b| a.market_id.cmp(&b.market_id));
+
+        let history = if history_ticks == 0 {
+            HashMap::new()
+        } else {
+            self.recent_histories(history_ticks).into_iter().collect()
+        };
+
+        // One pass, so each token's price and book come from the same write
+        let mut prices = HashMap::new();
+        let mut books = HashMap::new();
+        for (token_id, price, book) in self.iter_quotes() {
+            if let Some(book) = book {
+                books.insert(token_id.clone(), book);
+            }
+            prices.insert(token_id, price);
+        }
+
+        MarketSnapshot {
+            timestamp_ns: self.last_update_ns(),
+            pairs,
+            prices,
+            books,
+            history,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market::DepthLevel;
+
+    fn pair(id: &str) -> MarketPair {
+        MarketPair {
+            market_id: id.into(),
+            yes_token: format!("{}-yes", id),
+            no_token: format!("{}-no", id),
+            question: "Q?".into(),
+            category: None,
+            end_date: None,
+        }
+    }
+
+    #[test]
+    fn test_snapshot_is_detached_and_ordered() {
+        let data = MarketData::new();
+        data.register_pair(pair("m2"));
+        data.register_pair(pair("m1"));
+        for i in 0..5 {
+            data.update_price(&"m1-yes".into(), 0.40 + i as f64 * 0.01, 0.45);
+        }
+        data.update_order_book(
+            &"m1-yes".into(),
+            vec![DepthLevel::new(0.44, 10.0)],
+            vec![DepthLevel::new(0.45, 20.0)],
+        );
+
+        let snapshot = data.snapshot(3);
+        let ids: Vec<&str> = snapshot
+            .pairs()
+            .iter()
+            .map(|p| p.market_id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["m1", "m2"]);
+        assert_eq!(snapshot.get_history(&"m1-yes".into()).unwrap().len(), 3);
+        assert!(snapshot.get_pair(&"m2".into()).is_some());
+
+        // Later updates do not leak into an existing snapshot
+        data.update_price(&"m1-yes".into(), 0.50, 0.55);
+        assert_eq!(snapshot.get_ask(&"m1-yes".into()), Some(0.45));
+        assert_eq!(
+            snapshot
+                .get_order_book(&"m1-yes".into())
+                .unwrap()
+                .best_ask(),
+            Some(0.45)
+        );
+    }
+
+    #[test]
+    fn test_snapshots_share_unchanged_books_and_history() {
+        let data = MarketData::new();
+        for token in ["a", "b"] {
+            data.update_order_book(
+                &token.into(),
+                vec![DepthLevel::new(0.44, 10.0)],
+                vec![DepthLevel::new(0.45, 20.0)],
+            );
+        }
+
+        let first = data.snapshot(8);
+        let second = data.snapshot(8);
+        for token in ["a", "b"] {
+            assert!(Arc::ptr_eq(&first.books[token], &second.books[token]));
+            assert!(Arc::ptr_eq(&first.history[token], &second.history[token]));
+        }
+
+        // Only the updated token is copied; the earlier snapshot keeps its view
+        data.update_price(&"a".into(), 0.44, 0.45);
+        let third = data.snapshot(8);
+        assert!(!Arc::ptr_eq(&second.books["a"], &third.books["a"]));
+        assert!(!Arc::ptr_eq(&second.history["a"], &third.history["a"]));
+        assert!(Arc::ptr_eq(&second.books["b"], &third.books["b"]));
+        assert!(Arc::ptr_eq(&second.history["b"], &third.history["b"]));
+        assert!(
+            second.get_order_book(&"a".into()).unwrap().timestamp_ns
+                < third.get_order_book(&"a".into()).unwrap().timestamp_ns
+        );
+        assert_eq!(third.get_history(&"a".into()).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_builder_keeps_pairs_sorted() {
+        let snapshot = MarketSnapshot::new(1)
+            .with_pair(pair("b"))
+            .with_pair(pair("a"))
+            .with_price("a-yes".into(), PriceLevel::new(0.4, 0.5));
+        assert_eq!(snapshot.pairs()[0].market_id, "a");
+        assert_eq!(snapshot.get_bid(&"a-yes".into()), Some(0.4));
+        assert!(snapshot.has_data());
+    }
+}
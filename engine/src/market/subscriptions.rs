@@ -0,0 +1,134 @@
+//! Persisted market subscriptions.
+//!
+//! The registered market pairs and subscribed tokens are saved to a state
+//! file so a restarted engine can resubscribe as soon as the WebSocket
+//! connects, instead of waiting for discovery to repopulate them.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use super::data::{MarketData, MarketPair, TokenId};
+
+/// On-disk subscription state
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SubscriptionState {
+    pairs: Vec<MarketPair>,
+    /// Subscribed tokens that are not part of a saved pair
+    tokens: Vec<TokenId>,
+}
+
+impl MarketData {
+    /// Write the registered pairs and subscribed tokens to `path`
+    /// (atomically via a temp file). Returns the number of tokens saved.
+    ///
+    /// Nothing is written while no tokens are known, so a run that stops
+    /// before discovery does not wipe the previous run's state.
+    pub fn persist_subscriptions(&self, path: &Path) -> Result<usize> {
+        let all_tokens = self.subscription_tokens();
+        if all_tokens.is_empty() {
+            return Ok(0);
+        }
+
+        let mut pairs: Vec<MarketPair> = self.iter_pairs().collect();
+        pairs.sort_by(|a, b| a.market_id.cmp(&b.market_id));
+        let paired: HashSet<&TokenId> = pairs
+            .iter()
+            .flat_map(|p| [&p.yes_token, &p.no_token])
+            .collect();
+
+        let tokens: Vec<TokenId> = all_tokens
+            .iter()
+            .filter(|t| !paired.contains(t))
+            .cloned()
+            .collect();
+
+        let json = serde_json::to_vec_pretty(&SubscriptionState { pairs, tokens })?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, json)
+            .with_context(|| format!("Failed to write subscription state to {}", tmp.display()))?;
+        std::fs::rename(&tmp, path).with_context(|| {
+            format!("Failed to move subscription state into {}", path.display())
+        })?;
+        Ok(all_tokens.len())
+    }
+
+    /// Register pairs and track tokens saved with `persist_subscriptions`.
+    ///
+    /// Pairs owned by another shard are skipped. A missing file is not an
+    /// error (first start). Returns the number of tokens to subscribe to.
+    pub fn restore_subscriptions(&self, path: &Path) -> Result<usize> {
+        let data = match std::fs::read(path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => {
+                return Err(e).with_context(|| {
+                    format!("Failed to read subscription state {}", path.display())
+                })
+            }
+        };
+        let state: SubscriptionState = serde_json::from_slice(&data)
+            .with_context(|| format!("Invalid subscription state in {}", path.display()))?;
+
+        let saved_pairs = state.pairs.len();
+        let registered = state
+            .pairs
+            .into_iter()
+            .filter(|pair| self.register_pair(pair.clone()))
+            .count();
+        for token in state.tokens {
+            self.track_token(token);
+        }
+
+        let tokens = self.subscription_tokens().len();
+        info!(
+            "[MARKET] Restored {}/{} markets and {} tokens from {}",
+            registered,
+            saved_pairs,
+            tokens,
+            path.display()
+        );
+        Ok(tokens)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subscriptions_round_trip() {
+        let path = std::env::temp_dir().join(format!(
+            "poly_subscriptions_{}.json",
+            uuid::Uuid::new_v4().simple()
+        ));
+
+        let data = MarketData::new();
+        data.register_pair(MarketPair {
+            market_id: "m1".into(),
+            yes_token: "yes1".into(),
+            no_token: "no1".into(),
+            question: "Q?".into(),
+            category: Some("sports".into()),
+            end_date: None,
+        });
+        data.update_price(&"loose".into(), 0.4, 0.5);
+        assert_eq!(data.persist_subscriptions(&path).unwrap(), 3);
+
+        let restored = MarketData::new();
+        assert_eq!(restored.restore_subscriptions(&path).unwrap(), 3);
+        assert_eq!(
+            restored.subscription_tokens(),
+            vec!["loose".to_string(), "no1".to_string(), "yes1".to_string()]
+        );
+        assert_eq!(restored.get_market_id(&"no1".into()), Some("m1".into()));
+        // Restored tokens are subscribed to but carry no stale prices
+        assert!(!restored.has_data());
+
+        std::fs::remove_file(&path).ok();
+        assert_eq!(restored.restore_subscriptions(&path).unwrap(), 0);
+    }
+}
@@ -0,0 +1,368 @@
+//! Order book validation against exchange snapshots.
+//!
+//! Local books are built purely from WebSocket updates, so a dropped or
+//! misapplied message corrupts them silently. Every interval a random sample
+//! of tokens is fetched from the CLOB REST API and the top levels are
+//! compared with the in-memory book. A book that diverges by more than the
+//! tolerance is replaced with the REST snapshot and its token is
+//! resubscribed so the feed sends a fresh `book` message.
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use parking_lot::Mutex;
+use rand::seq::SliceRandom;
+use reqwest::Client;
+use serde::Deserialize;
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::metrics::{BOOK_CHECKS, BOOK_DIVERGENCE};
+
+use super::data::{DepthLevel, MarketData, TokenId};
+
+/// Prices closer than this are the same level
+const PRICE_EPSILON: f64 = 1e-6;
+/// Sizes within this relative difference are the same level
+const SIZE_TOLERANCE: f64 = 0.01;
+
+/// Book validation settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct BookValidatorConfig {
+    pub enabled: bool,
+
+    /// Time between sample checks
+    pub interval: Duration,
+
+    /// Tokens checked per interval
+    pub sample_size: usize,
+
+    /// Levels compared per side
+    pub depth: usize,
+
+    /// Fraction of compared levels (0-1) that may differ before a resync
+    pub tolerance: f64,
+}
+
+impl Default for BookValidatorConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval: Duration::from_secs(60),
+            sample_size: 5,
+            depth: 5,
+            tolerance: 0.5,
+        }
+    }
+}
+
+impl BookValidatorConfig {
+    /// Load from `BOOK_CHECK_ENABLED`, `BOOK_CHECK_INTERVAL_MS`,
+    /// `BOOK_CHECK_SAMPLE`, `BOOK_CHECK_DEPTH` and `BOOK_CHECK_TOLERANCE`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok();
+        Self {
+            enabled: var("BOOK_CHECK_ENABLED")
+                .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+                .unwrap_or(defaults.enabled),
+            interval: var("BOOK_CHECK_INTERVAL_MS")
+                .and_then(|v| v.parse().ok())
+                .filter(|ms| *ms > 0)
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.interval),
+            sample_size: var("BOOK_CHECK_SAMPLE")
+                .and_then(|v| v.parse().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.sample_size),
+            depth: var("BOOK_CHECK_DEPTH")
+                .and_then(|v| v.parse().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.depth),
+            tolerance: var("BOOK_CHECK_TOLERANCE")
+                .and_then(|v| v.parse::<f64>().ok())
+                .filter(|t| (0.0..=1.0).contains(t))
+                .unwrap_or(defaults.tolerance),
+        }
+    }
+}
+
+/// Tokens whose books should be re-sent by the WebSocket feed.
+#[derive(Debug, Default)]
+pub struct ResyncRequests {
+    tokens: Mutex<BTreeSet<TokenId>>,
+    notify: Notify,
+}
+
+impl ResyncRequests {
+    /// Queue a token for resubscription and wake the WebSocket handler.
+    pub fn request(&self, token_id: TokenId) {
+        self.tokens.lock().insert(token_id);
+        self.notify.notify_one();
+    }
+
+    /// Wait until a resync is requested.
+    pub async fn notified(&self) {
+        self.notify.notified().await;
+    }
+
+    /// Take all queued tokens (sorted).
+    pub fn take(&self) -> Vec<TokenId> {
+        std::mem::take(&mut *self.tokens.lock())
+            .into_iter()
+            .collect()
+    }
+}
+
+/// CLOB REST `/book` response
+#[derive(Debug, Deserialize)]
+struct RestBook {
+    #[serde(default)]
+    bids: Vec<RestLevel>,
+    #[serde(default)]
+    asks: Vec<RestLevel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RestLevel {
+    price: String,
+    size: String,
+}
+
+impl RestLevel {
+    fn parse(&self) -> Option<DepthLevel> {
+        let price: f64 = self.price.parse().ok()?;
+        let size: f64 = self.size.parse().ok()?;
+        let valid = price.is_finite() && (0.0..=1.0).contains(&price) && size.is_finite();
+        (valid && size > 0.0).then(|| DepthLevel::new(price, size))
+    }
+}
+
+/// Samples local books and compares them with exchange snapshots.
+pub struct BookValidator {
+    config: BookValidatorConfig,
+    client: Client,
+    base_url: String,
+    market_data: Arc<MarketData>,
+    resync: Arc<ResyncRequests>,
+}
+
+impl BookValidator {
+    /// Create a validator that fetches snapshots from `clob_url`.
+    pub fn new(
+        config: BookValidatorConfig,
+        clob_url: &str,
+        market_data: Arc<MarketData>,
+        resync: Arc<ResyncRequests>,
+    ) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .context("Failed to create book validation HTTP client")?;
+
+        Ok(Self {
+            config,
+            client,
+            base_url: clob_url.trim_end_matches('/').to_string(),
+            market_data,
+            resync,
+        })
+    }
+
+    /// Check a sample every interval until cancelled.
+    pub async fn run(self, cancel: CancellationToken) {
+        info!(
+            "[MARKET] Validating {} books every {}s (top {} levels, tolerance {:.0}%)",
+            self.config.sample_size,
+            self.config.interval.as_secs(),
+            self.config.depth,
+            self.config.tolerance * 100.0
+        );
+
+        let mut ticker = tokio::time::interval(self.config.interval);
+        ticker.tick().await;
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => self.check_sample().await,
+                _ = cancel.cancelled() => break,
+            }
+        }
+    }
+
+    /// Validate a random sample of the books we hold.
+    async fn check_sample(&self) {
+        let sample: Vec<TokenId> = {
+            let tokens = self.market_data.order_book_tokens();
+            tokens
+                .choose_multiple(&mut rand::thread_rng(), self.config.sample_size)
+                .cloned()
+                .collect()
+        };
+
+        let mut worst: f64 = 0.0;
+        for token_id in sample {
+            match self.check_token(&token_id).await {
+                Ok(divergence) => worst = worst.max(divergence),
+                Err(e) => {
+                    BOOK_CHECKS.with_label_values(&["error"]).inc();
+                    debug!("[MARKET] Book check for {} failed: {}", token_id, e);
+                }
+            }
+        }
+        BOOK_DIVERGENCE.set(worst);
+    }
+
+    /// Compare one book with the exchange and resync it if it diverged.
+    /// Returns the divergence.
+    async fn check_token(&self, token_id: &TokenId) -> Result<f64> {
+        let (bids, asks) = self.fetch_book(token_id).await?;
+        let Some(local) = self.market_data.get_order_book(token_id) else {
+            return Ok(0.0);
+        };
+
+        let divergence = book_divergence(
+            (&local.bids, &local.asks),
+            (&bids, &asks),
+            self.config.depth,
+        );
+        if divergence <= self.config.tolerance {
+            BOOK_CHECKS.with_label_values(&["ok"]).inc();
+            return Ok(divergence);
+        }
+
+        BOOK_CHECKS.with_label_values(&["diverged"]).inc();
+        warn!(
+            "[MARKET] Book for {} diverged {:.0}% from the exchange - resyncing",
+            token_id,
+            divergence * 100.0
+        );
+        let bids = sorted_levels(&bids, usize::MAX, true);
+        let asks = sorted_levels(&asks, usize::MAX, false);
+        let best_bid = bids.first().map(|l| l.price).unwrap_or(0.0);
+        let best_ask = asks.first().map(|l| l.price).unwrap_or(1.0);
+        self.market_data.update_order_book(token_id, bids, asks);
+        self.market_data.update_price(token_id, best_bid, best_ask);
+        self.resync.request(token_id.clone());
+        Ok(divergence)
+    }
+
+    /// Fetch the exchange's book for a token.
+    async fn fetch_book(&self, token_id: &TokenId) -> Result<(Vec<DepthLevel>, Vec<DepthLevel>)> {
+        let book: RestBook = self
+            .client
+            .get(format!("{}/book", self.base_url))
+            .query(&[("token_id", token_id)])
+            .send()
+            .await
+            .context("Failed to fetch book snapshot")?
+            .error_for_status()
+            .context("CLOB returned an error status")?
+            .json()
+            .await
+            .context("Failed to parse book snapshot")?;
+
+        let parse = |levels: &[RestLevel]| levels.iter().filter_map(RestLevel::parse).collect();
+        Ok((parse(&book.bids), parse(&book.asks)))
+    }
+}
+
+/// The best `depth` levels, best first (bids descending, asks ascending).
+fn sorted_levels(levels: &[DepthLevel], depth: usize, bids: bool) -> Vec<DepthLevel> {
+    let mut sorted = levels.to_vec();
+    sorted.sort_by(|a, b| {
+        if bids {
+            b.price.total_cmp(&a.price)
+        } else {
+            a.price.total_cmp(&b.price)
+        }
+    });
+    sorted.truncate(depth);
+    sorted
+}
+
+/// Fraction (0-1) of the top `depth` levels per side that differ in price
+/// or size between two books given as (bids, asks). A level present in only
+/// one book counts as different; books are compared best level first
+/// regardless of the order they are stored in.
+fn book_divergence(
+    local: (&[DepthLevel], &[DepthLevel]),
+    remote: (&[DepthLevel], &[DepthLevel]),
+    depth: usize,
+) -> f64 {
+    let mut compared = 0usize;
+    let mut mismatched = 0usize;
+
+    for (local, remote, bids) in [(local.0, remote.0, true), (local.1, remote.1, false)] {
+        let local = sorted_levels(local, depth, bids);
+        let remote = sorted_levels(remote, depth, bids);
+        for i in 0..local.len().max(remote.len()) {
+            compared += 1;
+            let same = match (local.get(i), remote.get(i)) {
+                (Some(a), Some(b)) => {
+                    (a.price - b.price).abs() < PRICE_EPSILON
+                        && (a.size - b.size).abs() <= SIZE_TOLERANCE * a.size.max(b.size)
+                }
+                _ => false,
+            };
+            if !same {
+                mismatched += 1;
+            }
+        }
+    }
+
+    if compared == 0 {
+        0.0
+    } else {
+        mismatched as f64 / compared as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn levels(levels: &[(f64, f64)]) -> Vec<DepthLevel> {
+        levels
+            .iter()
+            .map(|(p, s)| DepthLevel::new(*p, *s))
+            .collect()
+    }
+
+    #[test]
+    fn test_book_divergence() {
+        let bids = levels(&[(0.50, 100.0), (0.49, 200.0)]);
+        let asks = levels(&[(0.52, 100.0), (0.53, 50.0)]);
+        assert_eq!(book_divergence((&bids, &asks), (&bids, &asks), 5), 0.0);
+
+        // REST snapshots list levels worst first; order does not matter
+        let mut reversed = bids.clone();
+        reversed.reverse();
+        assert_eq!(book_divergence((&bids, &asks), (&reversed, &asks), 5), 0.0);
+
+        // One of four levels has a different size
+        let changed = levels(&[(0.52, 100.0), (0.53, 80.0)]);
+        assert_eq!(book_divergence((&bids, &asks), (&bids, &changed), 5), 0.25);
+
+        // A missing level counts against the book
+        let thin = levels(&[(0.50, 100.0)]);
+        assert_eq!(book_divergence((&thin, &asks), (&bids, &asks), 5), 0.25);
+
+        // Only the top `depth` levels are compared
+        assert_eq!(book_divergence((&thin, &asks), (&bids, &asks), 1), 0.0);
+
+        assert_eq!(book_divergence((&[], &[]), (&[], &[]), 5), 0.0);
+    }
+
+    #[test]
+    fn test_resync_requests_are_deduplicated() {
+        let resync = ResyncRequests::default();
+        resync.request("b".into());
+        resync.request("a".into());
+        resync.request("b".into());
+        assert_eq!(resync.take(), vec!["a".to_string(), "b".to_string()]);
+        assert!(resync.take().is_empty());
+    }
+}
@@ -0,0 +1,507 @@
+//! Large holder and open interest movements.
+//!
+//! A whale entering a market often precedes a repricing, and a repricing
+//! against a position we hold can leave no exit at our price. Every
+//! `WHALE_INTERVAL_MS` the tracked markets (those we hold, plus
+//! `WHALE_MARKETS`) are polled for their largest holders and open interest.
+//! Holder changes worth at least `WHALE_MIN_CHANGE_USD` at the current mid,
+//! and open interest changes of at least `WHALE_OI_CHANGE_PCT`, are logged,
+//! counted, and broadcast as events; strategies given the monitor can read
+//! recent moves per market to hold off or exit.
+//!
+//! Only the top `WHALE_HOLDERS` of each outcome are listed, so a wallet
+//! entering or leaving that list may have held just under its smallest
+//! entry. Such moves are measured from that smallest entry (or not at all
+//! if it is beyond the wallet's holding), which understates rather than
+//! invents them. The first poll of a market only
+//! sets its baseline.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::external::{PolymarketDataClient, TokenHolders};
+use crate::metrics::WHALE_EVENTS;
+use crate::risk::RiskManager;
+
+use super::data::{MarketData, MarketId, TokenId};
+
+/// Moves kept per market for strategies to look back on
+const RECENT_MOVES: usize = 20;
+
+/// Whale monitor settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct WhaleConfig {
+    pub enabled: bool,
+    /// Time between polls
+    pub interval: Duration,
+    /// Markets tracked besides the ones we hold
+    pub markets: Vec<MarketId>,
+    /// Most markets polled per interval
+    pub max_markets: usize,
+    /// Holders listed per outcome
+    pub holders: usize,
+    /// Smallest holder change (USD at the current mid) reported
+    pub min_change_usd: f64,
+    /// Smallest relative open interest change reported
+    pub oi_change_pct: f64,
+}
+
+impl Default for WhaleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval: Duration::from_secs(300),
+            markets: Vec::new(),
+            max_markets: 25,
+            holders: 20,
+            min_change_usd: 5000.0,
+            oi_change_pct: 0.1,
+        }
+    }
+}
+
+impl WhaleConfig {
+    /// Load from `WHALE_MONITOR_ENABLED`, `WHALE_INTERVAL_MS`,
+    /// `WHALE_MARKETS` (comma-separated condition IDs),
+    /// `WHALE_MAX_MARKETS`, `WHALE_HOLDERS`, `WHALE_MIN_CHANGE_USD` and
+    /// `WHALE_OI_CHANGE_PCT`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok();
+        Self {
+            enabled: var("WHALE_MONITOR_ENABLED")
+                .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+                .unwrap_or(defaults.enabled),
+            interval: var("WHALE_INTERVAL_MS")
+                .and_then(|v| v.parse().ok())
+                .filter(|ms| *ms > 0)
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.interval),
+            markets: var("WHALE_MARKETS")
+                .map(|v| {
+                    v.split(',')
+                        .map(str::trim)
+                        .filter(|m| !m.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or(defaults.markets),
+            max_markets: var("WHALE_MAX_MARKETS")
+                .and_then(|v| v.parse().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.max_markets),
+            holders: var("WHALE_HOLDERS")
+                .and_then(|v| v.parse().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.holders),
+            min_change_usd: var("WHALE_MIN_CHANGE_USD")
+                .and_then(|v| v.parse().ok())
+                .filter(|usd: &f64| usd.is_finite() && *usd > 0.0)
+                .unwrap_or(defaults.min_change_usd),
+            oi_change_pct: var("WHALE_OI_CHANGE_PCT")
+                .and_then(|v| v.parse().ok())
+                .filter(|pct: &f64| pct.is_finite() && *pct > 0.0)
+                .unwrap_or(defaults.oi_change_pct),
+        }
+    }
+}
+
+/// What a large holder did
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WhaleMoveKind {
+    /// Appeared among the largest holders
+    Entered,
+    Increased,
+    Reduced,
+    /// Left the largest holders
+    Exited,
+}
+
+impl WhaleMoveKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WhaleMoveKind::Entered => "entered",
+            WhaleMoveKind::Increased => "increased",
+            WhaleMoveKind::Reduced => "reduced",
+            WhaleMoveKind::Exited => "exited",
+        }
+    }
+}
+
+/// A large position change by one wallet
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WhaleMove {
+    pub market_id: MarketId,
+    pub token_id: TokenId,
+    pub wallet: String,
+    pub name: Option<String>,
+    pub kind: WhaleMoveKind,
+    /// Shares held before and after
+    pub before: f64,
+    pub after: f64,
+    /// Size of the change at the current mid
+    pub change_usd: f64,
+}
+
+/// A movement in a tracked market
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WhaleEvent {
+    Position(WhaleMove),
+    OpenInterest {
+        market_id: MarketId,
+        before: f64,
+        after: f64,
+    },
+}
+
+impl WhaleEvent {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            WhaleEvent::Position(m) => m.kind.as_str(),
+            WhaleEvent::OpenInterest { .. } => "open_interest",
+        }
+    }
+}
+
+/// Shares per (token, wallet) among the largest holders of a market
+type Holdings = HashMap<(TokenId, String), f64>;
+
+/// Last poll of one market
+struct MarketState {
+    holdings: Holdings,
+    /// Smallest listed holding per token, for lists that were full
+    floors: HashMap<TokenId, f64>,
+    open_interest: Option<f64>,
+}
+
+/// Polls tracked markets for large holder and open interest changes.
+pub struct WhaleMonitor {
+    config: WhaleConfig,
+    data: Arc<PolymarketDataClient>,
+    market_data: Arc<MarketData>,
+    risk_manager: Arc<RiskManager>,
+    states: Mutex<HashMap<MarketId, MarketState>>,
+    recent: Mutex<HashMap<MarketId, VecDeque<(Instant, WhaleMove)>>>,
+    events: broadcast::Sender<WhaleEvent>,
+}
+
+impl WhaleMonitor {
+    pub fn new(
+        config: WhaleConfig,
+        data: Arc<PolymarketDataClient>,
+        market_data: Arc<MarketData>,
+        risk_manager: Arc<RiskManager>,
+    ) -> Self {
+        Self {
+            config,
+            data,
+            market_data,
+            risk_manager,
+            states: Mutex::new(HashMap::new()),
+            recent: Mutex::new(HashMap::new()),
+            events: broadcast::channel(256).0,
+        }
+    }
+
+    /// Receive events from now on.
+    #[allow(dead_code)]
+    pub fn subscribe(&self) -> broadcast::Receiver<WhaleEvent> {
+        self.events.subscribe()
+    }
+
+    /// Holder moves in a market within `window`, oldest first.
+    #[allow(dead_code)]
+    pub fn recent_moves(&self, market_id: &MarketId, window: Duration) -> Vec<WhaleMove> {
+        self.recent
+            .lock()
+            .get(market_id)
+            .map_or_else(Vec::new, |moves| {
+                moves
+                    .iter()
+                    .filter(|(at, _)| at.elapsed() <= window)
+                    .map(|(_, m)| m.clone())
+                    .collect()
+            })
+    }
+
+    /// Poll every interval until cancelled.
+    pub async fn run(self: Arc<Self>, cancel: CancellationToken) {
+        info!(
+            "[WHALE] Watching holders every {:?} (moves from ${:.0}, open interest from {:.0}%)",
+            self.config.interval,
+            self.config.min_change_usd,
+            self.config.oi_change_pct * 100.0
+        );
+        let mut ticker = tokio::time::interval(self.config.interval);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => self.poll().await,
+                _ = cancel.cancelled() => break,
+            }
+        }
+    }
+
+    /// Held markets first, then the configured ones
+    fn tracked(&self) -> Vec<MarketId> {
+        let mut seen = HashSet::new();
+        let held = self
+            .risk_manager
+            .get_all_positions()
+            .into_iter()
+            .filter(|(_, p)| p.size > 0.0)
+            .filter_map(|(token, _)| self.market_data.get_market_id(&token));
+        held.chain(self.config.markets.iter().cloned())
+            .filter(|market_id| seen.insert(market_id.clone()))
+            .take(self.config.max_markets)
+            .collect()
+    }
+
+    async fn poll(&self) {
+        let tracked = self.tracked();
+        for market_id in &tracked {
+            let holders = match self.data.holders(market_id, self.config.holders).await {
+                Ok(holders) => holders,
+                Err(e) => {
+                    warn!("[WHALE] Holder lookup for {} failed: {:#}", market_id, e);
+                    continue;
+                }
+            };
+            // Open interest is optional; holders alone are still compared
+            let open_interest = self.data.open_interest(market_id).await.ok();
+            for event in self.observe(market_id, &holders, open_interest) {
+                self.emit(event);
+            }
+        }
+        let tracked: HashSet<&MarketId> = tracked.iter().collect();
+        self.states.lock().retain(|id, _| tracked.contains(id));
+        self.recent.lock().retain(|id, _| tracked.contains(id));
+    }
+
+    /// Compare a poll of a market with the last one.
+    fn observe(
+        &self,
+        market_id: &MarketId,
+        holders: &[TokenHolders],
+        open_interest: Option<f64>,
+    ) -> Vec<WhaleEvent> {
+        let mut holdings = Holdings::new();
+        let mut floors = HashMap::new();
+        for token in holders {
+            for holder in &token.holders {
+                *holdings
+                    .entry((token.token.clone(), holder.proxy_wallet.clone()))
+                    .or_default() += holder.amount;
+            }
+            if token.holders.len() >= self.config.holders {
+                let floor = token
+                    .holders
+                    .iter()
+                    .map(|h| h.amount)
+                    .fold(f64::INFINITY, f64::min);
+                floors.insert(token.token.clone(), floor);
+            }
+        }
+        let names: HashMap<&str, &str> = holders
+            .iter()
+            .flat_map(|t| &t.holders)
+            .filter_map(|h| Some((h.proxy_wallet.as_str(), h.name.as_deref()?)))
+            .collect();
+
+        let current = MarketState {
+            holdings,
+            floors,
+            open_interest,
+        };
+        let mut states = self.states.lock();
+        let events = match states.get(market_id) {
+            Some(previous) => self.compare(market_id, previous, &current, &names),
+            None => Vec::new(),
+        };
+        states.insert(market_id.clone(), current);
+        events
+    }
+
+    /// Events between two polls of a market.
+    fn compare(
+        &self,
+        market_id: &MarketId,
+        previous: &MarketState,
+        current: &MarketState,
+        names: &HashMap<&str, &str>,
+    ) -> Vec<WhaleEvent> {
+        let mut events = Vec::new();
+        let keys: HashSet<&(TokenId, String)> = previous
+            .holdings
+            .keys()
+            .chain(current.holdings.keys())
+            .collect();
+        for key in keys {
+            let (token_id, wallet) = key;
+            let (before, after, kind) =
+                match (previous.holdings.get(key), current.holdings.get(key)) {
+                    (Some(&before), Some(&after)) if after > before => {
+                        (before, after, WhaleMoveKind::Increased)
+                    }
+                    (Some(&before), Some(&after)) => (before, after, WhaleMoveKind::Reduced),
+                    (None, Some(&after)) => (
+                        previous.floors.get(token_id).map_or(0.0, |f| f.min(after)),
+                        after,
+                        WhaleMoveKind::Entered,
+                    ),
+                    (Some(&before), None) => (
+                        before,
+                        current.floors.get(token_id).map_or(0.0, |f| f.min(before)),
+                        WhaleMoveKind::Exited,
+                    ),
+                    (None, None) => continue,
+                };
+            let Some(price) = self.market_data.get_price(token_id) else {
+                continue;
+            };
+            let change_usd = (after - before).abs() * price.mid;
+            if change_usd < self.config.min_change_usd {
+                continue;
+            }
+            events.push(WhaleEvent::Position(WhaleMove {
+                market_id: market_id.clone(),
+                token_id: token_id.clone(),
+                wallet: wallet.clone(),
+                name: names.get(wallet.as_str()).map(|n| n.to_string()),
+                kind,
+                before,
+                after,
+                change_usd,
+            }));
+        }
+
+        if let (Some(before), Some(after)) = (previous.open_interest, current.open_interest) {
+            if before > 0.0 && ((after - before) / before).abs() >= self.config.oi_change_pct {
+                events.push(WhaleEvent::OpenInterest {
+                    market_id: market_id.clone(),
+                    before,
+                    after,
+                });
+            }
+        }
+        events
+    }
+
+    fn emit(&self, event: WhaleEvent) {
+        WHALE_EVENTS.with_label_values(&[event.kind()]).inc();
+        match &event {
+            WhaleEvent::Position(m) => {
+                info!(
+                    "[WHALE] {} {} {} in {} ({:.0} -> {:.0} shares, ${:.0})",
+                    m.name.as_deref().unwrap_or(&m.wallet),
+                    m.kind.as_str(),
+                    m.token_id,
+                    m.market_id,
+                    m.before,
+                    m.after,
+                    m.change_usd
+                );
+                let mut recent = self.recent.lock();
+                let moves = recent.entry(m.market_id.clone()).or_default();
+                if moves.len() == RECENT_MOVES {
+                    moves.pop_front();
+                }
+                moves.push_back((Instant::now(), m.clone()));
+            }
+            WhaleEvent::OpenInterest {
+                market_id,
+                before,
+                after,
+            } => info!(
+                "[WHALE] Open interest in {} moved ${:.0} -> ${:.0}",
+                market_id, before, after
+            ),
+        }
+        // No receivers is fine
+        let _ = self.events.send(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RiskConfig;
+    use crate::external::{Holder, PolymarketDataConfig};
+    use crate::market::MarketPair;
+
+    fn holders(token: &str, amounts: &[(&str, f64)]) -> TokenHolders {
+        TokenHolders {
+            token: token.into(),
+            holders: amounts
+                .iter()
+                .map(|(wallet, amount)| Holder {
+                    proxy_wallet: wallet.to_string(),
+                    amount: *amount,
+                    name: None,
+                    outcome_index: 0,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_reports_large_holder_and_open_interest_moves() {
+        let market_data = Arc::new(MarketData::new());
+        market_data.register_pair(MarketPair {
+            market_id: "m".into(),
+            yes_token: "yes".into(),
+            no_token: "no".into(),
+            question: "Q?".into(),
+            category: None,
+            end_date: None,
+        });
+        market_data.update_price(&"yes".to_string(), 0.49, 0.51);
+        let data =
+            PolymarketDataClient::new(PolymarketDataConfig::default(), "http://localhost").unwrap();
+        let monitor = WhaleMonitor::new(
+            WhaleConfig {
+                holders: 3,
+                min_change_usd: 1000.0,
+                ..WhaleConfig::default()
+            },
+            Arc::new(data),
+            market_data,
+            Arc::new(RiskManager::new(RiskConfig::default())),
+        );
+        let market = "m".to_string();
+
+        let first = [holders(
+            "yes",
+            &[("a", 10_000.0), ("b", 5_000.0), ("c", 4_000.0)],
+        )];
+        assert!(monitor.observe(&market, &first, Some(50_000.0)).is_empty());
+
+        // a sells 1k shares ($500, too small); d enters above c, who drops out
+        let second = [holders(
+            "yes",
+            &[("a", 9_000.0), ("b", 5_000.0), ("d", 12_000.0)],
+        )];
+        let events = monitor.observe(&market, &second, Some(60_000.0));
+        let moves: Vec<(&str, WhaleMoveKind, f64)> = events
+            .iter()
+            .filter_map(|e| match e {
+                WhaleEvent::Position(m) => Some((m.wallet.as_str(), m.kind, m.before)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(moves, vec![("d", WhaleMoveKind::Entered, 4_000.0)]);
+        assert!(events.contains(&WhaleEvent::OpenInterest {
+            market_id: market.clone(),
+            before: 50_000.0,
+            after: 60_000.0,
+        }));
+    }
+}
@@ -1,19 +1,65 @@
 //! Prometheus metrics for trading engine observability.
 
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use anyhow::{Context, Result};
 use lazy_static::lazy_static;
+use parking_lot::Mutex;
+use prometheus::proto::MetricType;
 use prometheus::{
-    opts, register_counter, register_counter_vec, register_gauge, register_histogram_vec, Counter,
-    CounterVec, Gauge, HistogramVec,
+    opts, register_counter, register_counter_vec, register_gauge, register_gauge_vec,
+    register_histogram_vec, Counter, CounterVec, Gauge, GaugeVec, HistogramVec, Opts,
 };
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+/// A registered counter whose value can be restored after a restart
+#[derive(Clone)]
+enum RestorableCounter {
+    Single(Counter),
+    Labeled(CounterVec),
+}
+
+lazy_static! {
+    /// Every engine counter by name, filled in as `init()` creates them
+    static ref COUNTERS: Mutex<HashMap<String, RestorableCounter>> = Mutex::new(HashMap::new());
+}
+
+/// Register a counter and make it restorable across restarts.
+fn counter(opts: Opts) -> prometheus::Result<Counter> {
+    let name = opts.fq_name();
+    let counter = register_counter!(opts)?;
+    COUNTERS
+        .lock()
+        .insert(name, RestorableCounter::Single(counter.clone()));
+    Ok(counter)
+}
+
+/// Register a labeled counter and make it restorable across restarts.
+fn counter_vec(opts: Opts, labels: &[&str]) -> prometheus::Result<CounterVec> {
+    let name = opts.fq_name();
+    let counter = register_counter_vec!(opts, labels)?;
+    COUNTERS
+        .lock()
+        .insert(name, RestorableCounter::Labeled(counter.clone()));
+    Ok(counter)
+}
 
 lazy_static! {
     // Order metrics
-    pub static ref ORDERS_TOTAL: CounterVec = register_counter_vec!(
+    pub static ref ORDERS_TOTAL: CounterVec = counter_vec(
         opts!("poly_orders_total", "Total orders placed"),
         &["side", "status", "strategy"]
     )
     .expect("Failed to create ORDERS_TOTAL metric");
 
+    pub static ref EXCHANGE_REJECTIONS: CounterVec = counter_vec(
+        opts!("poly_exchange_rejections_total", "Requests the CLOB rejected, by error code (see execution::error)"),
+        &["code"]
+    )
+    .expect("Failed to create EXCHANGE_REJECTIONS metric");
+
     pub static ref ORDER_LATENCY: HistogramVec = register_histogram_vec!(
         "poly_order_latency_seconds",
         "Order placement latency",
@@ -22,35 +68,512 @@ lazy_static! {
     )
     .expect("Failed to create ORDER_LATENCY metric");
 
-    // Strategy metrics
-    pub static ref SIGNALS_TOTAL: CounterVec = register_counter_vec!(
+    // Round trips to the exchange (see latency)
+    pub static ref EXCHANGE_RTT: GaugeVec = register_gauge_vec!(
+        opts!("poly_exchange_rtt_seconds", "Round-trip time to the exchange by path (rest, ws) and quantile over the probe window"),
+        &["path", "quantile"]
+    )
+    .expect("Failed to create EXCHANGE_RTT metric");
+
+    pub static ref LATENCY_PROBE_ERRORS: Counter = counter(
+        opts!("poly_latency_probe_errors_total", "CLOB REST latency probes that got no response")
+    )
+    .expect("Failed to create LATENCY_PROBE_ERRORS metric");
+
+    // Order queue in front of the CLOB client (see execution::order_queue)
+    pub static ref ORDER_QUEUE_WAIT: HistogramVec = register_histogram_vec!(
+        "poly_order_queue_wait_seconds",
+        "Time live requests waited in the order queue by priority class",
+        &["priority"],
+        vec![0.0, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5]
+    )
+    .expect("Failed to create ORDER_QUEUE_WAIT metric");
+
+    pub static ref ORDER_QUEUE_DEPTH: Gauge = register_gauge!(
+        "poly_order_queue_depth",
+        "Live requests waiting in the order queue"
+    )
+    .expect("Failed to create ORDER_QUEUE_DEPTH metric");
+
+    // Dry-run orders resting on the mock exchange (see execution::mock_exchange)
+    pub static ref MOCK_OPEN_ORDERS: Gauge = register_gauge!(
+        "poly_mock_open_orders",
+        "Orders resting on the in-process mock exchange"
+    )
+    .expect("Failed to create MOCK_OPEN_ORDERS metric");
+
+    // Sliced execution of large signals (see execution::twap)
+    pub static ref TWAP_ORDERS: CounterVec = counter_vec(
+        opts!("poly_twap_orders_total", "Sliced parent orders by how they ended"),
+        &["outcome"]
+    )
+    .expect("Failed to create TWAP_ORDERS metric");
+
+    // Strategy metrics; `mode` is the execution mode (live, mock, paper,
+    // dry_run), or observe for signals that are only recorded
+    pub static ref SIGNALS_TOTAL: CounterVec = counter_vec(
         opts!("poly_signals_total", "Total signals generated"),
-        &["strategy", "type"]
+        &["strategy", "type", "mode"]
     )
     .expect("Failed to create SIGNALS_TOTAL metric");
 
-    pub static ref EVALUATIONS_TOTAL: Counter = register_counter!(
+    pub static ref SIGNAL_EDGE: HistogramVec = register_histogram_vec!(
+        "poly_signal_edge",
+        "Edge per share of signals that carry one (trades behind a bucket: /debug/trades)",
+        &["strategy", "mode"],
+        vec![0.005, 0.01, 0.02, 0.03, 0.05, 0.075, 0.1, 0.15, 0.2, 0.3]
+    )
+    .expect("Failed to create SIGNAL_EDGE metric");
+
+    pub static ref FAST_PATH_DELAY: HistogramVec = register_histogram_vec!(
+        "poly_fast_path_delay_seconds",
+        "Time fast-path signals wait before the engine handles them",
+        &["strategy"],
+        vec![0.0001, 0.00025, 0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1]
+    )
+    .expect("Failed to create FAST_PATH_DELAY metric");
+
+    pub static ref EVALUATIONS_TOTAL: Counter = counter(
         opts!("poly_evaluations_total", "Total strategy evaluations")
     )
     .expect("Failed to create EVALUATIONS_TOTAL metric");
 
+    // Per-strategy execution statistics (see strategy::stats)
+    pub static ref STRATEGY_EVALUATIONS: CounterVec = counter_vec(
+        opts!("poly_strategy_evaluations_total", "Evaluations per strategy"),
+        &["strategy"]
+    )
+    .expect("Failed to create STRATEGY_EVALUATIONS metric");
+
+    pub static ref STRATEGY_EVAL_DURATION: HistogramVec = register_histogram_vec!(
+        "poly_strategy_eval_duration_seconds",
+        "Time spent in one evaluation per strategy",
+        &["strategy"],
+        vec![0.00001, 0.000025, 0.00005, 0.0001, 0.00025, 0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025]
+    )
+    .expect("Failed to create STRATEGY_EVAL_DURATION metric");
+
+    pub static ref STRATEGY_SIGNAL_OUTCOMES: CounterVec = counter_vec(
+        opts!("poly_strategy_signal_outcomes_total", "Signals per strategy executed or rejected (size or risk)"),
+        &["strategy", "outcome"]
+    )
+    .expect("Failed to create STRATEGY_SIGNAL_OUTCOMES metric");
+
     // Risk metrics
-    pub static ref RISK_REJECTIONS: CounterVec = register_counter_vec!(
+    pub static ref RISK_REJECTIONS: CounterVec = counter_vec(
         opts!("poly_risk_rejections_total", "Signals rejected by risk manager"),
         &["reason"]
     )
     .expect("Failed to create RISK_REJECTIONS metric");
 
+    pub static ref STRATEGY_DAILY_PNL: GaugeVec = register_gauge_vec!(
+        opts!("poly_strategy_daily_pnl_usd", "Realized P&L today per strategy (named instances apart)"),
+        &["strategy"]
+    )
+    .expect("Failed to create STRATEGY_DAILY_PNL metric");
+
+    // Rolling tail risk of the P&L history (see risk::var)
+    pub static ref VALUE_AT_RISK: GaugeVec = register_gauge_vec!(
+        opts!("poly_value_at_risk_dollars", "Historical value at risk of P&L over the horizon in dollars"),
+        &["horizon"]
+    )
+    .expect("Failed to create VALUE_AT_RISK metric");
+
+    pub static ref EXPECTED_SHORTFALL: GaugeVec = register_gauge_vec!(
+        opts!("poly_expected_shortfall_dollars", "Average loss beyond value at risk over the horizon in dollars"),
+        &["horizon"]
+    )
+    .expect("Failed to create EXPECTED_SHORTFALL metric");
+
+    // Fee reconciliation against exchange statements (see db::fees)
+    pub static ref FEE_STATEMENT_ORDERS: CounterVec = counter_vec(
+        opts!("poly_fee_statement_orders_total", "Statement orders imported, by whether a recorded trade matched"),
+        &["outcome"]
+    )
+    .expect("Failed to create FEE_STATEMENT_ORDERS metric");
+
+    // ClickHouse analytics sink (see db::clickhouse)
+    pub static ref ANALYTICS_ROWS: CounterVec = counter_vec(
+        opts!("poly_analytics_rows_total", "Analytics rows written, dropped on a full queue, or lost to failed inserts"),
+        &["table", "outcome"]
+    )
+    .expect("Failed to create ANALYTICS_ROWS metric");
+
     // System metrics
-    pub static ref WEBSOCKET_MESSAGES: Counter = register_counter!(
+    pub static ref WEBSOCKET_MESSAGES: Counter = counter(
         opts!("poly_websocket_messages_total", "WebSocket messages received")
     )
     .expect("Failed to create WEBSOCKET_MESSAGES metric");
 
+    pub static ref HTTP_UNAUTHORIZED: CounterVec = counter_vec(
+        opts!("poly_http_unauthorized_total", "Rejected unauthenticated HTTP/TLS requests"),
+        &["endpoint"]
+    )
+    .expect("Failed to create HTTP_UNAUTHORIZED metric");
+
     pub static ref DAILY_PNL: Gauge = register_gauge!(
         opts!("poly_daily_pnl_dollars", "Current daily P&L in dollars")
     )
     .expect("Failed to create DAILY_PNL metric");
+
+    pub static ref EQUITY: Gauge = register_gauge!(
+        opts!("poly_equity_dollars", "Cumulative P&L since startup (realized + unrealized) in dollars")
+    )
+    .expect("Failed to create EQUITY metric");
+
+    pub static ref DRAWDOWN: Gauge = register_gauge!(
+        opts!("poly_drawdown_dollars", "Current drawdown from the equity peak in dollars")
+    )
+    .expect("Failed to create DRAWDOWN metric");
+
+    pub static ref MAX_DRAWDOWN: Gauge = register_gauge!(
+        opts!("poly_max_drawdown_dollars", "Largest drawdown from the equity peak since startup in dollars")
+    )
+    .expect("Failed to create MAX_DRAWDOWN metric");
+
+    pub static ref IS_LEADER: Gauge = register_gauge!(
+        opts!("poly_is_leader", "1 when this instance holds the leader lease and may place orders")
+    )
+    .expect("Failed to create IS_LEADER metric");
+
+    pub static ref CLUSTER_DAILY_PNL: Gauge = register_gauge!(
+        opts!("poly_cluster_daily_pnl_dollars", "Daily P&L summed across all shards in dollars")
+    )
+    .expect("Failed to create CLUSTER_DAILY_PNL metric");
+
+    // External data metrics
+    pub static ref ESPN_FETCHES: CounterVec = counter_vec(
+        opts!("poly_espn_fetches_total", "ESPN scoreboard fetches by result (ok, not_modified, error)"),
+        &["league", "result"]
+    )
+    .expect("Failed to create ESPN_FETCHES metric");
+
+    pub static ref ESPN_FETCH_LATENCY: HistogramVec = register_histogram_vec!(
+        "poly_espn_fetch_latency_seconds",
+        "ESPN scoreboard fetch latency",
+        &["league"],
+        vec![0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0]
+    )
+    .expect("Failed to create ESPN_FETCH_LATENCY metric");
+
+    // Order book validation metrics
+    pub static ref BOOK_CHECKS: CounterVec = counter_vec(
+        opts!("poly_book_checks_total", "Order book checks against exchange snapshots by result (ok, diverged, error)"),
+        &["result"]
+    )
+    .expect("Failed to create BOOK_CHECKS metric");
+
+    pub static ref PRICE_BOOK_DIVERGENCE: Counter = counter(
+        opts!("poly_price_book_divergence_total", "Price changes that contradicted the stored book (book dropped and resynced)")
+    )
+    .expect("Failed to create PRICE_BOOK_DIVERGENCE metric");
+
+    pub static ref BOOK_DIVERGENCE: Gauge = register_gauge!(
+        opts!("poly_book_divergence_ratio", "Largest fraction of top levels differing from the exchange in the last sample")
+    )
+    .expect("Failed to create BOOK_DIVERGENCE metric");
+
+    // Market data memory metrics
+    pub static ref MARKET_DATA_ENTRIES: GaugeVec = register_gauge_vec!(
+        opts!("poly_market_data_entries", "Entries held in market data (tokens, books, depth_levels, history_entries, pairs)"),
+        &["kind"]
+    )
+    .expect("Failed to create MARKET_DATA_ENTRIES metric");
+
+    pub static ref MARKET_DATA_EVICTIONS: Counter = counter(
+        opts!("poly_market_data_evictions_total", "Tokens whose price, book and history were evicted as idle")
+    )
+    .expect("Failed to create MARKET_DATA_EVICTIONS metric");
+
+    // Markets per liquidity tier (see market::liquidity)
+    pub static ref MARKET_LIQUIDITY_TIERS: GaugeVec = register_gauge_vec!(
+        opts!("poly_market_liquidity_tiers", "Scored markets per liquidity tier"),
+        &["tier"]
+    )
+    .expect("Failed to create MARKET_LIQUIDITY_TIERS metric");
+
+    // Correlated exposure groups (see market::correlation)
+    pub static ref CORRELATION_GROUPS: Gauge = register_gauge!(
+        opts!("poly_correlation_groups", "Groups of markets whose returns are correlated")
+    )
+    .expect("Failed to create CORRELATION_GROUPS metric");
+
+    pub static ref CORRELATED_MARKETS: Gauge = register_gauge!(
+        opts!("poly_correlated_markets", "Markets in a correlation group")
+    )
+    .expect("Failed to create CORRELATED_MARKETS metric");
+
+    // WebSocket subscription selection (see market::prioritizer)
+    pub static ref WS_SUBSCRIPTIONS: GaugeVec = register_gauge_vec!(
+        opts!("poly_ws_subscriptions", "Candidate tokens per subscription set (core, rotating, idle)"),
+        &["set"]
+    )
+    .expect("Failed to create WS_SUBSCRIPTIONS metric");
+
+    // WebSocket transport tuning (see ws::transport)
+    pub static ref WS_CONNECT_DURATION: HistogramVec = register_histogram_vec!(
+        "poly_ws_connect_seconds",
+        "WebSocket connect time by stage (tcp, handshake) and tuning profile",
+        &["stage", "profile"],
+        vec![0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0]
+    )
+    .expect("Failed to create WS_CONNECT_DURATION metric");
+
+    pub static ref WS_BOOK_DELAY: HistogramVec = register_histogram_vec!(
+        "poly_ws_book_delay_seconds",
+        "Exchange timestamp to receipt of book updates, by tuning profile",
+        &["profile"],
+        vec![0.005, 0.01, 0.025, 0.05, 0.075, 0.1, 0.15, 0.25, 0.5, 1.0, 2.5]
+    )
+    .expect("Failed to create WS_BOOK_DELAY metric");
+
+    // Market data endpoint failover (see ws::failover)
+    pub static ref WS_ACTIVE_ENDPOINT: GaugeVec = register_gauge_vec!(
+        opts!("poly_ws_active_endpoint", "1 for the WebSocket endpoint in use, 0 for the others"),
+        &["endpoint", "host"]
+    )
+    .expect("Failed to create WS_ACTIVE_ENDPOINT metric");
+
+    pub static ref WS_FAILOVERS: CounterVec = counter_vec(
+        opts!("poly_ws_failovers_total", "WebSocket endpoint switches by reason (errors, stale, primary_recovered)"),
+        &["reason"]
+    )
+    .expect("Failed to create WS_FAILOVERS metric");
+
+    // Shared reconnect backoff and network incidents (see connectivity)
+    pub static ref RECONNECTS: CounterVec = counter_vec(
+        opts!("poly_reconnects_total", "Reconnect attempts by subsystem (ws, redis, db)"),
+        &["subsystem"]
+    )
+    .expect("Failed to create RECONNECTS metric");
+
+    pub static ref NETWORK_DEGRADED: Gauge = register_gauge!(
+        "poly_network_degraded",
+        "1 while a network incident is open and trading is paused"
+    )
+    .expect("Failed to create NETWORK_DEGRADED metric");
+
+    // Local market data relay (see ws::relay)
+    pub static ref RELAY_CLIENTS: Gauge = register_gauge!(
+        "poly_relay_clients",
+        "Clients connected to the market data relay"
+    )
+    .expect("Failed to create RELAY_CLIENTS metric");
+
+    pub static ref RELAY_SKIPPED: Counter = counter(opts!(
+        "poly_relay_skipped_total",
+        "Updates skipped by relay clients that fell behind"
+    ))
+    .expect("Failed to create RELAY_SKIPPED metric");
+
+    // Operator price alerts (see market::alerts)
+    pub static ref PRICE_ALERTS_FIRED: CounterVec = counter_vec(
+        opts!("poly_price_alerts_total", "Price alert rules fired"),
+        &["rule"]
+    )
+    .expect("Failed to create PRICE_ALERTS_FIRED metric");
+
+    // Gamma metadata refresh (see market::metadata)
+    pub static ref MARKET_EVENTS: CounterVec = counter_vec(
+        opts!("poly_market_events_total", "Markets listed, updated, closed, or delisted by metadata refresh"),
+        &["event"]
+    )
+    .expect("Failed to create MARKET_EVENTS metric");
+
+    pub static ref MARKET_METADATA_AGE: Gauge = register_gauge!(
+        "poly_market_metadata_age_seconds",
+        "Time since market metadata was last fully refreshed"
+    )
+    .expect("Failed to create MARKET_METADATA_AGE metric");
+
+    pub static ref MARKET_METADATA_ERRORS: Counter = counter(
+        opts!("poly_market_metadata_errors_total", "Failed Gamma metadata requests")
+    )
+    .expect("Failed to create MARKET_METADATA_ERRORS metric");
+
+    // Sum-to-100 opportunities that fell just short of min_edge
+    pub static ref NEAR_MISSES: Counter = counter(
+        opts!("poly_near_misses_total", "Sum-to-100 opportunities within the near-miss tolerance below min_edge")
+    )
+    .expect("Failed to create NEAR_MISSES metric");
+
+    // Automatic hedging of one-sided positions
+    pub static ref HEDGES: CounterVec = counter_vec(
+        opts!("poly_hedges_total", "Hedge attempts on one-sided positions by result"),
+        &["result"]
+    )
+    .expect("Failed to create HEDGES metric");
+
+    // Scaling out of positions before resolution (see risk::exit)
+    pub static ref RESOLUTION_EXITS: CounterVec = counter_vec(
+        opts!("poly_resolution_exits_total", "Pre-resolution exit attempts by result"),
+        &["result"]
+    )
+    .expect("Failed to create RESOLUTION_EXITS metric");
+
+    // Blocked opportunities held for retry (see strategy::retry)
+    pub static ref OPPORTUNITY_RETRIES: CounterVec = counter_vec(
+        opts!("poly_opportunity_retries_total", "Blocked opportunities held, retried or given up by result"),
+        &["result"]
+    )
+    .expect("Failed to create OPPORTUNITY_RETRIES metric");
+
+    // Hot-path log lines dropped by their budget (see log_budget)
+    pub static ref LOG_SUPPRESSED: CounterVec = counter_vec(
+        opts!("poly_log_suppressed_total", "Log lines dropped by the per-category log budget"),
+        &["category"]
+    )
+    .expect("Failed to create LOG_SUPPRESSED metric");
+
+}
+
+// A second block: one more metric in the first exceeds lazy_static's
+// macro recursion limit
+lazy_static! {
+    // Tokio runtime and monitored task groups (see runtime)
+    pub static ref RUNTIME_WORKERS: Gauge = register_gauge!(
+        "poly_runtime_workers",
+        "Tokio worker threads"
+    )
+    .expect("Failed to create RUNTIME_WORKERS metric");
+
+    pub static ref RUNTIME_ALIVE_TASKS: Gauge = register_gauge!(
+        "poly_runtime_alive_tasks",
+        "Tasks alive on the Tokio runtime"
+    )
+    .expect("Failed to create RUNTIME_ALIVE_TASKS metric");
+
+    pub static ref RUNTIME_GLOBAL_QUEUE_DEPTH: Gauge = register_gauge!(
+        "poly_runtime_global_queue_depth",
+        "Tasks waiting in the runtime's global queue"
+    )
+    .expect("Failed to create RUNTIME_GLOBAL_QUEUE_DEPTH metric");
+
+    pub static ref RUNTIME_BUSY_RATIO: Gauge = register_gauge!(
+        "poly_runtime_busy_ratio",
+        "Share of worker time spent running tasks over the last sample (0-1)"
+    )
+    .expect("Failed to create RUNTIME_BUSY_RATIO metric");
+
+    pub static ref TASK_MEAN_POLL: GaugeVec = register_gauge_vec!(
+        "poly_task_mean_poll_seconds",
+        "Mean time per poll over the last sample, by task group",
+        &["group"]
+    )
+    .expect("Failed to create TASK_MEAN_POLL metric");
+
+    pub static ref TASK_MEAN_SCHEDULED_DELAY: GaugeVec = register_gauge_vec!(
+        "poly_task_mean_scheduled_delay_seconds",
+        "Mean time from wake-up to poll over the last sample, by task group",
+        &["group"]
+    )
+    .expect("Failed to create TASK_MEAN_SCHEDULED_DELAY metric");
+
+    pub static ref TASK_SLOW_POLLS: CounterVec = counter_vec(
+        opts!("poly_task_slow_polls_total", "Polls over the slow-poll threshold, by task group"),
+        &["group"]
+    )
+    .expect("Failed to create TASK_SLOW_POLLS metric");
+
+    pub static ref TASK_LONG_DELAYS: CounterVec = counter_vec(
+        opts!("poly_task_long_delays_total", "Wake-ups that waited over the long-delay threshold for a worker, by task group"),
+        &["group"]
+    )
+    .expect("Failed to create TASK_LONG_DELAYS metric");
+
+    pub static ref TASKS_ALIVE: GaugeVec = register_gauge_vec!(
+        "poly_tasks_alive",
+        "Monitored tasks spawned and not yet finished, by task group",
+        &["group"]
+    )
+    .expect("Failed to create TASKS_ALIVE metric");
+
+    // Account funds
+    pub static ref FUNDS_BALANCE: Gauge = register_gauge!(
+        "poly_funds_balance_usd",
+        "Collateral in the wallet (estimated from paper P&L in dry run)"
+    )
+    .expect("Failed to create FUNDS_BALANCE metric");
+
+    pub static ref FUNDS_COMMITTED: GaugeVec = register_gauge_vec!(
+        "poly_funds_committed_usd",
+        "Notional committed to open positions (cost basis) and open buy orders",
+        &["kind"]
+    )
+    .expect("Failed to create FUNDS_COMMITTED metric");
+
+    pub static ref FUNDS_FREE: Gauge = register_gauge!(
+        "poly_funds_free_usd",
+        "Balance not reserved by open buy orders"
+    )
+    .expect("Failed to create FUNDS_FREE metric");
+
+    pub static ref FUNDS_UTILIZATION: Gauge = register_gauge!(
+        "poly_funds_utilization_ratio",
+        "Committed notional as a share of balance plus position cost (0-1)"
+    )
+    .expect("Failed to create FUNDS_UTILIZATION metric");
+
+    // Strategy governor
+    pub static ref STRATEGY_KILLS: CounterVec = counter_vec(
+        opts!("poly_strategy_kills_total", "Strategy kill rules tripped, by action taken"),
+        &["strategy", "action"]
+    )
+    .expect("Failed to create STRATEGY_KILLS metric");
+
+    // Polymarket data API (see external::polymarket_data)
+    pub static ref POLYMARKET_DATA_REQUESTS: CounterVec = counter_vec(
+        opts!("poly_data_api_requests_total", "Polymarket data API requests, by endpoint and result"),
+        &["endpoint", "result"]
+    )
+    .expect("Failed to create POLYMARKET_DATA_REQUESTS metric");
+
+    // Whale monitor (see market::whales)
+    pub static ref WHALE_EVENTS: CounterVec = counter_vec(
+        opts!("poly_whale_events_total", "Large holder and open interest moves in tracked markets"),
+        &["kind"]
+    )
+    .expect("Failed to create WHALE_EVENTS metric");
+
+    // Task restarts (see restarts)
+    pub static ref TASK_RESTARTS: CounterVec = counter_vec(
+        opts!("poly_task_restarts_total", "Internal task restarts, by component and how it ended"),
+        &["component", "exit"]
+    )
+    .expect("Failed to create TASK_RESTARTS metric");
+
+    pub static ref CRASH_LOOPS: Gauge = register_gauge!(
+        "poly_crash_looping_components",
+        "Components given up on after restarting too often"
+    )
+    .expect("Failed to create CRASH_LOOPS metric");
+
+    // Volatility gate (see strategy::volatility)
+    pub static ref VOLATILITY_COOLDOWNS: Counter = counter(opts!(
+        "poly_volatility_cooldowns_total",
+        "Market cooldowns started by fast mid moves"
+    ))
+    .expect("Failed to create VOLATILITY_COOLDOWNS metric");
+
+    pub static ref VOLATILITY_SUPPRESSED: CounterVec = counter_vec(
+        opts!("poly_volatility_suppressed_signals_total", "Signals suppressed during a market's volatility cooldown"),
+        &["strategy"]
+    )
+    .expect("Failed to create VOLATILITY_SUPPRESSED metric");
+
+    // Order lifecycle (see execution::order_tracker)
+    pub static ref ORDER_STATES: CounterVec = counter_vec(
+        opts!("poly_order_states_total", "Tracked orders entering each lifecycle state"),
+        &["state"]
+    )
+    .expect("Failed to create ORDER_STATES metric");
+
+    // Fault injection (only incremented in builds with the chaos feature)
+    pub static ref CHAOS_FAULTS: CounterVec = counter_vec(
+        opts!("poly_chaos_faults_total", "Faults injected for resilience testing"),
+        &["kind"]
+    )
+    .expect("Failed to create CHAOS_FAULTS metric");
 }
 
 /// Initialize all metrics (forces lazy_static initialization).
@@ -58,10 +581,327 @@ lazy_static! {
 pub fn init() {
     // Access each metric to force initialization
     lazy_static::initialize(&ORDERS_TOTAL);
+    lazy_static::initialize(&EXCHANGE_REJECTIONS);
     lazy_static::initialize(&ORDER_LATENCY);
+    lazy_static::initialize(&EXCHANGE_RTT);
+    lazy_static::initialize(&LATENCY_PROBE_ERRORS);
+    lazy_static::initialize(&ORDER_QUEUE_WAIT);
+    lazy_static::initialize(&ORDER_QUEUE_DEPTH);
+    lazy_static::initialize(&MOCK_OPEN_ORDERS);
+    lazy_static::initialize(&TWAP_ORDERS);
     lazy_static::initialize(&SIGNALS_TOTAL);
+    lazy_static::initialize(&SIGNAL_EDGE);
+    lazy_static::initialize(&FAST_PATH_DELAY);
     lazy_static::initialize(&EVALUATIONS_TOTAL);
+    lazy_static::initialize(&STRATEGY_EVALUATIONS);
+    lazy_static::initialize(&STRATEGY_EVAL_DURATION);
+    lazy_static::initialize(&STRATEGY_SIGNAL_OUTCOMES);
     lazy_static::initialize(&RISK_REJECTIONS);
+    lazy_static::initialize(&STRATEGY_DAILY_PNL);
+    lazy_static::initialize(&VALUE_AT_RISK);
+    lazy_static::initialize(&EXPECTED_SHORTFALL);
+    lazy_static::initialize(&FEE_STATEMENT_ORDERS);
+    lazy_static::initialize(&ANALYTICS_ROWS);
     lazy_static::initialize(&WEBSOCKET_MESSAGES);
+    lazy_static::initialize(&HTTP_UNAUTHORIZED);
     lazy_static::initialize(&DAILY_PNL);
+    lazy_static::initialize(&EQUITY);
+    lazy_static::initialize(&DRAWDOWN);
+    lazy_static::initialize(&MAX_DRAWDOWN);
+    lazy_static::initialize(&IS_LEADER);
+    lazy_static::initialize(&CLUSTER_DAILY_PNL);
+    lazy_static::initialize(&ESPN_FETCHES);
+    lazy_static::initialize(&ESPN_FETCH_LATENCY);
+    lazy_static::initialize(&BOOK_CHECKS);
+    lazy_static::initialize(&BOOK_DIVERGENCE);
+    lazy_static::initialize(&PRICE_BOOK_DIVERGENCE);
+    lazy_static::initialize(&MARKET_DATA_ENTRIES);
+    lazy_static::initialize(&MARKET_DATA_EVICTIONS);
+    lazy_static::initialize(&MARKET_LIQUIDITY_TIERS);
+    lazy_static::initialize(&CORRELATION_GROUPS);
+    lazy_static::initialize(&CORRELATED_MARKETS);
+    lazy_static::initialize(&WS_SUBSCRIPTIONS);
+    lazy_static::initialize(&WS_CONNECT_DURATION);
+    lazy_static::initialize(&WS_BOOK_DELAY);
+    lazy_static::initialize(&WS_ACTIVE_ENDPOINT);
+    lazy_static::initialize(&WS_FAILOVERS);
+    lazy_static::initialize(&RECONNECTS);
+    lazy_static::initialize(&NETWORK_DEGRADED);
+    lazy_static::initialize(&RELAY_CLIENTS);
+    lazy_static::initialize(&RELAY_SKIPPED);
+    lazy_static::initialize(&PRICE_ALERTS_FIRED);
+    lazy_static::initialize(&MARKET_EVENTS);
+    lazy_static::initialize(&MARKET_METADATA_AGE);
+    lazy_static::initialize(&MARKET_METADATA_ERRORS);
+    lazy_static::initialize(&NEAR_MISSES);
+    lazy_static::initialize(&HEDGES);
+    lazy_static::initialize(&RESOLUTION_EXITS);
+    lazy_static::initialize(&OPPORTUNITY_RETRIES);
+    lazy_static::initialize(&LOG_SUPPRESSED);
+    lazy_static::initialize(&RUNTIME_WORKERS);
+    lazy_static::initialize(&RUNTIME_ALIVE_TASKS);
+    lazy_static::initialize(&RUNTIME_GLOBAL_QUEUE_DEPTH);
+    lazy_static::initialize(&RUNTIME_BUSY_RATIO);
+    lazy_static::initialize(&TASK_MEAN_POLL);
+    lazy_static::initialize(&TASK_MEAN_SCHEDULED_DELAY);
+    lazy_static::initialize(&TASK_SLOW_POLLS);
+    lazy_static::initialize(&TASK_LONG_DELAYS);
+    lazy_static::initialize(&TASKS_ALIVE);
+    lazy_static::initialize(&FUNDS_BALANCE);
+    lazy_static::initialize(&FUNDS_COMMITTED);
+    lazy_static::initialize(&FUNDS_FREE);
+    lazy_static::initialize(&FUNDS_UTILIZATION);
+    lazy_static::initialize(&STRATEGY_KILLS);
+    lazy_static::initialize(&POLYMARKET_DATA_REQUESTS);
+    lazy_static::initialize(&WHALE_EVENTS);
+    lazy_static::initialize(&TASK_RESTARTS);
+    lazy_static::initialize(&CRASH_LOOPS);
+    lazy_static::initialize(&VOLATILITY_COOLDOWNS);
+    lazy_static::initialize(&VOLATILITY_SUPPRESSED);
+    lazy_static::initialize(&ORDER_STATES);
+    lazy_static::initialize(&CHAOS_FAULTS);
+}
+
+/// A single counter series saved across restarts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PersistedCounter {
+    pub name: String,
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    pub value: f64,
+}
+
+/// Collect the current value of every engine counter series.
+pub fn snapshot_counters() -> Vec<PersistedCounter> {
+    let mut counters = Vec::new();
+    for family in prometheus::gather() {
+        if family.get_field_type() != MetricType::COUNTER || !family.get_name().starts_with("poly_")
+        {
+            continue;
+        }
+        for metric in family.get_metric() {
+            let labels = metric
+                .get_label()
+                .iter()
+                .map(|l| (l.get_name().to_string(), l.get_value().to_string()))
+                .collect();
+            counters.push(PersistedCounter {
+                name: family.get_name().to_string(),
+                labels,
+                value: metric.get_counter().get_value(),
+            });
+        }
+    }
+    counters
+}
+
+/// Add persisted counter values back as a base offset.
+///
+/// Must run right after `init()` and before anything increments the
+/// counters, otherwise the restored values are added on top of live ones.
+/// Returns the number of series restored.
+pub fn restore_counters(counters: &[PersistedCounter]) -> usize {
+    let mut restored = 0;
+    for counter in counters {
+        if counter.value <= 0.0 || !counter.value.is_finite() {
+            continue;
+        }
+        let labels: HashMap<&str, &str> = counter
+            .labels
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        let registered = COUNTERS.lock().get(&counter.name).cloned();
+        let vec = match registered {
+            Some(RestorableCounter::Labeled(vec)) => vec,
+            Some(RestorableCounter::Single(single)) => {
+                single.inc_by(counter.value);
+                restored += 1;
+                continue;
+            }
+            None => {
+                warn!("[METRICS] Skipping unknown persisted counter {}", counter.name);
+                continue;
+            }
+        };
+        match vec.get_metric_with(&labels) {
+            Ok(c) => {
+                c.inc_by(counter.value);
+                restored += 1;
+            }
+            Err(e) => warn!(
+                "[METRICS] Skipping persisted {} with mismatched labels: {}",
+                counter.name, e
+            ),
+        }
+    }
+    restored
+}
+
+/// Write the current counter values to `path` (atomically via a temp file).
+pub fn persist_to_file(path: &Path) -> Result<()> {
+    let json = serde_json::to_vec_pretty(&snapshot_counters())?;
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, json)
+        .with_context(|| format!("Failed to write metrics state to {}", tmp.display()))?;
+    std::fs::rename(&tmp, path)
+        .with_context(|| format!("Failed to move metrics state into {}", path.display()))?;
+    Ok(())
+}
+
+/// Restore counter values previously saved with `persist_to_file`.
+///
+/// A missing file is not an error (first start).
+pub fn restore_from_file(path: &Path) -> Result<usize> {
+    let data = match std::fs::read(path) {
+        Ok(data) => data,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => {
+            return Err(e)
+                .with_context(|| format!("Failed to read metrics state {}", path.display()))
+        }
+    };
+    let counters: Vec<PersistedCounter> = serde_json::from_slice(&data)
+        .with_context(|| format!("Invalid metrics state in {}", path.display()))?;
+    let restored = restore_counters(&counters);
+    info!(
+        "[METRICS] Restored {} counter series from {}",
+        restored,
+        path.display()
+    );
+    Ok(restored)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prometheus::core::Collector;
+
+    fn risk_value(reason: &str) -> f64 {
+        RISK_REJECTIONS.with_label_values(&[reason]).get()
+    }
+
+    #[test]
+    fn test_snapshot_and_restore_counters() {
+        init();
+        RISK_REJECTIONS
+            .with_label_values(&["metrics_persist_test"])
+            .inc_by(3.0);
+
+        let snapshot = snapshot_counters();
+        let saved = snapshot
+            .iter()
+            .find(|c| {
+                c.name == "poly_risk_rejections_total"
+                    && c.labels.get("reason").map(String::as_str) == Some("metrics_persist_test")
+            })
+            .cloned()
+            .unwrap();
+        assert_eq!(saved.value, 3.0);
+
+        // Restoring adds the saved value as an offset
+        assert_eq!(restore_counters(&[saved]), 1);
+        assert_eq!(risk_value("metrics_persist_test"), 6.0);
+    }
+
+    #[test]
+    fn test_every_counter_round_trips() {
+        init();
+        let counters: Vec<(String, RestorableCounter)> = COUNTERS
+            .lock()
+            .iter()
+            .map(|(name, counter)| (name.clone(), counter.clone()))
+            .collect();
+
+        // Every poly_* counter family is restorable
+        for family in prometheus::gather() {
+            if family.get_field_type() == MetricType::COUNTER
+                && family.get_name().starts_with("poly_")
+            {
+                assert!(
+                    counters.iter().any(|(name, _)| name == family.get_name()),
+                    "{} is not restorable",
+                    family.get_name()
+                );
+            }
+        }
+
+        // Give each counter a series of its own (labels set to a test value)
+        const TEST_LABEL: &str = "metrics_round_trip_test";
+        let is_test_series = |c: &PersistedCounter| {
+            c.labels.is_empty() || c.labels.values().all(|v| v == TEST_LABEL)
+        };
+        for (_, counter) in &counters {
+            match counter {
+                RestorableCounter::Single(c) => c.inc(),
+                RestorableCounter::Labeled(vec) => {
+                    let labels = vec.desc()[0].variable_labels.len();
+                    vec.with_label_values(&vec![TEST_LABEL; labels]).inc_by(2.0);
+                }
+            }
+        }
+
+        let saved: Vec<PersistedCounter> = snapshot_counters()
+            .into_iter()
+            .filter(|c| counters.iter().any(|(name, _)| *name == c.name) && is_test_series(c))
+            .collect();
+        assert_eq!(saved.len(), counters.len());
+        assert_eq!(restore_counters(&saved), saved.len());
+
+        let after = snapshot_counters();
+        for before in &saved {
+            let now = after
+                .iter()
+                .find(|c| c.name == before.name && c.labels == before.labels)
+                .unwrap();
+            if before.labels.is_empty() {
+                // Other tests may bump unlabeled counters concurrently
+                assert!(now.value >= 2.0 * before.value, "{}", before.name);
+            } else {
+                assert_eq!(now.value, 2.0 * before.value, "{}", before.name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_restore_skips_unknown_and_mismatched() {
+        init();
+        let counters = vec![
+            PersistedCounter {
+                name: "poly_unknown_total".into(),
+                labels: BTreeMap::new(),
+                value: 1.0,
+            },
+            PersistedCounter {
+                name: "poly_risk_rejections_total".into(),
+                labels: [("bogus".to_string(), "x".to_string())].into(),
+                value: 1.0,
+            },
+        ];
+        assert_eq!(restore_counters(&counters), 0);
+    }
+
+    #[test]
+    fn test_persist_and_restore_file_round_trip() {
+        init();
+        let dir = std::env::temp_dir().join(format!("poly-metrics-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("metrics.json");
+
+        // Missing file is a clean first start
+        assert_eq!(restore_from_file(&path).unwrap(), 0);
+
+        RISK_REJECTIONS
+            .with_label_values(&["metrics_file_test"])
+            .inc();
+        persist_to_file(&path).unwrap();
+
+        let data = std::fs::read(&path).unwrap();
+        let counters: Vec<PersistedCounter> = serde_json::from_slice(&data).unwrap();
+        assert!(counters
+            .iter()
+            .any(|c| c.labels.get("reason").map(String::as_str) == Some("metrics_file_test")));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
 }
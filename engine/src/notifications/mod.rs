@@ -4,6 +4,12 @@
 //! the trading loop is never delayed by notification delivery.
 
 mod slack;
+mod templates;
 
 #[allow(unused_imports)]
-pub use slack::{ErrorAlert, OrderNotification, RiskAlert, SlackNotifier};
+pub use slack::{
+    ApprovalRequest, ErrorAlert, LegNotification, MarketVolumeLine, OrderNotification, PriceAlert,
+    RiskAlert, SlackNotifier, VolumeReport,
+};
+#[allow(unused_imports)]
+pub use templates::NotificationTemplates;
@@ -2,12 +2,17 @@
 //!
 //! All methods are fire-and-forget (non-blocking) - they spawn async tasks
 //! and return immediately to ensure the trading loop is never delayed.
+//! Message text comes from `NotificationTemplates` (see `templates`).
 
 use reqwest::Client;
 use serde::Serialize;
 use std::sync::Arc;
 use tracing::{info, warn};
 
+use crate::runtime::{self, TaskGroup};
+
+use super::templates::NotificationTemplates;
+
 /// Slack message payload
 #[derive(Debug, Serialize)]
 struct SlackMessage {
@@ -20,10 +25,10 @@ struct SlackMessage {
 
 /// Order notification for Slack
 #[allow(dead_code)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct OrderNotification {
     pub strategy: String,
-    pub order_type: String, // "BUY", "SELL", "ARBITRAGE"
+    pub order_type: String, // "BUY", "SELL", "ARBITRAGE", "BASKET"
     pub token_id: Option<String>,
     pub yes_token: Option<String>,
     pub no_token: Option<String>,
@@ -34,12 +39,29 @@ pub struct OrderNotification {
     pub order_id: Option<String>,
     pub status: String, // "FILLED", "FAILED: reason"
     pub pnl: Option<f64>,
+    /// What to do about a failed order (see `ExecutionError::hint`)
+    pub hint: Option<String>,
     pub is_paper: bool,
+    /// Metadata of the signal behind the order (JSON object)
+    pub metadata: Option<serde_json::Value>,
+    /// Each order of a basket (empty for other order types)
+    pub legs: Vec<LegNotification>,
+}
+
+/// One order of a basket notification
+#[derive(Debug, Clone, Serialize)]
+pub struct LegNotification {
+    pub token_id: String,
+    pub side: String, // "BUY", "SELL"
+    pub price: f64,
+    pub size: f64,
+    pub order_id: Option<String>,
+    pub status: String,
 }
 
 /// Risk violation alert for Slack
 #[allow(dead_code)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct RiskAlert {
     pub alert_type: String, // "DAILY_LOSS", "POSITION_LIMIT", "NOTIONAL_LIMIT"
     pub message: String,
@@ -49,13 +71,90 @@ pub struct RiskAlert {
 
 /// Error alert for Slack
 #[allow(dead_code)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ErrorAlert {
     pub source: String,
     pub error_type: String,
     pub message: String,
 }
 
+/// Operator price alert for Slack (see `market::alerts`)
+#[derive(Debug, Clone, Serialize)]
+pub struct PriceAlert {
+    pub rule: String,
+    pub token_id: String,
+    pub market_id: Option<String>,
+    pub question: Option<String>,
+    pub field: String, // "bid", "ask", "mid", "spread"
+    pub op: String,
+    pub threshold: f64,
+    pub value: f64,
+    pub bid: f64,
+    pub ask: f64,
+}
+
+/// Daily month-to-date volume report for Slack (see `execution::volume`)
+#[derive(Debug, Clone, Serialize)]
+pub struct VolumeReport {
+    /// UTC day the report closes (`YYYY-MM-DD`)
+    pub date: String,
+    /// Month the volume covers (`YYYY-MM`)
+    pub month: String,
+    pub maker_volume: f64,
+    pub taker_volume: f64,
+    pub maker_fills: u64,
+    pub taker_fills: u64,
+    /// Largest markets by volume
+    pub markets: Vec<MarketVolumeLine>,
+    /// Total volume of the month before, once one has closed
+    pub previous_month: Option<String>,
+    pub previous_volume: Option<f64>,
+}
+
+/// One market's line in a `VolumeReport`
+#[derive(Debug, Clone, Serialize)]
+pub struct MarketVolumeLine {
+    pub market_id: String,
+    pub maker_volume: f64,
+    pub taker_volume: f64,
+}
+
+/// Signal parked for manual approval (see `strategy::approvals`)
+#[derive(Debug, Clone, Serialize)]
+pub struct ApprovalRequest {
+    /// ID to approve or reject on the control API
+    pub id: u64,
+    pub strategy: String,
+    pub description: String,
+    pub notional: f64,
+    /// Why the signal needs approval
+    pub reason: String,
+    /// Seconds until it expires undecided
+    pub ttl_secs: u64,
+    pub metadata: Option<serde_json::Value>,
+}
+
+/// Template context for an approval request: its fields plus the metadata
+/// as text
+#[derive(Serialize)]
+struct ApprovalContext<'a> {
+    #[serde(flatten)]
+    request: &'a ApprovalRequest,
+    metadata_text: String,
+}
+
+/// Template context for an order: its fields plus derived display values
+#[derive(Serialize)]
+struct OrderContext<'a> {
+    #[serde(flatten)]
+    order: &'a OrderNotification,
+    emoji: &'static str,
+    paper_tag: &'static str,
+    token_short: &'a str,
+    /// Signal metadata as `key=value` pairs (empty without any)
+    metadata_text: String,
+}
+
 /// Async Slack notifier - all methods are fire-and-forget
 #[allow(dead_code)]
 pub struct SlackNotifier {
@@ -65,6 +164,9 @@ pub struct SlackNotifier {
     notify_orders: bool,
     notify_risk: bool,
     notify_errors: bool,
+    notify_alerts: bool,
+    notify_reports: bool,
+    templates: NotificationTemplates,
 }
 
 impl SlackNotifier {
@@ -74,6 +176,9 @@ impl SlackNotifier {
     /// - `SLACK_NOTIFY_ORDERS` (default: true)
     /// - `SLACK_NOTIFY_RISK` (default: true)
     /// - `SLACK_NOTIFY_ERRORS` (default: true)
+    /// - `SLACK_NOTIFY_ALERTS` (price alerts, default: true)
+    /// - `SLACK_NOTIFY_REPORTS` (daily volume report, default: true)
+    /// - `NOTIFY_TEMPLATE_DIR` (message template overrides)
     pub fn from_env() -> Self {
         let webhook_url = std::env::var("SLACK_WEBHOOK_URL").ok();
         let enabled = webhook_url.is_some();
@@ -87,11 +192,17 @@ impl SlackNotifier {
         let notify_errors = std::env::var("SLACK_NOTIFY_ERRORS")
             .map(|v| v.to_lowercase() != "false")
             .unwrap_or(true);
+        let notify_alerts = std::env::var("SLACK_NOTIFY_ALERTS")
+            .map(|v| v.to_lowercase() != "false")
+            .unwrap_or(true);
+        let notify_reports = std::env::var("SLACK_NOTIFY_REPORTS")
+            .map(|v| v.to_lowercase() != "false")
+            .unwrap_or(true);
 
         if enabled {
             info!(
-                "[SLACK] Notifications enabled | orders={} | risk={} | errors={}",
-                notify_orders, notify_risk, notify_errors
+                "[SLACK] Notifications enabled | orders={} | risk={} | errors={} | alerts={} | reports={}",
+                notify_orders, notify_risk, notify_errors, notify_alerts, notify_reports
             );
         } else {
             info!("[SLACK] Notifications disabled (SLACK_WEBHOOK_URL not set)");
@@ -113,6 +224,13 @@ impl SlackNotifier {
             notify_orders,
             notify_risk,
             notify_errors,
+            notify_alerts,
+            notify_reports,
+            templates: if enabled {
+                NotificationTemplates::from_env()
+            } else {
+                NotificationTemplates::builtin()
+            },
         }
     }
 
@@ -126,6 +244,9 @@ impl SlackNotifier {
             notify_orders: false,
             notify_risk: false,
             notify_errors: false,
+            notify_alerts: false,
+            notify_reports: false,
+            templates: NotificationTemplates::builtin(),
         }
     }
 
@@ -140,57 +261,7 @@ impl SlackNotifier {
             return;
         }
 
-        let emoji = match order.status.as_str() {
-            s if s.starts_with("FILLED") => {
-                if order.is_paper {
-                    ":memo:"
-                } else {
-                    ":chart_with_upwards_trend:"
-                }
-            }
-            _ => ":x:",
-        };
-
-        let paper_tag = if order.is_paper { " [PAPER]" } else { "" };
-
-        let text = match order.order_type.as_str() {
-            "ARBITRAGE" => {
-                let pnl_str = order
-                    .pnl
-                    .map(|p| format!(" | PnL: ${:.2}", p))
-                    .unwrap_or_default();
-                format!(
-                    "{} *{}*{} ARB\nYES@${:.4} + NO@${:.4} x {:.0}{}\nStatus: {}",
-                    emoji,
-                    order.strategy,
-                    paper_tag,
-                    order.yes_price.unwrap_or(0.0),
-                    order.no_price.unwrap_or(0.0),
-                    order.size,
-                    pnl_str,
-                    order.status
-                )
-            }
-            _ => {
-                let token_short = order
-                    .token_id
-                    .as_ref()
-                    .map(|t| &t[..8.min(t.len())])
-                    .unwrap_or("???");
-                format!(
-                    "{} *{}*{} {} {} @ ${:.4} x {:.0}\nStatus: {}",
-                    emoji,
-                    order.strategy,
-                    paper_tag,
-                    order.order_type,
-                    token_short,
-                    order.price.unwrap_or(0.0),
-                    order.size,
-                    order.status
-                )
-            }
-        };
-
+        let text = self.format_order(&order);
         self.send_message(text, ":robot_face:");
     }
 
@@ -201,10 +272,7 @@ impl SlackNotifier {
             return;
         }
 
-        let text = format!(
-            ":warning: *RISK ALERT: {}*\n{}\nCurrent: {:.2} | Limit: {:.2}",
-            alert.alert_type, alert.message, alert.current_value, alert.limit_value
-        );
+        let text = self.templates.render("risk", &alert);
 
         self.send_message(text, ":rotating_light:");
     }
@@ -216,14 +284,105 @@ impl SlackNotifier {
             return;
         }
 
-        let text = format!(
-            ":x: *ERROR in {}*\nType: {}\n{}",
-            alert.source, alert.error_type, alert.message
-        );
+        let text = self.templates.render("error", &alert);
 
         self.send_message(text, ":skull:");
     }
 
+    /// Notify that a price alert rule fired (fire-and-forget, non-blocking)
+    pub fn notify_price_alert(&self, alert: PriceAlert) {
+        if !self.enabled || !self.notify_alerts {
+            return;
+        }
+
+        let text = self.templates.render("price", &alert);
+
+        self.send_message(text, ":bell:");
+    }
+
+    /// Send the daily volume report (fire-and-forget, non-blocking)
+    pub fn notify_volume_report(&self, report: VolumeReport) {
+        if !self.enabled || !self.notify_reports {
+            return;
+        }
+
+        let text = self.templates.render("volume", &report);
+
+        self.send_message(text, ":bar_chart:");
+    }
+
+    /// Ask for approval of a parked signal (fire-and-forget, non-blocking).
+    /// Sent whenever Slack is enabled: a parked signal nobody hears about
+    /// can only expire.
+    pub fn notify_approval(&self, request: ApprovalRequest) {
+        if !self.enabled {
+            return;
+        }
+
+        let text = self.format_approval(&request);
+
+        self.send_message(text, ":raised_hand:");
+    }
+
+    /// Render the message text for an approval request.
+    fn format_approval(&self, request: &ApprovalRequest) -> String {
+        let ctx = ApprovalContext {
+            request,
+            metadata_text: metadata_text(request.metadata.as_ref()),
+        };
+        self.templates.render("approval", &ctx)
+    }
+
+    /// Render the message text for an order.
+    fn format_order(&self, order: &OrderNotification) -> String {
+        let emoji = match order.status.as_str() {
+            s if s.starts_with("FILLED") => {
+                if order.is_paper {
+                    ":memo:"
+                } else {
+                    ":chart_with_upwards_trend:"
+                }
+            }
+            // Placed with order tracking on; fills are recorded once confirmed
+            "PLACED" => ":hourglass_flowing_sand:",
+            _ => ":x:",
+        };
+        let token_short = order
+            .token_id
+            .as_ref()
+            .map(|t| &t[..8.min(t.len())])
+            .unwrap_or("???");
+        let ctx = OrderContext {
+            order,
+            emoji,
+            paper_tag: if order.is_paper { " [PAPER]" } else { "" },
+            token_short,
+            metadata_text: metadata_text(order.metadata.as_ref()),
+        };
+        self.templates.render("order", &ctx)
+    }
+
+    /// Send a message and wait for Slack to accept it (for the startup
+    /// self-test; ignores the per-category notification flags).
+    pub async fn send_test_message(&self, text: &str) -> anyhow::Result<()> {
+        let (Some(client), Some(webhook_url)) = (&self.client, &self.webhook_url) else {
+            anyhow::bail!("Slack notifications are not configured");
+        };
+
+        let message = SlackMessage {
+            text: text.to_string(),
+            username: Some("Poly-Rust Bot".to_string()),
+            icon_emoji: Some(":white_check_mark:".to_string()),
+        };
+        client
+            .post(webhook_url)
+            .json(&message)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
     /// Internal: Send a message to Slack (fire-and-forget)
     fn send_message(&self, text: String, icon: &str) {
         let client = match &self.client {
@@ -243,7 +402,7 @@ impl SlackNotifier {
         };
 
         // Fire-and-forget: spawn task and return immediately
-        tokio::spawn(async move {
+        runtime::spawn(TaskGroup::Slack, async move {
             match client.post(&webhook_url).json(&message).send().await {
                 Ok(resp) => {
                     if !resp.status().is_success() {
@@ -258,6 +417,21 @@ impl SlackNotifier {
     }
 }
 
+/// `key=value` pairs of a metadata object, strings unquoted.
+fn metadata_text(metadata: Option<&serde_json::Value>) -> String {
+    let Some(serde_json::Value::Object(entries)) = metadata else {
+        return String::new();
+    };
+    entries
+        .iter()
+        .map(|(key, value)| match value {
+            serde_json::Value::String(s) => format!("{}={}", key, s),
+            other => format!("{}={}", key, other),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 /// Helper to create a notifier from Arc for sharing
 impl SlackNotifier {
     #[allow(dead_code)]
@@ -291,10 +465,126 @@ mod tests {
             order_id: None,
             status: "FILLED".to_string(),
             pnl: Some(5.0),
+            hint: None,
             is_paper: false,
+            metadata: None,
+            legs: Vec::new(),
         };
 
-        // Just verify the struct can be created
         assert_eq!(order.strategy, "SumTo100");
+
+        let notifier = SlackNotifier::disabled();
+        assert_eq!(
+            notifier.format_order(&order),
+            ":chart_with_upwards_trend: *SumTo100* ARB\nYES@$0.4500 + NO@$0.5000 x 100 | PnL: $5.00\nStatus: FILLED"
+        );
+
+        let order = OrderNotification {
+            order_type: "BUY".to_string(),
+            token_id: Some("0123456789abcdef".to_string()),
+            price: Some(0.45),
+            pnl: None,
+            status: "PLACED".to_string(),
+            ..order
+        };
+        assert_eq!(
+            notifier.format_order(&order),
+            ":hourglass_flowing_sand: *SumTo100* BUY 01234567 @ $0.4500 x 100\nStatus: PLACED"
+        );
+
+        let order = OrderNotification {
+            status: "FAILED: rejected".to_string(),
+            is_paper: true,
+            ..order
+        };
+        assert_eq!(
+            notifier.format_order(&order),
+            ":x: *SumTo100* [PAPER] BUY 01234567 @ $0.4500 x 100\nStatus: FAILED: rejected"
+        );
+
+        let order = OrderNotification {
+            metadata: Some(serde_json::json!({"game_id": "401", "confidence": 0.9})),
+            ..order
+        };
+        assert_eq!(
+            notifier.format_order(&order),
+            ":x: *SumTo100* [PAPER] BUY 01234567 @ $0.4500 x 100\nStatus: FAILED: rejected\nContext: confidence=0.9 game_id=401"
+        );
+
+        let order = OrderNotification {
+            status: "FAILED: insufficient_balance".to_string(),
+            hint: Some("top up USDC".to_string()),
+            metadata: None,
+            ..order
+        };
+        assert_eq!(
+            notifier.format_order(&order),
+            ":x: *SumTo100* [PAPER] BUY 01234567 @ $0.4500 x 100\nStatus: FAILED: insufficient_balance\nFix: top up USDC"
+        );
+
+        let leg = |token: &str, status: &str| LegNotification {
+            token_id: token.to_string(),
+            side: "BUY".to_string(),
+            price: 0.3,
+            size: 100.0,
+            order_id: None,
+            status: status.to_string(),
+        };
+        let order = OrderNotification {
+            order_type: "BASKET".to_string(),
+            token_id: None,
+            price: None,
+            status: "PARTIAL: 1/2 legs".to_string(),
+            hint: None,
+            is_paper: false,
+            legs: vec![
+                leg("aaaaaaaaaaaa", "FILLED"),
+                leg("bbbbbbbbbbbb", "FAILED: rate_limited"),
+            ],
+            ..order
+        };
+        assert_eq!(
+            notifier.format_order(&order),
+            ":x: *SumTo100* BASKET x 2\nBUY aaaaaaaa @ $0.3000 x 100: FILLED\nBUY bbbbbbbb @ $0.3000 x 100: FAILED: rate_limited\nStatus: PARTIAL: 1/2 legs"
+        );
+    }
+
+    #[test]
+    fn test_price_alert_format() {
+        let alert = PriceAlert {
+            rule: "token=0123456789:ask<0.10".to_string(),
+            token_id: "0123456789".to_string(),
+            market_id: Some("m1".to_string()),
+            question: Some("Will it rain?".to_string()),
+            field: "ask".to_string(),
+            op: "<".to_string(),
+            threshold: 0.1,
+            value: 0.08,
+            bid: 0.05,
+            ask: 0.08,
+        };
+        let notifier = SlackNotifier::disabled();
+        assert_eq!(
+            notifier.templates.render("price", &alert),
+            ":bell: *PRICE ALERT* `token=0123456789:ask<0.10`\nWill it rain? | 01234567: ask 0.0800 < 0.1000\nBid: 0.0500 | Ask: 0.0800"
+        );
+    }
+
+    #[test]
+    fn test_approval_request_format() {
+        let request = ApprovalRequest {
+            id: 7,
+            strategy: "Sniper".to_string(),
+            description: "BUY 01234567 @ $0.9500 x 800.00 (final)".to_string(),
+            notional: 760.0,
+            reason: "notional $760.00 above $500.00".to_string(),
+            ttl_secs: 120,
+            metadata: Some(serde_json::json!({"game_id": "401"})),
+        };
+        let notifier = SlackNotifier::disabled();
+        assert_eq!(
+            notifier.format_approval(&request),
+            ":raised_hand: *APPROVAL NEEDED* #7 *Sniper*\nBUY 01234567 @ $0.9500 x 800.00 (final)\nNotional: $760.00 | notional $760.00 above $500.00\nContext: game_id=401\nApprove: `POST /control/approvals/approve?id=7` | Reject: `POST /control/approvals/reject?id=7` (expires in 120s)"
+        );
     }
 }
@@ -0,0 +1,227 @@
+//! Message templates for notifications.
+//!
+//! Each notification type renders through a minijinja template. Built-in
+//! templates reproduce the stock messages; ops can override any of them by
+//! dropping `<type>.j2` (`order.j2`, `risk.j2`, `error.j2`, `price.j2`,
+//! `volume.j2`, `approval.j2`) into `NOTIFY_TEMPLATE_DIR`, without
+//! recompiling. Every field of the notification is available to the
+//! template, plus a few derived ones (`emoji`, `paper_tag`, `token_short`
+//! for orders, `metadata_text` for orders and approvals).
+//!
+//! Numbers are formatted with the `fixed` filter: `{{ price|fixed(4) }}`
+//! (a missing value formats as zero).
+
+use std::path::Path;
+
+use minijinja::{Environment, UndefinedBehavior};
+use serde::Serialize;
+use tracing::{info, warn};
+
+/// Notification types that have a template, by template name
+pub const TEMPLATE_NAMES: [&str; 6] = ["order", "risk", "error", "price", "volume", "approval"];
+
+const DEFAULT_ORDER: &str = "{{ emoji }} *{{ strategy }}*{{ paper_tag }} \
+{%- if order_type == \"ARBITRAGE\" %} ARB
+YES@${{ yes_price|fixed(4) }} + NO@${{ no_price|fixed(4) }} x {{ size|fixed(0) }}\
+{%- if pnl is not none %} | PnL: ${{ pnl|fixed(2) }}{% endif %}
+Status: {{ status }}
+{%- elif order_type == \"BASKET\" %} BASKET x {{ legs|length }}
+{%- for leg in legs %}
+{{ leg.side }} {{ leg.token_id[:8] }} @ ${{ leg.price|fixed(4) }} x {{ leg.size|fixed(0) }}: {{ leg.status }}
+{%- endfor %}
+{%- if pnl is not none %}
+PnL: ${{ pnl|fixed(2) }}
+{%- endif %}
+Status: {{ status }}
+{%- else %} {{ order_type }} {{ token_short }} @ ${{ price|fixed(4) }} x {{ size|fixed(0) }}
+Status: {{ status }}
+{%- endif %}
+{%- if hint %}
+Fix: {{ hint }}
+{%- endif %}
+{%- if metadata_text %}
+Context: {{ metadata_text }}
+{%- endif %}";
+
+const DEFAULT_RISK: &str = ":warning: *RISK ALERT: {{ alert_type }}*
+{{ message }}
+Current: {{ current_value|fixed(2) }} | Limit: {{ limit_value|fixed(2) }}";
+
+const DEFAULT_ERROR: &str = ":x: *ERROR in {{ source }}*
+Type: {{ error_type }}
+{{ message }}";
+
+const DEFAULT_PRICE: &str = ":bell: *PRICE ALERT* `{{ rule }}`
+{% if question %}{{ question }} | {% endif %}{{ token_id[:8] }}: {{ field }} {{ value|fixed(4) }} {{ op }} {{ threshold|fixed(4) }}
+Bid: {{ bid|fixed(4) }} | Ask: {{ ask|fixed(4) }}";
+
+const DEFAULT_VOLUME: &str = ":bar_chart: *Daily volume report* {{ date }}
+{{ month }} to date: maker ${{ maker_volume|fixed(2) }} ({{ maker_fills }} fills) | taker ${{ taker_volume|fixed(2) }} ({{ taker_fills }} fills)
+{%- for m in markets %}
+{{ m.market_id[:12] }}: maker ${{ m.maker_volume|fixed(2) }} | taker ${{ m.taker_volume|fixed(2) }}
+{%- endfor %}
+{%- if previous_month %}
+{{ previous_month }} total: ${{ previous_volume|fixed(2) }}
+{%- endif %}";
+
+const DEFAULT_APPROVAL: &str = ":raised_hand: *APPROVAL NEEDED* #{{ id }} *{{ strategy }}*
+{{ description }}
+Notional: ${{ notional|fixed(2) }} | {{ reason }}
+{%- if metadata_text %}
+Context: {{ metadata_text }}
+{%- endif %}
+Approve: `POST /control/approvals/approve?id={{ id }}` | Reject: `POST /control/approvals/reject?id={{ id }}` (expires in {{ ttl_secs }}s)";
+
+/// Compiled notification templates (built-ins plus any overrides)
+pub struct NotificationTemplates {
+    env: Environment<'static>,
+    /// Built-ins only, used when an override fails to render
+    defaults: Environment<'static>,
+}
+
+impl NotificationTemplates {
+    /// Built-in templates only.
+    pub fn builtin() -> Self {
+        Self {
+            env: builtin_env(),
+            defaults: builtin_env(),
+        }
+    }
+
+    /// Built-in templates, overridden from `NOTIFY_TEMPLATE_DIR` if set.
+    pub fn from_env() -> Self {
+        match std::env::var("NOTIFY_TEMPLATE_DIR") {
+            Ok(dir) if !dir.is_empty() => Self::from_dir(Path::new(&dir)),
+            _ => Self::builtin(),
+        }
+    }
+
+    /// Built-in templates, overridden by any `<type>.j2` in `dir`.
+    ///
+    /// A missing file keeps the built-in; an unreadable or invalid one is
+    /// logged and also keeps the built-in, so a bad edit cannot silence
+    /// alerts.
+    pub fn from_dir(dir: &Path) -> Self {
+        let mut templates = Self::builtin();
+        for name in TEMPLATE_NAMES {
+            let path = dir.join(format!("{}.j2", name));
+            let source = match std::fs::read_to_string(&path) {
+                Ok(source) => source,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => {
+                    warn!("[NOTIFY] Cannot read template {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+            match templates.env.add_template_owned(name, source) {
+                Ok(()) => info!("[NOTIFY] Using template {}", path.display()),
+                Err(e) => warn!("[NOTIFY] Invalid template {}: {:#}", path.display(), e),
+            }
+        }
+        templates
+    }
+
+    /// Render a notification with the template for `name`, falling back to
+    /// the built-in if an override fails at render time.
+    pub fn render<S: Serialize>(&self, name: &str, ctx: &S) -> String {
+        let rendered = self
+            .env
+            .get_template(name)
+            .and_then(|template| template.render(ctx));
+        match rendered {
+            Ok(text) => text,
+            Err(e) => {
+                warn!("[NOTIFY] Template '{}' failed to render: {:#}", name, e);
+                self.defaults
+                    .get_template(name)
+                    .and_then(|template| template.render(ctx))
+                    .unwrap_or_default()
+            }
+        }
+    }
+}
+
+impl Default for NotificationTemplates {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+fn builtin_env() -> Environment<'static> {
+    let mut env = Environment::new();
+    env.set_undefined_behavior(UndefinedBehavior::Lenient);
+    env.add_filter("fixed", fixed);
+    for (name, source) in [
+        ("order", DEFAULT_ORDER),
+        ("risk", DEFAULT_RISK),
+        ("error", DEFAULT_ERROR),
+        ("price", DEFAULT_PRICE),
+        ("volume", DEFAULT_VOLUME),
+        ("approval", DEFAULT_APPROVAL),
+    ] {
+        env.add_template(name, source)
+            .expect("built-in notification template must compile");
+    }
+    env
+}
+
+/// `{{ value|fixed(places) }}`: format a number with a fixed number of
+/// decimals, treating a missing value as zero.
+fn fixed(value: Option<f64>, places: Option<usize>) -> String {
+    format!("{:.*}", places.unwrap_or(2), value.unwrap_or(0.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Risk {
+        alert_type: &'static str,
+        message: &'static str,
+        current_value: f64,
+        limit_value: f64,
+    }
+
+    fn risk() -> Risk {
+        Risk {
+            alert_type: "DAILY_LOSS",
+            message: "Daily loss limit reached",
+            current_value: -201.5,
+            limit_value: 200.0,
+        }
+    }
+
+    #[test]
+    fn test_builtin_risk_template() {
+        let templates = NotificationTemplates::builtin();
+        assert_eq!(
+            templates.render("risk", &risk()),
+            ":warning: *RISK ALERT: DAILY_LOSS*\nDaily loss limit reached\nCurrent: -201.50 | Limit: 200.00"
+        );
+    }
+
+    #[test]
+    fn test_overrides_from_dir() {
+        let dir = std::env::temp_dir().join(format!("poly-templates-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("risk.j2"),
+            "{{ alert_type|lower }} at {{ current_value|fixed(0) }}\n",
+        )
+        .unwrap();
+        // Invalid overrides keep the built-in
+        std::fs::write(dir.join("error.j2"), "{% if %}").unwrap();
+
+        let templates = NotificationTemplates::from_dir(&dir);
+        assert_eq!(templates.render("risk", &risk()), "daily_loss at -202");
+        assert!(templates
+            .render(
+                "error",
+                &serde_json::json!({"source": "ws", "error_type": "io", "message": "closed"})
+            )
+            .starts_with(":x: *ERROR in ws*"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,367 @@
+//! Profiling run against recorded market data
+//! (`poly-rust --profile=<capture>`).
+//!
+//! Replays a capture recorded with `WS_CAPTURE_PATH` (see `ws::capture`)
+//! through the market data store and the strategy engine, in dry run and
+//! without connecting to anything, to find CPU hotspots and async stalls
+//! without instrumenting production. Time is compressed by
+//! `PROFILE_SPEEDUP`: at 10 an hour of capture replays in six minutes, and
+//! the engine ticks ten times as often; 0 replays as fast as possible.
+//! Market pairs come from `MARKET_STATE_PATH`, as saved by the run that
+//! made the capture.
+//!
+//! Built with `--features profiling`, the run samples the CPU at
+//! `PROFILE_FREQUENCY_HZ` and writes `flamegraph.svg` and `profile.pb`
+//! (for `go tool pprof`) to `PROFILE_OUTPUT_DIR`, and serves tokio-console
+//! on its default port (task data needs `RUSTFLAGS="--cfg tokio_unstable"`).
+//! The `profiling` cargo profile keeps the symbols a flamegraph needs:
+//!
+//! `cargo run --profile profiling --features profiling -- --profile=capture.jsonl`
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result};
+use prometheus::core::Collector;
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+
+use crate::config::Config;
+use crate::execution::OrderManager;
+use crate::market::MarketData;
+use crate::metrics::{self, STRATEGY_EVAL_DURATION};
+use crate::risk::RiskManager;
+use crate::strategy::{CostModel, StrategyEngine, StrategyRegistry};
+use crate::ws::{read_capture, WebSocketHandler};
+
+/// Frames applied between yields when replaying as fast as possible
+const YIELD_EVERY: usize = 256;
+
+/// Profiling run settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfileConfig {
+    /// How many times faster than recorded to replay (0 = no pacing)
+    pub speedup: f64,
+    /// Where the flamegraph and pprof profile are written
+    pub output_dir: PathBuf,
+    /// CPU samples per second
+    pub frequency_hz: i32,
+}
+
+impl Default for ProfileConfig {
+    fn default() -> Self {
+        Self {
+            speedup: 10.0,
+            output_dir: PathBuf::from("profile"),
+            frequency_hz: 99,
+        }
+    }
+}
+
+impl ProfileConfig {
+    /// Load from `PROFILE_SPEEDUP`, `PROFILE_OUTPUT_DIR` and
+    /// `PROFILE_FREQUENCY_HZ`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok();
+        Self {
+            speedup: var("PROFILE_SPEEDUP")
+                .and_then(|v| v.parse().ok())
+                .filter(|s: &f64| s.is_finite() && *s >= 0.0)
+                .unwrap_or(defaults.speedup),
+            output_dir: var("PROFILE_OUTPUT_DIR")
+                .filter(|v| !v.is_empty())
+                .map(PathBuf::from)
+                .unwrap_or(defaults.output_dir),
+            frequency_hz: var("PROFILE_FREQUENCY_HZ")
+                .and_then(|v| v.parse().ok())
+                .filter(|hz| *hz > 0)
+                .unwrap_or(defaults.frequency_hz),
+        }
+    }
+}
+
+/// The capture to replay, if `--profile=<capture>` was passed.
+pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Option<PathBuf>> {
+    for arg in args {
+        if arg == "--profile" {
+            bail!("--profile needs a capture file: --profile=<path>");
+        }
+        if let Some(path) = arg.strip_prefix("--profile=") {
+            if path.is_empty() {
+                bail!("--profile needs a capture file: --profile=<path>");
+            }
+            return Ok(Some(PathBuf::from(path)));
+        }
+    }
+    Ok(None)
+}
+
+/// What a replay measured
+#[derive(Debug, Default)]
+struct ReplayStats {
+    frames: usize,
+    /// Time between the first and last recorded frame
+    recorded: Duration,
+    elapsed: Duration,
+    /// Longest time applying one frame
+    slowest_frame: Duration,
+    /// Furthest a frame was applied behind its schedule (runtime stalls,
+    /// or frames arriving faster than they are applied)
+    max_lag: Duration,
+}
+
+/// Replay `capture` through the engine and write the profile.
+pub async fn run(capture: &Path) -> Result<()> {
+    init_logging()?;
+    dotenvy::dotenv().ok();
+    let profile = ProfileConfig::from_env();
+    let mut config = Config::from_env()?;
+    config.dry_run = true;
+    metrics::init();
+
+    let frames = read_capture(capture)?;
+    if frames.is_empty() {
+        bail!("No frames in capture {}", capture.display());
+    }
+    info!(
+        "[PROFILE] Replaying {} frames from {} at {}x",
+        frames.len(),
+        capture.display(),
+        profile.speedup
+    );
+
+    let market_data = Arc::new(MarketData::new());
+    if let Ok(path) = std::env::var("MARKET_STATE_PATH") {
+        market_data.restore_subscriptions(Path::new(&path))?;
+    }
+    let cost_model = CostModel::new(config.cost.clone());
+    let mut risk_manager = RiskManager::new(config.risk.clone());
+    risk_manager.set_market_data(market_data.clone());
+    risk_manager.set_cost_model(cost_model.clone());
+    let order_manager = OrderManager::new(config.clone(), Some(market_data.clone())).await?;
+    let mut engine = StrategyEngine::new(
+        market_data.clone(),
+        Arc::new(risk_manager),
+        Arc::new(order_manager),
+    );
+    engine.set_cost_model(cost_model);
+    for strategy in StrategyRegistry::with_builtins().build_enabled(&config)? {
+        engine.add_strategy(strategy);
+    }
+    // Same number of evaluations per recorded second as live
+    if profile.speedup > 0.0 {
+        let interval = (engine.eval_interval() as f64 / profile.speedup).round() as u64;
+        engine.set_eval_interval(interval.max(1));
+    }
+    let cancellation_token = CancellationToken::new();
+    engine.set_cancellation_token(cancellation_token.clone());
+    let engine_task = tokio::spawn(async move { engine.run().await });
+
+    let ws_handler = WebSocketHandler::new(
+        config.ws_url.clone(),
+        market_data.clone(),
+        cancellation_token.clone(),
+    );
+
+    #[cfg(feature = "profiling")]
+    let profiler = start_profiler(profile.frequency_hz)?;
+
+    let stats = replay(&ws_handler, &frames, profile.speedup).await;
+    cancellation_token.cancel();
+    let _ = engine_task.await;
+
+    #[cfg(feature = "profiling")]
+    write_profile(&profiler, &profile.output_dir)?;
+    #[cfg(not(feature = "profiling"))]
+    info!("[PROFILE] Built without --features profiling - no flamegraph or pprof output");
+
+    info!(
+        "[PROFILE] {} frames ({:.1}s recorded) in {:.1}s | {:.0} frames/s | slowest frame {:?} | max lag {:?}",
+        stats.frames,
+        stats.recorded.as_secs_f64(),
+        stats.elapsed.as_secs_f64(),
+        stats.frames as f64 / stats.elapsed.as_secs_f64().max(1e-9),
+        stats.slowest_frame,
+        stats.max_lag
+    );
+    log_strategy_durations();
+    Ok(())
+}
+
+/// Logs at `RUST_LOG` plus `poly_rust=info`; with profiling on a
+/// `tokio_unstable` build, tokio-console gets the runtime's spans as well.
+fn init_logging() -> Result<()> {
+    #[cfg(feature = "profiling")]
+    {
+        use anyhow::Context;
+        use tracing_subscriber::layer::SubscriberExt;
+        use tracing_subscriber::util::SubscriberInitExt;
+        use tracing_subscriber::{fmt, EnvFilter, Layer};
+
+        let filter = EnvFilter::from_default_env()
+            .add_directive(crate::log_filter::DEFAULT_DIRECTIVE.parse()?);
+        // The console layer panics without the runtime's task spans
+        let console = cfg!(tokio_unstable).then(console_subscriber::spawn);
+        let serving_console = console.is_some();
+        tracing_subscriber::registry()
+            .with(console)
+            .with(fmt::layer().with_filter(filter))
+            .try_init()
+            .context("Failed to install tracing subscriber")?;
+        if !serving_console {
+            info!("[PROFILE] tokio-console needs RUSTFLAGS=\"--cfg tokio_unstable\"");
+        }
+    }
+    #[cfg(not(feature = "profiling"))]
+    crate::log_filter::LogFilter::init()?;
+    Ok(())
+}
+
+/// Apply `frames` at `speedup` times their recorded pace.
+async fn replay(
+    ws_handler: &WebSocketHandler,
+    frames: &[crate::ws::CapturedFrame],
+    speedup: f64,
+) -> ReplayStats {
+    let mut stats = ReplayStats::default();
+    let (Some(first), Some(last)) = (frames.first(), frames.last()) else {
+        return stats;
+    };
+    stats.recorded = Duration::from_millis(last.ts_ms.saturating_sub(first.ts_ms));
+
+    let started = Instant::now();
+    for (i, frame) in frames.iter().enumerate() {
+        if speedup > 0.0 {
+            let offset = frame.ts_ms.saturating_sub(first.ts_ms) as f64 / 1000.0 / speedup;
+            let due = started + Duration::from_secs_f64(offset);
+            tokio::time::sleep_until(due.into()).await;
+            stats.max_lag = stats
+                .max_lag
+                .max(Instant::now().saturating_duration_since(due));
+        } else if i % YIELD_EVERY == 0 {
+            tokio::task::yield_now().await;
+        }
+        let applied = Instant::now();
+        ws_handler.replay(&frame.msg);
+        stats.slowest_frame = stats.slowest_frame.max(applied.elapsed());
+        stats.frames += 1;
+    }
+    stats.elapsed = started.elapsed();
+    stats
+}
+
+/// Mean and total evaluation time per strategy over the run
+fn log_strategy_durations() {
+    for family in STRATEGY_EVAL_DURATION.collect() {
+        for metric in family.get_metric() {
+            let strategy = metric
+                .get_label()
+                .iter()
+                .find(|label| label.get_name() == "strategy")
+                .map(|label| label.get_value())
+                .unwrap_or("?");
+            let histogram = metric.get_histogram();
+            let count = histogram.get_sample_count();
+            if count == 0 {
+                continue;
+            }
+            let total = histogram.get_sample_sum();
+            info!(
+                "[PROFILE] {} | {} evaluations | mean {:.1}us | total {:.3}s",
+                strategy,
+                count,
+                total / count as f64 * 1e6,
+                total
+            );
+        }
+    }
+}
+
+#[cfg(feature = "profiling")]
+fn start_profiler(frequency_hz: i32) -> Result<pprof::ProfilerGuard<'static>> {
+    use anyhow::Context;
+
+    pprof::ProfilerGuardBuilder::default()
+        .frequency(frequency_hz)
+        .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+        .build()
+        .context("Failed to start the CPU profiler")
+}
+
+#[cfg(feature = "profiling")]
+fn write_profile(profiler: &pprof::ProfilerGuard<'_>, dir: &Path) -> Result<()> {
+    use anyhow::Context;
+    use pprof::protos::Message;
+
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let report = profiler.report().build()?;
+
+    let flamegraph = dir.join("flamegraph.svg");
+    report.flamegraph(std::fs::File::create(&flamegraph)?)?;
+
+    let pprof = dir.join("profile.pb");
+    let mut encoded = Vec::new();
+    report.pprof()?.encode(&mut encoded)?;
+    std::fs::write(&pprof, encoded)?;
+
+    info!(
+        "[PROFILE] Wrote {} and {}",
+        flamegraph.display(),
+        pprof.display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ws::CapturedFrame;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_capture_from_args() {
+        assert_eq!(from_args(args(&["--self-test"])).unwrap(), None);
+        assert_eq!(
+            from_args(args(&["--profile=/tmp/capture.jsonl"])).unwrap(),
+            Some(PathBuf::from("/tmp/capture.jsonl"))
+        );
+        assert!(from_args(args(&["--profile"])).is_err());
+        assert!(from_args(args(&["--profile="])).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_replay_paces_frames_and_applies_them() {
+        let market_data = Arc::new(MarketData::new());
+        let ws_handler = WebSocketHandler::new(
+            "wss://unused".to_string(),
+            market_data.clone(),
+            CancellationToken::new(),
+        );
+        let book = |bid: &str| CapturedFrame {
+            ts_ms: 0,
+            msg: format!(
+                r#"{{"type":"book","asset_id":"t1","bids":[{{"price":"{}","size":"10"}}],"asks":[{{"price":"0.60","size":"10"}}]}}"#,
+                bid
+            ),
+        };
+        let frames = vec![
+            book("0.40"),
+            CapturedFrame {
+                ts_ms: 200,
+                ..book("0.45")
+            },
+        ];
+
+        // 200ms recorded at 4x: about 50ms
+        let stats = replay(&ws_handler, &frames, 4.0).await;
+        assert_eq!(stats.frames, 2);
+        assert_eq!(stats.recorded, Duration::from_millis(200));
+        assert!(stats.elapsed >= Duration::from_millis(50));
+        assert_eq!(market_data.get_bid(&"t1".into()), Some(0.45));
+    }
+}
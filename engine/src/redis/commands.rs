@@ -0,0 +1,205 @@
+//! Operator commands received over Redis.
+//!
+//! The dashboard can't reach the control API of every instance, so the
+//! engine also listens on `<prefix>:commands`. A command is a JSON object
+//! with a `command` field and, optionally, an `instance` field to address a
+//! single instance (unset = every instance on the prefix):
+//!
+//! - `{"command": "loglevel", "filter": "poly_rust=info,poly_rust::ws=debug"}`
+//!
+//! Applied commands are recorded in the audit trail.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use futures_util::StreamExt;
+use serde::Deserialize;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::audit::{AuditAction, AuditLog};
+use crate::connectivity::{ConnectivitySupervisor, Subsystem};
+use crate::log_filter::LogFilter;
+
+/// Actor recorded in the audit log for commands received over Redis
+const REDIS_ACTOR: &str = "operator:redis";
+
+/// Wait before resubscribing after the connection drops (without a
+/// connectivity supervisor)
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// A command and the instance it is addressed to
+#[derive(Debug, Deserialize)]
+struct Envelope {
+    #[serde(default)]
+    instance: Option<String>,
+    #[serde(flatten)]
+    command: Command,
+}
+
+/// Commands the engine accepts
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "command")]
+pub enum Command {
+    /// Replace the log filter (`reset` restores the startup filter)
+    #[serde(rename = "loglevel")]
+    LogLevel { filter: String },
+}
+
+/// Subscribes to the commands channel and applies what arrives
+pub struct CommandListener {
+    redis_url: String,
+    channel: String,
+    instance_id: String,
+    log_filter: Arc<LogFilter>,
+    audit: Arc<AuditLog>,
+    /// Shared reconnect backoff (None = fixed delay)
+    connectivity: Option<Arc<ConnectivitySupervisor>>,
+}
+
+impl CommandListener {
+    pub fn new(
+        redis_url: &str,
+        channel: String,
+        instance_id: &str,
+        log_filter: Arc<LogFilter>,
+        audit: Arc<AuditLog>,
+    ) -> Self {
+        Self {
+            redis_url: redis_url.to_string(),
+            channel,
+            instance_id: instance_id.to_string(),
+            log_filter,
+            audit,
+            connectivity: None,
+        }
+    }
+
+    /// Take reconnect delays from the connectivity supervisor and report
+    /// the subscription's state to it
+    pub fn set_connectivity(&mut self, connectivity: Arc<ConnectivitySupervisor>) {
+        self.connectivity = Some(connectivity);
+    }
+
+    /// Listen until cancelled, resubscribing when the connection drops.
+    pub async fn run(self, cancel: CancellationToken) {
+        info!("[REDIS] Listening for commands on {}", self.channel);
+        loop {
+            tokio::select! {
+                result = self.listen() => {
+                    if let Err(e) = result {
+                        warn!("[REDIS] Command subscription failed: {:#}", e);
+                        if let Some(connectivity) = &self.connectivity {
+                            connectivity.report_down(Subsystem::Redis, format!("{:#}", e));
+                        }
+                    }
+                }
+                _ = cancel.cancelled() => break,
+            }
+            let delay = self
+                .connectivity
+                .as_ref()
+                .map_or(RECONNECT_DELAY, |c| c.reconnect_delay(Subsystem::Redis));
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                _ = cancel.cancelled() => break,
+            }
+        }
+    }
+
+    async fn listen(&self) -> Result<()> {
+        let client = redis::Client::open(self.redis_url.as_str())
+            .context("Failed to create Redis client")?;
+        let mut pubsub = client
+            .get_async_connection()
+            .await
+            .context("Failed to connect to Redis")?
+            .into_pubsub();
+        pubsub
+            .subscribe(&self.channel)
+            .await
+            .context("Failed to subscribe")?;
+        if let Some(connectivity) = &self.connectivity {
+            connectivity.report_up(Subsystem::Redis);
+        }
+
+        let mut messages = pubsub.on_message();
+        while let Some(msg) = messages.next().await {
+            let payload: String = match msg.get_payload() {
+                Ok(payload) => payload,
+                Err(e) => {
+                    warn!("[REDIS] Unreadable command: {}", e);
+                    continue;
+                }
+            };
+            match self.apply(&payload) {
+                Ok(Some(outcome)) => info!("[REDIS] Command applied: {}", outcome),
+                Ok(None) => {}
+                Err(e) => warn!("[REDIS] Command '{}' rejected: {:#}", payload, e),
+            }
+        }
+        anyhow::bail!("subscription closed")
+    }
+
+    /// Apply a command payload. Returns what changed, or None if the
+    /// command is addressed to another instance.
+    fn apply(&self, payload: &str) -> Result<Option<String>> {
+        let envelope: Envelope = serde_json::from_str(payload).context("Invalid command")?;
+        if envelope
+            .instance
+            .as_ref()
+            .is_some_and(|instance| *instance != self.instance_id)
+        {
+            return Ok(None);
+        }
+
+        match envelope.command {
+            Command::LogLevel { filter } => {
+                let current = self.log_filter.set(&filter)?;
+                self.audit.record(
+                    REDIS_ACTOR,
+                    AuditAction::ConfigChange,
+                    Some("log_filter"),
+                    format!("loglevel command: {}", current),
+                );
+                Ok(Some(format!("log filter is now '{}'", current)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listener(log_filter: Arc<LogFilter>) -> CommandListener {
+        CommandListener::new(
+            "redis://localhost",
+            "poly:commands".to_string(),
+            "east",
+            log_filter,
+            Arc::new(AuditLog::new()),
+        )
+    }
+
+    #[test]
+    fn test_loglevel_command_respects_instance() {
+        let log_filter = Arc::new(LogFilter::for_test("poly_rust=info"));
+        let listener = listener(log_filter.clone());
+
+        let west = r#"{"command":"loglevel","filter":"poly_rust=debug","instance":"west"}"#;
+        assert_eq!(listener.apply(west).unwrap(), None);
+        assert_eq!(log_filter.current(), "poly_rust=info");
+
+        let all = r#"{"command":"loglevel","filter":"poly_rust::ws=debug"}"#;
+        assert!(listener.apply(all).unwrap().is_some());
+        assert_eq!(log_filter.current(), "poly_rust::ws=debug");
+        assert_eq!(listener.audit.recent().len(), 1);
+
+        assert!(listener.apply(r#"{"command":"restart"}"#).is_err());
+        assert!(listener
+            .apply(r#"{"command":"loglevel","filter":"poly_rust=loud"}"#)
+            .is_err());
+    }
+}
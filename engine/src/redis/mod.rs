@@ -1,11 +1,14 @@
 //! Redis integration for Rust-Python communication.
 //!
 //! This module provides pub/sub functionality to stream trading data
-//! to the Python dashboard in real-time.
+//! to the Python dashboard in real-time, and receives operator commands.
 
+mod commands;
 mod publisher;
 
+pub use commands::CommandListener;
+
 #[allow(unused_imports)]
 pub use publisher::{
-    now_ms, EngineState, ErrorMessage, PositionInfo, RedisPublisher, SignalMessage, TradeMessage,
+    channels, now_ms, ErrorMessage, NearMissMessage, RedisPublisher, SignalMessage, TradeMessage,
 };
@@ -1,23 +1,49 @@
 //! Redis Publisher - Streams trading data to Python dashboard.
 //!
-//! Channels:
-//! - `poly:state`   - Engine state updates (every 100ms)
+//! Channels (shown with the default `poly` prefix; see `REDIS_CHANNEL_PREFIX`):
+//! - `poly:state`   - Engine state updates (every heartbeat, see `status`)
 //! - `poly:signals` - Trade signals as they happen
 //! - `poly:trades`  - Executed trades
 //! - `poly:errors`  - Error notifications
+//! - `poly:audit`   - Operator actions and automated interventions
+//! - `poly:near_misses` - Arbitrage opportunities just below the edge threshold
+//! - `poly:commands` - Operator commands to the engine (see `commands`)
+//! - `poly:portfolio` - Positions, exposure and NAV (every heartbeat)
+//!
+//! Every message carries an `instance_id` field so consumers can tell engine
+//! instances apart.
 
 use anyhow::{Context, Result};
 use redis::aio::ConnectionManager;
 use redis::AsyncCommands;
 use serde::Serialize;
+use serde_json::Value;
 use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
 use tokio::sync::RwLock;
-use tracing::{debug, info, warn};
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::chaos;
+use crate::connectivity::{ConnectivitySupervisor, Subsystem};
+use crate::log_budget::debug_limited;
+use crate::config::DEFAULT_INSTANCE_ID;
+use crate::risk::Portfolio;
+use crate::status::{EngineState, StatusBoard};
+
+/// Serialize a message, adding `instance_id` when it is a JSON object.
+fn stamped_json<T: Serialize>(value: &T, instance_id: &str) -> serde_json::Result<String> {
+    let mut value = serde_json::to_value(value)?;
+    if let Value::Object(map) = &mut value {
+        map.insert("instance_id".to_string(), Value::from(instance_id));
+    }
+    serde_json::to_string(&value)
+}
 
 /// Safely serialize a value to JSON, logging on failure instead of panicking.
 /// Returns None if serialization fails, allowing callers to gracefully skip publishing.
-fn serialize_or_log<T: Serialize>(value: &T, context: &str) -> Option<String> {
-    match serde_json::to_string(value) {
+fn serialize_or_log<T: Serialize>(value: &T, instance_id: &str, context: &str) -> Option<String> {
+    match stamped_json(value, instance_id) {
         Ok(json) => Some(json),
         Err(e) => {
             warn!("[REDIS] Failed to serialize {}: {}", context, e);
@@ -26,34 +52,17 @@ fn serialize_or_log<T: Serialize>(value: &T, context: &str) -> Option<String> {
     }
 }
 
-/// Redis channel names
+/// Redis channel names, relative to the configured prefix
 #[allow(dead_code)]
 pub mod channels {
-    pub const STATE: &str = "poly:state";
-    pub const SIGNALS: &str = "poly:signals";
-    pub const TRADES: &str = "poly:trades";
-    pub const ERRORS: &str = "poly:errors";
-}
-
-/// Engine state message published to Redis
-#[derive(Debug, Clone, Serialize)]
-pub struct EngineState {
-    pub timestamp_ms: u64,
-    pub status: String,
-    pub markets_tracked: usize,
-    pub opportunities_found: usize,
-    pub daily_pnl: f64,
-    pub daily_trades: u64,
-    pub positions: Vec<PositionInfo>,
-}
-
-/// Position info for state updates
-#[derive(Debug, Clone, Serialize)]
-pub struct PositionInfo {
-    pub token_id: String,
-    pub size: f64,
-    pub avg_cost: f64,
-    pub unrealized_pnl: f64,
+    pub const STATE: &str = "state";
+    pub const SIGNALS: &str = "signals";
+    pub const TRADES: &str = "trades";
+    pub const ERRORS: &str = "errors";
+    pub const AUDIT: &str = "audit";
+    pub const NEAR_MISSES: &str = "near_misses";
+    pub const COMMANDS: &str = "commands";
+    pub const PORTFOLIO: &str = "portfolio";
 }
 
 /// Trade signal message
@@ -61,7 +70,7 @@ pub struct PositionInfo {
 pub struct SignalMessage {
     pub timestamp_ms: u64,
     pub strategy: String,
-    pub signal_type: String, // "BUY", "SELL", "ARBITRAGE"
+    pub signal_type: String, // "BUY", "SELL", "ARBITRAGE", "BASKET"
     pub token_id: Option<String>,
     pub yes_token_id: Option<String>,
     pub no_token_id: Option<String>,
@@ -70,7 +79,17 @@ pub struct SignalMessage {
     pub no_price: Option<f64>,
     pub size: f64,
     pub edge: Option<f64>,
+    /// Orders of a basket signal
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub legs: Option<Value>,
     pub reason: String,
+    pub mode: String, // execution mode, or "observe"
+    /// Book and risk context of an observed signal
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<Value>,
+    /// Strategy-specific context (see `SignalMetadata`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
 }
 
 /// Executed trade message
@@ -92,6 +111,24 @@ pub struct TradeMessage {
     pub status: String,
     pub pnl: Option<f64>,
     pub is_paper: bool,
+    /// Metadata of the signal that made the trade
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+/// Sum-to-100 opportunity that fell just short of the minimum edge
+#[derive(Debug, Clone, Serialize)]
+pub struct NearMissMessage {
+    pub timestamp_ms: u64,
+    pub market_id: String,
+    pub yes_token_id: String,
+    pub no_token_id: String,
+    pub yes_price: f64,
+    pub no_price: f64,
+    pub sum: f64,
+    pub edge: f64,
+    pub min_edge: f64,
+    pub size: f64,
 }
 
 /// Error message
@@ -109,6 +146,12 @@ pub struct ErrorMessage {
 pub struct RedisPublisher {
     connection: Arc<RwLock<Option<ConnectionManager>>>,
     enabled: bool,
+    /// Prepended to every channel name (`<prefix>:<channel>`)
+    prefix: String,
+    /// Stamped into every published message
+    instance_id: String,
+    /// Told when publishes stop and start reaching Redis
+    connectivity: Option<Arc<ConnectivitySupervisor>>,
 }
 
 impl RedisPublisher {
@@ -131,14 +174,14 @@ impl RedisPublisher {
                 Ok(Self {
                     connection: Arc::new(RwLock::new(Some(connection))),
                     enabled: true,
+                    prefix: "poly".to_string(),
+                    instance_id: DEFAULT_INSTANCE_ID.to_string(),
+                    connectivity: None,
                 })
             }
             None => {
                 info!("Redis URL not configured - publisher disabled");
-                Ok(Self {
-                    connection: Arc::new(RwLock::new(None)),
-                    enabled: false,
-                })
+                Ok(Self::disabled())
             }
         }
     }
@@ -149,15 +192,63 @@ impl RedisPublisher {
         Self {
             connection: Arc::new(RwLock::new(None)),
             enabled: false,
+            prefix: "poly".to_string(),
+            instance_id: DEFAULT_INSTANCE_ID.to_string(),
+            connectivity: None,
+        }
+    }
+
+    /// Set the channel prefix and instance ID (see `Config::redis_prefix`).
+    pub fn set_namespace(&mut self, prefix: &str, instance_id: &str) {
+        self.prefix = prefix.to_string();
+        self.instance_id = instance_id.to_string();
+        if self.enabled {
+            info!(
+                "[REDIS] Publishing on {}:* as instance '{}'",
+                self.prefix, self.instance_id
+            );
         }
     }
 
+    /// Report the connection's state to the connectivity supervisor. The
+    /// connection manager reconnects on its own; publishes show whether it
+    /// is getting through.
+    pub fn set_connectivity(&mut self, connectivity: Arc<ConnectivitySupervisor>) {
+        self.connectivity = Some(connectivity);
+    }
+
+    /// Tell the connectivity supervisor whether a publish got through
+    /// (errors other than connection trouble are not reported).
+    fn report_connection<T>(&self, result: &redis::RedisResult<T>) {
+        let Some(connectivity) = &self.connectivity else {
+            return;
+        };
+        match result {
+            Ok(_) => connectivity.report_up(Subsystem::Redis),
+            Err(e)
+                if e.is_io_error()
+                    || e.is_connection_dropped()
+                    || e.is_connection_refusal()
+                    || e.is_timeout() =>
+            {
+                connectivity.report_down(Subsystem::Redis, e)
+            }
+            Err(_) => {}
+        }
+    }
+
+    /// Full channel name for a name from `channels`.
+    pub fn channel(&self, name: &str) -> String {
+        format!("{}:{}", self.prefix, name)
+    }
+
     /// Check if the publisher is enabled.
     pub fn is_enabled(&self) -> bool {
         self.enabled
     }
 
     /// Publish engine state update.
+    #[allow(dead_code)]
     pub async fn publish_state(&self, state: &EngineState) -> Result<()> {
         self.publish(channels::STATE, state).await
     }
@@ -180,13 +271,68 @@ impl RedisPublisher {
 
     /// Fire-and-forget state publish. Logs errors instead of returning them.
     /// Safe to call from spawned async tasks where errors would be silently dropped.
-    #[allow(dead_code)]
     pub async fn publish_state_logged(&self, state: &EngineState) {
         if let Err(e) = self.publish_with_context(channels::STATE, state, "engine state").await {
             warn!("[REDIS] Failed to publish state: {}", e);
         }
     }
 
+    /// Publish every engine status snapshot until cancelled. Does nothing
+    /// when the publisher is disabled.
+    pub async fn run_state_forwarder(
+        self: Arc<Self>,
+        status: Arc<StatusBoard>,
+        cancel: CancellationToken,
+    ) {
+        if !self.enabled {
+            return;
+        }
+        let mut updates = status.subscribe();
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => break,
+                update = updates.recv() => match update {
+                    Ok(state) => self.publish_state_logged(&state).await,
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!("[REDIS] Skipped {} engine state updates", skipped);
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+            }
+        }
+    }
+
+    /// Publish a portfolio snapshot with every engine status snapshot until
+    /// cancelled. Does nothing when the publisher is disabled.
+    pub async fn run_portfolio_forwarder(
+        self: Arc<Self>,
+        status: Arc<StatusBoard>,
+        portfolio: Arc<Portfolio>,
+        cancel: CancellationToken,
+    ) {
+        if !self.enabled {
+            return;
+        }
+        let mut updates = status.subscribe();
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => break,
+                update = updates.recv() => match update {
+                    Ok(_) | Err(RecvError::Lagged(_)) => {
+                        let snapshot = portfolio.snapshot();
+                        if let Err(e) = self
+                            .publish_with_context(channels::PORTFOLIO, &snapshot, "portfolio")
+                            .await
+                        {
+                            warn!("[REDIS] Failed to publish portfolio: {}", e);
+                        }
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+            }
+        }
+    }
+
     /// Fire-and-forget signal publish. Logs errors instead of returning them.
     /// Safe to call from spawned async tasks where errors would be silently dropped.
     #[allow(dead_code)]
@@ -205,6 +351,16 @@ impl RedisPublisher {
         }
     }
 
+    /// Fire-and-forget near-miss publish. Logs errors instead of returning them.
+    pub async fn publish_near_miss_logged(&self, near_miss: &NearMissMessage) {
+        if let Err(e) = self
+            .publish_with_context(channels::NEAR_MISSES, near_miss, "near miss")
+            .await
+        {
+            warn!("[REDIS] Failed to publish near miss: {}", e);
+        }
+    }
+
     /// Fire-and-forget error publish. Logs errors instead of returning them.
     /// Safe to call from spawned async tasks where errors would be silently dropped.
     #[allow(dead_code)]
@@ -220,14 +376,27 @@ impl RedisPublisher {
             return Ok(());
         }
 
-        let json = serde_json::to_string(message).context("Failed to serialize message")?;
+        let json =
+            stamped_json(message, &self.instance_id).context("Failed to serialize message")?;
+        let channel = self.channel(channel);
+        if chaos::fail_redis_publish() {
+            anyhow::bail!("injected publish fault on {} (chaos)", channel);
+        }
 
         let mut conn_guard = self.connection.write().await;
 
         if let Some(ref mut conn) = *conn_guard {
-            match conn.publish::<_, _, i32>(channel, &json).await {
+            let result = conn.publish::<_, _, i32>(&channel, &json).await;
+            self.report_connection(&result);
+            match result {
                 Ok(subscribers) => {
-                    debug!("Published to {} ({} subscribers)", channel, subscribers);
+                    debug_limited!(
+                        "redis_publish",
+                        None,
+                        "Published to {} ({} subscribers)",
+                        channel,
+                        subscribers
+                    );
                     Ok(())
                 }
                 Err(e) => {
@@ -252,17 +421,30 @@ impl RedisPublisher {
             return Ok(());
         }
 
-        let json = match serialize_or_log(message, context) {
+        let json = match serialize_or_log(message, &self.instance_id, context) {
             Some(j) => j,
             None => return Ok(()), // Serialization failed, already logged
         };
+        let channel = self.channel(channel);
+        if chaos::fail_redis_publish() {
+            anyhow::bail!("injected publish fault on {} (chaos)", channel);
+        }
 
         let mut conn_guard = self.connection.write().await;
 
         if let Some(ref mut conn) = *conn_guard {
-            match conn.publish::<_, _, i32>(channel, &json).await {
+            let result = conn.publish::<_, _, i32>(&channel, &json).await;
+            self.report_connection(&result);
+            match result {
                 Ok(subscribers) => {
-                    debug!("Published {} to {} ({} subscribers)", context, channel, subscribers);
+                    debug_limited!(
+                        "redis_publish",
+                        None,
+                        "Published {} to {} ({} subscribers)",
+                        context,
+                        channel,
+                        subscribers
+                    );
                     Ok(())
                 }
                 Err(e) => {
@@ -275,19 +457,29 @@ impl RedisPublisher {
         }
     }
 
-    /// Publish a raw JSON string to a channel.
+    /// Publish a raw JSON string to a channel (`instance_id` is added to
+    /// JSON objects).
     #[allow(dead_code)]
     pub async fn publish_raw(&self, channel: &str, json: &str) -> Result<()> {
         if !self.enabled {
             return Ok(());
         }
 
+        let json = match serde_json::from_str::<Value>(json) {
+            Ok(value) => stamped_json(&value, &self.instance_id)?,
+            Err(_) => json.to_string(),
+        };
+        let channel = self.channel(channel);
+        if chaos::fail_redis_publish() {
+            anyhow::bail!("injected publish fault on {} (chaos)", channel);
+        }
+
         let mut conn_guard = self.connection.write().await;
 
         if let Some(ref mut conn) = *conn_guard {
-            conn.publish::<_, _, i32>(channel, json)
-                .await
-                .context("Failed to publish raw message")?;
+            let result = conn.publish::<_, _, i32>(&channel, &json).await;
+            self.report_connection(&result);
+            result.context("Failed to publish raw message")?;
         }
 
         Ok(())
@@ -315,20 +507,23 @@ mod tests {
     }
 
     #[test]
-    fn test_serialize_state() {
-        let state = EngineState {
-            timestamp_ms: 1234567890,
-            status: "running".to_string(),
-            markets_tracked: 10,
-            opportunities_found: 5,
-            daily_pnl: 123.45,
-            daily_trades: 15,
-            positions: vec![],
+    fn test_namespace_applies_to_channels_and_messages() {
+        let mut publisher = RedisPublisher::disabled();
+        assert_eq!(publisher.channel(channels::STATE), "poly:state");
+
+        publisher.set_namespace("poly:eu-1", "eu-1");
+        assert_eq!(publisher.channel(channels::TRADES), "poly:eu-1:trades");
+
+        let error = ErrorMessage {
+            timestamp_ms: 1,
+            source: "test".to_string(),
+            error_type: "x".to_string(),
+            message: "boom".to_string(),
+            details: None,
         };
-
-        let json = serde_json::to_string(&state).unwrap();
-        assert!(json.contains("running"));
-        assert!(json.contains("123.45"));
+        let json: Value = serde_json::from_str(&stamped_json(&error, "eu-1").unwrap()).unwrap();
+        assert_eq!(json["instance_id"], "eu-1");
+        assert_eq!(json["message"], "boom");
     }
 
     #[test]
@@ -350,6 +545,7 @@ mod tests {
             status: "FILLED".to_string(),
             pnl: Some(5.0),
             is_paper: false,
+            metadata: None,
         };
 
         let json = serde_json::to_string(&trade).unwrap();
@@ -0,0 +1,262 @@
+//! Restarting internal tasks, and giving up on crash loops.
+//!
+//! Long-running components (the WebSocket feed, the strategy engine, the
+//! ESPN poller, the Redis state forwarder) run under
+//! `TaskSupervisor::supervise`. One that panics, or returns before
+//! shutdown, is restarted after `TASK_RESTART_DELAY_MS`. Restarts are
+//! counted per component; a component that would restart more than
+//! `TASK_RESTART_MAX` times within `TASK_RESTART_WINDOW_SECS` is crash
+//! looping. It is left stopped, `/ready` turns 503, and Slack gets one
+//! alert naming it, instead of the engine restarting it forever with
+//! nothing but log lines to show for it.
+//!
+//! Panics are caught with `catch_unwind`, which is why the release profile
+//! keeps `panic = "unwind"`.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures::FutureExt;
+use parking_lot::Mutex;
+use serde::Serialize;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, warn};
+
+use crate::metrics::{CRASH_LOOPS, TASK_RESTARTS};
+use crate::notifications::{ErrorAlert, SlackNotifier};
+
+/// Task restart settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct RestartConfig {
+    /// Restarts allowed within `window` before a component is given up on
+    pub max_restarts: usize,
+    /// Window restarts are counted in
+    pub window: Duration,
+    /// Pause before each restart
+    pub delay: Duration,
+}
+
+impl Default for RestartConfig {
+    fn default() -> Self {
+        Self {
+            max_restarts: 5,
+            window: Duration::from_secs(600),
+            delay: Duration::from_secs(1),
+        }
+    }
+}
+
+impl RestartConfig {
+    /// Load from `TASK_RESTART_MAX`, `TASK_RESTART_WINDOW_SECS` and
+    /// `TASK_RESTART_DELAY_MS`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok();
+        Self {
+            max_restarts: var("TASK_RESTART_MAX")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.max_restarts),
+            window: var("TASK_RESTART_WINDOW_SECS")
+                .and_then(|v| v.parse().ok())
+                .filter(|secs| *secs > 0)
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.window),
+            delay: var("TASK_RESTART_DELAY_MS")
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.delay),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Component {
+    /// Restarts within the window, oldest first
+    recent: VecDeque<Instant>,
+    total: u64,
+    crash_looping: bool,
+}
+
+/// Restart counts for one component (`/ready`)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ComponentRestarts {
+    pub component: &'static str,
+    pub restarts: u64,
+    /// Restarts within the window
+    pub recent: usize,
+    pub crash_looping: bool,
+}
+
+/// Restarts supervised components and tracks crash loops
+pub struct TaskSupervisor {
+    config: RestartConfig,
+    components: Mutex<BTreeMap<&'static str, Component>>,
+    slack: Option<Arc<SlackNotifier>>,
+}
+
+impl TaskSupervisor {
+    pub fn new(config: RestartConfig) -> Self {
+        Self {
+            config,
+            components: Mutex::new(BTreeMap::new()),
+            slack: None,
+        }
+    }
+
+    /// Alert on Slack when a component is given up on.
+    pub fn set_slack_notifier(&mut self, slack: Arc<SlackNotifier>) {
+        self.slack = Some(slack);
+    }
+
+    /// Run `task` as `component`, starting it again whenever it panics or
+    /// returns, until `cancel` fires or the component is crash looping.
+    /// Shutdown is left to the task: a task still running when `cancel`
+    /// fires is awaited, not dropped.
+    pub async fn supervise<F, Fut>(
+        self: Arc<Self>,
+        component: &'static str,
+        cancel: CancellationToken,
+        mut task: F,
+    ) where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        self.components.lock().entry(component).or_default();
+        loop {
+            let exit = match AssertUnwindSafe(task()).catch_unwind().await {
+                Ok(()) => "returned",
+                Err(_) => "panicked",
+            };
+            if cancel.is_cancelled() || !self.record_exit(component, exit, Instant::now()) {
+                return;
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(self.config.delay) => {}
+                _ = cancel.cancelled() => return,
+            }
+        }
+    }
+
+    /// Count an unexpected exit of `component`; returns whether it may be
+    /// restarted.
+    fn record_exit(&self, component: &'static str, exit: &str, now: Instant) -> bool {
+        let mut components = self.components.lock();
+        let state = components.entry(component).or_default();
+        while state
+            .recent
+            .front()
+            .is_some_and(|at| now.duration_since(*at) >= self.config.window)
+        {
+            state.recent.pop_front();
+        }
+        if state.recent.len() >= self.config.max_restarts {
+            state.crash_looping = true;
+            let recent = state.recent.len();
+            CRASH_LOOPS.set(components.values().filter(|c| c.crash_looping).count() as f64);
+            drop(components);
+            let message = format!(
+                "{} {} after {} restarts in {:?} - no longer restarting it; the engine is not ready",
+                component, exit, recent, self.config.window
+            );
+            error!("[RESTART] {}", message);
+            if let Some(slack) = &self.slack {
+                slack.notify_error(ErrorAlert {
+                    source: component.to_string(),
+                    error_type: "crash_loop".to_string(),
+                    message,
+                });
+            }
+            return false;
+        }
+        state.recent.push_back(now);
+        state.total += 1;
+        TASK_RESTARTS.with_label_values(&[component, exit]).inc();
+        warn!(
+            "[RESTART] {} {} - restarting ({} of {} allowed in {:?})",
+            component,
+            exit,
+            state.recent.len(),
+            self.config.max_restarts,
+            self.config.window
+        );
+        true
+    }
+
+    /// False once any component is crash looping.
+    pub fn is_ready(&self) -> bool {
+        !self.components.lock().values().any(|c| c.crash_looping)
+    }
+
+    /// Restart counts of every supervised component.
+    pub fn restarts(&self) -> Vec<ComponentRestarts> {
+        self.components
+            .lock()
+            .iter()
+            .map(|(component, state)| ComponentRestarts {
+                component,
+                restarts: state.total,
+                recent: state.recent.len(),
+                crash_looping: state.crash_looping,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn test_gives_up_after_too_many_restarts_in_window() {
+        let supervisor = TaskSupervisor::new(RestartConfig {
+            max_restarts: 2,
+            window: Duration::from_secs(60),
+            delay: Duration::ZERO,
+        });
+        let now = Instant::now();
+
+        assert!(supervisor.record_exit("ws", "panicked", now));
+        assert!(supervisor.record_exit("ws", "panicked", now));
+        // Old restarts leave the window
+        let later = now + Duration::from_secs(60);
+        assert!(supervisor.record_exit("ws", "returned", later));
+        assert!(supervisor.record_exit("ws", "returned", later));
+        assert!(supervisor.is_ready());
+
+        assert!(!supervisor.record_exit("ws", "panicked", later));
+        assert!(!supervisor.is_ready());
+        let restarts = supervisor.restarts();
+        assert_eq!(restarts.len(), 1);
+        assert_eq!(restarts[0].restarts, 4);
+        assert!(restarts[0].crash_looping);
+    }
+
+    #[tokio::test]
+    async fn test_restarts_panicking_task_until_crash_loop() {
+        let supervisor = Arc::new(TaskSupervisor::new(RestartConfig {
+            max_restarts: 3,
+            window: Duration::from_secs(60),
+            delay: Duration::ZERO,
+        }));
+        let runs = Arc::new(AtomicU32::new(0));
+        let counter = runs.clone();
+        supervisor
+            .clone()
+            .supervise("engine", CancellationToken::new(), move || {
+                let counter = counter.clone();
+                async move {
+                    counter.fetch_add(1, Ordering::Relaxed);
+                    panic!("boom");
+                }
+            })
+            .await;
+
+        // First run plus three restarts
+        assert_eq!(runs.load(Ordering::Relaxed), 4);
+        assert!(!supervisor.is_ready());
+    }
+}
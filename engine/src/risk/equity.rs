@@ -0,0 +1,80 @@
+//! Equity curve and drawdown tracking.
+//!
+//! Equity here is cumulative P&L since startup: realized P&L plus open
+//! positions marked to the mid. The strategy engine samples it every
+//! heartbeat; drawdown is the distance below the highest equity seen so far.
+
+use serde::Serialize;
+
+/// One point on the equity curve
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct EquitySample {
+    pub timestamp_ms: u64,
+    pub realized_pnl: f64,
+    pub unrealized_pnl: f64,
+    pub equity: f64,
+    /// Dollars below the running peak
+    pub drawdown: f64,
+    /// Largest drawdown seen so far
+    pub max_drawdown: f64,
+}
+
+/// Running peak and maximum drawdown of the equity curve.
+#[derive(Debug, Default)]
+pub struct EquityCurve {
+    peak: f64,
+    max_drawdown: f64,
+}
+
+impl EquityCurve {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a sample and return it with its drawdown.
+    pub fn record(
+        &mut self,
+        timestamp_ms: u64,
+        realized_pnl: f64,
+        unrealized_pnl: f64,
+    ) -> EquitySample {
+        let equity = realized_pnl + unrealized_pnl;
+        self.peak = self.peak.max(equity);
+        let drawdown = self.peak - equity;
+        self.max_drawdown = self.max_drawdown.max(drawdown);
+
+        EquitySample {
+            timestamp_ms,
+            realized_pnl,
+            unrealized_pnl,
+            equity,
+            drawdown,
+            max_drawdown: self.max_drawdown,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drawdown_from_running_peak() {
+        let mut curve = EquityCurve::new();
+
+        let sample = curve.record(1, -2.0, 0.0);
+        assert_eq!(sample.drawdown, 2.0);
+
+        let sample = curve.record(2, 5.0, 3.0);
+        assert_eq!(
+            (sample.equity, sample.drawdown, sample.max_drawdown),
+            (8.0, 0.0, 2.0)
+        );
+
+        let sample = curve.record(3, 5.0, -1.0);
+        assert_eq!((sample.drawdown, sample.max_drawdown), (4.0, 4.0));
+
+        let sample = curve.record(4, 7.0, 0.0);
+        assert_eq!((sample.drawdown, sample.max_drawdown), (1.0, 4.0));
+    }
+}
@@ -0,0 +1,409 @@
+//! Scaling out of positions as their markets approach resolution.
+//!
+//! Close to a market's end date spreads widen and the book thins, so an exit
+//! left until the last hours gets expensive, and holding into settlement
+//! carries the full outcome risk. The exit scheduler sells down positions in
+//! markets nearing their end date (from Gamma metadata) along a schedule of
+//! `(time before end, fraction exited)` stages. Each stage is cumulative: with
+//! `24:0.25,6:0.5,1:1` a quarter of the position is gone a day out, half six
+//! hours out and all of it in the final hour. Fractions apply to the largest
+//! size seen since the token entered the schedule, so a partial fill or a
+//! later buy doesn't reset progress. Exits sell at the best bid unless
+//! `RESOLUTION_EXIT_EXECUTION=market`, which sweeps the bids down to
+//! `RESOLUTION_EXIT_MIN_PRICE`.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::audit::{AuditAction, AuditLog};
+use crate::cluster::LeaderElection;
+use crate::execution::{ExecutionStyle, OrderManager, OrderPriority, Side};
+use crate::market::{MarketData, TokenId};
+use crate::metrics::RESOLUTION_EXITS;
+use crate::strategy::{PlacedOrder, SignalMetadata, TradeSignal};
+
+use super::RiskManager;
+
+/// Sells smaller than this are skipped (rounding left-overs)
+const MIN_EXIT_SIZE: f64 = 1e-6;
+
+/// One step of the exit schedule
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExitStage {
+    /// How long before the end date the stage starts
+    pub before: Duration,
+    /// Fraction of the position exited by then (0-1]
+    pub fraction: f64,
+}
+
+/// Exit scheduler settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExitConfig {
+    pub enabled: bool,
+
+    /// Stages, latest last
+    pub schedule: Vec<ExitStage>,
+
+    /// Lowest bid we will sell into
+    pub min_price: f64,
+
+    /// How often positions are checked
+    pub interval: Duration,
+
+    /// Sell at the best bid (limit) or sweep the bids (market)
+    pub style: ExecutionStyle,
+}
+
+impl Default for ExitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            schedule: parse_schedule("24:0.25,6:0.5,1:1").unwrap_or_default(),
+            min_price: 0.01,
+            interval: Duration::from_secs(30),
+            style: ExecutionStyle::Limit,
+        }
+    }
+}
+
+impl ExitConfig {
+    /// Load from `RESOLUTION_EXIT_ENABLED`, `RESOLUTION_EXIT_SCHEDULE`,
+    /// `RESOLUTION_EXIT_MIN_PRICE`, `RESOLUTION_EXIT_INTERVAL_MS` and
+    /// `RESOLUTION_EXIT_EXECUTION`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok();
+        Self {
+            enabled: var("RESOLUTION_EXIT_ENABLED")
+                .map(|v| v.to_lowercase() == "true" || v == "1")
+                .unwrap_or(defaults.enabled),
+            schedule: var("RESOLUTION_EXIT_SCHEDULE")
+                .and_then(|v| match parse_schedule(&v) {
+                    Some(schedule) => Some(schedule),
+                    None => {
+                        warn!(
+                            "[EXIT] Invalid RESOLUTION_EXIT_SCHEDULE '{}', using the default",
+                            v
+                        );
+                        None
+                    }
+                })
+                .unwrap_or(defaults.schedule),
+            min_price: var("RESOLUTION_EXIT_MIN_PRICE")
+                .and_then(|v| v.parse().ok())
+                .filter(|p: &f64| *p >= 0.0 && *p < 1.0)
+                .unwrap_or(defaults.min_price),
+            interval: var("RESOLUTION_EXIT_INTERVAL_MS")
+                .and_then(|v| v.parse().ok())
+                .filter(|ms| *ms > 0)
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.interval),
+            style: var("RESOLUTION_EXIT_EXECUTION")
+                .and_then(|v| ExecutionStyle::parse(&v))
+                .unwrap_or(defaults.style),
+        }
+    }
+
+    /// Cumulative fraction to have exited with `time_left` before the end
+    /// date (negative once it has passed).
+    fn fraction_at(&self, time_left: chrono::Duration) -> f64 {
+        self.schedule
+            .iter()
+            .filter(|stage| {
+                chrono::Duration::from_std(stage.before).is_ok_and(|before| time_left <= before)
+            })
+            .map(|stage| stage.fraction)
+            .fold(0.0, f64::max)
+    }
+}
+
+/// Parse `<hours before end>:<fraction exited>` pairs, comma separated
+/// (e.g. `24:0.25,6:0.5,1:1`). Stages are returned latest last.
+fn parse_schedule(s: &str) -> Option<Vec<ExitStage>> {
+    let mut schedule = s
+        .split(',')
+        .map(str::trim)
+        .filter(|stage| !stage.is_empty())
+        .map(|stage| {
+            let (hours, fraction) = stage.split_once(':')?;
+            let hours: f64 = hours.trim().parse().ok()?;
+            let fraction: f64 = fraction.trim().parse().ok()?;
+            if hours <= 0.0 || fraction <= 0.0 || fraction > 1.0 {
+                return None;
+            }
+            Some(ExitStage {
+                before: Duration::try_from_secs_f64(hours * 3600.0).ok()?,
+                fraction,
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+    if schedule.is_empty() {
+        return None;
+    }
+    schedule.sort_by_key(|stage| std::cmp::Reverse(stage.before));
+    Some(schedule)
+}
+
+/// Shares to sell now: what is held above the stage's remaining target.
+fn shares_to_sell(size: f64, baseline: f64, fraction: f64) -> f64 {
+    let target = baseline * (1.0 - fraction);
+    let excess = size - target;
+    if excess > MIN_EXIT_SIZE {
+        excess
+    } else {
+        0.0
+    }
+}
+
+/// Sells down positions in markets approaching their end date.
+pub struct ExitScheduler {
+    config: ExitConfig,
+    market_data: Arc<MarketData>,
+    risk_manager: Arc<RiskManager>,
+    order_manager: Arc<OrderManager>,
+    leader: Option<Arc<LeaderElection>>,
+    audit: Option<Arc<AuditLog>>,
+    /// Books exits through the strategy engine (None = with the risk
+    /// manager directly)
+    placed_orders: Option<UnboundedSender<PlacedOrder>>,
+    /// Largest size held since each token entered the schedule
+    baselines: Mutex<HashMap<TokenId, f64>>,
+}
+
+impl ExitScheduler {
+    /// Create an exit scheduler.
+    pub fn new(
+        config: ExitConfig,
+        market_data: Arc<MarketData>,
+        risk_manager: Arc<RiskManager>,
+        order_manager: Arc<OrderManager>,
+    ) -> Self {
+        Self {
+            config,
+            market_data,
+            risk_manager,
+            order_manager,
+            leader: None,
+            audit: None,
+            placed_orders: None,
+            baselines: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Only exit while this instance is the leader.
+    pub fn set_leader_election(&mut self, leader: Arc<LeaderElection>) {
+        self.leader = Some(leader);
+    }
+
+    /// Set the audit log used to record exits.
+    pub fn set_audit_log(&mut self, audit: Arc<AuditLog>) {
+        self.audit = Some(audit);
+    }
+
+    /// Book exits through the strategy engine, which records them and,
+    /// with order tracking on, books them once their fills are confirmed.
+    pub fn set_placed_orders(&mut self, placed_orders: UnboundedSender<PlacedOrder>) {
+        self.placed_orders = Some(placed_orders);
+    }
+
+    /// Check positions every interval until cancelled.
+    pub async fn run(self, cancel: CancellationToken) {
+        let schedule: Vec<String> = self
+            .config
+            .schedule
+            .iter()
+            .map(|s| format!("{:.0}% by {:?}", s.fraction * 100.0, s.before))
+            .collect();
+        info!(
+            "[EXIT] Scaling out before resolution: {} (min bid ${:.2}, every {}ms)",
+            schedule.join(", "),
+            self.config.min_price,
+            self.config.interval.as_millis()
+        );
+
+        let mut ticker = tokio::time::interval(self.config.interval);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => self.scale_out(Utc::now()).await,
+                _ = cancel.cancelled() => break,
+            }
+        }
+    }
+
+    /// Sell whatever each position holds above its stage's target.
+    async fn scale_out(&self, now: DateTime<Utc>) {
+        if self.leader.as_ref().is_some_and(|l| !l.is_leader())
+            || self.risk_manager.is_emergency_stopped()
+        {
+            return;
+        }
+
+        let positions = self.risk_manager.get_all_positions();
+        let mut due = Vec::new();
+        {
+            let mut baselines = self.baselines.lock();
+            baselines.retain(|token_id, _| positions.get(token_id).is_some_and(|p| p.size > 0.0));
+            for (token_id, position) in positions.iter().filter(|(_, p)| p.size > 0.0) {
+                let Some(end_date) = self.end_date(token_id) else {
+                    continue;
+                };
+                let fraction = self.config.fraction_at(end_date - now);
+                if fraction <= 0.0 {
+                    baselines.remove(token_id);
+                    continue;
+                }
+                let baseline = baselines.entry(token_id.clone()).or_insert(0.0);
+                *baseline = baseline.max(position.size);
+                // Exits placed but not yet confirmed are already on their way
+                let held = position.size - self.risk_manager.pending_size(token_id, Side::Sell);
+                let shares = shares_to_sell(held, *baseline, fraction);
+                if shares > 0.0 {
+                    due.push((token_id.clone(), shares, fraction, end_date));
+                }
+            }
+        }
+
+        for (token_id, shares, fraction, end_date) in due {
+            self.exit(&token_id, shares, fraction, end_date).await;
+        }
+    }
+
+    /// End date of the market a token trades in
+    fn end_date(&self, token_id: &TokenId) -> Option<DateTime<Utc>> {
+        let market_id = self.market_data.get_market_id(token_id)?;
+        self.market_data.get_pair(&market_id)?.end_date
+    }
+
+    /// Sell `shares` of a token at the best bid, or into the bids down to
+    /// the minimum price.
+    async fn exit(&self, token_id: &TokenId, shares: f64, fraction: f64, end_date: DateTime<Utc>) {
+        let Some(bid) = self.market_data.get_bid(token_id) else {
+            warn!(
+                "[EXIT] No bid for {} - {:.2} shares not exited",
+                token_id, shares
+            );
+            RESOLUTION_EXITS.with_label_values(&["no_price"]).inc();
+            return;
+        };
+        if bid < self.config.min_price {
+            warn!(
+                "[EXIT] {} bids ${:.4} (min ${:.2}) - not exiting",
+                token_id, bid, self.config.min_price
+            );
+            RESOLUTION_EXITS.with_label_values(&["too_cheap"]).inc();
+            return;
+        }
+
+        let placed = match self.config.style {
+            ExecutionStyle::Market => self
+                .order_manager
+                .place_market(
+                    token_id,
+                    Side::Sell,
+                    shares,
+                    self.config.min_price,
+                    OrderPriority::Taker,
+                )
+                .await
+                .map(|order| (order.order_id, order.avg_price, order.size)),
+            ExecutionStyle::Limit => self
+                .order_manager
+                .place_sell(token_id, bid, shares)
+                .await
+                .map(|order_id| (order_id, bid, shares)),
+        };
+        match placed {
+            Ok((order_id, price, shares)) => {
+                let signal = TradeSignal::Sell {
+                    token_id: token_id.clone(),
+                    price,
+                    size: shares,
+                    reason: format!("Resolution exit ({:.0}% stage)", fraction * 100.0),
+                    metadata: SignalMetadata::new(),
+                };
+                match &self.placed_orders {
+                    Some(placed_orders) => {
+                        let _ = placed_orders.send(PlacedOrder {
+                            source: "ResolutionExit",
+                            signal,
+                            order_id: order_id.clone(),
+                        });
+                    }
+                    None => {
+                        self.risk_manager.record_trade(&signal);
+                    }
+                }
+                RESOLUTION_EXITS.with_label_values(&["placed"]).inc();
+                if let Some(audit) = &self.audit {
+                    audit.record(
+                        "exit",
+                        AuditAction::Flatten,
+                        Some(token_id),
+                        format!(
+                            "order {}: sold {:.2} @ ${:.4} ({:.0}% stage, market ends {})",
+                            order_id,
+                            shares,
+                            price,
+                            fraction * 100.0,
+                            end_date.to_rfc3339()
+                        ),
+                    );
+                }
+                info!(
+                    "[EXIT] Order {}: sold {:.2} x {} @ ${:.4} ({:.0}% stage, market ends {})",
+                    order_id,
+                    shares,
+                    token_id,
+                    price,
+                    fraction * 100.0,
+                    end_date.to_rfc3339()
+                );
+            }
+            Err(e) => {
+                RESOLUTION_EXITS.with_label_values(&["failed"]).inc();
+                warn!("[EXIT] Exit of {} failed: {}", token_id, e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_schedule() {
+        let schedule = parse_schedule("1:1, 24:0.25,6:0.5").unwrap();
+        assert_eq!(schedule.len(), 3);
+        assert_eq!(schedule[0].before, Duration::from_secs(24 * 3600));
+        assert_eq!(schedule[2].fraction, 1.0);
+
+        assert!(parse_schedule("").is_none());
+        assert!(parse_schedule("24:1.5").is_none());
+        assert!(parse_schedule("soon:0.5").is_none());
+    }
+
+    #[test]
+    fn test_stages_are_cumulative() {
+        let config = ExitConfig::default();
+        let hours = chrono::Duration::hours;
+        assert_eq!(config.fraction_at(hours(48)), 0.0);
+        assert_eq!(config.fraction_at(hours(12)), 0.25);
+        assert_eq!(config.fraction_at(hours(3)), 0.5);
+        assert_eq!(config.fraction_at(chrono::Duration::minutes(30)), 1.0);
+        assert_eq!(config.fraction_at(hours(-1)), 1.0);
+
+        // 100 shares at the 25% stage leaves 75; a partial exit sells the rest
+        assert_eq!(shares_to_sell(100.0, 100.0, 0.25), 25.0);
+        assert_eq!(shares_to_sell(80.0, 100.0, 0.25), 5.0);
+        assert_eq!(shares_to_sell(75.0, 100.0, 0.25), 0.0);
+        assert_eq!(shares_to_sell(75.0, 100.0, 0.5), 25.0);
+    }
+}
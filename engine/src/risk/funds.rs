@@ -0,0 +1,289 @@
+//! Account-level funds usage.
+//!
+//! Risk limits cap each position, but nothing stops the account as a whole
+//! from running out of collateral, after which every buy fails with an
+//! insufficient-balance rejection. Every interval the monitor compares the
+//! wallet balance with what is committed: the cost basis of open positions
+//! (already spent from the balance) and the unfilled notional of resting
+//! buy orders (not yet spent, but reserved by the exchange). Free balance
+//! is the wallet balance less open orders. A Slack alert fires when it
+//! drops below `FUNDS_MIN_FREE_USD`, and once more when it recovers.
+//!
+//! In dry run there is no wallet; the balance is `FUNDS_PAPER_BALANCE_USD`
+//! plus realized P&L, less the cost of open positions.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::execution::{ExecutionResult, OrderManager};
+use crate::metrics::{FUNDS_BALANCE, FUNDS_COMMITTED, FUNDS_FREE, FUNDS_UTILIZATION};
+use crate::notifications::{ErrorAlert, SlackNotifier};
+use crate::risk::RiskManager;
+
+/// Low free balance clears once it is back above this multiple of the floor
+const RECOVERY_RATIO: f64 = 1.2;
+
+/// Funds monitor settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct FundsConfig {
+    pub enabled: bool,
+    /// Time between balance checks
+    pub interval: Duration,
+    /// Alert when free balance drops below this (zero = no alerts)
+    pub min_free_balance: f64,
+    /// Starting balance assumed in dry run
+    pub paper_balance: f64,
+}
+
+impl Default for FundsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval: Duration::from_secs(30),
+            min_free_balance: 50.0,
+            paper_balance: 1000.0,
+        }
+    }
+}
+
+impl FundsConfig {
+    /// Load from `FUNDS_MONITOR_ENABLED`, `FUNDS_CHECK_INTERVAL_MS`,
+    /// `FUNDS_MIN_FREE_USD` and `FUNDS_PAPER_BALANCE_USD`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok();
+        Self {
+            enabled: var("FUNDS_MONITOR_ENABLED")
+                .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+                .unwrap_or(defaults.enabled),
+            interval: var("FUNDS_CHECK_INTERVAL_MS")
+                .and_then(|v| v.parse().ok())
+                .filter(|ms| *ms > 0)
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.interval),
+            min_free_balance: var("FUNDS_MIN_FREE_USD")
+                .and_then(|v| v.parse().ok())
+                .filter(|usd: &f64| *usd >= 0.0)
+                .unwrap_or(defaults.min_free_balance),
+            paper_balance: var("FUNDS_PAPER_BALANCE_USD")
+                .and_then(|v| v.parse().ok())
+                .filter(|usd: &f64| *usd >= 0.0)
+                .unwrap_or(defaults.paper_balance),
+        }
+    }
+}
+
+/// Balance against what is committed, in USD
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct FundsUsage {
+    /// Collateral in the wallet
+    pub balance: f64,
+    /// Cost basis of open positions
+    pub positions: f64,
+    /// Unfilled notional of resting buy orders
+    pub open_orders: f64,
+    /// Balance not reserved by open orders
+    pub free: f64,
+    /// Positions and open orders as a share of balance plus positions
+    pub utilization: f64,
+}
+
+impl FundsUsage {
+    pub fn new(balance: f64, positions: f64, open_orders: f64) -> Self {
+        let total = balance + positions;
+        let utilization = if total > 0.0 {
+            ((positions + open_orders) / total).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        Self {
+            balance,
+            positions,
+            open_orders,
+            free: balance - open_orders,
+            utilization,
+        }
+    }
+}
+
+/// Tracks committed notional and alerts on low free balance.
+pub struct FundsMonitor {
+    config: FundsConfig,
+    order_manager: Arc<OrderManager>,
+    risk_manager: Arc<RiskManager>,
+    /// Unfilled notional of each resting buy order, by order ID
+    open_orders: Mutex<HashMap<String, f64>>,
+    /// Usage at the last balance check
+    last: Mutex<Option<FundsUsage>>,
+    /// Set while free balance is below the floor
+    low: AtomicBool,
+    slack: Option<Arc<SlackNotifier>>,
+}
+
+impl FundsMonitor {
+    pub fn new(
+        config: FundsConfig,
+        order_manager: Arc<OrderManager>,
+        risk_manager: Arc<RiskManager>,
+    ) -> Self {
+        Self {
+            config,
+            order_manager,
+            risk_manager,
+            open_orders: Mutex::new(HashMap::new()),
+            last: Mutex::new(None),
+            low: AtomicBool::new(false),
+            slack: None,
+        }
+    }
+
+    /// Set the Slack notifier used for low-balance alerts.
+    pub fn set_slack_notifier(&mut self, notifier: Arc<SlackNotifier>) {
+        self.slack = Some(notifier);
+    }
+
+    /// Record the unfilled notional of a resting buy order; zero removes it.
+    pub fn set_open_order(&self, order_id: &str, notional: f64) {
+        let mut open_orders = self.open_orders.lock();
+        if notional > 0.0 {
+            open_orders.insert(order_id.to_string(), notional);
+        } else {
+            open_orders.remove(order_id);
+        }
+    }
+
+    /// Unfilled notional of all resting buy orders
+    pub fn open_order_notional(&self) -> f64 {
+        self.open_orders.lock().values().sum()
+    }
+
+    /// Usage at the last balance check (None before the first)
+    pub fn last_usage(&self) -> Option<FundsUsage> {
+        *self.last.lock()
+    }
+
+    /// Cost basis of long positions
+    fn position_cost(&self) -> f64 {
+        self.risk_manager
+            .get_all_positions()
+            .values()
+            .filter(|p| p.size > 0.0)
+            .map(|p| p.size * p.avg_cost)
+            .sum()
+    }
+
+    /// Fetch the balance and work out current usage.
+    pub async fn usage(&self) -> ExecutionResult<FundsUsage> {
+        let positions = self.position_cost();
+        let balance = match self.order_manager.collateral_balance().await? {
+            Some(balance) => balance,
+            None => self.config.paper_balance + self.risk_manager.get_realized_pnl() - positions,
+        };
+        Ok(FundsUsage::new(
+            balance,
+            positions,
+            self.open_order_notional(),
+        ))
+    }
+
+    /// Check funds every interval until cancelled.
+    pub async fn run(self: Arc<Self>, cancellation_token: CancellationToken) {
+        info!(
+            "[FUNDS] Monitoring funds every {:?} (alert below ${:.2} free)",
+            self.config.interval, self.config.min_free_balance
+        );
+        let mut ticker = tokio::time::interval(self.config.interval);
+        loop {
+            tokio::select! {
+                _ = cancellation_token.cancelled() => return,
+                _ = ticker.tick() => {}
+            }
+            match self.usage().await {
+                Ok(usage) => self.record(&usage),
+                Err(e) => warn!("[FUNDS] Balance check failed: {}", e),
+            }
+        }
+    }
+
+    /// Export usage and alert when free balance crosses the floor.
+    fn record(&self, usage: &FundsUsage) {
+        *self.last.lock() = Some(*usage);
+        FUNDS_BALANCE.set(usage.balance);
+        FUNDS_COMMITTED
+            .with_label_values(&["positions"])
+            .set(usage.positions);
+        FUNDS_COMMITTED
+            .with_label_values(&["open_orders"])
+            .set(usage.open_orders);
+        FUNDS_FREE.set(usage.free);
+        FUNDS_UTILIZATION.set(usage.utilization);
+        debug!(
+            "[FUNDS] Balance ${:.2}, free ${:.2}, utilization {:.0}%",
+            usage.balance,
+            usage.free,
+            usage.utilization * 100.0
+        );
+
+        let floor = self.config.min_free_balance;
+        if floor <= 0.0 {
+            return;
+        }
+        let low = self.low.load(Ordering::Relaxed);
+        if !low && usage.free < floor {
+            self.low.store(true, Ordering::Relaxed);
+            let message = format!(
+                "free balance ${:.2} below ${:.2} (balance ${:.2}, open orders ${:.2}, positions ${:.2}, utilization {:.0}%)",
+                usage.free,
+                floor,
+                usage.balance,
+                usage.open_orders,
+                usage.positions,
+                usage.utilization * 100.0
+            );
+            warn!("[FUNDS] Low balance: {}", message);
+            self.alert("FUNDS_LOW", message);
+        } else if low && usage.free >= floor * RECOVERY_RATIO {
+            self.low.store(false, Ordering::Relaxed);
+            let message = format!("free balance back to ${:.2}", usage.free);
+            info!("[FUNDS] Recovered: {}", message);
+            self.alert("FUNDS_RECOVERED", message);
+        }
+    }
+
+    fn alert(&self, error_type: &str, message: String) {
+        if let Some(slack) = &self.slack {
+            slack.notify_error(ErrorAlert {
+                source: "funds-monitor".to_string(),
+                error_type: error_type.to_string(),
+                message,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_counts_positions_and_open_orders() {
+        // $600 in the wallet after buying $400 of positions; $100 bid resting
+        let usage = FundsUsage::new(600.0, 400.0, 100.0);
+        assert!((usage.free - 500.0).abs() < 1e-9);
+        assert!((usage.utilization - 0.5).abs() < 1e-9);
+
+        // Orders beyond the balance leave nothing free
+        let usage = FundsUsage::new(50.0, 0.0, 80.0);
+        assert!(usage.free < 0.0);
+        assert!((usage.utilization - 1.0).abs() < 1e-9);
+
+        assert_eq!(FundsUsage::new(0.0, 0.0, 0.0).utilization, 1.0);
+    }
+}
@@ -0,0 +1,332 @@
+//! Automatic hedging of one-sided positions.
+//!
+//! A failed arbitrage leg or a Sniper position leaves us holding one side of
+//! a market. Buying the same number of complement shares turns that into a
+//! YES+NO pair that pays out $1 whatever the outcome, so the directional risk
+//! becomes a known loss (or gain) of `1 - avg_cost - complement_price` per
+//! share. The hedger does this for any token whose unhedged cost basis is
+//! above `HEDGE_EXPOSURE_THRESHOLD`. By default hedges are market orders
+//! that take whatever complement liquidity exists up to `HEDGE_MAX_PRICE`;
+//! `HEDGE_EXECUTION=limit` buys at the best ask only.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::mpsc::UnboundedSender;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::cluster::LeaderElection;
+use crate::execution::{ExecutionStyle, OrderManager, OrderPriority, Side};
+use crate::market::{MarketData, TokenId};
+use crate::metrics::HEDGES;
+use crate::strategy::{PlacedOrder, SignalMetadata, TradeSignal};
+
+use super::manager::Position;
+use super::RiskManager;
+
+/// Hedger settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct HedgeConfig {
+    pub enabled: bool,
+
+    /// Unhedged cost basis (USD) above which a position is hedged
+    pub exposure_threshold: f64,
+
+    /// Highest complement price we will pay
+    pub max_price: f64,
+
+    /// How often positions are checked
+    pub interval: Duration,
+
+    /// Sweep the book (market) or buy at the best ask (limit)
+    pub style: ExecutionStyle,
+}
+
+impl Default for HedgeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            exposure_threshold: 50.0,
+            max_price: 0.99,
+            interval: Duration::from_secs(5),
+            style: ExecutionStyle::Market,
+        }
+    }
+}
+
+impl HedgeConfig {
+    /// Load from `HEDGE_ENABLED`, `HEDGE_EXPOSURE_THRESHOLD`,
+    /// `HEDGE_MAX_PRICE`, `HEDGE_INTERVAL_MS` and `HEDGE_EXECUTION`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            enabled: std::env::var("HEDGE_ENABLED")
+                .map(|v| v.to_lowercase() == "true" || v == "1")
+                .unwrap_or(defaults.enabled),
+            exposure_threshold: std::env::var("HEDGE_EXPOSURE_THRESHOLD")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|t: &f64| t.is_finite() && *t >= 0.0)
+                .unwrap_or(defaults.exposure_threshold),
+            max_price: std::env::var("HEDGE_MAX_PRICE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|p: &f64| *p > 0.0 && *p <= 1.0)
+                .unwrap_or(defaults.max_price),
+            interval: std::env::var("HEDGE_INTERVAL_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|ms| *ms > 0)
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.interval),
+            style: std::env::var("HEDGE_EXECUTION")
+                .ok()
+                .and_then(|v| ExecutionStyle::parse(&v))
+                .unwrap_or(defaults.style),
+        }
+    }
+}
+
+/// A position not offset by its complement
+#[derive(Debug, Clone, PartialEq)]
+struct Exposure {
+    token_id: TokenId,
+    complement: TokenId,
+    /// Shares held beyond the complement position
+    shares: f64,
+    avg_cost: f64,
+}
+
+impl Exposure {
+    /// Cost basis of the unhedged shares
+    fn notional(&self) -> f64 {
+        self.shares * self.avg_cost
+    }
+}
+
+/// Positions whose size exceeds their complement's.
+fn unhedged(
+    positions: &HashMap<TokenId, Position>,
+    complement: impl Fn(&TokenId) -> Option<TokenId>,
+) -> Vec<Exposure> {
+    positions
+        .iter()
+        .filter_map(|(token_id, position)| {
+            let complement = complement(token_id)?;
+            let hedged = positions.get(&complement).map_or(0.0, |p| p.size.max(0.0));
+            let shares = position.size - hedged;
+            (shares > 0.0).then(|| Exposure {
+                token_id: token_id.clone(),
+                complement,
+                shares,
+                avg_cost: position.avg_cost,
+            })
+        })
+        .collect()
+}
+
+/// Buys complement tokens to neutralise one-sided positions.
+pub struct Hedger {
+    config: HedgeConfig,
+    market_data: Arc<MarketData>,
+    risk_manager: Arc<RiskManager>,
+    order_manager: Arc<OrderManager>,
+    leader: Option<Arc<LeaderElection>>,
+    /// Books hedges through the strategy engine (None = with the risk
+    /// manager directly)
+    placed_orders: Option<UnboundedSender<PlacedOrder>>,
+}
+
+impl Hedger {
+    /// Create a hedger.
+    pub fn new(
+        config: HedgeConfig,
+        market_data: Arc<MarketData>,
+        risk_manager: Arc<RiskManager>,
+        order_manager: Arc<OrderManager>,
+    ) -> Self {
+        Self {
+            config,
+            market_data,
+            risk_manager,
+            order_manager,
+            leader: None,
+            placed_orders: None,
+        }
+    }
+
+    /// Only hedge while this instance is the leader.
+    pub fn set_leader_election(&mut self, leader: Arc<LeaderElection>) {
+        self.leader = Some(leader);
+    }
+
+    /// Book hedges through the strategy engine, which records them and,
+    /// with order tracking on, books them once their fills are confirmed.
+    pub fn set_placed_orders(&mut self, placed_orders: UnboundedSender<PlacedOrder>) {
+        self.placed_orders = Some(placed_orders);
+    }
+
+    /// Check positions every interval until cancelled.
+    pub async fn run(self, cancel: CancellationToken) {
+        info!(
+            "[HEDGE] Hedging positions above ${:.2} exposure (max price ${:.2}, every {}ms)",
+            self.config.exposure_threshold,
+            self.config.max_price,
+            self.config.interval.as_millis()
+        );
+
+        let mut ticker = tokio::time::interval(self.config.interval);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => self.hedge_exposures().await,
+                _ = cancel.cancelled() => break,
+            }
+        }
+    }
+
+    /// Hedge every exposure above the threshold.
+    async fn hedge_exposures(&self) {
+        if self.leader.as_ref().is_some_and(|l| !l.is_leader())
+            || self.risk_manager.is_emergency_stopped()
+        {
+            return;
+        }
+
+        let positions = self.risk_manager.get_all_positions();
+        let exposures = unhedged(&positions, |token| self.market_data.get_complement(token));
+        for mut exposure in exposures {
+            // Hedges placed but not yet confirmed already cover part of it
+            exposure.shares -= self
+                .risk_manager
+                .pending_size(&exposure.complement, Side::Buy);
+            if exposure.notional() > self.config.exposure_threshold {
+                self.hedge(&exposure).await;
+            }
+        }
+    }
+
+    /// Buy the complement of one exposure: sweep the book up to the price
+    /// limit, or a limit order at the best ask.
+    async fn hedge(&self, exposure: &Exposure) {
+        let Some(ask) = self.market_data.get_ask(&exposure.complement) else {
+            warn!(
+                "[HEDGE] No ask for complement of {} - ${:.2} exposure unhedged",
+                exposure.token_id,
+                exposure.notional()
+            );
+            HEDGES.with_label_values(&["no_price"]).inc();
+            return;
+        };
+        if ask > self.config.max_price {
+            warn!(
+                "[HEDGE] Complement of {} asks ${:.4} (limit ${:.2}) - not hedging",
+                exposure.token_id, ask, self.config.max_price
+            );
+            HEDGES.with_label_values(&["too_expensive"]).inc();
+            return;
+        }
+
+        let placed = match self.config.style {
+            ExecutionStyle::Market => self
+                .order_manager
+                .place_market(
+                    &exposure.complement,
+                    Side::Buy,
+                    exposure.shares,
+                    self.config.max_price,
+                    OrderPriority::Urgent,
+                )
+                .await
+                .map(|order| (order.order_id, order.avg_price, order.size)),
+            ExecutionStyle::Limit => self
+                .order_manager
+                .place_buy_with_priority(
+                    &exposure.complement,
+                    ask,
+                    exposure.shares,
+                    OrderPriority::Urgent,
+                )
+                .await
+                .map(|order_id| (order_id, ask, exposure.shares)),
+        };
+        match placed {
+            Ok((order_id, price, shares)) => {
+                let signal = TradeSignal::Buy {
+                    token_id: exposure.complement.clone(),
+                    price,
+                    size: shares,
+                    reason: format!("Hedge for {}", exposure.token_id),
+                    metadata: SignalMetadata::new(),
+                };
+                match &self.placed_orders {
+                    Some(placed_orders) => {
+                        let _ = placed_orders.send(PlacedOrder {
+                            source: "Hedge",
+                            signal,
+                            order_id: order_id.clone(),
+                        });
+                    }
+                    None => {
+                        self.risk_manager.record_trade(&signal);
+                    }
+                }
+                HEDGES.with_label_values(&["placed"]).inc();
+                info!(
+                    "[HEDGE] Order {}: bought {:.2} x {} @ ${:.4} against {} (locked P&L ${:.2})",
+                    order_id,
+                    shares,
+                    exposure.complement,
+                    price,
+                    exposure.token_id,
+                    (1.0 - exposure.avg_cost - price) * shares
+                );
+            }
+            Err(e) => {
+                HEDGES.with_label_values(&["failed"]).inc();
+                warn!("[HEDGE] Hedge for {} failed: {}", exposure.token_id, e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(size: f64, avg_cost: f64) -> Position {
+        Position {
+            size,
+            avg_cost,
+            realized_pnl: 0.0,
+        }
+    }
+
+    fn complement(token: &TokenId) -> Option<TokenId> {
+        match token.as_str() {
+            "yes" => Some("no".into()),
+            "no" => Some("yes".into()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_only_the_uncovered_side_is_exposed() {
+        let mut positions = HashMap::new();
+        positions.insert("yes".to_string(), position(100.0, 0.60));
+        positions.insert("no".to_string(), position(40.0, 0.35));
+        positions.insert("orphan".to_string(), position(10.0, 0.5));
+
+        let exposures = unhedged(&positions, complement);
+        assert_eq!(exposures.len(), 1);
+        assert_eq!(exposures[0].token_id, "yes");
+        assert_eq!(exposures[0].complement, "no");
+        assert_eq!(exposures[0].shares, 60.0);
+        assert!((exposures[0].notional() - 36.0).abs() < 1e-9);
+
+        // A fully paired position has no exposure
+        positions.insert("no".to_string(), position(100.0, 0.35));
+        assert!(unhedged(&positions, complement).is_empty());
+    }
+}
@@ -1,14 +1,23 @@
 //! Risk Manager - Position limits and daily loss tracking.
 
+use chrono::Utc;
 use parking_lot::RwLock;
-use std::collections::HashMap;
-use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
 use tracing::{info, warn};
 
-use crate::config::RiskConfig;
-use crate::market::TokenId;
-use crate::metrics::RISK_REJECTIONS;
-use crate::strategy::TradeSignal;
+use crate::audit::{AuditAction, AuditLog};
+use crate::config::{MarketBudget, RiskConfig};
+use crate::execution::Side;
+use crate::market::{
+    LiquidityTier, MarketCorrelations, MarketData, MarketId, MarketLiquidity, TokenId,
+};
+use crate::metrics::{RISK_REJECTIONS, STRATEGY_DAILY_PNL};
+use crate::strategy::{CostModel, Leg, TradeSignal};
+use crate::timezone::TradingTimezone;
 
 /// Position tracking for a single token.
 #[allow(dead_code)]
@@ -26,6 +35,51 @@ struct DailyStats {
     volume: f64,
 }
 
+/// Daily trade count and turnover for a single market.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MarketUsage {
+    pub trades: u64,
+    pub turnover: f64,
+}
+
+/// Size of placed orders on a token that are not yet confirmed filled.
+#[derive(Debug, Default, Clone, Copy)]
+struct PendingOrders {
+    buy: f64,
+    sell: f64,
+    /// Cost of the pending buys at their limit prices
+    buy_cost: f64,
+}
+
+/// Daily trade count, turnover, and realized P&L for a single strategy
+/// (or named strategy instance).
+#[derive(Debug, Default, Clone, Copy)]
+pub struct StrategyUsage {
+    pub trades: u64,
+    pub turnover: f64,
+    pub realized_pnl: f64,
+}
+
+/// Outcome of a single risk check, as reported by `RiskManager::evaluate`.
+#[derive(Debug, Clone, Serialize)]
+pub struct RiskCheck {
+    /// Rejection label, matching the `poly_risk_rejections_total` reason
+    pub name: &'static str,
+    pub passed: bool,
+    /// Why the check failed (None when it passed)
+    pub detail: Option<String>,
+}
+
+impl RiskCheck {
+    fn new(name: &'static str, passed: bool, detail: impl FnOnce() -> String) -> Self {
+        Self {
+            name,
+            passed,
+            detail: (!passed).then(detail),
+        }
+    }
+}
+
 /// Risk manager for position and loss limits.
 ///
 /// Uses atomic for daily P&L to avoid lock contention on the hot path.
@@ -33,17 +87,48 @@ struct DailyStats {
 pub struct RiskManager {
     config: RiskConfig,
     positions: RwLock<HashMap<TokenId, Position>>,
+    /// Placed orders awaiting fill confirmation, counted against the
+    /// position and exposure limits as if they fill
+    pending: RwLock<HashMap<TokenId, PendingOrders>>,
     daily_stats: RwLock<DailyStats>,
+    /// Daily trade count and turnover per market (reset with daily stats)
+    market_usage: RwLock<HashMap<MarketId, MarketUsage>>,
+    /// Daily trade count, turnover, and realized P&L per strategy name
+    strategy_usage: RwLock<HashMap<String, StrategyUsage>>,
+    /// Market data for resolving tokens to markets and categories
+    market_data: Option<Arc<MarketData>>,
     /// Daily P&L in microdollars (1 USD = 1_000_000 microdollars) for atomic ops
     daily_pnl_micro: AtomicI64,
+    /// Realized P&L since startup in microdollars (never reset)
+    total_pnl_micro: AtomicI64,
+    /// Combined daily P&L of the other shards in microdollars (sharded mode)
+    peer_pnl_micro: AtomicI64,
     /// Emergency stop flag - when true, all trading is halted
     #[allow(dead_code)]
     emergency_stop: AtomicBool,
+    /// Set once the daily loss limit trips, so the halt is audited once per day
+    daily_loss_halted: AtomicBool,
+    /// Audit trail for automated interventions
+    audit: Option<Arc<AuditLog>>,
+    /// Fees and slippage netted out of arbitrage profit
+    cost_model: CostModel,
+    /// Liquidity tiers and depth caps per market
+    liquidity: Option<Arc<MarketLiquidity>>,
+    /// Correlated exposure groups and their cost basis cap
+    correlations: Option<Arc<MarketCorrelations>>,
+    /// Multiplier on `max_position` from the VaR budget (f64 bits, 1.0 = none)
+    position_scale: AtomicU64,
+    /// Most basket legs the order manager sends in one request (None: no
+    /// cap, batches are simulated)
+    batch_limit: Option<usize>,
 }
 
 /// Conversion factor: 1 USD = 1_000_000 microdollars
 const MICRO_PER_DOLLAR: f64 = 1_000_000.0;
 
+/// Shaved off notional-derived sizes so `price * size` never rounds above a limit
+const SIZE_EPSILON: f64 = 1e-9;
+
 impl RiskManager {
     /// Create a new risk manager.
     pub fn new(config: RiskConfig) -> Self {
@@ -55,102 +140,654 @@ impl RiskManager {
         Self {
             config,
             positions: RwLock::new(HashMap::new()),
+            pending: RwLock::new(HashMap::new()),
             daily_stats: RwLock::new(DailyStats::default()),
+            market_usage: RwLock::new(HashMap::new()),
+            strategy_usage: RwLock::new(HashMap::new()),
+            market_data: None,
             daily_pnl_micro: AtomicI64::new(0),
+            total_pnl_micro: AtomicI64::new(0),
+            peer_pnl_micro: AtomicI64::new(0),
             emergency_stop: AtomicBool::new(false),
+            daily_loss_halted: AtomicBool::new(false),
+            audit: None,
+            cost_model: CostModel::default(),
+            liquidity: None,
+            correlations: None,
+            position_scale: AtomicU64::new(1.0f64.to_bits()),
+            batch_limit: None,
         }
     }
 
+    /// Set the cost model used to book arbitrage profit net of costs.
+    pub fn set_cost_model(&mut self, cost_model: CostModel) {
+        self.cost_model = cost_model;
+    }
+
+    /// Set the audit log used to record automated trading halts.
+    pub fn set_audit_log(&mut self, audit: Arc<AuditLog>) {
+        self.audit = Some(audit);
+    }
+
+    /// Set market data used to resolve tokens to markets and categories.
+    ///
+    /// Without market data, per-market budgets are tracked per token and
+    /// category overrides are never applied.
+    pub fn set_market_data(&mut self, market_data: Arc<MarketData>) {
+        self.market_data = Some(market_data);
+    }
+
+    /// Set the liquidity scores used to cap order size at real depth and
+    /// refuse markets below the minimum tier.
+    pub fn set_liquidity(&mut self, liquidity: Arc<MarketLiquidity>) {
+        self.liquidity = Some(liquidity);
+    }
+
+    /// Set the correlation groups whose combined open exposure is capped.
+    pub fn set_correlations(&mut self, correlations: Arc<MarketCorrelations>) {
+        self.correlations = Some(correlations);
+    }
+
+    /// Set how many legs the order manager can send in one batch request;
+    /// atomic baskets with more legs are refused.
+    pub fn set_batch_limit(&mut self, limit: Option<usize>) {
+        self.batch_limit = limit;
+    }
+
+    /// Liquidity tier of the market a token belongs to, if scored.
+    #[allow(dead_code)]
+    pub fn liquidity_tier(&self, token_id: &TokenId) -> Option<LiquidityTier> {
+        let liquidity = self.liquidity.as_ref()?;
+        let (market_id, _) = self.market_budget_for(token_id);
+        liquidity.score(&market_id).map(|s| s.tier)
+    }
+
+    /// Resolve the market a token belongs to and the budget that applies to it.
+    fn market_budget_for(&self, token_id: &TokenId) -> (MarketId, MarketBudget) {
+        let Some(market_data) = &self.market_data else {
+            return (token_id.clone(), self.config.market_budget);
+        };
+
+        let market_id = market_data
+            .get_market_id(token_id)
+            .unwrap_or_else(|| token_id.clone());
+
+        let budget = market_data
+            .get_pair(&market_id)
+            .and_then(|pair| pair.category)
+            .and_then(|category| {
+                self.config
+                    .category_budgets
+                    .get(&category.to_lowercase())
+                    .copied()
+            })
+            .unwrap_or(self.config.market_budget);
+
+        (market_id, budget)
+    }
+
     /// Check if a signal passes risk checks.
     pub fn check_signal(&self, signal: &TradeSignal) -> bool {
-        // Check emergency stop FIRST - highest priority safety check
-        if self.emergency_stop.load(Ordering::SeqCst) {
-            warn!("[RISK] Signal rejected - Emergency stop is active");
-            RISK_REJECTIONS
-                .with_label_values(&["emergency_stop"])
-                .inc();
-            return false;
-        }
-
-        // Check daily loss limit using atomic (no lock needed!)
-        let pnl = self.daily_pnl_micro.load(Ordering::Relaxed) as f64 / MICRO_PER_DOLLAR;
-        if pnl < -self.config.max_daily_loss {
-            warn!(
-                "Daily loss limit reached: ${:.2} < -${}",
-                pnl, self.config.max_daily_loss
-            );
-            RISK_REJECTIONS
-                .with_label_values(&["daily_loss_limit"])
-                .inc();
-            return false;
-        }
-
-        // Check notional limit
+        let Some(failed) = self.evaluate(signal).into_iter().find(|c| !c.passed) else {
+            return true;
+        };
+
+        let detail = failed.detail.unwrap_or_default();
+        warn!("[RISK] Signal rejected - {}", detail);
+        RISK_REJECTIONS.with_label_values(&[failed.name]).inc();
+
+        if failed.name == "daily_loss_limit"
+            && !self.daily_loss_halted.swap(true, Ordering::SeqCst)
+        {
+            if let Some(audit) = &self.audit {
+                audit.record(
+                    "risk",
+                    AuditAction::AutoDisable,
+                    None,
+                    detail.to_lowercase(),
+                );
+            }
+        }
+        false
+    }
+
+    /// Run every risk check that applies to a signal without side effects.
+    ///
+    /// Checks are returned in the order `check_signal` applies them; a signal
+    /// passes when all of them pass. Cancels have no checks.
+    pub fn evaluate(&self, signal: &TradeSignal) -> Vec<RiskCheck> {
+        let mut checks = Vec::new();
+
+        // Cancelling only ever reduces exposure
+        if matches!(signal, TradeSignal::Cancel { .. }) {
+            return checks;
+        }
+
+        // Emergency stop FIRST - highest priority safety check
+        let stopped = self.emergency_stop.load(Ordering::SeqCst);
+        checks.push(RiskCheck::new(
+            "emergency_stop",
+            !stopped,
+            || "Emergency stop is active".to_string(),
+        ));
+
+        // Daily loss limit using atomics (no lock needed!)
+        let pnl = self.limit_pnl();
+        checks.push(RiskCheck::new(
+            "daily_loss_limit",
+            pnl >= -self.config.max_daily_loss,
+            || format!("Daily loss limit reached: ${:.2} < -${}", pnl, self.config.max_daily_loss),
+        ));
+
         let notional = signal.notional();
-        if notional > self.config.max_notional {
-            warn!(
-                "Notional limit exceeded: ${:.2} > ${}",
-                notional, self.config.max_notional
-            );
-            RISK_REJECTIONS
-                .with_label_values(&["notional_limit"])
-                .inc();
-            return false;
-        }
-
-        // Check position size
+        checks.push(RiskCheck::new(
+            "notional_limit",
+            notional <= self.config.max_notional,
+            || format!("Notional limit exceeded: ${:.2} > ${}", notional, self.config.max_notional),
+        ));
+
+        // Per-market daily trade count and turnover budget
+        let (market_id, budget) = self.market_budget_for(signal.token_id());
+        let usage = self
+            .market_usage
+            .read()
+            .get(&market_id)
+            .copied()
+            .unwrap_or_default();
+        if budget.max_daily_trades > 0 {
+            checks.push(RiskCheck::new(
+                "market_trade_limit",
+                usage.trades < budget.max_daily_trades,
+                || {
+                    format!(
+                        "Market trade limit reached for {}: {} >= {}",
+                        market_id, usage.trades, budget.max_daily_trades
+                    )
+                },
+            ));
+        }
+        if budget.max_daily_turnover > 0.0 {
+            checks.push(RiskCheck::new(
+                "market_turnover_limit",
+                usage.turnover + notional <= budget.max_daily_turnover,
+                || {
+                    format!(
+                        "Market turnover limit exceeded for {}: ${:.2} + ${:.2} > ${}",
+                        market_id, usage.turnover, notional, budget.max_daily_turnover
+                    )
+                },
+            ));
+        }
+
+        // Entries must fit the market's real depth (exits are never blocked)
+        let is_exit = match signal {
+            TradeSignal::Sell { .. }
+            | TradeSignal::Arbitrage {
+                side: Side::Sell,
+                ..
+            } => true,
+            TradeSignal::Basket { legs, .. } => legs.iter().all(|l| l.side == Side::Sell),
+            _ => false,
+        };
+        if let (Some(liquidity), false) = (&self.liquidity, is_exit) {
+            checks.push(RiskCheck::new(
+                "liquidity_tier",
+                !liquidity.below_min_tier(&market_id),
+                || {
+                    format!(
+                        "Market {} is below liquidity tier {}",
+                        market_id,
+                        liquidity.config().min_tier
+                    )
+                },
+            ));
+            if let Some(cap) = liquidity.max_notional(&market_id) {
+                checks.push(RiskCheck::new("liquidity_depth", notional <= cap, || {
+                    format!(
+                        "Order exceeds market depth for {}: ${:.2} > ${:.2}",
+                        market_id, notional, cap
+                    )
+                }));
+            }
+        }
+
+        // Open cost basis of the market's correlation group
+        if let TradeSignal::Buy {
+            token_id,
+            price,
+            size,
+            ..
+        }
+        | TradeSignal::Bid {
+            token_id,
+            price,
+            size,
+            ..
+        } = signal
+        {
+            if let Some((group, exposure, cap)) = self.group_exposure(&market_id) {
+                let added = price * (size - self.complement_open(token_id)).max(0.0);
+                checks.push(RiskCheck::new(
+                    "group_exposure",
+                    exposure + added <= cap,
+                    || {
+                        format!(
+                            "Exposure limit exceeded for correlated group {}: ${:.2} + ${:.2} > ${}",
+                            group, exposure, added, cap
+                        )
+                    },
+                ));
+            }
+        }
+
+        // Position size (a resting bid is checked as if it fills)
         match signal {
-            TradeSignal::Buy { token_id, size, .. } => {
-                let positions = self.positions.read();
-                let current = positions.get(token_id).map(|p| p.size).unwrap_or(0.0);
-                if current + size > self.config.max_position {
-                    warn!(
-                        "Position limit exceeded: {} + {} > {}",
-                        current, size, self.config.max_position
-                    );
-                    RISK_REJECTIONS
-                        .with_label_values(&["position_limit"])
-                        .inc();
-                    return false;
-                }
+            TradeSignal::Buy { token_id, size, .. } | TradeSignal::Bid { token_id, size, .. } => {
+                let current = self.committed_long(token_id);
+                checks.push(RiskCheck::new(
+                    "position_limit",
+                    current + size <= self.max_position(),
+                    || {
+                        format!(
+                            "Position limit exceeded: {} + {} > {}",
+                            current,
+                            size,
+                            self.max_position()
+                        )
+                    },
+                ));
             }
             TradeSignal::Sell { token_id, size, .. } => {
-                let positions = self.positions.read();
-                let current = positions.get(token_id).map(|p| p.size).unwrap_or(0.0);
-                if current < *size {
-                    warn!("Cannot sell more than owned: {} < {}", current, size);
-                    RISK_REJECTIONS
-                        .with_label_values(&["insufficient_position"])
-                        .inc();
-                    return false;
-                }
+                let current = self.sellable(token_id);
+                checks.push(RiskCheck::new(
+                    "insufficient_position",
+                    current >= *size,
+                    || format!("Cannot sell more than owned: {} < {}", current, size),
+                ));
+            }
+            TradeSignal::Arbitrage {
+                yes_token,
+                no_token,
+                size,
+                side: Side::Sell,
+                ..
+            } => {
+                // Selling a pair needs both legs in inventory
+                let current = self.sellable(yes_token).min(self.sellable(no_token));
+                checks.push(RiskCheck::new(
+                    "insufficient_position",
+                    current >= *size,
+                    || format!("Cannot sell more pairs than owned: {} < {}", current, size),
+                ));
+                let net_edge = self.cost_model.estimate(signal).net_edge.unwrap_or_default();
+                checks.push(RiskCheck::new("no_edge", net_edge > 0.0, || {
+                    format!("Arbitrage has no edge after costs: ${:.4}", net_edge)
+                }));
             }
-            TradeSignal::Arbitrage { size, .. } => {
+            TradeSignal::Arbitrage {
+                yes_token,
+                no_token,
+                size,
+                ..
+            } => {
                 // For arbitrage, check total position doesn't exceed limit
-                if *size > self.config.max_position {
-                    warn!(
-                        "Arbitrage size exceeds limit: {} > {}",
-                        size, self.config.max_position
-                    );
-                    RISK_REJECTIONS
-                        .with_label_values(&["position_limit"])
-                        .inc();
-                    return false;
+                // (pairs still being bought count towards it)
+                let pending = self.pending_of(yes_token).buy.max(self.pending_of(no_token).buy);
+                checks.push(RiskCheck::new(
+                    "position_limit",
+                    pending + size <= self.max_position(),
+                    || {
+                        format!(
+                            "Arbitrage size exceeds limit: {} + {} > {}",
+                            pending,
+                            size,
+                            self.max_position()
+                        )
+                    },
+                ));
+                // Strategies may net costs differently; lock in only real profit
+                let net_edge = self.cost_model.estimate(signal).net_edge.unwrap_or_default();
+                checks.push(RiskCheck::new("no_edge", net_edge > 0.0, || {
+                    format!("Arbitrage has no edge after costs: ${:.4}", net_edge)
+                }));
+            }
+            TradeSignal::Basket {
+                legs,
+                payout,
+                atomic,
+                ..
+            } => {
+                // Two legs on one token would net against each other
+                let tokens: HashSet<&TokenId> = legs.iter().map(|l| &l.token_id).collect();
+                checks.push(RiskCheck::new(
+                    "basket_legs",
+                    !legs.is_empty() && tokens.len() == legs.len(),
+                    || {
+                        format!(
+                            "Basket legs must trade distinct tokens: {} legs on {} tokens",
+                            legs.len(),
+                            tokens.len()
+                        )
+                    },
+                ));
+                let cap = self.config.max_basket_notional;
+                if cap > 0.0 {
+                    checks.push(RiskCheck::new("basket_notional", notional <= cap, || {
+                        format!(
+                            "Basket notional limit exceeded: ${:.2} > ${}",
+                            notional, cap
+                        )
+                    }));
+                }
+                if self.config.basket_same_group {
+                    let groups = self.basket_groups(legs);
+                    checks.push(RiskCheck::new(
+                        "basket_group",
+                        groups.len() == 1 && !groups.contains(&None),
+                        || {
+                            format!(
+                                "Basket legs span {} markets or groups (unknown markets count apart)",
+                                groups.len()
+                            )
+                        },
+                    ));
+                }
+                if let (true, Some(limit)) = (*atomic, self.batch_limit) {
+                    checks.push(RiskCheck::new("basket_atomic", legs.len() <= limit, || {
+                        format!(
+                            "Atomic basket does not fit one batch: {} legs > {}",
+                            legs.len(),
+                            limit
+                        )
+                    }));
+                }
+
+                // Each leg as if it were sent alone
+                for leg in legs {
+                    checks.push(match leg.side {
+                        Side::Buy => {
+                            let current = self.committed_long(&leg.token_id);
+                            RiskCheck::new(
+                                "position_limit",
+                                current + leg.size <= self.max_position(),
+                                || {
+                                    format!(
+                                        "Position limit exceeded: {} + {} > {}",
+                                        current,
+                                        leg.size,
+                                        self.max_position()
+                                    )
+                                },
+                            )
+                        }
+                        Side::Sell => {
+                            let current = self.sellable(&leg.token_id);
+                            RiskCheck::new("insufficient_position", current >= leg.size, || {
+                                format!("Cannot sell more than owned: {} < {}", current, leg.size)
+                            })
+                        }
+                    });
+                }
+                if payout.is_some() {
+                    let net_edge = self.cost_model.estimate(signal).net_edge.unwrap_or_default();
+                    checks.push(RiskCheck::new("no_edge", net_edge > 0.0, || {
+                        format!("Basket has no edge after costs: ${:.4}", net_edge)
+                    }));
                 }
             }
+            TradeSignal::Cancel { .. } => {}
+        }
+
+        checks
+    }
+
+    /// The markets a basket's legs trade, as their correlation groups where
+    /// grouped (None for a token of unknown market).
+    fn basket_groups(&self, legs: &[Leg]) -> HashSet<Option<MarketId>> {
+        legs.iter()
+            .map(|leg| {
+                let market_id = self.market_data.as_ref()?.get_market_id(&leg.token_id)?;
+                let group = self
+                    .correlations
+                    .as_ref()
+                    .and_then(|c| c.group_of(&market_id));
+                Some(group.unwrap_or(market_id))
+            })
+            .collect()
+    }
+
+    /// Scale the position limit by `scale` (0-1], e.g. while the VaR budget
+    /// is exceeded. 1.0 restores the configured limit.
+    pub fn set_position_scale(&self, scale: f64) {
+        let scale = if scale.is_finite() { scale.clamp(0.0, 1.0) } else { 1.0 };
+        self.position_scale.store(scale.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Position limit after any VaR scaling.
+    fn max_position(&self) -> f64 {
+        self.config.max_position * f64::from_bits(self.position_scale.load(Ordering::Relaxed))
+    }
+
+    /// Correlation group of a market with its open cost basis and cap, when
+    /// the market is grouped and group exposure is capped.
+    ///
+    /// Shares matched by a complement position are not open exposure (see
+    /// `get_unrealized_pnl`); pending buys are, at their limit prices.
+    fn group_exposure(&self, market_id: &MarketId) -> Option<(MarketId, f64, f64)> {
+        let correlations = self.correlations.as_ref()?;
+        let cap = correlations.config().max_group_exposure;
+        if cap <= 0.0 {
+            return None;
+        }
+        let group = correlations.group_of(market_id)?;
+        let market_data = self.market_data.as_ref()?;
+
+        let positions = self.positions.read();
+        let exposure = positions
+            .iter()
+            .filter(|(token_id, _)| {
+                market_data
+                    .get_market_id(token_id)
+                    .and_then(|m| correlations.group_of(&m))
+                    .as_ref()
+                    == Some(&group)
+            })
+            .map(|(token_id, position)| {
+                let hedged = market_data
+                    .get_complement(token_id)
+                    .and_then(|c| positions.get(&c))
+                    .map_or(0.0, |p| p.size.max(0.0));
+                (position.size - hedged).max(0.0) * position.avg_cost
+            })
+            .sum::<f64>();
+        let pending: f64 = self
+            .pending
+            .read()
+            .iter()
+            .filter(|(token_id, _)| {
+                market_data
+                    .get_market_id(token_id)
+                    .and_then(|m| correlations.group_of(&m))
+                    .as_ref()
+                    == Some(&group)
+            })
+            .map(|(_, pending)| pending.buy_cost)
+            .sum();
+        Some((group, exposure + pending, cap))
+    }
+
+    /// Shares of the complement held beyond this token's position: buying
+    /// up to this many hedges rather than adds exposure.
+    fn complement_open(&self, token_id: &TokenId) -> f64 {
+        let Some(market_data) = &self.market_data else {
+            return 0.0;
+        };
+        let Some(complement) = market_data.get_complement(token_id) else {
+            return 0.0;
+        };
+        let positions = self.positions.read();
+        let held = |token: &TokenId| positions.get(token).map_or(0.0, |p| p.size.max(0.0));
+        (held(&complement) - held(token_id)).max(0.0)
+    }
+
+    /// Current position size for a token (0.0 when flat).
+    fn position_size(&self, token_id: &TokenId) -> f64 {
+        self.positions
+            .read()
+            .get(token_id)
+            .map(|p| p.size)
+            .unwrap_or(0.0)
+    }
+
+    /// Size of a token's orders awaiting fill confirmation.
+    fn pending_of(&self, token_id: &TokenId) -> PendingOrders {
+        self.pending.read().get(token_id).copied().unwrap_or_default()
+    }
+
+    /// Size of unconfirmed orders on one side of a token (see `reserve`).
+    pub fn pending_size(&self, token_id: &TokenId, side: Side) -> f64 {
+        let pending = self.pending_of(token_id);
+        match side {
+            Side::Buy => pending.buy,
+            Side::Sell => pending.sell,
+        }
+    }
+
+    /// Position once every pending buy on the token fills.
+    fn committed_long(&self, token_id: &TokenId) -> f64 {
+        self.position_size(token_id) + self.pending_of(token_id).buy
+    }
+
+    /// Position not already promised to a pending sell.
+    fn sellable(&self, token_id: &TokenId) -> f64 {
+        self.position_size(token_id) - self.pending_of(token_id).sell
+    }
+
+    /// Count a placed order against the limits until its fills are
+    /// confirmed and booked with `record_trade`, or it closes.
+    pub fn reserve(&self, token_id: &TokenId, side: Side, price: f64, size: f64) {
+        let mut pending = self.pending.write();
+        let entry = pending.entry(token_id.clone()).or_default();
+        match side {
+            Side::Buy => {
+                entry.buy += size;
+                entry.buy_cost += price * size;
+            }
+            Side::Sell => entry.sell += size,
+        }
+    }
+
+    /// Release `size` of an order reserved with `reserve`: the part just
+    /// booked as filled, or what was left unfilled when it closed.
+    pub fn release(&self, token_id: &TokenId, side: Side, price: f64, size: f64) {
+        let mut pending = self.pending.write();
+        let Some(entry) = pending.get_mut(token_id) else {
+            return;
+        };
+        match side {
+            Side::Buy => {
+                entry.buy = (entry.buy - size).max(0.0);
+                entry.buy_cost = (entry.buy_cost - price * size).max(0.0);
+            }
+            Side::Sell => entry.sell = (entry.sell - size).max(0.0),
+        }
+        if entry.buy <= SIZE_EPSILON && entry.sell <= SIZE_EPSILON {
+            pending.remove(token_id);
+        }
+    }
+
+    /// Cost model used to net fees and slippage out of arbitrage profit.
+    pub fn cost_model(&self) -> &CostModel {
+        &self.cost_model
+    }
+
+    /// Largest size for a new order on `token_id` at `price` that would pass
+    /// every check in `check_signal` right now.
+    ///
+    /// Returns 0.0 when trading is halted or a budget is exhausted. For
+    /// arbitrage, pass the YES token with `Side::Buy` and the combined
+    /// YES + NO price; the correlation group cap then applies as if the pair
+    /// were a single buy, which can only make the answer smaller.
+    pub fn max_allowed(&self, token_id: &TokenId, side: Side, price: f64) -> f64 {
+        if self.emergency_stop.load(Ordering::SeqCst) || !price.is_finite() || price <= 0.0 {
+            return 0.0;
+        }
+
+        if self.limit_pnl() < -self.config.max_daily_loss {
+            return 0.0;
+        }
+
+        let mut allowed = self.config.max_notional / price - SIZE_EPSILON;
+
+        let (market_id, budget) = self.market_budget_for(token_id);
+        let usage = self
+            .market_usage
+            .read()
+            .get(&market_id)
+            .copied()
+            .unwrap_or_default();
+        if budget.max_daily_trades > 0 && usage.trades >= budget.max_daily_trades {
+            return 0.0;
+        }
+        if budget.max_daily_turnover > 0.0 {
+            let remaining = budget.max_daily_turnover - usage.turnover;
+            allowed = allowed.min(remaining / price - SIZE_EPSILON);
+        }
+        if let (Some(liquidity), Side::Buy) = (&self.liquidity, side) {
+            if liquidity.below_min_tier(&market_id) {
+                return 0.0;
+            }
+            if let Some(cap) = liquidity.max_notional(&market_id) {
+                allowed = allowed.min(cap / price - SIZE_EPSILON);
+            }
+        }
+        if let (Some((_, exposure, cap)), Side::Buy) = (self.group_exposure(&market_id), side) {
+            let hedging = self.complement_open(token_id);
+            allowed = allowed.min(hedging + (cap - exposure) / price - SIZE_EPSILON);
         }
 
-        true
+        allowed = match side {
+            Side::Buy => allowed.min(self.max_position() - self.committed_long(token_id)),
+            Side::Sell => allowed.min(self.sellable(token_id)),
+        };
+
+        allowed.max(0.0)
     }
 
-    /// Record a trade for position tracking.
-    pub fn record_trade(&self, signal: &TradeSignal) {
+    /// Record a trade for position tracking. Returns the P&L it realized
+    /// (sells and arbitrage; zero for buys).
+    pub fn record_trade(&self, signal: &TradeSignal) -> f64 {
+        // Resting bids are recorded as a `Buy` when they fill
+        if matches!(signal, TradeSignal::Bid { .. } | TradeSignal::Cancel { .. }) {
+            return 0.0;
+        }
+        // A basket is recorded one leg at a time
+        if let TradeSignal::Basket {
+            legs,
+            reason,
+            metadata,
+            ..
+        } = signal
+        {
+            return legs
+                .iter()
+                .map(|leg| self.record_trade(&leg.to_signal(reason, metadata.clone())))
+                .sum();
+        }
+
         let mut positions = self.positions.write();
         let mut daily = self.daily_stats.write();
 
         daily.trades += 1;
         daily.volume += signal.notional();
 
+        let (market_id, _) = self.market_budget_for(signal.token_id());
+        let mut market_usage = self.market_usage.write();
+        let usage = market_usage.entry(market_id).or_default();
+        usage.trades += 1;
+        usage.turnover += signal.notional();
+        drop(market_usage);
+
         match signal {
             TradeSignal::Buy {
                 token_id,
@@ -164,6 +801,7 @@ impl RiskManager {
                 if position.size > 0.0 {
                     position.avg_cost = total_cost / position.size;
                 }
+                0.0
             }
             TradeSignal::Sell {
                 token_id,
@@ -179,20 +817,49 @@ impl RiskManager {
                     // Update atomic P&L (lock-free for check_signal)
                     let pnl_micro = (pnl * MICRO_PER_DOLLAR) as i64;
                     self.daily_pnl_micro.fetch_add(pnl_micro, Ordering::Relaxed);
+                    self.total_pnl_micro.fetch_add(pnl_micro, Ordering::Relaxed);
 
                     info!(
                         "Trade P&L: ${:.2} (total: ${:.2})",
                         pnl, position.realized_pnl
                     );
+                    pnl
+                } else {
+                    0.0
+                }
+            }
+            TradeSignal::Arbitrage {
+                yes_token,
+                no_token,
+                size,
+                side: Side::Sell,
+                ..
+            } => {
+                for token_id in [yes_token, no_token] {
+                    if let Some(position) = positions.get_mut(token_id) {
+                        position.size -= size;
+                    }
                 }
+
+                // Held to resolution the pair pays $1, so (like a buy-side
+                // arbitrage at entry) the profit over that is booked
+                let profit = self.cost_model.estimate(signal).net_edge.unwrap_or_default();
+                let profit_micro = (profit * MICRO_PER_DOLLAR) as i64;
+                self.daily_pnl_micro
+                    .fetch_add(profit_micro, Ordering::Relaxed);
+                self.total_pnl_micro
+                    .fetch_add(profit_micro, Ordering::Relaxed);
+
+                info!("Arbitrage exit profit locked: ${:.2}", profit);
+                profit
             }
             TradeSignal::Arbitrage {
                 yes_token,
                 no_token,
                 yes_price,
                 no_price,
-                profit_per_share,
                 size,
+                ..
             } => {
                 // Record both positions
                 let yes_pos = positions.entry(yes_token.clone()).or_default();
@@ -203,15 +870,22 @@ impl RiskManager {
                 no_pos.size += size;
                 no_pos.avg_cost = *no_price;
 
-                // Arbitrage profit is locked in
-                let profit = profit_per_share * size;
+                // Arbitrage profit is locked in, net of the shared cost model
+                // (not the signal's own `profit_per_share`)
+                let profit = self.cost_model.estimate(signal).net_edge.unwrap_or_default();
 
                 // Update atomic P&L (lock-free for check_signal)
                 let profit_micro = (profit * MICRO_PER_DOLLAR) as i64;
                 self.daily_pnl_micro
                     .fetch_add(profit_micro, Ordering::Relaxed);
+                self.total_pnl_micro
+                    .fetch_add(profit_micro, Ordering::Relaxed);
 
                 info!("Arbitrage profit locked: ${:.2}", profit);
+                profit
+            }
+            TradeSignal::Bid { .. } | TradeSignal::Cancel { .. } | TradeSignal::Basket { .. } => {
+                0.0
             }
         }
     }
@@ -233,18 +907,117 @@ impl RiskManager {
         self.daily_pnl_micro.load(Ordering::Relaxed) as f64 / MICRO_PER_DOLLAR
     }
 
+    /// Get P&L realized since startup (not reset daily).
+    pub fn get_realized_pnl(&self) -> f64 {
+        self.total_pnl_micro.load(Ordering::Relaxed) as f64 / MICRO_PER_DOLLAR
+    }
+
+    /// Mark open positions to the mid.
+    ///
+    /// Shares matched by a complement position are skipped: a YES+NO pair
+    /// pays $1 whatever happens, and its locked profit is already realized.
+    pub fn get_unrealized_pnl(&self) -> f64 {
+        let Some(market_data) = &self.market_data else {
+            return 0.0;
+        };
+
+        let positions = self.positions.read();
+        positions
+            .iter()
+            .map(|(token_id, position)| {
+                let hedged = market_data
+                    .get_complement(token_id)
+                    .and_then(|c| positions.get(&c))
+                    .map_or(0.0, |p| p.size.max(0.0));
+                let open = (position.size - hedged).max(0.0);
+                market_data
+                    .get_price(token_id)
+                    .map_or(0.0, |p| (p.mid - position.avg_cost) * open)
+            })
+            .sum()
+    }
+
+    /// Set the combined daily P&L of the other shards.
+    ///
+    /// The daily loss limit applies to the whole account, so in sharded mode
+    /// it is checked against this shard's P&L plus its peers'.
+    pub fn set_peer_daily_pnl(&self, pnl: f64) {
+        self.peer_pnl_micro
+            .store((pnl * MICRO_PER_DOLLAR) as i64, Ordering::Relaxed);
+    }
+
+    /// Daily P&L the loss limit is checked against (local + peer shards)
+    fn limit_pnl(&self) -> f64 {
+        let micro = self.daily_pnl_micro.load(Ordering::Relaxed)
+            + self.peer_pnl_micro.load(Ordering::Relaxed);
+        micro as f64 / MICRO_PER_DOLLAR
+    }
+
+    /// Get today's trade count and turnover for a market.
+    #[allow(dead_code)]
+    pub fn get_market_usage(&self, market_id: &MarketId) -> MarketUsage {
+        self.market_usage
+            .read()
+            .get(market_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Attribute a recorded trade and the P&L it realized to the strategy
+    /// that made it, so named instances of one strategy are tracked apart.
+    pub fn record_strategy_trade(&self, strategy: &str, signal: &TradeSignal, realized_pnl: f64) {
+        if matches!(signal, TradeSignal::Bid { .. } | TradeSignal::Cancel { .. }) {
+            return;
+        }
+        let mut usage = self.strategy_usage.write();
+        let entry = usage.entry(strategy.to_string()).or_default();
+        entry.trades += 1;
+        entry.turnover += signal.notional();
+        entry.realized_pnl += realized_pnl;
+        STRATEGY_DAILY_PNL
+            .with_label_values(&[strategy])
+            .set(entry.realized_pnl);
+    }
+
+    /// Get today's trade count, turnover, and realized P&L for a strategy.
+    #[allow(dead_code)]
+    pub fn get_strategy_usage(&self, strategy: &str) -> StrategyUsage {
+        self.strategy_usage
+            .read()
+            .get(strategy)
+            .copied()
+            .unwrap_or_default()
+    }
+
     /// Get daily trade count.
     pub fn get_daily_trades(&self) -> u64 {
         self.daily_stats.read().trades
     }
 
-    /// Reset daily stats (call at midnight).
-    #[allow(dead_code)]
+    /// Reset daily stats (called as each trading day starts, see
+    /// `run_daily_reset`).
     pub fn reset_daily(&self) {
         info!("Resetting daily stats");
         let mut daily = self.daily_stats.write();
         *daily = DailyStats::default();
+        self.market_usage.write().clear();
+        for strategy in self.strategy_usage.write().drain().map(|(name, _)| name) {
+            STRATEGY_DAILY_PNL.with_label_values(&[&strategy]).set(0.0);
+        }
         self.daily_pnl_micro.store(0, Ordering::Relaxed);
+        self.daily_loss_halted.store(false, Ordering::SeqCst);
+    }
+
+    /// Reset daily stats at midnight in the trading timezone until
+    /// cancelled.
+    pub async fn run_daily_reset(self: Arc<Self>, timezone: TradingTimezone, cancel: CancellationToken) {
+        info!("[RISK] Daily limits reset at midnight {}", timezone);
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep(timezone.until_next_day(Utc::now())) => self.reset_daily(),
+                _ = cancel.cancelled() => break,
+            }
+        }
     }
 
     /// Activate emergency stop - immediately halts all trading.
@@ -276,12 +1049,25 @@ impl RiskManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::market::{CorrelationConfig, LiquidityConfig, MarketPair};
+    use crate::strategy::{Leg, SignalMetadata};
 
     fn test_config() -> RiskConfig {
         RiskConfig {
             max_position: 100.0,
             max_notional: 1000.0,
             max_daily_loss: 500.0,
+            ..RiskConfig::default()
+        }
+    }
+
+    fn buy(token_id: &str, size: f64) -> TradeSignal {
+        TradeSignal::Buy {
+            token_id: token_id.to_string(),
+            price: 0.50,
+            size,
+            reason: "test".to_string(),
+            metadata: SignalMetadata::new(),
         }
     }
 
@@ -293,6 +1079,7 @@ mod tests {
             price: 0.50,
             size: 50.0,
             reason: "test".to_string(),
+            metadata: SignalMetadata::new(),
         };
 
         assert!(manager.check_signal(&signal));
@@ -308,6 +1095,7 @@ mod tests {
             price: 0.50,
             size: 80.0,
             reason: "test".to_string(),
+            metadata: SignalMetadata::new(),
         };
         assert!(manager.check_signal(&signal1));
         manager.record_trade(&signal1);
@@ -318,6 +1106,7 @@ mod tests {
             price: 0.50,
             size: 30.0, // 80 + 30 > 100
             reason: "test".to_string(),
+            metadata: SignalMetadata::new(),
         };
         assert!(!manager.check_signal(&signal2));
     }
@@ -332,6 +1121,7 @@ mod tests {
             price: 0.50,
             size: 10.0,
             reason: "test".to_string(),
+            metadata: SignalMetadata::new(),
         };
         manager.record_trade(&buy);
 
@@ -341,6 +1131,7 @@ mod tests {
             price: 0.60,
             size: 10.0,
             reason: "test".to_string(),
+            metadata: SignalMetadata::new(),
         };
         manager.record_trade(&sell);
 
@@ -348,6 +1139,358 @@ mod tests {
         assert!((manager.get_daily_pnl() - 1.0).abs() < 0.001);
     }
 
+    #[test]
+    fn test_realized_survives_reset_and_unrealized_skips_pairs() {
+        let market_data = Arc::new(MarketData::new());
+        market_data.register_pair(MarketPair {
+            market_id: "m".into(),
+            yes_token: "yes".into(),
+            no_token: "no".into(),
+            question: "?".into(),
+            category: None,
+            end_date: None,
+        });
+        market_data.update_price(&"yes".to_string(), 0.59, 0.61);
+        market_data.update_price(&"no".to_string(), 0.39, 0.41);
+
+        let mut manager = RiskManager::new(test_config());
+        manager.set_market_data(market_data);
+        manager.record_trade(&buy("yes", 12.0));
+        manager.record_trade(&TradeSignal::Sell {
+            token_id: "yes".into(),
+            price: 0.60,
+            size: 2.0,
+            reason: "test".into(),
+            metadata: SignalMetadata::new(),
+        });
+        manager.record_trade(&buy("no", 4.0));
+
+        // 6 unpaired YES marked at 0.60 against a 0.50 cost; NO fully paired
+        assert!((manager.get_unrealized_pnl() - 0.6).abs() < 1e-9);
+
+        manager.reset_daily();
+        assert_eq!(manager.get_daily_pnl(), 0.0);
+        assert!((manager.get_realized_pnl() - 0.2).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_arbitrage_booked_net_of_costs() {
+        let manager = RiskManager::new(test_config());
+        let arb = |yes_price: f64, profit_per_share: f64| TradeSignal::Arbitrage {
+            yes_token: "yes".into(),
+            no_token: "no".into(),
+            yes_price,
+            no_price: 0.50,
+            profit_per_share,
+            size: 10.0,
+            side: Side::Buy,
+            metadata: SignalMetadata::new(),
+        };
+
+        // A plugin reporting gross profit is booked after the 1% round-trip fee
+        assert!(manager.check_signal(&arb(0.45, 0.05)));
+        let profit = manager.record_trade(&arb(0.45, 0.05));
+        assert!((profit - (0.5 - 0.095)).abs() < 1e-9);
+
+        // Gross edge smaller than the fees is not an arbitrage
+        assert!(!manager.check_signal(&arb(0.495, 0.005)));
+    }
+
+    #[test]
+    fn test_arbitrage_sell_needs_both_legs_held() {
+        let manager = RiskManager::new(test_config());
+        let sell = |size: f64| TradeSignal::Arbitrage {
+            yes_token: "yes".into(),
+            no_token: "no".into(),
+            yes_price: 0.56,
+            no_price: 0.50,
+            profit_per_share: 0.05,
+            size,
+            side: Side::Sell,
+            metadata: SignalMetadata::new(),
+        };
+
+        manager.record_trade(&buy("yes", 10.0));
+        assert!(!manager.check_signal(&sell(5.0)));
+
+        manager.record_trade(&buy("no", 5.0));
+        assert!(manager.check_signal(&sell(5.0)));
+        assert!(!manager.check_signal(&sell(6.0)));
+
+        // Booked net of the 1% fee on $5.30 of proceeds
+        let profit = manager.record_trade(&sell(5.0));
+        assert!((profit - (0.3 - 0.053)).abs() < 1e-9);
+        assert_eq!(manager.position_size(&"yes".to_string()), 5.0);
+        assert_eq!(manager.position_size(&"no".to_string()), 0.0);
+    }
+
+    #[test]
+    fn test_basket_checks_and_records_each_leg() {
+        let manager = RiskManager::new(test_config());
+        manager.record_trade(&buy("held", 20.0));
+        let basket = |sell: f64| TradeSignal::Basket {
+            legs: vec![
+                Leg {
+                    token_id: "held".into(),
+                    side: Side::Sell,
+                    price: 0.60,
+                    size: sell,
+                },
+                Leg {
+                    token_id: "other".into(),
+                    side: Side::Buy,
+                    price: 0.40,
+                    size: 30.0,
+                },
+            ],
+            payout: None,
+            atomic: false,
+            reason: "test".to_string(),
+            metadata: SignalMetadata::new(),
+        };
+
+        assert!(manager.check_signal(&basket(20.0)));
+        assert!(!manager.check_signal(&basket(25.0)));
+
+        // The sell leg realizes $0.10 a share over the $0.50 cost
+        let pnl = manager.record_trade(&basket(20.0));
+        assert!((pnl - 2.0).abs() < 1e-9);
+        assert_eq!(manager.position_size(&"held".to_string()), 0.0);
+        assert_eq!(manager.position_size(&"other".to_string()), 30.0);
+    }
+
+    #[test]
+    fn test_basket_consistency_checks() {
+        let market_data = Arc::new(MarketData::new());
+        for m in ["m1", "m2"] {
+            market_data.register_pair(MarketPair {
+                market_id: m.into(),
+                yes_token: format!("{}-yes", m),
+                no_token: format!("{}-no", m),
+                question: "?".into(),
+                category: None,
+                end_date: None,
+            });
+        }
+        let mut manager = RiskManager::new(RiskConfig {
+            max_basket_notional: 20.0,
+            basket_same_group: true,
+            ..test_config()
+        });
+        manager.set_market_data(market_data);
+        manager.set_batch_limit(Some(2));
+
+        let basket = |tokens: &[&str], atomic: bool| TradeSignal::Basket {
+            legs: tokens
+                .iter()
+                .map(|t| Leg {
+                    token_id: t.to_string(),
+                    side: Side::Buy,
+                    price: 0.40,
+                    size: 20.0,
+                })
+                .collect(),
+            payout: None,
+            atomic,
+            reason: "test".to_string(),
+            metadata: SignalMetadata::new(),
+        };
+        let failed = |signal: &TradeSignal| -> Vec<&'static str> {
+            manager
+                .evaluate(signal)
+                .into_iter()
+                .filter(|c| !c.passed)
+                .map(|c| c.name)
+                .collect()
+        };
+
+        assert!(failed(&basket(&["m1-yes", "m1-no"], true)).is_empty());
+        assert_eq!(failed(&basket(&["m1-yes", "m1-yes"], false)), vec!["basket_legs"]);
+        assert_eq!(failed(&basket(&["m1-yes", "m2-no"], false)), vec!["basket_group"]);
+        // $24 of legs, three of them: over the basket cap and the batch size
+        assert_eq!(
+            failed(&basket(&["m1-yes", "m1-no", "m1-yes2"], true)),
+            vec!["basket_notional", "basket_group", "basket_atomic"]
+        );
+    }
+
+    #[test]
+    fn test_daily_loss_halt_is_audited_once() {
+        let mut manager = RiskManager::new(RiskConfig {
+            max_daily_loss: 1.0,
+            ..test_config()
+        });
+        let audit = Arc::new(AuditLog::new());
+        manager.set_audit_log(audit.clone());
+
+        // Lose $2 on a round trip
+        manager.record_trade(&buy("token1", 10.0));
+        manager.record_trade(&TradeSignal::Sell {
+            token_id: "token1".to_string(),
+            price: 0.30,
+            size: 10.0,
+            reason: "test".to_string(),
+            metadata: SignalMetadata::new(),
+        });
+
+        assert!(!manager.check_signal(&buy("token2", 1.0)));
+        assert!(!manager.check_signal(&buy("token2", 1.0)));
+
+        let events = audit.recent();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].action, AuditAction::AutoDisable);
+        assert_eq!(events[0].actor, "risk");
+    }
+
+    #[test]
+    fn test_peer_shard_losses_count_toward_daily_limit() {
+        let manager = RiskManager::new(RiskConfig {
+            max_daily_loss: 1.0,
+            ..test_config()
+        });
+        assert!(manager.check_signal(&buy("token1", 1.0)));
+
+        manager.set_peer_daily_pnl(-1.5);
+        assert!(!manager.check_signal(&buy("token1", 1.0)));
+        assert_eq!(manager.max_allowed(&"token1".to_string(), Side::Buy, 0.5), 0.0);
+        // Local P&L (what this shard publishes) is unaffected
+        assert_eq!(manager.get_daily_pnl(), 0.0);
+    }
+
+    #[test]
+    fn test_max_allowed_is_the_largest_passing_size() {
+        let mut config = test_config();
+        config.max_notional = 30.0;
+        let manager = RiskManager::new(config);
+        let token = "token1".to_string();
+
+        // Notional caps a $0.50 buy at 60 shares
+        let size = manager.max_allowed(&token, Side::Buy, 0.50);
+        assert!((size - 60.0).abs() < 1e-6);
+        assert!(manager.check_signal(&buy("token1", size)));
+        assert!(!manager.check_signal(&buy("token1", size + 0.01)));
+
+        // Position limit binds once we hold 80 of 100 shares
+        manager.record_trade(&buy("token1", 40.0));
+        manager.record_trade(&buy("token1", 40.0));
+        assert!((manager.max_allowed(&token, Side::Buy, 0.10) - 20.0).abs() < 1e-9);
+
+        // A VaR budget breach shrinks the position limit
+        manager.set_position_scale(0.9);
+        assert!((manager.max_allowed(&token, Side::Buy, 0.10) - 10.0).abs() < 1e-9);
+        assert!(!manager.check_signal(&buy("token1", 11.0)));
+        manager.set_position_scale(1.0);
+
+        // Sells are capped by the position held
+        assert!((manager.max_allowed(&token, Side::Sell, 0.10) - 80.0).abs() < 1e-9);
+        assert_eq!(
+            manager.max_allowed(&"token2".to_string(), Side::Sell, 0.10),
+            0.0
+        );
+
+        manager.emergency_stop();
+        assert_eq!(manager.max_allowed(&token, Side::Buy, 0.10), 0.0);
+    }
+
+    #[test]
+    fn test_pending_orders_count_until_released() {
+        let manager = RiskManager::new(test_config());
+        let token = "token1".to_string();
+
+        // A resting 60-share buy leaves room for 40 more
+        manager.reserve(&token, Side::Buy, 0.50, 60.0);
+        assert!(!manager.check_signal(&buy("token1", 50.0)));
+        assert!((manager.max_allowed(&token, Side::Buy, 0.50) - 40.0).abs() < 1e-6);
+
+        // 20 fill and are booked, the other 40 are cancelled
+        manager.release(&token, Side::Buy, 0.50, 20.0);
+        manager.record_trade(&buy("token1", 20.0));
+        manager.release(&token, Side::Buy, 0.50, 40.0);
+        assert!(manager.check_signal(&buy("token1", 80.0)));
+
+        // Shares promised to a pending sell cannot be sold again
+        manager.reserve(&token, Side::Sell, 0.50, 15.0);
+        assert_eq!(manager.max_allowed(&token, Side::Sell, 0.50), 5.0);
+        manager.release(&token, Side::Sell, 0.50, 15.0);
+        assert_eq!(manager.max_allowed(&token, Side::Sell, 0.50), 20.0);
+    }
+
+    #[test]
+    fn test_max_allowed_respects_market_turnover() {
+        let mut config = test_config();
+        config.market_budget.max_daily_turnover = 30.0;
+        let manager = RiskManager::new(config);
+        manager.record_trade(&buy("token1", 50.0));
+
+        // $25 used, $5 left at $0.50 = 10 shares
+        let size = manager.max_allowed(&"token1".to_string(), Side::Buy, 0.50);
+        assert!((size - 10.0).abs() < 1e-6);
+        assert!(manager.check_signal(&buy("token1", size)));
+    }
+
+    #[test]
+    fn test_liquidity_caps_entries_but_not_exits() {
+        let mut manager = RiskManager::new(test_config());
+        let liquidity = Arc::new(MarketLiquidity::new(LiquidityConfig {
+            min_tier: LiquidityTier::B,
+            ..LiquidityConfig::default()
+        }));
+        liquidity.set_score("thin", 40.0, LiquidityTier::B);
+        liquidity.set_score("dead", 1_000.0, LiquidityTier::C);
+        manager.set_liquidity(liquidity);
+
+        // Half of $40 of depth at $0.50 = 40 shares
+        let size = manager.max_allowed(&"thin".to_string(), Side::Buy, 0.50);
+        assert!((size - 40.0).abs() < 1e-6);
+        assert!(manager.check_signal(&buy("thin", size)));
+        assert!(!manager.check_signal(&buy("thin", 50.0)));
+
+        assert_eq!(manager.max_allowed(&"dead".to_string(), Side::Buy, 0.50), 0.0);
+        assert!(!manager.check_signal(&buy("dead", 1.0)));
+
+        // A position in a tier-C market can still be closed
+        manager.record_trade(&buy("dead", 10.0));
+        assert!(manager.max_allowed(&"dead".to_string(), Side::Sell, 0.50) > 9.0);
+    }
+
+    #[test]
+    fn test_correlated_markets_share_an_exposure_cap() {
+        let mut manager = RiskManager::new(test_config());
+        let market_data = Arc::new(MarketData::new());
+        for id in ["m1", "m2", "other"] {
+            market_data.register_pair(MarketPair {
+                market_id: id.into(),
+                yes_token: format!("{}-yes", id),
+                no_token: format!("{}-no", id),
+                question: "Q?".into(),
+                category: None,
+                end_date: None,
+            });
+        }
+        manager.set_market_data(market_data);
+        let correlations = Arc::new(MarketCorrelations::new(CorrelationConfig {
+            max_group_exposure: 30.0,
+            ..CorrelationConfig::default()
+        }));
+        correlations.set_group("m1", "m1");
+        correlations.set_group("m2", "m1");
+        manager.set_correlations(correlations);
+
+        // $20 of the group's $30 is used in m1
+        manager.record_trade(&buy("m1-yes", 40.0));
+        let size = manager.max_allowed(&"m2-yes".to_string(), Side::Buy, 0.50);
+        assert!((size - 20.0).abs() < 1e-6);
+        assert!(manager.check_signal(&buy("m2-yes", size)));
+        assert!(!manager.check_signal(&buy("m2-yes", 21.0)));
+
+        // Hedging with the complement doesn't add exposure
+        assert!(manager.check_signal(&buy("m1-no", 60.0)));
+        assert!(!manager.check_signal(&buy("m1-no", 61.0)));
+
+        // Ungrouped markets are not capped
+        assert!(manager.check_signal(&buy("other-yes", 100.0)));
+    }
+
     #[test]
     fn test_emergency_stop() {
         let manager = RiskManager::new(test_config());
@@ -356,6 +1499,7 @@ mod tests {
             price: 0.50,
             size: 50.0,
             reason: "test".to_string(),
+            metadata: SignalMetadata::new(),
         };
 
         // Initially, emergency stop is not active
@@ -378,4 +1522,107 @@ mod tests {
         // Signal should pass again
         assert!(manager.check_signal(&signal));
     }
+
+    #[test]
+    fn test_market_trade_count_limit() {
+        let mut config = test_config();
+        config.market_budget.max_daily_trades = 2;
+        let manager = RiskManager::new(config);
+
+        for _ in 0..2 {
+            let signal = buy("token1", 1.0);
+            assert!(manager.check_signal(&signal));
+            manager.record_trade(&signal);
+        }
+
+        // Third trade in the same market is rejected, other markets unaffected
+        assert!(!manager.check_signal(&buy("token1", 1.0)));
+        assert!(manager.check_signal(&buy("token2", 1.0)));
+
+        // Budgets reset with the daily stats
+        manager.reset_daily();
+        assert!(manager.check_signal(&buy("token1", 1.0)));
+    }
+
+    #[test]
+    fn test_market_turnover_limit() {
+        let mut config = test_config();
+        config.market_budget.max_daily_turnover = 30.0;
+        let manager = RiskManager::new(config);
+
+        // $25 notional fits the budget
+        let signal = buy("token1", 50.0);
+        assert!(manager.check_signal(&signal));
+        manager.record_trade(&signal);
+
+        // Another $10 would take turnover to $35 > $30
+        assert!(!manager.check_signal(&buy("token1", 20.0)));
+        assert!(manager.check_signal(&buy("token1", 10.0)));
+    }
+
+    #[test]
+    fn test_market_budget_category_override() {
+        let mut config = test_config();
+        config.market_budget.max_daily_trades = 10;
+        config.category_budgets.insert(
+            "sports".to_string(),
+            MarketBudget {
+                max_daily_trades: 1,
+                max_daily_turnover: 0.0,
+            },
+        );
+
+        let market_data = Arc::new(MarketData::new());
+        market_data.register_pair(MarketPair {
+            market_id: "nba".into(),
+            yes_token: "nba_yes".into(),
+            no_token: "nba_no".into(),
+            question: "Will the Lakers win?".into(),
+            category: Some("Sports".into()),
+            end_date: None,
+        });
+
+        let mut manager = RiskManager::new(config);
+        manager.set_market_data(market_data);
+
+        let signal = buy("nba_yes", 1.0);
+        assert!(manager.check_signal(&signal));
+        manager.record_trade(&signal);
+
+        // Both tokens share the market budget, which is capped at one trade
+        assert!(!manager.check_signal(&buy("nba_no", 1.0)));
+        assert_eq!(manager.get_market_usage(&"nba".to_string()).trades, 1);
+
+        // Uncategorized tokens fall back to the global budget
+        assert!(manager.check_signal(&buy("other", 1.0)));
+    }
+
+    #[test]
+    fn test_strategy_usage_tracks_instances_apart() {
+        let manager = RiskManager::new(test_config());
+        let sell = TradeSignal::Sell {
+            token_id: "token1".to_string(),
+            price: 0.60,
+            size: 10.0,
+            reason: "test".to_string(),
+            metadata: SignalMetadata::new(),
+        };
+
+        let pnl = manager.record_trade(&buy("token1", 10.0));
+        manager.record_strategy_trade("SumTo100@sports", &buy("token1", 10.0), pnl);
+        let pnl = manager.record_trade(&sell);
+        manager.record_strategy_trade("SumTo100@all", &sell, pnl);
+
+        let sports = manager.get_strategy_usage("SumTo100@sports");
+        assert_eq!(sports.trades, 1);
+        assert!((sports.turnover - 5.0).abs() < 1e-9);
+        assert_eq!(sports.realized_pnl, 0.0);
+
+        let all = manager.get_strategy_usage("SumTo100@all");
+        assert_eq!(all.trades, 1);
+        assert!((all.realized_pnl - 1.0).abs() < 1e-9);
+
+        manager.reset_daily();
+        assert_eq!(manager.get_strategy_usage("SumTo100@all").trades, 0);
+    }
 }
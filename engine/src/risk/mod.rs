@@ -1,5 +1,18 @@
 //! Risk management module.
 
+mod equity;
+mod exit;
+mod funds;
+mod hedger;
 mod manager;
+mod portfolio;
+mod var;
 
-pub use manager::RiskManager;
+pub use equity::{EquityCurve, EquitySample};
+pub use exit::{ExitConfig, ExitScheduler};
+pub use funds::{FundsConfig, FundsMonitor};
+pub use hedger::{HedgeConfig, Hedger};
+#[allow(unused_imports)]
+pub use manager::{MarketUsage, Position, RiskCheck, RiskManager, StrategyUsage};
+pub use portfolio::{Portfolio, PortfolioSnapshot};
+pub use var::{HourlyPnl, RiskAnalytics, VarConfig};
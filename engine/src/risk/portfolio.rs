@@ -0,0 +1,247 @@
+//! Portfolio breakdown.
+//!
+//! Positions from the risk manager marked to the mid: per-token cost basis,
+//! mark and unrealized P&L, exposure per market category, and net asset
+//! value. NAV is cash plus the market value of open positions; cash is the
+//! balance from the funds monitor's last check (paper balance in dry run),
+//! so it reads as positions only until the first check. Committed notional
+//! counts resting bids and the unfilled part of tracked buy orders. Served
+//! on `GET /portfolio` and published on the Redis `portfolio` channel every
+//! heartbeat.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use serde::Serialize;
+
+use crate::execution::{OrderTracker, Side};
+use crate::market::{MarketData, MarketId, TokenId};
+use crate::redis::now_ms;
+
+use super::{FundsMonitor, Position, RiskManager};
+
+/// Category of positions in markets without one
+const UNCATEGORIZED: &str = "uncategorized";
+
+/// One token's position
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TokenPosition {
+    pub token_id: TokenId,
+    pub market_id: Option<MarketId>,
+    pub category: Option<String>,
+    pub size: f64,
+    /// Average cost per share
+    pub avg_cost: f64,
+    pub cost_basis: f64,
+    /// Mid price (None until the token has a quote)
+    pub mark: Option<f64>,
+    /// Size marked at the mid (at cost while unquoted)
+    pub market_value: f64,
+    /// Shares matched by the complement position, whose profit is realized
+    pub hedged: f64,
+    pub unrealized_pnl: f64,
+}
+
+/// Positions in one market category
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct CategoryExposure {
+    pub positions: usize,
+    pub cost_basis: f64,
+    pub market_value: f64,
+    pub unrealized_pnl: f64,
+}
+
+/// Portfolio at one point in time
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PortfolioSnapshot {
+    pub timestamp_ms: u64,
+    /// Open positions, by token ID
+    pub positions: Vec<TokenPosition>,
+    /// Exposure by market category
+    pub categories: BTreeMap<String, CategoryExposure>,
+    /// Balance at the last funds check (None before the first)
+    pub cash: Option<f64>,
+    pub cost_basis: f64,
+    pub market_value: f64,
+    pub unrealized_pnl: f64,
+    /// P&L realized since startup
+    pub realized_pnl: f64,
+    /// Notional committed to unfilled buy orders
+    pub open_order_notional: f64,
+    /// Cash plus the market value of positions
+    pub nav: f64,
+}
+
+impl PortfolioSnapshot {
+    /// Mark positions to the mid and add them up.
+    pub fn build(
+        positions: &HashMap<TokenId, Position>,
+        market_data: &MarketData,
+        cash: Option<f64>,
+        realized_pnl: f64,
+        open_order_notional: f64,
+    ) -> Self {
+        let mut tokens: Vec<TokenPosition> = positions
+            .iter()
+            .filter(|(_, position)| position.size != 0.0)
+            .map(|(token_id, position)| {
+                let market_id = market_data.get_market_id(token_id);
+                let category = market_id
+                    .as_ref()
+                    .and_then(|m| market_data.get_pair(m))
+                    .and_then(|pair| pair.category);
+                let hedged = market_data
+                    .get_complement(token_id)
+                    .and_then(|c| positions.get(&c))
+                    .map_or(0.0, |p| p.size.clamp(0.0, position.size.max(0.0)));
+                let mark = market_data.get_price(token_id).map(|p| p.mid);
+                let open = position.size - hedged;
+                TokenPosition {
+                    token_id: token_id.clone(),
+                    market_id,
+                    category,
+                    size: position.size,
+                    avg_cost: position.avg_cost,
+                    cost_basis: position.size * position.avg_cost,
+                    mark,
+                    market_value: position.size * mark.unwrap_or(position.avg_cost),
+                    hedged,
+                    unrealized_pnl: mark.map_or(0.0, |m| (m - position.avg_cost) * open),
+                }
+            })
+            .collect();
+        tokens.sort_by(|a, b| a.token_id.cmp(&b.token_id));
+
+        let mut categories: BTreeMap<String, CategoryExposure> = BTreeMap::new();
+        for token in &tokens {
+            let category = token.category.as_deref().unwrap_or(UNCATEGORIZED);
+            let exposure = categories.entry(category.to_lowercase()).or_default();
+            exposure.positions += 1;
+            exposure.cost_basis += token.cost_basis;
+            exposure.market_value += token.market_value;
+            exposure.unrealized_pnl += token.unrealized_pnl;
+        }
+
+        let cost_basis = tokens.iter().map(|t| t.cost_basis).sum();
+        let market_value: f64 = tokens.iter().map(|t| t.market_value).sum();
+        let unrealized_pnl = tokens.iter().map(|t| t.unrealized_pnl).sum();
+        Self {
+            timestamp_ms: now_ms(),
+            positions: tokens,
+            categories,
+            cash,
+            cost_basis,
+            market_value,
+            unrealized_pnl,
+            realized_pnl,
+            open_order_notional,
+            nav: cash.unwrap_or(0.0) + market_value,
+        }
+    }
+}
+
+/// Builds portfolio snapshots from the engine's shared state.
+pub struct Portfolio {
+    market_data: Arc<MarketData>,
+    risk_manager: Arc<RiskManager>,
+    funds: Option<Arc<FundsMonitor>>,
+    orders: Option<Arc<OrderTracker>>,
+}
+
+impl Portfolio {
+    pub fn new(market_data: Arc<MarketData>, risk_manager: Arc<RiskManager>) -> Self {
+        Self {
+            market_data,
+            risk_manager,
+            funds: None,
+            orders: None,
+        }
+    }
+
+    /// Set the funds monitor that supplies cash and resting bid notional.
+    pub fn set_funds_monitor(&mut self, funds: Arc<FundsMonitor>) {
+        self.funds = Some(funds);
+    }
+
+    /// Set the tracker of placed orders that have not filled yet.
+    pub fn set_order_tracker(&mut self, orders: Arc<OrderTracker>) {
+        self.orders = Some(orders);
+    }
+
+    /// Current portfolio.
+    pub fn snapshot(&self) -> PortfolioSnapshot {
+        let resting = self.funds.as_ref().map_or(0.0, |f| f.open_order_notional());
+        let tracked: f64 = self.orders.as_ref().map_or(0.0, |orders| {
+            orders
+                .open_orders()
+                .iter()
+                .filter(|order| order.side == Side::Buy)
+                .map(|order| (order.size - order.filled) * order.price)
+                .sum()
+        });
+        PortfolioSnapshot::build(
+            &self.risk_manager.get_all_positions(),
+            &self.market_data,
+            self.funds
+                .as_ref()
+                .and_then(|f| f.last_usage())
+                .map(|usage| usage.balance),
+            self.risk_manager.get_realized_pnl(),
+            resting + tracked,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market::MarketPair;
+
+    fn position(size: f64, avg_cost: f64) -> Position {
+        Position {
+            size,
+            avg_cost,
+            realized_pnl: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_snapshot_marks_positions_and_groups_categories() {
+        let market_data = MarketData::new();
+        market_data.register_pair(MarketPair {
+            market_id: "m1".into(),
+            yes_token: "yes".into(),
+            no_token: "no".into(),
+            question: "Test?".into(),
+            category: Some("Sports".into()),
+            end_date: None,
+        });
+        market_data.update_price(&"yes".into(), 0.59, 0.61);
+        market_data.update_price(&"no".into(), 0.39, 0.41);
+
+        // 10 YES at 0.50, 4 of them matched by NO bought at 0.45
+        let positions = HashMap::from([
+            ("yes".to_string(), position(10.0, 0.5)),
+            ("no".to_string(), position(4.0, 0.45)),
+            ("loose".to_string(), position(2.0, 0.3)),
+        ]);
+        let snapshot = PortfolioSnapshot::build(&positions, &market_data, Some(100.0), 1.5, 20.0);
+
+        assert_eq!(snapshot.positions.len(), 3);
+        let yes = &snapshot.positions[2];
+        assert_eq!((yes.token_id.as_str(), yes.hedged), ("yes", 4.0));
+        // Only the 6 unmatched YES shares are marked to the mid
+        assert!((yes.unrealized_pnl - 0.6).abs() < 1e-9);
+        assert_eq!(snapshot.positions[1].unrealized_pnl, 0.0);
+        // Unquoted tokens stay at cost
+        assert_eq!(snapshot.positions[0].mark, None);
+        assert!((snapshot.positions[0].market_value - 0.6).abs() < 1e-9);
+
+        let sports = snapshot.categories["sports"];
+        assert_eq!(sports.positions, 2);
+        assert!((sports.market_value - 7.6).abs() < 1e-9);
+        assert_eq!(snapshot.categories["uncategorized"].positions, 1);
+        assert!((snapshot.nav - 108.2).abs() < 1e-9);
+        assert_eq!(snapshot.open_order_notional, 20.0);
+    }
+}
@@ -0,0 +1,285 @@
+//! Rolling value at risk and expected shortfall.
+//!
+//! Historical simulation over the persisted P&L: the store's hourly P&L
+//! (realized by trades plus the change in open positions marked to mid) is
+//! laid out hour by hour over `RISK_VAR_LOOKBACK_DAYS`, hours without data
+//! counting as flat, and summed over every rolling 1-day and 1-week window.
+//! VaR is the loss exceeded by only `1 - confidence` of those windows and
+//! expected shortfall the average loss in that tail. Both are reported as
+//! positive dollars (0 when even the tail made money).
+//!
+//! With `RISK_VAR_DAILY_LIMIT` set, a 1-day VaR above it scales the risk
+//! manager's position cap down by `limit / VaR` until it comes back under.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::db::TradeStore;
+use crate::metrics::{EXPECTED_SHORTFALL, VALUE_AT_RISK};
+
+use super::RiskManager;
+
+const SECS_PER_HOUR: i64 = 3600;
+
+/// Windows needed before a horizon is reported
+const MIN_WINDOWS: usize = 20;
+
+/// P&L of one UTC hour
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct HourlyPnl {
+    /// Start of the hour (Unix seconds)
+    pub hour_start: i64,
+    pub pnl: f64,
+}
+
+/// Horizons risk is reported for, as (label, hours)
+const HORIZONS: [(&str, usize); 2] = [("1d", 24), ("1w", 24 * 7)];
+
+/// VaR and expected shortfall over one horizon
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TailRisk {
+    var: f64,
+    expected_shortfall: f64,
+}
+
+/// Risk analytics settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct VarConfig {
+    pub enabled: bool,
+    /// Fraction of windows whose loss stays within VaR (e.g. 0.95)
+    pub confidence: f64,
+    /// History used for the estimate
+    pub lookback_days: i32,
+    /// How often the estimate is refreshed
+    pub interval: Duration,
+    /// 1-day VaR budget in dollars that scales the position cap (0 = off)
+    pub daily_limit: f64,
+}
+
+impl Default for VarConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            confidence: 0.95,
+            lookback_days: 30,
+            interval: Duration::from_secs(600),
+            daily_limit: 0.0,
+        }
+    }
+}
+
+impl VarConfig {
+    /// Load from `RISK_VAR_ENABLED`, `RISK_VAR_CONFIDENCE`,
+    /// `RISK_VAR_LOOKBACK_DAYS`, `RISK_VAR_INTERVAL_SECS` and
+    /// `RISK_VAR_DAILY_LIMIT`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok();
+        Self {
+            enabled: var("RISK_VAR_ENABLED")
+                .map(|v| v.to_lowercase() == "true" || v == "1")
+                .unwrap_or(defaults.enabled),
+            confidence: var("RISK_VAR_CONFIDENCE")
+                .and_then(|v| v.parse().ok())
+                .filter(|c: &f64| *c > 0.0 && *c < 1.0)
+                .unwrap_or(defaults.confidence),
+            lookback_days: var("RISK_VAR_LOOKBACK_DAYS")
+                .and_then(|v| v.parse().ok())
+                .filter(|d| *d > 0)
+                .unwrap_or(defaults.lookback_days),
+            interval: var("RISK_VAR_INTERVAL_SECS")
+                .and_then(|v| v.parse().ok())
+                .filter(|s| *s > 0)
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.interval),
+            daily_limit: var("RISK_VAR_DAILY_LIMIT")
+                .and_then(|v| v.parse().ok())
+                .filter(|l: &f64| *l >= 0.0)
+                .unwrap_or(defaults.daily_limit),
+        }
+    }
+}
+
+/// P&L of every rolling window of `horizon` hours over the complete hours
+/// from `start` (inclusive) to `end` (exclusive), both Unix seconds.
+fn window_pnls(history: &[HourlyPnl], start: i64, end: i64, horizon: usize) -> Vec<f64> {
+    let first = start.div_euclid(SECS_PER_HOUR);
+    let hours = (end.div_euclid(SECS_PER_HOUR) - first).max(0) as usize;
+    let mut series = vec![0.0; hours];
+    for point in history {
+        let index = point.hour_start.div_euclid(SECS_PER_HOUR) - first;
+        if (0..hours as i64).contains(&index) {
+            series[index as usize] += point.pnl;
+        }
+    }
+    if horizon == 0 || series.len() < horizon {
+        return Vec::new();
+    }
+
+    let mut sum: f64 = series[..horizon].iter().sum();
+    let mut windows = vec![sum];
+    for i in horizon..series.len() {
+        sum += series[i] - series[i - horizon];
+        windows.push(sum);
+    }
+    windows
+}
+
+/// Historical VaR and expected shortfall of `pnls` at `confidence`.
+fn tail_risk(pnls: &[f64], confidence: f64) -> Option<TailRisk> {
+    if pnls.len() < MIN_WINDOWS {
+        return None;
+    }
+    let mut losses: Vec<f64> = pnls.iter().map(|pnl| -pnl).collect();
+    losses.sort_by(|a, b| b.total_cmp(a));
+    // The epsilon keeps e.g. 5.000000000000004 windows from rounding up to 6
+    let tail = ((1.0 - confidence) * losses.len() as f64 - 1e-9).ceil() as usize;
+    let tail = tail.clamp(1, losses.len());
+    let var = losses[tail - 1];
+    let expected_shortfall = losses[..tail].iter().sum::<f64>() / tail as f64;
+    Some(TailRisk {
+        var: var.max(0.0),
+        expected_shortfall: expected_shortfall.max(0.0),
+    })
+}
+
+/// Position cap multiplier for a 1-day VaR against its budget
+fn position_scale(daily_var: f64, limit: f64) -> f64 {
+    if limit > 0.0 && daily_var > limit {
+        limit / daily_var
+    } else {
+        1.0
+    }
+}
+
+/// Periodically estimates tail risk from the store's P&L history
+pub struct RiskAnalytics {
+    config: VarConfig,
+    store: Arc<dyn TradeStore>,
+    risk_manager: Arc<RiskManager>,
+    is_paper: bool,
+}
+
+impl RiskAnalytics {
+    pub fn new(
+        config: VarConfig,
+        store: Arc<dyn TradeStore>,
+        risk_manager: Arc<RiskManager>,
+        is_paper: bool,
+    ) -> Self {
+        Self {
+            config,
+            store,
+            risk_manager,
+            is_paper,
+        }
+    }
+
+    /// Refresh the estimate every interval until cancelled.
+    pub async fn run(self, cancel: CancellationToken) {
+        info!(
+            "[VAR] Estimating {:.0}% VaR over {} days of P&L every {}s",
+            self.config.confidence * 100.0,
+            self.config.lookback_days,
+            self.config.interval.as_secs()
+        );
+        let mut ticker = tokio::time::interval(self.config.interval);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => self.refresh().await,
+                _ = cancel.cancelled() => break,
+            }
+        }
+    }
+
+    async fn refresh(&self) {
+        let history = match self
+            .store
+            .pnl_history(self.config.lookback_days, self.is_paper)
+            .await
+        {
+            Ok(history) => history,
+            Err(e) => {
+                warn!("[VAR] Failed to load P&L history: {:#}", e);
+                return;
+            }
+        };
+
+        let now = chrono::Utc::now().timestamp();
+        let start = now - i64::from(self.config.lookback_days) * 24 * SECS_PER_HOUR;
+        let mut daily_var = None;
+        for (label, hours) in HORIZONS {
+            let windows = window_pnls(&history, start, now, hours);
+            let Some(risk) = tail_risk(&windows, self.config.confidence) else {
+                debug!("[VAR] Not enough history for {} VaR yet", label);
+                continue;
+            };
+            VALUE_AT_RISK.with_label_values(&[label]).set(risk.var);
+            EXPECTED_SHORTFALL
+                .with_label_values(&[label])
+                .set(risk.expected_shortfall);
+            if hours == 24 {
+                daily_var = Some(risk.var);
+            }
+        }
+
+        if self.config.daily_limit > 0.0 {
+            let scale = position_scale(daily_var.unwrap_or(0.0), self.config.daily_limit);
+            if scale < 1.0 {
+                info!(
+                    "[VAR] 1d VaR ${:.2} over its ${:.2} budget: position cap scaled to {:.0}%",
+                    daily_var.unwrap_or(0.0),
+                    self.config.daily_limit,
+                    scale * 100.0
+                );
+            }
+            self.risk_manager.set_position_scale(scale);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hour(i: i64, pnl: f64) -> HourlyPnl {
+        HourlyPnl {
+            hour_start: i * SECS_PER_HOUR,
+            pnl,
+        }
+    }
+
+    #[test]
+    fn test_rolling_windows_fill_missing_hours() {
+        let history = [hour(0, 1.0), hour(2, -3.0), hour(3, 2.0), hour(9, 5.0)];
+        // Hours 0-4 are complete; hour 9 is outside the range
+        let windows = window_pnls(&history, 0, 5 * SECS_PER_HOUR + 10, 2);
+        assert_eq!(windows, vec![1.0, -3.0, -1.0, 2.0]);
+        assert!(window_pnls(&history, 0, SECS_PER_HOUR, 2).is_empty());
+    }
+
+    #[test]
+    fn test_var_and_expected_shortfall() {
+        // Losses of 1..=100 dollars
+        let pnls: Vec<f64> = (1..=100).map(|i| -(i as f64)).collect();
+        let risk = tail_risk(&pnls, 0.95).unwrap();
+        assert_eq!(risk.var, 96.0);
+        assert_eq!(risk.expected_shortfall, 98.0);
+
+        // A tail that made money reports no loss
+        let gains: Vec<f64> = (1..=100).map(|i| i as f64).collect();
+        assert_eq!(tail_risk(&gains, 0.95).unwrap().var, 0.0);
+        assert!(tail_risk(&pnls[..10], 0.95).is_none());
+    }
+
+    #[test]
+    fn test_position_scale() {
+        assert_eq!(position_scale(50.0, 100.0), 1.0);
+        assert_eq!(position_scale(200.0, 100.0), 0.5);
+        assert_eq!(position_scale(200.0, 0.0), 1.0);
+    }
+}
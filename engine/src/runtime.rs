@@ -0,0 +1,261 @@
+//! Tokio runtime settings and task observability.
+//!
+//! The engine builds its runtime from `RUNTIME_*` settings instead of
+//! `#[tokio::main]` defaults. Fire-and-forget work (DB writes, Redis
+//! publishes, Slack posts) and the strategy loop are spawned through
+//! [`spawn`] into a [`TaskGroup`], whose poll times and scheduling delays
+//! are sampled every `RUNTIME_METRICS_INTERVAL_MS` into Prometheus
+//! (`poly_task_*`, by group) along with runtime-wide figures
+//! (`poly_runtime_*`). A strategy loop whose scheduled delay climbs while
+//! the other groups' task counts spike is being starved by them.
+
+use std::future::Future;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use tokio::task::JoinHandle;
+use tokio_metrics::{RuntimeMetrics, RuntimeMonitor, TaskMetrics, TaskMonitor};
+use tokio_util::sync::CancellationToken;
+
+use crate::metrics::{
+    RUNTIME_ALIVE_TASKS, RUNTIME_BUSY_RATIO, RUNTIME_GLOBAL_QUEUE_DEPTH, RUNTIME_WORKERS,
+    TASKS_ALIVE, TASK_LONG_DELAYS, TASK_MEAN_POLL, TASK_MEAN_SCHEDULED_DELAY, TASK_SLOW_POLLS,
+};
+
+/// Polls longer than this count as slow
+const SLOW_POLL: Duration = Duration::from_millis(1);
+
+/// Waits for a worker longer than this count as long delays
+const LONG_DELAY: Duration = Duration::from_millis(1);
+
+/// Runtime settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuntimeConfig {
+    /// Worker threads (None = one per CPU core)
+    pub worker_threads: Option<usize>,
+    /// Most threads for blocking work (`spawn_blocking`)
+    pub max_blocking_threads: usize,
+    /// Ticks between checks of the global queue (None = Tokio's default)
+    pub global_queue_interval: Option<u32>,
+    /// Ticks between polls for I/O and timer events (None = Tokio's default)
+    pub event_interval: Option<u32>,
+    /// How often task and runtime metrics are sampled (zero = never)
+    pub metrics_interval: Duration,
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        Self {
+            worker_threads: None,
+            max_blocking_threads: 512,
+            global_queue_interval: None,
+            event_interval: None,
+            metrics_interval: Duration::from_secs(10),
+        }
+    }
+}
+
+impl RuntimeConfig {
+    /// Load from `RUNTIME_WORKER_THREADS`, `RUNTIME_MAX_BLOCKING_THREADS`,
+    /// `RUNTIME_GLOBAL_QUEUE_INTERVAL`, `RUNTIME_EVENT_INTERVAL` and
+    /// `RUNTIME_METRICS_INTERVAL_MS` (zero or unset = default).
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok();
+        Self {
+            worker_threads: var("RUNTIME_WORKER_THREADS")
+                .and_then(|v| v.parse().ok())
+                .filter(|n| *n > 0),
+            max_blocking_threads: var("RUNTIME_MAX_BLOCKING_THREADS")
+                .and_then(|v| v.parse().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.max_blocking_threads),
+            global_queue_interval: var("RUNTIME_GLOBAL_QUEUE_INTERVAL")
+                .and_then(|v| v.parse().ok())
+                .filter(|n| *n > 0),
+            event_interval: var("RUNTIME_EVENT_INTERVAL")
+                .and_then(|v| v.parse().ok())
+                .filter(|n| *n > 0),
+            metrics_interval: var("RUNTIME_METRICS_INTERVAL_MS")
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.metrics_interval),
+        }
+    }
+
+    /// Build the multi-threaded runtime.
+    pub fn build(&self) -> Result<tokio::runtime::Runtime> {
+        let mut builder = tokio::runtime::Builder::new_multi_thread();
+        builder
+            .enable_all()
+            .max_blocking_threads(self.max_blocking_threads);
+        if let Some(threads) = self.worker_threads {
+            builder.worker_threads(threads);
+        }
+        if let Some(interval) = self.global_queue_interval {
+            builder.global_queue_interval(interval);
+        }
+        if let Some(interval) = self.event_interval {
+            builder.event_interval(interval);
+        }
+        builder.build().context("Failed to build the Tokio runtime")
+    }
+}
+
+/// Tasks whose polls and scheduling delays are tracked together
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskGroup {
+    /// The strategy engine loop and the sliced orders it runs
+    Engine,
+    /// Trade and order-tag inserts
+    DbWrite,
+    /// Dashboard and audit publishes
+    RedisPublish,
+    /// Slack posts
+    Slack,
+}
+
+impl TaskGroup {
+    pub const ALL: [TaskGroup; 4] = [
+        TaskGroup::Engine,
+        TaskGroup::DbWrite,
+        TaskGroup::RedisPublish,
+        TaskGroup::Slack,
+    ];
+
+    /// Metric label
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaskGroup::Engine => "engine",
+            TaskGroup::DbWrite => "db_write",
+            TaskGroup::RedisPublish => "redis_publish",
+            TaskGroup::Slack => "slack",
+        }
+    }
+
+    /// Tasks spawned into the group that have not finished
+    pub fn alive(self) -> u64 {
+        let total = self.monitor().cumulative();
+        total.instrumented_count.saturating_sub(total.dropped_count)
+    }
+
+    fn monitor(self) -> &'static TaskMonitor {
+        lazy_static::lazy_static! {
+            static ref MONITORS: Vec<TaskMonitor> = TaskGroup::ALL
+                .iter()
+                .map(|_| {
+                    let mut builder = TaskMonitor::builder();
+                    builder
+                        .with_slow_poll_threshold(SLOW_POLL)
+                        .with_long_delay_threshold(LONG_DELAY);
+                    builder.build()
+                })
+                .collect();
+        }
+        &MONITORS[self as usize]
+    }
+}
+
+/// `tokio::spawn`, with the task's polls counted against `group`.
+pub fn spawn<F>(group: TaskGroup, task: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    tokio::spawn(group.monitor().instrument(task))
+}
+
+/// Export runtime and task group metrics every `interval` until cancelled.
+pub async fn run_metrics(interval: Duration, cancellation_token: CancellationToken) {
+    if interval.is_zero() {
+        return;
+    }
+    let mut runtime = RuntimeMonitor::new(&tokio::runtime::Handle::current()).intervals();
+    let mut groups: Vec<_> = TaskGroup::ALL
+        .iter()
+        .map(|group| (*group, group.monitor().intervals()))
+        .collect();
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        tokio::select! {
+            _ = cancellation_token.cancelled() => return,
+            _ = ticker.tick() => {}
+        }
+        if let Some(metrics) = runtime.next() {
+            export_runtime(&metrics);
+        }
+        for (group, intervals) in &mut groups {
+            if let Some(metrics) = intervals.next() {
+                export_group(*group, &metrics, &group.monitor().cumulative());
+            }
+        }
+    }
+}
+
+fn export_runtime(metrics: &RuntimeMetrics) {
+    RUNTIME_WORKERS.set(metrics.workers_count as f64);
+    RUNTIME_ALIVE_TASKS.set(metrics.live_tasks_count as f64);
+    RUNTIME_GLOBAL_QUEUE_DEPTH.set(metrics.global_queue_depth as f64);
+    let capacity = metrics.elapsed.as_secs_f64() * metrics.workers_count as f64;
+    if capacity > 0.0 {
+        RUNTIME_BUSY_RATIO.set((metrics.total_busy_duration.as_secs_f64() / capacity).min(1.0));
+    }
+}
+
+/// `interval` covers the last sample; `total` everything so far.
+fn export_group(group: TaskGroup, interval: &TaskMetrics, total: &TaskMetrics) {
+    let label = [group.as_str()];
+    if interval.total_poll_count > 0 {
+        TASK_MEAN_POLL
+            .with_label_values(&label)
+            .set(interval.mean_poll_duration().as_secs_f64());
+    }
+    if interval.total_scheduled_count > 0 {
+        TASK_MEAN_SCHEDULED_DELAY
+            .with_label_values(&label)
+            .set(interval.mean_scheduled_duration().as_secs_f64());
+    }
+    TASK_SLOW_POLLS
+        .with_label_values(&label)
+        .inc_by(interval.total_slow_poll_count as f64);
+    TASK_LONG_DELAYS
+        .with_label_values(&label)
+        .inc_by(interval.total_long_delay_count as f64);
+    TASKS_ALIVE
+        .with_label_values(&label)
+        .set(total.instrumented_count.saturating_sub(total.dropped_count) as f64);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_spawned_tasks_are_counted_by_group() {
+        let before = TaskGroup::Slack.monitor().cumulative();
+        spawn(TaskGroup::Slack, async { tokio::task::yield_now().await })
+            .await
+            .unwrap();
+        let after = TaskGroup::Slack.monitor().cumulative();
+        assert!(after.instrumented_count > before.instrumented_count);
+        assert!(after.total_poll_count >= before.total_poll_count + 2);
+
+        export_group(TaskGroup::Slack, &after, &after);
+        assert_eq!(
+            TASKS_ALIVE.with_label_values(&["slack"]).get(),
+            (after.instrumented_count - after.dropped_count) as f64
+        );
+    }
+
+    #[test]
+    fn test_runtime_builds_with_tuning() {
+        let config = RuntimeConfig {
+            worker_threads: Some(2),
+            global_queue_interval: Some(31),
+            event_interval: Some(31),
+            ..RuntimeConfig::default()
+        };
+        let runtime = config.build().unwrap();
+        assert_eq!(runtime.metrics().num_workers(), 2);
+    }
+}
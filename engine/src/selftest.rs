@@ -0,0 +1,209 @@
+//! Startup self-test (`poly-rust --self-test`).
+//!
+//! Checks every external dependency the engine is configured to use and
+//! exits non-zero with a diagnosis if any of them fails, so a deploy can be
+//! gated on it. Optional integrations that are not configured are skipped,
+//! not failed.
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use futures_util::SinkExt;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tracing::{error, info, warn};
+
+use crate::config::Config;
+use crate::db::{TradeRepository, TradeStore};
+use crate::execution::OrderManager;
+use crate::notifications::SlackNotifier;
+
+/// Per-check time limit
+const CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Result of one check
+#[derive(Debug)]
+enum Outcome {
+    Pass(String),
+    Skipped(&'static str),
+    /// Error and a hint about what to look at
+    Fail(String, &'static str),
+}
+
+#[derive(Debug)]
+struct Check {
+    name: &'static str,
+    outcome: Outcome,
+    elapsed: Duration,
+}
+
+/// True if `--self-test` was passed on the command line.
+pub fn requested() -> bool {
+    std::env::args().skip(1).any(|arg| arg == "--self-test")
+}
+
+/// Run every check, log a report, and fail if any check failed.
+pub async fn run(config: &Config) -> Result<()> {
+    info!("[SELF-TEST] Checking external dependencies...");
+
+    let redis_url = std::env::var("REDIS_URL").ok();
+    let database_url = std::env::var("DATABASE_URL").ok();
+    let slack = SlackNotifier::from_env();
+
+    let checks = vec![
+        check(
+            "websocket",
+            "check POLY_WS_URL and outbound network access",
+            async {
+                let (mut ws, _) = connect_async(&config.ws_url)
+                    .await
+                    .context("Failed to connect")?;
+                let _ = ws.send(Message::Close(None)).await;
+                Ok(Some(config.ws_url.clone()))
+            },
+        )
+        .await,
+        check(
+            "clob_auth",
+            "check POLY_API_KEY, POLY_API_SECRET and POLY_CLOB_URL",
+            async {
+                if config.api_key.is_empty() {
+                    return Ok(None);
+                }
+                let manager = OrderManager::new(config.clone(), None).await?;
+                manager.check_auth().await?;
+                Ok(Some(config.clob_url.clone()))
+            },
+        )
+        .await,
+        check(
+            "redis",
+            "check REDIS_URL and that Redis is reachable",
+            async {
+                let Some(url) = &redis_url else {
+                    return Ok(None);
+                };
+                let client = redis::Client::open(url.as_str()).context("Invalid REDIS_URL")?;
+                let mut conn = redis::aio::ConnectionManager::new(client).await?;
+                let pong: String = redis::cmd("PING").query_async(&mut conn).await?;
+                Ok(Some(pong))
+            },
+        )
+        .await,
+        check(
+            "database",
+            "check DATABASE_URL and that init.sql has been applied",
+            async {
+                let Some(url) = &database_url else {
+                    return Ok(None);
+                };
+                let repo = TradeRepository::new(Some(url)).await?;
+                let trades = repo
+                    .recent_trade_count(60)
+                    .await
+                    .context("Failed to query the trades table")?;
+                Ok(Some(format!("{} trades in the last hour", trades)))
+            },
+        )
+        .await,
+        check("slack", "check SLACK_WEBHOOK_URL", async {
+            if !slack.is_enabled() {
+                return Ok(None);
+            }
+            slack
+                .send_test_message(&format!(
+                    ":white_check_mark: Self-test from instance `{}`",
+                    config.instance_id
+                ))
+                .await?;
+            Ok(Some("test message sent".to_string()))
+        })
+        .await,
+        check(
+            "paper_order",
+            "check the order manager configuration",
+            async {
+                // Always simulated: the self-test must never trade
+                let mut dry_config = config.clone();
+                dry_config.dry_run = true;
+                let manager = OrderManager::new(dry_config, None).await?;
+                let order_id = manager
+                    .place_buy(&"self-test".to_string(), 0.01, 1.0)
+                    .await
+                    .context("Failed to place order")?;
+                manager
+                    .cancel_order(&order_id)
+                    .await
+                    .context("Failed to cancel order")?;
+                Ok(Some(format!("placed and cancelled {}", order_id)))
+            },
+        )
+        .await,
+    ];
+
+    report(&checks)
+}
+
+/// Run one check with the time limit. `Ok(None)` means not configured.
+async fn check<F>(name: &'static str, hint: &'static str, fut: F) -> Check
+where
+    F: Future<Output = Result<Option<String>>>,
+{
+    let start = Instant::now();
+    let outcome = match tokio::time::timeout(CHECK_TIMEOUT, fut).await {
+        Ok(Ok(Some(detail))) => Outcome::Pass(detail),
+        Ok(Ok(None)) => Outcome::Skipped("not configured"),
+        Ok(Err(e)) => Outcome::Fail(format!("{:#}", e), hint),
+        Err(_) => Outcome::Fail(format!("timed out after {:?}", CHECK_TIMEOUT), hint),
+    };
+    Check {
+        name,
+        outcome,
+        elapsed: start.elapsed(),
+    }
+}
+
+/// Log each check and return an error naming the failed ones.
+fn report(checks: &[Check]) -> Result<()> {
+    let mut failed = Vec::new();
+    for check in checks {
+        let ms = check.elapsed.as_millis();
+        match &check.outcome {
+            Outcome::Pass(detail) => {
+                info!("[SELF-TEST] PASS {} ({}ms): {}", check.name, ms, detail)
+            }
+            Outcome::Skipped(reason) => warn!("[SELF-TEST] SKIP {}: {}", check.name, reason),
+            Outcome::Fail(err, hint) => {
+                error!(
+                    "[SELF-TEST] FAIL {} ({}ms): {} - {}",
+                    check.name, ms, err, hint
+                );
+                failed.push(check.name);
+            }
+        }
+    }
+
+    if failed.is_empty() {
+        info!("[SELF-TEST] All checks passed");
+        Ok(())
+    } else {
+        anyhow::bail!("self-test failed: {}", failed.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_failed_checks_fail_the_report() {
+        let pass = check("a", "", async { Ok(Some("ok".into())) }).await;
+        let skip = check("b", "", async { Ok(None) }).await;
+        assert!(report(&[pass, skip]).is_ok());
+
+        let fail = check("c", "check C", async { anyhow::bail!("boom") }).await;
+        assert!(matches!(&fail.outcome, Outcome::Fail(e, "check C") if e == "boom"));
+        let err = report(&[fail]).unwrap_err();
+        assert_eq!(err.to_string(), "self-test failed: c");
+    }
+}
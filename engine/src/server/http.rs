@@ -0,0 +1,1723 @@
+//! Minimal hyper-based HTTP layer with routing, timeouts, and auth.
+
+use std::convert::Infallible;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use hyper::header::{HeaderValue, ALLOW, AUTHORIZATION, CONTENT_TYPE, WWW_AUTHENTICATE};
+use hyper::body::HttpBody;
+use hyper::server::accept::{self, Accept};
+use hyper::server::conn::AddrIncoming;
+use hyper::service::{make_service_fn, service_fn};
+use anyhow::{bail, Context, Result};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use serde::Deserialize;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio_rustls::TlsAcceptor;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use super::tls::TlsSettings;
+use crate::audit::{AuditAction, AuditLog};
+use crate::cluster::LeaderElection;
+use crate::db::{parse_statement, AttributionDimension, FeeErrorRow, TradeStore};
+use crate::market::{MarketData, SubscriptionPrioritizer, TokenId};
+use crate::metrics::HTTP_UNAUTHORIZED;
+use crate::execution::{Side, VolumeTracker};
+use crate::log_filter::LogFilter;
+use crate::restarts::TaskSupervisor;
+use crate::risk::{Portfolio, Position, RiskManager};
+use crate::status::StatusBoard;
+use crate::strategy::{
+    ApprovalError, ApprovalQueue, EdgeTuner, RecentTrades, SignalMetadata, TradeFilter, TradeSignal,
+};
+
+/// Actor recorded in the audit log for control endpoint actions
+const HTTP_ACTOR: &str = "operator:http";
+
+const JSON_CONTENT_TYPE: &str = "application/json";
+const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// HTTP server configuration
+#[derive(Debug, Clone)]
+pub struct HttpServerConfig {
+    /// Address to bind (default 127.0.0.1, off public interfaces)
+    pub bind_addr: IpAddr,
+
+    /// Port to listen on
+    pub port: u16,
+
+    /// Maximum time to read headers and produce a response
+    pub request_timeout: Duration,
+
+    /// Bearer token for control endpoints (None = control endpoints disabled)
+    pub auth_token: Option<String>,
+
+    /// Also require the bearer token for /metrics
+    pub protect_metrics: bool,
+
+    /// Serve HTTPS (and optionally require client certificates)
+    pub tls: Option<TlsSettings>,
+
+    /// Allow control endpoints over plain HTTP on a non-loopback address
+    pub allow_plaintext_control: bool,
+}
+
+impl Default for HttpServerConfig {
+    fn default() -> Self {
+        Self {
+            bind_addr: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            port: 8080,
+            request_timeout: Duration::from_secs(5),
+            auth_token: None,
+            protect_metrics: false,
+            tls: None,
+            allow_plaintext_control: false,
+        }
+    }
+}
+
+impl HttpServerConfig {
+    /// Load server configuration from environment variables.
+    ///
+    /// - `HTTP_BIND_ADDR` (default: 127.0.0.1)
+    /// - `HEALTH_PORT` (default: 8080)
+    /// - `HTTP_REQUEST_TIMEOUT_MS` (default: 5000)
+    /// - `HTTP_AUTH_TOKEN` (unset: control endpoints disabled)
+    /// - `HTTP_AUTH_METRICS` (default: false)
+    /// - `HTTP_TLS_CERT` + `HTTP_TLS_KEY` (both set: serve HTTPS)
+    /// - `HTTP_TLS_CLIENT_CA` (set: require client certificates)
+    /// - `HTTP_ALLOW_PLAINTEXT_CONTROL` (default: false)
+    ///
+    /// Fails if only one of the TLS cert and key is set, or if control
+    /// endpoints would be served over plain HTTP on a non-loopback address
+    /// without `HTTP_ALLOW_PLAINTEXT_CONTROL=true`.
+    pub fn from_env() -> Result<Self> {
+        let defaults = Self::default();
+        let bind_addr = std::env::var("HTTP_BIND_ADDR")
+            .ok()
+            .and_then(|a| a.parse().ok())
+            .unwrap_or(defaults.bind_addr);
+        let port = std::env::var("HEALTH_PORT")
+            .ok()
+            .and_then(|p| p.parse().ok())
+            .unwrap_or(defaults.port);
+        let request_timeout = std::env::var("HTTP_REQUEST_TIMEOUT_MS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .map(Duration::from_millis)
+            .unwrap_or(defaults.request_timeout);
+        let auth_token = std::env::var("HTTP_AUTH_TOKEN")
+            .ok()
+            .filter(|t| !t.is_empty());
+        let protect_metrics = std::env::var("HTTP_AUTH_METRICS")
+            .map(|v| v.to_lowercase() == "true")
+            .unwrap_or(false);
+        let tls = tls_settings(
+            std::env::var("HTTP_TLS_CERT").ok().filter(|v| !v.is_empty()),
+            std::env::var("HTTP_TLS_KEY").ok().filter(|v| !v.is_empty()),
+            std::env::var("HTTP_TLS_CLIENT_CA").ok().filter(|v| !v.is_empty()),
+        )?;
+        let allow_plaintext_control = std::env::var("HTTP_ALLOW_PLAINTEXT_CONTROL")
+            .map(|v| v.to_lowercase() == "true")
+            .unwrap_or(false);
+
+        let config = Self {
+            bind_addr,
+            port,
+            request_timeout,
+            auth_token,
+            protect_metrics,
+            tls,
+            allow_plaintext_control,
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Refuse to expose control endpoints over plain HTTP on a public
+    /// interface unless the operator opted in.
+    pub fn validate(&self) -> Result<()> {
+        if self.auth_token.is_some()
+            && self.tls.is_none()
+            && !self.bind_addr.is_loopback()
+            && !self.allow_plaintext_control
+        {
+            bail!(
+                "HTTP_AUTH_TOKEN would be sent over plain HTTP on {}: set HTTP_TLS_CERT/HTTP_TLS_KEY, \
+                 bind to 127.0.0.1, or set HTTP_ALLOW_PLAINTEXT_CONTROL=true",
+                self.bind_addr
+            );
+        }
+        Ok(())
+    }
+}
+
+/// TLS settings from the cert, key and client CA paths; both or neither of
+/// the cert and key must be set.
+fn tls_settings(
+    cert: Option<String>,
+    key: Option<String>,
+    client_ca: Option<String>,
+) -> Result<Option<TlsSettings>> {
+    match (cert, key) {
+        (Some(cert), Some(key)) => Ok(Some(TlsSettings {
+            cert_path: PathBuf::from(cert),
+            key_path: PathBuf::from(key),
+            client_ca_path: client_ca.map(PathBuf::from),
+        })),
+        (Some(_), None) => bail!("HTTP_TLS_CERT is set but HTTP_TLS_KEY is not"),
+        (None, Some(_)) => bail!("HTTP_TLS_KEY is set but HTTP_TLS_CERT is not"),
+        (None, None) if client_ca.is_some() => {
+            bail!("HTTP_TLS_CLIENT_CA needs HTTP_TLS_CERT and HTTP_TLS_KEY")
+        }
+        (None, None) => Ok(None),
+    }
+}
+
+/// Shared state available to request handlers
+pub struct HttpState {
+    pub start_time: Instant,
+    pub market_data: Arc<MarketData>,
+    pub risk_manager: Arc<RiskManager>,
+    pub audit: Arc<AuditLog>,
+    /// Reports whether this instance is the active leader
+    pub leader: Arc<LeaderElection>,
+    /// Source of the P&L attribution report
+    pub trade_repo: Arc<dyn TradeStore>,
+    /// Recent trades behind metric spikes (`/debug/trades`)
+    pub recent_trades: Arc<RecentTrades>,
+    /// Current WebSocket subscription selection (`/control/subscriptions`)
+    pub subscriptions: Arc<SubscriptionPrioritizer>,
+    /// Cancelled by `POST /control/shutdown` to stop the engine
+    pub shutdown: CancellationToken,
+    /// Runtime log filter (`/loglevel`)
+    pub log_filter: Arc<LogFilter>,
+    /// Monthly maker/taker volume per market (`/stats`)
+    pub volume: Arc<VolumeTracker>,
+    /// Tuned minimum edges per market (`/stats`)
+    pub edge_tuner: Arc<EdgeTuner>,
+    /// Positions, exposure and NAV (`/portfolio`)
+    pub portfolio: Arc<Portfolio>,
+    /// Signals parked for manual approval (`/control/approvals`)
+    pub approvals: Arc<ApprovalQueue>,
+    /// Latest engine heartbeat snapshot (`/status`)
+    pub status: Arc<StatusBoard>,
+    /// Task restarts and crash loops (`/ready`)
+    pub restarts: Arc<TaskSupervisor>,
+}
+
+/// Known routes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Route {
+    Health,
+    Ready,
+    Metrics,
+    Status,
+    Shutdown,
+    EmergencyStop,
+    Resume,
+    Audit,
+    PnlAttribution,
+    Subscriptions,
+    DebugTrades,
+    Simulate,
+    FeeImport,
+    FeeErrors,
+    LogLevel,
+    Stats,
+    Portfolio,
+    Approvals,
+    Approve,
+    Reject,
+}
+
+impl Route {
+    fn from_path(path: &str) -> Option<Self> {
+        match path {
+            "/" | "/health" => Some(Route::Health),
+            "/ready" => Some(Route::Ready),
+            "/metrics" => Some(Route::Metrics),
+            "/status" => Some(Route::Status),
+            "/control/shutdown" => Some(Route::Shutdown),
+            "/control/emergency-stop" => Some(Route::EmergencyStop),
+            "/control/resume" => Some(Route::Resume),
+            "/control/audit" => Some(Route::Audit),
+            "/control/pnl-attribution" => Some(Route::PnlAttribution),
+            "/control/subscriptions" => Some(Route::Subscriptions),
+            "/debug/trades" => Some(Route::DebugTrades),
+            "/control/simulate" => Some(Route::Simulate),
+            "/control/fees/import" => Some(Route::FeeImport),
+            "/control/fee-errors" => Some(Route::FeeErrors),
+            "/loglevel" => Some(Route::LogLevel),
+            "/stats" => Some(Route::Stats),
+            "/portfolio" => Some(Route::Portfolio),
+            "/control/approvals" => Some(Route::Approvals),
+            "/control/approvals/approve" => Some(Route::Approve),
+            "/control/approvals/reject" => Some(Route::Reject),
+            _ => None,
+        }
+    }
+
+    fn allowed_methods(self) -> &'static [Method] {
+        match self {
+            Route::Health
+            | Route::Ready
+            | Route::Metrics
+            | Route::Status
+            | Route::Audit
+            | Route::PnlAttribution
+            | Route::Subscriptions
+            | Route::DebugTrades
+            | Route::FeeErrors
+            | Route::Stats
+            | Route::Portfolio
+            | Route::Approvals => &[Method::GET, Method::HEAD],
+            Route::Shutdown
+            | Route::EmergencyStop
+            | Route::Resume
+            | Route::Simulate
+            | Route::FeeImport
+            | Route::Approve
+            | Route::Reject => &[Method::POST],
+            Route::LogLevel => &[Method::GET, Method::HEAD, Method::PUT],
+        }
+    }
+
+    fn requires_auth(self, auth: &AuthPolicy) -> bool {
+        match self {
+            Route::Health | Route::Ready => false,
+            Route::Metrics | Route::Status => auth.protect_metrics,
+            Route::Shutdown
+            | Route::EmergencyStop
+            | Route::Resume
+            | Route::Audit
+            | Route::PnlAttribution
+            | Route::Subscriptions
+            | Route::DebugTrades
+            | Route::Simulate
+            | Route::FeeImport
+            | Route::FeeErrors
+            | Route::LogLevel
+            | Route::Stats
+            | Route::Portfolio
+            | Route::Approvals
+            | Route::Approve
+            | Route::Reject => true,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Route::Health => "health",
+            Route::Ready => "ready",
+            Route::Metrics => "metrics",
+            Route::Status => "status",
+            Route::Shutdown => "control_shutdown",
+            Route::EmergencyStop => "control_emergency_stop",
+            Route::Resume => "control_resume",
+            Route::Audit => "control_audit",
+            Route::PnlAttribution => "control_pnl_attribution",
+            Route::Subscriptions => "control_subscriptions",
+            Route::DebugTrades => "debug_trades",
+            Route::Simulate => "control_simulate",
+            Route::FeeImport => "control_fee_import",
+            Route::FeeErrors => "control_fee_errors",
+            Route::LogLevel => "loglevel",
+            Route::Stats => "stats",
+            Route::Portfolio => "portfolio",
+            Route::Approvals => "control_approvals",
+            Route::Approve => "control_approve",
+            Route::Reject => "control_reject",
+        }
+    }
+}
+
+/// Which requests need the bearer token
+#[derive(Debug, Clone, Default)]
+struct AuthPolicy {
+    token: Option<String>,
+    protect_metrics: bool,
+}
+
+/// Health, metrics, and control HTTP server
+pub struct HttpServer {
+    config: HttpServerConfig,
+    state: Arc<HttpState>,
+    listener: TcpListener,
+    tls_acceptor: Option<TlsAcceptor>,
+}
+
+impl HttpServer {
+    /// Load the TLS config and bind the listener. Fails if either does, so
+    /// a bad cert path or a taken port stops startup instead of leaving the
+    /// engine running without health checks.
+    pub async fn bind(config: HttpServerConfig, state: HttpState) -> Result<Self> {
+        let tls_acceptor = config
+            .tls
+            .as_ref()
+            .map(TlsSettings::acceptor)
+            .transpose()
+            .context("Failed to load HTTP TLS config")?;
+        let addr = SocketAddr::new(config.bind_addr, config.port);
+        let listener = TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("Failed to bind HTTP server on {}", addr))?;
+
+        Ok(Self {
+            config,
+            state: Arc::new(state),
+            listener,
+            tls_acceptor,
+        })
+    }
+
+    /// Serve until the cancellation token fires, then drain in-flight requests.
+    pub async fn run(self, cancel: CancellationToken) {
+        let addr = SocketAddr::new(self.config.bind_addr, self.config.port);
+        let auth = AuthPolicy {
+            token: self.config.auth_token.clone(),
+            protect_metrics: self.config.protect_metrics,
+        };
+
+        let scheme = if self.tls_acceptor.is_some() { "https" } else { "http" };
+        let mtls = self
+            .config
+            .tls
+            .as_ref()
+            .is_some_and(TlsSettings::requires_client_cert);
+
+        if auth.token.is_some() {
+            info!(
+                "[HTTP] Control endpoints enabled (bearer auth{})",
+                if mtls { " + client certificates" } else { "" }
+            );
+            if self.tls_acceptor.is_none() && !self.config.bind_addr.is_loopback() {
+                warn!(
+                    "[HTTP] Control endpoints exposed over plain HTTP on {} (HTTP_ALLOW_PLAINTEXT_CONTROL=true)",
+                    addr
+                );
+            }
+        } else {
+            info!("[HTTP] Control endpoints disabled (HTTP_AUTH_TOKEN not set)");
+        }
+
+        log_listening(scheme, addr);
+        let result = match self.tls_acceptor {
+            Some(acceptor) => {
+                let incoming = tls_incoming(self.listener, acceptor, self.config.request_timeout, cancel.clone());
+                serve(incoming, self.state, auth, self.config.request_timeout, cancel).await
+            }
+            None => match AddrIncoming::from_listener(self.listener) {
+                Ok(incoming) => {
+                    serve(incoming, self.state, auth, self.config.request_timeout, cancel).await
+                }
+                Err(e) => Err(e),
+            },
+        };
+
+        if let Err(e) = result {
+            warn!("[HTTP] Server error: {}", e);
+        }
+        info!("[HTTP] Server stopped");
+    }
+}
+
+fn log_listening(scheme: &str, addr: SocketAddr) {
+    info!("[HTTP] Health check server listening on {}://{}", scheme, addr);
+    info!("[HTTP] Metrics available at {}://{}/metrics", scheme, addr);
+}
+
+/// Run the hyper server on any connection source until cancelled
+async fn serve<I>(
+    incoming: I,
+    state: Arc<HttpState>,
+    auth: AuthPolicy,
+    request_timeout: Duration,
+    cancel: CancellationToken,
+) -> hyper::Result<()>
+where
+    I: Accept,
+    I::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+    I::Conn: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let auth = Arc::new(auth);
+    let make_svc = make_service_fn(move |_conn: &I::Conn| {
+        let state = Arc::clone(&state);
+        let auth = Arc::clone(&auth);
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req| {
+                let state = Arc::clone(&state);
+                let auth = Arc::clone(&auth);
+                async move {
+                    let response =
+                        tokio::time::timeout(request_timeout, handle(req, &state, &auth))
+                            .await
+                            .unwrap_or_else(|_| {
+                                error_response(StatusCode::SERVICE_UNAVAILABLE, "request timed out")
+                            });
+                    Ok::<_, Infallible>(response)
+                }
+            }))
+        }
+    });
+
+    Server::builder(incoming)
+        .http1_header_read_timeout(request_timeout)
+        .serve(make_svc)
+        .with_graceful_shutdown(cancel.cancelled_owned())
+        .await
+}
+
+/// Accept TCP connections and complete TLS handshakes off the accept loop,
+/// so a slow or hostile client cannot stall other connections.
+fn tls_incoming(
+    listener: TcpListener,
+    acceptor: TlsAcceptor,
+    handshake_timeout: Duration,
+    cancel: CancellationToken,
+) -> impl Accept<Conn = tokio_rustls::server::TlsStream<tokio::net::TcpStream>, Error = std::io::Error>
+{
+    let (tx, rx) = mpsc::channel(64);
+
+    tokio::spawn(async move {
+        loop {
+            let (stream, peer) = tokio::select! {
+                _ = cancel.cancelled() => break,
+                accepted = listener.accept() => match accepted {
+                    Ok(conn) => conn,
+                    Err(e) => {
+                        warn!("[HTTP] Accept error: {}", e);
+                        continue;
+                    }
+                },
+            };
+
+            let acceptor = acceptor.clone();
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                match tokio::time::timeout(handshake_timeout, acceptor.accept(stream)).await {
+                    Ok(Ok(tls)) => {
+                        let _ = tx.send(Ok(tls)).await;
+                    }
+                    Ok(Err(e)) => {
+                        HTTP_UNAUTHORIZED.with_label_values(&["tls_handshake"]).inc();
+                        warn!("[HTTP] TLS handshake from {} rejected: {}", peer, e);
+                    }
+                    Err(_) => warn!("[HTTP] TLS handshake from {} timed out", peer),
+                }
+            });
+        }
+    });
+
+    accept::from_stream(futures::stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|conn| (conn, rx))
+    }))
+}
+
+/// Route a request and produce a response
+async fn handle(req: Request<Body>, state: &HttpState, auth: &AuthPolicy) -> Response<Body> {
+    let Some(route) = Route::from_path(req.uri().path()) else {
+        return error_response(StatusCode::NOT_FOUND, "not found");
+    };
+
+    let allowed = route.allowed_methods();
+    if !allowed.contains(req.method()) {
+        let mut response = error_response(StatusCode::METHOD_NOT_ALLOWED, "method not allowed");
+        let allow = allowed
+            .iter()
+            .map(Method::as_str)
+            .collect::<Vec<_>>()
+            .join(", ");
+        if let Ok(value) = HeaderValue::from_str(&allow) {
+            response.headers_mut().insert(ALLOW, value);
+        }
+        return response;
+    }
+
+    if route.requires_auth(auth) {
+        if let Some(rejection) = check_auth(&req, route, auth.token.as_deref()) {
+            return rejection;
+        }
+    }
+
+    let is_head = req.method() == Method::HEAD;
+    let response = match route {
+        Route::Health => text_response(StatusCode::OK, JSON_CONTENT_TYPE, health_body(state)),
+        Route::Ready => {
+            let ready = state.restarts.is_ready();
+            let body = serde_json::json!({
+                "ready": ready,
+                "components": state.restarts.restarts(),
+            });
+            let status = if ready {
+                StatusCode::OK
+            } else {
+                StatusCode::SERVICE_UNAVAILABLE
+            };
+            text_response(status, JSON_CONTENT_TYPE, body.to_string())
+        }
+        Route::Metrics => text_response(StatusCode::OK, METRICS_CONTENT_TYPE, metrics_body()),
+        Route::Status => match state.status.latest() {
+            Some(status) => {
+                let body = serde_json::to_string(&*status).unwrap_or_default();
+                text_response(StatusCode::OK, JSON_CONTENT_TYPE, body)
+            }
+            None => error_response(StatusCode::SERVICE_UNAVAILABLE, "no status yet"),
+        },
+        Route::Shutdown => {
+            warn!("[HTTP] Shutdown requested via control endpoint");
+            state.audit.record(HTTP_ACTOR, AuditAction::Shutdown, None, "POST /control/shutdown");
+            state.shutdown.cancel();
+            text_response(
+                StatusCode::ACCEPTED,
+                JSON_CONTENT_TYPE,
+                r#"{"status":"shutting_down"}"#.to_string(),
+            )
+        }
+        Route::EmergencyStop => {
+            state.risk_manager.emergency_stop();
+            state.audit.record(
+                HTTP_ACTOR,
+                AuditAction::EmergencyStop,
+                None,
+                "POST /control/emergency-stop",
+            );
+            text_response(
+                StatusCode::OK,
+                JSON_CONTENT_TYPE,
+                r#"{"status":"emergency_stopped"}"#.to_string(),
+            )
+        }
+        Route::Resume => {
+            state.risk_manager.clear_emergency_stop();
+            state.audit.record(
+                HTTP_ACTOR,
+                AuditAction::EmergencyStopCleared,
+                None,
+                "POST /control/resume",
+            );
+            text_response(
+                StatusCode::OK,
+                JSON_CONTENT_TYPE,
+                r#"{"status":"trading"}"#.to_string(),
+            )
+        }
+        Route::Audit => {
+            let body = serde_json::to_string(&state.audit.recent()).unwrap_or_default();
+            text_response(StatusCode::OK, JSON_CONTENT_TYPE, body)
+        }
+        Route::PnlAttribution => pnl_attribution_response(&req, state).await,
+        Route::Subscriptions => {
+            let body = serde_json::to_string(&state.subscriptions.plan()).unwrap_or_default();
+            text_response(StatusCode::OK, JSON_CONTENT_TYPE, body)
+        }
+        Route::DebugTrades => match parse_trade_filter(req.uri().query()) {
+            Ok(filter) => {
+                let trades = state.recent_trades.matching(&filter);
+                let body = serde_json::to_string(&trades).unwrap_or_default();
+                text_response(StatusCode::OK, JSON_CONTENT_TYPE, body)
+            }
+            Err(e) => error_response(StatusCode::BAD_REQUEST, &e),
+        },
+        Route::Simulate => simulate_response(req, state).await,
+        Route::FeeImport => fee_import_response(req, state).await,
+        Route::FeeErrors => fee_errors_response(&req, state).await,
+        Route::LogLevel => log_level_response(req, state).await,
+        Route::Stats => {
+            let body = serde_json::json!({
+                "volume": state.volume.snapshot(),
+                "min_edge": state.edge_tuner.snapshot(),
+            })
+            .to_string();
+            text_response(StatusCode::OK, JSON_CONTENT_TYPE, body)
+        }
+        Route::Portfolio => {
+            let body = serde_json::to_string(&state.portfolio.snapshot()).unwrap_or_default();
+            text_response(StatusCode::OK, JSON_CONTENT_TYPE, body)
+        }
+        Route::Approvals => {
+            let body = serde_json::to_string(&state.approvals.list()).unwrap_or_default();
+            text_response(StatusCode::OK, JSON_CONTENT_TYPE, body)
+        }
+        Route::Approve => approval_decision_response(&req, state, true),
+        Route::Reject => approval_decision_response(&req, state, false),
+    };
+
+    if is_head {
+        let (parts, _) = response.into_parts();
+        return Response::from_parts(parts, Body::empty());
+    }
+    response
+}
+
+/// Check the bearer token for a control endpoint, returning the rejection if any
+fn check_auth(req: &Request<Body>, route: Route, auth_token: Option<&str>) -> Option<Response<Body>> {
+    let Some(expected) = auth_token else {
+        HTTP_UNAUTHORIZED.with_label_values(&[route.label()]).inc();
+        warn!(
+            "[HTTP] Rejected {} {} (control endpoints disabled)",
+            req.method(),
+            req.uri().path()
+        );
+        return Some(error_response(
+            StatusCode::FORBIDDEN,
+            "control endpoints disabled",
+        ));
+    };
+
+    let provided = req
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+
+    match provided {
+        Some(token) if constant_time_eq(token.as_bytes(), expected.as_bytes()) => None,
+        _ => {
+            HTTP_UNAUTHORIZED.with_label_values(&[route.label()]).inc();
+            warn!("[HTTP] Unauthorized {} {}", req.method(), req.uri().path());
+            let mut response = error_response(StatusCode::UNAUTHORIZED, "unauthorized");
+            response
+                .headers_mut()
+                .insert(WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+            Some(response)
+        }
+    }
+}
+
+/// Compare secrets without short-circuiting on the first mismatch
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Query of `GET /control/pnl-attribution`
+#[derive(Debug, Clone, PartialEq)]
+struct AttributionQuery {
+    /// Days back, including today
+    days: i32,
+    by: Vec<AttributionDimension>,
+    /// Only paper (true) or live (false) trades; both when unset
+    paper: Option<bool>,
+}
+
+impl AttributionQuery {
+    const DEFAULT_DAYS: i32 = 7;
+    const MAX_DAYS: i32 = 365;
+
+    /// Parse `days=7&by=strategy,category&paper=false`.
+    fn parse(query: Option<&str>) -> Result<Self, String> {
+        let mut parsed = Self {
+            days: Self::DEFAULT_DAYS,
+            by: vec![AttributionDimension::Strategy],
+            paper: None,
+        };
+        for pair in query.unwrap_or("").split('&').filter(|p| !p.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            match key {
+                "days" => {
+                    parsed.days = value
+                        .parse()
+                        .ok()
+                        .filter(|d| (1..=Self::MAX_DAYS).contains(d))
+                        .ok_or_else(|| format!("days must be 1-{}", Self::MAX_DAYS))?;
+                }
+                "by" => parsed.by = AttributionDimension::parse_list(&value.replace("%2C", ","))?,
+                "paper" => {
+                    parsed.paper = Some(
+                        value
+                            .parse()
+                            .map_err(|_| "paper must be true or false".to_string())?,
+                    );
+                }
+                other => return Err(format!("unknown parameter '{}'", other)),
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+/// Approve or reject the parked signal named by `?id=N`.
+fn approval_decision_response(req: &Request<Body>, state: &HttpState, approve: bool) -> Response<Body> {
+    let id = req
+        .uri()
+        .query()
+        .unwrap_or("")
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("id="))
+        .and_then(|id| id.parse::<u64>().ok());
+    let Some(id) = id else {
+        return error_response(StatusCode::BAD_REQUEST, "id must be a pending approval id");
+    };
+
+    let (result, action, status) = if approve {
+        (state.approvals.approve(id), AuditAction::ApprovalGranted, "approved")
+    } else {
+        (state.approvals.reject(id), AuditAction::ApprovalRejected, "rejected")
+    };
+    match result {
+        Ok(description) => {
+            state.audit.record(HTTP_ACTOR, action, Some(&id.to_string()), description);
+            let body = serde_json::json!({ "id": id, "status": status }).to_string();
+            text_response(StatusCode::OK, JSON_CONTENT_TYPE, body)
+        }
+        Err(e @ ApprovalError::NotFound) => error_response(StatusCode::NOT_FOUND, &e.to_string()),
+        Err(e @ ApprovalError::EngineStopped) => {
+            error_response(StatusCode::SERVICE_UNAVAILABLE, &e.to_string())
+        }
+    }
+}
+
+/// Parse the `/debug/trades` query:
+/// `strategy=SumTo100&min_edge=0.05&max_edge=0.075&min_latency_ms=250`.
+fn parse_trade_filter(query: Option<&str>) -> Result<TradeFilter, String> {
+    let mut filter = TradeFilter::default();
+    for pair in query.unwrap_or("").split('&').filter(|p| !p.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let number = || {
+            value
+                .parse::<f64>()
+                .ok()
+                .filter(|v| v.is_finite())
+                .ok_or_else(|| format!("{} must be a number", key))
+        };
+        match key {
+            "strategy" => filter.strategy = Some(value.to_string()),
+            "min_edge" => filter.min_edge = Some(number()?),
+            "max_edge" => filter.max_edge = Some(number()?),
+            "min_latency_ms" => filter.min_latency_ms = Some(number()?),
+            other => return Err(format!("unknown parameter '{}'", other)),
+        }
+    }
+    Ok(filter)
+}
+
+async fn pnl_attribution_response(req: &Request<Body>, state: &HttpState) -> Response<Body> {
+    let query = match AttributionQuery::parse(req.uri().query()) {
+        Ok(q) => q,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, &e),
+    };
+    if !state.trade_repo.is_enabled() {
+        return error_response(StatusCode::SERVICE_UNAVAILABLE, "database disabled");
+    }
+
+    match state
+        .trade_repo
+        .pnl_attribution(query.days, &query.by, query.paper)
+        .await
+    {
+        Ok(rows) => {
+            let by: Vec<&str> = query.by.iter().map(|d| d.as_str()).collect();
+            let body = serde_json::json!({
+                "days": query.days,
+                "by": by,
+                "paper": query.paper,
+                "rows": rows,
+            });
+            text_response(StatusCode::OK, JSON_CONTENT_TYPE, body.to_string())
+        }
+        Err(e) => {
+            warn!("[HTTP] P&L attribution query failed: {:#}", e);
+            error_response(StatusCode::SERVICE_UNAVAILABLE, "query failed")
+        }
+    }
+}
+
+/// Body of `POST /control/simulate`
+#[derive(Debug, Deserialize)]
+struct SimulateRequest {
+    token_id: TokenId,
+    /// "buy" or "sell"
+    side: String,
+    size: f64,
+}
+
+/// Largest accepted `POST /control/simulate` body
+const MAX_SIMULATE_BODY: usize = 4 * 1024;
+
+/// Read a request body, refusing anything over `limit` bytes
+async fn read_body(body: Body, limit: usize) -> Result<Vec<u8>, Response<Body>> {
+    let mut body = body;
+    let mut buf = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk =
+            chunk.map_err(|_| error_response(StatusCode::BAD_REQUEST, "unreadable body"))?;
+        if buf.len() + chunk.len() > limit {
+            return Err(error_response(StatusCode::PAYLOAD_TOO_LARGE, "body too large"));
+        }
+        buf.extend_from_slice(&chunk);
+    }
+    Ok(buf)
+}
+
+/// Largest accepted `PUT /loglevel` body
+const MAX_LOG_FILTER_BODY: usize = 4 * 1024;
+
+/// Show the log filter, or replace it with the `RUST_LOG`-style directives
+/// in the body of a PUT (`reset` restores the startup filter).
+async fn log_level_response(req: Request<Body>, state: &HttpState) -> Response<Body> {
+    if req.method() == Method::PUT {
+        let body = match read_body(req.into_body(), MAX_LOG_FILTER_BODY).await {
+            Ok(body) => body,
+            Err(response) => return response,
+        };
+        let directives = String::from_utf8_lossy(&body);
+        let current = match state.log_filter.set(&directives) {
+            Ok(current) => current,
+            Err(e) => return error_response(StatusCode::BAD_REQUEST, &format!("{:#}", e)),
+        };
+        info!("[HTTP] Log filter set to '{}'", current);
+        state.audit.record(
+            HTTP_ACTOR,
+            AuditAction::ConfigChange,
+            Some("log_filter"),
+            format!("PUT /loglevel: {}", current),
+        );
+    }
+    let body = serde_json::json!({ "filter": state.log_filter.current() }).to_string();
+    text_response(StatusCode::OK, JSON_CONTENT_TYPE, body)
+}
+
+/// Walk the live book for a what-if order and report its VWAP, costs, the
+/// resulting position, and which risk checks it would pass. Nothing is
+/// placed and no risk counters move.
+async fn simulate_response(req: Request<Body>, state: &HttpState) -> Response<Body> {
+    let body = match read_body(req.into_body(), MAX_SIMULATE_BODY).await {
+        Ok(body) => body,
+        Err(response) => return response,
+    };
+    let sim: SimulateRequest = match serde_json::from_slice(&body) {
+        Ok(sim) => sim,
+        Err(_) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                "expected {token_id, side, size}",
+            )
+        }
+    };
+    let side = match sim.side.to_lowercase().as_str() {
+        "buy" => Side::Buy,
+        "sell" => Side::Sell,
+        _ => return error_response(StatusCode::BAD_REQUEST, "side must be buy or sell"),
+    };
+    if !sim.size.is_finite() || sim.size <= 0.0 {
+        return error_response(StatusCode::BAD_REQUEST, "size must be positive");
+    }
+
+    let Some(book) = state.market_data.get_order_book(&sim.token_id) else {
+        return error_response(StatusCode::NOT_FOUND, "no order book for token");
+    };
+    let fill = match side {
+        Side::Buy => book.vwap_buy(sim.size),
+        Side::Sell => book.vwap_sell(sim.size),
+    };
+    let Some(fill) = fill else {
+        return error_response(StatusCode::UNPROCESSABLE_ENTITY, "no liquidity on that side");
+    };
+
+    let signal = match side {
+        Side::Buy => TradeSignal::Buy {
+            token_id: sim.token_id.clone(),
+            price: fill.vwap,
+            size: sim.size,
+            reason: "simulation".to_string(),
+            metadata: SignalMetadata::new(),
+        },
+        Side::Sell => TradeSignal::Sell {
+            token_id: sim.token_id.clone(),
+            price: fill.vwap,
+            size: sim.size,
+            reason: "simulation".to_string(),
+            metadata: SignalMetadata::new(),
+        },
+    };
+    let costs = state.risk_manager.cost_model().estimate(&signal);
+    let checks = state.risk_manager.evaluate(&signal);
+    let position = state
+        .risk_manager
+        .get_position(&sim.token_id)
+        .unwrap_or_default();
+    let (after, realized_pnl) = position_after(&position, side, fill.vwap, sim.size);
+
+    let body = serde_json::json!({
+        "token_id": sim.token_id,
+        "side": side,
+        "size": sim.size,
+        "vwap": fill.vwap,
+        "fillable_size": fill.total_size,
+        "levels_used": fill.levels_used,
+        "notional": costs.notional,
+        "fees": costs.fees,
+        "slippage": costs.slippage,
+        "realized_pnl": realized_pnl,
+        "position": {"size": position.size, "avg_cost": position.avg_cost},
+        "resulting_position": {"size": after.size, "avg_cost": after.avg_cost},
+        "approved": checks.iter().all(|c| c.passed),
+        "checks": checks,
+    });
+    text_response(StatusCode::OK, JSON_CONTENT_TYPE, body.to_string())
+}
+
+/// Position after filling `size` at `price`, and the P&L a sell would
+/// realize, booked as `RiskManager::record_trade` would (an oversized sell
+/// only closes what is held).
+fn position_after(position: &Position, side: Side, price: f64, size: f64) -> (Position, f64) {
+    let mut after = position.clone();
+    match side {
+        Side::Buy => {
+            let total_cost = after.avg_cost * after.size + price * size;
+            after.size += size;
+            if after.size > 0.0 {
+                after.avg_cost = total_cost / after.size;
+            }
+            (after, 0.0)
+        }
+        Side::Sell => {
+            let sold = size.min(after.size);
+            let pnl = (price - after.avg_cost) * sold;
+            after.size -= sold;
+            after.realized_pnl += pnl;
+            (after, pnl)
+        }
+    }
+}
+
+/// Largest accepted fee statement upload
+const MAX_STATEMENT_BODY: usize = 8 * 1024 * 1024;
+
+/// Import an exchange fee statement (CSV or JSON) and reconcile it against
+/// recorded trades.
+async fn fee_import_response(req: Request<Body>, state: &HttpState) -> Response<Body> {
+    if !state.trade_repo.is_enabled() {
+        return error_response(StatusCode::SERVICE_UNAVAILABLE, "database disabled");
+    }
+    let body = match read_body(req.into_body(), MAX_STATEMENT_BODY).await {
+        Ok(body) => body,
+        Err(response) => return response,
+    };
+    let Ok(body) = String::from_utf8(body) else {
+        return error_response(StatusCode::BAD_REQUEST, "statement must be UTF-8");
+    };
+    let fees = match parse_statement(&body) {
+        Ok(fees) => fees,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, &e),
+    };
+
+    match state.trade_repo.reconcile_fees(&fees).await {
+        Ok(report) => {
+            info!(
+                "[HTTP] Fee statement imported: {}/{} orders matched, ${:.2} in fees",
+                report.matched, report.orders, report.fees
+            );
+            state.audit.record(
+                HTTP_ACTOR,
+                AuditAction::FeeImport,
+                None,
+                format!("{} orders, {} matched", report.orders, report.matched),
+            );
+            let body = serde_json::to_string(&report).unwrap_or_default();
+            text_response(StatusCode::OK, JSON_CONTENT_TYPE, body)
+        }
+        Err(e) => {
+            warn!("[HTTP] Fee reconciliation failed: {:#}", e);
+            error_response(StatusCode::SERVICE_UNAVAILABLE, "reconciliation failed")
+        }
+    }
+}
+
+/// Parse the `/control/fee-errors` query: `days=30`.
+fn parse_fee_errors_days(query: Option<&str>) -> Result<i32, String> {
+    const DEFAULT_DAYS: i32 = 30;
+    let mut days = DEFAULT_DAYS;
+    for pair in query.unwrap_or("").split('&').filter(|p| !p.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        match key {
+            "days" => {
+                days = value
+                    .parse()
+                    .ok()
+                    .filter(|d| (1..=AttributionQuery::MAX_DAYS).contains(d))
+                    .ok_or_else(|| format!("days must be 1-{}", AttributionQuery::MAX_DAYS))?;
+            }
+            other => return Err(format!("unknown parameter '{}'", other)),
+        }
+    }
+    Ok(days)
+}
+
+async fn fee_errors_response(req: &Request<Body>, state: &HttpState) -> Response<Body> {
+    let days = match parse_fee_errors_days(req.uri().query()) {
+        Ok(days) => days,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, &e),
+    };
+    if !state.trade_repo.is_enabled() {
+        return error_response(StatusCode::SERVICE_UNAVAILABLE, "database disabled");
+    }
+
+    match state.trade_repo.fee_estimate_errors(days).await {
+        Ok(markets) => {
+            let body = serde_json::json!({
+                "days": days,
+                "total": FeeErrorRow::total(&markets),
+                "markets": markets,
+            });
+            text_response(StatusCode::OK, JSON_CONTENT_TYPE, body.to_string())
+        }
+        Err(e) => {
+            warn!("[HTTP] Fee error query failed: {:#}", e);
+            error_response(StatusCode::SERVICE_UNAVAILABLE, "query failed")
+        }
+    }
+}
+
+fn health_body(state: &HttpState) -> String {
+    let uptime = state.start_time.elapsed().as_secs();
+    let tokens = state.market_data.token_count();
+    let order_books = state.market_data.order_book_count();
+    let markets = state.market_data.market_count();
+    let has_data = state.market_data.has_data();
+
+    let status = if has_data {
+        "healthy"
+    } else {
+        "waiting_for_data"
+    };
+
+    format!(
+        r#"{{"status":"{}","role":"{}","uptime_secs":{},"tokens":{},"order_books":{},"markets":{},"has_data":{}}}"#,
+        status,
+        state.leader.role(),
+        uptime,
+        tokens,
+        order_books,
+        markets,
+        has_data
+    )
+}
+
+/// Encode all registered Prometheus metrics
+fn metrics_body() -> String {
+    use prometheus::Encoder;
+    let encoder = prometheus::TextEncoder::new();
+    let metric_families = prometheus::gather();
+    let mut buffer = Vec::new();
+    encoder
+        .encode(&metric_families, &mut buffer)
+        .unwrap_or_default();
+    String::from_utf8(buffer).unwrap_or_default()
+}
+
+fn text_response(status: StatusCode, content_type: &'static str, body: String) -> Response<Body> {
+    let mut response = Response::new(Body::from(body));
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+    response
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response<Body> {
+    text_response(
+        status,
+        JSON_CONTENT_TYPE,
+        format!(r#"{{"error":"{}"}}"#, message),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RiskConfig;
+    use crate::db::TradeRepository;
+    use crate::market::{DepthLevel, SubscriptionConfig};
+    use crate::restarts::RestartConfig;
+    use crate::status::EngineState;
+    use crate::strategy::{ApprovalConfig, EdgeTuningConfig};
+
+    fn test_state() -> HttpState {
+        let market_data = Arc::new(MarketData::new());
+        let risk_manager = Arc::new(RiskManager::new(RiskConfig::default()));
+        HttpState {
+            start_time: Instant::now(),
+            subscriptions: Arc::new(SubscriptionPrioritizer::new(
+                SubscriptionConfig::default(),
+                market_data.clone(),
+            )),
+            portfolio: Arc::new(Portfolio::new(market_data.clone(), risk_manager.clone())),
+            market_data,
+            risk_manager,
+            audit: Arc::new(AuditLog::new()),
+            leader: Arc::new(LeaderElection::disabled("default")),
+            trade_repo: Arc::new(TradeRepository::disabled()),
+            recent_trades: Arc::new(RecentTrades::new()),
+            shutdown: CancellationToken::new(),
+            log_filter: Arc::new(LogFilter::for_test("poly_rust=info")),
+            volume: Arc::new(VolumeTracker::default()),
+            edge_tuner: Arc::new(EdgeTuner::new(EdgeTuningConfig {
+                max_min_edge: 0.05,
+                ..EdgeTuningConfig::default()
+            })),
+            approvals: Arc::new(ApprovalQueue::for_test(ApprovalConfig::default())),
+            status: Arc::new(StatusBoard::new()),
+            restarts: Arc::new(TaskSupervisor::new(RestartConfig::default())),
+        }
+    }
+
+    fn token_auth(token: &str) -> AuthPolicy {
+        AuthPolicy {
+            token: Some(token.to_string()),
+            protect_metrics: false,
+        }
+    }
+
+    fn request(method: Method, path: &str, token: Option<&str>) -> Request<Body> {
+        let mut builder = Request::builder().method(method).uri(path);
+        if let Some(token) = token {
+            builder = builder.header(AUTHORIZATION, format!("Bearer {}", token));
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    #[test]
+    fn test_tls_needs_both_cert_and_key() {
+        let cert = || Some("cert.pem".to_string());
+        let key = || Some("key.pem".to_string());
+
+        assert!(tls_settings(cert(), key(), None).unwrap().is_some());
+        assert!(tls_settings(None, None, None).unwrap().is_none());
+        assert!(tls_settings(cert(), None, None).is_err());
+        assert!(tls_settings(None, key(), None).is_err());
+        assert!(tls_settings(None, None, Some("ca.pem".into())).is_err());
+    }
+
+    #[test]
+    fn test_plaintext_control_needs_loopback_or_opt_in() {
+        let config = HttpServerConfig {
+            auth_token: Some("secret".into()),
+            ..HttpServerConfig::default()
+        };
+        assert!(config.bind_addr.is_loopback());
+        assert!(config.validate().is_ok());
+
+        let public = HttpServerConfig {
+            bind_addr: IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            ..config.clone()
+        };
+        assert!(public.validate().is_err());
+
+        // Health and metrics alone may be public
+        let health_only = HttpServerConfig {
+            auth_token: None,
+            ..public.clone()
+        };
+        assert!(health_only.validate().is_ok());
+
+        let opted_in = HttpServerConfig {
+            allow_plaintext_control: true,
+            ..public.clone()
+        };
+        assert!(opted_in.validate().is_ok());
+
+        let tls = HttpServerConfig {
+            tls: tls_settings(Some("cert.pem".into()), Some("key.pem".into()), None).unwrap(),
+            ..public
+        };
+        assert!(tls.validate().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_bind_fails_on_unreadable_tls_files() {
+        let config = HttpServerConfig {
+            port: 0,
+            tls: tls_settings(
+                Some("/nonexistent/cert.pem".into()),
+                Some("/nonexistent/key.pem".into()),
+                None,
+            )
+            .unwrap(),
+            ..HttpServerConfig::default()
+        };
+        let err = HttpServer::bind(config, test_state()).await.err().unwrap();
+        assert!(format!("{:#}", err).contains("/nonexistent/cert.pem"));
+    }
+
+    #[tokio::test]
+    async fn test_routes_health_and_metrics() {
+        let state = test_state();
+
+        let response = handle(request(Method::GET, "/health", None), &state, &AuthPolicy::default()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CONTENT_TYPE], JSON_CONTENT_TYPE);
+
+        let response = handle(request(Method::GET, "/ready", None), &state, &AuthPolicy::default()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = handle(request(Method::GET, "/metrics", None), &state, &AuthPolicy::default()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CONTENT_TYPE], METRICS_CONTENT_TYPE);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_path_and_wrong_method() {
+        let state = test_state();
+
+        let response = handle(request(Method::GET, "/nope", None), &state, &AuthPolicy::default()).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        // Old server matched any path containing "/metrics"
+        let response = handle(request(Method::GET, "/x/metrics", None), &state, &AuthPolicy::default()).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = handle(request(Method::POST, "/health", None), &state, &AuthPolicy::default()).await;
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(response.headers()[ALLOW], "GET, HEAD");
+    }
+
+    #[tokio::test]
+    async fn test_control_endpoint_auth() {
+        let state = test_state();
+
+        // Disabled without a configured token
+        let response = handle(
+            request(Method::POST, "/control/shutdown", None),
+            &state,
+            &AuthPolicy::default(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = handle(
+            request(Method::POST, "/control/shutdown", Some("wrong")),
+            &state,
+            &token_auth("secret"),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert!(!state.shutdown.is_cancelled());
+
+        let response = handle(
+            request(Method::POST, "/control/shutdown", Some("secret")),
+            &state,
+            &token_auth("secret"),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        assert!(state.shutdown.is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn test_protected_metrics_and_unauthorized_counter() {
+        crate::metrics::init();
+        let state = test_state();
+        let auth = AuthPolicy {
+            token: Some("secret".to_string()),
+            protect_metrics: true,
+        };
+        let before = HTTP_UNAUTHORIZED.with_label_values(&["metrics"]).get();
+
+        let response = handle(request(Method::GET, "/metrics", None), &state, &auth).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.headers()[WWW_AUTHENTICATE], "Bearer");
+        assert_eq!(
+            HTTP_UNAUTHORIZED.with_label_values(&["metrics"]).get(),
+            before + 1.0
+        );
+
+        let response = handle(request(Method::GET, "/metrics", Some("secret")), &state, &auth).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Health stays open for container health checks
+        let response = handle(request(Method::GET, "/health", None), &state, &auth).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_emergency_stop_and_resume_are_audited() {
+        let state = test_state();
+        let auth = token_auth("secret");
+
+        let response = handle(
+            request(Method::POST, "/control/emergency-stop", Some("secret")),
+            &state,
+            &auth,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(state.risk_manager.is_emergency_stopped());
+
+        let response = handle(
+            request(Method::POST, "/control/resume", Some("secret")),
+            &state,
+            &auth,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(!state.risk_manager.is_emergency_stopped());
+
+        let actions: Vec<_> = state.audit.recent().iter().map(|e| e.action).collect();
+        assert_eq!(
+            actions,
+            vec![AuditAction::EmergencyStop, AuditAction::EmergencyStopCleared]
+        );
+
+        // Audit trail itself requires auth
+        let response = handle(request(Method::GET, "/control/audit", None), &state, &auth).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_pnl_attribution_query_and_route() {
+        assert_eq!(
+            AttributionQuery::parse(Some("days=30&by=weekday%2Ccategory&paper=false")),
+            Ok(AttributionQuery {
+                days: 30,
+                by: vec![AttributionDimension::Weekday, AttributionDimension::Category],
+                paper: Some(false),
+            })
+        );
+        assert_eq!(AttributionQuery::parse(None).unwrap().days, 7);
+        assert!(AttributionQuery::parse(Some("days=0")).is_err());
+        assert!(AttributionQuery::parse(Some("by=market;1")).is_err());
+        assert!(AttributionQuery::parse(Some("limit=5")).is_err());
+
+        let state = test_state();
+        let auth = token_auth("secret");
+        let path = "/control/pnl-attribution?by=category";
+        let response = handle(request(Method::GET, path, None), &state, &auth).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = handle(request(Method::GET, path, Some("secret")), &state, &auth).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        let response = handle(
+            request(Method::GET, "/control/pnl-attribution?by=nope", Some("secret")),
+            &state,
+            &auth,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_debug_trades_filters_recent_trades() {
+        assert_eq!(
+            parse_trade_filter(Some("strategy=SumTo100&min_edge=0.05&min_latency_ms=250")),
+            Ok(TradeFilter {
+                strategy: Some("SumTo100".into()),
+                min_edge: Some(0.05),
+                max_edge: None,
+                min_latency_ms: Some(250.0),
+            })
+        );
+        assert!(parse_trade_filter(Some("min_edge=abc")).is_err());
+        assert!(parse_trade_filter(Some("limit=5")).is_err());
+
+        let state = test_state();
+        let auth = token_auth("secret");
+        let response = handle(request(Method::GET, "/debug/trades", None), &state, &auth).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = handle(
+            request(Method::GET, "/debug/trades?min_edge=0.01", Some("secret")),
+            &state,
+            &auth,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(&body[..], b"[]");
+    }
+
+    #[tokio::test]
+    async fn test_subscriptions_endpoint() {
+        let state = test_state();
+        let auth = token_auth("secret");
+        let response = handle(request(Method::GET, "/control/subscriptions", None), &state, &auth).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = handle(
+            request(Method::GET, "/control/subscriptions", Some("secret")),
+            &state,
+            &auth,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let plan: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(plan["max_assets"], 0);
+        assert_eq!(plan["core"], serde_json::json!([]));
+    }
+
+    async fn simulate(state: &HttpState, body: &str) -> (StatusCode, serde_json::Value) {
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("/control/simulate")
+            .header(AUTHORIZATION, "Bearer secret")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = handle(req, state, &token_auth("secret")).await;
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_simulate_endpoint() {
+        let state = test_state();
+        state.market_data.update_order_book(
+            &"token1".to_string(),
+            vec![DepthLevel { price: 0.45, size: 10.0 }],
+            vec![
+                DepthLevel { price: 0.50, size: 10.0 },
+                DepthLevel { price: 0.60, size: 10.0 },
+            ],
+        );
+
+        let (status, sim) = simulate(&state, r#"{"token_id":"token1","side":"buy","size":15}"#).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!((sim["vwap"].as_f64().unwrap() - 8.0 / 15.0).abs() < 1e-9);
+        assert_eq!(sim["levels_used"], 2);
+        assert_eq!(sim["resulting_position"]["size"], 15.0);
+        assert_eq!(sim["approved"], true);
+
+        // Selling what isn't held fails the position check but still reports
+        let (status, sim) = simulate(&state, r#"{"token_id":"token1","side":"sell","size":5}"#).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(sim["approved"], false);
+        let failed: Vec<&str> = sim["checks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|c| c["passed"] == false)
+            .map(|c| c["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(failed, ["insufficient_position"]);
+
+        // Simulating never records a trade
+        assert!(state.risk_manager.get_position(&"token1".to_string()).is_none());
+
+        let (status, _) = simulate(&state, r#"{"token_id":"token1","side":"hold","size":5}"#).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = simulate(&state, r#"{"token_id":"nope","side":"buy","size":5}"#).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_fee_endpoints() {
+        assert_eq!(parse_fee_errors_days(None), Ok(30));
+        assert_eq!(parse_fee_errors_days(Some("days=7")), Ok(7));
+        assert!(parse_fee_errors_days(Some("days=0")).is_err());
+        assert!(parse_fee_errors_days(Some("market=x")).is_err());
+
+        let state = test_state();
+        let auth = token_auth("secret");
+        let response = handle(request(Method::POST, "/control/fees/import", None), &state, &auth).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // Without a database there is nothing to reconcile against
+        let response = handle(
+            request(Method::POST, "/control/fees/import", Some("secret")),
+            &state,
+            &auth,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let response = handle(
+            request(Method::GET, "/control/fee-errors?days=7", Some("secret")),
+            &state,
+            &auth,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn test_log_level_endpoint() {
+        let state = test_state();
+        let auth = token_auth("secret");
+        let put = |body: &str| {
+            Request::builder()
+                .method(Method::PUT)
+                .uri("/loglevel")
+                .header(AUTHORIZATION, "Bearer secret")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+
+        let response = handle(request(Method::PUT, "/loglevel", None), &state, &auth).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = handle(put("poly_rust=info,poly_rust::ws=debug"), &state, &auth).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(state.log_filter.current().contains("poly_rust::ws=debug"));
+        assert_eq!(state.audit.recent()[0].action, AuditAction::ConfigChange);
+
+        let response = handle(put("poly_rust=loud"), &state, &auth).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = handle(put("reset"), &state, &auth).await;
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(&body[..], br#"{"filter":"poly_rust=info"}"#);
+    }
+
+    #[tokio::test]
+    async fn test_stats_reports_monthly_volume() {
+        let state = test_state();
+        let auth = token_auth("secret");
+        state.volume.record("m1", 30.0, true);
+        state.volume.record("m1", 12.5, false);
+
+        let response = handle(request(Method::GET, "/stats", None), &state, &auth).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = handle(request(Method::GET, "/stats", Some("secret")), &state, &auth).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let stats: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(stats["volume"]["total"]["maker_volume"], 30.0);
+        assert_eq!(stats["volume"]["markets"]["m1"]["taker_fills"], 1);
+        assert!(stats["min_edge"].as_object().unwrap().is_empty());
+
+        // A market with shortfall reports its raised minimum edge
+        state.edge_tuner.record("m1", 0.01, 0.0);
+        state.edge_tuner.min_edge("m1", 0.003);
+        let response = handle(request(Method::GET, "/stats", Some("secret")), &state, &auth).await;
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let stats: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let effective = stats["min_edge"]["m1"]["effective_min_edge"]
+            .as_f64()
+            .unwrap();
+        assert!((effective - 0.013).abs() < 1e-12);
+    }
+
+    #[tokio::test]
+    async fn test_portfolio_serves_positions_and_nav() {
+        let state = test_state();
+        let auth = token_auth("secret");
+        state.risk_manager.record_trade(&TradeSignal::Buy {
+            token_id: "token-1".into(),
+            price: 0.4,
+            size: 10.0,
+            reason: "test".into(),
+            metadata: SignalMetadata::new(),
+        });
+        state
+            .market_data
+            .update_price(&"token-1".into(), 0.49, 0.51);
+
+        let response = handle(request(Method::GET, "/portfolio", None), &state, &auth).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = handle(
+            request(Method::GET, "/portfolio", Some("secret")),
+            &state,
+            &auth,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let portfolio: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(portfolio["positions"][0]["token_id"], "token-1");
+        assert_eq!(portfolio["positions"][0]["mark"], 0.5);
+        assert_eq!(portfolio["categories"]["uncategorized"]["positions"], 1);
+        assert_eq!(portfolio["cash"], serde_json::Value::Null);
+        let unrealized = portfolio["unrealized_pnl"].as_f64().unwrap();
+        assert!((unrealized - 1.0).abs() < 1e-9);
+        assert_eq!(portfolio["nav"], 5.0);
+    }
+
+    #[tokio::test]
+    async fn test_status_serves_latest_snapshot() {
+        let state = test_state();
+        let auth = token_auth("secret");
+
+        let response = handle(request(Method::GET, "/status", None), &state, &auth).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        state.status.publish(EngineState {
+            timestamp_ms: 1,
+            status: "waiting_for_data".into(),
+            uptime_secs: 5,
+            evaluations: 0,
+            markets_tracked: 0,
+            order_books: 0,
+            opportunities_found: 0,
+            daily_pnl: 0.0,
+            daily_trades: 0,
+            equity: 0.0,
+            drawdown: 0.0,
+            max_drawdown: 0.0,
+            positions: vec![],
+            strategies: vec![],
+        });
+        let response = handle(request(Method::GET, "/status", None), &state, &auth).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let status: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(status["status"], "waiting_for_data");
+        assert_eq!(status["uptime_secs"], 5);
+    }
+
+    #[tokio::test]
+    async fn test_approvals_are_decided_and_audited() {
+        let approvals = ApprovalQueue::for_test(ApprovalConfig {
+            enabled: true,
+            notional_threshold: 100.0,
+            ..ApprovalConfig::default()
+        });
+        let state = HttpState {
+            approvals: Arc::new(approvals),
+            ..test_state()
+        };
+        let auth = token_auth("secret");
+        let signal = TradeSignal::Buy {
+            token_id: "token-1".into(),
+            price: 0.5,
+            size: 400.0,
+            reason: "test".into(),
+            metadata: SignalMetadata::new(),
+        };
+        let first = state.approvals.park_for_test("Clipper", &signal).unwrap();
+        let second = state.approvals.park_for_test("Clipper", &signal).unwrap();
+
+        let response = handle(request(Method::GET, "/control/approvals", Some("secret")), &state, &auth).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let pending: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(pending.as_array().unwrap().len(), 2);
+        assert_eq!(pending[0]["notional"], 200.0);
+
+        let path = format!("/control/approvals/approve?id={}", first);
+        let response = handle(request(Method::POST, &path, Some("secret")), &state, &auth).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(state.approvals.list().len(), 1);
+        let response = handle(request(Method::POST, &path, Some("secret")), &state, &auth).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let path = format!("/control/approvals/reject?id={}", second);
+        let response = handle(request(Method::POST, &path, Some("secret")), &state, &auth).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(state.approvals.list().is_empty());
+
+        let response = handle(
+            request(Method::POST, "/control/approvals/approve", Some("secret")),
+            &state,
+            &auth,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let actions: Vec<_> = state.audit.recent().iter().map(|e| e.action).collect();
+        assert_eq!(
+            actions,
+            vec![AuditAction::ApprovalGranted, AuditAction::ApprovalRejected]
+        );
+    }
+}
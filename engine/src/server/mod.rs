@@ -0,0 +1,16 @@
+//! HTTP server for health checks, Prometheus metrics, and control endpoints.
+//!
+//! Read-only endpoints (`/health`, `/ready`, `/metrics`) are always open. Control
+//! endpoints under `/control/` (shutdown, emergency-stop, resume, audit,
+//! pnl-attribution) and `/debug/trades` require a bearer token when
+//! `HTTP_AUTH_TOKEN` is set and are refused entirely otherwise. Every control
+//! action is written to the audit log. TLS and client-certificate auth are
+//! enabled via `HTTP_TLS_*`.
+
+mod http;
+mod tls;
+
+#[allow(unused_imports)]
+pub use http::{HttpServer, HttpServerConfig, HttpState};
+#[allow(unused_imports)]
+pub use tls::TlsSettings;
@@ -0,0 +1,119 @@
+//! Optional rustls termination (and mTLS) for the HTTP server.
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{bail, Context, Result};
+use tokio_rustls::rustls::server::AllowAnyAuthenticatedClient;
+use tokio_rustls::rustls::{Certificate, PrivateKey, RootCertStore, ServerConfig};
+use tokio_rustls::TlsAcceptor;
+
+/// TLS certificate paths
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TlsSettings {
+    /// PEM certificate chain presented to clients
+    pub cert_path: PathBuf,
+
+    /// PEM private key (PKCS#8, PKCS#1, or SEC1)
+    pub key_path: PathBuf,
+
+    /// PEM CA bundle; when set, clients must present a certificate it signed
+    pub client_ca_path: Option<PathBuf>,
+}
+
+impl TlsSettings {
+    /// Build a TLS acceptor from the configured PEM files.
+    pub fn acceptor(&self) -> Result<TlsAcceptor> {
+        let certs = load_certs(&self.cert_path)?;
+        let key = load_private_key(&self.key_path)?;
+
+        let builder = ServerConfig::builder().with_safe_defaults();
+        let builder = match &self.client_ca_path {
+            Some(ca_path) => {
+                let mut roots = RootCertStore::empty();
+                for cert in load_certs(ca_path)? {
+                    roots
+                        .add(&cert)
+                        .with_context(|| format!("Invalid client CA in {}", ca_path.display()))?;
+                }
+                builder.with_client_cert_verifier(AllowAnyAuthenticatedClient::new(roots).boxed())
+            }
+            None => builder.with_no_client_auth(),
+        };
+
+        let mut config = builder
+            .with_single_cert(certs, key)
+            .context("Invalid TLS certificate/key pair")?;
+        config.alpn_protocols = vec![b"http/1.1".to_vec()];
+
+        Ok(TlsAcceptor::from(Arc::new(config)))
+    }
+
+    /// Whether clients must authenticate with a certificate
+    pub fn requires_client_cert(&self) -> bool {
+        self.client_ca_path.is_some()
+    }
+}
+
+fn open_pem(path: &Path) -> Result<BufReader<File>> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    Ok(BufReader::new(file))
+}
+
+fn load_certs(path: &Path) -> Result<Vec<Certificate>> {
+    let certs = rustls_pemfile::certs(&mut open_pem(path)?)
+        .with_context(|| format!("Failed to parse certificates in {}", path.display()))?;
+    if certs.is_empty() {
+        bail!("No certificates found in {}", path.display());
+    }
+    Ok(certs.into_iter().map(Certificate).collect())
+}
+
+fn load_private_key(path: &Path) -> Result<PrivateKey> {
+    let mut reader = open_pem(path)?;
+    while let Some(item) = rustls_pemfile::read_one(&mut reader)
+        .with_context(|| format!("Failed to parse private key in {}", path.display()))?
+    {
+        match item {
+            rustls_pemfile::Item::PKCS8Key(key)
+            | rustls_pemfile::Item::RSAKey(key)
+            | rustls_pemfile::Item::ECKey(key) => return Ok(PrivateKey(key)),
+            _ => continue,
+        }
+    }
+    bail!("No private key found in {}", path.display())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_file(contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("poly-tls-{}.pem", uuid::Uuid::new_v4()));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_acceptor_reports_missing_and_empty_files() {
+        let settings = TlsSettings {
+            cert_path: PathBuf::from("/nonexistent/cert.pem"),
+            key_path: PathBuf::from("/nonexistent/key.pem"),
+            client_ca_path: None,
+        };
+        let err = settings.acceptor().err().unwrap().to_string();
+        assert!(err.contains("Failed to open /nonexistent/cert.pem"));
+
+        let empty = temp_file("not a pem file\n");
+        let settings = TlsSettings {
+            cert_path: empty.clone(),
+            key_path: empty.clone(),
+            client_ca_path: None,
+        };
+        let err = settings.acceptor().err().unwrap().to_string();
+        assert!(err.contains("No certificates found"));
+        std::fs::remove_file(empty).ok();
+    }
+}
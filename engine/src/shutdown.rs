@@ -0,0 +1,157 @@
+//! Ordered shutdown.
+//!
+//! Cancelling every task at once races order placements against the DB
+//! writes and notifications they spawn, so a fill can be sent and never
+//! recorded. Shutdown instead runs in stages: stop signal generation
+//! (including sliced orders), wait for in-flight orders (new ones are
+//! refused), flush DB and analytics writes, Redis publishes and Slack
+//! posts, close the WebSocket, then stop the rest. Only writes spawned with
+//! `runtime::spawn` into one of `FLUSHED_GROUPS` are waited for. Each stage
+//! is logged and gets at most `SHUTDOWN_STAGE_TIMEOUT_SECS`; a stage that
+//! overruns is logged and skipped so the process still exits.
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use tracing::{info, warn};
+
+use crate::runtime::TaskGroup;
+
+/// How often pending background writes are rechecked
+const FLUSH_POLL: Duration = Duration::from_millis(50);
+
+/// How often a stage still waiting reports progress
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Groups whose tasks are flushed before the WebSocket closes
+pub const FLUSHED_GROUPS: [TaskGroup; 3] = [
+    TaskGroup::DbWrite,
+    TaskGroup::RedisPublish,
+    TaskGroup::Slack,
+];
+
+/// Shutdown settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShutdownConfig {
+    /// Longest any one stage may take
+    pub stage_timeout: Duration,
+}
+
+impl Default for ShutdownConfig {
+    fn default() -> Self {
+        Self {
+            stage_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl ShutdownConfig {
+    /// Load from `SHUTDOWN_STAGE_TIMEOUT_SECS` (zero or unset = default).
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok();
+        Self {
+            stage_timeout: var("SHUTDOWN_STAGE_TIMEOUT_SECS")
+                .and_then(|v| v.parse().ok())
+                .filter(|n| *n > 0)
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.stage_timeout),
+        }
+    }
+}
+
+/// Runs shutdown stages one after another
+pub struct Shutdown {
+    config: ShutdownConfig,
+    started: Instant,
+}
+
+impl Shutdown {
+    pub fn new(config: ShutdownConfig) -> Self {
+        Self {
+            config,
+            started: Instant::now(),
+        }
+    }
+
+    /// Run one stage until it finishes or the stage timeout passes.
+    ///
+    /// Returns whether it finished.
+    pub async fn stage<F: Future>(&self, name: &str, work: F) -> bool {
+        info!("[SHUTDOWN] {}...", name);
+        let start = Instant::now();
+        match tokio::time::timeout(self.config.stage_timeout, work).await {
+            Ok(_) => {
+                info!(
+                    "[SHUTDOWN] {} - done in {}ms",
+                    name,
+                    start.elapsed().as_millis()
+                );
+                true
+            }
+            Err(_) => {
+                warn!(
+                    "[SHUTDOWN] {} - timed out after {}s, continuing",
+                    name,
+                    self.config.stage_timeout.as_secs()
+                );
+                false
+            }
+        }
+    }
+
+    /// Time since shutdown began
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+}
+
+/// Wait until every task spawned into `groups` has finished, reporting
+/// what is still pending.
+pub async fn flush(groups: &[TaskGroup]) {
+    let mut last_report = Instant::now();
+    loop {
+        let pending: Vec<String> = groups
+            .iter()
+            .filter(|group| group.alive() > 0)
+            .map(|group| format!("{}={}", group.as_str(), group.alive()))
+            .collect();
+        if pending.is_empty() {
+            return;
+        }
+        if last_report.elapsed() >= PROGRESS_INTERVAL {
+            info!("[SHUTDOWN] Still flushing: {}", pending.join(", "));
+            last_report = Instant::now();
+        }
+        tokio::time::sleep(FLUSH_POLL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_flush_waits_for_spawned_writes_and_stages_time_out() {
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+        crate::runtime::spawn(TaskGroup::DbWrite, async move {
+            let _ = rx.await;
+        });
+        let shutdown = Shutdown::new(ShutdownConfig {
+            stage_timeout: Duration::from_millis(100),
+        });
+        assert!(
+            !shutdown
+                .stage("Flushing", flush(&[TaskGroup::DbWrite]))
+                .await
+        );
+
+        tx.send(()).unwrap();
+        let shutdown = Shutdown::new(ShutdownConfig::default());
+        assert!(
+            shutdown
+                .stage("Flushing", flush(&[TaskGroup::DbWrite]))
+                .await
+        );
+    }
+}
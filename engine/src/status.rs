@@ -0,0 +1,143 @@
+//! Engine status snapshot.
+//!
+//! The engine builds one `EngineState` per heartbeat, whether or not Redis,
+//! the database or Slack are configured, and publishes it here. Everything
+//! that reports engine state renders from that snapshot: the heartbeat log,
+//! the Prometheus gauges, `GET /status`, and the Redis `state` channel the
+//! dashboard reads (forwarded by `RedisPublisher::run_state_forwarder`).
+//! Other tasks can follow updates with `subscribe`.
+
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use crate::metrics::{DAILY_PNL, DRAWDOWN, EQUITY, MAX_DRAWDOWN};
+use crate::strategy::StrategyStatsSnapshot;
+
+/// Status updates buffered per subscriber
+const UPDATE_CAPACITY: usize = 16;
+
+/// Engine state at one heartbeat
+#[derive(Debug, Clone, Serialize)]
+pub struct EngineState {
+    pub timestamp_ms: u64,
+    /// `waiting_for_data`, `observing`, `network_degraded`, `warming_up` or
+    /// `running`
+    pub status: String,
+    pub uptime_secs: u64,
+    pub evaluations: u64,
+    pub markets_tracked: usize,
+    pub order_books: usize,
+    pub opportunities_found: usize,
+    pub daily_pnl: f64,
+    pub daily_trades: u64,
+    /// Cumulative P&L since startup (realized + unrealized)
+    pub equity: f64,
+    pub drawdown: f64,
+    pub max_drawdown: f64,
+    pub positions: Vec<PositionInfo>,
+    /// Evaluation and signal statistics per strategy
+    pub strategies: Vec<StrategyStatsSnapshot>,
+}
+
+/// Position info for state updates
+#[derive(Debug, Clone, Serialize)]
+pub struct PositionInfo {
+    pub token_id: String,
+    pub size: f64,
+    pub avg_cost: f64,
+    pub unrealized_pnl: f64,
+}
+
+/// Latest engine state and its update stream
+pub struct StatusBoard {
+    latest: RwLock<Option<Arc<EngineState>>>,
+    updates: broadcast::Sender<Arc<EngineState>>,
+}
+
+impl Default for StatusBoard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StatusBoard {
+    pub fn new() -> Self {
+        Self {
+            latest: RwLock::new(None),
+            updates: broadcast::channel(UPDATE_CAPACITY).0,
+        }
+    }
+
+    /// Record a new snapshot: update the gauges, keep it for `latest` and
+    /// send it to subscribers.
+    pub fn publish(&self, state: EngineState) {
+        DAILY_PNL.set(state.daily_pnl);
+        EQUITY.set(state.equity);
+        DRAWDOWN.set(state.drawdown);
+        MAX_DRAWDOWN.set(state.max_drawdown);
+
+        let state = Arc::new(state);
+        *self.latest.write() = Some(Arc::clone(&state));
+        // No subscribers is fine; the snapshot is still kept
+        let _ = self.updates.send(state);
+    }
+
+    /// Most recent snapshot (None before the first heartbeat)
+    pub fn latest(&self) -> Option<Arc<EngineState>> {
+        self.latest.read().clone()
+    }
+
+    /// Receive every snapshot published from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<EngineState>> {
+        self.updates.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(status: &str) -> EngineState {
+        EngineState {
+            timestamp_ms: 1234567890,
+            status: status.to_string(),
+            uptime_secs: 60,
+            evaluations: 600,
+            markets_tracked: 10,
+            order_books: 20,
+            opportunities_found: 5,
+            daily_pnl: 123.45,
+            daily_trades: 15,
+            equity: 150.0,
+            drawdown: 2.5,
+            max_drawdown: 10.0,
+            positions: vec![],
+            strategies: vec![],
+        }
+    }
+
+    #[test]
+    fn test_serialize_state() {
+        let json = serde_json::to_string(&state("running")).unwrap();
+        assert!(json.contains("running"));
+        assert!(json.contains("123.45"));
+        assert!(json.contains(r#""max_drawdown":10.0"#));
+    }
+
+    #[test]
+    fn test_publish_keeps_latest_and_notifies() {
+        let board = StatusBoard::new();
+        assert!(board.latest().is_none());
+
+        let mut updates = board.subscribe();
+        board.publish(state("waiting_for_data"));
+        board.publish(state("running"));
+
+        assert_eq!(board.latest().unwrap().status, "running");
+        assert_eq!(updates.try_recv().unwrap().status, "waiting_for_data");
+        assert_eq!(updates.try_recv().unwrap().status, "running");
+    }
+}
@@ -0,0 +1,390 @@
+//! Manual approval of large or unusual trades.
+//!
+//! With `APPROVAL_MODE=true`, signals that pass risk but are above
+//! `APPROVAL_NOTIONAL` dollars, or come from a strategy listed in
+//! `APPROVAL_STRATEGIES` (newly enabled strategies still being trusted),
+//! are parked instead of executed. Each parked signal is pushed to Slack
+//! with its context and waits for `POST /control/approvals/approve?id=N` or
+//! `.../reject?id=N`. Approved signals go back to the engine through the
+//! fast path and are checked against risk again before executing; signals
+//! not decided within `APPROVAL_TTL_SECS` expire.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::{info, warn};
+
+use crate::notifications::{ApprovalRequest, SlackNotifier};
+
+use super::fast_path::FastSignal;
+use super::TradeSignal;
+
+/// Manual approval settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApprovalConfig {
+    pub enabled: bool,
+    /// Signals above this notional (USD) need approval (0 = no threshold)
+    pub notional_threshold: f64,
+    /// Strategies whose every signal needs approval
+    pub strategies: HashSet<String>,
+    /// How long a parked signal waits for a decision
+    pub ttl: Duration,
+}
+
+impl Default for ApprovalConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            notional_threshold: 500.0,
+            strategies: HashSet::new(),
+            ttl: Duration::from_secs(120),
+        }
+    }
+}
+
+impl ApprovalConfig {
+    /// Load from `APPROVAL_MODE`, `APPROVAL_NOTIONAL`, `APPROVAL_STRATEGIES`
+    /// (comma-separated names) and `APPROVAL_TTL_SECS`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok();
+        Self {
+            enabled: var("APPROVAL_MODE")
+                .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+                .unwrap_or(defaults.enabled),
+            notional_threshold: var("APPROVAL_NOTIONAL")
+                .and_then(|v| v.parse::<f64>().ok())
+                .filter(|n| *n >= 0.0)
+                .unwrap_or(defaults.notional_threshold),
+            strategies: var("APPROVAL_STRATEGIES")
+                .map(|v| {
+                    v.split(',')
+                        .map(str::trim)
+                        .filter(|s| !s.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or(defaults.strategies),
+            ttl: var("APPROVAL_TTL_SECS")
+                .and_then(|v| v.parse().ok())
+                .filter(|secs| *secs > 0)
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.ttl),
+        }
+    }
+
+    /// Why a signal needs approval, if it does.
+    fn reason(&self, strategy_name: &str, signal: &TradeSignal) -> Option<String> {
+        if !self.enabled || matches!(signal, TradeSignal::Cancel { .. }) {
+            return None;
+        }
+        if self.strategies.contains(strategy_name) {
+            return Some(format!("{} requires approval", strategy_name));
+        }
+        let notional = signal.notional();
+        (self.notional_threshold > 0.0 && notional > self.notional_threshold).then(|| {
+            format!(
+                "notional ${:.2} above ${:.2}",
+                notional, self.notional_threshold
+            )
+        })
+    }
+}
+
+/// A signal waiting for an operator decision
+struct Pending {
+    strategy_name: &'static str,
+    signal: TradeSignal,
+    contested: bool,
+    reason: String,
+    parked_at: Instant,
+}
+
+/// A parked signal as listed on `GET /control/approvals`
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingApproval {
+    pub id: u64,
+    pub strategy: String,
+    pub description: String,
+    pub notional: f64,
+    pub reason: String,
+    pub expires_in_secs: u64,
+    pub metadata: Option<serde_json::Value>,
+}
+
+/// Why a decision could not be applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApprovalError {
+    /// No pending signal with that ID (decided, expired or never parked)
+    NotFound,
+    /// The engine is gone
+    EngineStopped,
+}
+
+impl fmt::Display for ApprovalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApprovalError::NotFound => f.write_str("no pending signal with that id"),
+            ApprovalError::EngineStopped => f.write_str("engine stopped"),
+        }
+    }
+}
+
+/// A parked signal that will not execute, for the engine to report back to
+/// its strategy
+pub(super) struct Dropped {
+    pub strategy_name: &'static str,
+    pub signal: TradeSignal,
+    /// `approval_rejected` or `approval_expired`
+    pub reason: &'static str,
+}
+
+/// Signals parked for manual approval
+pub struct ApprovalQueue {
+    config: ApprovalConfig,
+    pending: Mutex<BTreeMap<u64, Pending>>,
+    /// Rejected and expired signals not yet reported to their strategies
+    dropped: Mutex<Vec<Dropped>>,
+    next_id: AtomicU64,
+    /// Approved signals go back to the engine here
+    tx: UnboundedSender<FastSignal>,
+    slack: Option<Arc<SlackNotifier>>,
+}
+
+impl ApprovalQueue {
+    pub(super) fn new(
+        config: ApprovalConfig,
+        tx: UnboundedSender<FastSignal>,
+        slack: Option<Arc<SlackNotifier>>,
+    ) -> Self {
+        if config.enabled {
+            info!(
+                "[APPROVAL] Manual approval above ${:.2} and for {:?} (TTL {:?})",
+                config.notional_threshold, config.strategies, config.ttl
+            );
+        }
+        Self {
+            config,
+            pending: Mutex::new(BTreeMap::new()),
+            dropped: Mutex::new(Vec::new()),
+            next_id: AtomicU64::new(1),
+            tx,
+            slack,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Park the signal if it needs approval; returns its ID if parked.
+    pub(super) fn park(
+        &self,
+        strategy_name: &'static str,
+        signal: &TradeSignal,
+        contested: bool,
+    ) -> Option<u64> {
+        let reason = self.config.reason(strategy_name, signal)?;
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        info!(
+            "[APPROVAL] #{} parked ({}): [{}] {}",
+            id,
+            reason,
+            strategy_name,
+            signal.description()
+        );
+        if let Some(slack) = &self.slack {
+            slack.notify_approval(ApprovalRequest {
+                id,
+                strategy: strategy_name.to_string(),
+                description: signal.description(),
+                notional: signal.notional(),
+                reason: reason.clone(),
+                ttl_secs: self.config.ttl.as_secs(),
+                metadata: signal.metadata().to_json(),
+            });
+        }
+        self.pending.lock().insert(
+            id,
+            Pending {
+                strategy_name,
+                signal: signal.clone(),
+                contested,
+                reason,
+                parked_at: Instant::now(),
+            },
+        );
+        Some(id)
+    }
+
+    /// Send a parked signal back to the engine for execution.
+    pub fn approve(&self, id: u64) -> Result<String, ApprovalError> {
+        let pending = self.take(id)?;
+        let description = pending.signal.description();
+        info!("[APPROVAL] #{} approved: {}", id, description);
+        self.tx
+            .send(FastSignal {
+                strategy_name: pending.strategy_name,
+                signal: pending.signal,
+                contested: pending.contested,
+                sent_at: Instant::now(),
+                approved: true,
+            })
+            .map_err(|_| ApprovalError::EngineStopped)?;
+        Ok(description)
+    }
+
+    /// Drop a parked signal.
+    pub fn reject(&self, id: u64) -> Result<String, ApprovalError> {
+        let pending = self.take(id)?;
+        let description = pending.signal.description();
+        info!("[APPROVAL] #{} rejected: {}", id, description);
+        self.dropped.lock().push(Dropped {
+            strategy_name: pending.strategy_name,
+            signal: pending.signal,
+            reason: "approval_rejected",
+        });
+        Ok(description)
+    }
+
+    /// Signals waiting for a decision, oldest first.
+    pub fn list(&self) -> Vec<PendingApproval> {
+        self.expire();
+        self.pending
+            .lock()
+            .iter()
+            .map(|(id, p)| PendingApproval {
+                id: *id,
+                strategy: p.strategy_name.to_string(),
+                description: p.signal.description(),
+                notional: p.signal.notional(),
+                reason: p.reason.clone(),
+                expires_in_secs: self
+                    .config
+                    .ttl
+                    .saturating_sub(p.parked_at.elapsed())
+                    .as_secs(),
+                metadata: p.signal.metadata().to_json(),
+            })
+            .collect()
+    }
+
+    /// Expire stale signals and hand back every signal that will not
+    /// execute since the last call.
+    pub(super) fn take_dropped(&self) -> Vec<Dropped> {
+        self.expire();
+        std::mem::take(&mut *self.dropped.lock())
+    }
+
+    /// A queue without an engine; approved signals are discarded.
+    #[cfg(test)]
+    pub(crate) fn for_test(config: ApprovalConfig) -> Self {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        // Keep the channel open so approvals succeed
+        std::mem::forget(rx);
+        Self::new(config, tx, None)
+    }
+
+    /// Park a signal as the engine would.
+    #[cfg(test)]
+    pub(crate) fn park_for_test(
+        &self,
+        strategy_name: &'static str,
+        signal: &TradeSignal,
+    ) -> Option<u64> {
+        self.park(strategy_name, signal, false)
+    }
+
+    fn take(&self, id: u64) -> Result<Pending, ApprovalError> {
+        self.expire();
+        self.pending
+            .lock()
+            .remove(&id)
+            .ok_or(ApprovalError::NotFound)
+    }
+
+    fn expire(&self) {
+        let ttl = self.config.ttl;
+        let mut expired = Vec::new();
+        self.pending.lock().retain(|id, p| {
+            if p.parked_at.elapsed() < ttl {
+                return true;
+            }
+            warn!(
+                "[APPROVAL] #{} expired undecided: {}",
+                id,
+                p.signal.description()
+            );
+            expired.push(Dropped {
+                strategy_name: p.strategy_name,
+                signal: p.signal.clone(),
+                reason: "approval_expired",
+            });
+            false
+        });
+        if !expired.is_empty() {
+            self.dropped.lock().extend(expired);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::SignalMetadata;
+    use tokio::sync::mpsc;
+
+    fn buy(size: f64) -> TradeSignal {
+        TradeSignal::Buy {
+            token_id: "token-1".into(),
+            price: 0.50,
+            size,
+            reason: "test".into(),
+            metadata: SignalMetadata::new(),
+        }
+    }
+
+    #[test]
+    fn test_park_approve_reject_and_expire() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let config = ApprovalConfig {
+            enabled: true,
+            notional_threshold: 100.0,
+            strategies: ["NewStrategy".to_string()].into(),
+            ttl: Duration::from_secs(60),
+        };
+        let queue = ApprovalQueue::new(config, tx, None);
+
+        // Small signals from trusted strategies go straight through
+        assert_eq!(queue.park("Clipper", &buy(100.0), false), None);
+
+        let large = queue.park("Clipper", &buy(400.0), false).unwrap();
+        let new = queue.park("NewStrategy", &buy(10.0), false).unwrap();
+        assert_eq!(queue.list().len(), 2);
+
+        queue.approve(large).unwrap();
+        let approved = rx.try_recv().unwrap();
+        assert!(approved.approved);
+        assert_eq!(approved.strategy_name, "Clipper");
+        assert_eq!(queue.approve(large), Err(ApprovalError::NotFound));
+
+        queue.reject(new).unwrap();
+        let dropped = queue.take_dropped();
+        assert_eq!(dropped.len(), 1);
+        assert_eq!(dropped[0].reason, "approval_rejected");
+        assert!(queue.list().is_empty());
+
+        // Undecided signals expire after the TTL
+        let stale = queue.park("Clipper", &buy(400.0), false).unwrap();
+        queue.pending.lock().get_mut(&stale).unwrap().parked_at =
+            Instant::now() - Duration::from_secs(61);
+        assert_eq!(queue.approve(stale), Err(ApprovalError::NotFound));
+        assert_eq!(queue.take_dropped()[0].reason, "approval_expired");
+    }
+}
@@ -3,48 +3,60 @@
 //! Finds markets where YES + NO < $1.00 and buys both for guaranteed profit.
 //! This is the purest form of arbitrage - zero directional risk.
 
+use std::sync::Arc;
+
 use crate::config::ClipperConfig;
-use crate::market::MarketData;
+use crate::execution::Side;
+use crate::market::MarketSnapshot;
+use crate::risk::RiskManager;
 
-use super::{Strategy, TradeSignal};
+use super::{CostModel, SignalMetadata, Strategy, TradeSignal};
 
 /// Clipper strategy for YES+NO arbitrage.
 pub struct ClipperStrategy {
     config: ClipperConfig,
+    /// Used to size trades within the remaining risk headroom
+    risk_manager: Option<Arc<RiskManager>>,
+    cost_model: CostModel,
 }
 
 impl ClipperStrategy {
     /// Create a new clipper strategy.
     pub fn new(config: ClipperConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            risk_manager: None,
+            cost_model: CostModel::default(),
+        }
     }
 
     /// Scan all markets for arbitrage opportunities.
-    fn scan_markets(&self, market_data: &MarketData) -> Option<TradeSignal> {
+    fn scan_markets(&self, snapshot: &MarketSnapshot) -> Option<TradeSignal> {
         // Get all market pairs (YES/NO token pairs)
-        for (_market_id, pair) in market_data.get_all_pairs() {
+        for pair in snapshot.pairs() {
             // Get best ask prices for both tokens
-            let yes_ask = match market_data.get_ask(&pair.yes_token) {
+            let yes_ask = match snapshot.get_ask(&pair.yes_token) {
                 Some(p) => p,
                 None => continue,
             };
-            let no_ask = match market_data.get_ask(&pair.no_token) {
+            let no_ask = match snapshot.get_ask(&pair.no_token) {
                 Some(p) => p,
                 None => continue,
             };
 
-            // Calculate total cost and profit
+            // Check if profitable after fees (taker on both legs)
             let total_cost = yes_ask + no_ask;
-            let profit_per_share = 1.0 - total_cost;
-
-            // Check if profitable after fees
-            // Polymarket has ~0.5% taker fee per side = 1% total for arb
-            let fees = total_cost * 0.01; // 1% fee estimate
-            let net_profit = profit_per_share - fees;
+            let net_profit = self.cost_model.arbitrage_edge(yes_ask, no_ask);
 
             if net_profit >= self.config.min_profit {
-                // Calculate position size
-                let size = self.calculate_size(yes_ask, no_ask);
+                // Calculate position size, shrunk to what risk will accept
+                let mut size = self.calculate_size(yes_ask, no_ask);
+                if let Some(risk) = &self.risk_manager {
+                    size = size.min(risk.max_allowed(&pair.yes_token, Side::Buy, total_cost));
+                }
+                if size <= 0.0 {
+                    continue;
+                }
 
                 return Some(TradeSignal::Arbitrage {
                     yes_token: pair.yes_token.clone(),
@@ -53,6 +65,8 @@ impl ClipperStrategy {
                     no_price: no_ask,
                     profit_per_share: net_profit,
                     size,
+                    side: Side::Buy,
+                    metadata: SignalMetadata::new().with("market_id", pair.market_id.as_str()),
                 });
             }
         }
@@ -75,8 +89,8 @@ impl ClipperStrategy {
 }
 
 impl Strategy for ClipperStrategy {
-    fn evaluate(&self, market_data: &MarketData) -> Option<TradeSignal> {
-        self.scan_markets(market_data)
+    fn evaluate(&self, snapshot: &MarketSnapshot) -> Option<TradeSignal> {
+        self.scan_markets(snapshot)
     }
 
     fn name(&self) -> &'static str {
@@ -86,6 +100,14 @@ impl Strategy for ClipperStrategy {
     fn is_active(&self) -> bool {
         self.config.enabled
     }
+
+    fn set_risk_manager(&mut self, risk_manager: Arc<RiskManager>) {
+        self.risk_manager = Some(risk_manager);
+    }
+
+    fn set_cost_model(&mut self, cost_model: CostModel) {
+        self.cost_model = cost_model;
+    }
 }
 
 #[cfg(test)]
@@ -106,6 +128,7 @@ mod tests {
             min_profit: 0.01,
             max_position: 100.0,
             max_notional: 50.0,
+            ..ClipperConfig::default()
         };
         let clipper = ClipperStrategy::new(config);
 
@@ -117,6 +140,45 @@ mod tests {
         assert!(size > 52.0);
     }
 
+    #[test]
+    fn test_size_is_capped_by_risk_headroom() {
+        use crate::config::RiskConfig;
+        use crate::market::{MarketPair, PriceLevel};
+
+        let config = ClipperConfig {
+            enabled: true,
+            min_profit: 0.01,
+            max_position: 100.0,
+            max_notional: 1000.0,
+            ..ClipperConfig::default()
+        };
+        let mut clipper = ClipperStrategy::new(config);
+        clipper.set_risk_manager(Arc::new(RiskManager::new(RiskConfig {
+            max_position: 100.0,
+            max_notional: 19.0,
+            max_daily_loss: 100.0,
+            ..RiskConfig::default()
+        })));
+
+        let snapshot = MarketSnapshot::new(0)
+            .with_pair(MarketPair {
+                market_id: "m1".into(),
+                yes_token: "yes".into(),
+                no_token: "no".into(),
+                question: "Q?".into(),
+                category: None,
+                end_date: None,
+            })
+            .with_price("yes".into(), PriceLevel::new(0.40, 0.45))
+            .with_price("no".into(), PriceLevel::new(0.45, 0.50));
+
+        // $19 at $0.95 per pair = 20 pairs, not the 100 the config allows
+        match clipper.evaluate(&snapshot) {
+            Some(TradeSignal::Arbitrage { size, .. }) => assert!((size - 20.0).abs() < 1e-6),
+            other => panic!("unexpected signal: {:?}", other),
+        }
+    }
+
     #[test]
     fn test_size_respects_max_position() {
         let config = ClipperConfig {
@@ -124,6 +186,7 @@ mod tests {
             min_profit: 0.01,
             max_position: 10.0,   // Low max position
             max_notional: 1000.0, // High notional
+            ..ClipperConfig::default()
         };
         let clipper = ClipperStrategy::new(config);
 
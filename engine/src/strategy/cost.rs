@@ -0,0 +1,308 @@
+//! Pre-trade cost estimates shared by strategies, risk, and persistence.
+//!
+//! Strategies size their edge with the same fee and slippage assumptions the
+//! risk manager books as P&L and the trade tables store, so an opportunity's
+//! logged edge, its recorded profit, and its `fees` column all agree.
+
+use std::sync::Arc;
+
+use crate::config::CostConfig;
+use crate::execution::Side;
+
+use super::edge_tuning::EdgeTuner;
+use super::TradeSignal;
+
+/// Estimated costs of executing a signal, in dollars
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CostEstimate {
+    pub notional: f64,
+    pub fees: f64,
+    pub slippage: f64,
+    /// Profit before costs, for signals with a known payoff (arbitrage)
+    pub gross_edge: Option<f64>,
+    /// `gross_edge` less fees and slippage
+    pub net_edge: Option<f64>,
+}
+
+/// Fee and slippage model applied to every signal.
+#[derive(Debug, Clone, Default)]
+pub struct CostModel {
+    config: CostConfig,
+    /// Raises minimum edges by realized shortfall (None = static)
+    tuner: Option<Arc<EdgeTuner>>,
+}
+
+impl CostModel {
+    pub fn new(config: CostConfig) -> Self {
+        Self {
+            config,
+            tuner: None,
+        }
+    }
+
+    /// Tune minimum edges per market (see `edge_tuning`).
+    pub fn set_edge_tuner(&mut self, tuner: Arc<EdgeTuner>) {
+        self.tuner = Some(tuner);
+    }
+
+    /// Minimum edge for a market configured with `base`, raised by the
+    /// market's realized execution shortfall when tuning is on.
+    pub fn min_edge(&self, market_id: &str, base: f64) -> f64 {
+        self.tuner
+            .as_ref()
+            .map_or(base, |tuner| tuner.min_edge(market_id, base))
+    }
+
+    /// Record an execution's expected and realized edge per share.
+    pub fn record_shortfall(&self, market_id: &str, expected_edge: f64, realized_edge: f64) {
+        if let Some(tuner) = &self.tuner {
+            tuner.record(market_id, expected_edge, realized_edge);
+        }
+    }
+
+    /// Costs of executing `signal` at its limit prices.
+    pub fn estimate(&self, signal: &TradeSignal) -> CostEstimate {
+        match signal {
+            TradeSignal::Buy { .. } | TradeSignal::Sell { .. } => {
+                let notional = signal.notional();
+                let (fees, slippage) = self.taker_costs(notional);
+                CostEstimate {
+                    notional,
+                    fees,
+                    slippage,
+                    ..CostEstimate::default()
+                }
+            }
+            TradeSignal::Bid { .. } => {
+                let notional = signal.notional();
+                CostEstimate {
+                    notional,
+                    fees: notional * self.config.maker_fee_rate,
+                    ..CostEstimate::default()
+                }
+            }
+            TradeSignal::Arbitrage {
+                yes_price,
+                no_price,
+                size,
+                side: Side::Buy,
+                ..
+            } => self.arbitrage(*yes_price, *no_price, *size),
+            TradeSignal::Arbitrage {
+                yes_price,
+                no_price,
+                size,
+                side: Side::Sell,
+                ..
+            } => self.arbitrage_sell(*yes_price, *no_price, *size),
+            TradeSignal::Cancel { .. } => CostEstimate::default(),
+            TradeSignal::Basket { .. } => {
+                let notional = signal.notional();
+                let (fees, slippage) = self.taker_costs(notional);
+                let gross = signal.basket_profit();
+                CostEstimate {
+                    notional,
+                    fees,
+                    slippage,
+                    gross_edge: gross,
+                    net_edge: gross.map(|gross| gross - fees - slippage),
+                }
+            }
+        }
+    }
+
+    /// Costs of buying `size` YES and NO shares, both crossing the spread.
+    pub fn arbitrage(&self, yes_price: f64, no_price: f64, size: f64) -> CostEstimate {
+        let notional = (yes_price + no_price) * size;
+        let (fees, slippage) = self.taker_costs(notional);
+        let gross = size - notional; // each YES + NO pair pays out $1
+        CostEstimate {
+            notional,
+            fees,
+            slippage,
+            gross_edge: Some(gross),
+            net_edge: Some(gross - fees - slippage),
+        }
+    }
+
+    /// Net profit per share of a YES + NO arbitrage at these asks.
+    pub fn arbitrage_edge(&self, yes_price: f64, no_price: f64) -> f64 {
+        self.arbitrage(yes_price, no_price, 1.0)
+            .net_edge
+            .unwrap_or_default()
+    }
+
+    /// Costs of selling `size` held YES and NO shares into the bids. The
+    /// edge is over holding the pair to resolution, where it pays $1.
+    pub fn arbitrage_sell(&self, yes_price: f64, no_price: f64, size: f64) -> CostEstimate {
+        let notional = (yes_price + no_price) * size;
+        let (fees, slippage) = self.taker_costs(notional);
+        let gross = notional - size;
+        CostEstimate {
+            notional,
+            fees,
+            slippage,
+            gross_edge: Some(gross),
+            net_edge: Some(gross - fees - slippage),
+        }
+    }
+
+    /// Net profit per share of selling a held YES + NO pair at these bids.
+    pub fn arbitrage_sell_edge(&self, yes_price: f64, no_price: f64) -> f64 {
+        self.arbitrage_sell(yes_price, no_price, 1.0)
+            .net_edge
+            .unwrap_or_default()
+    }
+
+    /// Net profit per share when one leg rests as a maker bid at
+    /// `rest_price` and the other crosses the spread at `hedge_price`.
+    pub fn spread_capture_edge(&self, rest_price: f64, hedge_price: f64) -> f64 {
+        let (hedge_fees, hedge_slippage) = self.taker_costs(hedge_price);
+        1.0 - rest_price
+            - hedge_price
+            - rest_price * self.config.maker_fee_rate
+            - hedge_fees
+            - hedge_slippage
+    }
+
+    /// Fee on a fill of `notional` dollars, as a maker (resting) or taker.
+    pub fn fee(&self, notional: f64, maker: bool) -> f64 {
+        let rate = if maker {
+            self.config.maker_fee_rate
+        } else {
+            self.config.taker_fee_rate
+        };
+        notional * rate
+    }
+
+    /// Fees and slippage on a taker order of `notional` dollars.
+    fn taker_costs(&self, notional: f64) -> (f64, f64) {
+        (
+            notional * self.config.taker_fee_rate,
+            notional * self.config.slippage_rate,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::{Leg, SignalMetadata};
+
+    fn model() -> CostModel {
+        CostModel::new(CostConfig {
+            taker_fee_rate: 0.01,
+            maker_fee_rate: 0.001,
+            slippage_rate: 0.002,
+        })
+    }
+
+    #[test]
+    fn test_arbitrage_estimate_nets_fees_and_slippage() {
+        let signal = TradeSignal::Arbitrage {
+            yes_token: "yes".into(),
+            no_token: "no".into(),
+            yes_price: 0.45,
+            no_price: 0.50,
+            profit_per_share: 0.0,
+            size: 100.0,
+            side: Side::Buy,
+            metadata: SignalMetadata::new(),
+        };
+        let estimate = model().estimate(&signal);
+
+        assert!((estimate.notional - 95.0).abs() < 1e-9);
+        assert!((estimate.fees - 0.95).abs() < 1e-9);
+        assert!((estimate.slippage - 0.19).abs() < 1e-9);
+        assert!((estimate.gross_edge.unwrap() - 5.0).abs() < 1e-9);
+        assert!((estimate.net_edge.unwrap() - 3.86).abs() < 1e-9);
+        assert!((model().arbitrage_edge(0.45, 0.50) - 0.0386).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_arbitrage_sell_estimate_nets_fees_and_slippage() {
+        let signal = TradeSignal::Arbitrage {
+            yes_token: "yes".into(),
+            no_token: "no".into(),
+            yes_price: 0.55,
+            no_price: 0.50,
+            profit_per_share: 0.0,
+            size: 100.0,
+            side: Side::Sell,
+            metadata: SignalMetadata::new(),
+        };
+        let estimate = model().estimate(&signal);
+
+        assert!((estimate.notional - 105.0).abs() < 1e-9);
+        assert!((estimate.gross_edge.unwrap() - 5.0).abs() < 1e-9);
+        assert!((estimate.net_edge.unwrap() - (5.0 - 1.05 - 0.21)).abs() < 1e-9);
+        assert!((model().arbitrage_sell_edge(0.55, 0.50) - 0.0374).abs() < 1e-9);
+        assert!(model().arbitrage_sell_edge(0.50, 0.50) < 0.0);
+    }
+
+    #[test]
+    fn test_basket_estimate_uses_payout() {
+        let leg = |token: &str, price| Leg {
+            token_id: token.into(),
+            side: Side::Buy,
+            price,
+            size: 100.0,
+        };
+        // Every outcome of a three-way market for $0.95
+        let signal = TradeSignal::Basket {
+            legs: vec![leg("a", 0.30), leg("b", 0.25), leg("c", 0.40)],
+            payout: Some(100.0),
+            atomic: false,
+            reason: String::new(),
+            metadata: SignalMetadata::new(),
+        };
+        let estimate = model().estimate(&signal);
+
+        assert!((estimate.notional - 95.0).abs() < 1e-9);
+        assert!((estimate.gross_edge.unwrap() - 5.0).abs() < 1e-9);
+        assert!((estimate.net_edge.unwrap() - 3.86).abs() < 1e-9);
+        assert!((signal.edge().unwrap() - 0.05).abs() < 1e-9);
+
+        let TradeSignal::Basket { legs, .. } = signal else {
+            unreachable!()
+        };
+        let unpriced = TradeSignal::Basket {
+            legs,
+            payout: None,
+            atomic: false,
+            reason: String::new(),
+            metadata: SignalMetadata::new(),
+        };
+        assert_eq!(model().estimate(&unpriced).net_edge, None);
+    }
+
+    #[test]
+    fn test_directional_and_resting_orders_have_no_edge() {
+        let buy = TradeSignal::Buy {
+            token_id: "t".into(),
+            price: 0.5,
+            size: 10.0,
+            reason: String::new(),
+            metadata: SignalMetadata::new(),
+        };
+        let estimate = model().estimate(&buy);
+        assert!((estimate.fees - 0.05).abs() < 1e-9);
+        assert!((estimate.slippage - 0.01).abs() < 1e-9);
+        assert_eq!(estimate.net_edge, None);
+
+        let bid = TradeSignal::Bid {
+            token_id: "t".into(),
+            price: 0.5,
+            size: 10.0,
+            reason: String::new(),
+            metadata: SignalMetadata::new(),
+        };
+        let estimate = model().estimate(&bid);
+        assert!((estimate.fees - 0.005).abs() < 1e-9);
+        assert_eq!(estimate.slippage, 0.0);
+
+        // Maker fee on the resting leg, taker fee and slippage on the other
+        let edge = model().spread_capture_edge(0.40, 0.50);
+        assert!((edge - (0.10 - 0.0004 - 0.005 - 0.001)).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,407 @@
+//! Decision log, for comparing engine versions before going live.
+//!
+//! With `DECISION_LOG_PATH` set, every signal the engine handles is
+//! appended to that file as one JSON line: the opportunity (strategy, kind,
+//! tokens, side, size, notional, edge), what the engine decided with it
+//! (`executed`, `rejected`, `parked` for approval, or `observed`) and why.
+//! Writes happen on a dedicated thread, as for the WS capture.
+//!
+//! Run the new version in dry run next to the old one, each with its own
+//! log, then `poly-rust --diff-decisions old.log new.log` prints how their
+//! behaviour differs: decisions per strategy, rejection reasons, and the
+//! opportunities one version acted on that the other did not.
+
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::execution::Side;
+use crate::redis::now_ms;
+
+use super::TradeSignal;
+
+/// Decisions waiting for the writer before new ones are dropped
+const LOG_BUFFER: usize = 16_384;
+
+/// Opportunities listed per section of a diff
+const DIFF_TOP: usize = 20;
+
+/// One decision on one signal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Decision {
+    pub ts_ms: u64,
+    pub strategy: String,
+    pub kind: String,
+    pub tokens: Vec<String>,
+    pub side: Option<Side>,
+    pub size: f64,
+    pub notional: f64,
+    pub edge: Option<f64>,
+    /// `executed`, `rejected`, `parked` or `observed`
+    pub decision: String,
+    #[serde(default)]
+    pub reasons: Vec<String>,
+}
+
+impl Decision {
+    pub fn new(strategy: &str, signal: &TradeSignal, decision: &str, reasons: Vec<String>) -> Self {
+        let side = match signal {
+            TradeSignal::Buy { .. } | TradeSignal::Bid { .. } => Some(Side::Buy),
+            TradeSignal::Sell { .. } => Some(Side::Sell),
+            TradeSignal::Arbitrage { side, .. } => Some(*side),
+            TradeSignal::Basket { .. } | TradeSignal::Cancel { .. } => None,
+        };
+        Self {
+            ts_ms: now_ms(),
+            strategy: strategy.to_string(),
+            kind: signal.kind().to_string(),
+            tokens: signal.tokens().into_iter().map(|t| t.to_string()).collect(),
+            side,
+            size: signal.size(),
+            notional: signal.notional(),
+            edge: signal.edge(),
+            decision: decision.to_string(),
+            reasons,
+        }
+    }
+
+    /// What identifies the opportunity across runs
+    fn opportunity(&self) -> String {
+        format!("{} {} {}", self.strategy, self.kind, self.tokens.join(","))
+    }
+}
+
+/// Appends decisions to the decision log
+pub struct DecisionLog {
+    tx: flume::Sender<Decision>,
+    dropped: AtomicU64,
+}
+
+impl DecisionLog {
+    /// Log to `DECISION_LOG_PATH`, if set.
+    pub fn from_env() -> Result<Option<Self>> {
+        match std::env::var("DECISION_LOG_PATH") {
+            Ok(path) if !path.is_empty() => Self::open(Path::new(&path)).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// Append to `path` (created if missing).
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open decision log {}", path.display()))?;
+        let (tx, rx) = flume::bounded(LOG_BUFFER);
+        std::thread::Builder::new()
+            .name("decision-log".to_string())
+            .spawn(move || write_decisions(file, rx))
+            .context("Failed to start decision log writer")?;
+        info!("[DECISIONS] Logging decisions to {}", path.display());
+        Ok(Self {
+            tx,
+            dropped: AtomicU64::new(0),
+        })
+    }
+
+    /// Queue a decision for writing.
+    pub fn record(&self, decision: Decision) {
+        if self.tx.try_send(decision).is_err() {
+            let dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+            if dropped.is_power_of_two() {
+                warn!(
+                    "[DECISIONS] Log writer behind - {} decisions dropped",
+                    dropped
+                );
+            }
+        }
+    }
+}
+
+fn write_decisions(file: File, rx: flume::Receiver<Decision>) {
+    let mut out = BufWriter::new(file);
+    while let Ok(decision) = rx.recv() {
+        let written = serde_json::to_writer(&mut out, &decision)
+            .map_err(std::io::Error::from)
+            .and_then(|()| out.write_all(b"\n"));
+        // Flush whenever the queue runs dry so a crash loses little
+        let flushed = written.and_then(|()| if rx.is_empty() { out.flush() } else { Ok(()) });
+        if let Err(e) = flushed {
+            warn!("[DECISIONS] Decision log stopped: {}", e);
+            return;
+        }
+    }
+    let _ = out.flush();
+}
+
+/// Read a decision log. Lines that do not parse (a torn last line after a
+/// crash) are skipped.
+pub fn read_decisions(path: &Path) -> Result<Vec<Decision>> {
+    let file = File::open(path)
+        .with_context(|| format!("Failed to open decision log {}", path.display()))?;
+    let mut decisions = Vec::new();
+    let mut skipped = 0;
+    for line in BufReader::new(file).lines() {
+        let line = line.with_context(|| format!("Failed to read {}", path.display()))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(&line) {
+            Ok(decision) => decisions.push(decision),
+            Err(_) => skipped += 1,
+        }
+    }
+    if skipped > 0 {
+        warn!("[DECISIONS] Skipped {} malformed lines in {}", skipped, path.display());
+    }
+    Ok(decisions)
+}
+
+/// The two logs to compare, if `--diff-decisions <old> <new>` was passed.
+pub fn diff_from_args(
+    args: impl IntoIterator<Item = String>,
+) -> Result<Option<(PathBuf, PathBuf)>> {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--diff-decisions" {
+            let (Some(old), Some(new)) = (args.next(), args.next()) else {
+                bail!("--diff-decisions needs two logs: --diff-decisions <old.log> <new.log>");
+            };
+            return Ok(Some((PathBuf::from(old), PathBuf::from(new))));
+        }
+    }
+    Ok(None)
+}
+
+/// Print how the decisions in `new` differ from those in `old`.
+pub fn run_diff(old: &Path, new: &Path) -> Result<()> {
+    let diff = DecisionDiff::new(&read_decisions(old)?, &read_decisions(new)?);
+    print!(
+        "{}",
+        diff.render(&old.display().to_string(), &new.display().to_string())
+    );
+    Ok(())
+}
+
+/// Decisions counted by outcome
+type Counts = BTreeMap<String, u64>;
+
+/// How two decision logs differ
+#[derive(Debug, Default)]
+pub struct DecisionDiff {
+    /// Decisions by outcome in the old and new log
+    totals: (Counts, Counts),
+    /// Decisions by strategy, then outcome
+    strategies: BTreeMap<String, (Counts, Counts)>,
+    /// Rejections by strategy and reason
+    reasons: BTreeMap<String, (u64, u64)>,
+    /// Decisions by opportunity, then outcome
+    opportunities: BTreeMap<String, (Counts, Counts)>,
+}
+
+impl DecisionDiff {
+    pub fn new(old: &[Decision], new: &[Decision]) -> Self {
+        let mut diff = Self::default();
+        for (decisions, is_new) in [(old, false), (new, true)] {
+            for decision in decisions {
+                let outcome = &decision.decision;
+                count(&mut diff.totals, is_new, outcome);
+                let strategy = diff.strategies.entry(decision.strategy.clone());
+                count(strategy.or_default(), is_new, outcome);
+                let opportunity = diff.opportunities.entry(decision.opportunity());
+                count(opportunity.or_default(), is_new, outcome);
+                for reason in &decision.reasons {
+                    let reason = format!("{} {}", decision.strategy, reason);
+                    let counts = diff.reasons.entry(reason).or_default();
+                    if is_new {
+                        counts.1 += 1;
+                    } else {
+                        counts.0 += 1;
+                    }
+                }
+            }
+        }
+        diff
+    }
+
+    /// Opportunities whose most common outcome changed, or that only one
+    /// log has, with the old and new outcome (`-` when absent).
+    fn changed_opportunities(&self) -> Vec<(&str, String, String)> {
+        let mut changed: Vec<_> = self
+            .opportunities
+            .iter()
+            .filter_map(|(opportunity, (old, new))| {
+                let (old_outcome, new_outcome) = (predominant(old), predominant(new));
+                (old_outcome != new_outcome).then(|| {
+                    (
+                        opportunity.as_str(),
+                        old_outcome.unwrap_or("-").to_string(),
+                        new_outcome.unwrap_or("-").to_string(),
+                    )
+                })
+            })
+            .collect();
+        // Ones acted on in either version first
+        changed.sort_by_key(|(_, old, new)| !(old == "executed" || new == "executed"));
+        changed
+    }
+
+    /// True when both logs made the same decisions in the same proportions
+    /// per opportunity.
+    pub fn is_empty(&self) -> bool {
+        self.changed_opportunities().is_empty()
+            && self.reasons.values().all(|(old, new)| old == new)
+            && self.strategies.values().all(|(old, new)| old == new)
+    }
+
+    /// A plain-text report.
+    pub fn render(&self, old_name: &str, new_name: &str) -> String {
+        let mut out = format!("Decisions: {} (old) vs {} (new)\n\n", old_name, new_name);
+        out += &format!(
+            "Total: {} -> {}\n",
+            describe(&self.totals.0),
+            describe(&self.totals.1)
+        );
+
+        out += "\nBy strategy:\n";
+        for (strategy, (old, new)) in &self.strategies {
+            let marker = if old == new { " " } else { "*" };
+            out += &format!(
+                "{} {}: {} -> {}\n",
+                marker,
+                strategy,
+                describe(old),
+                describe(new)
+            );
+        }
+
+        let reasons: Vec<_> = self
+            .reasons
+            .iter()
+            .filter(|(_, (old, new))| old != new)
+            .collect();
+        if !reasons.is_empty() {
+            out += "\nRejection reasons that changed:\n";
+            for (reason, (old, new)) in reasons {
+                out += &format!("  {}: {} -> {}\n", reason, old, new);
+            }
+        }
+
+        let changed = self.changed_opportunities();
+        if !changed.is_empty() {
+            out += &format!(
+                "\nOpportunities decided differently ({} of {}):\n",
+                changed.len(),
+                self.opportunities.len()
+            );
+            for (opportunity, old, new) in changed.iter().take(DIFF_TOP) {
+                out += &format!("  {}: {} -> {}\n", opportunity, old, new);
+            }
+            if changed.len() > DIFF_TOP {
+                out += &format!("  ... and {} more\n", changed.len() - DIFF_TOP);
+            }
+        }
+
+        if self.is_empty() {
+            out += "\nNo behavioural differences.\n";
+        }
+        out
+    }
+}
+
+fn count(pair: &mut (Counts, Counts), is_new: bool, outcome: &str) {
+    let counts = if is_new { &mut pair.1 } else { &mut pair.0 };
+    *counts.entry(outcome.to_string()).or_default() += 1;
+}
+
+/// The most common outcome (ties go to the first in name order)
+fn predominant(counts: &Counts) -> Option<&str> {
+    let mut best: Option<(&str, u64)> = None;
+    for (outcome, count) in counts {
+        if best.is_none_or(|(_, most)| *count > most) {
+            best = Some((outcome, *count));
+        }
+    }
+    best.map(|(outcome, _)| outcome)
+}
+
+fn describe(counts: &Counts) -> String {
+    if counts.is_empty() {
+        return "none".to_string();
+    }
+    counts
+        .iter()
+        .map(|(outcome, count)| format!("{} {}", count, outcome))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::SignalMetadata;
+
+    fn decision(token: &str, outcome: &str, reasons: &[&str]) -> Decision {
+        let signal = TradeSignal::Buy {
+            token_id: token.into(),
+            price: 0.40,
+            size: 10.0,
+            reason: "test".into(),
+            metadata: SignalMetadata::new(),
+        };
+        let reasons = reasons.iter().map(|r| r.to_string()).collect();
+        Decision::new("Clipper", &signal, outcome, reasons)
+    }
+
+    #[test]
+    fn test_diff_reports_changed_decisions() {
+        let old = vec![
+            decision("a", "executed", &[]),
+            decision("b", "executed", &[]),
+            decision("c", "rejected", &["position_limit"]),
+        ];
+        let new = vec![
+            decision("a", "executed", &[]),
+            decision("b", "rejected", &["position_limit"]),
+            decision("d", "executed", &[]),
+        ];
+        let diff = DecisionDiff::new(&old, &new);
+        assert!(!diff.is_empty());
+
+        let changed = diff.changed_opportunities();
+        let summary: Vec<_> = changed
+            .iter()
+            .map(|(o, old, new)| format!("{}: {} -> {}", o, old, new))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                "Clipper buy b: executed -> rejected",
+                "Clipper buy d: - -> executed",
+                "Clipper buy c: rejected -> -",
+            ]
+        );
+        let report = diff.render("old.log", "new.log");
+        assert!(report.contains("Total: 2 executed, 1 rejected -> 2 executed, 1 rejected"));
+
+        assert!(DecisionDiff::new(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn test_diff_args_take_two_logs() {
+        let args = |list: &[&str]| diff_from_args(list.iter().map(|s| s.to_string()));
+        assert_eq!(args(&["--dry-run"]).unwrap(), None);
+        assert!(args(&["--diff-decisions", "old.log"]).is_err());
+        assert_eq!(
+            args(&["--diff-decisions", "old.log", "new.log"]).unwrap(),
+            Some((PathBuf::from("old.log"), PathBuf::from("new.log")))
+        );
+    }
+}
@@ -0,0 +1,183 @@
+//! Minimum edge tuned per market by realized execution shortfall.
+//!
+//! The configured `min_edge` assumes the cost model's fees and slippage,
+//! but some markets cost more to trade than that: legs miss, books move
+//! before orders land. Each executed arbitrage records its execution
+//! shortfall (expected minus realized edge per share; a pair where only one
+//! leg was placed loses its whole edge). A market's effective minimum edge
+//! is the configured one plus its mean shortfall over the last
+//! `MIN_EDGE_TUNING_WINDOW` executions, capped at `MIN_EDGE_TUNING_MAX`.
+//! It never drops below the configured value. Effective values are served
+//! on `GET /stats`.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use parking_lot::Mutex;
+use serde::Serialize;
+
+use crate::market::MarketId;
+
+/// Minimum edge tuning settings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EdgeTuningConfig {
+    /// Highest effective minimum edge (0 = tuning off)
+    pub max_min_edge: f64,
+    /// Executions per market the shortfall is averaged over
+    pub window: usize,
+}
+
+impl Default for EdgeTuningConfig {
+    fn default() -> Self {
+        Self {
+            max_min_edge: 0.0,
+            window: 20,
+        }
+    }
+}
+
+impl EdgeTuningConfig {
+    /// Load from `MIN_EDGE_TUNING_MAX` and `MIN_EDGE_TUNING_WINDOW`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        Self {
+            max_min_edge: var("MIN_EDGE_TUNING_MAX")
+                .and_then(|v| v.parse::<f64>().ok())
+                .filter(|m| m.is_finite() && *m >= 0.0)
+                .unwrap_or(defaults.max_min_edge),
+            window: var("MIN_EDGE_TUNING_WINDOW")
+                .and_then(|v| v.parse().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.window),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.max_min_edge > 0.0
+    }
+}
+
+/// A market's tuned minimum edge, as served on `/stats`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct MarketEdge {
+    /// Configured minimum edge the market was last checked against
+    pub base_min_edge: f64,
+    pub effective_min_edge: f64,
+    /// Mean shortfall per share over the window
+    pub shortfall: f64,
+    pub executions: usize,
+}
+
+#[derive(Debug, Default)]
+struct MarketShortfall {
+    recent: VecDeque<f64>,
+    /// Last configured minimum edge asked for (None until asked)
+    base: Option<f64>,
+}
+
+impl MarketShortfall {
+    fn mean(&self) -> f64 {
+        if self.recent.is_empty() {
+            return 0.0;
+        }
+        self.recent.iter().sum::<f64>() / self.recent.len() as f64
+    }
+}
+
+/// Per-market execution shortfall and the minimum edges it implies
+#[derive(Debug)]
+pub struct EdgeTuner {
+    config: EdgeTuningConfig,
+    markets: Mutex<HashMap<MarketId, MarketShortfall>>,
+}
+
+impl EdgeTuner {
+    pub fn new(config: EdgeTuningConfig) -> Self {
+        Self {
+            config,
+            markets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record an execution's expected and realized edge per share.
+    /// Realizing more than expected counts as no shortfall.
+    pub fn record(&self, market_id: &str, expected_edge: f64, realized_edge: f64) {
+        let shortfall = (expected_edge - realized_edge).max(0.0);
+        if !shortfall.is_finite() {
+            return;
+        }
+        let mut markets = self.markets.lock();
+        let market = markets.entry(market_id.to_string()).or_default();
+        market.recent.push_back(shortfall);
+        while market.recent.len() > self.config.window {
+            market.recent.pop_front();
+        }
+    }
+
+    /// Effective minimum edge for a market configured with `base`.
+    pub fn min_edge(&self, market_id: &str, base: f64) -> f64 {
+        let mut markets = self.markets.lock();
+        let Some(market) = markets.get_mut(market_id) else {
+            return base;
+        };
+        market.base = Some(base);
+        self.effective(base, market.mean())
+    }
+
+    /// Tuned markets with their effective minimum edges.
+    pub fn snapshot(&self) -> BTreeMap<MarketId, MarketEdge> {
+        self.markets
+            .lock()
+            .iter()
+            .filter_map(|(market_id, market)| {
+                let base = market.base?;
+                let shortfall = market.mean();
+                Some((
+                    market_id.clone(),
+                    MarketEdge {
+                        base_min_edge: base,
+                        effective_min_edge: self.effective(base, shortfall),
+                        shortfall,
+                        executions: market.recent.len(),
+                    },
+                ))
+            })
+            .collect()
+    }
+
+    fn effective(&self, base: f64, shortfall: f64) -> f64 {
+        (base + shortfall).min(self.config.max_min_edge).max(base)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shortfall_raises_min_edge_within_bounds() {
+        let tuner = EdgeTuner::new(EdgeTuningConfig {
+            max_min_edge: 0.02,
+            window: 2,
+        });
+        assert_eq!(tuner.min_edge("m1", 0.003), 0.003);
+
+        // A lone leg loses the whole 1c edge; a clean pair loses nothing
+        tuner.record("m1", 0.01, 0.0);
+        tuner.record("m1", 0.01, 0.01);
+        assert!((tuner.min_edge("m1", 0.003) - 0.008).abs() < 1e-12);
+
+        // Capped at the maximum, and the window forgets old executions
+        tuner.record("m1", 0.05, 0.0);
+        tuner.record("m1", 0.05, 0.0);
+        assert_eq!(tuner.min_edge("m1", 0.003), 0.02);
+        // Better than expected never lowers it
+        tuner.record("m2", 0.01, 0.03);
+        assert_eq!(tuner.min_edge("m2", 0.003), 0.003);
+
+        let snapshot = tuner.snapshot();
+        assert_eq!(snapshot["m1"].effective_min_edge, 0.02);
+        assert_eq!(snapshot["m1"].executions, 2);
+        assert_eq!(snapshot["m2"].shortfall, 0.0);
+    }
+}
@@ -1,21 +1,49 @@
 //! Strategy engine that runs all strategies in a loop.
 
+use std::collections::HashMap;
+use std::panic::AssertUnwindSafe;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
-use tokio::time::interval;
+use std::time::{Duration, Instant};
+
+use futures::FutureExt;
+use parking_lot::Mutex;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::time::{interval, timeout};
 use tokio_util::sync::CancellationToken;
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
 
-use crate::db::{ArbTrade, Trade, TradeRepository};
-use crate::execution::OrderManager;
-use crate::market::MarketData;
-use crate::metrics::{DAILY_PNL, EVALUATIONS_TOTAL, SIGNALS_TOTAL};
-use crate::notifications::{OrderNotification, SlackNotifier};
-use crate::redis::{now_ms, EngineState, RedisPublisher, SignalMessage, TradeMessage};
-use crate::risk::RiskManager;
+use crate::cluster::LeaderElection;
+use crate::connectivity::ConnectivitySupervisor;
+use crate::db::{new_client_trade_id, AnalyticsSink, ArbTrade, OrderTag, Trade, TradeStore};
+use crate::execution::{
+    ExecutionResult, OrderFill, OrderManager, OrderTracker, OrderUpdate, Side, TwapEvent,
+    TwapExecutor, TwapOutcome, TwapReport, VolumeTracker,
+};
+use crate::external::EspnClient;
+use crate::log_budget::debug_limited;
+use crate::market::{MarketData, TokenId, WhaleMonitor};
+use crate::metrics::{
+    EVALUATIONS_TOTAL, FAST_PATH_DELAY, SIGNALS_TOTAL, SIGNAL_EDGE, VOLATILITY_SUPPRESSED,
+};
+use crate::notifications::{LegNotification, OrderNotification, SlackNotifier};
+use crate::redis::{now_ms, RedisPublisher, SignalMessage, TradeMessage};
+use crate::risk::{EquityCurve, FundsMonitor, PortfolioSnapshot, RiskCheck, RiskManager};
+use crate::runtime::{self, TaskGroup};
+use crate::status::{EngineState, PositionInfo, StatusBoard};
+use crate::watchdog::Watchdog;
 
-use super::{Strategy, TradeSignal};
+use super::approvals::{ApprovalConfig, ApprovalQueue};
+use super::decisions::{Decision, DecisionLog};
+use super::fast_path::{FastPath, FastSignal};
+use super::governor::{StrategyGovernor, StrategyHealth};
+use super::recent_trades::{RecentTrades, TradeTrace};
+use super::retry::{RetryConfig, RetryQueue};
+use super::stats::{ExecutionStats, SignalOutcome};
+use super::traits::SIGNAL_ID_KEY;
+use super::volatility::{VolatilityGate, VolatilityGateConfig};
+use super::warmup::{Warmup, WarmupConfig};
+use super::{CostModel, Leg, SignalMetadata, Strategy, TradeSignal};
 
 /// Get current time as nanoseconds since UNIX epoch (lock-free timestamp)
 fn now_ns() -> u64 {
@@ -25,10 +53,96 @@ fn now_ns() -> u64 {
         .as_nanos() as u64
 }
 
+/// Side, token, price, size and reason of a buy or sell signal
+fn order_fields(signal: &TradeSignal) -> Option<(&'static str, &TokenId, f64, f64, &str)> {
+    match signal {
+        TradeSignal::Buy {
+            token_id,
+            price,
+            size,
+            reason,
+            ..
+        } => Some(("BUY", token_id, *price, *size, reason)),
+        TradeSignal::Sell {
+            token_id,
+            price,
+            size,
+            reason,
+            ..
+        } => Some(("SELL", token_id, *price, *size, reason)),
+        _ => None,
+    }
+}
+
+/// Price ticks per token included in each strategy snapshot
+const SNAPSHOT_HISTORY_TICKS: usize = 32;
+
+/// Minimum time between fill checks on resting bids
+const BID_POLL_INTERVAL_NS: u64 = 1_000_000_000;
+
+/// Wait between checks for the hedger and exit scheduler to stop, once the
+/// engine has
+const PLACED_ORDER_WAIT: Duration = Duration::from_millis(50);
+
+/// A resting bid placed for a strategy, awaiting fills
+#[derive(Debug, Clone)]
+struct RestingBid {
+    strategy_name: &'static str,
+    order_id: String,
+    token_id: TokenId,
+    price: f64,
+    size: f64,
+    /// Size already recorded as filled
+    filled: f64,
+    /// Carried onto the trades its fills are recorded as
+    metadata: SignalMetadata,
+}
+
+/// A placed order whose fills are booked once the tracker confirms them
+#[derive(Debug, Clone)]
+struct ConfirmingOrder {
+    strategy_name: &'static str,
+    /// The order at its full size
+    leg: Leg,
+    reason: String,
+    metadata: SignalMetadata,
+    /// Arbitrage this order is a leg of (keyed by its YES order)
+    arbitrage: Option<String>,
+}
+
+/// An arbitrage booked once both of its legs are done
+#[derive(Debug, Clone)]
+struct ConfirmingArbitrage {
+    strategy_name: &'static str,
+    signal: TradeSignal,
+    /// Order ID, order and final fill (once closed) of the YES and NO legs
+    legs: [(String, Leg, Option<f64>); 2],
+}
+
+/// Orders awaiting fill confirmation
+#[derive(Debug, Default)]
+struct Confirmations {
+    orders: HashMap<String, ConfirmingOrder>,
+    arbitrages: HashMap<String, ConfirmingArbitrage>,
+}
+
+/// An order placed outside the engine, by the hedger or the resolution exit
+/// scheduler, for the engine to book like its own (see `placed_orders`)
+#[derive(Debug)]
+pub struct PlacedOrder {
+    /// Attributed like a strategy name
+    pub source: &'static str,
+    /// Buy or sell at the size placed and the price expected
+    pub signal: TradeSignal,
+    pub order_id: String,
+}
+
 /// Named signal for tracking which strategy generated it
 struct NamedSignal {
     strategy_name: &'static str,
     signal: TradeSignal,
+    /// Whether the strategy races other traders (see `Strategy::is_contested`)
+    contested: bool,
 }
 
 /// Strategy engine that evaluates all strategies and executes signals.
@@ -39,16 +153,80 @@ pub struct StrategyEngine {
     order_manager: Arc<OrderManager>,
     redis_publisher: Option<Arc<RedisPublisher>>,
     slack_notifier: Option<Arc<SlackNotifier>>,
-    trade_repo: Option<Arc<TradeRepository>>,
+    trade_repo: Option<Arc<dyn TradeStore>>,
+    /// High-volume analytics (signals, near misses) in ClickHouse
+    analytics: Option<Arc<AnalyticsSink>>,
+    /// Recent trades with order IDs, edge and latency for `/debug/trades`
+    recent_trades: Option<Arc<RecentTrades>>,
+    /// Monthly maker/taker volume per market, for fee-tier projection
+    volume: Option<Arc<VolumeTracker>>,
+    /// Standby instances evaluate strategies but do not execute signals
+    leader: Option<Arc<LeaderElection>>,
+    /// Record signals with their context instead of executing them
+    observe: bool,
+    /// Observe signals until the engine and the books they trade are warm
+    warmup: Option<Warmup>,
+    /// Suppresses signals for markets that are repricing fast
+    volatility: Option<VolatilityGate>,
+    /// Strong opportunities blocked by risk or exchange capacity
+    retries: Option<RetryQueue>,
+    /// Signals are observed while a network incident is open
+    connectivity: Option<Arc<ConnectivitySupervisor>>,
+    /// Pinged every tick so a supervisor can restart a wedged loop
+    watchdog: Option<Arc<Watchdog>>,
+    /// Live sports feed handed to strategies as they are added
+    game_feed: Option<Arc<EspnClient>>,
+    /// Large holder moves, handed to strategies as they are added
+    whales: Option<Arc<WhaleMonitor>>,
+    /// Shared fee and slippage model, handed to strategies as they are added
+    cost_model: CostModel,
     cancellation_token: Option<CancellationToken>,
     eval_interval_ms: u64,
     // Metrics for logging
     eval_count: AtomicU64,
     signal_count: AtomicU64,
+    /// Evaluation and signal statistics per strategy
+    stats: ExecutionStats,
     /// Last heartbeat time as nanoseconds since UNIX epoch (lock-free)
     last_heartbeat_ns: AtomicU64,
     /// Engine start time as nanoseconds since UNIX epoch
     start_time_ns: u64,
+    /// Resting bids from `TradeSignal::Bid`, polled for fills
+    resting_bids: Mutex<Vec<RestingBid>>,
+    /// Last resting bid fill check as nanoseconds since UNIX epoch
+    last_bid_poll_ns: AtomicU64,
+    /// Confirms fills of placed orders before they are booked (None =
+    /// orders count as filled when placed)
+    order_tracker: Option<Arc<OrderTracker>>,
+    confirmations: Mutex<Confirmations>,
+    /// Last tracked order poll as nanoseconds since UNIX epoch
+    last_order_poll_ns: AtomicU64,
+    /// Peak and drawdown of cumulative P&L, sampled each heartbeat
+    equity_curve: EquityCurve,
+    /// Latest heartbeat snapshot (`/status`, gauges, Redis state)
+    status_board: Arc<StatusBoard>,
+    /// Slices large buy and sell signals into child orders over time
+    twap: Option<Arc<TwapExecutor>>,
+    /// Progress of sliced orders, applied on the engine loop
+    twap_tx: UnboundedSender<TwapEvent>,
+    twap_rx: UnboundedReceiver<TwapEvent>,
+    /// Sliced orders started and not yet reported done
+    running_twaps: AtomicU64,
+    /// Event-driven signals handled ahead of the batch (see `FastPath`)
+    fast_tx: UnboundedSender<FastSignal>,
+    /// Taken by `run` while the loop is running
+    fast_rx: Option<UnboundedReceiver<FastSignal>>,
+    /// Orders of the hedger and exit scheduler, booked on the engine loop
+    placed_tx: UnboundedSender<PlacedOrder>,
+    placed_rx: UnboundedReceiver<PlacedOrder>,
+    /// Signals parked for manual approval, when approval mode is on
+    approvals: Option<Arc<ApprovalQueue>>,
+    /// Told about resting bids, whose notional is reserved from the balance
+    funds: Option<Arc<FundsMonitor>>,
+    /// Kill rules checked every heartbeat
+    governor: Option<StrategyGovernor>,
+    /// Every decision on a signal, for comparing versions in dry run
+    decisions: Option<Arc<DecisionLog>>,
 }
 
 impl StrategyEngine {
@@ -58,6 +236,9 @@ impl StrategyEngine {
         risk_manager: Arc<RiskManager>,
         order_manager: Arc<OrderManager>,
     ) -> Self {
+        let (twap_tx, twap_rx) = mpsc::unbounded_channel();
+        let (fast_tx, fast_rx) = mpsc::unbounded_channel();
+        let (placed_tx, placed_rx) = mpsc::unbounded_channel();
         Self {
             strategies: Vec::new(),
             market_data,
@@ -66,20 +247,63 @@ impl StrategyEngine {
             redis_publisher: None,
             slack_notifier: None,
             trade_repo: None,
+            analytics: None,
+            recent_trades: None,
+            volume: None,
+            leader: None,
+            observe: false,
+            warmup: None,
+            volatility: None,
+            retries: None,
+            connectivity: None,
+            watchdog: None,
+            game_feed: None,
+            whales: None,
+            cost_model: CostModel::default(),
             cancellation_token: None,
             eval_interval_ms: 100, // 10 Hz by default
             eval_count: AtomicU64::new(0),
             signal_count: AtomicU64::new(0),
-            last_heartbeat_ns: AtomicU64::new(now_ns()),
+            stats: ExecutionStats::default(),
+            last_heartbeat_ns: AtomicU64::new(0),
             start_time_ns: now_ns(),
+            resting_bids: Mutex::new(Vec::new()),
+            last_bid_poll_ns: AtomicU64::new(0),
+            order_tracker: None,
+            confirmations: Mutex::new(Confirmations::default()),
+            last_order_poll_ns: AtomicU64::new(0),
+            equity_curve: EquityCurve::new(),
+            status_board: Arc::new(StatusBoard::new()),
+            twap: None,
+            twap_tx,
+            twap_rx,
+            running_twaps: AtomicU64::new(0),
+            fast_tx,
+            fast_rx: Some(fast_rx),
+            placed_tx,
+            placed_rx,
+            approvals: None,
+            funds: None,
+            governor: None,
+            decisions: None,
         }
     }
 
+    /// Log every decision on a signal (see `decisions`).
+    pub fn set_decision_log(&mut self, log: Arc<DecisionLog>) {
+        self.decisions = Some(log);
+    }
+
     /// Set the cancellation token for graceful shutdown.
     pub fn set_cancellation_token(&mut self, token: CancellationToken) {
         self.cancellation_token = Some(token);
     }
 
+    /// Status board the engine publishes a snapshot to every heartbeat.
+    pub fn status_board(&self) -> Arc<StatusBoard> {
+        Arc::clone(&self.status_board)
+    }
+
     /// Set the Redis publisher for streaming data to dashboard.
     pub fn set_redis_publisher(&mut self, publisher: Arc<RedisPublisher>) {
         if publisher.is_enabled() {
@@ -97,27 +321,238 @@ impl StrategyEngine {
     }
 
     /// Set the trade repository for database persistence.
-    pub fn set_trade_repo(&mut self, repo: Arc<TradeRepository>) {
+    pub fn set_trade_repo(&mut self, repo: Arc<dyn TradeStore>) {
         if repo.is_enabled() {
             info!("[ENGINE] Database persistence enabled - storing trades");
             self.trade_repo = Some(repo);
         }
     }
 
+    /// Set the analytics sink for signals; call before adding strategies.
+    pub fn set_analytics_sink(&mut self, sink: Arc<AnalyticsSink>) {
+        info!("[ENGINE] Analytics sink enabled - recording signals to ClickHouse");
+        self.analytics = Some(sink);
+    }
+
+    /// Set the ring that records executed trades for drill-down.
+    pub fn set_recent_trades(&mut self, recent_trades: Arc<RecentTrades>) {
+        self.recent_trades = Some(recent_trades);
+    }
+
+    /// Set the tracker that counts filled volume by market and role.
+    pub fn set_volume_tracker(&mut self, volume: Arc<VolumeTracker>) {
+        self.volume = Some(volume);
+    }
+
+    /// Set the leader election; signals are only executed while leader.
+    pub fn set_leader_election(&mut self, leader: Arc<LeaderElection>) {
+        if leader.is_enabled() {
+            info!("[ENGINE] Leader election enabled - executing only while leader");
+            self.leader = Some(leader);
+        }
+    }
+
+    /// Set observe mode: every signal is recorded with its book and risk
+    /// context, and nothing is executed (not even paper fills).
+    pub fn set_observe(&mut self, observe: bool) {
+        if observe {
+            info!("[ENGINE] Observe mode - recording signals without executing them");
+        }
+        self.observe = observe;
+    }
+
+    /// Set the cold-start warm-up: signals are observed instead of executed
+    /// until it is over (see `warmup`).
+    pub fn set_warmup(&mut self, config: WarmupConfig) {
+        if config.duration.is_zero() && config.min_book_updates == 0 {
+            self.warmup = None;
+        } else {
+            self.warmup = Some(Warmup::new(config));
+        }
+    }
+
+    /// Book P&L only for fills the tracker confirms (see
+    /// `execution::order_tracker`).
+    pub fn set_order_tracker(&mut self, tracker: Arc<OrderTracker>) {
+        self.order_tracker = Some(tracker);
+    }
+
+    /// Drop signals for markets whose mid moves too fast (see `volatility`).
+    pub fn set_volatility_gate(&mut self, config: VolatilityGateConfig) {
+        if config.enabled() {
+            info!(
+                "[ENGINE] Volatility gate: {:.4} move within {:?} pauses a market for {:?}",
+                config.max_move, config.window, config.cooldown
+            );
+            self.volatility = Some(VolatilityGate::new(config));
+        } else {
+            self.volatility = None;
+        }
+    }
+
+    /// Observe signals instead of executing them while the connectivity
+    /// supervisor has a network incident open.
+    pub fn set_connectivity(&mut self, connectivity: Arc<ConnectivitySupervisor>) {
+        self.connectivity = Some(connectivity);
+    }
+
+    /// Retry blocked high-edge opportunities (see `retry`).
+    pub fn set_retries(&mut self, config: RetryConfig) {
+        self.retries = (!config.window.is_zero()).then(|| RetryQueue::new(config));
+    }
+
+    /// Set the watchdog beaten on every tick of the loop.
+    pub fn set_watchdog(&mut self, watchdog: Arc<Watchdog>) {
+        if watchdog.is_enabled() {
+            self.watchdog = Some(watchdog);
+        }
+    }
+
+    /// Set the funds monitor that tracks notional reserved by resting bids.
+    pub fn set_funds_monitor(&mut self, funds: Arc<FundsMonitor>) {
+        self.funds = Some(funds);
+    }
+
+    /// Apply kill rules to strategies (see `governor`).
+    pub fn set_governor(&mut self, governor: StrategyGovernor) {
+        info!(
+            "[ENGINE] {} strategy kill rules active",
+            governor.rules().len()
+        );
+        self.governor = Some(governor);
+    }
+
+    /// Set the live sports feed; call before adding strategies.
+    pub fn set_game_feed(&mut self, espn: Arc<EspnClient>) {
+        self.game_feed = Some(espn);
+    }
+
+    /// Set the whale monitor; call before adding strategies.
+    pub fn set_whale_monitor(&mut self, whales: Arc<WhaleMonitor>) {
+        self.whales = Some(whales);
+    }
+
+    /// Set the executor that slices large signals.
+    pub fn set_twap_executor(&mut self, twap: Arc<TwapExecutor>) {
+        info!(
+            "[ENGINE] TWAP enabled - slicing signals from ${:.0} into {} orders over {:?}",
+            twap.config().min_notional,
+            twap.config().slices,
+            twap.config().duration
+        );
+        self.twap = Some(twap);
+    }
+
+    /// Create the manual approval queue; call after `set_slack_notifier`.
+    /// Signals are only parked when approval mode is enabled, but the queue
+    /// is always returned for the control API.
+    pub fn set_approvals(&mut self, config: ApprovalConfig) -> Arc<ApprovalQueue> {
+        let queue = Arc::new(ApprovalQueue::new(
+            config,
+            self.fast_tx.clone(),
+            self.slack_notifier.clone(),
+        ));
+        if queue.is_enabled() {
+            self.approvals = Some(Arc::clone(&queue));
+        }
+        queue
+    }
+
+    /// Sender for orders placed outside the engine (hedges, resolution
+    /// exits), booked like the engine's own. A stopping engine waits for
+    /// every sender to be dropped, so only tasks that stop with it may
+    /// hold one.
+    pub fn placed_orders(&self) -> UnboundedSender<PlacedOrder> {
+        self.placed_tx.clone()
+    }
+
+    /// Set the cost model; call before adding strategies.
+    pub fn set_cost_model(&mut self, cost_model: CostModel) {
+        self.cost_model = cost_model;
+    }
+
     /// Add a strategy to the engine.
-    pub fn add_strategy(&mut self, strategy: Box<dyn Strategy>) {
+    pub fn add_strategy(&mut self, mut strategy: Box<dyn Strategy>) {
         info!("Adding strategy: {}", strategy.name());
+        strategy.set_risk_manager(Arc::clone(&self.risk_manager));
+        strategy.set_cost_model(self.cost_model.clone());
+        if let Some(espn) = &self.game_feed {
+            strategy.set_game_feed(Arc::clone(espn));
+        }
+        if let Some(whales) = &self.whales {
+            strategy.set_whale_monitor(Arc::clone(whales));
+        }
+        if let Some(publisher) = &self.redis_publisher {
+            strategy.set_redis_publisher(Arc::clone(publisher));
+        }
+        if let Some(sink) = &self.analytics {
+            strategy.set_analytics_sink(Arc::clone(sink));
+        }
+        strategy.set_fast_path(FastPath::new(
+            strategy.name(),
+            strategy.is_contested(),
+            Arc::clone(&self.market_data),
+            self.fast_tx.clone(),
+        ));
+        self.stats.register(strategy.name());
         self.strategies.push(strategy);
     }
 
+    /// The evaluation interval in milliseconds.
+    pub fn eval_interval(&self) -> u64 {
+        self.eval_interval_ms
+    }
+
     /// Set the evaluation interval in milliseconds.
-    #[allow(dead_code)]
     pub fn set_eval_interval(&mut self, ms: u64) {
         self.eval_interval_ms = ms;
     }
 
-    /// Run the strategy engine loop.
+    /// Run the strategy engine loop. The engine can be run again after the
+    /// loop returns or panics (see `restarts`).
     pub async fn run(&mut self) {
+        let Some(mut fast_rx) = self.fast_rx.take() else {
+            warn!("[ENGINE] Strategy engine is already running");
+            return;
+        };
+        let result = AssertUnwindSafe(self.run_loop(&mut fast_rx))
+            .catch_unwind()
+            .await;
+        self.fast_rx = Some(fast_rx);
+        if let Err(panic) = result {
+            std::panic::resume_unwind(panic);
+        }
+        self.finish_twaps().await;
+        self.finish_placed_orders().await;
+    }
+
+    /// Book the last children of sliced orders still running. They stop
+    /// at their next child once cancelled, but one already being placed
+    /// reports its fill after the loop has returned.
+    async fn finish_twaps(&mut self) {
+        while self.running_twaps.load(Ordering::Relaxed) > 0 {
+            let Some(event) = self.twap_rx.recv().await else {
+                break;
+            };
+            self.apply_twap_event(event);
+        }
+    }
+
+    /// Book the last orders of the hedger and exit scheduler. They stop
+    /// with the engine but may be placing one as it does; the engine holds
+    /// one sender, theirs are dropped as their tasks return.
+    async fn finish_placed_orders(&mut self) {
+        while self.placed_tx.strong_count() > 1 {
+            if let Ok(Some(placed)) = timeout(PLACED_ORDER_WAIT, self.placed_rx.recv()).await {
+                self.apply_placed_order(placed);
+            }
+        }
+        while let Ok(placed) = self.placed_rx.try_recv() {
+            self.apply_placed_order(placed);
+        }
+    }
+
+    async fn run_loop(&mut self, fast_rx: &mut UnboundedReceiver<FastSignal>) {
         info!(
             "Strategy engine starting with {} strategies @ {} Hz",
             self.strategies.len(),
@@ -129,17 +564,28 @@ impl StrategyEngine {
         let heartbeat_interval = Duration::from_secs(60); // Log heartbeat every minute
 
         loop {
-            // Check for cancellation with cancellation-aware tick
-            if let Some(ref token) = self.cancellation_token {
+            // Check for cancellation with cancellation-aware tick; fast-path
+            // signals are handled the moment they arrive
+            let fast = if let Some(ref token) = self.cancellation_token {
                 tokio::select! {
-                    _ = ticker.tick() => {}
+                    biased;
                     _ = token.cancelled() => {
                         info!("[ENGINE] Shutdown requested - stopping strategy engine gracefully");
                         return;
                     }
+                    Some(fast) = fast_rx.recv() => Some(fast),
+                    _ = ticker.tick() => None,
                 }
             } else {
-                ticker.tick().await;
+                tokio::select! {
+                    biased;
+                    Some(fast) = fast_rx.recv() => Some(fast),
+                    _ = ticker.tick() => None,
+                }
+            };
+            if let Some(fast) = fast {
+                self.handle_fast_signal(fast).await;
+                continue;
             }
 
             // Check for cancellation at the start of each loop iteration
@@ -150,6 +596,14 @@ impl StrategyEngine {
                 }
             }
 
+            // The loop is alive, even if it has nothing to evaluate yet
+            if let Some(ref watchdog) = self.watchdog {
+                watchdog.beat(Instant::now());
+            }
+
+            // Status snapshot (every minute), also while waiting for data
+            self.heartbeat(heartbeat_interval);
+
             // Skip if no market data yet
             if !self.market_data.has_data() {
                 if waiting_for_data {
@@ -162,66 +616,72 @@ impl StrategyEngine {
             if waiting_for_data {
                 waiting_for_data = false;
                 info!("[HEARTBEAT] Market data received! Starting strategy evaluation.");
+                if let Some(warmup) = &mut self.warmup {
+                    warmup.start(Instant::now());
+                }
             }
 
             // Increment eval counter (both internal counter and Prometheus metric)
-            let evals = self.eval_count.fetch_add(1, Ordering::Relaxed) + 1;
+            self.eval_count.fetch_add(1, Ordering::Relaxed);
             EVALUATIONS_TOTAL.inc();
 
-            // Periodic heartbeat log and Redis state publish (every minute)
-            let heartbeat_interval_ns = heartbeat_interval.as_nanos() as u64;
+            // Check resting bids for fills before strategies act on them
             let current_ns = now_ns();
-            let last_hb_ns = self.last_heartbeat_ns.load(Ordering::Relaxed);
-            if current_ns.saturating_sub(last_hb_ns) >= heartbeat_interval_ns {
-                let signals = self.signal_count.load(Ordering::Relaxed);
-                let markets = self.market_data.get_all_pairs().len();
-                let order_books = self.market_data.order_book_count();
-                let uptime_secs = current_ns.saturating_sub(self.start_time_ns) / 1_000_000_000;
-
-                info!(
-                    "[HEARTBEAT] Engine alive | evals={} | signals={} | markets={} | order_books={} | uptime={}s",
-                    evals,
-                    signals,
-                    markets,
-                    order_books,
-                    uptime_secs
-                );
+            let last_poll_ns = self.last_bid_poll_ns.load(Ordering::Relaxed);
+            if current_ns.saturating_sub(last_poll_ns) >= BID_POLL_INTERVAL_NS
+                && !self.resting_bids.lock().is_empty()
+            {
+                self.last_bid_poll_ns.store(current_ns, Ordering::Relaxed);
+                self.poll_resting_bids().await;
+            }
 
-                // Update Prometheus daily P&L gauge
-                DAILY_PNL.set(self.risk_manager.get_daily_pnl());
-
-                // Publish state to Redis (fire-and-forget, non-blocking)
-                if let Some(ref publisher) = self.redis_publisher {
-                    let state = EngineState {
-                        timestamp_ms: now_ms(),
-                        status: "running".to_string(),
-                        markets_tracked: markets,
-                        opportunities_found: signals as usize,
-                        daily_pnl: self.risk_manager.get_daily_pnl(),
-                        daily_trades: self.risk_manager.get_daily_trades(),
-                        positions: vec![], // TODO: Get from risk manager
-                    };
-                    let pub_clone = Arc::clone(publisher);
-                    tokio::spawn(async move {
-                        let _ = pub_clone.publish_state(&state).await;
-                    });
+            // Book confirmed fills of placed orders
+            if let Some(tracker) = &self.order_tracker {
+                let last_poll_ns = self.last_order_poll_ns.load(Ordering::Relaxed);
+                let poll_ns = tracker.poll_interval().as_nanos() as u64;
+                if current_ns.saturating_sub(last_poll_ns) >= poll_ns && tracker.len() > 0 {
+                    self.last_order_poll_ns.store(current_ns, Ordering::Relaxed);
+                    self.poll_tracked_orders(tracker).await;
                 }
+            }
+
+            // Book child fills of sliced orders
+            while let Ok(event) = self.twap_rx.try_recv() {
+                self.apply_twap_event(event);
+            }
 
-                self.last_heartbeat_ns.store(current_ns, Ordering::Relaxed);
+            // Book hedges and resolution exits
+            while let Ok(placed) = self.placed_rx.try_recv() {
+                self.apply_placed_order(placed);
             }
 
+            // Report rejected and expired approvals to their strategies
+            self.drain_approvals();
+
+            // Blocked opportunities that can go now
+            self.retry_opportunities().await;
+
+            // Capture one immutable view per tick so every strategy sees the same state
+            let snapshot = self.market_data.snapshot(SNAPSHOT_HISTORY_TICKS);
+
             // Phase 1: Collect all signals from all strategies (sync, CPU-bound)
             let signals: Vec<NamedSignal> = self
                 .strategies
                 .iter()
-                .filter(|s| s.is_active())
+                .filter(|s| s.is_active() && !self.disabled_by_governor(s.name()))
                 .filter_map(|strategy| {
-                    strategy
-                        .evaluate(&self.market_data)
-                        .map(|signal| NamedSignal {
-                            strategy_name: strategy.name(),
-                            signal,
-                        })
+                    let started = Instant::now();
+                    let signal = strategy.evaluate(&snapshot);
+                    self.stats.record_eval(
+                        strategy.name(),
+                        started.elapsed(),
+                        signal.is_some() as u64,
+                    );
+                    signal.map(|signal| NamedSignal {
+                        strategy_name: strategy.name(),
+                        signal,
+                        contested: strategy.is_contested(),
+                    })
                 })
                 .collect();
 
@@ -229,39 +689,447 @@ impl StrategyEngine {
                 continue;
             }
 
+            if let Some(leader) = &self.leader {
+                if !leader.is_leader() {
+                    debug_limited!(
+                        "engine_standby",
+                        None,
+                        "[ENGINE] Standby - skipping {} signal(s) this cycle",
+                        signals.len()
+                    );
+                    continue;
+                }
+            }
+
             // Count signals
             self.signal_count
                 .fetch_add(signals.len() as u64, Ordering::Relaxed);
 
             info!("[ENGINE] {} signal(s) generated this cycle", signals.len());
 
+            // Edges seen while a market reprices fast are likely phantoms
+            let signals: Vec<NamedSignal> = signals
+                .into_iter()
+                .filter(|named| !self.suppressed_by_volatility(named.strategy_name, &named.signal))
+                .collect();
+
+            if self.observe || self.paused_by_network() {
+                for named in signals {
+                    self.observe_signal(named.strategy_name, named.signal);
+                }
+                continue;
+            }
+
+            // Cold-start: observe what the engine is not yet warm enough to
+            // trade, and what kill rules have put in shadow mode
+            let (held, signals): (Vec<_>, Vec<_>) = signals.into_iter().partition(|named| {
+                self.held_by_warmup(&named.signal) || self.shadowed_by_governor(named.strategy_name)
+            });
+            for named in held {
+                self.observe_signal(named.strategy_name, named.signal);
+            }
+
             // Phase 2: Handle signals concurrently (async, I/O-bound)
             // This allows multiple orders to be in-flight simultaneously
             let futures: Vec<_> = signals
                 .into_iter()
-                .map(|named| self.handle_signal(named.strategy_name, named.signal))
+                .map(|named| {
+                    self.handle_signal(named.strategy_name, named.signal, named.contested, false)
+                })
                 .collect();
 
-            futures::future::join_all(futures).await;
+            // Fast-path signals don't wait for the batch to finish
+            let batch = futures::future::join_all(futures);
+            tokio::pin!(batch);
+            loop {
+                tokio::select! {
+                    biased;
+                    Some(fast) = fast_rx.recv() => self.handle_fast_signal(fast).await,
+                    _ = &mut batch => break,
+                }
+            }
         }
     }
 
-    /// Handle a trade signal from a strategy.
-    async fn handle_signal(&self, strategy_name: &str, signal: TradeSignal) {
-        info!("[{}] Signal: {}", strategy_name, signal.description());
+    /// Handle a signal pushed through the fast path, outside the tick.
+    async fn handle_fast_signal(&self, fast: FastSignal) {
+        let queued = fast.sent_at.elapsed();
+        FAST_PATH_DELAY
+            .with_label_values(&[fast.strategy_name])
+            .observe(queued.as_secs_f64());
 
-        // Record signal in Prometheus metrics
-        let signal_type = match &signal {
-            TradeSignal::Buy { .. } => "buy",
-            TradeSignal::Sell { .. } => "sell",
-            TradeSignal::Arbitrage { .. } => "arbitrage",
+        if let Some(leader) = &self.leader {
+            if !leader.is_leader() {
+                debug!(
+                    "[ENGINE] Standby - skipping fast-path signal from {}",
+                    fast.strategy_name
+                );
+                return;
+            }
+        }
+
+        self.signal_count.fetch_add(1, Ordering::Relaxed);
+        info!(
+            "[ENGINE] Fast-path signal from {} ({}us queued)",
+            fast.strategy_name,
+            queued.as_micros()
+        );
+        if self.suppressed_by_volatility(fast.strategy_name, &fast.signal) {
+            return;
+        }
+
+        if self.observe
+            || self.paused_by_network()
+            || self.held_by_warmup(&fast.signal)
+            || self.shadowed_by_governor(fast.strategy_name)
+        {
+            self.observe_signal(fast.strategy_name, fast.signal);
+        } else {
+            self.handle_signal(
+                fast.strategy_name,
+                fast.signal,
+                fast.contested,
+                fast.approved,
+            )
+            .await;
+        }
+    }
+
+    /// Log and publish an `EngineState` snapshot once per interval. The
+    /// status board feeds the gauges, `/status` and the Redis state channel.
+    fn heartbeat(&mut self, interval: Duration) {
+        let current_ns = now_ns();
+        let last_hb_ns = self.last_heartbeat_ns.load(Ordering::Relaxed);
+        if current_ns.saturating_sub(last_hb_ns) < interval.as_nanos() as u64 {
+            return;
+        }
+        self.last_heartbeat_ns.store(current_ns, Ordering::Relaxed);
+
+        // Sample the equity curve
+        let equity = self.equity_curve.record(
+            now_ms(),
+            self.risk_manager.get_realized_pnl(),
+            self.risk_manager.get_unrealized_pnl(),
+        );
+        if let Some(ref repo) = self.trade_repo {
+            repo.insert_equity_sample(equity, self.order_manager.is_dry_run());
+        }
+
+        // Open positions marked to the mid, as on `/portfolio`
+        let positions = PortfolioSnapshot::build(
+            &self.risk_manager.get_all_positions(),
+            &self.market_data,
+            None,
+            0.0,
+            0.0,
+        )
+        .positions
+        .into_iter()
+        .map(|p| PositionInfo {
+            token_id: p.token_id,
+            size: p.size,
+            avg_cost: p.avg_cost,
+            unrealized_pnl: p.unrealized_pnl,
+        })
+        .collect();
+
+        let state = EngineState {
+            timestamp_ms: now_ms(),
+            status: self.status().to_string(),
+            uptime_secs: current_ns.saturating_sub(self.start_time_ns) / 1_000_000_000,
+            evaluations: self.eval_count.load(Ordering::Relaxed),
+            markets_tracked: self.market_data.get_all_pairs().len(),
+            order_books: self.market_data.order_book_count(),
+            opportunities_found: self.signal_count.load(Ordering::Relaxed) as usize,
+            daily_pnl: self.risk_manager.get_daily_pnl(),
+            daily_trades: self.risk_manager.get_daily_trades(),
+            equity: equity.equity,
+            drawdown: equity.drawdown,
+            max_drawdown: equity.max_drawdown,
+            positions,
+            strategies: self.stats.snapshot(),
+        };
+        // Kill rules are judged on the figures the heartbeat reports
+        if let Some(governor) = &self.governor {
+            for stats in &state.strategies {
+                let health = StrategyHealth {
+                    consecutive_losses: governor.losing_streak(stats.strategy),
+                    daily_pnl: self
+                        .risk_manager
+                        .get_strategy_usage(stats.strategy)
+                        .realized_pnl,
+                    executed: stats.executed,
+                    rejected: stats.rejected,
+                };
+                governor.check(stats.strategy, &health);
+            }
+        }
+
+        let per_strategy = state
+            .strategies
+            .iter()
+            .map(|s| s.summary())
+            .collect::<Vec<_>>()
+            .join("; ");
+        info!(
+            "[HEARTBEAT] Engine {} | evals={} | signals={} | markets={} | order_books={} | uptime={}s | {}",
+            state.status,
+            state.evaluations,
+            state.opportunities_found,
+            state.markets_tracked,
+            state.order_books,
+            state.uptime_secs,
+            per_strategy
+        );
+        self.status_board.publish(state);
+    }
+
+    /// Whether a signal is only observed because the engine or one of its
+    /// books is still warming up.
+    fn held_by_warmup(&self, signal: &TradeSignal) -> bool {
+        self.warmup
+            .as_ref()
+            .is_some_and(|w| w.holds(signal, &self.market_data, Instant::now()))
+    }
+
+    /// Whether a signal is dropped because a market it trades is in a
+    /// volatility cooldown
+    fn suppressed_by_volatility(&self, strategy_name: &str, signal: &TradeSignal) -> bool {
+        let Some(gate) = &self.volatility else {
+            return false;
         };
+        if !gate.suppresses(signal, &self.market_data, Instant::now(), now_ns()) {
+            return false;
+        }
+        VOLATILITY_SUPPRESSED
+            .with_label_values(&[strategy_name])
+            .inc();
+        debug!(
+            "[ENGINE] {} signal suppressed during volatility cooldown",
+            strategy_name
+        );
+        self.log_decision(
+            strategy_name,
+            signal,
+            "suppressed",
+            vec!["volatility".into()],
+        );
+        true
+    }
+
+    /// Whether a kill rule has stopped the strategy being evaluated
+    fn disabled_by_governor(&self, strategy: &str) -> bool {
+        self.governor
+            .as_ref()
+            .is_some_and(|g| g.is_disabled(strategy))
+    }
+
+    /// Whether a kill rule has put the strategy in shadow mode
+    fn shadowed_by_governor(&self, strategy: &str) -> bool {
+        self.governor
+            .as_ref()
+            .is_some_and(|g| g.is_shadowed(strategy))
+    }
+
+    /// Whether trading is paused for a network incident
+    fn paused_by_network(&self) -> bool {
+        self.connectivity
+            .as_ref()
+            .is_some_and(|c| c.pauses_trading())
+    }
+
+    /// Engine status published with its state
+    fn status(&self) -> &'static str {
+        if !self.market_data.has_data() {
+            "waiting_for_data"
+        } else if self.observe {
+            "observing"
+        } else if self.paused_by_network() {
+            "network_degraded"
+        } else if self
+            .warmup
+            .as_ref()
+            .is_some_and(|w| w.in_progress(Instant::now()))
+        {
+            "warming_up"
+        } else {
+            "running"
+        }
+    }
+
+    /// Execution mode label for signal metrics and messages
+    fn signal_mode(&self) -> &'static str {
+        if self.observe {
+            "observe"
+        } else {
+            self.order_manager.mode_label()
+        }
+    }
+
+    fn record_signal_metrics(&self, strategy_name: &'static str, signal: &TradeSignal) {
+        let mode = self.signal_mode();
         SIGNALS_TOTAL
-            .with_label_values(&[strategy_name, signal_type])
+            .with_label_values(&[strategy_name, signal.kind(), mode])
             .inc();
+        if let Some(edge) = signal.edge() {
+            SIGNAL_EDGE
+                .with_label_values(&[strategy_name, mode])
+                .observe(edge);
+        }
+    }
+
+    /// Record a signal in observe mode: what would have been sent, the book
+    /// it was generated against, and whether risk would have approved it.
+    /// Nothing is executed and no risk state changes.
+    fn observe_signal(&self, strategy_name: &'static str, signal: TradeSignal) {
+        info!("[{}] Observed: {}", strategy_name, signal.description());
+        self.record_signal_metrics(strategy_name, &signal);
+
+        let rounded = self.apply_order_rules(&signal);
+        let checks = rounded
+            .as_ref()
+            .map(|s| self.risk_manager.evaluate(s))
+            .unwrap_or_default();
+        let (action, rejection) = observed_outcome(rounded.is_some(), &checks);
+        if self.decisions.is_some() {
+            let failed: Vec<String> = if rounded.is_none() {
+                vec!["below_min_size".to_string()]
+            } else {
+                checks
+                    .iter()
+                    .filter(|c| !c.passed)
+                    .map(|c| c.name.to_string())
+                    .collect()
+            };
+            let decision = if failed.is_empty() {
+                "observed"
+            } else {
+                "rejected"
+            };
+            self.log_decision(strategy_name, &signal, decision, failed);
+        }
+        if rejection.is_some() {
+            self.stats
+                .record_outcome(strategy_name, SignalOutcome::Rejected);
+        }
+
+        let books: Vec<_> = signal
+            .tokens()
+            .into_iter()
+            .map(|token_id| {
+                let quote = self.market_data.get_quote(token_id);
+                let book = quote.as_ref().and_then(|(_, book)| book.as_ref());
+                serde_json::json!({
+                    "token_id": token_id,
+                    "bid": quote.as_ref().map(|(level, _)| level.bid),
+                    "ask": quote.as_ref().map(|(level, _)| level.ask),
+                    "bid_depth": book.map(|b| b.total_bid_size()),
+                    "ask_depth": book.map(|b| b.total_ask_size()),
+                })
+            })
+            .collect();
+        let mut msg = self.signal_message(strategy_name, &signal);
+        msg.context = Some(serde_json::json!({
+            "action": action,
+            "executable_size": rounded.as_ref().map(TradeSignal::size),
+            "notional": signal.notional(),
+            "books": books,
+            "checks": checks,
+        }));
+
+        if let Some(ref repo) = self.trade_repo {
+            repo.insert_signal(msg.clone(), action, rejection);
+        }
+        self.publish_signal(msg);
+
+        // The bid was never placed, so the strategy must not wait on it
+        if let TradeSignal::Bid { token_id, .. } = &signal {
+            if let Some(strategy) = self.strategy(strategy_name) {
+                strategy.on_bid_done(token_id, 0.0);
+            }
+        }
+    }
+
+    /// The signal with its size rounded down to the market's share
+    /// increment, or None if it falls below the minimum order size.
+    fn apply_order_rules(&self, signal: &TradeSignal) -> Option<TradeSignal> {
+        let mut signal = signal.clone();
+        match &mut signal {
+            TradeSignal::Buy { token_id, size, .. }
+            | TradeSignal::Sell { token_id, size, .. }
+            | TradeSignal::Bid { token_id, size, .. } => {
+                *size = self.market_data.order_rules(token_id).round_size(*size)?;
+            }
+            TradeSignal::Arbitrage {
+                yes_token,
+                no_token,
+                size,
+                ..
+            } => {
+                // Both legs share one size, so it must be valid in both books
+                let yes = self.market_data.order_rules(yes_token).round_size(*size)?;
+                let no = self.market_data.order_rules(no_token).round_size(*size)?;
+                *size = yes.min(no);
+            }
+            TradeSignal::Basket { legs, payout, .. } => {
+                // Every leg must be valid; the payout shrinks with the
+                // most-rounded leg
+                if legs.is_empty() {
+                    return None;
+                }
+                let mut scale: f64 = 1.0;
+                for leg in legs.iter_mut() {
+                    let rounded = self
+                        .market_data
+                        .order_rules(&leg.token_id)
+                        .round_size(leg.size)?;
+                    scale = scale.min(rounded / leg.size);
+                    leg.size = rounded;
+                }
+                if let Some(payout) = payout {
+                    *payout *= scale;
+                }
+            }
+            TradeSignal::Cancel { .. } => {}
+        }
+        Some(signal)
+    }
+
+    /// Handle a trade signal from a strategy. `approved` signals were
+    /// released from the approval queue and are not parked again.
+    async fn handle_signal(
+        &self,
+        strategy_name: &'static str,
+        signal: TradeSignal,
+        contested: bool,
+        approved: bool,
+    ) {
+        info!("[{}] Signal: {}", strategy_name, signal.description());
+
+        // Record signal in Prometheus metrics
+        self.record_signal_metrics(strategy_name, &signal);
 
-        // Publish signal to Redis (fire-and-forget)
-        self.publish_signal_to_redis(strategy_name, &signal);
+        // Publish signal to Redis and analytics (fire-and-forget)
+        self.publish_signal(self.signal_message(strategy_name, &signal));
+
+        // Round to the market's share increment so what we record matches
+        // what is sent; reject sizes below the minimum order size
+        let Some(signal) = self.apply_order_rules(&signal) else {
+            warn!(
+                "[{}] Signal rejected, size below market minimum: {}",
+                strategy_name,
+                signal.description()
+            );
+            self.stats
+                .record_outcome(strategy_name, SignalOutcome::Rejected);
+            self.notify_rejected(strategy_name, &signal, "below_min_size");
+            if let TradeSignal::Bid { token_id, .. } = &signal {
+                if let Some(strategy) = self.strategy(strategy_name) {
+                    strategy.on_bid_done(token_id, 0.0);
+                }
+            }
+            return;
+        };
 
         // Check risk limits
         if !self.risk_manager.check_signal(&signal) {
@@ -270,86 +1138,180 @@ impl StrategyEngine {
                 strategy_name,
                 signal.description()
             );
+            self.stats
+                .record_outcome(strategy_name, SignalOutcome::Rejected);
+            self.notify_rejected(strategy_name, &signal, "risk_limits");
+            if !self.risk_manager.is_emergency_stopped() {
+                self.hold_for_retry(strategy_name, &signal, approved);
+            }
+            if let TradeSignal::Bid { token_id, .. } = &signal {
+                if let Some(strategy) = self.strategy(strategy_name) {
+                    strategy.on_bid_done(token_id, 0.0);
+                }
+            }
+            return;
+        }
+
+        // Large signals and untrusted strategies wait for an operator
+        if !approved {
+            if let Some(approvals) = &self.approvals {
+                if approvals.park(strategy_name, &signal, contested).is_some() {
+                    self.log_decision(strategy_name, &signal, "parked", Vec::new());
+                    return;
+                }
+            }
+        }
+
+        // Every order placed for the signal is tagged with its ID
+        let signal = signal.with_metadata(SIGNAL_ID_KEY, uuid::Uuid::new_v4().to_string());
+
+        // Large buys and sells are sent as slices over time
+        let twap = self
+            .twap
+            .as_ref()
+            .filter(|t| !contested && t.should_slice(&signal));
+        if let Some(twap) = twap {
+            if twap.start(strategy_name, signal.clone(), self.twap_tx.clone()) {
+                self.running_twaps.fetch_add(1, Ordering::Relaxed);
+                self.stats
+                    .record_outcome(strategy_name, SignalOutcome::Executed);
+                self.notify_executed(strategy_name, &signal);
+            } else {
+                self.notify_rejected(strategy_name, &signal, "twap_busy");
+                debug_limited!(
+                    "twap_busy",
+                    None,
+                    "[{}] Sliced order already running, dropping: {}",
+                    strategy_name,
+                    signal.description()
+                );
+            }
             return;
         }
 
         // Execute the signal
+        self.stats
+            .record_outcome(strategy_name, SignalOutcome::Executed);
+        let started = Instant::now();
         match &signal {
             TradeSignal::Buy {
                 token_id,
                 price,
                 size,
                 reason,
-            } => match self.order_manager.place_buy(token_id, *price, *size).await {
-                Ok(order_id) => {
-                    info!("[{}] Buy order placed: {}", strategy_name, order_id);
-                    self.risk_manager.record_trade(&signal);
-                    self.publish_trade_to_redis(strategy_name, &signal, Some(&order_id), "FILLED");
-                    self.notify_slack_order(
-                        strategy_name,
-                        "BUY",
-                        Some(token_id),
-                        None,
-                        None,
-                        Some(*price),
-                        None,
-                        None,
-                        *size,
-                        Some(&order_id),
-                        "FILLED",
-                        None,
-                    );
-                    self.persist_trade_to_db(
-                        strategy_name,
-                        token_id,
-                        "BUY",
-                        *price,
-                        *size,
-                        Some(&order_id),
-                        "FILLED",
-                        Some(reason.as_str()),
-                    );
-                }
-                Err(e) => {
-                    warn!("[{}] Buy order failed: {}", strategy_name, e);
-                    let status = format!("FAILED: {}", e);
-                    self.publish_trade_to_redis(strategy_name, &signal, None, &status);
-                    self.notify_slack_order(
-                        strategy_name,
-                        "BUY",
-                        Some(token_id),
-                        None,
-                        None,
-                        Some(*price),
-                        None,
-                        None,
-                        *size,
-                        None,
-                        &status,
-                        None,
-                    );
-                    self.persist_trade_to_db(
-                        strategy_name,
-                        token_id,
-                        "BUY",
-                        *price,
-                        *size,
-                        None,
-                        &status,
-                        Some(reason.as_str()),
-                    );
+                ..
+            } => {
+                let placed = if contested {
+                    self.order_manager
+                        .place_contested_buy(
+                            token_id,
+                            *price,
+                            *size,
+                            signal.metadata().event_age_ms(),
+                        )
+                        .await
+                } else {
+                    self.order_manager.place_buy(token_id, *price, *size).await
+                };
+                match placed {
+                    Ok(order_id) => {
+                        info!("[{}] Buy order placed: {}", strategy_name, order_id);
+                        self.tag_order(strategy_name, &signal, &order_id, token_id, "BUY");
+                        self.notify_executed(strategy_name, &signal);
+                        let status = self.placed_status();
+                        let order_ids = vec![order_id.clone()];
+                        self.trace_trade(strategy_name, &signal, started, order_ids, status);
+                        self.book_or_track(strategy_name, &signal, &order_id);
+                        self.publish_trade_to_redis(
+                            strategy_name,
+                            &signal,
+                            Some(&order_id),
+                            status,
+                        );
+                        self.notify_slack_order(
+                            strategy_name,
+                            "BUY",
+                            Some(token_id),
+                            None,
+                            None,
+                            Some(*price),
+                            None,
+                            None,
+                            *size,
+                            Some(&order_id),
+                            status,
+                            None,
+                            None,
+                            signal.metadata(),
+                        );
+                        self.persist_trade_to_db(
+                            strategy_name,
+                            token_id,
+                            "BUY",
+                            *price,
+                            *size,
+                            Some(&order_id),
+                            status,
+                            Some(reason.as_str()),
+                            None,
+                            false,
+                            signal.metadata(),
+                        );
+                    }
+                    Err(e) => {
+                        warn!("[{}] Buy order failed: {}", strategy_name, e);
+                        let status = format!("FAILED: {}", e.reason());
+                        self.notify_rejected(strategy_name, &signal, e.reason());
+                        self.trace_trade(strategy_name, &signal, started, Vec::new(), &status);
+                        self.publish_trade_to_redis(strategy_name, &signal, None, &status);
+                        self.notify_slack_order(
+                            strategy_name,
+                            "BUY",
+                            Some(token_id),
+                            None,
+                            None,
+                            Some(*price),
+                            None,
+                            None,
+                            *size,
+                            None,
+                            &status,
+                            None,
+                            e.hint(),
+                            signal.metadata(),
+                        );
+                        self.persist_trade_to_db(
+                            strategy_name,
+                            token_id,
+                            "BUY",
+                            *price,
+                            *size,
+                            None,
+                            &status,
+                            Some(reason.as_str()),
+                            None,
+                            false,
+                            signal.metadata(),
+                        );
+                    }
                 }
-            },
+            }
             TradeSignal::Sell {
                 token_id,
                 price,
                 size,
                 reason,
+                ..
             } => match self.order_manager.place_sell(token_id, *price, *size).await {
                 Ok(order_id) => {
                     info!("[{}] Sell order placed: {}", strategy_name, order_id);
-                    self.risk_manager.record_trade(&signal);
-                    self.publish_trade_to_redis(strategy_name, &signal, Some(&order_id), "FILLED");
+                    self.tag_order(strategy_name, &signal, &order_id, token_id, "SELL");
+                    self.notify_executed(strategy_name, &signal);
+                    let status = self.placed_status();
+                    let order_ids = vec![order_id.clone()];
+                    self.trace_trade(strategy_name, &signal, started, order_ids, status);
+                    let pnl = self.book_or_track(strategy_name, &signal, &order_id);
+                    self.publish_trade_to_redis(strategy_name, &signal, Some(&order_id), status);
                     self.notify_slack_order(
                         strategy_name,
                         "SELL",
@@ -361,8 +1323,10 @@ impl StrategyEngine {
                         None,
                         *size,
                         Some(&order_id),
-                        "FILLED",
+                        status,
+                        None,
                         None,
+                        signal.metadata(),
                     );
                     self.persist_trade_to_db(
                         strategy_name,
@@ -371,13 +1335,18 @@ impl StrategyEngine {
                         *price,
                         *size,
                         Some(&order_id),
-                        "FILLED",
+                        status,
                         Some(reason.as_str()),
+                        pnl,
+                        false,
+                        signal.metadata(),
                     );
                 }
                 Err(e) => {
                     warn!("[{}] Sell order failed: {}", strategy_name, e);
-                    let status = format!("FAILED: {}", e);
+                    let status = format!("FAILED: {}", e.reason());
+                    self.notify_rejected(strategy_name, &signal, e.reason());
+                    self.trace_trade(strategy_name, &signal, started, Vec::new(), &status);
                     self.publish_trade_to_redis(strategy_name, &signal, None, &status);
                     self.notify_slack_order(
                         strategy_name,
@@ -392,6 +1361,8 @@ impl StrategyEngine {
                         None,
                         &status,
                         None,
+                        e.hint(),
+                        signal.metadata(),
                     );
                     self.persist_trade_to_db(
                         strategy_name,
@@ -402,9 +1373,83 @@ impl StrategyEngine {
                         None,
                         &status,
                         Some(reason.as_str()),
+                        None,
+                        false,
+                        signal.metadata(),
                     );
                 }
             },
+            TradeSignal::Bid {
+                token_id,
+                price,
+                size,
+                metadata,
+                ..
+            } => match self.order_manager.place_bid(token_id, *price, *size).await {
+                Ok(order_id) => {
+                    info!("[{}] Resting bid placed: {}", strategy_name, order_id);
+                    self.tag_order(strategy_name, &signal, &order_id, token_id, "BUY");
+                    self.notify_executed(strategy_name, &signal);
+                    let order_ids = vec![order_id.clone()];
+                    self.trace_trade(strategy_name, &signal, started, order_ids, "RESTING");
+                    if let Some(funds) = &self.funds {
+                        funds.set_open_order(&order_id, price * size);
+                    }
+                    self.resting_bids.lock().push(RestingBid {
+                        strategy_name,
+                        order_id,
+                        token_id: token_id.clone(),
+                        price: *price,
+                        size: *size,
+                        filled: 0.0,
+                        metadata: metadata.clone(),
+                    });
+                }
+                Err(e) => {
+                    warn!("[{}] Resting bid failed: {}", strategy_name, e);
+                    self.notify_rejected(strategy_name, &signal, e.reason());
+                    if let Some(strategy) = self.strategy(strategy_name) {
+                        strategy.on_bid_done(token_id, 0.0);
+                    }
+                }
+            },
+            TradeSignal::Cancel { token_id, .. } => {
+                let bids: Vec<RestingBid> = self
+                    .resting_bids
+                    .lock()
+                    .iter()
+                    .filter(|b| b.strategy_name == strategy_name && b.token_id == *token_id)
+                    .cloned()
+                    .collect();
+                if bids.is_empty() {
+                    if let Some(strategy) = self.strategy(strategy_name) {
+                        strategy.on_bid_done(token_id, 0.0);
+                    }
+                }
+                for bid in bids {
+                    if let Err(e) = self.order_manager.cancel_order(&bid.order_id).await {
+                        warn!(
+                            "[{}] Cancel of {} failed: {}",
+                            strategy_name, bid.order_id, e
+                        );
+                        continue;
+                    }
+                    // Pick up any fill that raced the cancel
+                    let filled = self
+                        .order_manager
+                        .order_fill(&bid.order_id, &bid.token_id, bid.price, bid.size)
+                        .await
+                        .map(|fill| fill.filled)
+                        .unwrap_or(bid.filled);
+                    self.apply_bid_fill(
+                        &bid.order_id,
+                        OrderFill {
+                            filled,
+                            open: false,
+                        },
+                    );
+                }
+            }
             TradeSignal::Arbitrage {
                 yes_token,
                 no_token,
@@ -412,24 +1457,114 @@ impl StrategyEngine {
                 no_price,
                 profit_per_share,
                 size,
+                side,
+                metadata,
             } => {
-                // For arbitrage, we need to place both orders
-                let buy_yes = self
-                    .order_manager
-                    .place_buy(yes_token, *yes_price, *size)
-                    .await;
-                let buy_no = self
-                    .order_manager
-                    .place_buy(no_token, *no_price, *size)
-                    .await;
-
-                match (buy_yes, buy_no) {
+                // For arbitrage, we need to place both orders (both buys, or
+                // both sells of held inventory)
+                let (yes_leg, no_leg) = match side {
+                    Side::Buy => (
+                        self.order_manager
+                            .place_buy(yes_token, *yes_price, *size)
+                            .await,
+                        self.order_manager
+                            .place_buy(no_token, *no_price, *size)
+                            .await,
+                    ),
+                    Side::Sell => (
+                        self.order_manager
+                            .place_sell(yes_token, *yes_price, *size)
+                            .await,
+                        self.order_manager
+                            .place_sell(no_token, *no_price, *size)
+                            .await,
+                    ),
+                };
+
+                let leg_side = match side {
+                    Side::Buy => "BUY",
+                    Side::Sell => "SELL",
+                };
+                for (leg, token_id) in [(&yes_leg, yes_token), (&no_leg, no_token)] {
+                    if let Ok(order_id) = leg {
+                        self.tag_order(strategy_name, &signal, order_id, token_id, leg_side);
+                    }
+                }
+
+                // A lone filled leg is a real one-sided position change:
+                // record it so the risk limits (and the hedger) see it
+                let filled_leg = match (&yes_leg, &no_leg) {
+                    (Ok(order_id), Err(_)) => Some((yes_token, *yes_price, order_id)),
+                    (Err(_), Ok(order_id)) => Some((no_token, *no_price, order_id)),
+                    _ => None,
+                };
+                if let Some((token_id, price, order_id)) = filled_leg {
+                    let token_id = token_id.clone();
+                    let size = *size;
+                    let reason = "Arbitrage leg (other leg failed)".to_string();
+                    let metadata = metadata.clone();
+                    let leg = match side {
+                        Side::Buy => TradeSignal::Buy {
+                            token_id,
+                            price,
+                            size,
+                            reason,
+                            metadata,
+                        },
+                        Side::Sell => TradeSignal::Sell {
+                            token_id,
+                            price,
+                            size,
+                            reason,
+                            metadata,
+                        },
+                    };
+                    self.book_or_track(strategy_name, &leg, order_id);
+                }
+
+                // Execution shortfall for minimum edge tuning: a lone leg
+                // loses the pair's edge
+                if yes_leg.is_ok() || no_leg.is_ok() {
+                    if let Some(market_id) = self.market_data.get_market_id(yes_token) {
+                        let realized = if filled_leg.is_some() {
+                            0.0
+                        } else {
+                            *profit_per_share
+                        };
+                        self.cost_model
+                            .record_shortfall(&market_id, *profit_per_share, realized);
+                    }
+                }
+
+                // Neither leg reached the book: try again once there is room
+                let blocked = matches!(
+                    (&yes_leg, &no_leg),
+                    (Err(a), Err(b)) if a.is_capacity() && b.is_capacity()
+                );
+                if blocked {
+                    self.hold_for_retry(strategy_name, &signal, approved);
+                }
+
+                let kind = match side {
+                    Side::Buy => "ARBITRAGE",
+                    Side::Sell => "ARBITRAGE_SELL",
+                };
+                match (yes_leg, no_leg) {
                     (Ok(yes_id), Ok(no_id)) => {
                         info!(
                             "[{}] Arbitrage orders placed: YES={}, NO={}",
                             strategy_name, yes_id, no_id
                         );
-                        self.risk_manager.record_trade(&signal);
+                        self.notify_executed(strategy_name, &signal);
+                        let status = self.placed_status();
+                        self.trace_trade(
+                            strategy_name,
+                            &signal,
+                            started,
+                            vec![yes_id.clone(), no_id.clone()],
+                            status,
+                        );
+                        self.book_or_track_arbitrage(strategy_name, &signal, &yes_id, &no_id);
                         let pnl = profit_per_share * size;
                         // Publish arbitrage trade
                         self.publish_arb_trade_to_redis(
@@ -440,13 +1575,15 @@ impl StrategyEngine {
                             *no_price,
                             *size,
                             *profit_per_share,
+                            *side,
                             Some(&yes_id),
                             Some(&no_id),
-                            "FILLED",
+                            status,
+                            signal.metadata(),
                         );
                         self.notify_slack_order(
                             strategy_name,
-                            "ARBITRAGE",
+                            kind,
                             None,
                             Some(yes_token),
                             Some(no_token),
@@ -455,8 +1592,10 @@ impl StrategyEngine {
                             Some(*no_price),
                             *size,
                             None,
-                            "FILLED",
-                            Some(pnl),
+                            status,
+                            (status == "FILLED").then_some(pnl),
+                            None,
+                            signal.metadata(),
                         );
                         self.persist_arb_trade_to_db(
                             strategy_name,
@@ -466,14 +1605,18 @@ impl StrategyEngine {
                             *no_price,
                             *size,
                             *profit_per_share,
+                            *side,
                             Some(&yes_id),
                             Some(&no_id),
-                            "FILLED",
+                            status,
+                            signal.metadata(),
                         );
                     }
                     (Err(e), _) | (_, Err(e)) => {
                         warn!("[{}] Arbitrage order failed: {}", strategy_name, e);
-                        let status = format!("FAILED: {}", e);
+                        let status = format!("FAILED: {}", e.reason());
+                        self.notify_rejected(strategy_name, &signal, e.reason());
+                        self.trace_trade(strategy_name, &signal, started, Vec::new(), &status);
                         self.publish_arb_trade_to_redis(
                             strategy_name,
                             yes_token,
@@ -482,13 +1625,15 @@ impl StrategyEngine {
                             *no_price,
                             *size,
                             *profit_per_share,
+                            *side,
                             None,
                             None,
                             &status,
+                            signal.metadata(),
                         );
                         self.notify_slack_order(
                             strategy_name,
-                            "ARBITRAGE",
+                            kind,
                             None,
                             Some(yes_token),
                             Some(no_token),
@@ -499,6 +1644,8 @@ impl StrategyEngine {
                             None,
                             &status,
                             None,
+                            e.hint(),
+                            signal.metadata(),
                         );
                         self.persist_arb_trade_to_db(
                             strategy_name,
@@ -508,12 +1655,824 @@ impl StrategyEngine {
                             *no_price,
                             *size,
                             *profit_per_share,
+                            *side,
                             None,
                             None,
                             &status,
+                            signal.metadata(),
+                        );
+                    }
+                }
+            }
+            TradeSignal::Basket {
+                legs,
+                atomic,
+                reason,
+                metadata,
+                ..
+            } => {
+                let results = self.order_manager.place_batch(legs, *atomic).await;
+
+                // Every leg that reached the book is its own position change
+                let placed = self.placed_status();
+                let mut order_ids = Vec::new();
+                for (i, (leg, result)) in legs.iter().zip(&results).enumerate() {
+                    let side = match leg.side {
+                        Side::Buy => "BUY",
+                        Side::Sell => "SELL",
+                    };
+                    let leg_signal = leg.to_signal(reason, metadata.clone().with("basket_leg", i));
+                    match result {
+                        Ok(order_id) => {
+                            self.tag_order(strategy_name, &signal, order_id, &leg.token_id, side);
+                            let pnl = self.book_or_track(strategy_name, &leg_signal, order_id);
+                            self.publish_trade_to_redis(
+                                strategy_name,
+                                &leg_signal,
+                                Some(order_id),
+                                placed,
+                            );
+                            self.persist_trade_to_db(
+                                strategy_name,
+                                &leg.token_id,
+                                side,
+                                leg.price,
+                                leg.size,
+                                Some(order_id),
+                                placed,
+                                Some(reason.as_str()),
+                                pnl.filter(|_| leg.side == Side::Sell),
+                                false,
+                                leg_signal.metadata(),
+                            );
+                            order_ids.push(order_id.clone());
+                        }
+                        Err(e) => {
+                            let status = format!("FAILED: {}", e.reason());
+                            self.publish_trade_to_redis(strategy_name, &leg_signal, None, &status);
+                            self.persist_trade_to_db(
+                                strategy_name,
+                                &leg.token_id,
+                                side,
+                                leg.price,
+                                leg.size,
+                                None,
+                                &status,
+                                Some(reason.as_str()),
+                                None,
+                                false,
+                                leg_signal.metadata(),
+                            );
+                        }
+                    }
+                }
+
+                // No leg reached the book: try again once there is room
+                let blocked = results
+                    .iter()
+                    .all(|r| r.as_ref().is_err_and(|e| e.is_capacity()));
+                if blocked {
+                    self.hold_for_retry(strategy_name, &signal, approved);
+                }
+
+                let failed = results.iter().find_map(|r| r.as_ref().err());
+                let status = match failed {
+                    None => placed.to_string(),
+                    Some(e) if order_ids.is_empty() => format!("FAILED: {}", e.reason()),
+                    Some(_) => format!("PARTIAL: {}/{} legs", order_ids.len(), legs.len()),
+                };
+                match failed {
+                    None => {
+                        info!(
+                            "[{}] Basket orders placed: {}",
+                            strategy_name,
+                            order_ids.join(", ")
                         );
+                        self.notify_executed(strategy_name, &signal);
+                    }
+                    Some(e) => {
+                        warn!("[{}] Basket {}: {}", strategy_name, status, e);
+                        self.notify_rejected(strategy_name, &signal, e.reason());
                     }
                 }
+                self.notify_slack_basket(
+                    strategy_name,
+                    &signal,
+                    &results,
+                    placed,
+                    &status,
+                    failed.and_then(|e| e.hint()),
+                );
+                self.trace_trade(strategy_name, &signal, started, order_ids, &status);
+            }
+        }
+    }
+
+    /// Book a child order of a sliced order, or report the finished parent.
+    fn apply_twap_event(&self, event: TwapEvent) {
+        match event {
+            TwapEvent::Fill {
+                strategy,
+                signal,
+                order_id,
+                started,
+            } => {
+                let Some((side, token_id, price, size, reason)) = order_fields(&signal) else {
+                    return;
+                };
+                info!(
+                    "[{}] TWAP child placed: {} ({})",
+                    strategy, order_id, reason
+                );
+                let status = self.placed_status();
+                self.tag_order(strategy, &signal, &order_id, token_id, side);
+                self.trace_trade(strategy, &signal, started, vec![order_id.clone()], status);
+                let pnl = self.book_or_track(strategy, &signal, &order_id);
+                self.publish_trade_to_redis(strategy, &signal, Some(&order_id), status);
+                self.persist_trade_to_db(
+                    strategy,
+                    token_id,
+                    side,
+                    price,
+                    size,
+                    Some(&order_id),
+                    status,
+                    Some(reason),
+                    pnl.filter(|_| side == "SELL"),
+                    false,
+                    signal.metadata(),
+                );
+            }
+            TwapEvent::Done(report) => {
+                self.running_twaps.fetch_sub(1, Ordering::Relaxed);
+                self.report_twap(&report);
+            }
+        }
+    }
+
+    /// Log a finished sliced order and send one Slack alert for it.
+    fn report_twap(&self, report: &TwapReport) {
+        let side = match report.side {
+            Side::Buy => "BUY",
+            Side::Sell => "SELL",
+        };
+        info!(
+            "[TWAP] {} {} {} done ({}): {:.2}/{:.2} filled @ {:.4} avg (limit {:.4}) in {}/{} slices",
+            report.strategy,
+            side,
+            report.token_id,
+            report.outcome.as_str(),
+            report.filled,
+            report.requested,
+            report.avg_price,
+            report.limit_price,
+            report.slices_sent,
+            report.slices
+        );
+        let status = match &report.outcome {
+            TwapOutcome::Completed => "FILLED".to_string(),
+            TwapOutcome::OrderFailed(reason) if report.filled <= 0.0 => {
+                format!("FAILED: {}", reason)
+            }
+            outcome if report.filled <= 0.0 => format!("FAILED: {}", outcome.as_str()),
+            outcome => format!("PARTIAL: {}", outcome.as_str()),
+        };
+        self.notify_slack_order(
+            report.strategy,
+            &format!("TWAP {}", side),
+            Some(&report.token_id),
+            None,
+            None,
+            (report.filled > 0.0).then_some(report.avg_price),
+            None,
+            None,
+            report.filled,
+            None,
+            &status,
+            None,
+            None,
+            &report.metadata,
+        );
+    }
+
+    /// Book an order of the hedger or exit scheduler like one the engine
+    /// placed.
+    fn apply_placed_order(&self, placed: PlacedOrder) {
+        let PlacedOrder {
+            source,
+            signal,
+            order_id,
+        } = placed;
+        let Some((side, token_id, price, size, reason)) = order_fields(&signal) else {
+            return;
+        };
+        let status = self.placed_status();
+        self.tag_order(source, &signal, &order_id, token_id, side);
+        let pnl = self.book_or_track(source, &signal, &order_id);
+        self.publish_trade_to_redis(source, &signal, Some(&order_id), status);
+        self.persist_trade_to_db(
+            source,
+            token_id,
+            side,
+            price,
+            size,
+            Some(&order_id),
+            status,
+            Some(reason),
+            pnl.filter(|_| side == "SELL"),
+            false,
+            signal.metadata(),
+        );
+    }
+
+    /// Book a placed buy or sell now, returning the P&L it realized, or
+    /// with order tracking on, once its fills are confirmed (None).
+    fn book_or_track(
+        &self,
+        strategy_name: &'static str,
+        signal: &TradeSignal,
+        order_id: &str,
+    ) -> Option<f64> {
+        let (side, token_id, price, size, reason) = match signal {
+            TradeSignal::Buy {
+                token_id,
+                price,
+                size,
+                reason,
+                ..
+            } => (Side::Buy, token_id, price, size, reason),
+            TradeSignal::Sell {
+                token_id,
+                price,
+                size,
+                reason,
+                ..
+            } => (Side::Sell, token_id, price, size, reason),
+            _ => return Some(self.record_trade(strategy_name, signal)),
+        };
+        let leg = Leg {
+            token_id: token_id.clone(),
+            side,
+            price: *price,
+            size: *size,
+        };
+        let Some(tracker) = self.tracker_for_placed_orders() else {
+            return Some(self.record_trade(strategy_name, signal));
+        };
+        tracker.track(order_id, &leg.token_id, leg.side, leg.price, leg.size);
+        self.risk_manager
+            .reserve(&leg.token_id, leg.side, leg.price, leg.size);
+        self.confirmations.lock().orders.insert(
+            order_id.to_string(),
+            ConfirmingOrder {
+                strategy_name,
+                leg,
+                reason: reason.clone(),
+                metadata: signal.metadata().clone(),
+                arbitrage: None,
+            },
+        );
+        None
+    }
+
+    /// Book a placed arbitrage now, or with order tracking on, once both
+    /// legs are done: the size both filled as the arbitrage, any excess
+    /// of one leg as a single trade.
+    fn book_or_track_arbitrage(
+        &self,
+        strategy_name: &'static str,
+        signal: &TradeSignal,
+        yes_id: &str,
+        no_id: &str,
+    ) {
+        let TradeSignal::Arbitrage {
+            yes_token,
+            no_token,
+            yes_price,
+            no_price,
+            size,
+            side,
+            ..
+        } = signal
+        else {
+            return;
+        };
+        let Some(tracker) = self.tracker_for_placed_orders() else {
+            self.record_trade(strategy_name, signal);
+            return;
+        };
+        let legs = [
+            (yes_id, yes_token, *yes_price),
+            (no_id, no_token, *no_price),
+        ]
+        .map(|(order_id, token_id, price)| {
+            let leg = Leg {
+                token_id: token_id.clone(),
+                side: *side,
+                price,
+                size: *size,
+            };
+            tracker.track(order_id, token_id, *side, price, *size);
+            self.risk_manager.reserve(token_id, *side, price, *size);
+            (order_id.to_string(), leg, None)
+        });
+        let mut confirmations = self.confirmations.lock();
+        for (order_id, leg, _) in &legs {
+            confirmations.orders.insert(
+                order_id.clone(),
+                ConfirmingOrder {
+                    strategy_name,
+                    leg: leg.clone(),
+                    reason: "Arbitrage leg".to_string(),
+                    metadata: signal.metadata().clone(),
+                    arbitrage: Some(yes_id.to_string()),
+                },
+            );
+        }
+        confirmations.arbitrages.insert(
+            yes_id.to_string(),
+            ConfirmingArbitrage {
+                strategy_name,
+                signal: signal.clone(),
+                legs,
+            },
+        );
+    }
+
+    /// The order tracker, unless orders fill on placement (paper trading)
+    fn tracker_for_placed_orders(&self) -> Option<&Arc<OrderTracker>> {
+        self.order_tracker
+            .as_ref()
+            .filter(|_| !self.order_manager.fills_on_placement())
+    }
+
+    /// Status recorded for an order when it is placed: FILLED, or PLACED
+    /// when its fills are recorded once confirmed.
+    fn placed_status(&self) -> &'static str {
+        if self.tracker_for_placed_orders().is_some() {
+            "PLACED"
+        } else {
+            "FILLED"
+        }
+    }
+
+    /// Poll the status of tracked orders and book what they filled.
+    /// Orders older than the tracker's max age are cancelled and closed at
+    /// their last fill.
+    async fn poll_tracked_orders(&self, tracker: &OrderTracker) {
+        for order in tracker.open_orders() {
+            // An order whose cancel failed may still fill: keep it tracked
+            // (and reserved) and retry the cancel on the next poll
+            let mut cancelled = false;
+            if order.placed_at.elapsed() >= tracker.max_age() {
+                info!(
+                    "[ENGINE] Cancelling {} unconfirmed after {:?}",
+                    order.order_id,
+                    tracker.max_age()
+                );
+                match self.order_manager.cancel_order(&order.order_id).await {
+                    Ok(()) => cancelled = true,
+                    Err(e) => warn!(
+                        "[ENGINE] Cancel of {} failed, retrying next poll: {}",
+                        order.order_id, e
+                    ),
+                }
+            }
+            let update = match self
+                .order_manager
+                .order_fill(&order.order_id, &order.token_id, order.price, order.size)
+                .await
+            {
+                Ok(fill) => tracker.apply(
+                    &order.order_id,
+                    OrderFill {
+                        open: fill.open && !cancelled,
+                        ..fill
+                    },
+                ),
+                Err(e) if cancelled => {
+                    warn!("[ENGINE] Status check for {} failed: {}", order.order_id, e);
+                    tracker.close(&order.order_id)
+                }
+                Err(e) => {
+                    debug!("[ENGINE] Status check for {} failed: {}", order.order_id, e);
+                    None
+                }
+            };
+            if let Some(update) = update {
+                self.apply_order_update(update);
+            }
+        }
+    }
+
+    /// Book the new fill of a tracked order; arbitrage legs are booked
+    /// together once both are closed.
+    fn apply_order_update(&self, update: OrderUpdate) {
+        let order = &update.order;
+        let mut confirmations = self.confirmations.lock();
+        let entry = if order.state.is_open() {
+            confirmations.orders.get(&order.order_id).cloned()
+        } else {
+            confirmations.orders.remove(&order.order_id)
+        };
+        let Some(entry) = entry else {
+            return;
+        };
+        let Some(key) = entry.arbitrage else {
+            drop(confirmations);
+            // The new fill is booked below; a closed order's rest never fills
+            let unfilled = if order.state.is_open() {
+                0.0
+            } else {
+                order.size - order.filled
+            };
+            self.risk_manager.release(
+                &entry.leg.token_id,
+                entry.leg.side,
+                entry.leg.price,
+                update.new_fill + unfilled,
+            );
+            if update.new_fill > 0.0 {
+                let leg = Leg {
+                    size: update.new_fill,
+                    ..entry.leg
+                };
+                info!(
+                    "[{}] Fill confirmed: {} {} @ ${:.4} x {:.2} ({})",
+                    entry.strategy_name,
+                    order.order_id,
+                    order.token_id,
+                    leg.price,
+                    leg.size,
+                    order.state.as_str()
+                );
+                self.book_fill(
+                    entry.strategy_name,
+                    &leg.to_signal(&entry.reason, entry.metadata),
+                    &order.order_id,
+                );
+            }
+            return;
+        };
+        if order.state.is_open() {
+            return;
+        }
+        let Some(arbitrage) = confirmations.arbitrages.get_mut(&key) else {
+            return;
+        };
+        for (order_id, _, filled) in arbitrage.legs.iter_mut() {
+            if *order_id == order.order_id {
+                *filled = Some(order.filled);
+            }
+        }
+        if arbitrage.legs.iter().any(|(_, _, filled)| filled.is_none()) {
+            return;
+        }
+        if let Some(arbitrage) = confirmations.arbitrages.remove(&key) {
+            drop(confirmations);
+            self.book_arbitrage(arbitrage);
+        }
+    }
+
+    /// Book an arbitrage whose legs are both closed: the size both legs
+    /// filled as the arbitrage, the excess of one leg as a single trade.
+    fn book_arbitrage(&self, arbitrage: ConfirmingArbitrage) {
+        for (_, leg, _) in &arbitrage.legs {
+            self.risk_manager
+                .release(&leg.token_id, leg.side, leg.price, leg.size);
+        }
+        let filled = arbitrage
+            .legs
+            .each_ref()
+            .map(|(_, _, filled)| filled.unwrap_or(0.0));
+        let matched = filled[0].min(filled[1]);
+        info!(
+            "[{}] Arbitrage fills confirmed: YES {:.2}, NO {:.2}",
+            arbitrage.strategy_name, filled[0], filled[1]
+        );
+        let [(yes_id, ..), (no_id, ..)] = &arbitrage.legs;
+        if let TradeSignal::Arbitrage {
+            yes_token,
+            no_token,
+            yes_price,
+            no_price,
+            profit_per_share,
+            side,
+            metadata,
+            ..
+        } = &arbitrage.signal
+        {
+            if matched > 0.0 {
+                let mut signal = arbitrage.signal.clone();
+                if let TradeSignal::Arbitrage { size, .. } = &mut signal {
+                    *size = matched;
+                }
+                self.record_trade(arbitrage.strategy_name, &signal);
+                self.publish_arb_trade_to_redis(
+                    arbitrage.strategy_name,
+                    yes_token,
+                    no_token,
+                    *yes_price,
+                    *no_price,
+                    matched,
+                    *profit_per_share,
+                    *side,
+                    Some(yes_id),
+                    Some(no_id),
+                    "FILLED",
+                    metadata,
+                );
+                self.persist_arb_trade_to_db(
+                    arbitrage.strategy_name,
+                    yes_token,
+                    no_token,
+                    *yes_price,
+                    *no_price,
+                    matched,
+                    *profit_per_share,
+                    *side,
+                    Some(yes_id),
+                    Some(no_id),
+                    "FILLED",
+                    metadata,
+                );
+            }
+        }
+        for ((order_id, leg, _), filled) in arbitrage.legs.iter().zip(filled) {
+            if filled > matched {
+                let leg = Leg {
+                    size: filled - matched,
+                    ..leg.clone()
+                };
+                self.book_fill(
+                    arbitrage.strategy_name,
+                    &leg.to_signal(
+                        "Arbitrage leg (other leg short)",
+                        arbitrage.signal.metadata().clone(),
+                    ),
+                    order_id,
+                );
+            }
+        }
+    }
+
+    /// Book a confirmed fill of a tracked buy or sell and record it as
+    /// FILLED.
+    fn book_fill(&self, strategy_name: &'static str, signal: &TradeSignal, order_id: &str) {
+        let Some((side, token_id, price, size, reason)) = order_fields(signal) else {
+            return;
+        };
+        let pnl = self.record_trade(strategy_name, signal);
+        self.publish_trade_to_redis(strategy_name, signal, Some(order_id), "FILLED");
+        self.persist_trade_to_db(
+            strategy_name,
+            token_id,
+            side,
+            price,
+            size,
+            Some(order_id),
+            "FILLED",
+            Some(reason),
+            (side == "SELL").then_some(pnl),
+            false,
+            signal.metadata(),
+        );
+    }
+
+    /// Book a trade with the risk manager, attributed to the strategy that
+    /// made it. Returns the P&L it realized.
+    fn record_trade(&self, strategy_name: &str, signal: &TradeSignal) -> f64 {
+        let pnl = self.risk_manager.record_trade(signal);
+        self.risk_manager
+            .record_strategy_trade(strategy_name, signal, pnl);
+        if let Some(governor) = &self.governor {
+            governor.record_pnl(strategy_name, pnl);
+        }
+        pnl
+    }
+
+    /// Record an executed signal in the recent-trades ring.
+    fn trace_trade(
+        &self,
+        strategy_name: &'static str,
+        signal: &TradeSignal,
+        started: Instant,
+        order_ids: Vec<String>,
+        status: &str,
+    ) {
+        let Some(recent_trades) = &self.recent_trades else {
+            return;
+        };
+        recent_trades.record(TradeTrace {
+            timestamp_ms: now_ms(),
+            strategy: strategy_name,
+            signal_type: signal.kind(),
+            token_id: signal.token_id().clone(),
+            order_ids,
+            edge: signal.edge(),
+            latency_ms: started.elapsed().as_secs_f64() * 1000.0,
+            status: status.to_string(),
+        });
+    }
+
+    /// Report parked signals that were rejected or expired back to their
+    /// strategies.
+    fn drain_approvals(&self) {
+        let Some(approvals) = &self.approvals else {
+            return;
+        };
+        for dropped in approvals.take_dropped() {
+            self.stats
+                .record_outcome(dropped.strategy_name, SignalOutcome::Rejected);
+            self.notify_rejected(dropped.strategy_name, &dropped.signal, dropped.reason);
+            if let TradeSignal::Bid { token_id, .. } = &dropped.signal {
+                if let Some(strategy) = self.strategy(dropped.strategy_name) {
+                    strategy.on_bid_done(token_id, 0.0);
+                }
+            }
+        }
+    }
+
+    /// Hold a blocked signal for retry, if retries are on and its edge is
+    /// strong enough.
+    fn hold_for_retry(&self, strategy_name: &'static str, signal: &TradeSignal, approved: bool) {
+        if let Some(retries) = &self.retries {
+            retries.hold(strategy_name, signal, approved, Instant::now());
+        }
+    }
+
+    /// Send held opportunities that still have their edge and would pass
+    /// risk back through the engine.
+    async fn retry_opportunities(&self) {
+        let Some(retries) = &self.retries else {
+            return;
+        };
+        if self.order_manager.is_circuit_open()
+            || self.paused_by_network()
+            || self.leader.as_ref().is_some_and(|l| !l.is_leader())
+        {
+            return;
+        }
+        let ready = retries.take_ready(&self.market_data, Instant::now(), |signal| {
+            self.risk_manager
+                .evaluate(signal)
+                .iter()
+                .all(|check| check.passed)
+        });
+        for retry in ready {
+            info!(
+                "[RETRY] Retrying [{}] {}",
+                retry.strategy_name,
+                retry.signal.description()
+            );
+            self.handle_signal(retry.strategy_name, retry.signal, false, retry.approved)
+                .await;
+        }
+    }
+
+    /// Report a signal that reached the exchange back to its strategy.
+    fn notify_executed(&self, strategy_name: &str, signal: &TradeSignal) {
+        self.log_decision(strategy_name, signal, "executed", Vec::new());
+        if let Some(strategy) = self.strategy(strategy_name) {
+            strategy.on_signal_executed(signal);
+        }
+    }
+
+    /// Report a signal that did not execute back to its strategy.
+    fn notify_rejected(&self, strategy_name: &str, signal: &TradeSignal, reason: &str) {
+        if self.decisions.is_some() {
+            // Name the checks that failed rather than just "risk_limits"
+            let mut reasons: Vec<String> = if reason == "risk_limits" {
+                self.risk_manager
+                    .evaluate(signal)
+                    .into_iter()
+                    .filter(|c| !c.passed)
+                    .map(|c| c.name.to_string())
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            if reasons.is_empty() {
+                reasons.push(reason.to_string());
+            }
+            self.log_decision(strategy_name, signal, "rejected", reasons);
+        }
+        if let Some(strategy) = self.strategy(strategy_name) {
+            strategy.on_signal_rejected(signal, reason);
+        }
+    }
+
+    fn log_decision(
+        &self,
+        strategy_name: &str,
+        signal: &TradeSignal,
+        decision: &str,
+        reasons: Vec<String>,
+    ) {
+        if let Some(log) = &self.decisions {
+            log.record(Decision::new(strategy_name, signal, decision, reasons));
+        }
+    }
+
+    /// Look up a strategy by name.
+    fn strategy(&self, name: &str) -> Option<&dyn Strategy> {
+        self.strategies
+            .iter()
+            .find(|s| s.name() == name)
+            .map(|s| s.as_ref())
+    }
+
+    /// Check every resting bid for fills.
+    async fn poll_resting_bids(&self) {
+        let bids: Vec<RestingBid> = self.resting_bids.lock().clone();
+        for bid in bids {
+            match self
+                .order_manager
+                .order_fill(&bid.order_id, &bid.token_id, bid.price, bid.size)
+                .await
+            {
+                Ok(fill) => self.apply_bid_fill(&bid.order_id, fill),
+                Err(e) => debug!("[ENGINE] Fill check for {} failed: {}", bid.order_id, e),
+            }
+        }
+    }
+
+    /// Record any new fill on a resting bid as a trade and, once the bid is
+    /// closed, stop tracking it and tell its strategy.
+    fn apply_bid_fill(&self, order_id: &str, fill: OrderFill) {
+        let (bid, new_fill) = {
+            let mut bids = self.resting_bids.lock();
+            let Some(index) = bids.iter().position(|b| b.order_id == order_id) else {
+                return;
+            };
+            let filled = fill.filled.clamp(bids[index].filled, bids[index].size);
+            let new_fill = filled - bids[index].filled;
+            bids[index].filled = filled;
+            let bid = if fill.open {
+                bids[index].clone()
+            } else {
+                bids.swap_remove(index)
+            };
+            (bid, new_fill)
+        };
+        if let Some(funds) = &self.funds {
+            let remaining = if fill.open {
+                bid.size - bid.filled
+            } else {
+                0.0
+            };
+            funds.set_open_order(&bid.order_id, remaining * bid.price);
+        }
+
+        if new_fill > 0.0 {
+            let reason = format!("resting bid {} filled", bid.order_id);
+            info!(
+                "[{}] Bid filled: {} @ ${:.4} x {:.2}",
+                bid.strategy_name, bid.token_id, bid.price, new_fill
+            );
+            let signal = TradeSignal::Buy {
+                token_id: bid.token_id.clone(),
+                price: bid.price,
+                size: new_fill,
+                reason: reason.clone(),
+                metadata: bid.metadata.clone(),
+            };
+            self.record_trade(bid.strategy_name, &signal);
+            self.publish_trade_to_redis(bid.strategy_name, &signal, Some(&bid.order_id), "FILLED");
+            self.notify_slack_order(
+                bid.strategy_name,
+                "BUY",
+                Some(&bid.token_id),
+                None,
+                None,
+                Some(bid.price),
+                None,
+                None,
+                new_fill,
+                Some(&bid.order_id),
+                "FILLED",
+                None,
+                None,
+                &bid.metadata,
+            );
+            self.persist_trade_to_db(
+                bid.strategy_name,
+                &bid.token_id,
+                "BUY",
+                bid.price,
+                new_fill,
+                Some(&bid.order_id),
+                "FILLED",
+                Some(&reason),
+                None,
+                true,
+                &bid.metadata,
+            );
+        }
+
+        if !fill.open {
+            if let Some(strategy) = self.strategy(bid.strategy_name) {
+                strategy.on_bid_done(&bid.token_id, bid.filled);
             }
         }
     }
@@ -534,6 +2493,8 @@ impl StrategyEngine {
         order_id: Option<&str>,
         status: &str,
         pnl: Option<f64>,
+        hint: Option<&str>,
+        metadata: &SignalMetadata,
     ) {
         if let Some(ref notifier) = self.slack_notifier {
             let notification = OrderNotification {
@@ -549,81 +2510,225 @@ impl StrategyEngine {
                 order_id: order_id.map(|s| s.to_string()),
                 status: status.to_string(),
                 pnl,
+                hint: hint.map(|h| h.to_string()),
                 is_paper: self.order_manager.is_dry_run(),
+                metadata: metadata.to_json(),
+                legs: Vec::new(),
             };
             notifier.notify_order(notification);
         }
     }
 
-    /// Publish signal to Redis (fire-and-forget, non-blocking)
-    fn publish_signal_to_redis(&self, strategy_name: &str, signal: &TradeSignal) {
-        if let Some(ref publisher) = self.redis_publisher {
-            let msg = match signal {
-                TradeSignal::Buy {
-                    token_id,
-                    price,
-                    size,
-                    reason,
-                } => SignalMessage {
-                    timestamp_ms: now_ms(),
-                    strategy: strategy_name.to_string(),
-                    signal_type: "BUY".to_string(),
-                    token_id: Some(token_id.clone()),
-                    yes_token_id: None,
-                    no_token_id: None,
-                    price: Some(*price),
-                    yes_price: None,
-                    no_price: None,
-                    size: *size,
-                    edge: None,
-                    reason: reason.clone(),
-                },
-                TradeSignal::Sell {
-                    token_id,
-                    price,
-                    size,
-                    reason,
-                } => SignalMessage {
-                    timestamp_ms: now_ms(),
-                    strategy: strategy_name.to_string(),
-                    signal_type: "SELL".to_string(),
-                    token_id: Some(token_id.clone()),
-                    yes_token_id: None,
-                    no_token_id: None,
-                    price: Some(*price),
-                    yes_price: None,
-                    no_price: None,
-                    size: *size,
-                    edge: None,
-                    reason: reason.clone(),
-                },
-                TradeSignal::Arbitrage {
-                    yes_token,
-                    no_token,
-                    yes_price,
-                    no_price,
-                    profit_per_share,
-                    size,
-                } => SignalMessage {
-                    timestamp_ms: now_ms(),
-                    strategy: strategy_name.to_string(),
-                    signal_type: "ARBITRAGE".to_string(),
-                    token_id: None,
-                    yes_token_id: Some(yes_token.clone()),
-                    no_token_id: Some(no_token.clone()),
-                    price: None,
-                    yes_price: Some(*yes_price),
-                    no_price: Some(*no_price),
-                    size: *size,
-                    edge: Some(*profit_per_share),
-                    reason: format!(
-                        "Arbitrage: YES@{:.4} + NO@{:.4} = {:.4} profit",
-                        yes_price, no_price, profit_per_share
-                    ),
+    /// Send one Slack notification for a basket, listing each leg's outcome:
+    /// `placed` for legs that reached the book (fire-and-forget)
+    fn notify_slack_basket(
+        &self,
+        strategy: &str,
+        signal: &TradeSignal,
+        results: &[ExecutionResult<String>],
+        placed: &str,
+        status: &str,
+        hint: Option<&str>,
+    ) {
+        let (Some(notifier), TradeSignal::Basket { legs, .. }) = (&self.slack_notifier, signal)
+        else {
+            return;
+        };
+        let legs = legs
+            .iter()
+            .zip(results)
+            .map(|(leg, result)| LegNotification {
+                token_id: leg.token_id.clone(),
+                side: match leg.side {
+                    Side::Buy => "BUY",
+                    Side::Sell => "SELL",
+                }
+                .to_string(),
+                price: leg.price,
+                size: leg.size,
+                order_id: result.as_ref().ok().cloned(),
+                status: match result {
+                    Ok(_) => placed.to_string(),
+                    Err(e) => format!("FAILED: {}", e.reason()),
                 },
-            };
+            })
+            .collect();
+        notifier.notify_order(OrderNotification {
+            strategy: strategy.to_string(),
+            order_type: "BASKET".to_string(),
+            token_id: None,
+            yes_token: None,
+            no_token: None,
+            price: None,
+            yes_price: None,
+            no_price: None,
+            size: signal.size(),
+            order_id: None,
+            status: status.to_string(),
+            pnl: None,
+            hint: hint.map(|h| h.to_string()),
+            is_paper: self.order_manager.is_dry_run(),
+            metadata: signal.metadata().to_json(),
+            legs,
+        });
+    }
+
+    /// Signal message for Redis and the signals table
+    fn signal_message(&self, strategy_name: &str, signal: &TradeSignal) -> SignalMessage {
+        match signal {
+            TradeSignal::Buy {
+                token_id,
+                price,
+                size,
+                reason,
+                ..
+            } => SignalMessage {
+                timestamp_ms: now_ms(),
+                strategy: strategy_name.to_string(),
+                signal_type: "BUY".to_string(),
+                token_id: Some(token_id.clone()),
+                yes_token_id: None,
+                no_token_id: None,
+                price: Some(*price),
+                yes_price: None,
+                no_price: None,
+                size: *size,
+                edge: None,
+                legs: None,
+                reason: reason.clone(),
+                mode: self.signal_mode().to_string(),
+                context: None,
+                metadata: signal.metadata().to_json(),
+            },
+            TradeSignal::Sell {
+                token_id,
+                price,
+                size,
+                reason,
+                ..
+            } => SignalMessage {
+                timestamp_ms: now_ms(),
+                strategy: strategy_name.to_string(),
+                signal_type: "SELL".to_string(),
+                token_id: Some(token_id.clone()),
+                yes_token_id: None,
+                no_token_id: None,
+                price: Some(*price),
+                yes_price: None,
+                no_price: None,
+                size: *size,
+                edge: None,
+                legs: None,
+                reason: reason.clone(),
+                mode: self.signal_mode().to_string(),
+                context: None,
+                metadata: signal.metadata().to_json(),
+            },
+            TradeSignal::Arbitrage {
+                yes_token,
+                no_token,
+                yes_price,
+                no_price,
+                profit_per_share,
+                size,
+                side,
+                ..
+            } => SignalMessage {
+                timestamp_ms: now_ms(),
+                strategy: strategy_name.to_string(),
+                signal_type: match side {
+                    Side::Buy => "ARBITRAGE",
+                    Side::Sell => "ARBITRAGE_SELL",
+                }
+                .to_string(),
+                token_id: None,
+                yes_token_id: Some(yes_token.clone()),
+                no_token_id: Some(no_token.clone()),
+                price: None,
+                yes_price: Some(*yes_price),
+                no_price: Some(*no_price),
+                size: *size,
+                edge: Some(*profit_per_share),
+                legs: None,
+                reason: format!(
+                    "Arbitrage: YES@{:.4} + NO@{:.4} = {:.4} profit",
+                    yes_price, no_price, profit_per_share
+                ),
+                mode: self.signal_mode().to_string(),
+                context: None,
+                metadata: signal.metadata().to_json(),
+            },
+            TradeSignal::Bid {
+                token_id,
+                price,
+                size,
+                reason,
+                ..
+            } => SignalMessage {
+                timestamp_ms: now_ms(),
+                strategy: strategy_name.to_string(),
+                signal_type: "BID".to_string(),
+                token_id: Some(token_id.clone()),
+                yes_token_id: None,
+                no_token_id: None,
+                price: Some(*price),
+                yes_price: None,
+                no_price: None,
+                size: *size,
+                edge: None,
+                legs: None,
+                reason: reason.clone(),
+                mode: self.signal_mode().to_string(),
+                context: None,
+                metadata: signal.metadata().to_json(),
+            },
+            TradeSignal::Cancel { token_id, reason } => SignalMessage {
+                timestamp_ms: now_ms(),
+                strategy: strategy_name.to_string(),
+                signal_type: "CANCEL".to_string(),
+                token_id: Some(token_id.clone()),
+                yes_token_id: None,
+                no_token_id: None,
+                price: None,
+                yes_price: None,
+                no_price: None,
+                size: 0.0,
+                edge: None,
+                legs: None,
+                reason: reason.clone(),
+                mode: self.signal_mode().to_string(),
+                context: None,
+                metadata: signal.metadata().to_json(),
+            },
+            TradeSignal::Basket { legs, reason, .. } => SignalMessage {
+                timestamp_ms: now_ms(),
+                strategy: strategy_name.to_string(),
+                signal_type: "BASKET".to_string(),
+                token_id: None,
+                yes_token_id: None,
+                no_token_id: None,
+                price: None,
+                yes_price: None,
+                no_price: None,
+                size: signal.size(),
+                edge: signal.edge(),
+                legs: serde_json::to_value(legs).ok(),
+                reason: reason.clone(),
+                mode: self.signal_mode().to_string(),
+                context: None,
+                metadata: signal.metadata().to_json(),
+            },
+        }
+    }
+
+    /// Publish signal to Redis and the analytics sink (fire-and-forget, non-blocking)
+    fn publish_signal(&self, msg: SignalMessage) {
+        if let Some(ref sink) = self.analytics {
+            sink.record_signal(&msg);
+        }
+        if let Some(ref publisher) = self.redis_publisher {
             let pub_clone = Arc::clone(publisher);
-            tokio::spawn(async move {
+            runtime::spawn(TaskGroup::RedisPublish, async move {
                 let _ = pub_clone.publish_signal(&msg).await;
             });
         }
@@ -661,6 +2766,7 @@ impl StrategyEngine {
                     status: status.to_string(),
                     pnl: None,
                     is_paper: self.order_manager.is_dry_run(),
+                    metadata: signal.metadata().to_json(),
                 },
                 TradeSignal::Sell {
                     token_id,
@@ -684,11 +2790,12 @@ impl StrategyEngine {
                     status: status.to_string(),
                     pnl: None,
                     is_paper: self.order_manager.is_dry_run(),
+                    metadata: signal.metadata().to_json(),
                 },
                 _ => return, // Arbitrage handled separately
             };
             let pub_clone = Arc::clone(publisher);
-            tokio::spawn(async move {
+            runtime::spawn(TaskGroup::RedisPublish, async move {
                 let _ = pub_clone.publish_trade(&msg).await;
             });
         }
@@ -705,9 +2812,11 @@ impl StrategyEngine {
         no_price: f64,
         size: f64,
         edge: f64,
+        side: Side,
         yes_order_id: Option<&str>,
         no_order_id: Option<&str>,
         status: &str,
+        metadata: &SignalMetadata,
     ) {
         if let Some(ref publisher) = self.redis_publisher {
             let pnl = if status.starts_with("FILLED") {
@@ -718,7 +2827,11 @@ impl StrategyEngine {
             let msg = TradeMessage {
                 timestamp_ms: now_ms(),
                 strategy: strategy_name.to_string(),
-                trade_type: "ARBITRAGE".to_string(),
+                trade_type: match side {
+                    Side::Buy => "ARBITRAGE",
+                    Side::Sell => "ARBITRAGE_SELL",
+                }
+                .to_string(),
                 token_id: None,
                 yes_token_id: Some(yes_token.to_string()),
                 no_token_id: Some(no_token.to_string()),
@@ -732,15 +2845,30 @@ impl StrategyEngine {
                 status: status.to_string(),
                 pnl,
                 is_paper: self.order_manager.is_dry_run(),
+                metadata: metadata.to_json(),
             };
             let pub_clone = Arc::clone(publisher);
-            tokio::spawn(async move {
+            runtime::spawn(TaskGroup::RedisPublish, async move {
                 let _ = pub_clone.publish_trade(&msg).await;
             });
         }
     }
 
-    /// Persist trade to database (fire-and-forget, non-blocking)
+    /// Count a fill toward its market's monthly volume.
+    fn record_volume(&self, token_id: &str, notional: f64, maker: bool) {
+        if let Some(volume) = &self.volume {
+            let token_id = token_id.to_string();
+            let market_id = self
+                .market_data
+                .get_market_id(&token_id)
+                .unwrap_or(token_id);
+            volume.record(&market_id, notional, maker);
+        }
+    }
+
+    /// Persist trade to database (fire-and-forget, non-blocking).
+    /// `maker` marks resting-bid fills, which the fee estimate prices as maker
+    /// and the volume tracker counts as maker volume.
     #[allow(clippy::too_many_arguments)]
     fn persist_trade_to_db(
         &self,
@@ -752,9 +2880,21 @@ impl StrategyEngine {
         order_id: Option<&str>,
         status: &str,
         reason: Option<&str>,
+        realized_pnl: Option<f64>,
+        maker: bool,
+        metadata: &SignalMetadata,
     ) {
+        if status == "FILLED" {
+            self.record_volume(token_id, price * size, maker);
+        }
         if let Some(ref repo) = self.trade_repo {
+            let market_id = self.market_data.get_market_id(&token_id.to_string());
+            let category = market_id
+                .as_ref()
+                .and_then(|id| self.market_data.get_pair(id))
+                .and_then(|pair| pair.category);
             let trade = Trade {
+                client_trade_id: new_client_trade_id(),
                 token_id: token_id.to_string(),
                 side: side.to_string(),
                 price,
@@ -764,12 +2904,41 @@ impl StrategyEngine {
                 strategy: strategy_name.to_string(),
                 signal_reason: reason.map(|s| s.to_string()),
                 is_paper: self.order_manager.is_dry_run(),
+                market_id,
+                category,
+                realized_pnl,
+                estimated_fee: (status == "FILLED")
+                    .then(|| self.cost_model.fee(price * size, maker)),
+                metadata: metadata.to_json(),
             };
             repo.insert_trade(trade);
         }
     }
 
-    /// Persist arbitrage trade to database (fire-and-forget, non-blocking)
+    /// Record which strategy and signal placed an order, so its fills in the
+    /// exchange history can be attributed back (fire-and-forget).
+    fn tag_order(
+        &self,
+        strategy_name: &str,
+        signal: &TradeSignal,
+        order_id: &str,
+        token_id: &str,
+        side: &str,
+    ) {
+        if let Some(ref repo) = self.trade_repo {
+            repo.insert_order_tag(OrderTag {
+                order_id: order_id.to_string(),
+                strategy: strategy_name.to_string(),
+                signal_id: signal.signal_id().map(|id| id.to_string()),
+                token_id: token_id.to_string(),
+                side: side.to_string(),
+                is_paper: self.order_manager.is_dry_run(),
+            });
+        }
+    }
+
+    /// Persist arbitrage trade to database (fire-and-forget, non-blocking).
+    /// Filled legs count as taker volume.
     #[allow(clippy::too_many_arguments)]
     fn persist_arb_trade_to_db(
         &self,
@@ -780,39 +2949,355 @@ impl StrategyEngine {
         no_price: f64,
         size: f64,
         _profit_per_share: f64,
+        side: Side,
         yes_order_id: Option<&str>,
         no_order_id: Option<&str>,
         status: &str,
+        metadata: &SignalMetadata,
     ) {
+        if status == "FILLED" {
+            self.record_volume(yes_token, yes_price * size, false);
+            self.record_volume(no_token, no_price * size, false);
+        }
         if let Some(ref repo) = self.trade_repo {
-            let total_cost = (yes_price + no_price) * size;
-            let fee_rate = 0.01; // 1% total fees
-            let fees = total_cost * fee_rate;
-            let gross_profit = (1.0 - yes_price - no_price) * size;
-            let net_profit = gross_profit - fees;
+            let costs = match side {
+                Side::Buy => self.cost_model.arbitrage(yes_price, no_price, size),
+                Side::Sell => self.cost_model.arbitrage_sell(yes_price, no_price, size),
+            };
 
+            let pair = self
+                .market_data
+                .get_market_id(&yes_token.to_string())
+                .and_then(|id| self.market_data.get_pair(&id));
             let trade = ArbTrade {
-                market_id: format!(
-                    "{}:{}",
-                    &yes_token[..8.min(yes_token.len())],
-                    &no_token[..8.min(no_token.len())]
+                client_trade_id: new_client_trade_id(),
+                market_id: pair.as_ref().map_or_else(
+                    || {
+                        format!(
+                            "{}:{}",
+                            &yes_token[..8.min(yes_token.len())],
+                            &no_token[..8.min(no_token.len())]
+                        )
+                    },
+                    |pair| pair.market_id.clone(),
                 ),
                 yes_token_id: yes_token.to_string(),
                 no_token_id: no_token.to_string(),
                 yes_price,
                 no_price,
                 size,
-                total_cost,
-                fees,
-                gross_profit,
-                net_profit,
+                total_cost: costs.notional,
+                // Slippage is stored with fees so that gross - fees = net
+                fees: costs.fees + costs.slippage,
+                gross_profit: costs.gross_edge.unwrap_or_default(),
+                net_profit: costs.net_edge.unwrap_or_default(),
                 yes_order_id: yes_order_id.map(|s| s.to_string()),
                 no_order_id: no_order_id.map(|s| s.to_string()),
                 status: status.to_string(),
                 strategy: strategy_name.to_string(),
                 is_paper: self.order_manager.is_dry_run(),
+                category: pair.and_then(|pair| pair.category),
+                estimated_fee: costs.fees,
+                metadata: metadata.to_json(),
             };
             repo.insert_arb_trade(trade);
         }
     }
 }
+
+/// How an observed signal would have fared: `OBSERVED` if it would have been
+/// sent, otherwise the rejection and why.
+fn observed_outcome(
+    meets_order_rules: bool,
+    checks: &[RiskCheck],
+) -> (&'static str, Option<String>) {
+    if !meets_order_rules {
+        return (
+            "REJECTED_SIZE",
+            Some("size below market minimum".to_string()),
+        );
+    }
+    match checks.iter().find(|c| !c.passed) {
+        Some(check) => (
+            "REJECTED_RISK",
+            Some(
+                check
+                    .detail
+                    .clone()
+                    .unwrap_or_else(|| check.name.to_string()),
+            ),
+        ),
+        None => ("OBSERVED", None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{
+        ClipperConfig, Config, CostConfig, OrderGuardConfig, OrderQueueConfig, RiskConfig,
+        SniperConfig, SumTo100Config,
+    };
+    use crate::execution::{
+        MockExchange, MockExchangeConfig, OrderTrackingConfig, TwapConfig, TwapExecutor,
+    };
+    use crate::market::DepthLevel;
+
+    /// Keeps single-leg trades in memory
+    #[derive(Default)]
+    struct MemoryStore {
+        trades: Mutex<Vec<Trade>>,
+    }
+
+    impl TradeStore for MemoryStore {
+        fn is_enabled(&self) -> bool {
+            true
+        }
+
+        fn insert_trade(&self, trade: Trade) {
+            self.trades.lock().push(trade);
+        }
+
+        fn insert_arb_trade(&self, _trade: ArbTrade) {}
+    }
+
+    /// Engine placing dry-run orders on a mock exchange (bid 0.40, ask
+    /// 0.45), with a position limit of 15 shares.
+    async fn mock_engine() -> StrategyEngine {
+        let config = Config {
+            ws_url: "wss://test.com".into(),
+            clob_url: "https://test.com".into(),
+            private_key: "0x1234".into(),
+            api_key: "test-key".into(),
+            api_secret: "test-secret".into(),
+            dry_run: true,
+            observe: false,
+            risk: RiskConfig {
+                max_position: 15.0,
+                ..RiskConfig::default()
+            },
+            sniper: SniperConfig::default(),
+            clipper: ClipperConfig::default(),
+            sum_to_100: SumTo100Config::default(),
+            sum_to_100_instances: Default::default(),
+            order_guard: OrderGuardConfig::default(),
+            order_queue: OrderQueueConfig::default(),
+            cost: CostConfig::default(),
+            strategies: None,
+            instance_id: "default".into(),
+            redis_prefix: "poly".into(),
+            trading_timezone: "UTC".into(),
+        };
+        let market_data = Arc::new(MarketData::new());
+        market_data.update_order_book(
+            &"token1".to_string(),
+            vec![DepthLevel::new(0.40, 100.0)],
+            vec![DepthLevel::new(0.45, 100.0)],
+        );
+        let mut order_manager = OrderManager::new(config.clone(), Some(market_data.clone()))
+            .await
+            .unwrap();
+        order_manager.set_mock_exchange(Arc::new(MockExchange::new(
+            MockExchangeConfig::default(),
+            market_data.clone(),
+        )));
+        StrategyEngine::new(
+            market_data,
+            Arc::new(RiskManager::new(config.risk)),
+            Arc::new(order_manager),
+        )
+    }
+
+    /// `mock_engine` with orders tracked until filled.
+    async fn tracking_engine(max_age: Duration) -> (StrategyEngine, Arc<OrderTracker>) {
+        let mut engine = mock_engine().await;
+        let tracker = Arc::new(OrderTracker::new(OrderTrackingConfig {
+            enabled: true,
+            max_age,
+            ..OrderTrackingConfig::default()
+        }));
+        engine.set_order_tracker(tracker.clone());
+        (engine, tracker)
+    }
+
+    /// A buy below the ask, which rests unfilled on the mock exchange
+    fn resting_buy() -> TradeSignal {
+        TradeSignal::Buy {
+            token_id: "token1".to_string(),
+            price: 0.41,
+            size: 10.0,
+            reason: "test".to_string(),
+            metadata: SignalMetadata::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unconfirmed_order_counts_against_position_limit() {
+        let (engine, tracker) = tracking_engine(Duration::from_secs(60)).await;
+
+        engine.handle_signal("Test", resting_buy(), false, false).await;
+        assert_eq!(tracker.len(), 1);
+        assert!(engine.risk_manager.get_position(&"token1".to_string()).is_none());
+
+        // Still unconfirmed: the same opportunity again would exceed 15 shares
+        assert!(!engine.risk_manager.check_signal(&resting_buy()));
+        engine.handle_signal("Test", resting_buy(), false, false).await;
+        assert_eq!(tracker.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_expired_order_is_cancelled_and_released() {
+        let (engine, tracker) = tracking_engine(Duration::ZERO).await;
+
+        engine.handle_signal("Test", resting_buy(), false, false).await;
+        let order_id = tracker.open_orders()[0].order_id.clone();
+        engine.poll_tracked_orders(&tracker).await;
+
+        assert_eq!(tracker.len(), 0);
+        assert!(engine.confirmations.lock().orders.is_empty());
+        let fill = engine
+            .order_manager
+            .order_fill(&order_id, &"token1".to_string(), 0.41, 10.0)
+            .await
+            .unwrap();
+        assert!(!fill.open);
+        assert!(engine.risk_manager.check_signal(&resting_buy()));
+    }
+
+    #[tokio::test]
+    async fn test_failed_cancel_keeps_order_reserved() {
+        let (engine, tracker) = tracking_engine(Duration::ZERO).await;
+
+        // Unknown to the exchange: both the cancel and the status check fail
+        engine.book_or_track("Test", &resting_buy(), "mock-999");
+        engine.poll_tracked_orders(&tracker).await;
+
+        assert_eq!(tracker.len(), 1);
+        assert!(engine.confirmations.lock().orders.contains_key("mock-999"));
+        assert!(!engine.risk_manager.check_signal(&resting_buy()));
+    }
+
+    #[tokio::test]
+    async fn test_tracked_order_is_recorded_filled_once_confirmed() {
+        let (mut engine, tracker) = tracking_engine(Duration::from_secs(60)).await;
+        let store = Arc::new(MemoryStore::default());
+        engine.set_trade_repo(store.clone());
+
+        // At the ask: fills on arrival, but is only booked once polled
+        let buy = TradeSignal::Buy {
+            token_id: "token1".to_string(),
+            price: 0.45,
+            size: 10.0,
+            reason: "test".to_string(),
+            metadata: SignalMetadata::default(),
+        };
+        engine.handle_signal("Test", buy, false, false).await;
+        {
+            let trades = store.trades.lock();
+            assert_eq!(trades.len(), 1);
+            assert_eq!(trades[0].status, "PLACED");
+            assert!(trades[0].estimated_fee.is_none());
+        }
+
+        engine.poll_tracked_orders(&tracker).await;
+        assert_eq!(tracker.len(), 0);
+        let position = engine.risk_manager.get_position(&"token1".to_string());
+        assert_eq!(position.unwrap().size, 10.0);
+        let trades = store.trades.lock();
+        assert_eq!(trades.len(), 2);
+        assert_eq!(trades[1].status, "FILLED");
+        assert_eq!(trades[1].size, 10.0);
+        assert_eq!(trades[1].order_id, trades[0].order_id);
+    }
+
+    #[tokio::test]
+    async fn test_stopping_engine_tracks_last_placed_order() {
+        let (mut engine, tracker) = tracking_engine(Duration::from_secs(60)).await;
+
+        // A hedge placed as the engine stops, sent before the hedger returns
+        let placed_orders = engine.placed_orders();
+        placed_orders
+            .send(PlacedOrder {
+                source: "Hedge",
+                signal: resting_buy(),
+                order_id: "mock-999".to_string(),
+            })
+            .unwrap();
+        drop(placed_orders);
+        engine.finish_placed_orders().await;
+
+        assert_eq!(tracker.len(), 1);
+        assert!(engine.risk_manager.get_position(&"token1".to_string()).is_none());
+        assert!(!engine.risk_manager.check_signal(&resting_buy()));
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_reports_positions() {
+        let (mut engine, _) = tracking_engine(Duration::from_secs(60)).await;
+        engine.risk_manager.record_trade(&resting_buy());
+
+        engine.heartbeat(Duration::ZERO);
+        let state = engine.status_board.latest().unwrap();
+        assert_eq!(state.positions.len(), 1);
+        assert_eq!(state.positions[0].token_id, "token1");
+        assert_eq!(state.positions[0].size, 10.0);
+        // Bought at 0.41, marked at the 0.425 mid
+        assert!((state.positions[0].unrealized_pnl - 0.15).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_stopping_engine_books_last_twap_child() {
+        let mut engine = mock_engine().await;
+        let cancel = CancellationToken::new();
+        engine.set_cancellation_token(cancel.clone());
+        engine.set_twap_executor(Arc::new(TwapExecutor::new(
+            TwapConfig {
+                min_notional: 1.0,
+                slices: 2,
+                duration: Duration::from_secs(60),
+            },
+            engine.order_manager.clone(),
+            engine.market_data.clone(),
+            engine.risk_manager.clone(),
+            cancel.clone(),
+        )));
+
+        let buy = TradeSignal::Buy {
+            token_id: "token1".to_string(),
+            price: 0.45,
+            size: 10.0,
+            reason: "test".to_string(),
+            metadata: SignalMetadata::default(),
+        };
+        engine.handle_signal("Test", buy, false, false).await;
+        // The first child is placed before the engine loop sees its fill
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        cancel.cancel();
+        engine.run().await;
+
+        let position = engine.risk_manager.get_position(&"token1".to_string());
+        assert_eq!(position.map(|p| p.size), Some(5.0));
+        assert_eq!(engine.running_twaps.load(Ordering::Relaxed), 0);
+    }
+
+    fn check(name: &'static str, passed: bool) -> RiskCheck {
+        RiskCheck {
+            name,
+            passed,
+            detail: (!passed).then(|| format!("{} exceeded", name)),
+        }
+    }
+
+    #[test]
+    fn test_observed_outcome() {
+        assert_eq!(observed_outcome(true, &[]), ("OBSERVED", None));
+        assert_eq!(
+            observed_outcome(
+                true,
+                &[check("daily_trades", true), check("position_limit", false)]
+            ),
+            ("REJECTED_RISK", Some("position_limit exceeded".to_string()))
+        );
+        assert_eq!(observed_outcome(false, &[]).0, "REJECTED_SIZE");
+    }
+}
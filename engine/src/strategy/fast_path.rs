@@ -0,0 +1,73 @@
+//! Fast path for event-driven signals.
+//!
+//! Strategies that react to external events (a game going final) cannot
+//! afford to wait for the next evaluation tick and then for every other
+//! strategy's orders. They push signals through a `FastPath` instead: the
+//! engine picks them up as soon as they arrive, ahead of the batch, and runs
+//! them through the same order rules, risk checks and execution.
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::market::{MarketData, MarketSnapshot};
+
+use super::TradeSignal;
+
+/// Price ticks per token in fast-path snapshots (events need the book, not history)
+const FAST_PATH_HISTORY_TICKS: usize = 0;
+
+/// A signal pushed through the fast path, awaiting the engine
+pub(super) struct FastSignal {
+    pub strategy_name: &'static str,
+    pub signal: TradeSignal,
+    pub contested: bool,
+    pub sent_at: Instant,
+    /// Released from the approval queue; not parked again
+    pub approved: bool,
+}
+
+/// Handle a strategy uses to submit signals outside the evaluation tick.
+#[derive(Clone)]
+pub struct FastPath {
+    strategy_name: &'static str,
+    contested: bool,
+    market_data: Arc<MarketData>,
+    tx: UnboundedSender<FastSignal>,
+}
+
+impl FastPath {
+    pub(super) fn new(
+        strategy_name: &'static str,
+        contested: bool,
+        market_data: Arc<MarketData>,
+        tx: UnboundedSender<FastSignal>,
+    ) -> Self {
+        Self {
+            strategy_name,
+            contested,
+            market_data,
+            tx,
+        }
+    }
+
+    /// Current market state, for pricing a signal at the time of the event.
+    pub fn snapshot(&self) -> MarketSnapshot {
+        self.market_data.snapshot(FAST_PATH_HISTORY_TICKS)
+    }
+
+    /// Queue a signal for immediate handling. Returns false once the engine
+    /// is gone.
+    pub fn send(&self, signal: TradeSignal) -> bool {
+        self.tx
+            .send(FastSignal {
+                strategy_name: self.strategy_name,
+                signal,
+                contested: self.contested,
+                sent_at: Instant::now(),
+                approved: false,
+            })
+            .is_ok()
+    }
+}
@@ -0,0 +1,420 @@
+//! Declarative kill criteria per strategy.
+//!
+//! Rules come from `STRATEGY_KILL_RULES`, separated by `;`, each naming a
+//! strategy (case-insensitive) and its limits:
+//!
+//! `Sniper: max_consecutive_losses=5, max_daily_loss=50, max_reject_rate=0.8, action=shadow`
+//!
+//! - `max_consecutive_losses`: losing closes in a row (a winning close resets)
+//! - `max_daily_loss`: realized loss today, in USD
+//! - `max_reject_rate`: share of signals rejected, once the strategy has at
+//!   least `STRATEGY_KILL_MIN_SIGNALS` outcomes
+//! - `action`: `alert` (notify only), `shadow` (observe signals instead of
+//!   executing them) or `disable` (stop evaluating); default `disable`
+//!
+//! The engine checks the rules every heartbeat. A tripped rule is logged,
+//! audited and sent to Slack once, and its action holds until restart.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use tracing::warn;
+
+use crate::audit::{AuditAction, AuditLog};
+use crate::metrics::STRATEGY_KILLS;
+use crate::notifications::{ErrorAlert, SlackNotifier};
+
+/// What a tripped rule does to its strategy, weakest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum KillAction {
+    /// Notify only
+    Alert,
+    /// Observe signals instead of executing them
+    Shadow,
+    /// Stop evaluating the strategy
+    Disable,
+}
+
+impl KillAction {
+    fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "alert" => Some(Self::Alert),
+            "shadow" => Some(Self::Shadow),
+            "disable" => Some(Self::Disable),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Alert => "alert",
+            Self::Shadow => "shadow",
+            Self::Disable => "disable",
+        }
+    }
+}
+
+/// Limits on one strategy
+#[derive(Debug, Clone, PartialEq)]
+pub struct KillRule {
+    pub strategy: String,
+    pub max_consecutive_losses: Option<u32>,
+    pub max_daily_loss: Option<f64>,
+    pub max_reject_rate: Option<f64>,
+    pub action: KillAction,
+}
+
+impl KillRule {
+    /// Parse one `strategy: key=value, ...` entry.
+    pub fn parse(entry: &str) -> Option<Self> {
+        let (strategy, limits) = entry.split_once(':')?;
+        let strategy = strategy.trim();
+        if strategy.is_empty() {
+            return None;
+        }
+        let mut rule = Self {
+            strategy: strategy.to_string(),
+            max_consecutive_losses: None,
+            max_daily_loss: None,
+            max_reject_rate: None,
+            action: KillAction::Disable,
+        };
+        for limit in limits.split(',').map(str::trim).filter(|l| !l.is_empty()) {
+            let (key, value) = limit.split_once('=')?;
+            let value = value.trim();
+            match key.trim() {
+                "max_consecutive_losses" => {
+                    rule.max_consecutive_losses = Some(value.parse().ok().filter(|n| *n > 0)?)
+                }
+                "max_daily_loss" => {
+                    rule.max_daily_loss = Some(value.parse().ok().filter(|usd: &f64| *usd > 0.0)?)
+                }
+                "max_reject_rate" => {
+                    rule.max_reject_rate = Some(
+                        value
+                            .parse()
+                            .ok()
+                            .filter(|r: &f64| (0.0..=1.0).contains(r))?,
+                    )
+                }
+                "action" => rule.action = KillAction::parse(value)?,
+                _ => return None,
+            }
+        }
+        let has_limit = rule.max_consecutive_losses.is_some()
+            || rule.max_daily_loss.is_some()
+            || rule.max_reject_rate.is_some();
+        has_limit.then_some(rule)
+    }
+
+    fn applies_to(&self, strategy: &str) -> bool {
+        self.strategy.eq_ignore_ascii_case(strategy)
+    }
+
+    /// The first limit `health` breaks, described
+    fn breach(&self, health: &StrategyHealth, min_signals: u64) -> Option<String> {
+        if let Some(max) = self.max_consecutive_losses {
+            if health.consecutive_losses >= max {
+                return Some(format!(
+                    "{} consecutive losses (max {})",
+                    health.consecutive_losses, max
+                ));
+            }
+        }
+        if let Some(max) = self.max_daily_loss {
+            if -health.daily_pnl >= max {
+                return Some(format!(
+                    "daily loss ${:.2} (max ${:.2})",
+                    -health.daily_pnl, max
+                ));
+            }
+        }
+        if let Some(max) = self.max_reject_rate {
+            let outcomes = health.executed + health.rejected;
+            if outcomes >= min_signals.max(1) {
+                let rate = health.rejected as f64 / outcomes as f64;
+                if rate >= max {
+                    return Some(format!(
+                        "reject rate {:.0}% of {} signals (max {:.0}%)",
+                        rate * 100.0,
+                        outcomes,
+                        max * 100.0
+                    ));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Kill rule settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct GovernorConfig {
+    pub rules: Vec<KillRule>,
+    /// Signal outcomes needed before the reject rate is judged
+    pub min_signals: u64,
+}
+
+impl Default for GovernorConfig {
+    fn default() -> Self {
+        Self {
+            rules: Vec::new(),
+            min_signals: 20,
+        }
+    }
+}
+
+impl GovernorConfig {
+    /// Load from `STRATEGY_KILL_RULES` and `STRATEGY_KILL_MIN_SIGNALS`.
+    /// Invalid rules are skipped with a warning.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok();
+        Self {
+            rules: var("STRATEGY_KILL_RULES")
+                .map(|v| parse_rules(&v))
+                .unwrap_or_default(),
+            min_signals: var("STRATEGY_KILL_MIN_SIGNALS")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.min_signals),
+        }
+    }
+}
+
+fn parse_rules(value: &str) -> Vec<KillRule> {
+    value
+        .split(';')
+        .map(str::trim)
+        .filter(|e| !e.is_empty())
+        .filter_map(|entry| {
+            let rule = KillRule::parse(entry);
+            if rule.is_none() {
+                warn!("STRATEGY_KILL_RULES entry '{}' is invalid, skipping", entry);
+            }
+            rule
+        })
+        .collect()
+}
+
+/// What the rules are checked against for one strategy
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct StrategyHealth {
+    pub consecutive_losses: u32,
+    /// Realized P&L today
+    pub daily_pnl: f64,
+    /// Signals executed and rejected since startup
+    pub executed: u64,
+    pub rejected: u64,
+}
+
+/// Applies kill rules to strategies
+pub struct StrategyGovernor {
+    config: GovernorConfig,
+    /// Losing closes in a row per strategy
+    losing_streaks: Mutex<HashMap<String, u32>>,
+    /// Indexes of rules that have tripped
+    tripped: Mutex<HashSet<usize>>,
+    /// Strongest action taken per strategy
+    actions: Mutex<HashMap<String, KillAction>>,
+    slack: Option<Arc<SlackNotifier>>,
+    audit: Option<Arc<AuditLog>>,
+}
+
+impl StrategyGovernor {
+    pub fn new(config: GovernorConfig) -> Self {
+        Self {
+            config,
+            losing_streaks: Mutex::new(HashMap::new()),
+            tripped: Mutex::new(HashSet::new()),
+            actions: Mutex::new(HashMap::new()),
+            slack: None,
+            audit: None,
+        }
+    }
+
+    /// Set the Slack notifier used when a rule trips.
+    pub fn set_slack_notifier(&mut self, notifier: Arc<SlackNotifier>) {
+        self.slack = Some(notifier);
+    }
+
+    /// Set the audit log used to record tripped rules.
+    pub fn set_audit_log(&mut self, audit: Arc<AuditLog>) {
+        self.audit = Some(audit);
+    }
+
+    pub fn rules(&self) -> &[KillRule] {
+        &self.config.rules
+    }
+
+    /// Count a trade's realized P&L toward the strategy's losing streak
+    /// (trades that realize nothing, like opening buys, are ignored).
+    pub fn record_pnl(&self, strategy: &str, pnl: f64) {
+        let mut streaks = self.losing_streaks.lock();
+        let streak = streaks.entry(strategy.to_string()).or_default();
+        if pnl < 0.0 {
+            *streak += 1;
+        } else if pnl > 0.0 {
+            *streak = 0;
+        }
+    }
+
+    /// Losing closes in a row for a strategy
+    pub fn losing_streak(&self, strategy: &str) -> u32 {
+        self.losing_streaks
+            .lock()
+            .get(strategy)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Strongest action taken against a strategy so far
+    pub fn action(&self, strategy: &str) -> Option<KillAction> {
+        self.actions.lock().get(strategy).copied()
+    }
+
+    /// Whether the strategy's signals are observed, not executed
+    pub fn is_shadowed(&self, strategy: &str) -> bool {
+        self.action(strategy) >= Some(KillAction::Shadow)
+    }
+
+    /// Whether the strategy is no longer evaluated
+    pub fn is_disabled(&self, strategy: &str) -> bool {
+        self.action(strategy) == Some(KillAction::Disable)
+    }
+
+    /// Check a strategy against its rules and act on those newly broken.
+    pub fn check(&self, strategy: &str, health: &StrategyHealth) {
+        for (index, rule) in self.config.rules.iter().enumerate() {
+            if !rule.applies_to(strategy) || self.tripped.lock().contains(&index) {
+                continue;
+            }
+            let Some(reason) = rule.breach(health, self.config.min_signals) else {
+                continue;
+            };
+            self.tripped.lock().insert(index);
+            self.trip(strategy, rule.action, &reason);
+        }
+    }
+
+    fn trip(&self, strategy: &str, action: KillAction, reason: &str) {
+        {
+            let mut actions = self.actions.lock();
+            let current = actions.entry(strategy.to_string()).or_insert(action);
+            *current = (*current).max(action);
+        }
+        STRATEGY_KILLS
+            .with_label_values(&[strategy, action.as_str()])
+            .inc();
+        let outcome = match action {
+            KillAction::Alert => "still trading",
+            KillAction::Shadow => "signals now observed only",
+            KillAction::Disable => "disabled",
+        };
+        warn!(
+            "[GOVERNOR] {} kill rule tripped: {} - {}",
+            strategy, reason, outcome
+        );
+        if action != KillAction::Alert {
+            if let Some(audit) = &self.audit {
+                audit.record(
+                    "governor",
+                    AuditAction::StrategyToggle,
+                    Some(strategy),
+                    format!("{} ({})", reason, action.as_str()),
+                );
+            }
+        }
+        if let Some(slack) = &self.slack {
+            slack.notify_error(ErrorAlert {
+                source: format!("governor/{}", strategy),
+                error_type: format!("STRATEGY_KILL_{}", action.as_str().to_uppercase()),
+                message: format!("{} kill rule tripped: {} - {}", strategy, reason, outcome),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rules() {
+        let rules = parse_rules(
+            "SNIPER: max_consecutive_losses=5, max_daily_loss=50, max_reject_rate=0.8; \
+             SumTo100: max_daily_loss=20, action=shadow; Clipper: max_reject_rate=2; Bad",
+        );
+        assert_eq!(rules.len(), 2);
+        assert_eq!(
+            rules[0],
+            KillRule {
+                strategy: "SNIPER".into(),
+                max_consecutive_losses: Some(5),
+                max_daily_loss: Some(50.0),
+                max_reject_rate: Some(0.8),
+                action: KillAction::Disable,
+            }
+        );
+        assert!(rules[0].applies_to("Sniper"));
+        assert_eq!(rules[1].action, KillAction::Shadow);
+        assert_eq!(KillRule::parse("Sniper: action=alert"), None);
+    }
+
+    #[test]
+    fn test_rules_trip_once_and_escalate() {
+        let mut governor = StrategyGovernor::new(GovernorConfig {
+            rules: parse_rules(
+                "GovA: max_consecutive_losses=3, action=alert; \
+                 GovA: max_daily_loss=50, action=shadow; \
+                 GovA: max_reject_rate=0.5, action=disable",
+            ),
+            min_signals: 10,
+        });
+        let audit = Arc::new(AuditLog::new());
+        governor.set_audit_log(audit.clone());
+
+        for pnl in [-1.0, -1.0, 0.0, 2.0, -1.0, -1.0] {
+            governor.record_pnl("GovA", pnl);
+        }
+        assert_eq!(governor.losing_streak("GovA"), 2);
+        governor.record_pnl("GovA", -1.0);
+        let mut health = StrategyHealth {
+            consecutive_losses: governor.losing_streak("GovA"),
+            daily_pnl: -10.0,
+            executed: 2,
+            rejected: 7,
+        };
+        governor.check("GovA", &health);
+        assert_eq!(governor.action("GovA"), Some(KillAction::Alert));
+        assert!(!governor.is_shadowed("GovA"));
+
+        health.daily_pnl = -50.0;
+        governor.check("GovA", &health);
+        assert!(governor.is_shadowed("GovA"));
+        assert!(!governor.is_disabled("GovA"));
+
+        // Too few outcomes to judge the reject rate, then enough
+        health.executed = 3;
+        governor.check("GovA", &health);
+        assert!(governor.is_disabled("GovA"));
+
+        // Shadowing and disabling are audited as toggles; alerts are not
+        let toggles: Vec<String> = audit
+            .recent()
+            .iter()
+            .filter(|e| e.action == AuditAction::StrategyToggle)
+            .map(|e| e.details.clone())
+            .collect();
+        assert_eq!(toggles.len(), 2);
+        assert!(toggles[0].ends_with("(shadow)"));
+        assert!(toggles[1].ends_with("(disable)"));
+        assert_eq!(
+            STRATEGY_KILLS.with_label_values(&["GovA", "alert"]).get(),
+            1.0
+        );
+
+        // Other strategies are untouched
+        assert_eq!(governor.action("GovB"), None);
+    }
+}
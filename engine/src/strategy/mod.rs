@@ -1,13 +1,53 @@
 //! Trading strategies.
 
+mod approvals;
 mod clipper;
+mod cost;
+pub mod decisions;
+mod edge_tuning;
 mod engine;
+mod fast_path;
+mod governor;
+// Plugin ABI is only exercised when a plugin host feature is enabled
+#[allow(dead_code)]
+pub mod plugin;
+#[cfg(feature = "python")]
+pub mod python;
+mod recent_trades;
+mod registry;
+mod retry;
 mod sniper;
+mod sniper_race;
+mod spread_clipper;
+mod state;
+mod stats;
 mod sum_to_100;
 mod traits;
+mod volatility;
+#[cfg(feature = "wasm-plugins")]
+pub mod wasm;
+mod warmup;
+mod win_model;
 
+pub use approvals::{ApprovalConfig, ApprovalError, ApprovalQueue};
 pub use clipper::ClipperStrategy;
-pub use engine::StrategyEngine;
+#[allow(unused_imports)]
+pub use cost::{CostEstimate, CostModel};
+pub use decisions::DecisionLog;
+pub use edge_tuning::{EdgeTuner, EdgeTuningConfig};
+pub use engine::{PlacedOrder, StrategyEngine};
+pub use fast_path::FastPath;
+pub use governor::{GovernorConfig, StrategyGovernor};
+pub use recent_trades::{RecentTrades, TradeFilter};
+#[allow(unused_imports)]
+pub use registry::{StrategyBuilder, StrategyRegistry};
+pub use retry::RetryConfig;
 pub use sniper::SniperStrategy;
+pub use sniper_race::SniperRacer;
+pub use spread_clipper::SpreadClipperStrategy;
+pub use state::StrategyState;
+pub use stats::StrategyStatsSnapshot;
 pub use sum_to_100::SumTo100Strategy;
-pub use traits::{Strategy, TradeSignal};
+pub use traits::{Leg, SignalMetadata, Strategy, TradeSignal, EVENT_MS};
+pub use volatility::VolatilityGateConfig;
+pub use warmup::WarmupConfig;
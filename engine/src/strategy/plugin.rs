@@ -0,0 +1,332 @@
+//! Language-neutral plugin ABI: a read-only market snapshot goes in as JSON,
+//! a list of signals comes back out.
+//!
+//! Shared by external strategy hosts so every plugin sees the same data and
+//! every returned signal goes through the same validation.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tracing::warn;
+
+use crate::execution::Side;
+use crate::market::{DepthLevel, MarketSnapshot, OrderBook};
+
+use super::{Leg, TradeSignal};
+
+/// Order book levels included per side
+pub const DEFAULT_BOOK_DEPTH: usize = 10;
+
+/// Read-only market view handed to plugins
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginSnapshot {
+    pub timestamp_ns: u64,
+    pub pairs: Vec<PluginPair>,
+    pub books: Vec<PluginBook>,
+}
+
+/// A YES/NO market pair
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginPair {
+    pub market_id: String,
+    pub yes_token: String,
+    pub no_token: String,
+    pub question: String,
+    pub category: Option<String>,
+}
+
+/// Top-of-book depth for one token (`[price, size]` levels, best first)
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginBook {
+    pub token_id: String,
+    pub best_bid: Option<f64>,
+    pub best_ask: Option<f64>,
+    pub bids: Vec<[f64; 2]>,
+    pub asks: Vec<[f64; 2]>,
+    pub timestamp_ns: u64,
+}
+
+impl PluginBook {
+    fn from_book(book: &OrderBook, depth: usize) -> Self {
+        let levels = |side: &[DepthLevel]| -> Vec<[f64; 2]> {
+            side.iter().take(depth).map(|l| [l.price, l.size]).collect()
+        };
+        Self {
+            token_id: book.token_id.clone(),
+            best_bid: book.best_bid(),
+            best_ask: book.best_ask(),
+            bids: levels(&book.bids),
+            asks: levels(&book.asks),
+            timestamp_ns: book.timestamp_ns,
+        }
+    }
+}
+
+impl PluginSnapshot {
+    /// Capture the snapshot's pairs and their order books.
+    pub fn from_snapshot(snapshot: &MarketSnapshot, depth: usize) -> Self {
+        let mut pairs = Vec::new();
+        let mut books = Vec::new();
+
+        for pair in snapshot.pairs() {
+            for token in [&pair.yes_token, &pair.no_token] {
+                if let Some(book) = snapshot.get_order_book(token) {
+                    books.push(PluginBook::from_book(book, depth));
+                }
+            }
+            pairs.push(PluginPair {
+                market_id: pair.market_id.clone(),
+                yes_token: pair.yes_token.clone(),
+                no_token: pair.no_token.clone(),
+                question: pair.question.clone(),
+                category: pair.category.clone(),
+            });
+        }
+
+        Self {
+            timestamp_ns: snapshot.timestamp_ns(),
+            pairs,
+            books,
+        }
+    }
+}
+
+/// A signal returned by a plugin
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PluginSignal {
+    Buy {
+        token_id: String,
+        price: f64,
+        size: f64,
+        #[serde(default)]
+        reason: String,
+        #[serde(default)]
+        metadata: Map<String, Value>,
+    },
+    Sell {
+        token_id: String,
+        price: f64,
+        size: f64,
+        #[serde(default)]
+        reason: String,
+        #[serde(default)]
+        metadata: Map<String, Value>,
+    },
+    Arbitrage {
+        yes_token: String,
+        no_token: String,
+        yes_price: f64,
+        no_price: f64,
+        size: f64,
+        /// Defaults to `1 - yes_price - no_price`
+        #[serde(default)]
+        profit_per_share: Option<f64>,
+        #[serde(default)]
+        metadata: Map<String, Value>,
+    },
+    Basket {
+        /// `{"token_id", "side": "BUY" | "SELL", "price", "size"}` each
+        legs: Vec<Leg>,
+        /// Value at resolution, for baskets with a known payoff
+        #[serde(default)]
+        payout: Option<f64>,
+        /// All legs in one request or none
+        #[serde(default)]
+        atomic: bool,
+        #[serde(default)]
+        reason: String,
+        #[serde(default)]
+        metadata: Map<String, Value>,
+    },
+}
+
+fn valid_price(price: f64) -> bool {
+    price.is_finite() && price > 0.0 && price < 1.0
+}
+
+fn valid_size(size: f64) -> bool {
+    size.is_finite() && size > 0.0
+}
+
+impl PluginSignal {
+    /// Validate and convert into an engine signal.
+    ///
+    /// Returns None (with a warning) for out-of-range prices or sizes so a
+    /// buggy plugin cannot submit nonsense orders.
+    pub fn into_trade_signal(self, plugin: &str) -> Option<TradeSignal> {
+        let signal = match self {
+            PluginSignal::Buy {
+                token_id,
+                price,
+                size,
+                reason,
+                metadata,
+            } if valid_price(price) && valid_size(size) => TradeSignal::Buy {
+                token_id,
+                price,
+                size,
+                reason: format!("[{}] {}", plugin, reason),
+                metadata: metadata.into(),
+            },
+            PluginSignal::Sell {
+                token_id,
+                price,
+                size,
+                reason,
+                metadata,
+            } if valid_price(price) && valid_size(size) => TradeSignal::Sell {
+                token_id,
+                price,
+                size,
+                reason: format!("[{}] {}", plugin, reason),
+                metadata: metadata.into(),
+            },
+            PluginSignal::Arbitrage {
+                yes_token,
+                no_token,
+                yes_price,
+                no_price,
+                size,
+                profit_per_share,
+                metadata,
+            } if valid_price(yes_price) && valid_price(no_price) && valid_size(size) => {
+                TradeSignal::Arbitrage {
+                    yes_token,
+                    no_token,
+                    yes_price,
+                    no_price,
+                    profit_per_share: profit_per_share.unwrap_or(1.0 - yes_price - no_price),
+                    size,
+                    side: Side::Buy,
+                    metadata: metadata.into(),
+                }
+            }
+            PluginSignal::Basket {
+                legs,
+                payout,
+                atomic,
+                reason,
+                metadata,
+            } if !legs.is_empty()
+                && legs
+                    .iter()
+                    .all(|l| valid_price(l.price) && valid_size(l.size))
+                && payout.is_none_or(f64::is_finite) =>
+            {
+                TradeSignal::Basket {
+                    legs,
+                    payout,
+                    atomic,
+                    reason: format!("[{}] {}", plugin, reason),
+                    metadata: metadata.into(),
+                }
+            }
+            invalid => {
+                warn!(
+                    "[{}] Discarding invalid plugin signal: {:?}",
+                    plugin, invalid
+                );
+                return None;
+            }
+        };
+        Some(signal)
+    }
+}
+
+/// Parse a plugin's JSON output (a signal list) into the first valid signal.
+pub fn first_valid_signal(plugin: &str, json: &[u8]) -> Option<TradeSignal> {
+    if json.is_empty() {
+        return None;
+    }
+    match serde_json::from_slice::<Vec<PluginSignal>>(json) {
+        Ok(signals) => signals
+            .into_iter()
+            .find_map(|s| s.into_trade_signal(plugin)),
+        Err(e) => {
+            warn!("[{}] Invalid plugin output: {}", plugin, e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market::{MarketData, MarketPair};
+
+    #[test]
+    fn test_snapshot_includes_pair_books() {
+        let market_data = MarketData::new();
+        market_data.register_pair(MarketPair {
+            market_id: "m1".into(),
+            yes_token: "yes".into(),
+            no_token: "no".into(),
+            question: "Q?".into(),
+            category: Some("sports".into()),
+            end_date: None,
+        });
+        market_data.update_order_book(
+            &"yes".into(),
+            vec![DepthLevel::new(0.44, 10.0)],
+            vec![DepthLevel::new(0.45, 20.0), DepthLevel::new(0.46, 5.0)],
+        );
+
+        let snapshot = PluginSnapshot::from_snapshot(&market_data.snapshot(0), 1);
+        assert_eq!(snapshot.pairs.len(), 1);
+        assert_eq!(snapshot.books.len(), 1);
+        assert_eq!(snapshot.books[0].asks, vec![[0.45, 20.0]]);
+        assert_eq!(snapshot.books[0].best_bid, Some(0.44));
+    }
+
+    #[test]
+    fn test_first_valid_signal_skips_invalid_entries() {
+        let json = br#"[
+            {"type": "buy", "token_id": "a", "price": 1.5, "size": 10},
+            {"type": "arbitrage", "yes_token": "y", "no_token": "n",
+             "yes_price": 0.45, "no_price": 0.50, "size": 10, "metadata": {"model": "v2"}}
+        ]"#;
+
+        match first_valid_signal("test", json) {
+            Some(TradeSignal::Arbitrage {
+                profit_per_share,
+                metadata,
+                ..
+            }) => {
+                assert!((profit_per_share - 0.05).abs() < 1e-9);
+                assert_eq!(metadata.get("model"), Some(&Value::from("v2")));
+            }
+            other => panic!("unexpected signal: {:?}", other),
+        }
+
+        assert!(first_valid_signal("test", b"not json").is_none());
+        assert!(first_valid_signal("test", b"[]").is_none());
+    }
+
+    #[test]
+    fn test_basket_signal_parses_legs() {
+        let json = br#"[
+            {"type": "basket", "legs": []},
+            {"type": "basket", "payout": 10, "reason": "three-way",
+             "legs": [
+                {"token_id": "a", "side": "BUY", "price": 0.30, "size": 10},
+                {"token_id": "b", "side": "BUY", "price": 0.25, "size": 10},
+                {"token_id": "c", "side": "SELL", "price": 0.40, "size": 5}
+             ]}
+        ]"#;
+
+        match first_valid_signal("test", json) {
+            Some(TradeSignal::Basket {
+                legs,
+                payout,
+                reason,
+                ..
+            }) => {
+                assert_eq!(legs.len(), 3);
+                assert_eq!(legs[2].side, Side::Sell);
+                assert_eq!(payout, Some(10.0));
+                assert_eq!(reason, "[test] three-way");
+            }
+            other => panic!("unexpected signal: {:?}", other),
+        }
+    }
+}
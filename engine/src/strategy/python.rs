@@ -0,0 +1,310 @@
+//! Python strategy bridge (feature `python`).
+//!
+//! Loads a Python class from a source file and calls its
+//! `evaluate(snapshot)` method on every tick. The snapshot is the same
+//! read-only view WASM plugins receive (a plain dict), and the method returns
+//! a signal dict, a list of signal dicts, or `None`:
+//!
+//! ```python
+//! class Strategy:
+//!     def evaluate(self, snapshot):
+//!         return {"type": "buy", "token_id": "...", "price": 0.42, "size": 10}
+//! ```
+//!
+//! Each strategy runs on its own interpreter thread. The engine waits at most
+//! the configured timeout for a result; a call still running when the next
+//! tick arrives causes that tick to be skipped rather than queued.
+
+use std::ffi::CString;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use tracing::{debug, info, warn};
+
+use crate::market::MarketSnapshot;
+
+use super::plugin::{first_valid_signal, PluginSnapshot, DEFAULT_BOOK_DEPTH};
+use super::registry::StrategyRegistry;
+use super::{Strategy, TradeSignal};
+
+/// Consecutive failed or timed-out calls before a strategy is disabled
+const MAX_CONSECUTIVE_FAILURES: u32 = 5;
+
+/// Default per-call timeout
+const DEFAULT_TIMEOUT_MS: u64 = 50;
+
+/// Class loaded when the spec names only a file
+const DEFAULT_CLASS: &str = "Strategy";
+
+/// One evaluation handed to the interpreter thread
+struct Request {
+    snapshot_json: String,
+    reply: mpsc::SyncSender<Result<String>>,
+}
+
+/// A strategy implemented by a Python class.
+pub struct PythonStrategy {
+    name: &'static str,
+    timeout: Duration,
+    requests: mpsc::Sender<Request>,
+    busy: Arc<AtomicBool>,
+    consecutive_failures: AtomicU32,
+    disabled: AtomicBool,
+}
+
+impl PythonStrategy {
+    /// Load `class_name` from the Python file at `path` and start its
+    /// interpreter thread.
+    pub fn load(name: &str, path: &Path, class_name: &str, timeout: Duration) -> Result<Self> {
+        let source = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read Python strategy {}", path.display()))?;
+        let module_name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("strategy");
+
+        let instance = Python::with_gil(|py| -> PyResult<Py<PyAny>> {
+            let module = PyModule::from_code(
+                py,
+                &c_string(&source)?,
+                &c_string(&path.display().to_string())?,
+                &c_string(module_name)?,
+            )?;
+            Ok(module.getattr(class_name)?.call0()?.unbind())
+        })
+        .map_err(|e| anyhow!("{}", e))
+        .with_context(|| format!("Failed to load {}:{}", path.display(), class_name))?;
+
+        let (requests, rx) = mpsc::channel::<Request>();
+        let busy = Arc::new(AtomicBool::new(false));
+        let worker_busy = busy.clone();
+        std::thread::Builder::new()
+            .name(format!("py-{}", module_name))
+            .spawn(move || {
+                while let Ok(request) = rx.recv() {
+                    let result = Python::with_gil(|py| call_evaluate(py, &instance, &request))
+                        .map_err(|e| anyhow!("{}", e));
+                    worker_busy.store(false, Ordering::Release);
+                    // The caller may have timed out and dropped the receiver
+                    let _ = request.reply.send(result);
+                }
+            })
+            .context("Failed to spawn Python strategy thread")?;
+
+        info!(
+            "[PYTHON] Loaded strategy {} from {}:{} (timeout={}ms)",
+            name,
+            path.display(),
+            class_name,
+            timeout.as_millis()
+        );
+
+        Ok(Self {
+            // Strategy names are &'static; strategies are loaded once at startup
+            name: Box::leak(name.to_string().into_boxed_str()),
+            timeout,
+            requests,
+            busy,
+            consecutive_failures: AtomicU32::new(0),
+            disabled: AtomicBool::new(false),
+        })
+    }
+
+    fn record_failure(&self, error: &str) {
+        let failures = self.consecutive_failures.fetch_add(1, Ordering::Relaxed) + 1;
+        warn!(
+            "[{}] Python call failed ({}): {}",
+            self.name, failures, error
+        );
+        if failures >= MAX_CONSECUTIVE_FAILURES {
+            self.disabled.store(true, Ordering::Relaxed);
+            warn!(
+                "[{}] Python strategy disabled after {} consecutive failures",
+                self.name, failures
+            );
+        }
+    }
+}
+
+fn c_string(s: &str) -> PyResult<CString> {
+    CString::new(s).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+/// Call `instance.evaluate(snapshot)` and return its result as JSON.
+fn call_evaluate(py: Python<'_>, instance: &Py<PyAny>, request: &Request) -> PyResult<String> {
+    let json = py.import("json")?;
+    let snapshot = json.call_method1("loads", (request.snapshot_json.as_str(),))?;
+    let result = instance.bind(py).call_method1("evaluate", (snapshot,))?;
+
+    if result.is_none() {
+        return Ok(String::new());
+    }
+    let result = if result.downcast::<PyDict>().is_ok() {
+        PyList::new(py, [result])?.into_any()
+    } else {
+        result
+    };
+    json.call_method1("dumps", (result,))?.extract()
+}
+
+impl Strategy for PythonStrategy {
+    fn evaluate(&self, snapshot: &MarketSnapshot) -> Option<TradeSignal> {
+        if self.disabled.load(Ordering::Relaxed) {
+            return None;
+        }
+        if self.busy.swap(true, Ordering::AcqRel) {
+            debug!("[{}] Previous call still running, skipping tick", self.name);
+            return None;
+        }
+
+        let snapshot = PluginSnapshot::from_snapshot(snapshot, DEFAULT_BOOK_DEPTH);
+        let snapshot_json = match serde_json::to_string(&snapshot) {
+            Ok(json) => json,
+            Err(e) => {
+                self.busy.store(false, Ordering::Release);
+                warn!("[{}] Failed to serialize snapshot: {}", self.name, e);
+                return None;
+            }
+        };
+
+        let (reply, response) = mpsc::sync_channel(1);
+        let request = Request {
+            snapshot_json,
+            reply,
+        };
+        if self.requests.send(request).is_err() {
+            self.busy.store(false, Ordering::Release);
+            self.record_failure("interpreter thread exited");
+            return None;
+        }
+
+        match response.recv_timeout(self.timeout) {
+            Ok(Ok(output)) => {
+                self.consecutive_failures.store(0, Ordering::Relaxed);
+                first_valid_signal(self.name, output.as_bytes())
+            }
+            Ok(Err(e)) => {
+                self.record_failure(&e.to_string());
+                None
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                self.record_failure(&format!("timed out after {}ms", self.timeout.as_millis()));
+                None
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                self.record_failure("interpreter thread exited");
+                None
+            }
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn is_active(&self) -> bool {
+        !self.disabled.load(Ordering::Relaxed)
+    }
+}
+
+/// Register every strategy listed in `PYTHON_STRATEGIES` (comma-separated
+/// `path.py[:Class]` specs) under the name `python:<file stem>`.
+pub fn register_strategies_from_env(registry: &mut StrategyRegistry) {
+    let Ok(specs) = std::env::var("PYTHON_STRATEGIES") else {
+        return;
+    };
+    let timeout = Duration::from_millis(
+        std::env::var("PYTHON_STRATEGY_TIMEOUT_MS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_TIMEOUT_MS),
+    );
+
+    for spec in specs.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let (path, class_name) = match spec.rsplit_once(':') {
+            Some((path, class)) => (PathBuf::from(path), class.to_string()),
+            None => (PathBuf::from(spec), DEFAULT_CLASS.to_string()),
+        };
+        let stem = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("strategy")
+            .to_string();
+        let name = format!("python:{}", stem);
+        let strategy_name = name.clone();
+        registry.register(&name, move |_config| {
+            Ok(Box::new(PythonStrategy::load(
+                &strategy_name,
+                &path,
+                &class_name,
+                timeout,
+            )?))
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_module(source: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "poly_strategy_{}.py",
+            uuid::Uuid::new_v4().simple()
+        ));
+        std::fs::write(&path, source).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_python_strategy_returns_signal() {
+        let path = write_module(
+            "class Strategy:\n    def evaluate(self, snapshot):\n        assert 'pairs' in snapshot\n        return {'type': 'buy', 'token_id': 'tok', 'price': 0.4, 'size': 5, 'reason': 'py'}\n",
+        );
+        let strategy =
+            PythonStrategy::load("python:test", &path, "Strategy", Duration::from_secs(5)).unwrap();
+
+        match strategy.evaluate(&MarketSnapshot::default()) {
+            Some(TradeSignal::Buy {
+                token_id, reason, ..
+            }) => {
+                assert_eq!(token_id, "tok");
+                assert_eq!(reason, "[python:test] py");
+            }
+            other => panic!("unexpected signal: {:?}", other),
+        }
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_slow_python_strategy_times_out() {
+        let path = write_module(
+            "import time\nclass Slow:\n    def evaluate(self, snapshot):\n        time.sleep(0.5)\n        return None\n",
+        );
+        let strategy =
+            PythonStrategy::load("python:slow", &path, "Slow", Duration::from_millis(20)).unwrap();
+
+        let start = std::time::Instant::now();
+        assert!(strategy.evaluate(&MarketSnapshot::default()).is_none());
+        // Still running: the next tick is skipped without waiting
+        assert!(strategy.evaluate(&MarketSnapshot::default()).is_none());
+        assert!(start.elapsed() < Duration::from_millis(400));
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_missing_class_is_an_error() {
+        let path = write_module("x = 1\n");
+        let err = PythonStrategy::load("python:bad", &path, "Strategy", Duration::from_secs(1))
+            .err()
+            .unwrap();
+        assert!(format!("{:#}", err).contains("Strategy"));
+        std::fs::remove_file(path).ok();
+    }
+}
@@ -0,0 +1,150 @@
+//! Ring of recent trade attempts for drilling into metric spikes.
+//!
+//! The prometheus crate has no exemplar support, so when a bucket of
+//! `poly_signal_edge` or `poly_order_latency_seconds` spikes, the trades
+//! behind it are found here instead: every executed signal is recorded with
+//! its strategy, order IDs, edge and latency, and `/debug/trades` filters
+//! them by the same dimensions.
+
+use std::collections::VecDeque;
+
+use parking_lot::Mutex;
+use serde::Serialize;
+
+/// Trades kept in memory
+const CAPACITY: usize = 500;
+
+/// One executed (or failed) signal
+#[derive(Debug, Clone, Serialize)]
+pub struct TradeTrace {
+    pub timestamp_ms: u64,
+    pub strategy: &'static str,
+    /// `TradeSignal::kind`
+    pub signal_type: &'static str,
+    pub token_id: String,
+    /// Order IDs returned by the exchange (both legs for arbitrage)
+    pub order_ids: Vec<String>,
+    /// Edge per share, where the signal has one
+    pub edge: Option<f64>,
+    /// Time spent placing the order(s)
+    pub latency_ms: f64,
+    pub status: String,
+}
+
+/// Which trades to return
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TradeFilter {
+    pub strategy: Option<String>,
+    pub min_edge: Option<f64>,
+    pub max_edge: Option<f64>,
+    pub min_latency_ms: Option<f64>,
+}
+
+impl TradeFilter {
+    fn matches(&self, trade: &TradeTrace) -> bool {
+        let edge_in = |bound: Option<f64>, within: fn(f64, f64) -> bool| {
+            bound.is_none_or(|b| trade.edge.is_some_and(|e| within(e, b)))
+        };
+        self.strategy
+            .as_deref()
+            .is_none_or(|s| s.eq_ignore_ascii_case(trade.strategy))
+            && edge_in(self.min_edge, |e, b| e >= b)
+            && edge_in(self.max_edge, |e, b| e <= b)
+            && self.min_latency_ms.is_none_or(|l| trade.latency_ms >= l)
+    }
+}
+
+/// Bounded in-memory log of recent trades.
+pub struct RecentTrades {
+    trades: Mutex<VecDeque<TradeTrace>>,
+}
+
+impl Default for RecentTrades {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RecentTrades {
+    pub fn new() -> Self {
+        Self {
+            trades: Mutex::new(VecDeque::with_capacity(CAPACITY)),
+        }
+    }
+
+    /// Record a trade, dropping the oldest when full.
+    pub fn record(&self, trade: TradeTrace) {
+        let mut trades = self.trades.lock();
+        if trades.len() == CAPACITY {
+            trades.pop_front();
+        }
+        trades.push_back(trade);
+    }
+
+    /// Matching trades, newest first.
+    pub fn matching(&self, filter: &TradeFilter) -> Vec<TradeTrace> {
+        self.trades
+            .lock()
+            .iter()
+            .rev()
+            .filter(|t| filter.matches(t))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trace(strategy: &'static str, edge: Option<f64>, latency_ms: f64) -> TradeTrace {
+        TradeTrace {
+            timestamp_ms: 0,
+            strategy,
+            signal_type: "arbitrage",
+            token_id: "t".into(),
+            order_ids: vec!["o1".into()],
+            edge,
+            latency_ms,
+            status: "FILLED".into(),
+        }
+    }
+
+    #[test]
+    fn test_filters_by_strategy_edge_and_latency() {
+        let recent = RecentTrades::new();
+        recent.record(trace("SumTo100", Some(0.02), 5.0));
+        recent.record(trace("SumTo100", Some(0.08), 300.0));
+        recent.record(trace("Sniper", None, 20.0));
+
+        assert_eq!(recent.matching(&TradeFilter::default()).len(), 3);
+        assert_eq!(recent.matching(&TradeFilter::default())[0].strategy, "Sniper");
+
+        let bucket = TradeFilter {
+            strategy: Some("sumto100".into()),
+            min_edge: Some(0.05),
+            max_edge: Some(0.1),
+            ..TradeFilter::default()
+        };
+        let found = recent.matching(&bucket);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].edge, Some(0.08));
+
+        let slow = TradeFilter {
+            min_latency_ms: Some(100.0),
+            ..TradeFilter::default()
+        };
+        assert_eq!(recent.matching(&slow).len(), 1);
+    }
+
+    #[test]
+    fn test_drops_oldest_when_full() {
+        let recent = RecentTrades::new();
+        for i in 0..CAPACITY + 10 {
+            recent.record(trace("SumTo100", None, i as f64));
+        }
+        let all = recent.matching(&TradeFilter::default());
+        assert_eq!(all.len(), CAPACITY);
+        assert_eq!(all.last().unwrap().latency_ms, 10.0);
+    }
+}
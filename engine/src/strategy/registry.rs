@@ -0,0 +1,248 @@
+//! Strategy registry - maps config names to strategy constructors.
+//!
+//! To ship a new strategy, add one `register` call in `with_builtins` (or
+//! register it at runtime, as WASM plugins do); `main` builds whatever
+//! `STRATEGIES` selects. Strategies registered with `register_instanced` can
+//! be selected more than once as `<name>@<instance>`, each with its own
+//! config.
+
+use anyhow::{bail, Context, Result};
+use tracing::info;
+
+use crate::config::Config;
+
+use super::{ClipperStrategy, SniperStrategy, SpreadClipperStrategy, Strategy, SumTo100Strategy};
+
+/// Constructs a strategy from the engine configuration and, for a named
+/// instance, the instance name
+pub type StrategyBuilder =
+    Box<dyn Fn(&Config, Option<&str>) -> Result<Box<dyn Strategy>> + Send + Sync>;
+
+/// Registry of named strategy builders, in registration order.
+pub struct StrategyRegistry {
+    builders: Vec<(String, StrategyBuilder)>,
+}
+
+impl StrategyRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self {
+            builders: Vec::new(),
+        }
+    }
+
+    /// Create a registry with all built-in strategies.
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        registry.register("sniper", |config| {
+            Ok(Box::new(SniperStrategy::new(config.sniper.clone())))
+        });
+        registry.register("clipper", |config| {
+            Ok(Box::new(ClipperStrategy::new(config.clipper.clone())))
+        });
+        registry.register("spreadclipper", |config| {
+            Ok(Box::new(SpreadClipperStrategy::new(config.clipper.clone())))
+        });
+        registry.register_instanced("sumto100", |config, instance| {
+            let Some(instance) = instance else {
+                return Ok(Box::new(SumTo100Strategy::new(config.sum_to_100.clone())));
+            };
+            let instance_config = config
+                .sum_to_100_instances
+                .get(instance)
+                .with_context(|| format!("No config loaded for sumto100@{}", instance))?;
+            Ok(Box::new(
+                SumTo100Strategy::new(instance_config.clone()).with_instance(instance),
+            ))
+        });
+        registry
+    }
+
+    /// Register a strategy builder under a (case-insensitive) name,
+    /// replacing any builder already registered under that name.
+    pub fn register<F>(&mut self, name: &str, builder: F)
+    where
+        F: Fn(&Config) -> Result<Box<dyn Strategy>> + Send + Sync + 'static,
+    {
+        let label = name.to_lowercase();
+        self.register_instanced(name, move |config, instance| match instance {
+            Some(instance) => bail!(
+                "Strategy '{}' does not support named instances ('{}@{}')",
+                label,
+                label,
+                instance
+            ),
+            None => builder(config),
+        });
+    }
+
+    /// Register a strategy builder that also accepts named instances
+    /// (`<name>@<instance>` in `STRATEGIES`).
+    pub fn register_instanced<F>(&mut self, name: &str, builder: F)
+    where
+        F: Fn(&Config, Option<&str>) -> Result<Box<dyn Strategy>> + Send + Sync + 'static,
+    {
+        let name = name.to_lowercase();
+        let builder: StrategyBuilder = Box::new(builder);
+        match self.builders.iter_mut().find(|(n, _)| *n == name) {
+            Some(entry) => entry.1 = builder,
+            None => self.builders.push((name, builder)),
+        }
+    }
+
+    /// Registered strategy names, in registration order.
+    pub fn names(&self) -> Vec<&str> {
+        self.builders.iter().map(|(n, _)| n.as_str()).collect()
+    }
+
+    fn builder(&self, name: &str) -> Option<&StrategyBuilder> {
+        self.builders
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, b)| b)
+    }
+
+    /// Build the strategies selected by `config.strategies`.
+    ///
+    /// With no selection every registered strategy is built (each still
+    /// honours its own `*_ENABLED` flag). Unknown names are an error so a
+    /// typo in `STRATEGIES` cannot silently disable a strategy. An entry
+    /// `<name>@<instance>` builds a named instance of `<name>`.
+    pub fn build_enabled(&self, config: &Config) -> Result<Vec<Box<dyn Strategy>>> {
+        let selected: Vec<String> = match &config.strategies {
+            Some(list) => list.iter().map(|s| s.to_lowercase()).collect(),
+            None => self.builders.iter().map(|(n, _)| n.clone()).collect(),
+        };
+
+        let unknown: Vec<&str> = selected
+            .iter()
+            .filter(|entry| self.builder(strategy_kind(entry)).is_none())
+            .map(String::as_str)
+            .collect();
+        if !unknown.is_empty() {
+            bail!(
+                "Unknown strategies in STRATEGIES: {} (available: {})",
+                unknown.join(", "),
+                self.names().join(", ")
+            );
+        }
+
+        let mut strategies = Vec::with_capacity(selected.len());
+        for entry in &selected {
+            let (name, instance) = match entry.split_once('@') {
+                Some((name, instance)) => (name, Some(instance)),
+                None => (entry.as_str(), None),
+            };
+            if let Some(builder) = self.builder(name) {
+                let strategy = builder(config, instance)
+                    .with_context(|| format!("Failed to build strategy '{}'", entry))?;
+                strategies.push(strategy);
+            }
+        }
+
+        info!("[REGISTRY] Built strategies: {}", selected.join(", "));
+        Ok(strategies)
+    }
+}
+
+/// Strategy type of a `STRATEGIES` entry (`sumto100` for `sumto100@sports`)
+fn strategy_kind(entry: &str) -> &str {
+    entry.split_once('@').map_or(entry, |(kind, _)| kind)
+}
+
+impl Default for StrategyRegistry {
+    fn default() -> Self {
+        Self::with_builtins()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{
+        ClipperConfig, CostConfig, OrderGuardConfig, OrderQueueConfig, RiskConfig,
+        SniperConfig, SumTo100Config,
+    };
+
+    fn test_config(strategies: Option<Vec<&str>>) -> Config {
+        Config {
+            ws_url: "wss://test.com".into(),
+            clob_url: "https://test.com".into(),
+            private_key: "0x1234".into(),
+            api_key: "test-key".into(),
+            api_secret: "test-secret".into(),
+            dry_run: true,
+            observe: false,
+            risk: RiskConfig::default(),
+            sniper: SniperConfig::default(),
+            clipper: ClipperConfig::default(),
+            sum_to_100: SumTo100Config::default(),
+            sum_to_100_instances: Default::default(),
+            order_guard: OrderGuardConfig::default(),
+            order_queue: OrderQueueConfig::default(),
+            cost: CostConfig::default(),
+            strategies: strategies.map(|l| l.into_iter().map(String::from).collect()),
+            instance_id: "default".into(),
+            redis_prefix: "poly".into(),
+            trading_timezone: "UTC".into(),
+        }
+    }
+
+    #[test]
+    fn test_builds_all_builtins_by_default() {
+        let registry = StrategyRegistry::with_builtins();
+        let strategies = registry.build_enabled(&test_config(None)).unwrap();
+        let names: Vec<_> = strategies.iter().map(|s| s.name()).collect();
+        assert_eq!(names, vec!["Sniper", "Clipper", "SpreadClipper", "SumTo100"]);
+    }
+
+    #[test]
+    fn test_builds_selected_strategies_in_order() {
+        let registry = StrategyRegistry::with_builtins();
+        let config = test_config(Some(vec!["sumto100", "Clipper"]));
+        let names: Vec<_> = registry
+            .build_enabled(&config)
+            .unwrap()
+            .iter()
+            .map(|s| s.name())
+            .collect();
+        assert_eq!(names, vec!["SumTo100", "Clipper"]);
+    }
+
+    #[test]
+    fn test_unknown_strategy_is_an_error() {
+        let registry = StrategyRegistry::with_builtins();
+        let err = registry
+            .build_enabled(&test_config(Some(vec!["clipper", "moonshot"])))
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains("moonshot"));
+        assert!(err.contains("available: sniper, clipper, spreadclipper, sumto100"));
+    }
+
+    #[test]
+    fn test_builds_named_instances() {
+        let registry = StrategyRegistry::with_builtins();
+        let mut config = test_config(Some(vec!["sumto100@sports", "sumto100@all"]));
+        for name in ["sports", "all"] {
+            config
+                .sum_to_100_instances
+                .insert(name.into(), SumTo100Config::default());
+        }
+        let names: Vec<_> = registry
+            .build_enabled(&config)
+            .unwrap()
+            .iter()
+            .map(|s| s.name())
+            .collect();
+        assert_eq!(names, vec!["SumTo100@sports", "SumTo100@all"]);
+
+        // Only instanced builders accept a name
+        let err = registry
+            .build_enabled(&test_config(Some(vec!["clipper@fast"])))
+            .err()
+            .unwrap();
+        assert!(format!("{:#}", err).contains("does not support named instances"));
+    }
+}
@@ -0,0 +1,366 @@
+//! Retrying strong opportunities that were blocked.
+//!
+//! An arbitrage or a basket with a known payout turned down by the risk
+//! limits (the notional or position limit is full for the moment) or
+//! refused by the exchange for capacity (rate limit, open circuit
+//! breaker) would otherwise just be gone. With
+//! `RETRY_WINDOW_MS` set, signals with a gross edge of at least
+//! `RETRY_MIN_EDGE` per share are held for that long instead. Every tick
+//! each held signal is re-priced against the current books; once the edge
+//! still clears the bar and every risk check would pass, it goes back
+//! through the engine. An opportunity is never held past the window it
+//! was first blocked in, however often it is blocked again.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use tracing::info;
+
+use crate::execution::Side;
+use crate::market::{MarketData, TokenId};
+use crate::metrics::OPPORTUNITY_RETRIES;
+
+use super::{Leg, TradeSignal};
+
+/// Opportunity retry settings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryConfig {
+    /// How long a blocked opportunity is retried (zero = disabled)
+    pub window: Duration,
+    /// Gross edge per share an opportunity needs to be held and retried
+    pub min_edge: f64,
+    /// Most opportunities held at once
+    pub max_pending: usize,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            window: Duration::ZERO,
+            min_edge: 0.02,
+            max_pending: 16,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Load from `RETRY_WINDOW_MS`, `RETRY_MIN_EDGE` and `RETRY_MAX_PENDING`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok();
+        Self {
+            window: var("RETRY_WINDOW_MS")
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.window),
+            min_edge: var("RETRY_MIN_EDGE")
+                .and_then(|v| v.parse().ok())
+                .filter(|e: &f64| e.is_finite() && *e >= 0.0)
+                .unwrap_or(defaults.min_edge),
+            max_pending: var("RETRY_MAX_PENDING")
+                .and_then(|v| v.parse().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.max_pending),
+        }
+    }
+}
+
+/// A blocked opportunity waiting for capacity
+struct Held {
+    strategy_name: &'static str,
+    signal: TradeSignal,
+    approved: bool,
+    deadline: Instant,
+}
+
+/// An opportunity to send through the engine again
+pub(super) struct Retry {
+    pub strategy_name: &'static str,
+    pub signal: TradeSignal,
+    pub approved: bool,
+}
+
+/// Blocked opportunities retried until their window closes
+pub(super) struct RetryQueue {
+    config: RetryConfig,
+    held: Mutex<Vec<Held>>,
+    /// When each opportunity's window closes, by strategy and first token
+    deadlines: Mutex<HashMap<(&'static str, TokenId), Instant>>,
+}
+
+impl RetryQueue {
+    pub fn new(config: RetryConfig) -> Self {
+        info!(
+            "[RETRY] Retrying blocked opportunities with edge >= ${:.4} for {:?}",
+            config.min_edge, config.window
+        );
+        Self {
+            config,
+            held: Mutex::new(Vec::new()),
+            deadlines: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Hold a blocked signal for retry if its edge is worth it; returns
+    /// whether it was held.
+    pub fn hold(
+        &self,
+        strategy_name: &'static str,
+        signal: &TradeSignal,
+        approved: bool,
+        now: Instant,
+    ) -> bool {
+        if !signal
+            .edge()
+            .is_some_and(|edge| edge >= self.config.min_edge)
+        {
+            return false;
+        }
+        let key = (strategy_name, signal.token_id().clone());
+        let deadline = *self
+            .deadlines
+            .lock()
+            .entry(key.clone())
+            .or_insert(now + self.config.window);
+        if deadline <= now {
+            return false;
+        }
+
+        let mut held = self.held.lock();
+        if let Some(existing) = held
+            .iter_mut()
+            .find(|h| h.strategy_name == strategy_name && *h.signal.token_id() == key.1)
+        {
+            existing.signal = signal.clone();
+            return true;
+        }
+        if held.len() >= self.config.max_pending {
+            OPPORTUNITY_RETRIES.with_label_values(&["full"]).inc();
+            return false;
+        }
+        info!(
+            "[RETRY] Holding [{}] {} for up to {:?}",
+            strategy_name,
+            signal.description(),
+            deadline - now
+        );
+        OPPORTUNITY_RETRIES.with_label_values(&["held"]).inc();
+        held.push(Held {
+            strategy_name,
+            signal: signal.clone(),
+            approved,
+            deadline,
+        });
+        true
+    }
+
+    /// Drop expired opportunities and take those that, re-priced against
+    /// the current books, still clear the edge and pass `clear`.
+    pub fn take_ready(
+        &self,
+        market_data: &MarketData,
+        now: Instant,
+        clear: impl Fn(&TradeSignal) -> bool,
+    ) -> Vec<Retry> {
+        self.deadlines.lock().retain(|_, deadline| *deadline > now);
+
+        let mut ready = Vec::new();
+        self.held.lock().retain(|held| {
+            if held.deadline <= now {
+                info!(
+                    "[RETRY] Gave up on [{}] {}",
+                    held.strategy_name,
+                    held.signal.description()
+                );
+                OPPORTUNITY_RETRIES.with_label_values(&["expired"]).inc();
+                return false;
+            }
+            let Some(signal) = reprice(&held.signal, market_data)
+                .filter(|s| s.edge().is_some_and(|edge| edge >= self.config.min_edge))
+            else {
+                return true;
+            };
+            if !clear(&signal) {
+                return true;
+            }
+            OPPORTUNITY_RETRIES.with_label_values(&["retried"]).inc();
+            ready.push(Retry {
+                strategy_name: held.strategy_name,
+                signal,
+                approved: held.approved,
+            });
+            false
+        });
+        ready
+    }
+}
+
+/// An arbitrage or basket re-priced at the VWAP of the current books,
+/// sized to the depth every leg still shows.
+fn reprice(signal: &TradeSignal, market_data: &MarketData) -> Option<TradeSignal> {
+    if let TradeSignal::Basket { .. } = signal {
+        return reprice_basket(signal, market_data);
+    }
+    let TradeSignal::Arbitrage {
+        yes_token,
+        no_token,
+        size,
+        side,
+        metadata,
+        ..
+    } = signal
+    else {
+        return None;
+    };
+    let yes_book = market_data.get_order_book(yes_token)?;
+    let no_book = market_data.get_order_book(no_token)?;
+    let sweep = |size: f64| match side {
+        Side::Buy => Some((yes_book.vwap_buy(size)?, no_book.vwap_buy(size)?)),
+        Side::Sell => Some((yes_book.vwap_sell(size)?, no_book.vwap_sell(size)?)),
+    };
+    let (mut yes, mut no) = sweep(*size)?;
+    let fillable = yes.total_size.min(no.total_size);
+    if fillable < *size {
+        (yes, no) = sweep(fillable)?;
+    }
+    let profit_per_share = match side {
+        Side::Buy => 1.0 - yes.vwap - no.vwap,
+        Side::Sell => yes.vwap + no.vwap - 1.0,
+    };
+    Some(TradeSignal::Arbitrage {
+        yes_token: yes_token.clone(),
+        no_token: no_token.clone(),
+        yes_price: yes.vwap,
+        no_price: no.vwap,
+        profit_per_share,
+        size: fillable,
+        side: *side,
+        metadata: metadata.clone(),
+    })
+}
+
+/// A basket re-priced leg by leg. Legs keep their proportions: all shrink
+/// by the fraction the thinnest book can fill, and the payout with them.
+fn reprice_basket(signal: &TradeSignal, market_data: &MarketData) -> Option<TradeSignal> {
+    let TradeSignal::Basket {
+        legs,
+        payout,
+        atomic,
+        reason,
+        metadata,
+    } = signal
+    else {
+        return None;
+    };
+    let books = legs
+        .iter()
+        .map(|leg| market_data.get_order_book(&leg.token_id))
+        .collect::<Option<Vec<_>>>()?;
+    let sweep = |i: usize, size: f64| match legs[i].side {
+        Side::Buy => books[i].vwap_buy(size),
+        Side::Sell => books[i].vwap_sell(size),
+    };
+    let mut scale: f64 = 1.0;
+    for (i, leg) in legs.iter().enumerate() {
+        scale = scale.min(sweep(i, leg.size)?.total_size / leg.size);
+    }
+    let legs = legs
+        .iter()
+        .enumerate()
+        .map(|(i, leg)| {
+            let size = leg.size * scale;
+            Some(Leg {
+                price: sweep(i, size)?.vwap,
+                size,
+                ..leg.clone()
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(TradeSignal::Basket {
+        legs,
+        payout: payout.map(|p| p * scale),
+        atomic: *atomic,
+        reason: reason.clone(),
+        metadata: metadata.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market::DepthLevel;
+    use crate::strategy::SignalMetadata;
+
+    fn arbitrage(profit_per_share: f64) -> TradeSignal {
+        TradeSignal::Arbitrage {
+            yes_token: "yes".into(),
+            no_token: "no".into(),
+            yes_price: 0.45,
+            no_price: 0.50,
+            profit_per_share,
+            size: 100.0,
+            side: Side::Buy,
+            metadata: SignalMetadata::new(),
+        }
+    }
+
+    fn set_asks(market_data: &MarketData, yes: f64, no: f64, size: f64) {
+        market_data.update_order_book(&"yes".into(), vec![], vec![DepthLevel::new(yes, size)]);
+        market_data.update_order_book(&"no".into(), vec![], vec![DepthLevel::new(no, size)]);
+    }
+
+    #[test]
+    fn test_retries_when_edge_holds_and_capacity_frees_up() {
+        let queue = RetryQueue::new(RetryConfig {
+            window: Duration::from_secs(3),
+            ..RetryConfig::default()
+        });
+        let market_data = MarketData::new();
+        let now = Instant::now();
+
+        // Weak opportunities are not worth holding
+        assert!(!queue.hold("SumTo100", &arbitrage(0.01), false, now));
+        assert!(queue.hold("SumTo100", &arbitrage(0.05), false, now));
+
+        // Edge gone from the book: keep waiting
+        set_asks(&market_data, 0.50, 0.49, 100.0);
+        assert!(queue.take_ready(&market_data, now, |_| true).is_empty());
+
+        // Edge back but risk still full
+        set_asks(&market_data, 0.45, 0.50, 60.0);
+        assert!(queue.take_ready(&market_data, now, |_| false).is_empty());
+
+        // Re-priced and resized to the depth on the book
+        let ready = queue.take_ready(&market_data, now, |_| true);
+        assert_eq!(ready.len(), 1);
+        let TradeSignal::Arbitrage {
+            size,
+            profit_per_share,
+            ..
+        } = ready[0].signal
+        else {
+            panic!("expected an arbitrage");
+        };
+        assert_eq!(size, 60.0);
+        assert!((profit_per_share - 0.05).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_window_is_not_extended_by_blocking_again() {
+        let queue = RetryQueue::new(RetryConfig {
+            window: Duration::from_secs(3),
+            ..RetryConfig::default()
+        });
+        let market_data = MarketData::new();
+        let now = Instant::now();
+
+        assert!(queue.hold("SumTo100", &arbitrage(0.05), false, now));
+        let later = now + Duration::from_secs(2);
+        assert!(queue.hold("SumTo100", &arbitrage(0.05), false, later));
+
+        let closed = now + Duration::from_secs(3);
+        assert!(queue.take_ready(&market_data, closed, |_| true).is_empty());
+        assert!(queue.held.lock().is_empty());
+    }
+}
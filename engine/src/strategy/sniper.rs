@@ -2,17 +2,41 @@
 //!
 //! Uses ESPN data to detect finished games before Polymarket prices update.
 //! Buys winning outcomes at stale prices.
+//!
+//! With pre-positioning enabled it also buys in the closing minutes of a game
+//! when the price lags a live win-probability model (see `win_model`).
+//!
+//! With the fast path enabled (`SNIPER_FAST_PATH`) it listens for games going
+//! final and sends the winner's buy straight to the engine (see `FastPath`).
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{info, warn};
 
 use crate::config::SniperConfig;
-use crate::market::{MarketData, TokenId};
+use crate::execution::Side;
+use crate::external::{EspnClient, Game, GameStatus};
+use crate::market::{MarketSnapshot, TokenId};
+use crate::risk::RiskManager;
 
-use super::{Strategy, TradeSignal};
+use super::sniper_race::{game_metadata, outcome_tokens};
+use super::win_model::home_win_probability;
+use super::{FastPath, SignalMetadata, Strategy, StrategyState, TradeSignal};
 
 /// Sniper strategy for sports time arbitrage.
 pub struct SniperStrategy {
     config: SniperConfig,
-    /// Cache of games we've already sniped (to avoid duplicate orders)
-    sniped_games: std::collections::HashSet<String>,
+    /// Markets we've already sniped (to avoid duplicate orders), marked
+    /// once the exchange accepts the buy
+    sniped_games: StrategyState<HashSet<String>>,
+    /// Used to size orders within the remaining risk headroom
+    risk_manager: Option<Arc<RiskManager>>,
+    /// Live scores for pre-positioning
+    game_feed: Option<Arc<EspnClient>>,
+    /// (game ID, token) pairs already pre-positioned (one entry each)
+    prepositioned: StrategyState<HashSet<(String, TokenId)>>,
 }
 
 impl SniperStrategy {
@@ -20,53 +44,233 @@ impl SniperStrategy {
     pub fn new(config: SniperConfig) -> Self {
         Self {
             config,
-            sniped_games: std::collections::HashSet::new(),
+            sniped_games: StrategyState::default(),
+            risk_manager: None,
+            game_feed: None,
+            prepositioned: StrategyState::default(),
         }
     }
 
-    /// Check if a game has already been sniped.
-    fn already_sniped(&self, game_id: &str) -> bool {
-        self.sniped_games.contains(game_id)
+    /// Check if a market has already been sniped.
+    fn already_sniped(&self, market_id: &str) -> bool {
+        self.sniped_games.with(|s| s.contains(market_id))
     }
 
-    /// Mark a game as sniped.
-    #[allow(dead_code)]
-    fn mark_sniped(&mut self, game_id: String) {
-        self.sniped_games.insert(game_id);
+    /// Mark a market as sniped.
+    fn mark_sniped(&self, market_id: &str) {
+        self.sniped_games.with(|s| s.insert(market_id.to_string()));
     }
 
-    /// Find arbitrage opportunity for a finished game.
-    fn find_opportunity(
-        &self,
-        _game_id: &str,
-        winning_token: &TokenId,
-        market_data: &MarketData,
-    ) -> Option<TradeSignal> {
-        // Get current ask price for winning token
-        let ask = market_data.get_ask(winning_token)?;
+    /// Largest order size within `base` and the current risk headroom.
+    fn capped_size(&self, base: f64, token_id: &TokenId, price: f64) -> f64 {
+        capped_size(self.risk_manager.as_deref(), base, token_id, price)
+    }
 
-        // Check if price is within our range (stale opportunity)
-        if ask < self.config.min_price || ask > self.config.max_price {
+    /// Find a market whose price lags the live win-probability model in the
+    /// closing minutes of a game.
+    fn find_preposition(&self, snapshot: &MarketSnapshot) -> Option<TradeSignal> {
+        if !self.config.preposition {
             return None;
         }
+        let espn = self.game_feed.as_ref()?;
 
-        // Calculate expected profit
-        let expected_profit = 1.0 - ask;
-        if expected_profit < self.config.min_profit {
-            return None;
+        for game in espn.try_all_games() {
+            if game.status != GameStatus::InProgress {
+                continue;
+            }
+            let Some(remaining) = game.seconds_remaining() else {
+                continue;
+            };
+            if remaining > self.config.preposition_window_secs {
+                continue;
+            }
+            let Some(home_prob) = home_win_probability(&game) else {
+                continue;
+            };
+
+            for pair in snapshot.sports_markets() {
+                for (team, token_id) in
+                    outcome_tokens(&game, pair, self.config.match_min_confidence)
+                {
+                    let Some(ask) = snapshot.get_ask(&token_id) else {
+                        continue;
+                    };
+                    if ask < self.config.min_price || ask > self.config.max_price {
+                        continue;
+                    }
+                    let prob = if team == game.home_team {
+                        home_prob
+                    } else {
+                        1.0 - home_prob
+                    };
+                    if prob - ask < self.config.preposition_min_edge {
+                        continue;
+                    }
+
+                    let key = (game.id.clone(), token_id.clone());
+                    if self.prepositioned.with(|p| p.contains(&key)) {
+                        continue;
+                    }
+                    let size = self.capped_size(
+                        self.config.order_size * self.config.preposition_size_fraction,
+                        &token_id,
+                        ask,
+                    );
+                    if size <= 0.0 {
+                        continue;
+                    }
+                    self.prepositioned.with(|p| p.insert(key));
+
+                    return Some(TradeSignal::Buy {
+                        token_id,
+                        price: ask,
+                        size,
+                        reason: format!(
+                            "preposition: {} model {:.2} vs ask {:.2} ({:.0}s left)",
+                            team, prob, ask, remaining
+                        ),
+                        metadata: game_metadata(&game)
+                            .with("team", team.as_str())
+                            .with("model_probability", prob)
+                            .with("seconds_remaining", remaining),
+                    });
+                }
+            }
         }
 
+        None
+    }
+
+    /// Find arbitrage opportunity for a finished game.
+    fn find_opportunity(
+        &self,
+        market_id: &str,
+        winning_token: &TokenId,
+        snapshot: &MarketSnapshot,
+    ) -> Option<TradeSignal> {
+        let (price, size, expected_profit) = time_arb(
+            &self.config,
+            self.risk_manager.as_deref(),
+            winning_token,
+            snapshot,
+        )?;
         Some(TradeSignal::Buy {
             token_id: winning_token.clone(),
-            price: ask,
-            size: self.config.order_size,
+            price,
+            size,
             reason: format!("time_arb: EV ${:.4}", expected_profit),
+            metadata: SignalMetadata::new().with("market_id", market_id),
         })
     }
 }
 
+/// Largest order size within `base` and the risk headroom, if known.
+fn capped_size(risk: Option<&RiskManager>, base: f64, token_id: &TokenId, price: f64) -> f64 {
+    match risk {
+        Some(risk) => base.min(risk.max_allowed(token_id, Side::Buy, price)),
+        None => base,
+    }
+}
+
+/// Price, size and expected profit per share of buying a winning token at
+/// its current ask, if the ask is still stale enough to be worth taking.
+fn time_arb(
+    config: &SniperConfig,
+    risk: Option<&RiskManager>,
+    winning_token: &TokenId,
+    snapshot: &MarketSnapshot,
+) -> Option<(f64, f64, f64)> {
+    // Get current ask price for winning token
+    let ask = snapshot.get_ask(winning_token)?;
+
+    // Check if price is within our range (stale opportunity)
+    if ask < config.min_price || ask > config.max_price {
+        return None;
+    }
+
+    // Calculate expected profit
+    let expected_profit = 1.0 - ask;
+    if expected_profit < config.min_profit {
+        return None;
+    }
+
+    let size = capped_size(risk, config.order_size, winning_token, ask);
+    if size <= 0.0 {
+        return None;
+    }
+    Some((ask, size, expected_profit))
+}
+
+/// Buys of the winner of a finished game in every market on it.
+fn finished_game_signals(
+    config: &SniperConfig,
+    risk: Option<&RiskManager>,
+    game: &Game,
+    snapshot: &MarketSnapshot,
+) -> Vec<TradeSignal> {
+    let Some(winner) = game.winner() else {
+        return Vec::new();
+    };
+    snapshot
+        .sports_markets()
+        .iter()
+        .flat_map(|pair| {
+            outcome_tokens(game, pair, config.match_min_confidence)
+                .into_iter()
+                .map(move |(team, token_id)| (pair, team, token_id))
+        })
+        .filter(|(_, team, _)| team == winner)
+        .filter_map(|(pair, _, token_id)| {
+            let (price, size, expected_profit) = time_arb(config, risk, &token_id, snapshot)?;
+            Some(TradeSignal::Buy {
+                token_id,
+                price,
+                size,
+                reason: format!(
+                    "time_arb_fast: {} won {}, EV ${:.4}",
+                    winner, game.id, expected_profit
+                ),
+                metadata: game_metadata(game)
+                    .with("team", winner)
+                    .with("market_id", pair.market_id.as_str()),
+            })
+        })
+        .collect()
+}
+
+/// Send the winner's buys through the fast path as games go final, until
+/// the feed or the engine goes away.
+async fn listen_finished(
+    config: SniperConfig,
+    risk: Option<Arc<RiskManager>>,
+    espn: Arc<EspnClient>,
+    fast_path: FastPath,
+) {
+    let mut finished = espn.subscribe_finished();
+    let mut sniped = HashSet::new();
+    loop {
+        let game = match finished.recv().await {
+            Ok(game) => game,
+            Err(RecvError::Lagged(n)) => {
+                warn!("[SNIPER] Fast path missed {} game completions", n);
+                continue;
+            }
+            Err(RecvError::Closed) => return,
+        };
+        if !sniped.insert(game.id.clone()) {
+            continue;
+        }
+        let snapshot = fast_path.snapshot();
+        for signal in finished_game_signals(&config, risk.as_deref(), &game, &snapshot) {
+            if !fast_path.send(signal) {
+                return;
+            }
+        }
+    }
+}
+
 impl Strategy for SniperStrategy {
-    fn evaluate(&self, market_data: &MarketData) -> Option<TradeSignal> {
+    fn evaluate(&self, snapshot: &MarketSnapshot) -> Option<TradeSignal> {
         // In full implementation, this would:
         // 1. Poll ESPN for finished games
         // 2. Match games to Polymarket markets
@@ -76,21 +280,22 @@ impl Strategy for SniperStrategy {
         // For now, check if we have any sports markets with stale prices
         // This is a simplified version - full ESPN integration comes later
 
-        // Get all sports markets from market data
-        for (market_id, pair) in market_data.get_sports_markets() {
+        // Get all sports markets from the snapshot
+        for pair in snapshot.sports_markets() {
             // Skip if already sniped
-            if self.already_sniped(&market_id) {
+            if self.already_sniped(&pair.market_id) {
                 continue;
             }
 
             // Check both YES and NO tokens for opportunities
             // In real implementation, we'd know which one won from ESPN
-            if let Some(signal) = self.find_opportunity(&market_id, &pair.yes_token, market_data) {
+            if let Some(signal) = self.find_opportunity(&pair.market_id, &pair.yes_token, snapshot)
+            {
                 return Some(signal);
             }
         }
 
-        None
+        self.find_preposition(snapshot)
     }
 
     fn name(&self) -> &'static str {
@@ -100,6 +305,53 @@ impl Strategy for SniperStrategy {
     fn is_active(&self) -> bool {
         self.config.enabled
     }
+
+    fn is_contested(&self) -> bool {
+        true
+    }
+
+    fn set_risk_manager(&mut self, risk_manager: Arc<RiskManager>) {
+        self.risk_manager = Some(risk_manager);
+    }
+
+    fn set_game_feed(&mut self, espn: Arc<EspnClient>) {
+        self.game_feed = Some(espn);
+    }
+
+    fn on_signal_executed(&self, signal: &TradeSignal) {
+        if let Some(market_id) = signal.metadata().get("market_id").and_then(|v| v.as_str()) {
+            self.mark_sniped(market_id);
+        }
+    }
+
+    fn on_signal_rejected(&self, signal: &TradeSignal, _reason: &str) {
+        // Let a pre-position that never went out be tried again
+        if let Some(game_id) = signal.metadata().get("game_id").and_then(|v| v.as_str()) {
+            let key = (game_id.to_string(), signal.token_id().clone());
+            self.prepositioned.with(|p| p.remove(&key));
+        }
+    }
+
+    fn set_fast_path(&mut self, fast_path: FastPath) {
+        // Race mode already fires on completions, with pre-signed orders
+        if !self.config.enabled || !self.config.fast_path || self.config.presign {
+            return;
+        }
+        let Some(espn) = self.game_feed.clone() else {
+            return;
+        };
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            warn!("[SNIPER] No async runtime - fast path disabled");
+            return;
+        };
+        info!("[SNIPER] Fast path enabled - buying winners as games go final");
+        runtime.spawn(listen_finished(
+            self.config.clone(),
+            self.risk_manager.clone(),
+            espn,
+            fast_path,
+        ));
+    }
 }
 
 #[cfg(test)]
@@ -116,10 +368,134 @@ mod tests {
     #[test]
     fn test_sniped_tracking() {
         let config = SniperConfig::default();
-        let mut sniper = SniperStrategy::new(config);
+        let sniper = SniperStrategy::new(config);
 
         assert!(!sniper.already_sniped("game1"));
-        sniper.mark_sniped("game1".to_string());
+        sniper.mark_sniped("game1");
         assert!(sniper.already_sniped("game1"));
     }
+
+    #[test]
+    fn test_executed_signal_stops_resniping() {
+        use crate::market::{MarketPair, PriceLevel};
+
+        let sniper = SniperStrategy::new(SniperConfig::default());
+        let snapshot = MarketSnapshot::new(1)
+            .with_pair(MarketPair {
+                market_id: "m1".into(),
+                yes_token: "lakers".into(),
+                no_token: "celtics".into(),
+                question: "Will the Lakers beat the Celtics?".into(),
+                category: Some("sports".into()),
+                end_date: None,
+            })
+            .with_price("lakers".into(), PriceLevel::new(0.78, 0.80));
+
+        // A rejected buy is retried on the next tick
+        let signal = sniper.evaluate(&snapshot).expect("stale winner");
+        sniper.on_signal_rejected(&signal, "risk_limits");
+        let signal = sniper.evaluate(&snapshot).expect("retried");
+
+        sniper.on_signal_executed(&signal);
+        assert!(sniper.already_sniped("m1"));
+        assert!(sniper.evaluate(&snapshot).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_preposition_buys_lagging_leader_once() {
+        use crate::external::{Game, League};
+        use crate::market::{MarketPair, PriceLevel};
+
+        let espn = Arc::new(EspnClient::new(vec![League::Nba], 1000).unwrap());
+        espn.set_games(
+            League::Nba,
+            vec![Game {
+                id: "401".into(),
+                league: League::Nba,
+                home_team: "Los Angeles Lakers".into(),
+                away_team: "Boston Celtics".into(),
+                home_score: 100,
+                away_score: 92,
+                status: GameStatus::InProgress,
+                period: 4,
+                clock_secs: 60.0,
+                observed_at_ms: 0,
+            }],
+        )
+        .await;
+
+        let mut sniper = SniperStrategy::new(SniperConfig {
+            preposition: true,
+            // Keep the final-result path from taking this market
+            min_profit: 0.40,
+            ..SniperConfig::default()
+        });
+        sniper.set_game_feed(espn);
+
+        let snapshot = MarketSnapshot::new(1)
+            .with_pair(MarketPair {
+                market_id: "m1".into(),
+                yes_token: "lakers".into(),
+                no_token: "celtics".into(),
+                question: "Will the Lakers beat the Celtics?".into(),
+                category: Some("sports".into()),
+                end_date: None,
+            })
+            .with_price("lakers".into(), PriceLevel::new(0.68, 0.70));
+
+        match sniper.evaluate(&snapshot) {
+            Some(TradeSignal::Buy { token_id, size, .. }) => {
+                assert_eq!(token_id, "lakers");
+                assert!((size - 2.5).abs() < 1e-9);
+            }
+            other => panic!("unexpected signal: {:?}", other),
+        }
+        assert!(sniper.evaluate(&snapshot).is_none());
+    }
+
+    #[test]
+    fn test_finished_game_buys_winner_only() {
+        use crate::external::{Game, League};
+        use crate::market::{MarketPair, PriceLevel};
+
+        let game = Game {
+            id: "401".into(),
+            league: League::Nba,
+            home_team: "Los Angeles Lakers".into(),
+            away_team: "Boston Celtics".into(),
+            home_score: 98,
+            away_score: 104,
+            status: GameStatus::Final,
+            period: 4,
+            clock_secs: 0.0,
+            observed_at_ms: 0,
+        };
+        let snapshot = MarketSnapshot::new(1)
+            .with_pair(MarketPair {
+                market_id: "m1".into(),
+                yes_token: "lakers".into(),
+                no_token: "celtics".into(),
+                question: "Will the Lakers beat the Celtics?".into(),
+                category: Some("sports".into()),
+                end_date: None,
+            })
+            .with_price("lakers".into(), PriceLevel::new(0.30, 0.32))
+            .with_price("celtics".into(), PriceLevel::new(0.78, 0.80));
+
+        let config = SniperConfig::default();
+        let signals = finished_game_signals(&config, None, &game, &snapshot);
+        match signals.as_slice() {
+            [TradeSignal::Buy {
+                token_id, price, ..
+            }] => {
+                assert_eq!(token_id, "celtics");
+                assert!((price - 0.80).abs() < 1e-9);
+            }
+            other => panic!("unexpected signals: {:?}", other),
+        }
+
+        // Already repriced: nothing left to take
+        let repriced = snapshot.with_price("celtics".into(), PriceLevel::new(0.98, 0.99));
+        assert!(finished_game_signals(&config, None, &game, &repriced).is_empty());
+    }
 }
@@ -0,0 +1,317 @@
+//! Sniper race mode (`SNIPER_PRESIGN`).
+//!
+//! For time arbitrage the first order in wins, so the exact price matters
+//! less than latency. While a game is in progress we pre-sign a buy at
+//! `SNIPER_MAX_PRICE` for each team's winning token; when ESPN reports the
+//! game final, the winner's order is submitted as-is, with no signing or
+//! serialization on the critical path. Templates are re-signed once they
+//! are older than `SNIPER_PRESIGN_MAX_AGE_MS`.
+//!
+//! Markets are matched to games by team name (see `external::teams`): a
+//! market whose question mentions both teams is assumed to resolve YES for
+//! the team named first.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::cluster::LeaderElection;
+use crate::config::SniperConfig;
+use crate::execution::{OrderManager, Side, SignedOrder};
+use crate::external::{teams, EspnClient, Game, GameStatus, League};
+use crate::market::{MarketData, MarketPair, TokenId};
+use crate::risk::RiskManager;
+
+use super::{SignalMetadata, TradeSignal, EVENT_MS};
+
+/// Pre-signs and fires Sniper orders on game completion.
+pub struct SniperRacer {
+    config: SniperConfig,
+    market_data: Arc<MarketData>,
+    risk_manager: Arc<RiskManager>,
+    order_manager: Arc<OrderManager>,
+    leader: Option<Arc<LeaderElection>>,
+    /// Game ID -> winning team -> order to submit if that team wins
+    armed: Mutex<HashMap<String, HashMap<String, SignedOrder>>>,
+}
+
+impl SniperRacer {
+    /// Create a racer.
+    pub fn new(
+        config: SniperConfig,
+        market_data: Arc<MarketData>,
+        risk_manager: Arc<RiskManager>,
+        order_manager: Arc<OrderManager>,
+    ) -> Self {
+        Self {
+            config,
+            market_data,
+            risk_manager,
+            order_manager,
+            leader: None,
+            armed: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Only fire while this instance is the leader.
+    pub fn set_leader_election(&mut self, leader: Arc<LeaderElection>) {
+        self.leader = Some(leader);
+    }
+
+    /// Arm in-progress games and fire on completions until cancelled.
+    pub async fn run(self: Arc<Self>, espn: Arc<EspnClient>, cancel: CancellationToken) {
+        let leagues: Vec<League> = self
+            .config
+            .leagues
+            .iter()
+            .filter_map(|name| League::from_name(name))
+            .collect();
+        info!(
+            "[SNIPER] Race mode enabled for {:?} (limit ${:.2}, re-sign after {}ms)",
+            leagues, self.config.max_price, self.config.presign_max_age_ms
+        );
+
+        let mut finished = espn.subscribe_finished();
+        let mut arm_ticker =
+            tokio::time::interval(Duration::from_millis(self.config.poll_interval_ms));
+
+        loop {
+            tokio::select! {
+                game = finished.recv() => match game {
+                    Ok(game) => self.fire(&game).await,
+                    Err(RecvError::Lagged(n)) => warn!("[SNIPER] Missed {} game completions", n),
+                    Err(RecvError::Closed) => break,
+                },
+                _ = arm_ticker.tick() => {
+                    for league in &leagues {
+                        let games = espn.get_games(*league).await;
+                        self.refresh(&games).await;
+                    }
+                }
+                _ = cancel.cancelled() => break,
+            }
+        }
+    }
+
+    /// Arm (or re-sign) orders for in-progress games and drop games that are
+    /// no longer in progress.
+    async fn refresh(&self, games: &[Game]) {
+        let max_age = Duration::from_millis(self.config.presign_max_age_ms);
+        let pairs: Vec<MarketPair> = self.market_data.iter_pairs().collect();
+
+        for game in games.iter().filter(|g| g.status == GameStatus::InProgress) {
+            let min_confidence = self.config.match_min_confidence;
+            for (team, token_id) in pairs
+                .iter()
+                .flat_map(|pair| outcome_tokens(game, pair, min_confidence))
+            {
+                let fresh = self
+                    .armed
+                    .lock()
+                    .get(&game.id)
+                    .and_then(|orders| orders.get(&team))
+                    .is_some_and(|order| order.age() < max_age);
+                if fresh {
+                    continue;
+                }
+
+                let price = self.config.max_price;
+                let size = self.config.order_size.min(self.risk_manager.max_allowed(
+                    &token_id,
+                    Side::Buy,
+                    price,
+                ));
+                if size <= 0.0 {
+                    continue;
+                }
+
+                match self.order_manager.presign_buy(&token_id, price, size).await {
+                    Ok(order) => {
+                        debug!(
+                            "[SNIPER] Armed {} {} -> {} @ ${:.2} x {:.2}",
+                            game.id, team, token_id, price, size
+                        );
+                        self.armed
+                            .lock()
+                            .entry(game.id.clone())
+                            .or_default()
+                            .insert(team, order);
+                    }
+                    Err(e) => warn!("[SNIPER] Failed to pre-sign order for {}: {}", game.id, e),
+                }
+            }
+        }
+
+        // Finished games are removed by `fire`; drop any other game from
+        // this league that is no longer in progress (postponed, cancelled)
+        self.armed.lock().retain(|id, _| {
+            games
+                .iter()
+                .find(|g| &g.id == id)
+                .is_none_or(|g| g.status == GameStatus::InProgress)
+        });
+    }
+
+    /// Submit the pre-signed order for the winner of a finished game.
+    async fn fire(&self, game: &Game) {
+        let Some(mut orders) = self.armed.lock().remove(&game.id) else {
+            return;
+        };
+        let Some(order) = game.winner().and_then(|winner| orders.remove(winner)) else {
+            debug!("[SNIPER] No armed order for winner of {}", game.id);
+            return;
+        };
+        if self.leader.as_ref().is_some_and(|l| !l.is_leader()) {
+            debug!("[SNIPER] Standby - not firing for {}", game.id);
+            return;
+        }
+
+        let signal = TradeSignal::Buy {
+            token_id: order.token_id.clone(),
+            price: order.price,
+            size: order.size,
+            reason: format!(
+                "time_arb_race: {} won {}",
+                game.winner().unwrap_or("?"),
+                game.id
+            ),
+            metadata: game_metadata(game),
+        };
+        if !self.risk_manager.check_signal(&signal) {
+            return;
+        }
+
+        let event_age_ms = signal.metadata().event_age_ms();
+        match self.order_manager.submit_presigned(&order, event_age_ms).await {
+            Ok(order_id) => {
+                self.risk_manager.record_trade(&signal);
+                info!(
+                    "[SNIPER] Race order {} fired for {} ({} @ ${:.2} x {:.2}, signed {}ms earlier)",
+                    order_id,
+                    game.id,
+                    order.token_id,
+                    order.price,
+                    order.size,
+                    order.age().as_millis()
+                );
+            }
+            Err(e) => warn!("[SNIPER] Race order for {} failed: {}", game.id, e),
+        }
+    }
+}
+
+/// Metadata identifying the game a Sniper signal trades on.
+pub(super) fn game_metadata(game: &Game) -> SignalMetadata {
+    SignalMetadata::new()
+        .with("game_id", game.id.as_str())
+        .with("league", game.league.name())
+        .with("home_team", game.home_team.as_str())
+        .with("away_team", game.away_team.as_str())
+        .with("score", format!("{}-{}", game.home_score, game.away_score))
+        .with(EVENT_MS, game.observed_at_ms)
+}
+
+/// Winning token for each team if `pair` is a market on `game`.
+///
+/// The market must mention both teams with at least `min_confidence`; the
+/// team named first is taken to be the YES side.
+pub(super) fn outcome_tokens(
+    game: &Game,
+    pair: &MarketPair,
+    min_confidence: f64,
+) -> Vec<(String, TokenId)> {
+    let find = |team: &str| {
+        teams::find_team(game.league, &pair.question, team)
+            .filter(|m| m.confidence >= min_confidence)
+    };
+    let (Some(home), Some(away)) = (find(&game.home_team), find(&game.away_team)) else {
+        return Vec::new();
+    };
+    if home.position == away.position {
+        // Both names resolved to the same words (e.g. a shared alias)
+        return Vec::new();
+    }
+
+    let (yes_team, no_team) = if home.position < away.position {
+        (&game.home_team, &game.away_team)
+    } else {
+        (&game.away_team, &game.home_team)
+    };
+    vec![
+        (yes_team.clone(), pair.yes_token.clone()),
+        (no_team.clone(), pair.no_token.clone()),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game() -> Game {
+        Game {
+            id: "401".into(),
+            league: League::Nba,
+            home_team: "Los Angeles Lakers".into(),
+            away_team: "Boston Celtics".into(),
+            home_score: 0,
+            away_score: 0,
+            status: GameStatus::InProgress,
+            period: 4,
+            clock_secs: 120.0,
+            observed_at_ms: 0,
+        }
+    }
+
+    fn pair(question: &str) -> MarketPair {
+        MarketPair {
+            market_id: "m1".into(),
+            yes_token: "yes".into(),
+            no_token: "no".into(),
+            question: question.into(),
+            category: Some("sports".into()),
+            end_date: None,
+        }
+    }
+
+    #[test]
+    fn test_metadata_carries_event_age() {
+        let mut game = game();
+        game.observed_at_ms = crate::redis::now_ms() - 1_500;
+        let age = game_metadata(&game).event_age_ms().unwrap();
+        assert!((1_500..60_000).contains(&age));
+
+        assert_eq!(SignalMetadata::new().event_age_ms(), None);
+    }
+
+    #[test]
+    fn test_first_named_team_is_yes() {
+        let tokens = outcome_tokens(&game(), &pair("Will the Celtics beat the Lakers?"), 0.7);
+        assert_eq!(
+            tokens,
+            vec![
+                ("Boston Celtics".to_string(), "yes".to_string()),
+                ("Los Angeles Lakers".to_string(), "no".to_string()),
+            ]
+        );
+
+        let tokens = outcome_tokens(&game(), &pair("LA Lakers vs. Boston Celtics"), 0.7);
+        assert_eq!(
+            tokens[0],
+            ("Los Angeles Lakers".to_string(), "yes".to_string())
+        );
+    }
+
+    #[test]
+    fn test_unrelated_market_is_not_matched() {
+        assert!(outcome_tokens(&game(), &pair("Will the Lakers win the title?"), 0.7).is_empty());
+        // Typo matches are only used when the threshold allows them
+        let typo = pair("Will the Celtic beat the Lakers?");
+        assert!(outcome_tokens(&game(), &typo, 0.7).is_empty());
+        assert_eq!(outcome_tokens(&game(), &typo, 0.5).len(), 2);
+    }
+}
@@ -0,0 +1,396 @@
+//! Spread-capture Clipper - YES+NO arbitrage entered with a resting bid.
+//!
+//! Instead of crossing both asks, rests a bid slightly inside the spread on
+//! the cheaper side and only crosses the other side once that bid fills.
+//! Entry is cheaper (no taker fee and no spread on the first leg) but the
+//! position is one-sided until the second leg executes, and the second ask
+//! may have moved by then.
+//!
+//! Each market moves through a small leg state machine:
+//! idle -> resting -> hedging -> idle, or resting -> cancelling -> idle when
+//! the bid times out or the opportunity disappears.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use tracing::{info, warn};
+
+use crate::config::ClipperConfig;
+use crate::execution::Side;
+use crate::market::{MarketId, MarketSnapshot, TokenId};
+use crate::risk::RiskManager;
+
+use super::{CostModel, SignalMetadata, Strategy, StrategyState, TradeSignal};
+
+/// Leg state for one market
+#[derive(Debug, Clone, PartialEq)]
+enum Leg {
+    /// Bid resting on `rest_token`; `hedge_token` is crossed once it fills
+    Resting {
+        rest_token: TokenId,
+        hedge_token: TokenId,
+        price: f64,
+        since_ns: u64,
+    },
+    /// Cancel sent; waiting for the engine to report any fill
+    Cancelling {
+        rest_token: TokenId,
+        hedge_token: TokenId,
+        since_ns: u64,
+    },
+    /// First leg filled `size`; cross the hedge leg
+    Hedging { hedge_token: TokenId, size: f64 },
+}
+
+/// Clipper variant that rests the first leg and crosses the second.
+pub struct SpreadClipperStrategy {
+    config: ClipperConfig,
+    /// Used to size trades within the remaining risk headroom
+    risk_manager: Option<Arc<RiskManager>>,
+    /// Markets with a leg in progress
+    legs: StrategyState<HashMap<MarketId, Leg>>,
+    cost_model: CostModel,
+}
+
+impl SpreadClipperStrategy {
+    /// Create a new spread-capture clipper.
+    pub fn new(config: ClipperConfig) -> Self {
+        Self {
+            config,
+            risk_manager: None,
+            legs: StrategyState::default(),
+            cost_model: CostModel::default(),
+        }
+    }
+
+    /// Advance markets with a leg in progress; returns the first signal.
+    fn manage_legs(&self, snapshot: &MarketSnapshot) -> Option<TradeSignal> {
+        let now = snapshot.timestamp_ns();
+        let timeout_ns = self.config.spread_rest_timeout_ms * 1_000_000;
+        self.legs.with(|legs| {
+            let mut finished = Vec::new();
+            let mut signal = None;
+            for (market_id, leg) in legs.iter_mut() {
+                match leg.clone() {
+                    Leg::Hedging { hedge_token, size } => {
+                        let Some(ask) = snapshot.get_ask(&hedge_token) else {
+                            continue;
+                        };
+                        info!(
+                            "[CLIPPER] Spread capture: bid filled on {}, crossing hedge @ ${:.4} x {:.2}",
+                            market_id, ask, size
+                        );
+                        finished.push(market_id.clone());
+                        signal = Some(TradeSignal::Buy {
+                            token_id: hedge_token,
+                            price: ask,
+                            size,
+                            reason: format!("Spread capture hedge for {}", market_id),
+                            metadata: SignalMetadata::new()
+                                .with("market_id", market_id.as_str())
+                                .with("leg", "hedge"),
+                        });
+                        break;
+                    }
+                    Leg::Resting {
+                        rest_token,
+                        hedge_token,
+                        price,
+                        since_ns,
+                    } => {
+                        let expired = now.saturating_sub(since_ns) >= timeout_ns;
+                        let still_profitable = snapshot
+                            .get_ask(&hedge_token)
+                            .is_some_and(|ask| self.net_profit(price, ask) >= self.config.min_profit);
+                        if !expired && still_profitable {
+                            continue;
+                        }
+                        let reason = if expired {
+                            "bid not filled in time"
+                        } else {
+                            "opportunity gone"
+                        };
+                        *leg = Leg::Cancelling {
+                            rest_token: rest_token.clone(),
+                            hedge_token,
+                            since_ns: now,
+                        };
+                        signal = Some(TradeSignal::Cancel {
+                            token_id: rest_token,
+                            reason: format!("Spread capture on {}: {}", market_id, reason),
+                        });
+                        break;
+                    }
+                    Leg::Cancelling { since_ns, .. } => {
+                        // The outcome never arrived (e.g. the engine is on standby)
+                        if now.saturating_sub(since_ns) >= timeout_ns {
+                            warn!(
+                                "[CLIPPER] Spread capture on {}: no cancel outcome, giving up",
+                                market_id
+                            );
+                            finished.push(market_id.clone());
+                        }
+                    }
+                }
+            }
+
+            for market_id in finished {
+                legs.remove(&market_id);
+            }
+            signal
+        })
+    }
+
+    /// Find a market to rest a new bid in.
+    fn find_entry(&self, snapshot: &MarketSnapshot) -> Option<TradeSignal> {
+        self.legs.with(|legs| {
+            for pair in snapshot.pairs() {
+                if legs.contains_key(&pair.market_id) {
+                    continue;
+                }
+                let (Some(yes_ask), Some(no_ask)) = (
+                    snapshot.get_ask(&pair.yes_token),
+                    snapshot.get_ask(&pair.no_token),
+                ) else {
+                    continue;
+                };
+
+                // Rest on the cheaper side, cross the other
+                let (rest_token, hedge_token, rest_ask, hedge_ask) = if yes_ask <= no_ask {
+                    (&pair.yes_token, &pair.no_token, yes_ask, no_ask)
+                } else {
+                    (&pair.no_token, &pair.yes_token, no_ask, yes_ask)
+                };
+                let Some(rest_bid) = snapshot.get_bid(rest_token) else {
+                    continue;
+                };
+
+                // Must improve the bid without crossing the ask
+                let price = rest_bid + self.config.spread_improve;
+                if price >= rest_ask || price <= 0.0 {
+                    continue;
+                }
+                let net_profit = self.net_profit(price, hedge_ask);
+                if net_profit < self.config.min_profit {
+                    continue;
+                }
+
+                let cost = price + hedge_ask;
+                let mut size = self
+                    .config
+                    .max_position
+                    .min(self.config.max_notional / cost);
+                if let Some(risk) = &self.risk_manager {
+                    size = size.min(risk.max_allowed(rest_token, Side::Buy, cost));
+                }
+                if size <= 0.0 {
+                    continue;
+                }
+
+                legs.insert(
+                    pair.market_id.clone(),
+                    Leg::Resting {
+                        rest_token: rest_token.clone(),
+                        hedge_token: hedge_token.clone(),
+                        price,
+                        since_ns: snapshot.timestamp_ns(),
+                    },
+                );
+                return Some(TradeSignal::Bid {
+                    token_id: rest_token.clone(),
+                    price,
+                    size,
+                    reason: format!(
+                        "Spread capture on {}: rest @ ${:.4}, hedge ask ${:.4}, net ${:.4}/share",
+                        pair.market_id, price, hedge_ask, net_profit
+                    ),
+                    metadata: SignalMetadata::new()
+                        .with("market_id", pair.market_id.as_str())
+                        .with("leg", "rest")
+                        .with("hedge_ask", hedge_ask),
+                });
+            }
+            None
+        })
+    }
+
+    /// Profit per share after the maker fee on the resting leg and the
+    /// taker fee and slippage on the crossing leg.
+    fn net_profit(&self, rest_price: f64, hedge_ask: f64) -> f64 {
+        self.cost_model.spread_capture_edge(rest_price, hedge_ask)
+    }
+}
+
+impl Strategy for SpreadClipperStrategy {
+    fn evaluate(&self, snapshot: &MarketSnapshot) -> Option<TradeSignal> {
+        self.manage_legs(snapshot)
+            .or_else(|| self.find_entry(snapshot))
+    }
+
+    fn name(&self) -> &'static str {
+        "SpreadClipper"
+    }
+
+    fn is_active(&self) -> bool {
+        self.config.spread_enabled
+    }
+
+    fn set_risk_manager(&mut self, risk_manager: Arc<RiskManager>) {
+        self.risk_manager = Some(risk_manager);
+    }
+
+    fn set_cost_model(&mut self, cost_model: CostModel) {
+        self.cost_model = cost_model;
+    }
+
+    fn on_bid_done(&self, token_id: &TokenId, filled: f64) {
+        self.legs.with(|legs| {
+            let Some((market_id, hedge_token)) =
+                legs.iter().find_map(|(market_id, leg)| match leg {
+                    Leg::Resting {
+                        rest_token,
+                        hedge_token,
+                        ..
+                    }
+                    | Leg::Cancelling {
+                        rest_token,
+                        hedge_token,
+                        ..
+                    } if rest_token == token_id => Some((market_id.clone(), hedge_token.clone())),
+                    _ => None,
+                })
+            else {
+                return;
+            };
+
+            if filled > 0.0 {
+                legs.insert(
+                    market_id,
+                    Leg::Hedging {
+                        hedge_token,
+                        size: filled,
+                    },
+                );
+            } else {
+                legs.remove(&market_id);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market::{MarketPair, PriceLevel};
+
+    fn config() -> ClipperConfig {
+        ClipperConfig {
+            min_profit: 0.01,
+            max_position: 100.0,
+            max_notional: 1000.0,
+            spread_enabled: true,
+            spread_improve: 0.01,
+            spread_rest_timeout_ms: 1000,
+            ..ClipperConfig::default()
+        }
+    }
+
+    /// YES 0.40/0.45, NO 0.48/0.52: crossing costs 0.97, resting YES @ 0.41
+    /// and crossing NO costs 0.93
+    fn snapshot(timestamp_ns: u64, no_ask: f64) -> MarketSnapshot {
+        MarketSnapshot::new(timestamp_ns)
+            .with_pair(MarketPair {
+                market_id: "m1".into(),
+                yes_token: "yes".into(),
+                no_token: "no".into(),
+                question: "Q?".into(),
+                category: None,
+                end_date: None,
+            })
+            .with_price("yes".into(), PriceLevel::new(0.40, 0.45))
+            .with_price("no".into(), PriceLevel::new(0.48, no_ask))
+    }
+
+    #[test]
+    fn test_rests_cheaper_side_then_hedges_after_fill() {
+        let strategy = SpreadClipperStrategy::new(config());
+
+        match strategy.evaluate(&snapshot(0, 0.52)) {
+            Some(TradeSignal::Bid {
+                token_id, price, ..
+            }) => {
+                assert_eq!(token_id, "yes");
+                assert!((price - 0.41).abs() < 1e-9);
+            }
+            other => panic!("unexpected signal: {:?}", other),
+        }
+        // Waiting for the fill
+        assert!(strategy.evaluate(&snapshot(1, 0.52)).is_none());
+
+        strategy.on_bid_done(&"yes".to_string(), 40.0);
+        match strategy.evaluate(&snapshot(2, 0.53)) {
+            Some(TradeSignal::Buy {
+                token_id,
+                price,
+                size,
+                ..
+            }) => {
+                assert_eq!(token_id, "no");
+                assert_eq!(price, 0.53);
+                assert_eq!(size, 40.0);
+            }
+            other => panic!("unexpected signal: {:?}", other),
+        }
+        assert!(strategy.legs.get().is_empty());
+    }
+
+    #[test]
+    fn test_cancels_when_bid_times_out_or_edge_disappears() {
+        let strategy = SpreadClipperStrategy::new(config());
+        assert!(strategy.evaluate(&snapshot(0, 0.52)).is_some());
+
+        // Hedge ask jumped: 1 - 0.41 - 0.60 is a loss
+        assert!(matches!(
+            strategy.evaluate(&snapshot(1, 0.60)),
+            Some(TradeSignal::Cancel { ref token_id, .. }) if token_id == "yes"
+        ));
+        strategy.on_bid_done(&"yes".to_string(), 0.0);
+        assert!(strategy.legs.get().is_empty());
+
+        // Rest again, then time out after 1s
+        assert!(strategy.evaluate(&snapshot(2, 0.52)).is_some());
+        assert!(strategy.evaluate(&snapshot(500_000_000, 0.52)).is_none());
+        assert!(matches!(
+            strategy.evaluate(&snapshot(1_000_000_002, 0.52)),
+            Some(TradeSignal::Cancel { .. })
+        ));
+
+        // A fill that raced the cancel is still hedged
+        strategy.on_bid_done(&"yes".to_string(), 5.0);
+        assert!(matches!(
+            strategy.evaluate(&snapshot(1_000_000_003, 0.52)),
+            Some(TradeSignal::Buy { size, .. }) if size == 5.0
+        ));
+    }
+
+    #[test]
+    fn test_skips_unprofitable_and_crossed_spreads() {
+        let strategy = SpreadClipperStrategy::new(config());
+        // 1 - 0.41 - 0.59 leaves nothing
+        assert!(strategy.evaluate(&snapshot(0, 0.59)).is_none());
+
+        // Improving the bid would cross the ask
+        let tight = MarketSnapshot::new(0)
+            .with_pair(MarketPair {
+                market_id: "m1".into(),
+                yes_token: "yes".into(),
+                no_token: "no".into(),
+                question: "Q?".into(),
+                category: None,
+                end_date: None,
+            })
+            .with_price("yes".into(), PriceLevel::new(0.445, 0.45))
+            .with_price("no".into(), PriceLevel::new(0.48, 0.50));
+        assert!(strategy.evaluate(&tight).is_none());
+    }
+}
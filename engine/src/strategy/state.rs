@@ -0,0 +1,75 @@
+//! Interior-mutable state for strategies.
+//!
+//! `Strategy` methods take `&self`: the engine holds strategies immutably
+//! while signals from one tick execute concurrently, and execution feedback
+//! (`on_bid_done`, `on_signal_executed`, ...) arrives while other signals are
+//! still in flight. Strategies that keep caches or state machines (sniped
+//! markets, resting legs) hold them in a `StrategyState` rather than taking
+//! `&mut self` or reaching for their own locks. Plain counters and
+//! timestamps can stay atomics.
+
+use std::fmt;
+
+use parking_lot::Mutex;
+
+/// Mutable strategy state behind a lock that is only held for the duration
+/// of a closure, so a guard can never outlive a call or be held across an
+/// `.await`.
+#[derive(Default)]
+pub struct StrategyState<T>(Mutex<T>);
+
+impl<T> StrategyState<T> {
+    /// Wrap initial state
+    #[allow(dead_code)]
+    pub fn new(value: T) -> Self {
+        Self(Mutex::new(value))
+    }
+
+    /// Run `f` with exclusive access to the state.
+    ///
+    /// Do not call back into the engine or another strategy from `f`.
+    pub fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        f(&mut self.0.lock())
+    }
+
+    /// A copy of the current state
+    #[allow(dead_code)]
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.0.lock().clone()
+    }
+
+    /// Replace the state, returning the previous value
+    #[allow(dead_code)]
+    pub fn replace(&self, value: T) -> T {
+        std::mem::replace(&mut self.0.lock(), value)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for StrategyState<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("StrategyState")
+            .field(&*self.0.lock())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_mutates_in_place() {
+        let state = StrategyState::new(Vec::new());
+        state.with(|v| v.push(1));
+        let len = state.with(|v| {
+            v.push(2);
+            v.len()
+        });
+        assert_eq!(len, 2);
+        assert_eq!(state.replace(Vec::new()), vec![1, 2]);
+        assert!(state.get().is_empty());
+    }
+}
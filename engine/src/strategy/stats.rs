@@ -0,0 +1,211 @@
+//! Per-strategy execution statistics.
+//!
+//! The engine's own counters only say how many evaluations and signals there
+//! were in total, which hides a strategy whose evaluation has grown slow or
+//! whose signals are all being rejected. Each strategy gets its evaluation
+//! count and duration, signals generated, signals executed (sent to the
+//! order manager) and signals rejected (below the minimum size or by risk).
+//! Counters and a duration histogram are exported per strategy; the
+//! heartbeat and `EngineState` carry percentiles over the last
+//! `DURATION_WINDOW` evaluations.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use serde::Serialize;
+
+use crate::metrics::{STRATEGY_EVALUATIONS, STRATEGY_EVAL_DURATION, STRATEGY_SIGNAL_OUTCOMES};
+
+/// Evaluation durations kept per strategy for percentiles
+const DURATION_WINDOW: usize = 1024;
+
+/// What became of a signal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignalOutcome {
+    /// Sent to the order manager (or a slicer)
+    Executed,
+    /// Dropped for size or by risk
+    Rejected,
+}
+
+impl SignalOutcome {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SignalOutcome::Executed => "executed",
+            SignalOutcome::Rejected => "rejected",
+        }
+    }
+}
+
+/// Point-in-time statistics of one strategy
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StrategyStatsSnapshot {
+    pub strategy: &'static str,
+    pub evals: u64,
+    pub eval_p50_us: f64,
+    pub eval_p99_us: f64,
+    pub signals: u64,
+    pub executed: u64,
+    pub rejected: u64,
+}
+
+impl StrategyStatsSnapshot {
+    /// Heartbeat log segment
+    pub fn summary(&self) -> String {
+        format!(
+            "{}: evals={} eval_p50={:.0}us eval_p99={:.0}us signals={} executed={} rejected={}",
+            self.strategy,
+            self.evals,
+            self.eval_p50_us,
+            self.eval_p99_us,
+            self.signals,
+            self.executed,
+            self.rejected
+        )
+    }
+}
+
+/// Counters of one strategy
+struct StrategyStats {
+    name: &'static str,
+    evals: AtomicU64,
+    signals: AtomicU64,
+    executed: AtomicU64,
+    rejected: AtomicU64,
+    /// Recent evaluation durations in microseconds
+    durations_us: Mutex<VecDeque<f64>>,
+}
+
+impl StrategyStats {
+    fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            evals: AtomicU64::new(0),
+            signals: AtomicU64::new(0),
+            executed: AtomicU64::new(0),
+            rejected: AtomicU64::new(0),
+            durations_us: Mutex::new(VecDeque::with_capacity(DURATION_WINDOW)),
+        }
+    }
+
+    fn snapshot(&self) -> StrategyStatsSnapshot {
+        let mut durations: Vec<f64> = self.durations_us.lock().iter().copied().collect();
+        durations.sort_by(f64::total_cmp);
+        StrategyStatsSnapshot {
+            strategy: self.name,
+            evals: self.evals.load(Ordering::Relaxed),
+            eval_p50_us: percentile(&durations, 0.50),
+            eval_p99_us: percentile(&durations, 0.99),
+            signals: self.signals.load(Ordering::Relaxed),
+            executed: self.executed.load(Ordering::Relaxed),
+            rejected: self.rejected.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Nearest-rank percentile of sorted values (0 when empty)
+fn percentile(sorted: &[f64], q: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = (q * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Statistics of every strategy in the engine
+#[derive(Default)]
+pub struct ExecutionStats {
+    strategies: Vec<StrategyStats>,
+}
+
+impl ExecutionStats {
+    /// Start tracking a strategy (no-op if already tracked).
+    pub fn register(&mut self, name: &'static str) {
+        if self.get(name).is_none() {
+            self.strategies.push(StrategyStats::new(name));
+        }
+    }
+
+    fn get(&self, name: &str) -> Option<&StrategyStats> {
+        self.strategies.iter().find(|s| s.name == name)
+    }
+
+    /// Record one evaluation and how many signals it produced.
+    pub fn record_eval(&self, name: &'static str, duration: Duration, signals: u64) {
+        let Some(stats) = self.get(name) else {
+            return;
+        };
+        stats.evals.fetch_add(1, Ordering::Relaxed);
+        stats.signals.fetch_add(signals, Ordering::Relaxed);
+        {
+            let mut durations = stats.durations_us.lock();
+            if durations.len() == DURATION_WINDOW {
+                durations.pop_front();
+            }
+            durations.push_back(duration.as_secs_f64() * 1e6);
+        }
+        STRATEGY_EVALUATIONS.with_label_values(&[name]).inc();
+        STRATEGY_EVAL_DURATION
+            .with_label_values(&[name])
+            .observe(duration.as_secs_f64());
+    }
+
+    /// Record what became of a signal.
+    pub fn record_outcome(&self, name: &'static str, outcome: SignalOutcome) {
+        let Some(stats) = self.get(name) else {
+            return;
+        };
+        match outcome {
+            SignalOutcome::Executed => stats.executed.fetch_add(1, Ordering::Relaxed),
+            SignalOutcome::Rejected => stats.rejected.fetch_add(1, Ordering::Relaxed),
+        };
+        STRATEGY_SIGNAL_OUTCOMES
+            .with_label_values(&[name, outcome.as_str()])
+            .inc();
+    }
+
+    /// Statistics of every strategy, in the order they were added
+    pub fn snapshot(&self) -> Vec<StrategyStatsSnapshot> {
+        self.strategies
+            .iter()
+            .map(StrategyStats::snapshot)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_records_per_strategy() {
+        let mut stats = ExecutionStats::default();
+        stats.register("StatsA");
+        stats.register("StatsB");
+        stats.register("StatsA");
+
+        for us in 1..=100 {
+            stats.record_eval("StatsA", Duration::from_micros(us), (us % 10 == 0) as u64);
+        }
+        stats.record_outcome("StatsA", SignalOutcome::Executed);
+        stats.record_outcome("StatsA", SignalOutcome::Rejected);
+        stats.record_outcome("StatsA", SignalOutcome::Rejected);
+        // Unknown strategies are ignored
+        stats.record_eval("Unknown", Duration::from_micros(5), 1);
+
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.len(), 2);
+        let a = &snapshot[0];
+        assert_eq!(
+            (a.evals, a.signals, a.executed, a.rejected),
+            (100, 10, 1, 2)
+        );
+        assert!((a.eval_p50_us - 50.0).abs() < 1e-6);
+        assert!((a.eval_p99_us - 99.0).abs() < 1e-6);
+        assert_eq!(snapshot[1].evals, 0);
+        assert_eq!(snapshot[1].eval_p99_us, 0.0);
+        assert!(a.summary().starts_with("StatsA: evals=100 eval_p50=50us"));
+    }
+}
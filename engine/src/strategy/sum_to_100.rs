@@ -1,27 +1,37 @@
 //! Sum-to-100 Arbitrage Strategy
 //!
-//! Exploits markets where YES_ask + NO_ask < 1.00.
+//! Exploits markets where YES_ask + NO_ask < 1.00 and, with `sell_enabled`,
+//! sells held pairs where YES_bid + NO_bid > 1.00.
 //! Uses VWAP calculations for depth-aware pricing.
 
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use tracing::info;
 
-use crate::analysis::SumDeviationAnalyzer;
+use crate::analysis::{SumDeviationAnalyzer, SumDeviationOpportunity};
 use crate::config::SumTo100Config;
-use crate::market::MarketData;
+use crate::db::AnalyticsSink;
+use crate::execution::Side;
+use crate::market::MarketSnapshot;
+use crate::redis::RedisPublisher;
+use crate::risk::RiskManager;
 
-use super::{Strategy, TradeSignal};
+use super::{CostModel, SignalMetadata, Strategy, TradeSignal};
 
 /// SumTo100 arbitrage strategy
 pub struct SumTo100Strategy {
+    /// "SumTo100", or "SumTo100@<instance>" for a named instance
+    name: &'static str,
     config: SumTo100Config,
     analyzer: SumDeviationAnalyzer,
     /// Last evaluation timestamp (for rate limiting)
     last_evaluation_ns: AtomicU64,
     /// Minimum interval between evaluations (nanoseconds)
     min_interval_ns: u64,
+    /// Used to size trades within the remaining risk headroom
+    risk_manager: Option<Arc<RiskManager>>,
 }
 
 impl SumTo100Strategy {
@@ -29,10 +39,38 @@ impl SumTo100Strategy {
     pub fn new(config: SumTo100Config) -> Self {
         let analyzer = SumDeviationAnalyzer::new(config.clone());
         Self {
+            name: "SumTo100",
             config,
             analyzer,
             last_evaluation_ns: AtomicU64::new(0),
             min_interval_ns: 100_000_000, // 100ms minimum between evaluations
+            risk_manager: None,
+        }
+    }
+
+    /// Name this as a separate instance, so its signals, trades, metrics,
+    /// and risk usage are reported under "SumTo100@<instance>".
+    pub fn with_instance(mut self, instance: &str) -> Self {
+        // Built once at startup; the trait hands out `&'static str` names
+        self.name = Box::leak(format!("SumTo100@{}", instance).into_boxed_str());
+        self
+    }
+
+    /// Shrink an opportunity to what risk will accept rather than have the
+    /// signal rejected. Sells are capped to the pairs held, so without a
+    /// risk manager (no inventory) they are skipped.
+    fn tradable_size(&self, opp: &SumDeviationOpportunity) -> f64 {
+        match (opp.side, &self.risk_manager) {
+            (Side::Buy, Some(risk)) => {
+                opp.recommended_size
+                    .min(risk.max_allowed(&opp.yes_token, Side::Buy, opp.sum))
+            }
+            (Side::Buy, None) => opp.recommended_size,
+            (Side::Sell, Some(risk)) => opp
+                .recommended_size
+                .min(risk.max_allowed(&opp.yes_token, Side::Sell, opp.yes_vwap.vwap))
+                .min(risk.max_allowed(&opp.no_token, Side::Sell, opp.no_vwap.vwap)),
+            (Side::Sell, None) => 0.0,
         }
     }
 
@@ -46,7 +84,7 @@ impl SumTo100Strategy {
 }
 
 impl Strategy for SumTo100Strategy {
-    fn evaluate(&self, market_data: &MarketData) -> Option<TradeSignal> {
+    fn evaluate(&self, snapshot: &MarketSnapshot) -> Option<TradeSignal> {
         // Rate limiting: don't evaluate too frequently
         let now = Self::now_ns();
         let last = self.last_evaluation_ns.load(Ordering::Relaxed);
@@ -55,25 +93,27 @@ impl Strategy for SumTo100Strategy {
         }
         self.last_evaluation_ns.store(now, Ordering::Relaxed);
 
-        // Find opportunities
-        let opportunities = self.analyzer.analyze(market_data);
-
-        if opportunities.is_empty() {
-            return None;
-        }
-
-        // Take the best opportunity (highest edge)
-        let best = &opportunities[0];
+        // Take the best opportunity (highest edge) we can size
+        let (best, size) = self
+            .analyzer
+            .analyze(snapshot)
+            .into_iter()
+            .find_map(|opp| {
+                let size = self.tradable_size(&opp);
+                (size > 0.0).then_some((opp, size))
+            })?;
 
         // Log the opportunity
         info!(
-            "SumTo100 opportunity: {} YES@${:.4} + NO@${:.4} = ${:.4} | edge={:.2}% | size={:.0} | confidence={:.0}%",
+            "{} {}opportunity: {} YES@${:.4} + NO@${:.4} = ${:.4} | edge={:.2}% | size={:.0} | confidence={:.0}%",
+            self.name,
+            if best.side == Side::Sell { "sell " } else { "" },
             best.market_id,
             best.yes_vwap.vwap,
             best.no_vwap.vwap,
             best.sum,
             best.edge * 100.0,
-            best.recommended_size,
+            size,
             best.confidence * 100.0
         );
 
@@ -84,23 +124,45 @@ impl Strategy for SumTo100Strategy {
             yes_price: best.yes_vwap.vwap,
             no_price: best.no_vwap.vwap,
             profit_per_share: best.edge,
-            size: best.recommended_size,
+            size,
+            side: best.side,
+            metadata: SignalMetadata::new()
+                .with("market_id", best.market_id.as_str())
+                .with("confidence", best.confidence)
+                .with("sum", best.sum),
         })
     }
 
     fn name(&self) -> &'static str {
-        "SumTo100"
+        self.name
     }
 
     fn is_active(&self) -> bool {
         self.config.enabled
     }
+
+    fn set_risk_manager(&mut self, risk_manager: Arc<RiskManager>) {
+        self.risk_manager = Some(risk_manager);
+    }
+
+    fn set_cost_model(&mut self, cost_model: CostModel) {
+        self.analyzer.set_cost_model(cost_model);
+    }
+
+    fn set_redis_publisher(&mut self, publisher: Arc<RedisPublisher>) {
+        self.analyzer.set_redis_publisher(publisher);
+    }
+
+    fn set_analytics_sink(&mut self, sink: Arc<AnalyticsSink>) {
+        self.analyzer.set_analytics_sink(sink);
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::market::{DepthLevel, MarketPair};
+    use crate::config::RiskConfig;
+    use crate::market::{DepthLevel, MarketData, MarketPair};
 
     fn create_test_config() -> SumTo100Config {
         SumTo100Config {
@@ -109,9 +171,13 @@ mod tests {
             max_position: 100.0,
             max_notional: 100.0,
             min_liquidity: 10.0,
-            fee_rate: 0.01,
             paper_trading: true,
             max_book_age_ms: 60000,
+            max_participation: 1.0,
+            depth_haircut: 0.0,
+            near_miss_tolerance: 0.0,
+            categories: Vec::new(),
+            sell_enabled: false,
         }
     }
 
@@ -127,6 +193,8 @@ mod tests {
             yes_token: "yes_token".into(),
             no_token: "no_token".into(),
             question: "Test?".into(),
+            category: None,
+            end_date: None,
         };
         market_data.register_pair(pair);
 
@@ -141,7 +209,7 @@ mod tests {
             vec![DepthLevel::new(0.50, 100.0)],
         );
 
-        let signal = strategy.evaluate(&market_data);
+        let signal = strategy.evaluate(&market_data.snapshot(0));
         assert!(signal.is_some());
 
         if let Some(TradeSignal::Arbitrage {
@@ -159,6 +227,64 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_strategy_sells_held_pairs() {
+        let mut config = create_test_config();
+        config.sell_enabled = true;
+        let mut strategy = SumTo100Strategy::new(config);
+        let market_data = MarketData::new();
+        market_data.register_pair(MarketPair {
+            market_id: "test_market".into(),
+            yes_token: "yes_token".into(),
+            no_token: "no_token".into(),
+            question: "Test?".into(),
+            category: None,
+            end_date: None,
+        });
+        market_data.update_order_book(
+            &"yes_token".into(),
+            vec![DepthLevel::new(0.56, 100.0)],
+            vec![DepthLevel::new(0.57, 100.0)],
+        );
+        market_data.update_order_book(
+            &"no_token".into(),
+            vec![DepthLevel::new(0.50, 100.0)],
+            vec![DepthLevel::new(0.51, 100.0)],
+        );
+        let snapshot = market_data.snapshot(0);
+
+        // Nothing to sell without inventory
+        assert!(strategy.evaluate(&snapshot).is_none());
+
+        let risk = Arc::new(RiskManager::new(RiskConfig::default()));
+        for token_id in ["yes_token", "no_token"] {
+            risk.record_trade(&TradeSignal::Buy {
+                token_id: token_id.to_string(),
+                price: 0.45,
+                size: 30.0,
+                reason: "test".to_string(),
+                metadata: SignalMetadata::new(),
+            });
+        }
+        strategy.set_risk_manager(risk);
+        strategy.last_evaluation_ns.store(0, Ordering::Relaxed);
+
+        match strategy.evaluate(&snapshot) {
+            Some(TradeSignal::Arbitrage {
+                yes_price,
+                no_price,
+                size,
+                side: Side::Sell,
+                ..
+            }) => {
+                assert!((yes_price - 0.56).abs() < 0.001);
+                assert!((no_price - 0.50).abs() < 0.001);
+                assert!((size - 30.0).abs() < 1e-6);
+            }
+            other => panic!("Expected sell-side Arbitrage signal, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_strategy_respects_enabled() {
         let mut config = create_test_config();
@@ -1,6 +1,117 @@
 //! Strategy trait and common types.
 
-use crate::market::{MarketData, TokenId};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::db::AnalyticsSink;
+use crate::execution::Side;
+use crate::external::EspnClient;
+use crate::market::{MarketSnapshot, TokenId, WhaleMonitor};
+use crate::redis::{now_ms, RedisPublisher};
+use crate::risk::RiskManager;
+
+use super::{CostModel, FastPath};
+
+/// Metadata key of the ID the engine gives each signal it executes. Every
+/// order placed for the signal is tagged with it (see `db::OrderTag`).
+pub const SIGNAL_ID_KEY: &str = "signal_id";
+
+/// Strategy-specific context carried with a signal (a Sniper game ID, a
+/// SumTo100 opportunity's confidence) into Redis, the database and Slack.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct SignalMetadata(BTreeMap<String, Value>);
+
+/// Metadata key for when the signal's triggering event was seen (ms since epoch)
+pub const EVENT_MS: &str = "event_ms";
+
+impl SignalMetadata {
+    /// Empty metadata
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an entry, replacing any with the same key.
+    pub fn with(mut self, key: &str, value: impl Into<Value>) -> Self {
+        self.0.insert(key.to_string(), value.into());
+        self
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.0.get(key)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Time since the external event behind the signal (the `event_ms`
+    /// entry, e.g. the ESPN poll that saw a game end), if it has one.
+    pub fn event_age_ms(&self) -> Option<u64> {
+        let event_ms = self.get(EVENT_MS)?.as_u64()?;
+        Some(now_ms().saturating_sub(event_ms))
+    }
+
+    /// JSON object of the entries, or None when there are none.
+    pub fn to_json(&self) -> Option<Value> {
+        (!self.is_empty()).then(|| serde_json::to_value(self).unwrap_or_default())
+    }
+}
+
+impl From<serde_json::Map<String, Value>> for SignalMetadata {
+    fn from(entries: serde_json::Map<String, Value>) -> Self {
+        Self(entries.into_iter().collect())
+    }
+}
+
+/// One order of a `TradeSignal::Basket`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Leg {
+    pub token_id: TokenId,
+    pub side: Side,
+    pub price: f64,
+    pub size: f64,
+}
+
+impl Leg {
+    pub fn notional(&self) -> f64 {
+        self.price * self.size
+    }
+
+    /// Cash the leg moves: paid for a buy (negative), received for a sell
+    pub fn cash_flow(&self) -> f64 {
+        match self.side {
+            Side::Buy => -self.notional(),
+            Side::Sell => self.notional(),
+        }
+    }
+
+    /// The leg as a single-order signal, for position tracking and trade
+    /// records.
+    pub fn to_signal(&self, reason: &str, metadata: SignalMetadata) -> TradeSignal {
+        let (token_id, price, size) = (self.token_id.clone(), self.price, self.size);
+        let reason = reason.to_string();
+        match self.side {
+            Side::Buy => TradeSignal::Buy {
+                token_id,
+                price,
+                size,
+                reason,
+                metadata,
+            },
+            Side::Sell => TradeSignal::Sell {
+                token_id,
+                price,
+                size,
+                reason,
+                metadata,
+            },
+        }
+    }
+}
 
 /// Trade signal generated by a strategy
 #[allow(dead_code)]
@@ -12,6 +123,7 @@ pub enum TradeSignal {
         price: f64,
         size: f64,
         reason: String,
+        metadata: SignalMetadata,
     },
 
     /// Simple sell order
@@ -20,9 +132,11 @@ pub enum TradeSignal {
         price: f64,
         size: f64,
         reason: String,
+        metadata: SignalMetadata,
     },
 
-    /// Arbitrage opportunity (buy YES and NO)
+    /// Arbitrage opportunity: buy YES and NO below $1.00, or (`Side::Sell`)
+    /// sell held YES and NO above $1.00
     Arbitrage {
         yes_token: TokenId,
         no_token: TokenId,
@@ -30,17 +144,77 @@ pub enum TradeSignal {
         no_price: f64,
         profit_per_share: f64,
         size: f64,
+        side: Side,
+        metadata: SignalMetadata,
+    },
+
+    /// Resting limit buy. Unlike `Buy` it is not assumed to fill: the engine
+    /// tracks it and reports the outcome through `Strategy::on_bid_done`.
+    Bid {
+        token_id: TokenId,
+        price: f64,
+        size: f64,
+        reason: String,
+        metadata: SignalMetadata,
+    },
+
+    /// Cancel the strategy's resting bids on a token
+    Cancel { token_id: TokenId, reason: String },
+
+    /// Orders on any number of tokens, in one market or several, sent
+    /// together as a batch: e.g. every outcome of a multi-outcome market,
+    /// or offsetting positions across related markets.
+    Basket {
+        legs: Vec<Leg>,
+        /// What the basket's position change is worth at resolution, for
+        /// baskets with a known payoff (`size` for a full outcome set
+        /// bought, `-size` for one sold)
+        payout: Option<f64>,
+        /// Send every leg in one batch request or none of them; refused
+        /// where that is not possible
+        atomic: bool,
+        reason: String,
+        metadata: SignalMetadata,
     },
 }
 
 impl TradeSignal {
-    /// Get the primary token ID for this signal
+    /// Get the primary token ID for this signal (a basket's first leg)
     #[allow(dead_code)]
     pub fn token_id(&self) -> &TokenId {
+        static NO_TOKEN: TokenId = TokenId::new();
         match self {
             TradeSignal::Buy { token_id, .. } => token_id,
             TradeSignal::Sell { token_id, .. } => token_id,
             TradeSignal::Arbitrage { yes_token, .. } => yes_token,
+            TradeSignal::Bid { token_id, .. } => token_id,
+            TradeSignal::Cancel { token_id, .. } => token_id,
+            TradeSignal::Basket { legs, .. } => legs.first().map_or(&NO_TOKEN, |l| &l.token_id),
+        }
+    }
+
+    /// Every token this signal trades (every leg of an arbitrage or basket)
+    pub fn tokens(&self) -> Vec<&TokenId> {
+        match self {
+            TradeSignal::Arbitrage {
+                yes_token,
+                no_token,
+                ..
+            } => vec![yes_token, no_token],
+            TradeSignal::Basket { legs, .. } => legs.iter().map(|l| &l.token_id).collect(),
+            _ => vec![self.token_id()],
+        }
+    }
+
+    /// Shares per leg (the largest leg of a basket; zero for a cancel)
+    pub fn size(&self) -> f64 {
+        match self {
+            TradeSignal::Basket { legs, .. } => legs.iter().map(|l| l.size).fold(0.0, f64::max),
+            TradeSignal::Buy { size, .. }
+            | TradeSignal::Sell { size, .. }
+            | TradeSignal::Arbitrage { size, .. }
+            | TradeSignal::Bid { size, .. } => *size,
+            TradeSignal::Cancel { .. } => 0.0,
         }
     }
 
@@ -55,6 +229,88 @@ impl TradeSignal {
                 size,
                 ..
             } => (yes_price + no_price) * size,
+            TradeSignal::Bid { price, size, .. } => price * size,
+            TradeSignal::Cancel { .. } => 0.0,
+            TradeSignal::Basket { legs, .. } => legs.iter().map(Leg::notional).sum(),
+        }
+    }
+
+    /// Short lowercase name of the signal kind (metric label)
+    pub fn kind(&self) -> &'static str {
+        match self {
+            TradeSignal::Buy { .. } => "buy",
+            TradeSignal::Sell { .. } => "sell",
+            TradeSignal::Arbitrage {
+                side: Side::Buy, ..
+            } => "arbitrage",
+            TradeSignal::Arbitrage {
+                side: Side::Sell, ..
+            } => "arbitrage_sell",
+            TradeSignal::Bid { .. } => "bid",
+            TradeSignal::Cancel { .. } => "cancel",
+            TradeSignal::Basket { .. } => "basket",
+        }
+    }
+
+    /// Strategy-specific context (empty for a cancel)
+    pub fn metadata(&self) -> &SignalMetadata {
+        static EMPTY: SignalMetadata = SignalMetadata(BTreeMap::new());
+        match self {
+            TradeSignal::Buy { metadata, .. }
+            | TradeSignal::Sell { metadata, .. }
+            | TradeSignal::Arbitrage { metadata, .. }
+            | TradeSignal::Bid { metadata, .. }
+            | TradeSignal::Basket { metadata, .. } => metadata,
+            TradeSignal::Cancel { .. } => &EMPTY,
+        }
+    }
+
+    /// Add a metadata entry, replacing any with the same key (no-op for a
+    /// cancel, which carries none).
+    pub fn with_metadata(mut self, key: &str, value: impl Into<Value>) -> Self {
+        match &mut self {
+            TradeSignal::Buy { metadata, .. }
+            | TradeSignal::Sell { metadata, .. }
+            | TradeSignal::Arbitrage { metadata, .. }
+            | TradeSignal::Bid { metadata, .. }
+            | TradeSignal::Basket { metadata, .. } => {
+                *metadata = std::mem::take(metadata).with(key, value);
+            }
+            TradeSignal::Cancel { .. } => {}
+        }
+        self
+    }
+
+    /// ID the engine gave this signal when it executed it
+    pub fn signal_id(&self) -> Option<&str> {
+        self.metadata().get(SIGNAL_ID_KEY).and_then(Value::as_str)
+    }
+
+    /// Expected edge per share, for signals that carry one (per share of
+    /// the largest leg for a basket)
+    pub fn edge(&self) -> Option<f64> {
+        match self {
+            TradeSignal::Arbitrage {
+                profit_per_share, ..
+            } => Some(*profit_per_share),
+            TradeSignal::Basket { .. } => {
+                let size = self.size();
+                self.basket_profit()
+                    .filter(|_| size > 0.0)
+                    .map(|profit| profit / size)
+            }
+            _ => None,
+        }
+    }
+
+    /// Profit of a basket with a known payout before costs: the payout
+    /// plus the cash its legs move.
+    pub fn basket_profit(&self) -> Option<f64> {
+        match self {
+            TradeSignal::Basket { legs, payout, .. } => {
+                payout.map(|payout| payout + legs.iter().map(Leg::cash_flow).sum::<f64>())
+            }
+            _ => None,
         }
     }
 
@@ -66,6 +322,7 @@ impl TradeSignal {
                 price,
                 size,
                 reason,
+                ..
             } => {
                 format!(
                     "BUY {} @ ${:.4} x {:.2} ({})",
@@ -80,6 +337,7 @@ impl TradeSignal {
                 price,
                 size,
                 reason,
+                ..
             } => {
                 format!(
                     "SELL {} @ ${:.4} x {:.2} ({})",
@@ -94,21 +352,66 @@ impl TradeSignal {
                 no_price,
                 profit_per_share,
                 size,
+                side,
                 ..
             } => {
+                let label = match side {
+                    Side::Buy => "ARB",
+                    Side::Sell => "ARB SELL",
+                };
                 format!(
-                    "ARB YES@${:.4} + NO@${:.4} = ${:.4} profit x {:.2}",
-                    yes_price, no_price, profit_per_share, size
+                    "{} YES@${:.4} + NO@${:.4} = ${:.4} profit x {:.2}",
+                    label, yes_price, no_price, profit_per_share, size
                 )
             }
+            TradeSignal::Bid {
+                token_id,
+                price,
+                size,
+                reason,
+                ..
+            } => {
+                format!(
+                    "BID {} @ ${:.4} x {:.2} ({})",
+                    &token_id[..8.min(token_id.len())],
+                    price,
+                    size,
+                    reason
+                )
+            }
+            TradeSignal::Cancel { token_id, reason } => {
+                format!(
+                    "CANCEL bids on {} ({})",
+                    &token_id[..8.min(token_id.len())],
+                    reason
+                )
+            }
+            TradeSignal::Basket { legs, reason, .. } => {
+                let legs: Vec<String> = legs
+                    .iter()
+                    .map(|leg| {
+                        format!(
+                            "{:?} {} @ ${:.4} x {:.2}",
+                            leg.side,
+                            &leg.token_id[..8.min(leg.token_id.len())],
+                            leg.price,
+                            leg.size
+                        )
+                    })
+                    .collect();
+                format!("BASKET {} ({})", legs.join(" + "), reason)
+            }
         }
     }
 }
 
 /// Strategy trait - implement this for each trading strategy
+///
+/// Methods take `&self`; keep caches and other mutable state in a
+/// `StrategyState` (see `state`).
 pub trait Strategy: Send + Sync {
-    /// Evaluate current market conditions and optionally generate a trade signal
-    fn evaluate(&self, market_data: &MarketData) -> Option<TradeSignal>;
+    /// Evaluate this tick's market snapshot and optionally generate a trade signal
+    fn evaluate(&self, snapshot: &MarketSnapshot) -> Option<TradeSignal>;
 
     /// Strategy name for logging
     fn name(&self) -> &'static str;
@@ -117,4 +420,62 @@ pub trait Strategy: Send + Sync {
     fn is_active(&self) -> bool {
         true
     }
+
+    /// Whether signals race other traders for stale quotes.
+    ///
+    /// Contested buys are simulated with a fill probability in paper mode
+    /// instead of always filling at the displayed ask.
+    fn is_contested(&self) -> bool {
+        false
+    }
+
+    /// Give the strategy the risk manager so it can size signals to fit the
+    /// current limits (see `RiskManager::max_allowed`) instead of having them
+    /// rejected. Called when the strategy is added to the engine.
+    fn set_risk_manager(&mut self, _risk_manager: Arc<RiskManager>) {}
+
+    /// Give the strategy the shared cost model, so its edges net out the
+    /// same fees and slippage that risk and persistence apply. Called when
+    /// the strategy is added to the engine.
+    fn set_cost_model(&mut self, _cost_model: CostModel) {}
+
+    /// Give the strategy the live sports feed, for strategies that trade on
+    /// game state. Called when the strategy is added to the engine, if the
+    /// engine has a feed.
+    fn set_game_feed(&mut self, _espn: Arc<EspnClient>) {}
+
+    /// Give the strategy the whale monitor, for strategies that hold off or
+    /// exit when large holders move. Called when the strategy is added to
+    /// the engine, if the engine has a monitor.
+    fn set_whale_monitor(&mut self, _whales: Arc<WhaleMonitor>) {}
+
+    /// Give the strategy the Redis publisher, for strategies that stream
+    /// diagnostics to the dashboard. Called when the strategy is added to the
+    /// engine, if the engine has an enabled publisher.
+    fn set_redis_publisher(&mut self, _publisher: Arc<RedisPublisher>) {}
+
+    /// Give the strategy the analytics sink, for strategies that record
+    /// high-volume diagnostics. Called when the strategy is added to the
+    /// engine, if the engine has a sink.
+    fn set_analytics_sink(&mut self, _sink: Arc<AnalyticsSink>) {}
+
+    /// Give the strategy the fast path, for strategies that react to events
+    /// between ticks: signals sent through it are risk-checked and executed
+    /// immediately instead of waiting for the next evaluation. Called last
+    /// when the strategy is added to the engine.
+    fn set_fast_path(&mut self, _fast_path: FastPath) {}
+
+    /// Called when a resting bid from a `TradeSignal::Bid` is done: fully
+    /// filled, cancelled, or never placed. `filled` is the size that
+    /// executed (0 if none).
+    fn on_bid_done(&self, _token_id: &TokenId, _filled: f64) {}
+
+    /// Called when one of this strategy's signals was sent to the exchange
+    /// and accepted (a placed order, a started TWAP, a resting bid).
+    fn on_signal_executed(&self, _signal: &TradeSignal) {}
+
+    /// Called when one of this strategy's signals did not execute: rejected
+    /// by order rules or risk, or refused by the exchange. `reason` is a
+    /// short snake_case cause such as `risk_limits` or an exchange error.
+    fn on_signal_rejected(&self, _signal: &TradeSignal, _reason: &str) {}
 }
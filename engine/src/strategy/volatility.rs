@@ -0,0 +1,189 @@
+//! Volatility gate.
+//!
+//! During fast repricing an edge can appear and vanish within milliseconds
+//! and orders sent after it chase fills that are no longer there. When a
+//! market's mid moves more than `VOLATILITY_GATE_MOVE` within
+//! `VOLATILITY_GATE_WINDOW_MS`, signals trading it are suppressed for
+//! `VOLATILITY_GATE_COOLDOWN_MS`. Cooldowns are per market: a move in
+//! either token of a pair pauses both.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use tracing::info;
+
+use crate::market::{MarketData, MarketId, TokenId};
+use crate::metrics::VOLATILITY_COOLDOWNS;
+
+use super::TradeSignal;
+
+/// Volatility gate settings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VolatilityGateConfig {
+    /// Mid move (in price) that starts a cooldown (0 = gate off)
+    pub max_move: f64,
+    /// Period the move is measured over
+    pub window: Duration,
+    /// How long signals for the market are suppressed
+    pub cooldown: Duration,
+}
+
+impl Default for VolatilityGateConfig {
+    fn default() -> Self {
+        Self {
+            max_move: 0.0,
+            window: Duration::from_millis(500),
+            cooldown: Duration::from_secs(5),
+        }
+    }
+}
+
+impl VolatilityGateConfig {
+    /// Load from `VOLATILITY_GATE_MOVE`, `VOLATILITY_GATE_WINDOW_MS` and
+    /// `VOLATILITY_GATE_COOLDOWN_MS`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        Self {
+            max_move: var("VOLATILITY_GATE_MOVE")
+                .and_then(|v| v.parse::<f64>().ok())
+                .filter(|m| m.is_finite() && *m >= 0.0)
+                .unwrap_or(defaults.max_move),
+            window: var("VOLATILITY_GATE_WINDOW_MS")
+                .and_then(|v| v.parse().ok())
+                .filter(|ms| *ms > 0)
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.window),
+            cooldown: var("VOLATILITY_GATE_COOLDOWN_MS")
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.cooldown),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.max_move > 0.0
+    }
+}
+
+/// Cooldown state of the volatility gate
+pub(super) struct VolatilityGate {
+    config: VolatilityGateConfig,
+    /// End of the cooldown per market
+    cooldowns: Mutex<HashMap<MarketId, Instant>>,
+}
+
+impl VolatilityGate {
+    pub fn new(config: VolatilityGateConfig) -> Self {
+        Self {
+            config,
+            cooldowns: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether a signal must be suppressed because a market it trades is
+    /// repricing (or cooling down from it). Cancels always go through.
+    /// `now_ns` is the wall clock the price history is stamped with.
+    pub fn suppresses(
+        &self,
+        signal: &TradeSignal,
+        market_data: &MarketData,
+        now: Instant,
+        now_ns: u64,
+    ) -> bool {
+        if matches!(signal, TradeSignal::Cancel { .. }) {
+            return false;
+        }
+        let mut cooldowns = self.cooldowns.lock();
+        cooldowns.retain(|_, until| *until > now);
+
+        let mut suppressed = false;
+        for token_id in signal.tokens() {
+            let market_id = market_data
+                .get_market_id(token_id)
+                .unwrap_or_else(|| token_id.clone());
+            if cooldowns.contains_key(&market_id) {
+                suppressed = true;
+                continue;
+            }
+            let moved = self.recent_move(market_data, token_id, now_ns);
+            if moved > self.config.max_move {
+                info!(
+                    "[VOLATILITY] {} moved {:.4} within {:?} - suppressing signals for {:?}",
+                    market_id, moved, self.config.window, self.config.cooldown
+                );
+                VOLATILITY_COOLDOWNS.inc();
+                cooldowns.insert(market_id, now + self.config.cooldown);
+                suppressed = true;
+            }
+        }
+        suppressed
+    }
+
+    /// Range of the token's mid over the window ending at `now_ns`.
+    fn recent_move(&self, market_data: &MarketData, token_id: &TokenId, now_ns: u64) -> f64 {
+        let since = now_ns.saturating_sub(self.config.window.as_nanos() as u64);
+        let Some(history) = market_data.get_history(token_id) else {
+            return 0.0;
+        };
+        let (low, high) = history
+            .iter()
+            .filter(|tick| tick.timestamp_ns >= since)
+            .fold((f64::MAX, f64::MIN), |(low, high), tick| {
+                (low.min(tick.price), high.max(tick.price))
+            });
+        (high - low).max(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market::MarketPair;
+    use crate::strategy::SignalMetadata;
+
+    fn buy(token_id: &str) -> TradeSignal {
+        TradeSignal::Buy {
+            token_id: token_id.into(),
+            price: 0.5,
+            size: 10.0,
+            reason: "test".into(),
+            metadata: SignalMetadata::new(),
+        }
+    }
+
+    #[test]
+    fn test_fast_move_suppresses_market_until_cooldown_ends() {
+        let market_data = MarketData::new();
+        market_data.register_pair(MarketPair {
+            market_id: "m1".into(),
+            yes_token: "yes".into(),
+            no_token: "no".into(),
+            question: "Test?".into(),
+            category: None,
+            end_date: None,
+        });
+        market_data.update_price(&"yes".into(), 0.49, 0.51);
+        market_data.update_price(&"no".into(), 0.49, 0.51);
+
+        let gate = VolatilityGate::new(VolatilityGateConfig {
+            max_move: 0.05,
+            window: Duration::from_secs(60),
+            cooldown: Duration::from_secs(5),
+        });
+        let now = Instant::now();
+        let now_ns = market_data.get_price(&"yes".into()).unwrap().timestamp_ns;
+        assert!(!gate.suppresses(&buy("no"), &market_data, now, now_ns));
+
+        market_data.update_price(&"yes".into(), 0.59, 0.61);
+        assert!(gate.suppresses(&buy("yes"), &market_data, now, now_ns));
+        // The other side of the pair cools down with it
+        assert!(gate.suppresses(&buy("no"), &market_data, now, now_ns));
+
+        // Once the move is outside the window and the cooldown is over
+        let later_ns = now_ns + Duration::from_secs(120).as_nanos() as u64;
+        let later = now + Duration::from_secs(6);
+        assert!(!gate.suppresses(&buy("no"), &market_data, later, later_ns));
+    }
+}
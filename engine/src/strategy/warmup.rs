@@ -0,0 +1,154 @@
+//! Cold-start protection.
+//!
+//! A fresh process sees its first books one by one and a strategy could
+//! fire on a half-populated view (one side of a pair, a book that has not
+//! been corrected by a second snapshot). For `WARMUP_SECS` after the first
+//! market data arrives every signal is observed instead of executed, and
+//! after that a signal is still only observed while any book it trades has
+//! seen fewer than `WARMUP_BOOK_UPDATES` snapshots.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use tracing::info;
+
+use crate::market::MarketData;
+
+use super::TradeSignal;
+
+/// Warm-up settings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WarmupConfig {
+    /// Observe-only period after the first market data (zero = none)
+    pub duration: Duration,
+    /// Book snapshots each traded token needs before execution (0 = none)
+    pub min_book_updates: u64,
+}
+
+impl Default for WarmupConfig {
+    fn default() -> Self {
+        Self {
+            duration: Duration::from_secs(30),
+            min_book_updates: 2,
+        }
+    }
+}
+
+impl WarmupConfig {
+    /// Load from `WARMUP_SECS` and `WARMUP_BOOK_UPDATES`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        Self {
+            duration: var("WARMUP_SECS")
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.duration),
+            min_book_updates: var("WARMUP_BOOK_UPDATES")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.min_book_updates),
+        }
+    }
+}
+
+/// Warm-up state of the engine
+pub(super) struct Warmup {
+    config: WarmupConfig,
+    /// End of the observe-only period, set once market data arrives
+    ready_at: Option<Instant>,
+    /// Set once the end of the period has been logged
+    finished: AtomicBool,
+}
+
+impl Warmup {
+    pub fn new(config: WarmupConfig) -> Self {
+        Self {
+            config,
+            ready_at: None,
+            finished: AtomicBool::new(false),
+        }
+    }
+
+    /// Start the observe-only period (first market data); later calls are
+    /// ignored.
+    pub fn start(&mut self, now: Instant) {
+        if self.ready_at.is_none() {
+            info!(
+                "[WARMUP] Observing for {:?} before executing (books need {} snapshots)",
+                self.config.duration, self.config.min_book_updates
+            );
+            self.ready_at = Some(now + self.config.duration);
+        }
+    }
+
+    /// Whether the engine is still in its observe-only period.
+    pub fn in_progress(&self, now: Instant) -> bool {
+        match self.ready_at {
+            None => true,
+            Some(ready_at) if now < ready_at => true,
+            Some(_) => {
+                if !self.finished.swap(true, Ordering::Relaxed) {
+                    info!("[WARMUP] Warm-up complete - executing signals");
+                }
+                false
+            }
+        }
+    }
+
+    /// Whether a signal must only be observed: still warming up, or a book
+    /// it trades has too few snapshots. Cancels always go through.
+    pub fn holds(&self, signal: &TradeSignal, market_data: &MarketData, now: Instant) -> bool {
+        if matches!(signal, TradeSignal::Cancel { .. }) {
+            return false;
+        }
+        self.in_progress(now)
+            || signal
+                .tokens()
+                .into_iter()
+                .any(|token_id| market_data.book_updates(token_id) < self.config.min_book_updates)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market::DepthLevel;
+    use crate::strategy::SignalMetadata;
+
+    #[test]
+    fn test_holds_until_period_ends_and_books_fill() {
+        let market_data = MarketData::new();
+        let token = "token-1".to_string();
+        let buy = TradeSignal::Buy {
+            token_id: token.clone(),
+            price: 0.5,
+            size: 10.0,
+            reason: "test".into(),
+            metadata: SignalMetadata::new(),
+        };
+        let mut warmup = Warmup::new(WarmupConfig {
+            duration: Duration::from_secs(10),
+            min_book_updates: 2,
+        });
+        let now = Instant::now();
+
+        // Nothing executes before market data arrives or during the period
+        assert!(warmup.holds(&buy, &market_data, now));
+        warmup.start(now);
+        assert!(warmup.in_progress(now + Duration::from_secs(9)));
+
+        // After it, each book still needs two snapshots
+        let later = now + Duration::from_secs(10);
+        assert!(!warmup.in_progress(later));
+        market_data.update_order_book(&token, vec![DepthLevel::new(0.49, 5.0)], vec![]);
+        assert!(warmup.holds(&buy, &market_data, later));
+        market_data.update_order_book(&token, vec![DepthLevel::new(0.49, 5.0)], vec![]);
+        assert!(!warmup.holds(&buy, &market_data, later));
+
+        let cancel = TradeSignal::Cancel {
+            token_id: "token-2".into(),
+            reason: "test".into(),
+        };
+        assert!(!warmup.holds(&cancel, &market_data, now));
+    }
+}
@@ -0,0 +1,316 @@
+//! WASM strategy plugins (feature `wasm-plugins`).
+//!
+//! Plugins are plain WebAssembly modules with no imports (no WASI, no host
+//! calls) and a narrow ABI:
+//!
+//! - `memory` - exported linear memory
+//! - `poly_alloc(len: u32) -> u32` - reserve `len` bytes for the input
+//! - `poly_evaluate(ptr: u32, len: u32) -> u64` - read the snapshot JSON at
+//!   `ptr..ptr+len` and return `(out_ptr << 32) | out_len` pointing at a JSON
+//!   signal list, or 0 for no signals
+//!
+//! Each call runs with a fuel budget and a memory cap; a plugin that traps
+//! repeatedly is disabled.
+
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+use anyhow::{bail, Context, Result};
+use parking_lot::Mutex;
+use tracing::{info, warn};
+use wasmtime::{
+    Config as WasmConfig, Engine, Instance, Linker, Memory, Module, Store, StoreLimits,
+    StoreLimitsBuilder, TypedFunc,
+};
+
+use crate::market::MarketSnapshot;
+
+use super::plugin::{first_valid_signal, PluginSnapshot, DEFAULT_BOOK_DEPTH};
+use super::registry::StrategyRegistry;
+use super::{Strategy, TradeSignal};
+
+/// Consecutive failed calls before a plugin is disabled
+const MAX_CONSECUTIVE_FAILURES: u32 = 5;
+
+/// Resource limits applied to every plugin call
+#[derive(Debug, Clone, Copy)]
+pub struct WasmLimits {
+    /// Fuel (roughly, wasm instructions) per evaluation
+    pub fuel_per_call: u64,
+    /// Maximum linear memory in bytes
+    pub max_memory_bytes: usize,
+    /// Order book levels per side in the snapshot
+    pub book_depth: usize,
+}
+
+impl Default for WasmLimits {
+    fn default() -> Self {
+        Self {
+            fuel_per_call: 10_000_000,
+            max_memory_bytes: 16 * 1024 * 1024,
+            book_depth: DEFAULT_BOOK_DEPTH,
+        }
+    }
+}
+
+impl WasmLimits {
+    /// Load limits from `WASM_PLUGIN_FUEL` and `WASM_PLUGIN_MAX_MEMORY_MB`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            fuel_per_call: std::env::var("WASM_PLUGIN_FUEL")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.fuel_per_call),
+            max_memory_bytes: std::env::var("WASM_PLUGIN_MAX_MEMORY_MB")
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .map(|mb| mb * 1024 * 1024)
+                .unwrap_or(defaults.max_memory_bytes),
+            book_depth: defaults.book_depth,
+        }
+    }
+}
+
+/// Instantiated plugin state (guarded by a mutex; wasm calls are not reentrant)
+struct PluginInstance {
+    store: Store<StoreLimits>,
+    memory: Memory,
+    alloc: TypedFunc<u32, u32>,
+    evaluate: TypedFunc<(u32, u32), u64>,
+}
+
+/// A strategy backed by a sandboxed WASM module.
+pub struct WasmStrategy {
+    name: &'static str,
+    limits: WasmLimits,
+    instance: Mutex<PluginInstance>,
+    consecutive_failures: AtomicU32,
+    disabled: AtomicBool,
+}
+
+impl WasmStrategy {
+    /// Load a plugin from a `.wasm` (or `.wat`) file.
+    pub fn load(path: &Path, limits: WasmLimits) -> Result<Self> {
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("Failed to read WASM plugin {}", path.display()))?;
+        let stem = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("plugin");
+        Self::from_bytes(&format!("wasm:{}", stem), &bytes, limits)
+    }
+
+    /// Compile and instantiate a plugin from module bytes.
+    pub fn from_bytes(name: &str, bytes: &[u8], limits: WasmLimits) -> Result<Self> {
+        let mut config = WasmConfig::new();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config)?;
+        let module = Module::new(&engine, bytes).context("Invalid WASM module")?;
+
+        if let Some(import) = module.imports().next() {
+            bail!(
+                "WASM plugin {} imports {}::{} - plugins must not import host functions",
+                name,
+                import.module(),
+                import.name()
+            );
+        }
+
+        let limiter = StoreLimitsBuilder::new()
+            .memory_size(limits.max_memory_bytes)
+            .instances(1)
+            .build();
+        let mut store = Store::new(&engine, limiter);
+        store.limiter(|l| l);
+        store.set_fuel(limits.fuel_per_call)?;
+
+        let instance: Instance = Linker::new(&engine)
+            .instantiate(&mut store, &module)
+            .context("Failed to instantiate WASM plugin")?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .context("WASM plugin must export `memory`")?;
+        let alloc = instance
+            .get_typed_func::<u32, u32>(&mut store, "poly_alloc")
+            .context("WASM plugin must export `poly_alloc(u32) -> u32`")?;
+        let evaluate = instance
+            .get_typed_func::<(u32, u32), u64>(&mut store, "poly_evaluate")
+            .context("WASM plugin must export `poly_evaluate(u32, u32) -> u64`")?;
+
+        info!(
+            "[WASM] Loaded plugin {} (fuel/call={}, max_memory={}MB)",
+            name,
+            limits.fuel_per_call,
+            limits.max_memory_bytes / (1024 * 1024)
+        );
+
+        Ok(Self {
+            // Strategy names are &'static; plugins are loaded once at startup
+            name: Box::leak(name.to_string().into_boxed_str()),
+            limits,
+            instance: Mutex::new(PluginInstance {
+                store,
+                memory,
+                alloc,
+                evaluate,
+            }),
+            consecutive_failures: AtomicU32::new(0),
+            disabled: AtomicBool::new(false),
+        })
+    }
+
+    /// Run one evaluation call, returning the plugin's raw JSON output.
+    fn call(&self, input: &[u8]) -> Result<Vec<u8>> {
+        let mut guard = self.instance.lock();
+        let inst = &mut *guard;
+        inst.store.set_fuel(self.limits.fuel_per_call)?;
+
+        let len = u32::try_from(input.len()).context("Snapshot too large")?;
+        let ptr = inst.alloc.call(&mut inst.store, len)?;
+        inst.memory
+            .write(&mut inst.store, ptr as usize, input)
+            .context("poly_alloc returned an out-of-bounds pointer")?;
+
+        let packed = inst.evaluate.call(&mut inst.store, (ptr, len))?;
+        if packed == 0 {
+            return Ok(Vec::new());
+        }
+        let out_ptr = (packed >> 32) as usize;
+        let out_len = (packed & 0xFFFF_FFFF) as usize;
+        let mut output = vec![0u8; out_len];
+        inst.memory
+            .read(&inst.store, out_ptr, &mut output)
+            .context("poly_evaluate returned an out-of-bounds result")?;
+        Ok(output)
+    }
+}
+
+impl Strategy for WasmStrategy {
+    fn evaluate(&self, snapshot: &MarketSnapshot) -> Option<TradeSignal> {
+        if self.disabled.load(Ordering::Relaxed) {
+            return None;
+        }
+
+        let snapshot = PluginSnapshot::from_snapshot(snapshot, self.limits.book_depth);
+        let input = match serde_json::to_vec(&snapshot) {
+            Ok(input) => input,
+            Err(e) => {
+                warn!("[{}] Failed to serialize snapshot: {}", self.name, e);
+                return None;
+            }
+        };
+
+        match self.call(&input) {
+            Ok(output) => {
+                self.consecutive_failures.store(0, Ordering::Relaxed);
+                first_valid_signal(self.name, &output)
+            }
+            Err(e) => {
+                let failures = self.consecutive_failures.fetch_add(1, Ordering::Relaxed) + 1;
+                warn!("[{}] Plugin call failed ({}): {:#}", self.name, failures, e);
+                if failures >= MAX_CONSECUTIVE_FAILURES {
+                    self.disabled.store(true, Ordering::Relaxed);
+                    warn!(
+                        "[{}] Plugin disabled after {} consecutive failures",
+                        self.name, failures
+                    );
+                }
+                None
+            }
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn is_active(&self) -> bool {
+        !self.disabled.load(Ordering::Relaxed)
+    }
+}
+
+/// Register every plugin listed in `WASM_PLUGINS` (comma-separated paths)
+/// under the name `wasm:<file stem>`.
+pub fn register_plugins_from_env(registry: &mut StrategyRegistry) {
+    let Ok(paths) = std::env::var("WASM_PLUGINS") else {
+        return;
+    };
+    let limits = WasmLimits::from_env();
+
+    for path in paths.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let path = std::path::PathBuf::from(path);
+        let stem = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("plugin")
+            .to_string();
+        registry.register(&format!("wasm:{}", stem), move |_config| {
+            Ok(Box::new(WasmStrategy::load(&path, limits)?))
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Plugin that always returns a fixed BUY signal stored in a data segment
+    const BUY_PLUGIN: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (data (i32.const 0) "[{\"type\":\"buy\",\"token_id\":\"tok\",\"price\":0.4,\"size\":5}]")
+          (func (export "poly_alloc") (param i32) (result i32) (i32.const 1024))
+          (func (export "poly_evaluate") (param i32 i32) (result i64)
+            (i64.const 54)))
+    "#;
+
+    /// Plugin that never returns
+    const SPIN_PLUGIN: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (func (export "poly_alloc") (param i32) (result i32) (i32.const 0))
+          (func (export "poly_evaluate") (param i32 i32) (result i64)
+            (loop (br 0))
+            (i64.const 0)))
+    "#;
+
+    #[test]
+    fn test_plugin_returns_signal() {
+        let plugin =
+            WasmStrategy::from_bytes("wasm:buy", BUY_PLUGIN.as_bytes(), WasmLimits::default())
+                .unwrap();
+        match plugin.evaluate(&MarketSnapshot::default()) {
+            Some(TradeSignal::Buy {
+                token_id, price, ..
+            }) => {
+                assert_eq!(token_id, "tok");
+                assert!((price - 0.4).abs() < 1e-9);
+            }
+            other => panic!("unexpected signal: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_runaway_plugin_runs_out_of_fuel_and_is_disabled() {
+        let limits = WasmLimits {
+            fuel_per_call: 10_000,
+            ..WasmLimits::default()
+        };
+        let plugin = WasmStrategy::from_bytes("wasm:spin", SPIN_PLUGIN.as_bytes(), limits).unwrap();
+        for _ in 0..MAX_CONSECUTIVE_FAILURES {
+            assert!(plugin.evaluate(&MarketSnapshot::default()).is_none());
+        }
+        assert!(!plugin.is_active());
+    }
+
+    #[test]
+    fn test_plugin_with_imports_is_rejected() {
+        let wat = r#"(module (import "env" "f" (func)))"#;
+        let err = WasmStrategy::from_bytes("wasm:bad", wat.as_bytes(), WasmLimits::default())
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains("must not import"));
+    }
+}
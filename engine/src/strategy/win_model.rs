@@ -0,0 +1,99 @@
+//! Rough in-game win probability.
+//!
+//! Models the final margin as the current lead plus normal noise whose
+//! variance shrinks linearly with the time left, so a given lead is worth
+//! more the closer the game is to the end. No home advantage, possession,
+//! or team strength: it is meant to flag prices that lag the scoreboard by a
+//! wide margin late in a game, not to price markets.
+
+use crate::external::{Game, League};
+
+/// Standard deviation of the full-game scoring margin per league
+fn margin_std_dev(league: League) -> Option<f64> {
+    match league {
+        League::Nba => Some(12.0),
+        League::Nfl => Some(13.5),
+        League::Nhl => Some(1.6),
+        League::Mlb => None,
+    }
+}
+
+/// Probability that the home team wins, or None when the league has no
+/// clock-based model.
+pub fn home_win_probability(game: &Game) -> Option<f64> {
+    let std_dev = margin_std_dev(game.league)?;
+    let (periods, period_secs) = game.league.regulation()?;
+    let remaining = game.seconds_remaining()?;
+    let lead = f64::from(game.home_score) - f64::from(game.away_score);
+
+    if remaining <= 0.0 {
+        return Some(if lead > 0.0 {
+            1.0
+        } else if lead < 0.0 {
+            0.0
+        } else {
+            0.5
+        });
+    }
+
+    let total = f64::from(periods) * period_secs;
+    let sigma = std_dev * (remaining / total).min(1.0).sqrt();
+    Some(normal_cdf(lead / sigma))
+}
+
+/// Standard normal CDF (Abramowitz & Stegun 7.1.26, |error| < 1.5e-7)
+fn normal_cdf(x: f64) -> f64 {
+    let z = x.abs() / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.327_591_1 * z);
+    let poly = t
+        * (0.254_829_592
+            + t * (-0.284_496_736
+                + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let erf = 1.0 - poly * (-z * z).exp();
+    if x >= 0.0 {
+        0.5 * (1.0 + erf)
+    } else {
+        0.5 * (1.0 - erf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::external::GameStatus;
+
+    fn nba_game(home_score: u32, away_score: u32, period: u32, clock_secs: f64) -> Game {
+        Game {
+            id: "1".into(),
+            league: League::Nba,
+            home_team: "Lakers".into(),
+            away_team: "Celtics".into(),
+            home_score,
+            away_score,
+            status: GameStatus::InProgress,
+            period,
+            clock_secs,
+            observed_at_ms: 0,
+        }
+    }
+
+    #[test]
+    fn test_lead_is_worth_more_late() {
+        let tied = home_win_probability(&nba_game(90, 90, 4, 60.0)).unwrap();
+        assert!((tied - 0.5).abs() < 1e-6);
+
+        let early = home_win_probability(&nba_game(60, 52, 2, 600.0)).unwrap();
+        let late = home_win_probability(&nba_game(100, 92, 4, 60.0)).unwrap();
+        assert!(early > 0.5 && early < late);
+        assert!(late > 0.95);
+
+        let trailing = home_win_probability(&nba_game(92, 100, 4, 60.0)).unwrap();
+        assert!((trailing - (1.0 - late)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_normal_cdf() {
+        assert!((normal_cdf(0.0) - 0.5).abs() < 1e-7);
+        assert!((normal_cdf(1.96) - 0.975).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,105 @@
+//! Trading timezone for daily boundaries.
+//!
+//! The trading day starts at midnight in `TRADING_TIMEZONE` (an IANA name
+//! such as `America/New_York`, default `UTC`). Daily risk limits reset then,
+//! the daily report covers the day that just closed, monthly volume rolls
+//! over on the first of the local month, and P&L attribution buckets trades
+//! by local day and hour. Offsets come from chrono-tz, so a day spanning a
+//! DST change is 23 or 25 hours long instead of drifting by an hour.
+
+use std::fmt;
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+/// Timezone whose midnight starts the trading day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TradingTimezone(Tz);
+
+impl Default for TradingTimezone {
+    fn default() -> Self {
+        Self(Tz::UTC)
+    }
+}
+
+impl TradingTimezone {
+    /// Parse an IANA timezone name (`UTC`, `America/New_York`).
+    pub fn parse(name: &str) -> Option<Self> {
+        name.trim().parse::<Tz>().ok().map(Self)
+    }
+
+    /// IANA name, also understood by Postgres `AT TIME ZONE`
+    pub fn name(self) -> &'static str {
+        self.0.name()
+    }
+
+    /// Trading day containing `now`
+    pub fn date(self, now: DateTime<Utc>) -> NaiveDate {
+        now.with_timezone(&self.0).date_naive()
+    }
+
+    /// Trading month containing `now` (`YYYY-MM`)
+    pub fn month_key(self, now: DateTime<Utc>) -> String {
+        let local = now.with_timezone(&self.0);
+        format!("{:04}-{:02}", local.year(), local.month())
+    }
+
+    /// First instant of the trading day `date`: local midnight, or the end
+    /// of the DST gap where a zone skips midnight.
+    pub fn day_start(self, date: NaiveDate) -> DateTime<Utc> {
+        let midnight = date.and_time(NaiveTime::MIN);
+        (0..=24 * 60)
+            .find_map(|minutes| {
+                self.0
+                    .from_local_datetime(&(midnight + ChronoDuration::minutes(minutes)))
+                    .earliest()
+            })
+            .expect("a DST gap is shorter than a day")
+            .with_timezone(&Utc)
+    }
+
+    /// Time from `now` until the next trading day starts
+    pub fn until_next_day(self, now: DateTime<Utc>) -> Duration {
+        let tomorrow = self.date(now) + ChronoDuration::days(1);
+        (self.day_start(tomorrow) - now)
+            .to_std()
+            .unwrap_or_default()
+    }
+}
+
+impl fmt::Display for TradingTimezone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_days_follow_local_midnight_across_dst() {
+        let ny = TradingTimezone::parse("America/New_York").unwrap();
+        assert!(TradingTimezone::parse("Mars/Olympus").is_none());
+
+        // 02:00 UTC is still the previous evening in New York
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 2, 0, 0).unwrap();
+        assert_eq!(ny.date(now), NaiveDate::from_ymd_opt(2026, 2, 28).unwrap());
+        assert_eq!(ny.month_key(now), "2026-02");
+        assert_eq!(TradingTimezone::default().month_key(now), "2026-03");
+
+        // Clocks spring forward on 2026-03-08: that day is 23 hours long
+        let midnight = Utc.with_ymd_and_hms(2026, 3, 8, 5, 0, 0).unwrap();
+        assert_eq!(ny.until_next_day(midnight), Duration::from_secs(23 * 3600));
+        assert_eq!(
+            ny.day_start(NaiveDate::from_ymd_opt(2026, 3, 9).unwrap()),
+            Utc.with_ymd_and_hms(2026, 3, 9, 4, 0, 0).unwrap()
+        );
+
+        // Santiago skips midnight itself when DST starts
+        let santiago = TradingTimezone::parse("America/Santiago").unwrap();
+        let start = santiago.day_start(NaiveDate::from_ymd_opt(2026, 9, 6).unwrap());
+        assert_eq!(start, Utc.with_ymd_and_hms(2026, 9, 6, 4, 0, 0).unwrap());
+    }
+}
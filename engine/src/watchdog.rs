@@ -0,0 +1,200 @@
+//! Liveness signals for process supervisors.
+//!
+//! A wedged strategy loop leaves the process alive, so a plain restart
+//! policy never fires. The engine loop beats this watchdog on every tick;
+//! a beat pings the systemd watchdog (`WATCHDOG=1`) and rewrites the
+//! heartbeat file, at most once per interval. If the loop stops ticking,
+//! the pings stop and systemd kills and restarts the service, or an
+//! external check sees the heartbeat file go stale.
+//!
+//! Under systemd, run with `Type=notify`, `WatchdogSec=30s` and
+//! `Restart=on-failure`: systemd sets `WATCHDOG_USEC` and the engine pings
+//! at half that period. `READY=1` is sent once startup is done and
+//! `STOPPING=1` when shutdown begins. Outside systemd the notifications
+//! are no-ops and only the heartbeat file (`HEARTBEAT_FILE`) is written.
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use parking_lot::Mutex;
+use sd_notify::NotifyState;
+use tracing::{info, warn};
+
+/// Watchdog settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchdogConfig {
+    /// systemd watchdog timeout (None = not supervised by a watchdog)
+    pub systemd_timeout: Option<Duration>,
+    /// File rewritten with the epoch milliseconds of each beat
+    pub heartbeat_file: Option<PathBuf>,
+    /// Time between heartbeat file writes
+    pub heartbeat_interval: Duration,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            systemd_timeout: None,
+            heartbeat_file: None,
+            heartbeat_interval: Duration::from_secs(5),
+        }
+    }
+}
+
+impl WatchdogConfig {
+    /// Load from `WATCHDOG_USEC` (set by systemd), `HEARTBEAT_FILE` and
+    /// `HEARTBEAT_INTERVAL_MS`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        let mut usec = 0;
+        Self {
+            systemd_timeout: sd_notify::watchdog_enabled(false, &mut usec)
+                .then(|| Duration::from_micros(usec))
+                .filter(|timeout| !timeout.is_zero()),
+            heartbeat_file: var("HEARTBEAT_FILE").map(PathBuf::from),
+            heartbeat_interval: var("HEARTBEAT_INTERVAL_MS")
+                .and_then(|v| v.parse().ok())
+                .filter(|ms| *ms > 0)
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.heartbeat_interval),
+        }
+    }
+
+    /// Time between beats: half the systemd timeout, as systemd
+    /// recommends, or the heartbeat interval if that is shorter
+    pub fn beat_interval(&self) -> Duration {
+        match self.systemd_timeout {
+            Some(timeout) if self.heartbeat_file.is_some() => {
+                (timeout / 2).min(self.heartbeat_interval)
+            }
+            Some(timeout) => timeout / 2,
+            None => self.heartbeat_interval,
+        }
+    }
+}
+
+/// Pings the supervisor while the engine loop is alive
+pub struct Watchdog {
+    config: WatchdogConfig,
+    last_beat: Mutex<Option<Instant>>,
+}
+
+impl Watchdog {
+    pub fn new(config: WatchdogConfig) -> Self {
+        if let Some(timeout) = config.systemd_timeout {
+            info!(
+                "[WATCHDOG] systemd watchdog enabled ({:?} timeout)",
+                timeout
+            );
+        }
+        if let Some(path) = &config.heartbeat_file {
+            info!(
+                "[WATCHDOG] Writing heartbeat to {} every {:?}",
+                path.display(),
+                config.heartbeat_interval
+            );
+        }
+        Self {
+            config,
+            last_beat: Mutex::new(None),
+        }
+    }
+
+    /// Whether beats go anywhere
+    pub fn is_enabled(&self) -> bool {
+        self.config.systemd_timeout.is_some() || self.config.heartbeat_file.is_some()
+    }
+
+    /// Tell systemd that startup is done (no-op outside systemd).
+    pub fn notify_ready(&self) {
+        if let Err(e) = sd_notify::notify(false, &[NotifyState::Ready]) {
+            warn!("[WATCHDOG] Failed to notify systemd of readiness: {}", e);
+        }
+    }
+
+    /// Tell systemd that shutdown has begun (no-op outside systemd).
+    pub fn notify_stopping(&self) {
+        if let Err(e) = sd_notify::notify(false, &[NotifyState::Stopping]) {
+            warn!("[WATCHDOG] Failed to notify systemd of shutdown: {}", e);
+        }
+    }
+
+    /// Record that the engine loop is alive. Cheap enough to call on every
+    /// tick: pings and writes happen at most once per beat interval.
+    pub fn beat(&self, now: Instant) {
+        {
+            let mut last_beat = self.last_beat.lock();
+            if last_beat.is_some_and(|last| now.duration_since(last) < self.config.beat_interval())
+            {
+                return;
+            }
+            *last_beat = Some(now);
+        }
+
+        if self.config.systemd_timeout.is_some() {
+            if let Err(e) = sd_notify::notify(false, &[NotifyState::Watchdog]) {
+                warn!("[WATCHDOG] Failed to ping systemd watchdog: {}", e);
+            }
+        }
+        if let Some(path) = &self.config.heartbeat_file {
+            let epoch_ms = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis();
+            if let Err(e) = std::fs::write(path, format!("{}\n", epoch_ms)) {
+                warn!(
+                    "[WATCHDOG] Failed to write heartbeat file {}: {}",
+                    path.display(),
+                    e
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_beat_interval_follows_systemd_timeout() {
+        let mut config = WatchdogConfig {
+            systemd_timeout: Some(Duration::from_secs(30)),
+            ..Default::default()
+        };
+        assert_eq!(config.beat_interval(), Duration::from_secs(15));
+        config.heartbeat_file = Some(PathBuf::from("heartbeat"));
+        assert_eq!(config.beat_interval(), Duration::from_secs(5));
+        config.systemd_timeout = Some(Duration::from_secs(4));
+        assert_eq!(config.beat_interval(), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_beat_writes_heartbeat_file_once_per_interval() {
+        let path = std::env::temp_dir().join(format!("poly-heartbeat-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let watchdog = Watchdog::new(WatchdogConfig {
+            heartbeat_file: Some(path.clone()),
+            ..Default::default()
+        });
+        assert!(watchdog.is_enabled());
+
+        let start = Instant::now();
+        watchdog.beat(start);
+        let written: u128 = std::fs::read_to_string(&path)
+            .unwrap()
+            .trim()
+            .parse()
+            .unwrap();
+        assert!(written > 0);
+
+        // Within the interval the file is left alone
+        std::fs::remove_file(&path).unwrap();
+        watchdog.beat(start + Duration::from_secs(1));
+        assert!(!path.exists());
+        watchdog.beat(start + Duration::from_secs(5));
+        assert!(path.exists());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
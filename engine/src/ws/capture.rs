@@ -0,0 +1,164 @@
+//! Recording of the raw market data stream for later replay.
+//!
+//! With `WS_CAPTURE_PATH` set, every text frame the handler receives is
+//! appended to that file as one JSON line, `{"ts_ms": <receive time>,
+//! "msg": "<frame>"}`. Writes happen on a dedicated thread; when it falls
+//! behind, frames are dropped (and counted) rather than slowing the socket.
+//! `poly-rust --profile <file>` replays a capture through the engine.
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+/// Frames waiting for the writer before new ones are dropped
+const CAPTURE_BUFFER: usize = 65_536;
+
+/// One recorded frame
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CapturedFrame {
+    /// When the frame arrived (ms since UNIX epoch)
+    pub ts_ms: u64,
+    /// The frame as received
+    pub msg: String,
+}
+
+/// Appends received frames to a capture file
+pub struct WsCapture {
+    tx: flume::Sender<CapturedFrame>,
+    dropped: AtomicU64,
+}
+
+impl WsCapture {
+    /// Capture to `WS_CAPTURE_PATH`, if set.
+    pub fn from_env() -> Result<Option<Self>> {
+        match std::env::var("WS_CAPTURE_PATH") {
+            Ok(path) if !path.is_empty() => Self::open(Path::new(&path)).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// Append to `path` (created if missing).
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open capture file {}", path.display()))?;
+        let (tx, rx) = flume::bounded(CAPTURE_BUFFER);
+        std::thread::Builder::new()
+            .name("ws-capture".to_string())
+            .spawn(move || write_frames(file, rx))
+            .context("Failed to start capture writer")?;
+        info!("[WS] Capturing market data to {}", path.display());
+        Ok(Self {
+            tx,
+            dropped: AtomicU64::new(0),
+        })
+    }
+
+    /// Queue a received frame for writing.
+    pub fn record(&self, ts_ms: u64, msg: &str) {
+        let frame = CapturedFrame {
+            ts_ms,
+            msg: msg.to_string(),
+        };
+        if self.tx.try_send(frame).is_err() {
+            let dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+            if dropped.is_power_of_two() {
+                warn!("[WS] Capture writer behind - {} frames dropped", dropped);
+            }
+        }
+    }
+}
+
+fn write_frames(file: File, rx: flume::Receiver<CapturedFrame>) {
+    let mut out = BufWriter::new(file);
+    while let Ok(frame) = rx.recv() {
+        let written = serde_json::to_writer(&mut out, &frame)
+            .map_err(std::io::Error::from)
+            .and_then(|()| out.write_all(b"\n"));
+        // Flush whenever the queue runs dry so a crash loses little
+        let flushed = written.and_then(|()| if rx.is_empty() { out.flush() } else { Ok(()) });
+        if let Err(e) = flushed {
+            warn!("[WS] Capture stopped: {}", e);
+            return;
+        }
+    }
+    let _ = out.flush();
+}
+
+/// Read a capture file, in the order it was recorded. Lines that do not
+/// parse (a torn last line after a crash) are skipped.
+pub fn read_capture(path: &Path) -> Result<Vec<CapturedFrame>> {
+    let file = File::open(path)
+        .with_context(|| format!("Failed to open capture file {}", path.display()))?;
+    let mut frames = Vec::new();
+    let mut skipped = 0;
+    for line in BufReader::new(file).lines() {
+        let line = line.with_context(|| format!("Failed to read {}", path.display()))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(&line) {
+            Ok(frame) => frames.push(frame),
+            Err(_) => skipped += 1,
+        }
+    }
+    if skipped > 0 {
+        warn!(
+            "[WS] Skipped {} malformed lines in {}",
+            skipped,
+            path.display()
+        );
+    }
+    Ok(frames)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_round_trip() {
+        let path = std::env::temp_dir().join(format!("ws-capture-{}.jsonl", std::process::id()));
+        std::fs::remove_file(&path).ok();
+
+        let capture = WsCapture::open(&path).unwrap();
+        capture.record(1_000, r#"{"type":"book","asset_id":"a"}"#);
+        capture.record(1_250, "line\nbreak");
+        drop(capture);
+
+        // The writer drains the queue once the sender is gone
+        let mut frames = Vec::new();
+        for _ in 0..100 {
+            frames = read_capture(&path).unwrap();
+            if frames.len() == 2 {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert_eq!(
+            frames,
+            vec![
+                CapturedFrame {
+                    ts_ms: 1_000,
+                    msg: r#"{"type":"book","asset_id":"a"}"#.to_string(),
+                },
+                CapturedFrame {
+                    ts_ms: 1_250,
+                    msg: "line\nbreak".to_string(),
+                },
+            ]
+        );
+
+        // A torn last line is skipped
+        std::fs::write(&path, "{\"ts_ms\":5,\"msg\":\"x\"}\n{\"ts_ms\":6,\"ms").unwrap();
+        assert_eq!(read_capture(&path).unwrap().len(), 1);
+        std::fs::remove_file(&path).ok();
+    }
+}
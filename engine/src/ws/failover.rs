@@ -0,0 +1,303 @@
+//! Failover between market data WebSocket endpoints.
+//!
+//! `POLY_WS_URL` is the primary endpoint; `WS_FALLBACK_URLS` lists
+//! secondaries (a regional mirror or a self-hosted relay). Each connection
+//! that fails, ends within `HEALTHY_SESSION` of connecting or goes stale
+//! (no market data for `WS_STALE_SECS` while subscribed) counts against the
+//! active endpoint; after `WS_FAILOVER_MAX_ERRORS` in a row the handler moves
+//! to the next endpoint in the list. While on a secondary the primary is
+//! probed every `WS_PRIMARY_RETRY_SECS` and the handler switches back as soon
+//! as a probe connects.
+//!
+//! The active endpoint is exported as `poly_ws_active_endpoint`, labelled by
+//! `endpoint` (`primary`, `fallback1`, ...) and `host`.
+
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use tokio::sync::Notify;
+use tokio::time::timeout;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::metrics::{WS_ACTIVE_ENDPOINT, WS_FAILOVERS};
+
+use super::transport::WsTransportConfig;
+
+/// A connection that stays up this long is healthy, however it ends
+pub(super) const HEALTHY_SESSION: Duration = Duration::from_secs(300);
+
+/// Time allowed for a probe of the primary to connect
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Failover settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct WsFailoverConfig {
+    /// Endpoints to fail over to, in order
+    pub fallback_urls: Vec<String>,
+    /// Consecutive failed connections before moving to the next endpoint
+    pub max_errors: u32,
+    /// Reconnect when no market data arrives for this long (None = off)
+    pub stale_after: Option<Duration>,
+    /// How often the primary is probed while on a fallback
+    pub primary_retry: Duration,
+}
+
+impl Default for WsFailoverConfig {
+    fn default() -> Self {
+        Self {
+            fallback_urls: Vec::new(),
+            max_errors: 3,
+            stale_after: None,
+            primary_retry: Duration::from_secs(60),
+        }
+    }
+}
+
+impl WsFailoverConfig {
+    /// Load from `WS_FALLBACK_URLS` (comma separated),
+    /// `WS_FAILOVER_MAX_ERRORS`, `WS_STALE_SECS` (0 = off) and
+    /// `WS_PRIMARY_RETRY_SECS`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok();
+        Self {
+            fallback_urls: var("WS_FALLBACK_URLS")
+                .map(|v| {
+                    v.split(',')
+                        .map(str::trim)
+                        .filter(|url| !url.is_empty())
+                        .map(String::from)
+                        .collect()
+                })
+                .unwrap_or(defaults.fallback_urls),
+            max_errors: var("WS_FAILOVER_MAX_ERRORS")
+                .and_then(|v| v.parse().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.max_errors),
+            stale_after: var("WS_STALE_SECS")
+                .and_then(|v| v.parse().ok())
+                .map(|secs: u64| (secs > 0).then(|| Duration::from_secs(secs)))
+                .unwrap_or(defaults.stale_after),
+            primary_retry: var("WS_PRIMARY_RETRY_SECS")
+                .and_then(|v| v.parse().ok())
+                .filter(|secs| *secs > 0)
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.primary_retry),
+        }
+    }
+}
+
+/// Metric label of the endpoint at `index`
+fn endpoint_label(index: usize) -> String {
+    if index == 0 {
+        "primary".to_string()
+    } else {
+        format!("fallback{}", index)
+    }
+}
+
+/// Host (and port) of a URL, so credentials in the path or query stay out
+/// of metric labels
+fn host_label(url: &str) -> String {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|u| {
+            let host = u.host_str()?.to_string();
+            Some(match u.port() {
+                Some(port) => format!("{}:{}", host, port),
+                None => host,
+            })
+        })
+        .unwrap_or_else(|| "invalid".to_string())
+}
+
+#[derive(Debug)]
+struct State {
+    /// Index of the endpoint in use
+    active: usize,
+    /// Consecutive failed connections to it
+    errors: u32,
+}
+
+/// The endpoints and which one is in use
+pub(super) struct Endpoints {
+    urls: Vec<String>,
+    config: WsFailoverConfig,
+    state: Mutex<State>,
+    /// Signalled when a probe of the primary connects
+    primary_up: Notify,
+}
+
+impl Endpoints {
+    pub(super) fn new(primary: String, config: WsFailoverConfig) -> Self {
+        let mut urls = vec![primary];
+        urls.extend(config.fallback_urls.iter().cloned());
+        let endpoints = Self {
+            urls,
+            config,
+            state: Mutex::new(State {
+                active: 0,
+                errors: 0,
+            }),
+            primary_up: Notify::new(),
+        };
+        endpoints.export(0);
+        endpoints
+    }
+
+    pub(super) fn primary_url(&self) -> &str {
+        &self.urls[0]
+    }
+
+    /// URL of the endpoint in use
+    pub(super) fn active_url(&self) -> String {
+        self.urls[self.state.lock().active].clone()
+    }
+
+    /// Label of the endpoint in use (`primary`, `fallback1`, ...)
+    pub(super) fn active_label(&self) -> String {
+        endpoint_label(self.state.lock().active)
+    }
+
+    pub(super) fn on_fallback(&self) -> bool {
+        self.state.lock().active != 0
+    }
+
+    pub(super) fn has_fallbacks(&self) -> bool {
+        self.urls.len() > 1
+    }
+
+    pub(super) fn stale_after(&self) -> Option<Duration> {
+        self.config.stale_after
+    }
+
+    /// Count a failed connection; returns the URL to use next if that
+    /// exhausted the active endpoint's budget.
+    pub(super) fn record_failure(&self, reason: &str) -> Option<String> {
+        let mut state = self.state.lock();
+        state.errors += 1;
+        if !self.has_fallbacks() || state.errors < self.config.max_errors {
+            return None;
+        }
+        let from = state.active;
+        state.active = (from + 1) % self.urls.len();
+        state.errors = 0;
+        let to = state.active;
+        drop(state);
+
+        WS_FAILOVERS.with_label_values(&[reason]).inc();
+        warn!(
+            "[WS] Failing over from {} ({}) to {} ({}) after {} failed connections",
+            endpoint_label(from),
+            host_label(&self.urls[from]),
+            endpoint_label(to),
+            host_label(&self.urls[to]),
+            self.config.max_errors
+        );
+        self.export(to);
+        Some(self.urls[to].clone())
+    }
+
+    /// A connection stayed up: the endpoint's budget starts over.
+    pub(super) fn record_healthy(&self) {
+        self.state.lock().errors = 0;
+    }
+
+    /// Switch back to the primary after a probe reached it.
+    pub(super) fn use_primary(&self) {
+        {
+            let mut state = self.state.lock();
+            if state.active == 0 {
+                return;
+            }
+            state.active = 0;
+            state.errors = 0;
+        }
+        WS_FAILOVERS.with_label_values(&["primary_recovered"]).inc();
+        info!(
+            "[WS] Primary endpoint ({}) reachable again - switching back",
+            host_label(&self.urls[0])
+        );
+        self.export(0);
+    }
+
+    /// Resolves when a probe of the primary connects
+    pub(super) async fn primary_recovered(&self) {
+        self.primary_up.notified().await
+    }
+
+    /// Probe the primary every `primary_retry` while on a fallback, until
+    /// cancelled.
+    pub(super) async fn watch_primary(
+        &self,
+        transport: WsTransportConfig,
+        cancel: CancellationToken,
+    ) {
+        let mut ticker = tokio::time::interval(self.config.primary_retry);
+        ticker.tick().await;
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = cancel.cancelled() => break,
+            }
+            if !self.on_fallback() {
+                continue;
+            }
+            match timeout(PROBE_TIMEOUT, transport.connect(&self.urls[0])).await {
+                Ok(Ok(mut ws)) => {
+                    let _ = ws.close(None).await;
+                    self.primary_up.notify_one();
+                }
+                Ok(Err(e)) => debug!("[WS] Primary probe failed: {:#}", e),
+                Err(_) => debug!("[WS] Primary probe timed out"),
+            }
+        }
+    }
+
+    /// Mark the endpoint at `active` as the one in use
+    fn export(&self, active: usize) {
+        for (index, url) in self.urls.iter().enumerate() {
+            WS_ACTIVE_ENDPOINT
+                .with_label_values(&[&endpoint_label(index), &host_label(url)])
+                .set(if index == active { 1.0 } else { 0.0 });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fails_over_after_budget_and_back_to_primary() {
+        let endpoints = Endpoints::new(
+            "wss://primary.example/ws".to_string(),
+            WsFailoverConfig {
+                fallback_urls: vec!["wss://relay.example:8443/ws?token=secret".to_string()],
+                max_errors: 2,
+                ..Default::default()
+            },
+        );
+        assert_eq!(endpoints.record_failure("errors"), None);
+        // A healthy session resets the budget
+        endpoints.record_healthy();
+        assert_eq!(endpoints.record_failure("errors"), None);
+        assert_eq!(
+            endpoints.record_failure("errors").as_deref(),
+            Some("wss://relay.example:8443/ws?token=secret")
+        );
+        assert!(endpoints.on_fallback());
+        assert_eq!(endpoints.active_label(), "fallback1");
+        assert_eq!(host_label(&endpoints.active_url()), "relay.example:8443");
+
+        endpoints.use_primary();
+        assert_eq!(endpoints.active_url(), "wss://primary.example/ws");
+
+        // Without fallbacks the primary is kept however often it fails
+        let single = Endpoints::new("wss://only.example".to_string(), Default::default());
+        for _ in 0..10 {
+            assert_eq!(single.record_failure("errors"), None);
+        }
+    }
+}
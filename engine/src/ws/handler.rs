@@ -2,40 +2,30 @@
 
 use anyhow::{Context, Result};
 use futures_util::{SinkExt, StreamExt};
-use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::net::TcpStream;
+use tokio::sync::watch;
 use tokio::time::{interval, timeout};
-use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+use tokio_tungstenite::tungstenite::Message;
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 
-use crate::market::{DepthLevel, MarketData};
-use crate::metrics::WEBSOCKET_MESSAGES;
-
-/// Parse and validate a price string.
-/// Returns None if the price is not a finite number in range [0.0, 1.0].
-fn parse_price(s: &str) -> Option<f64> {
-    let price: f64 = s.parse().ok()?;
-    if price.is_finite() && (0.0..=1.0).contains(&price) {
-        Some(price)
-    } else {
-        None
-    }
-}
-
-/// Parse and validate a size string.
-/// Returns None if the size is not a positive finite number.
-fn parse_size(s: &str) -> Option<f64> {
-    let size: f64 = s.parse().ok()?;
-    if size.is_finite() && size > 0.0 {
-        Some(size)
-    } else {
-        None
-    }
-}
+use crate::chaos;
+use crate::connectivity::{ConnectivitySupervisor, Subsystem};
+use crate::latency::{LatencyPath, LatencyProbe};
+use crate::log_budget::debug_limited;
+use crate::market::{MarketData, PairResolver, ResyncRequests, SubscriptionPrioritizer};
+use crate::metrics::{WEBSOCKET_MESSAGES, WS_BOOK_DELAY};
+
+use super::parse::{
+    parse_levels, parse_price, parse_tick_size, short_id, BookUpdate, PriceChangeUpdate, Side,
+    SubscribeMessage, TickSizeChangeUpdate, WsMessage,
+};
+use super::capture::WsCapture;
+use super::failover::{Endpoints, WsFailoverConfig, HEALTHY_SESSION};
+use super::transport::WsTransportConfig;
 
 /// Get current time as nanoseconds since UNIX epoch (lock-free timestamp)
 fn now_ns() -> u64 {
@@ -45,60 +35,6 @@ fn now_ns() -> u64 {
         .as_nanos() as u64
 }
 
-/// WebSocket message types from Polymarket
-#[allow(dead_code)]
-#[derive(Debug, Deserialize)]
-#[serde(tag = "type")]
-pub enum WsMessage {
-    #[serde(rename = "book")]
-    Book(BookUpdate),
-    #[serde(rename = "price_change")]
-    PriceChange(PriceChangeUpdate),
-    #[serde(rename = "tick_size_change")]
-    TickSizeChange(()),
-    #[serde(other)]
-    Unknown,
-}
-
-#[allow(dead_code)]
-#[derive(Debug, Deserialize)]
-pub struct BookUpdate {
-    pub asset_id: String,
-    pub market: Option<String>,
-    pub bids: Vec<PriceSize>,
-    pub asks: Vec<PriceSize>,
-    pub timestamp: Option<String>,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct PriceSize {
-    pub price: String,
-    pub size: String,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct PriceChangeUpdate {
-    pub asset_id: String,
-    pub price: String,
-    pub side: String,
-}
-
-// TickSizeChangeUpdate is now parsed as () since we don't use it
-// but keeping the struct definition for documentation
-#[allow(dead_code)]
-#[derive(Debug, Deserialize)]
-pub struct TickSizeChangeUpdate {
-    pub asset_id: String,
-    pub tick_size: String,
-}
-
-/// Subscription message to send to Polymarket
-#[derive(Debug, Serialize)]
-pub struct SubscribeMessage {
-    pub r#type: String,
-    pub assets_ids: Vec<String>,
-}
-
 /// WebSocket connection statistics for observability
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
@@ -110,9 +46,21 @@ pub struct WebSocketStats {
     pub uptime_secs: u64,
 }
 
+/// How a connection that did not fail came to an end
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SessionEnd {
+    /// Closed by the server or the stream ended
+    Closed,
+    /// No market data for the staleness budget
+    Stale,
+    /// Left a fallback because the primary is reachable again
+    PrimaryRecovered,
+}
+
 /// WebSocket handler with observability
 pub struct WebSocketHandler {
-    url: String,
+    /// Primary endpoint, fallbacks and the one in use
+    endpoints: Arc<Endpoints>,
     market_data: Arc<MarketData>,
     cancellation_token: CancellationToken,
     // Stats for logging
@@ -122,12 +70,26 @@ pub struct WebSocketHandler {
     reconnect_count: AtomicU64,
     /// Connection start time as nanoseconds since UNIX epoch (0 = not connected)
     connection_start_ns: AtomicU64,
+    /// Tokens to resubscribe to for a fresh book snapshot
+    resync: Arc<ResyncRequests>,
+    /// Chooses the subscribed tokens under an asset limit (None = all tokens)
+    subscriptions: Option<Arc<SubscriptionPrioritizer>>,
+    /// Receives the round trip of each ping
+    latency: Option<Arc<LatencyProbe>>,
+    /// Socket tuning for the connection
+    transport: WsTransportConfig,
+    /// Shared reconnect backoff (None = the handler's own)
+    connectivity: Option<Arc<ConnectivitySupervisor>>,
+    /// Records received frames for replay (None = not recording)
+    capture: Option<Arc<WsCapture>>,
+    /// Registers the pair of tokens that arrive without one
+    pair_resolver: Option<Arc<PairResolver>>,
 }
 
 impl WebSocketHandler {
     pub fn new(url: String, market_data: Arc<MarketData>, cancellation_token: CancellationToken) -> Self {
         Self {
-            url,
+            endpoints: Arc::new(Endpoints::new(url, WsFailoverConfig::default())),
             market_data,
             cancellation_token,
             messages_received: AtomicU64::new(0),
@@ -135,6 +97,80 @@ impl WebSocketHandler {
             price_changes: AtomicU64::new(0),
             reconnect_count: AtomicU64::new(0),
             connection_start_ns: AtomicU64::new(0), // 0 = not connected
+            resync: Arc::new(ResyncRequests::default()),
+            subscriptions: None,
+            latency: None,
+            transport: WsTransportConfig::default(),
+            connectivity: None,
+            capture: None,
+            pair_resolver: None,
+        }
+    }
+
+    /// Share resync requests with the book validator
+    pub fn set_resync_requests(&mut self, resync: Arc<ResyncRequests>) {
+        self.resync = resync;
+    }
+
+    /// Follow the prioritizer's selection instead of subscribing to every token
+    pub fn set_subscriptions(&mut self, subscriptions: Arc<SubscriptionPrioritizer>) {
+        self.subscriptions = Some(subscriptions);
+    }
+
+    /// Report ping round trips to the latency probe
+    pub fn set_latency_probe(&mut self, probe: Arc<LatencyProbe>) {
+        self.latency = Some(probe);
+    }
+
+    /// Tune the socket of each connection
+    pub fn set_transport(&mut self, transport: WsTransportConfig) {
+        self.transport = transport;
+    }
+
+    /// Record every received frame (see `ws::capture`).
+    pub fn set_capture(&mut self, capture: Arc<WsCapture>) {
+        self.capture = Some(capture);
+    }
+
+    /// Look up and register the market of unknown tokens on the feed
+    pub fn set_pair_resolver(&mut self, resolver: Arc<PairResolver>) {
+        self.pair_resolver = Some(resolver);
+    }
+
+    /// Take reconnect delays from the connectivity supervisor and report
+    /// the connection's state to it
+    pub fn set_connectivity(&mut self, connectivity: Arc<ConnectivitySupervisor>) {
+        self.connectivity = Some(connectivity);
+    }
+
+    /// Fail over to secondary endpoints when the primary misbehaves
+    pub fn set_failover(&mut self, config: WsFailoverConfig) {
+        let primary = self.endpoints.primary_url().to_string();
+        self.endpoints = Arc::new(Endpoints::new(primary, config));
+    }
+
+    /// Tokens to be subscribed to now
+    fn wanted_tokens(&self) -> Vec<String> {
+        match &self.subscriptions {
+            Some(subscriptions) => subscriptions.active_tokens(),
+            None => self.market_data.subscription_tokens(),
+        }
+    }
+
+    /// Wait until the prioritizer changes the selection or tokens are
+    /// registered in (or removed from) market data
+    async fn subscriptions_changed(&self, token_changes: &mut watch::Receiver<u64>) {
+        let registered = async {
+            if token_changes.changed().await.is_err() {
+                std::future::pending::<()>().await;
+            }
+        };
+        match &self.subscriptions {
+            Some(subscriptions) => tokio::select! {
+                _ = subscriptions.changed() => {}
+                _ = registered => {}
+            },
+            None => registered.await,
         }
     }
 
@@ -159,7 +195,18 @@ impl WebSocketHandler {
 
     /// Run the WebSocket handler with automatic reconnection
     pub async fn run(&self) -> Result<()> {
-        info!("[WS] WebSocket handler starting | url={}", self.url);
+        info!(
+            "[WS] WebSocket handler starting | url={}",
+            self.endpoints.primary_url()
+        );
+
+        // Probe the primary while a fallback is in use
+        if self.endpoints.has_fallbacks() {
+            let endpoints = self.endpoints.clone();
+            let transport = self.transport.clone();
+            let cancel = self.cancellation_token.child_token();
+            tokio::spawn(async move { endpoints.watch_primary(transport, cancel).await });
+        }
 
         loop {
             // Check for cancellation before each connection attempt
@@ -168,19 +215,8 @@ impl WebSocketHandler {
                 return Ok(());
             }
 
-            match self.connect_and_handle().await {
-                Ok(_) => {
-                    info!("[WS] WebSocket connection closed normally");
-                }
-                Err(e) => {
-                    // Check if we were cancelled during connection
-                    if self.cancellation_token.is_cancelled() {
-                        info!("[WS] Shutdown requested - stopping WebSocket handler");
-                        return Ok(());
-                    }
-                    error!("[WS] WebSocket error: {}", e);
-                }
-            }
+            let result = self.connect_and_handle().await;
+            let uptime = Duration::from_secs(self.get_stats().uptime_secs);
 
             // Check for cancellation before reconnect
             if self.cancellation_token.is_cancelled() {
@@ -191,27 +227,68 @@ impl WebSocketHandler {
             // Clear connection start time (0 = not connected)
             self.connection_start_ns.store(0, Ordering::Relaxed);
 
+            // Short-lived and stale connections count against the endpoint
+            let failure = match &result {
+                Ok(SessionEnd::PrimaryRecovered) => {
+                    self.endpoints.use_primary();
+                    self.reconnect_count.store(0, Ordering::Relaxed);
+                    continue;
+                }
+                Ok(SessionEnd::Stale) => Some("stale"),
+                Ok(SessionEnd::Closed) => {
+                    info!("[WS] WebSocket connection closed normally");
+                    (uptime < HEALTHY_SESSION).then_some("errors")
+                }
+                Err(e) => {
+                    error!("[WS] WebSocket error: {}", e);
+                    (uptime < HEALTHY_SESSION).then_some("errors")
+                }
+            };
+            if let Some(connectivity) = &self.connectivity {
+                match &result {
+                    Err(e) => connectivity.report_down(Subsystem::Ws, e),
+                    Ok(SessionEnd::Stale) => connectivity.report_down(Subsystem::Ws, "stale"),
+                    Ok(_) => connectivity.report_down(Subsystem::Ws, "closed"),
+                }
+            }
+            match failure {
+                Some(reason) => {
+                    // A new endpoint is tried straight away
+                    if self.endpoints.record_failure(reason).is_some() {
+                        self.reconnect_count.store(0, Ordering::Relaxed);
+                        continue;
+                    }
+                }
+                None => self.endpoints.record_healthy(),
+            }
+
             // Increment reconnect counter
             let reconnects = self.reconnect_count.fetch_add(1, Ordering::Relaxed) + 1;
 
-            // Calculate exponential backoff with jitter
-            let base_delay = 5u64;
-            let max_delay = 300u64;
-            let backoff_factor = 2u64.pow((reconnects - 1).min(6) as u32);
-            let delay_secs = (base_delay * backoff_factor).min(max_delay);
-            let jitter = rand::random::<u64>() % (delay_secs / 5 + 1);
-            let final_delay = delay_secs + jitter;
+            // Calculate exponential backoff with jitter (shared across
+            // subsystems when supervised)
+            let final_delay = match &self.connectivity {
+                Some(connectivity) => connectivity.reconnect_delay(Subsystem::Ws),
+                None => {
+                    let base_delay = 5u64;
+                    let max_delay = 300u64;
+                    let backoff_factor = 2u64.pow((reconnects - 1).min(6) as u32);
+                    let delay_secs = (base_delay * backoff_factor).min(max_delay);
+                    let jitter = rand::random::<u64>() % (delay_secs / 5 + 1);
+                    Duration::from_secs(delay_secs + jitter)
+                }
+            };
 
             // Log stats before reconnect
             let stats = self.get_stats();
             warn!(
-                "[WS] Reconnecting in {}s (attempt {}) | total_msgs={} | books={} | prices={}",
-                final_delay, reconnects, stats.messages_received, stats.book_updates, stats.price_changes
+                "[WS] Reconnecting in {:.1}s (attempt {}) | total_msgs={} | books={} | prices={}",
+                final_delay.as_secs_f64(), reconnects, stats.messages_received, stats.book_updates, stats.price_changes
             );
 
             // Sleep with cancellation support
             tokio::select! {
-                _ = tokio::time::sleep(Duration::from_secs(final_delay)) => {}
+                _ = tokio::time::sleep(final_delay) => {}
                 _ = self.cancellation_token.cancelled() => {
                     info!("[WS] Shutdown requested during reconnect delay - stopping WebSocket handler");
                     return Ok(());
@@ -221,14 +298,18 @@ impl WebSocketHandler {
     }
 
     /// Connect and handle messages
-    async fn connect_and_handle(&self) -> Result<()> {
-        info!("[WS] Connecting to WebSocket: {}", self.url);
+    async fn connect_and_handle(&self) -> Result<SessionEnd> {
+        let url = self.endpoints.active_url();
+        info!(
+            "[WS] Connecting to WebSocket ({}): {}",
+            self.endpoints.active_label(),
+            url
+        );
 
         // Use rustls-tls-native-roots (via tokio-tungstenite feature flags)
-        let connect_future = connect_async(&self.url);
-        let timeout_result: Result<Result<(WebSocketStream<MaybeTlsStream<TcpStream>>, _), _>, _> =
-            timeout(Duration::from_secs(10), connect_future).await;
-        let (ws_stream, _) = timeout_result
+        let connect_future = self.transport.connect(&url);
+        let ws_stream = timeout(Duration::from_secs(10), connect_future)
+            .await
             .context("Connection timeout")?
             .context("Failed to connect")?;
 
@@ -237,13 +318,21 @@ impl WebSocketHandler {
 
         // Reset reconnect counter on successful connection
         self.reconnect_count.store(0, Ordering::Relaxed);
+        if let Some(connectivity) = &self.connectivity {
+            connectivity.report_up(Subsystem::Ws);
+        }
 
-        info!("[WS] WebSocket connected successfully");
+        info!(
+            "[WS] WebSocket connected successfully | nodelay={} recv_buffer={:?}",
+            self.transport.nodelay, self.transport.recv_buffer
+        );
 
         let (mut write, mut read) = ws_stream.split();
 
         // Send subscription for all tracked tokens
-        let token_ids: Vec<String> = self.market_data.iter_prices().map(|(id, _)| id).collect();
+        let mut token_changes = self.market_data.watch_tokens();
+        let token_ids: Vec<String> = self.wanted_tokens();
+        let mut subscribed: BTreeSet<String> = token_ids.iter().cloned().collect();
 
         if !token_ids.is_empty() {
             let subscribe_msg = SubscribeMessage {
@@ -260,10 +349,19 @@ impl WebSocketHandler {
 
         // Ping interval to keep connection alive
         let mut ping_interval = interval(Duration::from_secs(30));
+        // Send time of the ping awaiting its pong
+        let mut ping_sent: Option<std::time::Instant> = None;
         // Heartbeat interval for logging (every 60 seconds)
         let mut heartbeat_interval = interval(Duration::from_secs(60));
         // Skip immediate first tick
         heartbeat_interval.tick().await;
+        // Reconnect when market data stops arriving while subscribed
+        let stale_after = self.endpoints.stale_after();
+        let mut stale_check = interval(
+            stale_after.map_or(Duration::from_secs(60), |d| (d / 4).max(Duration::from_secs(1))),
+        );
+        let mut last_data = std::time::Instant::now();
+        let on_fallback = self.endpoints.on_fallback();
 
         loop {
             tokio::select! {
@@ -272,7 +370,13 @@ impl WebSocketHandler {
                     info!("[WS] Shutdown requested - closing WebSocket connection gracefully");
                     // Send close frame to cleanly close the WebSocket
                     let _ = write.send(Message::Close(None)).await;
-                    return Ok(());
+                    return Ok(SessionEnd::Closed);
+                }
+
+                // Leave the fallback once the primary is back
+                _ = self.endpoints.primary_recovered(), if on_fallback => {
+                    let _ = write.send(Message::Close(None)).await;
+                    return Ok(SessionEnd::PrimaryRecovered);
                 }
 
                 // Handle incoming messages
@@ -281,13 +385,22 @@ impl WebSocketHandler {
                         Some(Ok(Message::Text(text))) => {
                             self.messages_received.fetch_add(1, Ordering::Relaxed);
                             WEBSOCKET_MESSAGES.inc();
-                            self.handle_message(&text);
+                            last_data = std::time::Instant::now();
+                            if let Some(capture) = &self.capture {
+                                capture.record(now_ns() / 1_000_000, &text);
+                            }
+                            if !chaos::drop_ws_message() {
+                                self.handle_message(&text);
+                            }
                         }
                         Some(Ok(Message::Ping(data))) => {
                             write.send(Message::Pong(data)).await?;
                             debug!("[WS] Responded to ping");
                         }
                         Some(Ok(Message::Pong(_))) => {
+                            if let (Some(sent), Some(latency)) = (ping_sent.take(), &self.latency) {
+                                latency.record(LatencyPath::Ws, sent.elapsed());
+                            }
                             debug!("[WS] Received pong");
                         }
                         Some(Ok(Message::Close(frame))) => {
@@ -306,17 +419,72 @@ impl WebSocketHandler {
                     }
                 }
 
+                // Resubscribe to tokens whose books need a fresh snapshot
+                _ = self.resync.notified() => {
+                    let tokens = self.resync.take();
+                    if !tokens.is_empty() {
+                        let msg = serde_json::to_string(&SubscribeMessage {
+                            r#type: "subscribe".into(),
+                            assets_ids: tokens.clone(),
+                        })?;
+                        write.send(Message::Text(msg)).await?;
+                        info!("[WS] Resubscribed to {} tokens for fresh books", tokens.len());
+                    }
+                }
+
+                // Follow the prioritizer as it rotates the long tail, and
+                // markets registered after connecting
+                _ = self.subscriptions_changed(&mut token_changes) => {
+                    let wanted: BTreeSet<String> = self.wanted_tokens().into_iter().collect();
+                    let removed: Vec<String> = subscribed.difference(&wanted).cloned().collect();
+                    let added: Vec<String> = wanted.difference(&subscribed).cloned().collect();
+                    for (kind, tokens) in [("unsubscribe", &removed), ("subscribe", &added)] {
+                        if tokens.is_empty() {
+                            continue;
+                        }
+                        let msg = serde_json::to_string(&SubscribeMessage {
+                            r#type: kind.into(),
+                            assets_ids: tokens.clone(),
+                        })?;
+                        write.send(Message::Text(msg)).await?;
+                    }
+                    if !removed.is_empty() || !added.is_empty() {
+                        info!(
+                            "[WS] Subscriptions updated: +{} -{} ({} tokens)",
+                            added.len(),
+                            removed.len(),
+                            wanted.len()
+                        );
+                    }
+                    subscribed = wanted;
+                }
+
                 // Send periodic pings
                 _ = ping_interval.tick() => {
                     write.send(Message::Ping(vec![])).await?;
+                    ping_sent = Some(std::time::Instant::now());
                     debug!("[WS] Sent ping");
                 }
 
+                _ = stale_check.tick(), if stale_after.is_some() => {
+                    if !subscribed.is_empty()
+                        && stale_after.is_some_and(|after| last_data.elapsed() > after)
+                    {
+                        warn!(
+                            "[WS] No market data for {}s - reconnecting",
+                            last_data.elapsed().as_secs()
+                        );
+                        let _ = write.send(Message::Close(None)).await;
+                        return Ok(SessionEnd::Stale);
+                    }
+                }
+
                 // Log heartbeat stats
                 _ = heartbeat_interval.tick() => {
                     let stats = self.get_stats();
                     info!(
-                        "[WS HEARTBEAT] connected=true | uptime={}s | msgs={} | books={} | prices={} | tokens={}",
+                        "[WS HEARTBEAT] connected=true | endpoint={} | uptime={}s | msgs={} | books={} | prices={} | tokens={}",
+                        self.endpoints.active_label(),
                         stats.uptime_secs,
                         stats.messages_received,
                         stats.book_updates,
@@ -327,7 +495,13 @@ impl WebSocketHandler {
             }
         }
 
-        Ok(())
+        Ok(SessionEnd::Closed)
+    }
+
+    /// Apply a recorded frame as if it had just arrived.
+    pub fn replay(&self, text: &str) {
+        self.messages_received.fetch_add(1, Ordering::Relaxed);
+        self.handle_message(text);
     }
 
     /// Handle a single WebSocket message
@@ -340,14 +514,14 @@ impl WebSocketHandler {
             Ok(WsMessage::PriceChange(update)) => {
                 self.handle_price_change(update);
             }
-            Ok(WsMessage::TickSizeChange(_)) => {
-                // Ignore tick size changes
+            Ok(WsMessage::TickSizeChange(update)) => {
+                self.handle_tick_size_change(update);
             }
             Ok(WsMessage::Unknown) => {
-                debug!("Unknown message type: {}", text);
+                debug_limited!("ws_unknown", None, "Unknown message type: {}", text);
             }
             Err(e) => {
-                debug!("Failed to parse message: {} - {}", e, text);
+                debug_limited!("ws_parse", None, "Failed to parse message: {} - {}", e, text);
             }
         }
     }
@@ -357,36 +531,42 @@ impl WebSocketHandler {
         // Increment counter
         self.book_updates.fetch_add(1, Ordering::Relaxed);
 
-        // Parse ALL depth levels (not just first) with validation
-        let bids: Vec<DepthLevel> = update
-            .bids
-            .iter()
-            .filter_map(|p| Some(DepthLevel::new(parse_price(&p.price)?, parse_size(&p.size)?)))
-            .collect();
+        if update.asset_id.is_empty() {
+            debug_limited!("ws_book", None, "[WS] Ignoring book update without asset_id");
+            return;
+        }
+        if let Some(resolver) = &self.pair_resolver {
+            resolver.observe(&update.asset_id);
+        }
 
-        let asks: Vec<DepthLevel> = update
-            .asks
-            .iter()
-            .filter_map(|p| Some(DepthLevel::new(parse_price(&p.price)?, parse_size(&p.size)?)))
-            .collect();
+        // Exchange timestamp is epoch milliseconds
+        if let Some(sent_ms) = update.timestamp.as_deref().and_then(|t| t.parse::<u64>().ok()) {
+            let delay_ms = (now_ns() / 1_000_000).saturating_sub(sent_ms);
+            WS_BOOK_DELAY
+                .with_label_values(&[self.transport.profile()])
+                .observe(delay_ms as f64 / 1000.0);
+        }
 
-        // Store full order book depth
-        self.market_data
-            .update_order_book(&update.asset_id, bids.clone(), asks.clone());
+        // Parse ALL depth levels (not just first) with validation, best first
+        let bids = parse_levels(&update.bids, true);
+        let asks = parse_levels(&update.asks, false);
 
-        // Also update top-of-book PriceLevel for backward compatibility with existing strategies
+        // Store full order book depth (also sets the top-of-book price)
         let best_bid = bids.first().map(|l| l.price).unwrap_or(0.0);
         let best_ask = asks.first().map(|l| l.price).unwrap_or(1.0);
+        let (bid_levels, ask_levels) = (bids.len(), asks.len());
         self.market_data
-            .update_price(&update.asset_id, best_bid, best_ask);
+            .update_order_book(&update.asset_id, bids, asks);
 
-        debug!(
+        debug_limited!(
+            "ws_book",
+            Some(update.asset_id.as_str()),
             "[WS] Book update: {} bid={:.4} ask={:.4} depth={}b/{}a",
-            &update.asset_id[..8.min(update.asset_id.len())],
+            short_id(&update.asset_id),
             best_bid,
             best_ask,
-            bids.len(),
-            asks.len()
+            bid_levels,
+            ask_levels
         );
     }
 
@@ -395,21 +575,27 @@ impl WebSocketHandler {
         // Increment counter
         self.price_changes.fetch_add(1, Ordering::Relaxed);
 
-        // Validate price is in valid range [0.0, 1.0]
-        if let Some(price) = parse_price(&update.price) {
+        // Validate price is in valid range [0.0, 1.0] and side is BUY/SELL
+        let side = Side::parse(&update.side);
+        if let (Some(price), Some(side)) = (parse_price(&update.price), side) {
             // Get current price to update only one side
             if let Some(current) = self.market_data.get_price(&update.asset_id) {
-                let (bid, ask) = if update.side == "BUY" {
+                let (bid, ask) = if side == Side::Buy {
                     (price, current.ask)
                 } else {
                     (current.bid, price)
                 };
 
-                self.market_data.update_price(&update.asset_id, bid, ask);
+                // A new level the book has no size for: fetch a fresh book
+                if !self.market_data.update_price(&update.asset_id, bid, ask) {
+                    self.resync.request(update.asset_id.clone());
+                }
 
-                debug!(
+                debug_limited!(
+                    "ws_price",
+                    Some(update.asset_id.as_str()),
                     "[WS] Price change: {} {} @ {:.4}",
-                    &update.asset_id[..8.min(update.asset_id.len())],
+                    short_id(&update.asset_id),
                     update.side,
                     price
                 );
@@ -417,14 +603,109 @@ impl WebSocketHandler {
         }
     }
 
-    /// Subscribe to additional tokens
+    /// Handle tick size change (order prices are snapped to the new tick)
+    fn handle_tick_size_change(&self, update: TickSizeChangeUpdate) {
+        if update.asset_id.is_empty() {
+            return;
+        }
+        if let Some(tick_size) = parse_tick_size(&update.tick_size) {
+            self.market_data.set_tick_size(&update.asset_id, tick_size);
+            info!(
+                "[WS] Tick size change: {} -> {}",
+                short_id(&update.asset_id),
+                tick_size
+            );
+        }
+    }
+
+    /// Subscribe to additional tokens. A live connection sends the
+    /// subscribe message right away; otherwise they go out on connect.
     #[allow(dead_code)]
     pub async fn subscribe(&self, token_ids: Vec<String>) -> Result<()> {
-        // This would need a reference to the write half
-        // For now, tokens should be pre-registered before connecting
         for token_id in token_ids {
-            self.market_data.update_price(&token_id, 0.0, 1.0);
+            self.market_data.track_token(token_id);
         }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn handler() -> WebSocketHandler {
+        WebSocketHandler::new(
+            "ws://localhost".to_string(),
+            Arc::new(MarketData::new()),
+            CancellationToken::new(),
+        )
+    }
+
+    fn book_message(asset_id: &str, bids: &[(f64, f64)], asks: &[(f64, f64)]) -> String {
+        let side = |levels: &[(f64, f64)]| {
+            levels
+                .iter()
+                .map(|(p, s)| serde_json::json!({ "price": p.to_string(), "size": s.to_string() }))
+                .collect::<Vec<_>>()
+        };
+        serde_json::json!({
+            "type": "book",
+            "asset_id": asset_id,
+            "bids": side(bids),
+            "asks": side(asks),
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_malformed_messages_leave_state_untouched() {
+        let handler = handler();
+        for text in [
+            "",
+            "[]",
+            "{\"type\":\"book\"}",
+            "{\"type\":\"book\",\"asset_id\":\"\",\"bids\":[],\"asks\":[]}",
+            "{\"type\":\"price_change\",\"asset_id\":\"a\",\"price\":\"0.5\",\"side\":\"BUY\"}",
+        ] {
+            handler.handle_message(text);
+        }
+        assert_eq!(handler.market_data.token_count(), 0);
+        assert!(handler.market_data.get_order_book(&String::new()).is_none());
+
+        // Unknown sides no longer overwrite the ask
+        handler.handle_message(&book_message("tok", &[(0.40, 10.0)], &[(0.60, 10.0)]));
+        handler.handle_message(
+            "{\"type\":\"price_change\",\"asset_id\":\"tok\",\"price\":\"0.9\",\"side\":\"buy\"}",
+        );
+        let price = handler.market_data.get_price(&"tok".to_string()).unwrap();
+        assert_eq!((price.bid, price.ask), (0.40, 0.60));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_book_update_keeps_best_prices(
+            asset_id in "\\PC{1,24}",
+            bids in prop::collection::vec((0.0f64..=1.0, 0.0f64..1e9), 0..300),
+            asks in prop::collection::vec((0.0f64..=1.0, 0.0f64..1e9), 0..300),
+        ) {
+            let handler = handler();
+            handler.handle_message(&book_message(&asset_id, &bids, &asks));
+
+            let best = |levels: &[(f64, f64)], bids: bool| {
+                levels
+                    .iter()
+                    .filter(|(_, s)| *s > 0.0)
+                    .map(|(p, _)| *p)
+                    .reduce(|a, b| if bids { a.max(b) } else { a.min(b) })
+            };
+            let book = handler.market_data.get_order_book(&asset_id).unwrap();
+            prop_assert_eq!(book.best_bid(), best(&bids, true));
+            prop_assert_eq!(book.best_ask(), best(&asks, false));
+
+            let price = handler.market_data.get_price(&asset_id).unwrap();
+            prop_assert_eq!(price.bid, book.best_bid().unwrap_or(0.0));
+            prop_assert_eq!(price.ask, book.best_ask().unwrap_or(1.0));
+        }
+    }
+}
@@ -1,6 +1,16 @@
-//! WebSocket handler for Polymarket price feeds.
+//! WebSocket handler for Polymarket price feeds, and a relay that serves
+//! the normalized stream to local tools.
 
+mod capture;
+mod failover;
 mod handler;
+mod parse;
+mod relay;
+mod transport;
 
+pub use capture::{read_capture, CapturedFrame, WsCapture};
+pub use failover::WsFailoverConfig;
 #[allow(unused_imports)]
 pub use handler::{WebSocketHandler, WebSocketStats};
+pub use relay::{MarketRelay, RelayConfig};
+pub use transport::WsTransportConfig;
@@ -0,0 +1,253 @@
+//! Parsing and validation of Polymarket WebSocket messages.
+//!
+//! Everything that arrives on the socket is untrusted: fields may be missing,
+//! numbers may be out of range or written in odd precisions, books may be
+//! empty or thousands of levels deep, and ids may contain arbitrary UTF-8.
+//! The functions here never panic and only let through values the market
+//! data store can hold as-is.
+
+use serde::{Deserialize, Serialize};
+
+use crate::market::DepthLevel;
+
+/// WebSocket message types from Polymarket
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type")]
+pub enum WsMessage {
+    #[serde(rename = "book")]
+    Book(BookUpdate),
+    #[serde(rename = "price_change")]
+    PriceChange(PriceChangeUpdate),
+    #[serde(rename = "tick_size_change")]
+    TickSizeChange(TickSizeChangeUpdate),
+    #[serde(other)]
+    Unknown,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+pub struct BookUpdate {
+    pub asset_id: String,
+    pub market: Option<String>,
+    pub bids: Vec<PriceSize>,
+    pub asks: Vec<PriceSize>,
+    pub timestamp: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PriceSize {
+    pub price: String,
+    pub size: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PriceChangeUpdate {
+    pub asset_id: String,
+    pub price: String,
+    pub side: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TickSizeChangeUpdate {
+    pub asset_id: String,
+    #[serde(alias = "new_tick_size")]
+    pub tick_size: String,
+}
+
+/// Subscription message to send to Polymarket
+#[derive(Debug, Serialize)]
+pub struct SubscribeMessage {
+    pub r#type: String,
+    pub assets_ids: Vec<String>,
+}
+
+/// Book side of a price change
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+impl Side {
+    /// Parse `BUY` / `SELL`; anything else is rejected.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "BUY" => Some(Self::Buy),
+            "SELL" => Some(Self::Sell),
+            _ => None,
+        }
+    }
+}
+
+/// Parse and validate a price string.
+/// Returns None if the price is not a finite number in range [0.0, 1.0].
+pub fn parse_price(s: &str) -> Option<f64> {
+    let price: f64 = s.parse().ok()?;
+    if price.is_finite() && (0.0..=1.0).contains(&price) {
+        // Normalise -0.0
+        Some(price + 0.0)
+    } else {
+        None
+    }
+}
+
+/// Parse and validate a tick size string.
+/// Returns None if the tick is not a finite number in range (0.0, 1.0).
+pub fn parse_tick_size(s: &str) -> Option<f64> {
+    let tick: f64 = s.parse().ok()?;
+    (tick.is_finite() && tick > 0.0 && tick < 1.0).then_some(tick)
+}
+
+/// Parse and validate a size string.
+/// Returns None if the size is not a positive finite number.
+pub fn parse_size(s: &str) -> Option<f64> {
+    let size: f64 = s.parse().ok()?;
+    if size.is_finite() && size > 0.0 {
+        Some(size)
+    } else {
+        None
+    }
+}
+
+/// Parse one side of a book, dropping invalid levels and sorting best first
+/// (bids descending, asks ascending). The feed does not guarantee an order:
+/// snapshots commonly list the worst level first.
+pub fn parse_levels(levels: &[PriceSize], bids: bool) -> Vec<DepthLevel> {
+    let mut parsed: Vec<DepthLevel> = levels
+        .iter()
+        .filter_map(|p| {
+            Some(DepthLevel::new(
+                parse_price(&p.price)?,
+                parse_size(&p.size)?,
+            ))
+        })
+        .collect();
+    if bids {
+        parsed.sort_by(|a, b| b.price.total_cmp(&a.price));
+    } else {
+        parsed.sort_by(|a, b| a.price.total_cmp(&b.price));
+    }
+    parsed
+}
+
+/// First 8 characters of an id for logging (safe on any UTF-8).
+pub fn short_id(id: &str) -> &str {
+    id.char_indices().nth(8).map_or(id, |(end, _)| &id[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn level(price: &str, size: &str) -> PriceSize {
+        PriceSize {
+            price: price.to_string(),
+            size: size.to_string(),
+        }
+    }
+
+    /// Numeric-ish strings, valid and not
+    fn number_string() -> impl Strategy<Value = String> {
+        prop_oneof![
+            (0.0f64..=1.0).prop_map(|p| p.to_string()),
+            any::<f64>().prop_map(|p| p.to_string()),
+            (0u32..=100, 0u32..=20).prop_map(|(p, digits)| format!(
+                "0.{:0>width$}",
+                p,
+                width = digits as usize
+            )),
+            Just("1e-400".to_string()),
+            Just("-0.0".to_string()),
+            Just("NaN".to_string()),
+            Just("inf".to_string()),
+            Just(String::new()),
+            "\\PC{0,12}",
+        ]
+    }
+
+    fn price_sizes(max: usize) -> impl Strategy<Value = Vec<PriceSize>> {
+        prop::collection::vec(
+            (number_string(), number_string()).prop_map(|(p, s)| PriceSize { price: p, size: s }),
+            0..max,
+        )
+    }
+
+    #[test]
+    fn test_parse_levels_sorts_best_first() {
+        let raw = [level("0.48", "10"), level("0.50", "5"), level("0.49", "x")];
+        let bids = parse_levels(&raw, true);
+        assert_eq!(
+            bids.iter().map(|l| l.price).collect::<Vec<_>>(),
+            vec![0.50, 0.48]
+        );
+        let asks = parse_levels(&raw, false);
+        assert_eq!(
+            asks.iter().map(|l| l.price).collect::<Vec<_>>(),
+            vec![0.48, 0.50]
+        );
+    }
+
+    #[test]
+    fn test_short_id_respects_char_boundaries() {
+        assert_eq!(short_id("0x1234567890"), "0x123456");
+        assert_eq!(short_id("abc"), "abc");
+        assert_eq!(short_id("ééééééééé"), "éééééééé");
+    }
+
+    proptest! {
+        #[test]
+        fn prop_arbitrary_text_never_panics(text in "\\PC{0,256}") {
+            let _ = serde_json::from_str::<WsMessage>(&text);
+            let _ = short_id(&text);
+            let _ = Side::parse(&text);
+        }
+
+        #[test]
+        fn prop_parsed_levels_are_valid_and_sorted(raw in price_sizes(64), bids in any::<bool>()) {
+            let levels = parse_levels(&raw, bids);
+            prop_assert!(levels.len() <= raw.len());
+            for l in &levels {
+                prop_assert!((0.0..=1.0).contains(&l.price) && l.price.is_sign_positive());
+                prop_assert!(l.size.is_finite() && l.size > 0.0);
+            }
+            for pair in levels.windows(2) {
+                if bids {
+                    prop_assert!(pair[0].price >= pair[1].price);
+                } else {
+                    prop_assert!(pair[0].price <= pair[1].price);
+                }
+            }
+        }
+
+        #[test]
+        fn prop_book_messages_round_trip(
+            asset_id in "\\PC{0,40}",
+            bids in prop::collection::vec((number_string(), number_string()), 0..32),
+            asks in prop::collection::vec((number_string(), number_string()), 0..32),
+        ) {
+            let side = |levels: &[(String, String)]| {
+                levels
+                    .iter()
+                    .map(|(p, s)| serde_json::json!({ "price": p, "size": s }))
+                    .collect::<Vec<_>>()
+            };
+            let text = serde_json::json!({
+                "type": "book",
+                "asset_id": asset_id,
+                "bids": side(&bids),
+                "asks": side(&asks),
+            })
+            .to_string();
+            match serde_json::from_str::<WsMessage>(&text) {
+                Ok(WsMessage::Book(update)) => {
+                    prop_assert_eq!(update.asset_id, asset_id);
+                    prop_assert_eq!(update.bids.len(), bids.len());
+                    prop_assert_eq!(update.asks.len(), asks.len());
+                }
+                other => prop_assert!(false, "unexpected parse: {:?}", other),
+            }
+        }
+    }
+}
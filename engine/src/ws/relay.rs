@@ -0,0 +1,355 @@
+//! Local relay of the normalized market data stream.
+//!
+//! Research notebooks and the dashboard want the same books the engine
+//! trades on without opening their own Polymarket connections, so the relay
+//! serves the updates `MarketData` applies over a WebSocket on
+//! `RELAY_BIND` (localhost by default). A client starts with no tokens and
+//! picks what it wants:
+//!
+//! - `{"type": "subscribe", "tokens": ["<token_id>", ...]}` (`"*"` = all)
+//! - `{"type": "unsubscribe", "tokens": [...]}`
+//!
+//! Newly subscribed tokens get the current book (or price) straight away,
+//! then `book` and `price` messages as they change. After each request the
+//! client is sent its filter as `subscriptions`. A client that falls more
+//! than the update buffer behind is sent `lagged` with the number of
+//! updates it missed, and should resubscribe for fresh books.
+
+use std::collections::BTreeSet;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use futures_util::{SinkExt, StreamExt};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::Semaphore;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::market::{DepthLevel, MarketData, QuoteUpdate, TokenId};
+use crate::metrics::{RELAY_CLIENTS, RELAY_SKIPPED};
+
+/// Token that subscribes to every token
+const ALL_TOKENS: &str = "*";
+
+/// Relay settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelayConfig {
+    pub enabled: bool,
+    /// Address to listen on
+    pub bind: SocketAddr,
+    /// Connections served at once; further clients are turned away
+    pub max_clients: usize,
+}
+
+impl Default for RelayConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind: SocketAddr::from(([127, 0, 0, 1], 8765)),
+            max_clients: 16,
+        }
+    }
+}
+
+impl RelayConfig {
+    /// Load from `RELAY_ENABLED`, `RELAY_BIND` and `RELAY_MAX_CLIENTS`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok();
+        Self {
+            enabled: var("RELAY_ENABLED")
+                .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+                .unwrap_or(defaults.enabled),
+            bind: var("RELAY_BIND")
+                .and_then(|v| match v.parse() {
+                    Ok(addr) => Some(addr),
+                    Err(_) => {
+                        warn!("[RELAY] Invalid RELAY_BIND '{}', using the default", v);
+                        None
+                    }
+                })
+                .unwrap_or(defaults.bind),
+            max_clients: var("RELAY_MAX_CLIENTS")
+                .and_then(|v| v.parse().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.max_clients),
+        }
+    }
+}
+
+/// Request from a client
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientRequest {
+    Subscribe { tokens: Vec<TokenId> },
+    Unsubscribe { tokens: Vec<TokenId> },
+}
+
+/// Tokens a client receives updates for
+#[derive(Debug, Default)]
+struct TokenFilter {
+    all: bool,
+    tokens: BTreeSet<TokenId>,
+}
+
+impl TokenFilter {
+    fn matches(&self, token_id: &TokenId) -> bool {
+        self.all || self.tokens.contains(token_id)
+    }
+
+    /// Apply a request; returns the tokens newly subscribed by name.
+    fn apply(&mut self, request: ClientRequest) -> Vec<TokenId> {
+        match request {
+            ClientRequest::Subscribe { tokens } => {
+                let mut added = Vec::new();
+                for token in tokens {
+                    if token == ALL_TOKENS {
+                        self.all = true;
+                    } else if self.tokens.insert(token.clone()) {
+                        added.push(token);
+                    }
+                }
+                added
+            }
+            ClientRequest::Unsubscribe { tokens } => {
+                for token in tokens {
+                    if token == ALL_TOKENS {
+                        self.all = false;
+                    } else {
+                        self.tokens.remove(&token);
+                    }
+                }
+                Vec::new()
+            }
+        }
+    }
+
+    fn message(&self) -> String {
+        json!({ "type": "subscriptions", "all": self.all, "tokens": self.tokens }).to_string()
+    }
+}
+
+fn levels(levels: &[DepthLevel]) -> Value {
+    levels.iter().map(|l| json!([l.price, l.size])).collect()
+}
+
+/// Wire form of an update: levels are `[price, size]`, best first
+fn encode(update: &QuoteUpdate) -> String {
+    match update {
+        QuoteUpdate::Book(book) => json!({
+            "type": "book",
+            "token_id": book.token_id,
+            "bids": levels(&book.bids),
+            "asks": levels(&book.asks),
+            "timestamp_ns": book.timestamp_ns,
+        }),
+        QuoteUpdate::Price { token_id, price } => json!({
+            "type": "price",
+            "token_id": token_id,
+            "bid": price.bid,
+            "ask": price.ask,
+            "timestamp_ns": price.timestamp_ns,
+        }),
+    }
+    .to_string()
+}
+
+/// Current book of a token, or its price if there is no book
+fn snapshot(market_data: &MarketData, token_id: &TokenId) -> Option<QuoteUpdate> {
+    let (price, book) = market_data.get_quote(token_id)?;
+    Some(match book {
+        Some(book) => QuoteUpdate::Book(Arc::new(book)),
+        None => QuoteUpdate::Price {
+            token_id: token_id.clone(),
+            price,
+        },
+    })
+}
+
+/// Serves market data updates to local WebSocket clients
+pub struct MarketRelay {
+    config: RelayConfig,
+    market_data: Arc<MarketData>,
+}
+
+impl MarketRelay {
+    pub fn new(config: RelayConfig, market_data: Arc<MarketData>) -> Self {
+        Self {
+            config,
+            market_data,
+        }
+    }
+
+    /// Listen on the configured address until cancelled.
+    pub async fn run(self, cancel: CancellationToken) -> Result<()> {
+        let listener = TcpListener::bind(self.config.bind)
+            .await
+            .with_context(|| format!("Failed to bind relay to {}", self.config.bind))?;
+        info!(
+            "[RELAY] Serving market data on ws://{} (max {} clients)",
+            self.config.bind, self.config.max_clients
+        );
+        self.serve(listener, cancel).await;
+        Ok(())
+    }
+
+    async fn serve(self, listener: TcpListener, cancel: CancellationToken) {
+        let slots = Arc::new(Semaphore::new(self.config.max_clients));
+        loop {
+            let (stream, addr) = tokio::select! {
+                accepted = listener.accept() => match accepted {
+                    Ok(accepted) => accepted,
+                    Err(e) => {
+                        warn!("[RELAY] Accept failed: {}", e);
+                        continue;
+                    }
+                },
+                _ = cancel.cancelled() => break,
+            };
+            let Ok(slot) = slots.clone().try_acquire_owned() else {
+                warn!(
+                    "[RELAY] Refusing {}: {} clients connected",
+                    addr, self.config.max_clients
+                );
+                continue;
+            };
+            let market_data = self.market_data.clone();
+            let cancel = cancel.clone();
+            tokio::spawn(async move {
+                RELAY_CLIENTS.inc();
+                if let Err(e) = serve_client(stream, addr, &market_data, cancel).await {
+                    debug!("[RELAY] Client {} ended: {:#}", addr, e);
+                }
+                RELAY_CLIENTS.dec();
+                drop(slot);
+            });
+        }
+    }
+}
+
+/// Relay updates to one client until it disconnects or we shut down.
+async fn serve_client(
+    stream: TcpStream,
+    addr: SocketAddr,
+    market_data: &MarketData,
+    cancel: CancellationToken,
+) -> Result<()> {
+    stream.set_nodelay(true)?;
+    let ws = tokio_tungstenite::accept_async(stream)
+        .await
+        .context("WebSocket handshake failed")?;
+    let (mut write, mut read) = ws.split();
+    let mut updates = market_data.subscribe_updates();
+    let mut filter = TokenFilter::default();
+    info!("[RELAY] Client {} connected", addr);
+
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => {
+                let _ = write.send(Message::Close(None)).await;
+                break;
+            }
+
+            msg = read.next() => match msg {
+                Some(Ok(Message::Text(text))) => {
+                    let request = match serde_json::from_str::<ClientRequest>(&text) {
+                        Ok(request) => request,
+                        Err(e) => {
+                            let error = json!({ "type": "error", "message": e.to_string() });
+                            write.send(Message::Text(error.to_string())).await?;
+                            continue;
+                        }
+                    };
+                    for token_id in filter.apply(request) {
+                        if let Some(update) = snapshot(market_data, &token_id) {
+                            write.send(Message::Text(encode(&update))).await?;
+                        }
+                    }
+                    write.send(Message::Text(filter.message())).await?;
+                }
+                Some(Ok(Message::Ping(data))) => write.send(Message::Pong(data)).await?,
+                Some(Ok(Message::Close(_))) | None => break,
+                Some(Err(e)) => return Err(e.into()),
+                _ => {}
+            },
+
+            update = updates.recv() => match update {
+                Ok(update) => {
+                    if filter.matches(update.token_id()) {
+                        write.send(Message::Text(encode(&update))).await?;
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    RELAY_SKIPPED.inc_by(skipped as f64);
+                    let lagged = json!({ "type": "lagged", "skipped": skipped });
+                    write.send(Message::Text(lagged.to_string())).await?;
+                }
+                Err(RecvError::Closed) => break,
+            },
+        }
+    }
+
+    info!("[RELAY] Client {} disconnected", addr);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+    async fn next_json(client: &mut WebSocketStream<MaybeTlsStream<TcpStream>>) -> Value {
+        match client.next().await.unwrap().unwrap() {
+            Message::Text(text) => serde_json::from_str(&text).unwrap(),
+            other => panic!("unexpected message {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_relays_subscribed_tokens_only() {
+        let market_data = Arc::new(MarketData::new());
+        market_data.update_order_book(
+            &"yes".to_string(),
+            vec![DepthLevel::new(0.40, 10.0)],
+            vec![DepthLevel::new(0.45, 5.0)],
+        );
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let cancel = CancellationToken::new();
+        let relay = MarketRelay::new(RelayConfig::default(), market_data.clone());
+        tokio::spawn(relay.serve(listener, cancel.clone()));
+
+        let (mut client, _) = tokio_tungstenite::connect_async(format!("ws://{}", addr))
+            .await
+            .unwrap();
+        // The snapshot and the filter come back before any update
+        let subscribe = r#"{"type":"subscribe","tokens":["yes"]}"#;
+        client.send(Message::Text(subscribe.into())).await.unwrap();
+        let book = next_json(&mut client).await;
+        assert_eq!(book["type"], "book");
+        assert_eq!(book["bids"][0], json!([0.40, 10.0]));
+        assert_eq!(next_json(&mut client).await["tokens"], json!(["yes"]));
+
+        market_data.update_price(&"no".to_string(), 0.50, 0.55);
+        market_data.update_price(&"yes".to_string(), 0.41, 0.45);
+        let price = next_json(&mut client).await;
+        assert_eq!(
+            (price["type"].as_str(), price["token_id"].as_str()),
+            (Some("price"), Some("yes"))
+        );
+        assert_eq!(price["bid"], 0.41);
+
+        client
+            .send(Message::Text(r#"{"type":"resubscribe"}"#.into()))
+            .await
+            .unwrap();
+        assert_eq!(next_json(&mut client).await["type"], "error");
+        cancel.cancel();
+    }
+}
@@ -0,0 +1,169 @@
+//! Socket settings for the market data WebSocket.
+//!
+//! The Sniper races other traders on every book update, so the connection
+//! is tuned for latency: Nagle's algorithm is disabled so pongs and
+//! subscribes are not held back waiting for an ACK, and the kernel receive
+//! buffer can be raised so bursts of book snapshots are not throttled by
+//! the TCP window. The buffer is set before connecting, since the window
+//! scale is fixed during the handshake.
+//!
+//! Connect stages and the delay of book updates are exported labelled by
+//! `profile` (`tuned`, or `default` with the tuning off), so a run with
+//! `WS_TCP_NODELAY=false` and no receive buffer gives the baseline.
+//!
+//! permessage-deflate is not offered: the WebSocket client cannot inflate
+//! compressed frames (it rejects frames with RSV1 set), so negotiating it
+//! would break the stream as soon as the server agreed.
+
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use tokio::net::{lookup_host, TcpSocket, TcpStream};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
+use tokio_tungstenite::{client_async_tls_with_config, MaybeTlsStream, WebSocketStream};
+
+use crate::metrics::WS_CONNECT_DURATION;
+
+/// Socket and WebSocket settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct WsTransportConfig {
+    /// Disable Nagle's algorithm (TCP_NODELAY)
+    pub nodelay: bool,
+    /// Kernel receive buffer (SO_RCVBUF) in bytes; None keeps the OS default
+    pub recv_buffer: Option<u32>,
+    /// Largest accepted message in bytes (initial snapshots can be large)
+    pub max_message_size: usize,
+}
+
+impl Default for WsTransportConfig {
+    fn default() -> Self {
+        Self {
+            nodelay: true,
+            recv_buffer: None,
+            max_message_size: 64 << 20,
+        }
+    }
+}
+
+impl WsTransportConfig {
+    /// Load from `WS_TCP_NODELAY`, `WS_RECV_BUFFER_BYTES` (0 = OS default)
+    /// and `WS_MAX_MESSAGE_BYTES`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok();
+        Self {
+            nodelay: var("WS_TCP_NODELAY")
+                .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+                .unwrap_or(defaults.nodelay),
+            recv_buffer: var("WS_RECV_BUFFER_BYTES")
+                .and_then(|v| v.parse().ok())
+                .map(|bytes: u32| (bytes > 0).then_some(bytes))
+                .unwrap_or(defaults.recv_buffer),
+            max_message_size: var("WS_MAX_MESSAGE_BYTES")
+                .and_then(|v| v.parse().ok())
+                .filter(|bytes| *bytes > 0)
+                .unwrap_or(defaults.max_message_size),
+        }
+    }
+
+    /// Metric label: `tuned` if any socket tuning is on
+    pub fn profile(&self) -> &'static str {
+        if self.nodelay || self.recv_buffer.is_some() {
+            "tuned"
+        } else {
+            "default"
+        }
+    }
+
+    fn websocket_config(&self) -> WebSocketConfig {
+        WebSocketConfig {
+            max_message_size: Some(self.max_message_size),
+            max_frame_size: Some(self.max_message_size),
+            ..Default::default()
+        }
+    }
+
+    /// Open a WebSocket to `url` over a socket with these settings.
+    pub async fn connect(&self, url: &str) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>> {
+        let request = url.into_client_request().context("Invalid WebSocket URL")?;
+        let uri = request.uri();
+        let host = uri.host().context("WebSocket URL has no host")?;
+        let default_port = if uri.scheme_str() == Some("wss") {
+            443
+        } else {
+            80
+        };
+        let port = uri.port_u16().unwrap_or(default_port);
+        // IPv6 literals keep their brackets in the URI
+        let host = host.trim_matches(|c| c == '[' || c == ']').to_string();
+
+        let started = Instant::now();
+        let addr = lookup_host((host.as_str(), port))
+            .await
+            .with_context(|| format!("Failed to resolve {}", host))?
+            .next()
+            .with_context(|| format!("No address for {}", host))?;
+        let socket = if addr.is_ipv4() {
+            TcpSocket::new_v4()?
+        } else {
+            TcpSocket::new_v6()?
+        };
+        if let Some(bytes) = self.recv_buffer {
+            socket
+                .set_recv_buffer_size(bytes)
+                .context("Failed to set receive buffer")?;
+        }
+        let stream = socket.connect(addr).await.context("TCP connect failed")?;
+        stream.set_nodelay(self.nodelay)?;
+        WS_CONNECT_DURATION
+            .with_label_values(&["tcp", self.profile()])
+            .observe(started.elapsed().as_secs_f64());
+
+        let started = Instant::now();
+        let (ws, _) =
+            client_async_tls_with_config(request, stream, Some(self.websocket_config()), None)
+                .await
+                .context("WebSocket handshake failed")?;
+        WS_CONNECT_DURATION
+            .with_label_values(&["handshake", self.profile()])
+            .observe(started.elapsed().as_secs_f64());
+        Ok(ws)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_follows_tuning() {
+        let mut config = WsTransportConfig::default();
+        assert_eq!(config.profile(), "tuned");
+        config.nodelay = false;
+        assert_eq!(config.profile(), "default");
+        config.recv_buffer = Some(1 << 20);
+        assert_eq!(config.profile(), "tuned");
+    }
+
+    #[tokio::test]
+    async fn test_connect_applies_socket_settings() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            tokio_tungstenite::accept_async(stream).await.unwrap()
+        });
+
+        let config = WsTransportConfig {
+            recv_buffer: Some(256 << 10),
+            ..Default::default()
+        };
+        let ws = config.connect(&format!("ws://{}", addr)).await.unwrap();
+        let MaybeTlsStream::Plain(stream) = ws.get_ref() else {
+            panic!("expected a plain TCP stream");
+        };
+        assert!(stream.nodelay().unwrap());
+        server.await.unwrap();
+    }
+}